@@ -0,0 +1,697 @@
+# Deutsche Oberfläche, folgt locales/en.ftl.
+# Fehlende Texte werden auf Englisch angezeigt.
+
+## Tabs and header
+tab-upload = 📤 Hochladen
+tab-download = 📥 Herunterladen
+tab-files = 📋 Dateien
+tab-lists = 📚 Listen
+tab-filesystem = 🗂 Dateisystem
+tab-history = 🕘 Verlauf
+tab-stats = 📊 Statistik
+tab-billing = 🧾 Abrechnung
+tab-settings = ⚙ Einstellungen
+tab-about = ℹ Über
+theme-switch-light = Zum hellen Design wechseln
+theme-switch-dark = Zum dunklen Design wechseln
+error-message = ❌ Fehler: { $error }
+
+## Upload
+upload-file-size = 📏 Größe: { $size }
+will-upload-as = Wird hochgeladen als: { $name }
+upload-files-selected = 📁 { $count } Dateien ausgewählt:
+upload-order = Reihenfolge:
+upload-order-as-selected = Wie ausgewählt
+upload-order-smallest-first = Kleinste zuerst
+upload-order-largest-first = Größte zuerst
+upload-concurrency = Parallele Uploads:
+upload-concurrency-tooltip = Wie viele Dateien gleichzeitig hochgeladen werden
+rename-files-optional = Dateien umbenennen (optional, leer lassen, um den Namen zu behalten):
+upload-running = ⏳ Wird hochgeladen...
+upload-files-button = 🚀 { $count } Dateien hochladen
+upload-bundle-button = 📦 Als Link-Paket hochladen...
+upload-bundle-tooltip = Lädt die Dateien in eine neue Liste hoch und speichert eine HTML- oder Markdown-Seite mit allen Links und Vorschaubildern, zum Verschicken oder Hosten
+quota-warning-file-size = { $count ->
+    [one] Eine Datei ist
+   *[other] { $count } Dateien sind
+} größer als das Limit deines Kontos von { $limit } pro Datei, der Upload wird wahrscheinlich abgelehnt.
+quota-warning-storage = Dieser Upload ({ $size }) ist größer als die { $remaining } Speicher, die deinem Konto noch bleiben.
+quota-upload-anyway = Trotzdem hochladen
+status-storage = 💾 Speicher:
+status-transfer = 📶 Übertragung diesen Monat:
+status-unlimited = { $used } (unbegrenzt)
+upload-directory-running = ⏳ Wird komprimiert und hochgeladen...
+upload-directory-button = 🚀 Ordner hochladen
+archive-format = Archivformat:
+archive-format-tar-gz = tar.gz
+archive-format-zip = zip (öffnet unter Windows ohne weitere Programme)
+archive-format-tar-zst = tar.zst (kleiner, schneller)
+archive-parallel = Auf allen Kernen komprimieren
+archive-parallel-tooltip = Nutzt jeden Prozessorkern für zstd, damit schnelle Verbindungen nicht auf die Kompression warten
+exclude-patterns = Ausschließen (ein Muster pro Zeile, wie .gitignore):
+exclude-patterns-tooltip = *.tmp lässt passende Dateien überall weg, node_modules/ lässt Ordner mit diesem Namen weg, !keep.tmp nimmt eine Datei wieder auf
+directory-after-exclusions = 📏 { $count } Dateien, { $size } nach Ausschlüssen
+upload-progress = Fortschritt: { $percent } %
+transfer-rate = { $speed }/s, noch { $eta }
+transfer-rate-no-eta = { $speed }/s
+queue-eta = ⏱ Alle Übertragungen fertig in { $eta }, gegen { $time }
+queue-eta-tooltip = Beim Durchschnitt der letzten 30 Sekunden, { $speed }/s
+queue-summary = { $files ->
+    [one] 1 Datei
+   *[other] { $files } Dateien
+}, { $size } in { $duration }
+
+## Download
+download-location-not-set = Kein Standard-Downloadordner festgelegt
+download-location = 📁 Downloadordner: { $path }
+quota-used-this-month = { $used } von { $allowance } in diesem Monat verbraucht
+quota-remaining = Voraussichtlich verbleibend: { $remaining }
+quota-rate-limited = ⚠ In diesem Monat { $count ->
+    [one] einmal
+   *[other] { $count }-mal
+} gedrosselt
+quota-server = Server: { $used } von { $limit } verbraucht ({ $checked })
+history-duplicates = { $count } Einträge mit demselben Link
+
+## Files
+loading-files = Dateien werden geladen...
+deleting-file = Datei wird gelöscht...
+api-key-get-from = Deinen API-Schlüssel erhältst du unter { $url }
+login-heading = 🔐 Mit Benutzername und Passwort anmelden
+login-username = Benutzername:
+login-password = Passwort:
+login-button = Anmelden
+login-note = Erstellt eine Sitzung für diese App und speichert ihren Schlüssel als API-Schlüssel. Das Passwort wird nicht gespeichert.
+files-showing = { $shown } von { $total } Dateien angezeigt
+files-selected-count = { $count } ausgewählt
+files-deleting = 🗑 Dateien werden gelöscht... { $done }/{ $total }
+file-views = 👁 { $count } Aufrufe
+file-downloads = ⬇ { $count } Downloads
+filter-all-types = Alle Typen
+sort-name = Name
+sort-size = Größe
+sort-views = Aufrufe
+sort-downloads = Downloads
+sort-upload-date = Hochladedatum
+sort-ascending = Aufsteigend
+sort-descending = Absteigend
+view-switch-list = Zur Listenansicht wechseln
+view-switch-grid = Zur Rasteransicht wechseln
+changes-new = { $count } neu
+changes-changed = { $count } geändert
+changes-deleted = { $count } gelöscht
+changes-deleted-files = 🗑 Gelöschte Dateien ({ $count })
+
+## Lists
+refreshing-lists = Listen werden aktualisiert...
+creating-list = Liste wird erstellt...
+deleting-list = Liste wird gelöscht...
+updating-list = Liste wird aktualisiert...
+list-downloading = 📥 Wird heruntergeladen...
+list-summary = Dateien: { $files } | Erstellt: { $created }
+list-edit-heading = Liste bearbeiten: { $title }
+list-file-count = Dateien in dieser Liste: { $count }
+
+## Stats
+stats-heading = Nutzungsstatistik
+stats-local-only = Nur auf diesem Computer gezählt, nichts wird irgendwohin gesendet.
+stats-empty = Noch nichts übertragen. Gezählt wird, wenn die Übertragungswarteschlange leer wird.
+stats-since = Gezählt seit:
+stats-uploads = Hochgeladen:
+stats-downloads = Heruntergeladen:
+stats-files-and-size = { $files ->
+    [one] 1 Datei
+   *[other] { $files } Dateien
+}, { $size }
+stats-average-speed = Durchschnittliche Geschwindigkeit:
+stats-most-active = Aktivste Stunden:
+stats-by-hour = Dateien nach Tageszeit
+stats-hour-tooltip = { $hour }: { $files ->
+    [one] 1 Datei
+   *[other] { $files } Dateien
+}
+stats-export = 📤 Als JSON exportieren...
+stats-reset = Zurücksetzen
+
+## Billing
+billing-heading = Transaktionen und Abrechnung
+billing-no-transactions = Noch keine Transaktionen.
+billing-balance = Guthaben
+billing-balance-chart = Guthaben
+billing-per-month = Pro Monat
+billing-month = Monat
+billing-subscription = Abo
+billing-storage = Speicher
+billing-bandwidth = Bandbreite
+billing-total = Berechnet
+billing-deposits = Einzahlungen
+billing-transactions = Transaktionen
+billing-date = Datum
+billing-change = Änderung
+billing-used = Speicher / Bandbreite genutzt
+
+## Filesystem
+loading = Wird geladen...
+updating-share = Freigabe wird aktualisiert...
+share-window-title = 🔒 Privat teilen
+
+## Settings
+settings-language = 🌐 Sprache:
+api-key-from-environment = 🔑 API-Schlüssel aus der Umgebung: { $prefix }...
+loading-user-info = Kontoinformationen werden geladen...
+account-unlimited = Unbegrenzt
+account-username = 👤 Benutzername: { $username }
+account-email = 📧 E-Mail: { $email }
+account-files = 📁 Dateien: { $count }
+account-storage = 💾 Speicher: { $used } / { $total }
+account-monthly-transfer = 📊 Monatliches Transfervolumen: { $used } / { $cap }
+account-expiry-days = ⏰ Ablauf der Dateien nach Tagen: { $days }
+account-balance = 💳 Guthaben: { $balance } Mikro-EUR
+hotlinking-enabled = 🔗 Hotlinking erlauben
+hotlinking-tooltip = Direkte Links und Einbettungen deiner Dateien funktionieren ohne die Downloadseite
+hotlinking-note = Downloads über Hotlinks gehen von deinem monatlichen Transfervolumen ab, eine beliebte Einbettung kann es also schnell aufbrauchen. Ist es aus, gehen Besucher über die Downloadseite und ihre Ratenlimits.
+sharing-settings = 🎨 Branding und Einbettung
+embed-domains = Domains, die deine Dateien einbetten dürfen
+embed-domains-hint = example.com blog.example.com
+branding-theme = Design des Viewers
+branding-header-image = Kopfbild (Datei-ID)
+branding-header-link = Link des Kopfbilds
+branding-background-image = Hintergrundbild (Datei-ID)
+sharing-settings-save = 💾 Speichern
+sharing-settings-reset = Änderungen verwerfen
+sharing-settings-saving = Wird gespeichert...
+sessions-heading = 🔑 Aktive Sitzungen
+sessions-description = Überall, wo dieses Konto angemeldet ist, API-Schlüssel eingeschlossen
+sessions-key = Schlüssel
+sessions-app = App
+sessions-created = Erstellt
+sessions-last-used = Zuletzt genutzt
+sessions-this-app = (diese App)
+sessions-revoke = Widerrufen
+sessions-revoke-current-warning = Das meldet diese App ab, ihr API-Schlüssel funktioniert dann nicht mehr.
+sessions-revoke-confirm = Trotzdem widerrufen
+profile-active = 👤 Profil: { $name }
+profile-name-empty = Der Profilname darf nicht leer sein
+profile-exists = Ein Profil namens „{ $name }“ existiert bereits
+
+## About
+about-version = Version: { $version }
+
+## Dialogs
+error-window-title = Fehler
+error-hint-unauthorized = Der API-Schlüssel wurde abgelehnt. Prüfe ihn in den Einstellungen.
+error-hint-not-found = Die Datei oder Liste existiert nicht oder wurde entfernt.
+error-hint-too-large = Die Datei ist größer, als pixeldrain annimmt.
+error-hint-rate-limited = Zu viele Anfragen oder das Transferlimit ist erreicht. Versuche es später erneut.
+error-hint-server = pixeldrain hat ein Serverproblem. Versuche es in ein paar Minuten erneut.
+error-context-hotlinking = Hotlinking konnte nicht geändert werden
+error-context-sharing-settings = Die Freigabe-Einstellungen konnten nicht gespeichert werden
+error-context-revoke-session = Die Sitzung konnte nicht widerrufen werden
+error-context-load-sessions = Die Sitzungen konnten nicht geladen werden
+error-context-upload = Fehler beim Hochladen
+error-context-add-upload-to-list = Der Upload konnte nicht zur Liste hinzugefügt werden
+error-context-add-uploads-to-list = Die Uploads konnten nicht zur Liste hinzugefügt werden
+error-context-split-upload = Fehler beim geteilten Hochladen
+error-context-directory-upload = Fehler beim Hochladen des Ordners
+error-context-list-download = Fehler beim Herunterladen der Liste
+error-context-file-info = Die Dateiinformationen konnten nicht abgerufen werden
+error-context-download = Fehler beim Herunterladen
+error-context-list-zip = Fehler beim Herunterladen der Liste als ZIP
+error-context-account-backup = Fehler bei der Kontosicherung
+link-upload-title = 🔗 Hochladen über einen Link angefordert
+
+## Diagnostics
+diagnostics-capture = 🩺 Diagnosedaten für die nächste Übertragung aufzeichnen
+diagnostics-capture-hint = Zeichnet Zeiten der Anfragen, Wiederholungen und Antwort-Header in einer Logdatei auf, die du einem Fehlerbericht anhängen kannst. API-Schlüssel werden nicht aufgezeichnet.
+diagnostics-saved = 🩺 Diagnosedaten gespeichert unter { $path }
+diagnostics-copy-path = Pfad kopieren
+diagnostics-save-failed = Diagnosedaten konnten nicht gespeichert werden: { $error }
+
+## History
+
+history-unavailable = Verlauf nicht verfügbar: { $error }
+history-view-limited = Die neuesten { $count } Einträge werden angezeigt, ältere findest du über die Suche
+history-uploads = Uploads
+history-downloads = Downloads
+history-from = Von
+history-to = bis
+history-invalid-date = Kein gültiges Datum: { $date } (Format JJJJ-MM-TT)
+history-export-csv = 💾 Als CSV exportieren
+history-export-json = 💾 Als JSON exportieren
+history-exported = ✅ Verlauf exportiert nach { $path }
+history-no-matches = Keine Einträge entsprechen den aktuellen Filtern
+history-column-date = Datum
+history-column-name = Name
+history-column-size = Größe
+history-column-status = Status
+history-column-link = Link
+history-column-saved-to = Gespeichert unter
+settings-high-contrast = Kontrastreiches Design
+settings-high-contrast-tooltip = Schwarz-weiße Farben, kräftige Umrisse, gut sichtbarer Fokusrahmen und größere Bedienelemente
+settings-link-format = Links kopieren als
+settings-auto-link-type = Viewer- oder Direktlink nach Dateityp wählen
+settings-auto-link-type-tooltip = Kopierte Links der angehakten Dateiarten laden direkt herunter, die anderen öffnen den Viewer
+settings-direct-links-for = Direktlinks für:
+file-category-image = Bilder
+file-category-video = Videos
+file-category-audio = Audio
+file-category-document = Dokumente
+file-category-archive = Archive
+file-category-other = Programme und andere Dateien
+settings-history-storage = Speicherort des Verlaufs
+settings-infer-extensions = Downloads ohne Dateiendung eine Endung geben
+settings-infer-extensions-tooltip = Die Endung kommt vom Dateityp, den der Server erkannt hat, damit sich Dateien unter Windows mit dem richtigen Programm öffnen
+settings-join-split-parts = 🧩 Geteilte Dateien beim Download ihrer Liste zusammenfügen
+settings-join-split-parts-tooltip = Eine Liste aus Teilen wie video.mkv.001, video.mkv.002 wird als video.mkv heruntergeladen
+upload-too-large = { $name } ist größer, als dein Konto erlaubt ({ $limit }). Die Datei kann in { $parts } Teilen hochgeladen werden, die in einer Liste zusammenbleiben.
+upload-split = ✂ Teilen und hochladen
+split-upload-needs-account = Geteilte Dateien bleiben in einer Liste zusammen, dafür brauchst du einen API-Schlüssel
+split-upload-not-encrypted = Geteilte Uploads können noch nicht verschlüsselt werden, schalte die Verschlüsselung aus, um diese Datei in Teilen hochzuladen
+encryption-keys = 🔑 Verschlüsselungsschlüssel
+encryption-keys-note = Kennungen der Schlüssel, die du auf diesem Computer hast. Downloads von Dateien, die mit einem anderen Schlüssel verschlüsselt sind, zeigen eine Warnung. Gespeichert wird nur die Passphrase unten, und nur wenn du sie dir merken lässt.
+encryption-key-add = ➕ Hinzufügen
+encryption-key-remove = Diesen Schlüssel entfernen
+key-id-hint = Schlüsselkennung
+encryption-passphrase = Passphrase
+encryption-key-id = Ihre Schlüsselkennung
+remember-encryption-passphrase = Passphrase merken
+remember-encryption-passphrase-tooltip = Speichert die Passphrase im Schlüsselbund des Systems, sonst gibst du sie bei jedem Start der App neu ein
+encryption-passphrase-not-stored = Die Passphrase konnte nicht im Schlüsselbund des Systems gespeichert werden: { $error }
+encryption-no-passphrase = Lege zuerst in den Einstellungen eine Passphrase für die Verschlüsselung fest
+storage-sqlite = SQLite-Datenbank (schnelle Suche)
+storage-json = JSON-Datei
+settings-cache-limit = Limit des Datenträger-Caches
+settings-cache-limit-tooltip = Zählt die zwischengespeicherten Vorschaubilder und die Liste unfertiger Übertragungen. Nur Vorschaubilder werden entfernt, um darunter zu bleiben, unfertige Übertragungen bleiben, bis sie fortgesetzt oder verworfen werden. Ihre unvollständigen Downloads liegen im Download-Ordner und zählen nicht. Vorschauen werden nicht auf dem Datenträger gespeichert.
+settings-cache-used = { $used } belegt, davon { $resume } durch die Liste unfertiger Übertragungen
+settings-cache-clear = Cache leeren
+
+## File details
+
+file-show-details = Details anzeigen
+file-show-details-of = { $name }, klicken für Details
+file-details = Dateidetails
+file-details-close = Schließen
+file-details-yes = Ja
+file-details-no = Nein
+file-details-never = Nie
+file-details-name = Name
+file-details-id = ID
+file-details-url = Link
+file-details-size = Größe
+file-details-type = Typ
+file-details-uploaded = Hochgeladen
+file-details-last-viewed = Zuletzt angesehen
+file-details-views = Aufrufe
+file-details-downloads = Downloads
+file-details-bandwidth = Verbrauchte Bandbreite
+file-details-paid-bandwidth = Verbrauchte bezahlte Bandbreite
+file-details-expires = Läuft ab
+file-details-expires-after-downloads = Läuft ab nach Downloads
+file-details-inactive-expiry = Läuft ab, wenn nicht angesehen bis
+file-details-expiry-note = Dein Tarif entfernt Dateien, die eine Weile nicht angesehen oder heruntergeladen wurden, jeder Aufruf startet den Zeitraum neu. Pixeldrain bietet keine Möglichkeit, das pro Datei zu ändern.
+file-details-availability = Verfügbarkeit
+file-details-available = Verfügbar
+file-details-availability-message = Verfügbarkeitshinweis
+file-details-abuse = Missbrauchsmeldung
+file-details-no-abuse-report = Keine
+file-details-sha256 = SHA-256
+file-details-speed-limit = Download-Geschwindigkeitslimit
+file-details-unlimited = Unbegrenzt
+file-details-can-edit = Bearbeitbar
+file-details-can-download = Herunterladbar
+file-details-show-ads = Zeigt Werbung
+file-details-video-player = Videoplayer erlaubt
+file-key = Verschlüsselungsschlüssel
+file-key-note = Mit welchem Schlüssel diese Datei verschlüsselt ist, auf diesem Computer für dieses Konto gemerkt
+file-key-save = Speichern
+key-not-configured = 🔒 Mit dem Schlüssel „{ $key }“ verschlüsselt, der hier nicht eingerichtet ist
+fix-type = Typ korrigieren
+fix-type-note = Pixeldrain kann den Typ einer Datei nicht ändern, deshalb wird sie mit dem Typ unten erneut hochgeladen und bekommt einen neuen Link.
+fix-type-delete-original = Original danach löschen
+fix-type-button = Mit diesem Typ erneut hochladen
+fix-type-running = Wird erneut hochgeladen...
+fix-type-done = Neuer Link:
+
+## QR codes
+qr-button = QR
+qr-show = QR-Code des Links anzeigen, um ihn auf dem Handy zu öffnen
+qr-window-title = QR-Code
+
+## Skipped files
+upload-skipped = { $count ->
+    [one] 1 Datei wurde übersprungen
+   *[other] { $count } Dateien wurden übersprungen
+}
+upload-duplicates = { $count ->
+    [one] 📎 1 Datei ist schon in deinem Konto und wurde nicht erneut hochgeladen:
+   *[other] 📎 { $count } Dateien sind schon in deinem Konto und wurden nicht erneut hochgeladen:
+}
+upload-duplicates-copy = 📋 Vorhandene Links kopieren
+upload-duplicates-anyway = Trotzdem hochladen
+upload-nothing-to-upload = Keine der ausgewählten Dateien kann hochgeladen werden
+problem-empty = leere Datei
+problem-special = keine normale Datei
+problem-unreadable = nicht lesbar: { $error }
+
+## Labels, buttons and tooltips
+since-last-refresh = Seit der letzten Aktualisierung:
+dismiss = Ausblenden
+file-badge-new = 🆕 Neu
+file-badge-changed = ✏ Geändert
+anonymous-upload = 👤 Anonymes Hochladen
+authenticated-upload = 🔐 Angemeldetes Hochladen
+using-api-key-from-environment-variable = API-Schlüssel aus der Umgebungsvariable wird verwendet
+using-api-key-from-settings = API-Schlüssel aus den Einstellungen wird verwendet
+no-api-key-configured-upload-is = ⚠ Kein API-Schlüssel eingerichtet - der Upload ist anonym und wird nicht mit deinem Konto verknüpft
+please-enter-your-api-key-in = Gib deinen API-Schlüssel in den Einstellungen ein, um in dein Konto hochzuladen.
+upload-anonymously-ignore-my-api-key = 👤 Anonym hochladen (API-Schlüssel ignorieren)
+applies-to-the-next-upload-only = Gilt nur für den nächsten Upload
+anonymous-upload-consequences = Die Datei erscheint nicht in deinem Konto: Du kannst sie später nicht löschen, zu Listen hinzufügen oder ihre Statistiken sehen, sie unterliegt den Limits und dem Ablauf anonymer Uploads, und der Server kann sie ablehnen.
+upload-add-to-list = Zur Liste hinzufügen:
+upload-no-list = (keine)
+upload-reload-lists = Deine Listen neu laden
+file-to-upload = Hochzuladende Datei:
+rename-file-optional = 📝 Datei umbenennen (optional):
+use-original = Original verwenden
+clear = Leeren
+rename-archive-optional = 📝 Archiv umbenennen (optional):
+no-file-or-directory-selected = 📁 Keine Datei und kein Ordner ausgewählt
+select-files = 📁 Dateien auswählen
+select-directory = 📂 Ordner auswählen
+paste-image = 📋 Bild einfügen
+paste-image-hover = Lädt das Bild aus der Zwischenablage hoch, auch mit Strg+V
+paste-image-none = In der Zwischenablage ist kein Bild
+verify-upload-size-sha-256 = 🔍 Upload prüfen (Größe + SHA-256)
+re-hash-the-local-file-after = Die lokale Datei nach dem Hochladen erneut hashen und mit den Serverdaten vergleichen
+skip-duplicate-uploads = 📎 Dateien überspringen, die schon in meinem Konto sind
+skip-duplicate-uploads-tooltip = Hasht Dateien vor dem Hochladen und bietet den vorhandenen Link an, wenn dein Konto eine Datei mit derselben SHA-256 hat
+encrypt-uploads = 🔒 Vor dem Hochladen verschlüsseln
+encrypt-uploads-tooltip = Verschlüsselt Dateien mit der Passphrase aus den Einstellungen und lädt sie als .enc-Dateien hoch. Downloads von .enc-Dateien werden entschlüsselt, wenn die Passphrase gesetzt ist.
+check-before-large-uploads = 🩺 Verbindung vor großen Uploads testen
+check-before-large-uploads-tooltip = Prüft vor Uploads ab 1 GB den API-Schlüssel und freien Speicher und lädt eine winzige Testdatei hoch und löscht sie wieder
+upload-button = 🚀 Hochladen
+preparing-hashing-files = 🔎 Vorbereitung: Dateien werden gehasht...
+checking-before-upload = API-Schlüssel, Speicher und Verbindung werden geprüft...
+check-key-failed = Die Prüfung des API-Schlüssels ist fehlgeschlagen: { $error }
+check-no-space = Auf dem Konto ist nicht genug freier Speicher für diesen Upload
+check-upload-failed = Der Test-Upload ist fehlgeschlagen: { $error }
+check-delete-failed = Die Testdatei { $id } konnte nicht gelöscht werden: { $error }
+uploading-directory = Ordner wird hochgeladen...
+uploading = 📤 Wird hochgeladen...
+upload-complete-url-copied-to-clipboard = ✅ Hochladen abgeschlossen! URL in die Zwischenablage kopiert.
+recent-uploads = Letzte Uploads
+no-uploads-yet = Noch keine Uploads
+no-uploads-match-the-current-filters = Keine Uploads passen zu den aktuellen Filtern
+failed = ❌ Fehlgeschlagen
+verified = ✅ Geprüft
+size-and-sha-256-match-the = Größe und SHA-256 stimmen mit der lokalen Datei überein
+mismatch = ⚠ Abweichung
+the-uploaded-file-does-not-match = Die hochgeladene Datei stimmt nicht mit der lokalen Datei überein
+anonymous = 👤 Anonym
+not-linked-to-your-account-it = Nicht mit deinem Konto verknüpft, kann hier nicht verwaltet oder gelöscht werden
+copy = 📋 Kopieren
+public-file-download = ⬇ Öffentliche Datei herunterladen
+download-any-public-pixeldrain-file-or = Lade beliebige öffentliche PixelDrain-Dateien oder -Listen herunter (kein API-Schlüssel nötig)
+download-url = URL:
+download-url-hint = Link oder Teil eines Namens aus deinem Verlauf
+download-button = ⬇ Herunterladen
+download-as-zip = 📦 Als ZIP herunterladen
+downloading = Wird heruntergeladen...
+done = ✅ Fertig
+recent-downloads = Letzte Downloads
+no-downloads-yet = Noch keine Downloads
+no-downloads-match-the-current-filters = Keine Downloads passen zu den aktuellen Filtern
+download-quota-estimate = 📊 Geschätztes Downloadkontingent
+monthly-allowance = Monatliches Kontingent:
+size-field-tooltip = Eine Größe wie 1,5 GB oder 500 MB, wird übernommen, wenn du Enter drückst oder das Feld verlässt
+server-reports-no-transfer-limit-for = Der Server meldet kein Transferlimit für diese Verbindung
+server-limits-not-checked-yet = Serverlimits noch nicht abgefragt
+check-rate-limits = Limits abfragen
+counted-by-this-app-only-downloads = Nur von dieser App gezählt, Downloads im Browser sind nicht enthalten.
+search-names-and-urls = 🔍 Namen und URLs durchsuchen
+group-by-day = Nach Tag gruppieren
+collapse-duplicates = Duplikate zusammenfassen
+only-failures = Nur Fehlschläge
+your-files = Deine Dateien
+refresh = 🔄 Aktualisieren
+no-api-key-configured = ⚠ Kein API-Schlüssel eingerichtet
+set-your-api-key-in-settings = Lege deinen API-Schlüssel in den Einstellungen fest oder nutze die Umgebungsvariable PIXELDRAIN_API_KEY (die Einstellungen haben Vorrang)
+no-files-found-click-refresh-to = Keine Dateien gefunden. Klicke auf „Aktualisieren“, um deine Dateien zu laden.
+no-files-match-the-current-search = Keine Dateien passen zur aktuellen Suche und zum Filter.
+select-all = ☑ Alle auswählen
+select-all-files-matching-the-current = Alle Dateien auswählen, die zum aktuellen Filter passen
+clear-selection = ☐ Auswahl aufheben
+download-selected-as-zip = 📦 Auswahl als ZIP herunterladen
+copy-selected-urls = 📋 URLs der Auswahl kopieren
+delete-selected = 🗑 Auswahl löschen
+building-zip-bundle = 📦 ZIP-Archiv wird erstellt...
+copy-url = URL kopieren
+delete = Löschen
+copy-url-button = 📋 URL kopieren
+copy-as = Link kopieren als…
+link-format-plain = Link
+link-format-markdown = Markdown
+link-format-bbcode = BBCode
+link-format-html = HTML-Link
+link-format-direct = Direkter Download-Link
+delete-button = 🗑 Löschen
+search-by-name-or-id = Nach Name oder ID suchen
+sort-by = Sortieren:
+your-lists = Deine Listen
+refresh-lists = 🔄 Listen aktualisieren
+share-privately = 🔒 Privat teilen...
+lists-are-always-public-share-a = Listen sind immer öffentlich. Teile stattdessen einen Dateisystemordner mit Passwort.
+create-new-list = Neue Liste erstellen
+list-title = Titel:
+select-files-to-add-to-the = Dateien für die Liste auswählen:
+create-list = Liste erstellen
+no-lists-found-click-refresh-lists = Keine Listen gefunden. Klicke auf „Listen aktualisieren“ oder erstelle eine neue Liste.
+add-remove-files = Dateien hinzufügen/entfernen:
+save-changes = Änderungen speichern
+an-api-key-is-required-to = 🔑 Zum Durchsuchen des Dateisystems wird ein API-Schlüssel benötigt. Lege ihn in den Einstellungen fest.
+filesystem = Dateisystem
+up = ⬆ Nach oben
+upload-files-here = 📤 Dateien hierher hochladen
+new-folder-name = Name des neuen Ordners
+create-folder = 📁 Ordner erstellen
+this-folder-is-empty = Dieser Ordner ist leer
+rename = Umbenennen
+share = Teilen
+download = Herunterladen
+new-name = Neuer Name:
+rename-confirm = ✅ Umbenennen
+cancel = Abbrechen
+pixeldrain-api-key = PixelDrain-API-Schlüssel:
+paste = 📋 Einfügen
+show-api-key = API-Schlüssel anzeigen
+hide-api-key = API-Schlüssel verbergen
+store-api-key-in-the-settings = API-Schlüssel in der Einstellungsdatei speichern (Klartext)
+only-needed-when-no-system-keyring = Nur nötig, wenn kein Systemschlüsselbund verfügbar ist
+the-api-key-is-saved-unencrypted = ⚠ Der API-Schlüssel wird unverschlüsselt in settings.json gespeichert
+the-api-key-is-kept-in = 🔒 Der API-Schlüssel liegt im Systemschlüsselbund
+environment-api-key-will-be-used = 💡 Der API-Schlüssel aus der Umgebung wird ersatzweise verwendet
+settings-api-key-will-be-used = ✅ Der API-Schlüssel aus den Einstellungen wird verwendet (hat Vorrang vor der Umgebung)
+default-download-location = Standard-Downloadordner:
+choose-folder = 📁 Ordner wählen
+account-information = Kontoinformationen
+no-account-information-available-set-api = Keine Kontoinformationen verfügbar. Lege einen API-Schlüssel in den Einstellungen oder in PIXELDRAIN_API_KEY fest und klicke dann auf Aktualisieren.
+save-settings = 💾 Einstellungen speichern
+settings-saved-successfully = ✅ Einstellungen gespeichert!
+api-key-download-location-and-preferences = API-Schlüssel, Downloadordner und die Einstellungen unten gehören zu diesem Profil.
+remove-profile = Profil entfernen
+new-profile-name = Name des neuen Profils
+add-profile = ➕ Profil hinzufügen
+server-url = Server-URL:
+server-url-reset = ↺ Standard
+change-this-only-for-a-self = Nur für eine selbst gehostete oder gespiegelte pixeldrain-Instanz ändern
+window-name-shown-in-the-title = Fenstername (in Titelleiste und Taskleiste angezeigt):
+window-name-hint = z. B. Arbeitskonto
+tint-taskbar-icon = Taskleistensymbol einfärben
+desktop-notification-when-a-transfer-finishes = 🔔 Benachrichtigen, wenn eine Übertragung endet oder ein Upload das Kontingent überschreitet
+prevent-sleep-during-transfers = ☕ Computer während Übertragungen wach halten
+prevent-sleep-during-transfers-tooltip = Verhindert den Ruhezustand, solange Uploads oder Downloads laufen, damit sie über Nacht nicht abbrechen. Der Bildschirm kann sich trotzdem ausschalten.
+sleep-when-transfers-finish = 💤 Danach in den Ruhezustand
+sleep-when-transfers-finish-tooltip = Versetzt den Computer in den Ruhezustand, sobald alle laufenden Übertragungen fertig sind. Gilt nur für die aktuellen Übertragungen.
+sleep-failed = Der Computer konnte nicht in den Ruhezustand versetzt werden: { $error }
+a-modern-unofficial-desktop-client-for = Ein moderner, inoffizieller Desktop-Client für den Filehoster PixelDrain.
+built-with-rust-and-egui = Entwickelt mit Rust und egui.
+about-features = Funktionen:
+upload-files-and-folders-with-progress = • 📤 Dateien und Ordner mit Fortschrittsanzeige hochladen (API-Schlüssel nötig)
+download-files-from-pixeldrain-urls-no = • 📥 Dateien von PixelDrain-URLs herunterladen (kein API-Schlüssel nötig)
+copy-shareable-links-to-clipboard = • 📋 Links zum Teilen in die Zwischenablage kopieren
+manage-your-uploaded-files = • 📁 Hochgeladene Dateien verwalten
+configure-api-key-and-settings = • ⚙ API-Schlüssel und Einstellungen festlegen
+environment-variable-support-pixeldrain-api-key = • 🔑 Unterstützung für die Umgebungsvariable PIXELDRAIN_API_KEY
+technical-details = Technische Details
+error-status = Status:
+error-endpoint = Endpunkt:
+error-request-id = Anfrage-ID:
+error-time = Zeit:
+copy-details = 📋 Details kopieren
+copy-a-snippet-for-a-bug = Einen Ausschnitt für einen Fehlerbericht kopieren
+ok = OK
+another-application-asked-to-upload = Eine andere Anwendung möchte hochladen:
+lists-have-no-access-control-so = Listen haben keine Zugriffskontrolle, daher funktioniert privates Teilen über Dateisystempfade.
+share-path = Pfad:
+password = Passwort:
+leave-empty-for-a-public-link = Leer lassen für einen öffentlichen Link
+share-button = 🔗 Teilen
+stop-sharing = 🚫 Freigabe beenden
+sharing-disabled = ✅ Freigabe beendet
+export-import-settings = 📦 Einstellungen exportieren / importieren
+include-api-keys = API-Schlüssel und Webhook-URL einschließen
+passphrase = Passphrase:
+the-export-is-encrypted-with-this = Der Export wird mit dieser Passphrase verschlüsselt. Sie wird zum Importieren wieder benötigt.
+api-keys-are-left-out-the = API-Schlüssel und die Webhook-URL werden weggelassen, die Passphrase wird nur zum Importieren verschlüsselter Exporte benötigt.
+export-button = 📤 Exportieren...
+import-button = 📥 Importieren...
+app-data-note = Oder alles auf einmal: Einstellungen, Verlauf und zwischengespeicherte Dateilisten in einem Archiv. API-Schlüssel sind nur enthalten, wenn du sie oben einschließt, verschlüsselt mit der Passphrase.
+app-data-export-button = 🗄 App-Daten exportieren...
+app-data-import-button = ♻ App-Daten wiederherstellen...
+visual-duplicates = 🖼 Optische Duplikate
+visual-duplicates-help = Findet Bilder, die gleich aussehen, auch wenn sie in anderer Größe oder Qualität neu gespeichert wurden. Nur die Vorschaubilder werden heruntergeladen und auf diesem Computer verglichen.
+visual-duplicates-find = 🔍 Optische Duplikate suchen
+visual-duplicates-hashing = Vergleiche Vorschaubilder... { $done }/{ $total }
+visual-duplicates-none = Keine Bilder sehen gleich aus.
+visual-duplicates-oldest = ältestes, bleibt
+visual-duplicates-select-copies = ☑ Kopien auswählen
+visual-duplicates-copies-tooltip = Jedes Bild einer Gruppe außer dem ältesten Upload
+visual-duplicates-delete-copies = 🗑 { $count ->
+    [one] { $count } Kopie
+   *[other] { $count } Kopien
+} löschen
+metadata-concurrency = Parallele Metadaten-Anfragen:
+metadata-concurrency-tooltip = Wie viele Vorschaubilder, Dateidetails und Auflistungen gleichzeitig geladen werden. Getrennt von Uploads und Downloads, damit viele davon Übertragungen nicht ausbremsen.
+list-loading-files = Lade die Dateien der Liste...
+list-file-description = Beschreibung
+list-remove-file = Aus der Liste entfernen, die Datei selbst bleibt erhalten
+list-drag-to-reorder = Auf eine andere Datei ziehen, um sie dorthin zu verschieben, wird mit der Liste gespeichert
+reauth-title = 🔑 API-Schlüssel abgelehnt
+reauth-message = PixelDrain hat den API-Schlüssel abgelehnt, vielleicht wurde er widerrufen oder ist abgelaufen. Anfragen damit funktionieren erst wieder, wenn du ihn ersetzt.
+reauth-paused = { $count ->
+    [one] { $count } Upload ist pausiert und startet mit dem neuen Schlüssel erneut.
+   *[other] { $count } Uploads sind pausiert und starten mit dem neuen Schlüssel erneut.
+}
+reauth-new-key = Neuer API-Schlüssel:
+reauth-save = 💾 Speichern und fortfahren
+reauth-log-in = Anmelden...
+reauth-log-in-tooltip = Hol dir im Tab Einstellungen mit Benutzername und Passwort einen neuen Schlüssel
+reauth-not-now = Nicht jetzt
+resume-title = ⏸ Unterbrochene Übertragungen
+resume-message = { $count ->
+    [one] Diese Übertragung war nicht fertig, als die App zuletzt geschlossen wurde:
+   *[other] Diese { $count } Übertragungen waren nicht fertig, als die App zuletzt geschlossen wurde:
+}
+resume-upload = ⬆ { $name }
+resume-download = ⬇ { $url }
+resume-download-partial = ⬇ { $name }, { $size } heruntergeladen
+resume-button = ▶ Fortsetzen
+resume-discard = Verwerfen
+resume-not-now = Nicht jetzt
+resume-not-now-tooltip = Sie werden beim nächsten Start wieder angeboten. Downloads machen dort weiter, wo sie aufgehört haben, Uploads beginnen von vorn.
+batch-list-offer = { $count } Dateien hochgeladen. Pack sie in eine Liste, um sie mit einem Link zu teilen:
+batch-list-new = Neue Liste
+batch-list-title-hint = Titel der Liste
+batch-list-create = 📋 Liste erstellen
+batch-list-add = ➕ Zur Liste hinzufügen
+batch-list-dismiss = Diese Uploads nicht in eine Liste packen
+batch-list-running = Packe die Uploads in die Liste...
+batch-list-done = Link der Liste in die Zwischenablage kopiert:
+notice-upload-done = Upload fertig
+notice-upload-failed = Upload fehlgeschlagen
+notice-download-done = Download fertig
+notice-download-failed = Download fehlgeschlagen
+notice-quota-warning = Kontingentwarnung
+sink-off = Aus
+sink-desktop = Desktopbenachrichtigung
+sink-toast = Im Fenster
+sink-webhook = Webhook
+sink-log = Nur Protokoll
+notification-webhook-url = Webhook-URL:
+notification-webhook-url-tooltip = Benachrichtigungen werden hierhin als JSON mit den Feldern event, summary und body gesendet
+quota-warning-notice = Upload über dem Kontingent
+toast-dismiss = Zum Schließen klicken
+
+cli-uploaded = { $name } hochgeladen ({ $size })
+cli-downloaded = { $name } heruntergeladen ({ $size })
+cli-bad-size = { $size } ist keine Größe, gib eine wie 1,5GB oder 500MB an
+cli-too-big = { $path } ist { $size } groß, mehr als das Maximum von { $max }
+
+account-export = Konto sichern
+account-export-note = Lädt alle Dateien deines Kontos in einen Ordner herunter, mit einer manifest.json ihrer Details und deiner Listen. Sicherst du erneut in denselben Ordner, wird nur Neues heruntergeladen.
+account-export-concurrency = Parallele Downloads:
+account-export-button = Konto exportieren...
+account-export-progress = Sichere Datei { $done } von { $total }...
+account-export-done = { $count ->
+    [one] Eine Datei nach { $folder } gesichert
+   *[other] { $count } Dateien nach { $folder } gesichert
+}
+account-export-failed = { $count ->
+    [one] Eine Datei konnte nicht heruntergeladen werden, das Manifest nennt den Grund
+   *[other] { $count } Dateien konnten nicht heruntergeladen werden, das Manifest nennt die Gründe
+}
+debug-panel = 🐞 Debug
+stall-timeout = Übertragungen ohne Fortschritt markieren nach:
+stall-timeout-tooltip = Übertragungen, deren Fortschritt sich so lange nicht bewegt hat, werden hier und am Einstellungen-Tab mit ⚠ markiert, sie hängen womöglich an einer verstummten Verbindung.
+debug-no-transfers = Keine laufenden Übertragungen.
+debug-idle = { $seconds } s ohne Fortschritt
+debug-cancel-retry = Abbrechen und neu starten
+debug-retrying = Versuch { $attempt } von { $max } fehlgeschlagen
+folder-sync = 🔄 Ordner-Sync
+folder-sync-note = Lädt die Dateien eines Ordners hoch, die das Konto oder die Liste, mit der er synchronisiert wird, noch nicht hat oder die sich geändert haben, abgeglichen nach Name und SHA-256. Unterordner werden nicht synchronisiert. Jeder Ordner hat eigene Regeln. Die Vorschau zeigt, was ein Sync tun würde, ohne etwas zu ändern.
+folder-sync-folder = Ordner:
+folder-sync-no-folder = keiner hinzugefügt
+folder-sync-add = ➕ Ordner hinzufügen...
+folder-sync-remove = ➖ Nicht mehr synchronisieren
+folder-sync-pair = { $folder } → { $target }
+folder-sync-target = Ziel:
+folder-sync-whole-account = Konto
+folder-sync-max-size = Nur Dateien bis
+folder-sync-include = Nur diese Endungen:
+folder-sync-exclude = Außer:
+folder-sync-extensions-hint = jpg, png
+folder-sync-propagate-deletes = Anbieten, synchronisierte Dateien aus dem Konto zu löschen, die nicht mehr im Ordner sind
+folder-sync-propagate-deletes-tooltip = Nur Dateien, die dieser Sync aus dem Ordner hochgeladen hat, zählen. Sie werden gelöscht, nachdem du den Plan angesehen und bestätigt hast.
+folder-sync-conflicts = Wenn die Version im Konto neuer ist:
+folder-sync-newest-wins = Behalten
+folder-sync-ask = Nachfragen
+folder-sync-preview = 🔍 Vorschau
+folder-sync-run = 🔄 Jetzt synchronisieren
+folder-sync-progress = Synchronisiere { $done } von { $total }...
+folder-sync-plan = { $changes } Änderungen, { $unchanged } unverändert, { $skipped } im Konto neuer, { $excluded } durch die Regeln ausgelassen
+folder-sync-upload = ⬆ { $name } hochladen
+folder-sync-replace = 🔁 { $name } ersetzen
+folder-sync-delete = 🗑 { $name } aus dem Konto löschen
+folder-sync-conflict = ⚠ { $name } ist im Konto neuer, nicht synchronisiert
+folder-sync-done = ✅ { $uploaded } hochgeladen
+folder-sync-delete-gone = 🗑 Die { $count } nicht mehr im Ordner vorhandenen Dateien löschen...
+undo-files = { $count ->
+    [one] { $count } Datei
+   *[other] { $count } Dateien
+}
+undo-deleting = 🗑 { $name } wird in { $seconds } s gelöscht
+undo-button = ↩ Rückgängig
+delete-undo-secs = Zeit zum Rückgängigmachen von Löschungen:
+delete-undo-secs-tooltip = Gelöschte Dateien und Listen werden erst nach dieser Zeit gelöscht, bis dahin kannst du es über eine Meldung rückgängig machen. 0 löscht sofort.
+delete-confirm-title = Löschen?
+delete-confirm-files = { $count ->
+    [one] { $count } Datei
+   *[other] { $count } Dateien
+}, { $size }, insgesamt { $views } Aufrufe:
+delete-confirm-views = { $views } Aufrufe
+delete-confirm-list = Liste „{ $title }“ mit { $count } Dateien. Die Dateien selbst bleiben erhalten.
+delete-confirm-permanent = Gelöschte Dateien und Listen lassen sich nach Ablauf der Zeit zum Rückgängigmachen nicht wiederherstellen.
+delete-confirm-dont-ask = Nicht mehr fragen
+confirm-deletes = Vor dem Löschen von Dateien und Listen fragen
+check-for-updates = Beim Start nach einer neuen Version suchen
+check-for-updates-tooltip = Fragt GitHub nach der neuesten Version. Eine neuere wird im Tab Über angezeigt.
+update-available = 🎉 Version { $version } ist verfügbar, dies ist { $current }.
+update-download = ⬇ Herunterladen
+update-changelog = Was ist neu
+log-view = 📜 Protokoll
+log-filter = Protokollstufen:
+log-filter-tooltip = Eine Stufe für alles und optional eine je Modul, etwa "info,pixeldrain_api=debug". Stufen sind error, warn, info, debug und trace.
+log-to-file = Protokoll auch in eine Datei schreiben, die ab einer bestimmten Größe rotiert wird
+log-search = Suchen
+log-copy = 📋 Kopieren
+log-clear = Leeren
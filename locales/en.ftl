@@ -0,0 +1,697 @@
+# English UI text, the reference every other translation follows.
+# Message ids are shared by all languages, see src/i18n.rs.
+
+## Tabs and header
+tab-upload = 📤 Upload
+tab-download = 📥 Download
+tab-files = 📋 Files
+tab-lists = 📚 Lists
+tab-filesystem = 🗂 Filesystem
+tab-history = 🕘 History
+tab-stats = 📊 Stats
+tab-billing = 🧾 Billing
+tab-settings = ⚙ Settings
+tab-about = ℹ About
+theme-switch-light = Switch to Light Theme
+theme-switch-dark = Switch to Dark Theme
+error-message = ❌ Error: { $error }
+
+## Upload
+upload-file-size = 📏 Size: { $size }
+will-upload-as = Will upload as: { $name }
+upload-files-selected = 📁 { $count } files selected:
+upload-order = Order:
+upload-order-as-selected = As selected
+upload-order-smallest-first = Smallest first
+upload-order-largest-first = Largest first
+upload-concurrency = Parallel uploads:
+upload-concurrency-tooltip = How many files are uploaded at the same time
+rename-files-optional = Rename files (optional, leave empty to keep the name):
+upload-running = ⏳ Uploading...
+upload-files-button = 🚀 Upload { $count } Files
+upload-bundle-button = 📦 Upload as link bundle...
+upload-bundle-tooltip = Uploads the files into a new list and saves an HTML or Markdown page with every link and thumbnail, to send or host anywhere
+quota-warning-file-size = { $count ->
+    [one] A file is
+   *[other] { $count } files are
+} bigger than your account's limit of { $limit } per file, the upload will likely be refused.
+quota-warning-storage = This upload ({ $size }) is bigger than the { $remaining } of storage your account has left.
+quota-upload-anyway = Upload anyway
+status-storage = 💾 Storage:
+status-transfer = 📶 Transfer this month:
+status-unlimited = { $used } (unlimited)
+upload-directory-running = ⏳ Compressing & Uploading...
+upload-directory-button = 🚀 Upload Directory
+archive-format = Archive format:
+archive-format-tar-gz = tar.gz
+archive-format-zip = zip (opens on Windows without extra tools)
+archive-format-tar-zst = tar.zst (smaller, faster)
+archive-parallel = Compress on all cores
+archive-parallel-tooltip = Uses every processor core for zstd, so fast connections aren't held back by compression
+exclude-patterns = Exclude (one pattern per line, like .gitignore):
+exclude-patterns-tooltip = *.tmp leaves out matching files anywhere, node_modules/ leaves out directories of that name, !keep.tmp brings a file back
+directory-after-exclusions = 📏 { $count } files, { $size } after exclusions
+upload-progress = Progress: { $percent }%
+transfer-rate = { $speed }/s, { $eta } remaining
+transfer-rate-no-eta = { $speed }/s
+queue-eta = ⏱ All transfers done in { $eta }, around { $time }
+queue-eta-tooltip = At the average of the last 30 seconds, { $speed }/s
+queue-summary = { $files ->
+    [one] 1 file
+   *[other] { $files } files
+}, { $size } in { $duration }
+
+## Download
+download-location-not-set = Default download location not set
+download-location = 📁 Download location: { $path }
+quota-used-this-month = { $used } of { $allowance } used this month
+quota-remaining = Estimated remaining: { $remaining }
+quota-rate-limited = ⚠ Rate limited { $count ->
+    [one] once
+   *[other] { $count } times
+} this month
+quota-server = Server: { $used } of { $limit } used ({ $checked })
+history-duplicates = { $count } entries with the same link
+
+## Files
+loading-files = Loading files...
+deleting-file = Deleting file...
+api-key-get-from = Get your API key from { $url }
+login-heading = 🔐 Log in with username and password
+login-username = Username:
+login-password = Password:
+login-button = Log in
+login-note = Creates a session for this app and saves its key as the API key. The password isn't stored.
+files-showing = Showing { $shown } of { $total } files
+files-selected-count = { $count } selected
+files-deleting = 🗑 Deleting files... { $done }/{ $total }
+file-views = 👁 { $count } views
+file-downloads = ⬇ { $count } downloads
+filter-all-types = All types
+sort-name = Name
+sort-size = Size
+sort-views = Views
+sort-downloads = Downloads
+sort-upload-date = Upload date
+sort-ascending = Ascending
+sort-descending = Descending
+view-switch-list = Switch to list view
+view-switch-grid = Switch to grid view
+changes-new = { $count } new
+changes-changed = { $count } changed
+changes-deleted = { $count } deleted
+changes-deleted-files = 🗑 Deleted files ({ $count })
+
+## Lists
+refreshing-lists = Refreshing lists...
+creating-list = Creating list...
+deleting-list = Deleting list...
+updating-list = Updating list...
+list-downloading = 📥 Downloading...
+list-summary = Files: { $files } | Created: { $created }
+list-edit-heading = Edit List: { $title }
+list-file-count = Files in this list: { $count }
+
+## Stats
+stats-heading = Usage statistics
+stats-local-only = Counted on this computer only, nothing is sent anywhere.
+stats-empty = Nothing transferred yet. Statistics are counted when the transfer queue empties.
+stats-since = Counting since:
+stats-uploads = Uploads:
+stats-downloads = Downloads:
+stats-files-and-size = { $files ->
+    [one] 1 file
+   *[other] { $files } files
+}, { $size }
+stats-average-speed = Average speed:
+stats-most-active = Most active hours:
+stats-by-hour = Files by hour of the day
+stats-hour-tooltip = { $hour }: { $files ->
+    [one] 1 file
+   *[other] { $files } files
+}
+stats-export = 📤 Export as JSON...
+stats-reset = Reset
+
+## Billing
+billing-heading = Transactions and billing
+billing-no-transactions = No transactions yet.
+billing-balance = Balance
+billing-balance-chart = Balance
+billing-per-month = Per month
+billing-month = Month
+billing-subscription = Subscription
+billing-storage = Storage
+billing-bandwidth = Bandwidth
+billing-total = Charged
+billing-deposits = Deposits
+billing-transactions = Transactions
+billing-date = Date
+billing-change = Change
+billing-used = Storage / bandwidth used
+
+## Filesystem
+loading = Loading...
+updating-share = Updating share...
+share-window-title = 🔒 Share privately
+
+## Settings
+settings-language = 🌐 Language:
+api-key-from-environment = 🔑 API Key from environment: { $prefix }...
+loading-user-info = Loading user info...
+account-unlimited = Unlimited
+account-username = 👤 Username: { $username }
+account-email = 📧 Email: { $email }
+account-files = 📁 Files: { $count }
+account-storage = 💾 Storage: { $used } / { $total }
+account-monthly-transfer = 📊 Monthly Transfer: { $used } / { $cap }
+account-expiry-days = ⏰ Files Expiry Days: { $days }
+account-balance = 💳 Balance: { $balance } micro EUR
+hotlinking-enabled = 🔗 Allow hotlinking
+hotlinking-tooltip = Lets direct links and embeds of your files work without the download page
+hotlinking-note = Downloads through hotlinks are paid from your account's monthly transfer, so a popular embed can use it up quickly. When off, visitors go through the download page and its rate limits.
+sharing-settings = 🎨 Branding and embedding
+embed-domains = Domains allowed to embed your files
+embed-domains-hint = example.com blog.example.com
+branding-theme = Viewer theme
+branding-header-image = Header image (file ID)
+branding-header-link = Header link
+branding-background-image = Background image (file ID)
+sharing-settings-save = 💾 Save
+sharing-settings-reset = Undo changes
+sharing-settings-saving = Saving...
+sessions-heading = 🔑 Active sessions
+sessions-description = Everywhere this account is logged in, API keys included
+sessions-key = Key
+sessions-app = App
+sessions-created = Created
+sessions-last-used = Last used
+sessions-this-app = (this app)
+sessions-revoke = Revoke
+sessions-revoke-current-warning = This logs out this app, its API key stops working.
+sessions-revoke-confirm = Revoke anyway
+profile-active = 👤 Profile: { $name }
+profile-name-empty = Profile name can't be empty
+profile-exists = A profile named "{ $name }" already exists
+
+## About
+about-version = Version: { $version }
+
+## Dialogs
+error-window-title = Error
+error-hint-unauthorized = The API key was rejected. Check it in Settings.
+error-hint-not-found = The file or list doesn't exist or was removed.
+error-hint-too-large = The file is larger than pixeldrain accepts.
+error-hint-rate-limited = Too many requests or the transfer limit was reached. Try again later.
+error-hint-server = pixeldrain had a server problem. Try again in a few minutes.
+error-context-hotlinking = Failed to change hotlinking
+error-context-sharing-settings = Failed to save the sharing settings
+error-context-revoke-session = Failed to revoke the session
+error-context-load-sessions = Failed to load the sessions
+error-context-upload = Upload error
+error-context-add-upload-to-list = Failed to add the upload to the list
+error-context-add-uploads-to-list = Failed to add the uploads to the list
+error-context-split-upload = Split upload error
+error-context-directory-upload = Directory upload error
+error-context-list-download = List download error
+error-context-file-info = Failed to get file info
+error-context-download = Download error
+error-context-list-zip = List ZIP download error
+error-context-account-backup = Account backup error
+link-upload-title = 🔗 Upload requested by a link
+
+## Diagnostics
+diagnostics-capture = 🩺 Capture diagnostics for the next transfer
+diagnostics-capture-hint = Records request timings, retries and response headers to a log file you can attach to a bug report. API keys are not recorded.
+diagnostics-saved = 🩺 Diagnostics saved to { $path }
+diagnostics-copy-path = Copy path
+diagnostics-save-failed = Failed to save diagnostics: { $error }
+
+## History
+
+history-unavailable = History unavailable: { $error }
+history-view-limited = Showing the newest { $count } entries, search to find older ones
+history-uploads = Uploads
+history-downloads = Downloads
+history-from = From
+history-to = to
+history-invalid-date = Not a date: { $date } (use YYYY-MM-DD)
+history-export-csv = 💾 Export CSV
+history-export-json = 💾 Export JSON
+history-exported = ✅ History exported to { $path }
+history-no-matches = No entries match the current filters
+history-column-date = Date
+history-column-name = Name
+history-column-size = Size
+history-column-status = Status
+history-column-link = Link
+history-column-saved-to = Saved to
+settings-high-contrast = High-contrast theme
+settings-high-contrast-tooltip = Black and white colors, bold outlines, a clearly visible focus outline and larger controls
+settings-link-format = Copied links as
+settings-auto-link-type = Pick viewer or direct links by file type
+settings-auto-link-type-tooltip = Copied links of the ticked kinds of files download directly, the others open in the viewer
+settings-direct-links-for = Direct links for:
+file-category-image = Images
+file-category-video = Videos
+file-category-audio = Audio
+file-category-document = Documents
+file-category-archive = Archives
+file-category-other = Programs and other files
+settings-history-storage = History storage
+settings-infer-extensions = Add a file extension to downloads that have none
+settings-infer-extensions-tooltip = Takes the extension from the file type the server detected, so files open in the right program on Windows
+settings-join-split-parts = 🧩 Join split files when downloading their list
+settings-join-split-parts-tooltip = A list of parts named like video.mkv.001, video.mkv.002 is downloaded as video.mkv
+upload-too-large = { $name } is bigger than your account allows ({ $limit }). It can be uploaded in { $parts } parts, kept together in a list.
+upload-split = ✂ Split and upload
+split-upload-needs-account = Split files are kept together in a list, which needs an API key
+split-upload-not-encrypted = Split uploads can't be encrypted yet, turn off encryption to upload this file in parts
+encryption-keys = 🔑 Encryption keys
+encryption-keys-note = Identifiers of the keys you have on this computer. Downloads of files encrypted with another key show a warning. Only the passphrase below is stored, and only when you choose to remember it.
+encryption-key-add = ➕ Add
+encryption-key-remove = Remove this key
+key-id-hint = Key identifier
+encryption-passphrase = Passphrase
+encryption-key-id = Its key identifier
+remember-encryption-passphrase = Remember the passphrase
+remember-encryption-passphrase-tooltip = Keeps the passphrase in the system keyring, otherwise it's entered again every time the app starts
+encryption-passphrase-not-stored = Couldn't store the passphrase in the system keyring: { $error }
+encryption-no-passphrase = Set an encryption passphrase in Settings first
+storage-sqlite = SQLite database (fast search)
+storage-json = JSON file
+settings-cache-limit = Disk cache limit
+settings-cache-limit-tooltip = Counts the cached thumbnails and the list of unfinished transfers. Only thumbnails are removed to stay under it, unfinished transfers are kept until they're resumed or discarded. Their partial downloads are in the download folder and don't count. Previews aren't stored on disk.
+settings-cache-used = { $used } used, { $resume } of it by the list of unfinished transfers
+settings-cache-clear = Clear cache
+
+## File details
+
+file-show-details = Show details
+file-show-details-of = { $name }, click for details
+file-details = File details
+file-details-close = Close
+file-details-yes = Yes
+file-details-no = No
+file-details-never = Never
+file-details-name = Name
+file-details-id = ID
+file-details-url = Link
+file-details-size = Size
+file-details-type = Type
+file-details-uploaded = Uploaded
+file-details-last-viewed = Last viewed
+file-details-views = Views
+file-details-downloads = Downloads
+file-details-bandwidth = Bandwidth used
+file-details-paid-bandwidth = Paid bandwidth used
+file-details-expires = Expires
+file-details-expires-after-downloads = Expires after downloads
+file-details-inactive-expiry = Expires if not viewed by
+file-details-expiry-note = Your plan removes files that haven't been viewed or downloaded for a while, every view starts the period over. Pixeldrain doesn't offer a way to change this per file.
+file-details-availability = Availability
+file-details-available = Available
+file-details-availability-message = Availability message
+file-details-abuse = Abuse report
+file-details-no-abuse-report = None
+file-details-sha256 = SHA-256
+file-details-speed-limit = Download speed limit
+file-details-unlimited = Unlimited
+file-details-can-edit = Can edit
+file-details-can-download = Can download
+file-details-show-ads = Shows ads
+file-details-video-player = Video player allowed
+file-key = Encryption key
+file-key-note = Which key this file was encrypted with, remembered on this computer for this account
+file-key-save = Save
+key-not-configured = 🔒 Encrypted with the key "{ $key }", which isn't configured here
+fix-type = Fix type
+fix-type-note = Pixeldrain can't change the type of a file, so it's uploaded again with the type below and gets a new link.
+fix-type-delete-original = Delete the original afterwards
+fix-type-button = Upload again with this type
+fix-type-running = Uploading again...
+fix-type-done = New link:
+
+## QR codes
+qr-button = QR
+qr-show = Show a QR code of the link, to open it on a phone
+qr-window-title = QR code
+
+## Skipped files
+upload-skipped = { $count ->
+    [one] 1 file was skipped
+   *[other] { $count } files were skipped
+}
+upload-duplicates = { $count ->
+    [one] 📎 1 file is already in your account and wasn't uploaded again:
+   *[other] 📎 { $count } files are already in your account and weren't uploaded again:
+}
+upload-duplicates-copy = 📋 Copy existing links
+upload-duplicates-anyway = Upload anyway
+upload-nothing-to-upload = None of the selected files can be uploaded
+problem-empty = empty file
+problem-special = not a regular file
+problem-unreadable = can't be read: { $error }
+
+## Labels, buttons and tooltips
+since-last-refresh = Since last refresh:
+dismiss = Dismiss
+file-badge-new = 🆕 New
+file-badge-changed = ✏ Changed
+anonymous-upload = 👤 Anonymous Upload
+authenticated-upload = 🔐 Authenticated Upload
+using-api-key-from-environment-variable = Using API key from environment variable
+using-api-key-from-settings = Using API key from settings
+no-api-key-configured-upload-is = ⚠ No API key configured - the upload will be anonymous and won't be linked to your account
+please-enter-your-api-key-in = Enter your API key in settings to upload to your account.
+upload-anonymously-ignore-my-api-key = 👤 Upload anonymously (ignore my API key)
+applies-to-the-next-upload-only = Applies to the next upload only
+anonymous-upload-consequences = The file won't appear in your account: you can't delete it, add it to lists or see its stats later, it is subject to anonymous upload limits and expiry, and the server may refuse it.
+upload-add-to-list = Add to list:
+upload-no-list = (none)
+upload-reload-lists = Reload your lists
+file-to-upload = File to upload:
+rename-file-optional = 📝 Rename file (optional):
+use-original = Use original
+clear = Clear
+rename-archive-optional = 📝 Rename archive (optional):
+no-file-or-directory-selected = 📁 No file or directory selected
+select-files = 📁 Select Files
+select-directory = 📂 Select Directory
+paste-image = 📋 Paste Image
+paste-image-hover = Upload the image on the clipboard, also Ctrl+V
+paste-image-none = There is no image on the clipboard
+verify-upload-size-sha-256 = 🔍 Verify upload (size + SHA-256)
+re-hash-the-local-file-after = Re-hash the local file after upload and compare it with the server metadata
+skip-duplicate-uploads = 📎 Skip files already in my account
+skip-duplicate-uploads-tooltip = Hashes files before uploading and offers the existing link when your account has a file with the same SHA-256
+encrypt-uploads = 🔒 Encrypt before uploading
+encrypt-uploads-tooltip = Encrypts files with the passphrase from Settings and uploads them as .enc files. Downloads of .enc files are decrypted when the passphrase is set.
+check-before-large-uploads = 🩺 Test the connection before large uploads
+check-before-large-uploads-tooltip = Before uploading 1 GB or more, checks the API key and free space and uploads and deletes a tiny test file
+upload-button = 🚀 Upload
+preparing-hashing-files = 🔎 Preparing: hashing files...
+checking-before-upload = Checking the API key, space and connection...
+check-key-failed = The API key check failed: { $error }
+check-no-space = There's not enough free space on the account for this upload
+check-upload-failed = The test upload failed: { $error }
+check-delete-failed = The test file { $id } could not be deleted: { $error }
+uploading-directory = Uploading directory...
+uploading = 📤 Uploading...
+upload-complete-url-copied-to-clipboard = ✅ Upload complete! URL copied to clipboard.
+recent-uploads = Recent Uploads
+no-uploads-yet = No uploads yet
+no-uploads-match-the-current-filters = No uploads match the current filters
+failed = ❌ Failed
+verified = ✅ Verified
+size-and-sha-256-match-the = Size and SHA-256 match the local file
+mismatch = ⚠ Mismatch
+the-uploaded-file-does-not-match = The uploaded file does not match the local file
+anonymous = 👤 Anonymous
+not-linked-to-your-account-it = Not linked to your account, it can't be managed or deleted from here
+copy = 📋 Copy
+public-file-download = ⬇ Public File Download
+download-any-public-pixeldrain-file-or = Download any public PixelDrain file or list (no API key required)
+download-url = URL:
+download-url-hint = Link, or part of a name from your history
+download-button = ⬇ Download
+download-as-zip = 📦 Download as ZIP
+downloading = Downloading...
+done = ✅ Done
+recent-downloads = Recent Downloads
+no-downloads-yet = No downloads yet
+no-downloads-match-the-current-filters = No downloads match the current filters
+download-quota-estimate = 📊 Download quota estimate
+monthly-allowance = Monthly allowance:
+size-field-tooltip = A size like 1.5 GB or 500 MB, applied when you press Enter or leave the field
+server-reports-no-transfer-limit-for = Server reports no transfer limit for this connection
+server-limits-not-checked-yet = Server limits not checked yet
+check-rate-limits = Check rate limits
+counted-by-this-app-only-downloads = Counted by this app only, downloads in the browser are not included.
+search-names-and-urls = 🔍 Search names and URLs
+group-by-day = Group by day
+collapse-duplicates = Collapse duplicates
+only-failures = Only failures
+your-files = Your Files
+refresh = 🔄 Refresh
+no-api-key-configured = ⚠ No API key configured
+set-your-api-key-in-settings = Set your API key in Settings or use PIXELDRAIN_API_KEY environment variable (settings override environment)
+no-files-found-click-refresh-to = No files found. Click 'Refresh' to load your files.
+no-files-match-the-current-search = No files match the current search and filter.
+select-all = ☑ Select all
+select-all-files-matching-the-current = Select all files matching the current filter
+clear-selection = ☐ Clear selection
+download-selected-as-zip = 📦 Download selected as ZIP
+copy-selected-urls = 📋 Copy selected URLs
+delete-selected = 🗑 Delete selected
+building-zip-bundle = 📦 Building ZIP bundle...
+copy-url = Copy URL
+delete = Delete
+copy-url-button = 📋 Copy URL
+copy-as = Copy link as…
+link-format-plain = Link
+link-format-markdown = Markdown
+link-format-bbcode = BBCode
+link-format-html = HTML link
+link-format-direct = Direct download link
+delete-button = 🗑 Delete
+search-by-name-or-id = Search by name or ID
+sort-by = Sort:
+your-lists = Your Lists
+refresh-lists = 🔄 Refresh Lists
+share-privately = 🔒 Share privately...
+lists-are-always-public-share-a = Lists are always public. Share a filesystem folder with a password instead.
+create-new-list = Create New List
+list-title = Title:
+select-files-to-add-to-the = Select files to add to the list:
+create-list = Create List
+no-lists-found-click-refresh-lists = No lists found. Click 'Refresh Lists' or create a new list.
+add-remove-files = Add/remove files:
+save-changes = Save Changes
+an-api-key-is-required-to = 🔑 An API key is required to browse the filesystem. Set one in Settings.
+filesystem = Filesystem
+up = ⬆ Up
+upload-files-here = 📤 Upload files here
+new-folder-name = New folder name
+create-folder = 📁 Create folder
+this-folder-is-empty = This folder is empty
+rename = Rename
+share = Share
+download = Download
+new-name = New name:
+rename-confirm = ✅ Rename
+cancel = Cancel
+pixeldrain-api-key = PixelDrain API Key:
+paste = 📋 Paste
+show-api-key = Show the API key
+hide-api-key = Hide the API key
+store-api-key-in-the-settings = Store API key in the settings file (plaintext)
+only-needed-when-no-system-keyring = Only needed when no system keyring is available
+the-api-key-is-saved-unencrypted = ⚠ The API key is saved unencrypted in settings.json
+the-api-key-is-kept-in = 🔒 The API key is kept in the system keyring
+environment-api-key-will-be-used = 💡 Environment API key will be used as fallback
+settings-api-key-will-be-used = ✅ Settings API key will be used (overrides environment)
+default-download-location = Default Download Location:
+choose-folder = 📁 Choose Folder
+account-information = Account Information
+no-account-information-available-set-api = No account information available. Set API key in settings or PIXELDRAIN_API_KEY environment variable, then click Refresh.
+save-settings = 💾 Save Settings
+settings-saved-successfully = ✅ Settings saved successfully!
+api-key-download-location-and-preferences = API key, download location and preferences below belong to this profile.
+remove-profile = Remove profile
+new-profile-name = New profile name
+add-profile = ➕ Add profile
+server-url = Server URL:
+server-url-reset = ↺ Default
+change-this-only-for-a-self = Change this only for a self-hosted or mirrored pixeldrain instance
+window-name-shown-in-the-title = Window name (shown in the title bar and taskbar):
+window-name-hint = e.g. Work account
+tint-taskbar-icon = Tint taskbar icon
+desktop-notification-when-a-transfer-finishes = 🔔 Notify when a transfer finishes or an upload goes over the quota
+prevent-sleep-during-transfers = ☕ Keep the computer awake during transfers
+prevent-sleep-during-transfers-tooltip = Stops the system from going to sleep while uploads or downloads run, so they aren't cut off overnight. The screen can still turn off.
+sleep-when-transfers-finish = 💤 Sleep when done
+sleep-when-transfers-finish-tooltip = Puts the computer to sleep once every running transfer has finished. Only applies to the current transfers.
+sleep-failed = Couldn't put the computer to sleep: { $error }
+a-modern-unofficial-desktop-client-for = A modern unofficial desktop client for PixelDrain file sharing service.
+built-with-rust-and-egui = Built with Rust and egui.
+about-features = Features:
+upload-files-and-folders-with-progress = • 📤 Upload files and folders with progress tracking (API key required)
+download-files-from-pixeldrain-urls-no = • 📥 Download files from PixelDrain URLs (no API key required)
+copy-shareable-links-to-clipboard = • 📋 Copy shareable links to clipboard
+manage-your-uploaded-files = • 📁 Manage your uploaded files
+configure-api-key-and-settings = • ⚙ Configure API key and settings
+environment-variable-support-pixeldrain-api-key = • 🔑 Environment variable support (PIXELDRAIN_API_KEY)
+technical-details = Technical details
+error-status = Status:
+error-endpoint = Endpoint:
+error-request-id = Request ID:
+error-time = Time:
+copy-details = 📋 Copy details
+copy-a-snippet-for-a-bug = Copy a snippet for a bug report
+ok = OK
+another-application-asked-to-upload = Another application asked to upload:
+lists-have-no-access-control-so = Lists have no access control, so private sharing works on filesystem paths.
+share-path = Path:
+password = Password:
+leave-empty-for-a-public-link = Leave empty for a public link
+share-button = 🔗 Share
+stop-sharing = 🚫 Stop sharing
+sharing-disabled = ✅ Sharing disabled
+export-import-settings = 📦 Export / import settings
+include-api-keys = Include API keys and the webhook URL
+passphrase = Passphrase:
+the-export-is-encrypted-with-this = The export is encrypted with this passphrase. It is needed again to import it.
+api-keys-are-left-out-the = API keys and the webhook URL are left out, the passphrase is only needed to import encrypted exports.
+export-button = 📤 Export...
+import-button = 📥 Import...
+app-data-note = Or everything at once: settings, history and cached file lists in one archive. API keys are only in it when included above, encrypted with the passphrase.
+app-data-export-button = 🗄 Export app data...
+app-data-import-button = ♻ Restore app data...
+visual-duplicates = 🖼 Visual duplicates
+visual-duplicates-help = Finds images that look the same, even saved again at another size or quality. Only the thumbnails are downloaded and compared on this computer.
+visual-duplicates-find = 🔍 Find visual duplicates
+visual-duplicates-hashing = Comparing thumbnails... { $done }/{ $total }
+visual-duplicates-none = No images look the same.
+visual-duplicates-oldest = oldest, kept
+visual-duplicates-select-copies = ☑ Select copies
+visual-duplicates-copies-tooltip = Every image of a group except the oldest upload
+visual-duplicates-delete-copies = 🗑 Delete { $count ->
+    [one] { $count } copy
+   *[other] { $count } copies
+}
+metadata-concurrency = Parallel metadata requests:
+metadata-concurrency-tooltip = How many thumbnails, file details and listings are loaded at the same time. Kept apart from uploads and downloads so loading many of them doesn't slow transfers down.
+list-loading-files = Loading the files of the list...
+list-file-description = Description
+list-remove-file = Remove from the list, the file itself is kept
+list-drag-to-reorder = Drag onto another file to move it there, saved with the list
+reauth-title = 🔑 API key refused
+reauth-message = PixelDrain refused the API key, it may have been revoked or expired. Requests with it won't work until it's replaced.
+reauth-paused = { $count ->
+    [one] { $count } upload is paused and starts again with the new key.
+   *[other] { $count } uploads are paused and start again with the new key.
+}
+reauth-new-key = New API key:
+reauth-save = 💾 Save and continue
+reauth-log-in = Log in...
+reauth-log-in-tooltip = Get a new key with your username and password on the Settings tab
+reauth-not-now = Not now
+resume-title = ⏸ Interrupted transfers
+resume-message = { $count ->
+    [one] This transfer wasn't finished when the app closed last time:
+   *[other] These { $count } transfers weren't finished when the app closed last time:
+}
+resume-upload = ⬆ { $name }
+resume-download = ⬇ { $url }
+resume-download-partial = ⬇ { $name }, { $size } downloaded
+resume-button = ▶ Resume
+resume-discard = Discard
+resume-not-now = Not now
+resume-not-now-tooltip = They're offered again at the next start. Downloads continue where they stopped, uploads start over.
+batch-list-offer = { $count } files uploaded. Put them in a list to share them with one link:
+batch-list-new = New list
+batch-list-title-hint = List title
+batch-list-create = 📋 Create list
+batch-list-add = ➕ Add to list
+batch-list-dismiss = Don't put these uploads in a list
+batch-list-running = Putting the uploads in the list...
+batch-list-done = List link copied to clipboard:
+notice-upload-done = Upload finished
+notice-upload-failed = Upload failed
+notice-download-done = Download finished
+notice-download-failed = Download failed
+notice-quota-warning = Quota warning
+sink-off = Off
+sink-desktop = Desktop notification
+sink-toast = In the window
+sink-webhook = Webhook
+sink-log = Log only
+notification-webhook-url = Webhook URL:
+notification-webhook-url-tooltip = Notifications are posted here as JSON with the fields event, summary and body
+quota-warning-notice = Upload over the quota
+toast-dismiss = Click to dismiss
+
+cli-uploaded = Uploaded { $name } ({ $size })
+cli-downloaded = Downloaded { $name } ({ $size })
+cli-bad-size = { $size } isn't a size, give one like 1.5GB or 500MB
+cli-too-big = { $path } is { $size }, more than the maximum of { $max }
+
+account-export = Back up account
+account-export-note = Downloads every file of your account into a folder, with a manifest.json of their details and your lists. Backing up into the same folder again only downloads what's new.
+account-export-concurrency = Parallel downloads:
+account-export-button = Export account...
+account-export-progress = Backing up file { $done } of { $total }...
+account-export-done = { $count ->
+    [one] Backed up one file to { $folder }
+   *[other] Backed up { $count } files to { $folder }
+}
+account-export-failed = { $count ->
+    [one] One file couldn't be downloaded, the manifest says why
+   *[other] { $count } files couldn't be downloaded, the manifest says why
+}
+debug-panel = 🐞 Debug
+stall-timeout = Flag transfers without progress after:
+stall-timeout-tooltip = Transfers whose progress hasn't moved for this long are marked ⚠ here and on the Settings tab, they may hang on a connection that went silent.
+debug-no-transfers = No transfers running.
+debug-idle = { $seconds } s without progress
+debug-cancel-retry = Cancel and retry
+debug-retrying = attempt { $attempt } of { $max } failed
+folder-sync = 🔄 Folder sync
+folder-sync-note = Uploads the files of a folder that the account, or the list it's synced to, doesn't have yet or that changed, matched by name and SHA-256. Subfolders are not synced. Each folder has its own rules. Preview shows what a sync would do without changing anything.
+folder-sync-folder = Folder:
+folder-sync-no-folder = none added
+folder-sync-add = ➕ Add folder...
+folder-sync-remove = ➖ Stop syncing
+folder-sync-pair = { $folder } → { $target }
+folder-sync-target = Sync to:
+folder-sync-whole-account = the account
+folder-sync-max-size = Only files up to
+folder-sync-include = Only these extensions:
+folder-sync-exclude = Except:
+folder-sync-extensions-hint = jpg, png
+folder-sync-propagate-deletes = Offer to delete synced files from the account when they're no longer in the folder
+folder-sync-propagate-deletes-tooltip = Only files this sync uploaded from the folder count. They're deleted once you confirm, after looking at the plan.
+folder-sync-conflicts = When the account's version is newer:
+folder-sync-newest-wins = Keep it
+folder-sync-ask = Ask
+folder-sync-preview = 🔍 Preview
+folder-sync-run = 🔄 Sync now
+folder-sync-progress = Syncing { $done } of { $total }...
+folder-sync-plan = { $changes } changes, { $unchanged } unchanged, { $skipped } newer in the account, { $excluded } left out by the rules
+folder-sync-upload = ⬆ Upload { $name }
+folder-sync-replace = 🔁 Replace { $name }
+folder-sync-delete = 🗑 Delete { $name } from the account
+folder-sync-conflict = ⚠ { $name } is newer in the account, not synced
+folder-sync-done = ✅ Uploaded { $uploaded }
+folder-sync-delete-gone = 🗑 Delete the { $count } files gone from the folder...
+undo-files = { $count ->
+    [one] { $count } file
+   *[other] { $count } files
+}
+undo-deleting = 🗑 Deleting { $name } in { $seconds } s
+undo-button = ↩ Undo
+delete-undo-secs = Time to undo deletions:
+delete-undo-secs-tooltip = Deleted files and lists are only deleted after this long, with a toast to undo it until then. 0 deletes right away.
+delete-confirm-title = Delete?
+delete-confirm-files = { $count ->
+    [one] { $count } file
+   *[other] { $count } files
+}, { $size }, { $views } views in total:
+delete-confirm-views = { $views } views
+delete-confirm-list = List "{ $title }" with { $count } files. The files themselves are kept.
+delete-confirm-permanent = Deleted files and lists can't be brought back once the undo time is over.
+delete-confirm-dont-ask = Don't ask again
+confirm-deletes = Ask before deleting files and lists
+check-for-updates = Check for a new version at startup
+check-for-updates-tooltip = Asks GitHub for the latest release. A newer one is shown in the About tab.
+update-available = 🎉 Version { $version } is available, this is { $current }.
+update-download = ⬇ Download
+update-changelog = What's new
+log-view = 📜 Log
+log-filter = Log levels:
+log-filter-tooltip = A level for everything and optionally one per module, like "info,pixeldrain_api=debug". Levels are error, warn, info, debug and trace.
+log-to-file = Also write the log to a file, rotated when it gets big
+log-search = Search
+log-copy = 📋 Copy
+log-clear = Clear
@@ -0,0 +1,215 @@
+// diagnostics.rs - Network diagnostics for a single transfer
+// When enabled for a transfer, the API client records every request, the
+// response status and headers, retries, and how long each 1 MiB chunk took.
+// The log is written to a folder chosen by the caller so it can be attached to a
+// bug report.
+use chrono::{DateTime, Utc};
+use std::fmt::Display;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+const CHUNK_SIZE: u64 = 1024 * 1024;
+
+// Never written to the log, they carry credentials
+const REDACTED_HEADERS: [&str; 3] = ["authorization", "cookie", "set-cookie"];
+
+#[derive(Debug)]
+pub struct TransferDiagnostics {
+    label: String,
+    started: Instant,
+    started_at: DateTime<Utc>,
+    inner: Mutex<Recording>,
+}
+
+#[derive(Debug, Default)]
+struct Recording {
+    lines: Vec<String>,
+    chunk: Option<ChunkTimer>,
+}
+
+#[derive(Debug)]
+struct ChunkTimer {
+    index: u64,
+    offset: u64,
+    bytes: u64,
+    total: Option<u64>,
+    since: Instant,
+}
+
+impl TransferDiagnostics {
+    pub fn new(label: &str) -> Self {
+        Self {
+            label: label.to_string(),
+            started: Instant::now(),
+            started_at: Utc::now(),
+            inner: Mutex::new(Recording::default()),
+        }
+    }
+
+    fn log(&self, recording: &mut Recording, line: String) {
+        let elapsed = self.started.elapsed().as_secs_f64();
+        recording.lines.push(format!("[+{:>9.3}s] {}", elapsed, line));
+    }
+
+    pub fn note(&self, message: &str) {
+        let mut recording = self.inner.lock().unwrap();
+        self.log(&mut recording, message.to_string());
+    }
+
+    pub fn request(&self, method: &str, url: &str) {
+        let mut recording = self.inner.lock().unwrap();
+        self.log(&mut recording, format!("request {} {}", method, url));
+    }
+
+    pub fn response(&self, response: &reqwest::blocking::Response) {
+        let mut recording = self.inner.lock().unwrap();
+        self.flush_chunk(&mut recording);
+        self.log(&mut recording, format!("response {} from {}", response.status(), response.url().path()));
+        for (name, value) in response.headers() {
+            if REDACTED_HEADERS.contains(&name.as_str()) {
+                continue;
+            }
+            recording.lines.push(format!("             {}: {}", name, value.to_str().unwrap_or("<binary>")));
+        }
+    }
+
+    pub fn retry(&self, attempt: usize, max_attempts: usize, reason: &dyn Display) {
+        let mut recording = self.inner.lock().unwrap();
+        self.flush_chunk(&mut recording);
+        self.log(&mut recording, format!("attempt {}/{} failed, retrying: {}", attempt, max_attempts, reason));
+    }
+
+    /// Start timing the body of a request or response, `total` bytes if known
+    pub fn begin_transfer(&self, total: Option<u64>) {
+        let mut recording = self.inner.lock().unwrap();
+        self.flush_chunk(&mut recording);
+        recording.chunk = Some(ChunkTimer { index: 0, offset: 0, bytes: 0, total, since: Instant::now() });
+    }
+
+    /// Count `bytes` moved, logging a line for every completed chunk
+    pub fn transferred(&self, bytes: usize) {
+        let mut recording = self.inner.lock().unwrap();
+        let Some(chunk) = recording.chunk.as_mut() else {
+            return;
+        };
+        chunk.bytes += bytes as u64;
+        if chunk.bytes >= CHUNK_SIZE {
+            let line = chunk.finish();
+            self.log(&mut recording, line);
+        }
+    }
+
+    /// Log the last, partial chunk once the body is complete
+    pub fn end_transfer(&self) {
+        let mut recording = self.inner.lock().unwrap();
+        self.flush_chunk(&mut recording);
+        recording.chunk = None;
+    }
+
+    fn flush_chunk(&self, recording: &mut Recording) {
+        if let Some(chunk) = recording.chunk.as_mut()
+            && chunk.bytes > 0
+        {
+            let line = chunk.finish();
+            self.log(recording, line);
+        }
+    }
+
+    pub fn report(&self) -> String {
+        let recording = self.inner.lock().unwrap();
+        let mut report = format!(
+            "PixelDrain client {} transfer diagnostics\nTransfer: {}\nStarted: {}\nOS: {} {}\n\n",
+            env!("CARGO_PKG_VERSION"),
+            self.label,
+            self.started_at.format("%Y-%m-%d %H:%M:%S UTC"),
+            std::env::consts::OS,
+            std::env::consts::ARCH,
+        );
+        for line in &recording.lines {
+            report.push_str(line);
+            report.push('\n');
+        }
+        report
+    }
+
+    /// Write the report to `dir` and return its path
+    pub fn save(&self, dir: &Path) -> io::Result<PathBuf> {
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join(format!("transfer-{}.log", self.started_at.format("%Y%m%d-%H%M%S")));
+        std::fs::write(&path, self.report())?;
+        Ok(path)
+    }
+}
+
+impl ChunkTimer {
+    fn finish(&mut self) -> String {
+        let elapsed = self.since.elapsed();
+        let speed = self.bytes as f64 / elapsed.as_secs_f64().max(0.001) / CHUNK_SIZE as f64;
+        let of_total = self.total.map(|total| format!(" of {}", total)).unwrap_or_default();
+        let line = format!(
+            "chunk #{} at byte {}{}: {} bytes in {} ms ({:.2} MiB/s)",
+            self.index,
+            self.offset,
+            of_total,
+            self.bytes,
+            elapsed.as_millis(),
+            speed
+        );
+        self.index += 1;
+        self.offset += self.bytes;
+        self.bytes = 0;
+        self.since = Instant::now();
+        line
+    }
+}
+
+/// Diagnostics requested for one transfer. The recording is saved when the
+/// worker drops this, however the transfer ended, and the outcome is put in `saved_to`.
+pub struct DiagnosticsCapture {
+    recorder: Arc<TransferDiagnostics>,
+    dir: PathBuf,
+    saved_to: Arc<Mutex<Option<Result<PathBuf, String>>>>,
+}
+
+impl DiagnosticsCapture {
+    /// Capture for the transfer `label`, saved in `dir` when dropped
+    pub fn new(label: &str, dir: PathBuf, saved_to: Arc<Mutex<Option<Result<PathBuf, String>>>>) -> Self {
+        Self { recorder: Arc::new(TransferDiagnostics::new(label)), dir, saved_to }
+    }
+
+    pub fn recorder(&self) -> Arc<TransferDiagnostics> {
+        self.recorder.clone()
+    }
+}
+
+impl Drop for DiagnosticsCapture {
+    fn drop(&mut self) {
+        *self.saved_to.lock().unwrap() = Some(self.recorder.save(&self.dir).map_err(|e| e.to_string()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn logs_one_line_per_chunk_and_the_remainder() {
+        let diagnostics = TransferDiagnostics::new("test.bin");
+        diagnostics.begin_transfer(Some(2 * CHUNK_SIZE + 10));
+        for _ in 0..(2 * CHUNK_SIZE / 8192) {
+            diagnostics.transferred(8192);
+        }
+        diagnostics.transferred(10);
+        diagnostics.end_transfer();
+        diagnostics.retry(1, 3, &"timed out");
+
+        let report = diagnostics.report();
+        assert!(report.contains("Transfer: test.bin"));
+        assert!(report.contains(&format!("chunk #0 at byte 0 of {}: {} bytes", 2 * CHUNK_SIZE + 10, CHUNK_SIZE)));
+        assert!(report.contains(&format!("chunk #1 at byte {}", CHUNK_SIZE)));
+        assert!(report.contains(&format!("chunk #2 at byte {}", 2 * CHUNK_SIZE)));
+        assert!(report.contains("attempt 1/3 failed, retrying: timed out"));
+    }
+}
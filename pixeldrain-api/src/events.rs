@@ -0,0 +1,274 @@
+// events.rs - Transfer events for programs that run transfers, such as a GUI
+// or a TUI. Every upload and download reports when it starts, its progress, and
+// whether it finished or failed, on channels or callbacks. What the API clients
+// send while they transfer waits in a feed until the program drains it, say
+// once per frame, so a client never waits on the interface.
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Mutex};
+
+use crate::{Progress, ProgressSender, TransferEvent as ClientEvent};
+
+pub type TransferId = u64;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Direction {
+    Upload,
+    Download,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    Started { id: TransferId, direction: Direction, name: String },
+    /// Fraction done, from 0.0 to 1.0
+    Progress { id: TransferId, fraction: f32 },
+    /// An attempt failed for `reason`, the client tries again
+    Retrying { id: TransferId, attempt: usize, max_attempts: usize, reason: String },
+    /// `link` is the file URL of an upload, or where a download was saved
+    Finished { id: TransferId, direction: Direction, name: String, link: String },
+    Failed { id: TransferId, direction: Direction, name: String, error: String },
+}
+
+/// Outcome of a transfer, as the worker that ran it knows it
+pub enum TransferOutcome {
+    UploadFinished { name: String, url: String },
+    UploadFailed { name: String, error: String },
+    DownloadFinished { name: String, path: String },
+    DownloadFailed { name: String, error: String },
+}
+
+type Callback = Box<dyn Fn(&Event) + Send + Sync>;
+type ProgressHandler = Box<dyn FnMut(Progress) + Send>;
+
+/// What an API client sent, waiting to be handed to `on_progress`
+struct Feed {
+    events: Receiver<ClientEvent>,
+    on_progress: ProgressHandler,
+}
+
+// A feed with the transfer it belongs to, if any
+type FeedEntry = (Option<TransferId>, Arc<Mutex<Feed>>);
+
+/// Where transfer events are sent. Callbacks run on the worker thread of the
+/// transfer or on the thread draining the feeds, so they should return quickly.
+#[derive(Default)]
+pub struct Events {
+    next_id: AtomicU64,
+    channels: Mutex<Vec<Sender<Event>>>,
+    callbacks: Mutex<Vec<Callback>>,
+    feeds: Mutex<Vec<FeedEntry>>,
+}
+
+impl Events {
+    /// Receive every event from now on. Dropping the receiver unsubscribes.
+    pub fn subscribe(&self) -> Receiver<Event> {
+        let (sender, receiver) = mpsc::channel();
+        self.channels.lock().unwrap().push(sender);
+        receiver
+    }
+
+    /// Call `callback` with every event from now on
+    pub fn on_event(&self, callback: impl Fn(&Event) + Send + Sync + 'static) {
+        self.callbacks.lock().unwrap().push(Box::new(callback));
+    }
+
+    pub fn emit(&self, event: Event) {
+        for callback in self.callbacks.lock().unwrap().iter() {
+            callback(&event);
+        }
+        self.channels.lock().unwrap().retain(|sender| sender.send(event.clone()).is_ok());
+    }
+
+    /// Announce a new transfer. It reports failure when dropped without `end`,
+    /// so a worker that gives up early still closes it.
+    pub fn start(self: &Arc<Self>, direction: Direction, name: impl Into<String>) -> Transfer {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let name = name.into();
+        self.emit(Event::Started { id, direction, name: name.clone() });
+        Transfer { id, direction, name, events: self.clone(), ended: false }
+    }
+
+    /// Sender for an API client working outside of a transfer. What it sends
+    /// goes to `on_progress` when the feeds are drained.
+    pub fn progress_sender(&self, on_progress: impl FnMut(Progress) + Send + 'static) -> ProgressSender {
+        self.feed(None, Box::new(on_progress))
+    }
+
+    fn feed(&self, transfer: Option<TransferId>, on_progress: ProgressHandler) -> ProgressSender {
+        let (sender, events) = mpsc::channel();
+        self.feeds.lock().unwrap().push((transfer, Arc::new(Mutex::new(Feed { events, on_progress }))));
+        sender
+    }
+
+    /// Hand what the API clients sent since the last call to its handlers.
+    /// True while any client still has a sender, so more can follow.
+    pub fn drain_progress(&self) -> bool {
+        self.drain_feeds(None);
+        !self.feeds.lock().unwrap().is_empty()
+    }
+
+    /// Drain the feeds of `only` or else all of them, and drop the ones whose
+    /// client is done. The list isn't locked while handlers run.
+    fn drain_feeds(&self, only: Option<TransferId>) {
+        let feeds: Vec<FeedEntry> = self.feeds.lock().unwrap()
+            .iter()
+            .filter(|(transfer, _)| only.is_none() || *transfer == only)
+            .cloned()
+            .collect();
+        let closed: Vec<Arc<Mutex<Feed>>> = feeds
+            .into_iter()
+            .filter(|(transfer, feed)| !self.drain_feed(*transfer, &mut feed.lock().unwrap()))
+            .map(|(_, feed)| feed)
+            .collect();
+        self.feeds.lock().unwrap().retain(|(_, feed)| !closed.iter().any(|closed| Arc::ptr_eq(closed, feed)));
+    }
+
+    /// False once the client dropped its sender
+    fn drain_feed(&self, transfer: Option<TransferId>, feed: &mut Feed) -> bool {
+        loop {
+            match feed.events.try_recv() {
+                Ok(ClientEvent::Progress(progress)) => (feed.on_progress)(progress),
+                Ok(ClientEvent::Retrying { attempt, max_attempts, reason }) => {
+                    if let Some(id) = transfer {
+                        self.emit(Event::Retrying { id, attempt, max_attempts, reason });
+                    }
+                }
+                // The transfer itself reports its start and outcome
+                Ok(ClientEvent::Started { .. } | ClientEvent::Completed | ClientEvent::Failed { .. }) => {}
+                Err(TryRecvError::Empty) => return true,
+                Err(TryRecvError::Disconnected) => return false,
+            }
+        }
+    }
+
+    /// Hand over what's left for transfer `id` and forget its feeds
+    fn close_feeds(&self, id: TransferId) {
+        self.drain_feeds(Some(id));
+        self.feeds.lock().unwrap().retain(|(transfer, _)| *transfer != Some(id));
+    }
+}
+
+/// A running transfer, held by the worker that runs it
+pub struct Transfer {
+    id: TransferId,
+    direction: Direction,
+    name: String,
+    events: Arc<Events>,
+    ended: bool,
+}
+
+impl Transfer {
+    pub fn id(&self) -> TransferId {
+        self.id
+    }
+
+    /// Progress reports for use in progress callbacks
+    pub fn progress(&self) -> TransferProgress {
+        TransferProgress { id: self.id, events: self.events.clone() }
+    }
+
+    pub fn end(mut self, outcome: TransferOutcome) {
+        self.ended = true;
+        self.events.close_feeds(self.id);
+        let (id, direction) = (self.id, self.direction);
+        self.events.emit(match outcome {
+            TransferOutcome::UploadFinished { name, url: link } | TransferOutcome::DownloadFinished { name, path: link } => {
+                Event::Finished { id, direction, name, link }
+            }
+            TransferOutcome::UploadFailed { name, error } | TransferOutcome::DownloadFailed { name, error } => {
+                Event::Failed { id, direction, name, error }
+            }
+        });
+    }
+}
+
+impl Drop for Transfer {
+    fn drop(&mut self) {
+        if !self.ended {
+            self.events.close_feeds(self.id);
+            self.events.emit(Event::Failed {
+                id: self.id,
+                direction: self.direction,
+                name: std::mem::take(&mut self.name),
+                error: "Stopped before it finished".to_string(),
+            });
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct TransferProgress {
+    id: TransferId,
+    events: Arc<Events>,
+}
+
+impl TransferProgress {
+    pub fn report(&self, fraction: f32) {
+        self.events.emit(Event::Progress { id: self.id, fraction });
+    }
+
+    /// Sender for an API client working on this transfer. What it sends goes
+    /// to `on_progress` when the feeds are drained, and all of it before the
+    /// transfer ends.
+    pub fn sender(&self, on_progress: impl FnMut(Progress) + Send + 'static) -> ProgressSender {
+        self.events.feed(Some(self.id), Box::new(on_progress))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transfers_report_start_progress_and_outcome() {
+        let events = Arc::new(Events::default());
+        let receiver = events.subscribe();
+        let seen = Arc::new(Mutex::new(0));
+        {
+            let seen = seen.clone();
+            events.on_event(move |_| *seen.lock().unwrap() += 1);
+        }
+
+        let upload = events.start(Direction::Upload, "a.txt");
+        upload.progress().report(0.5);
+        upload.end(TransferOutcome::UploadFinished { name: "a.txt".to_string(), url: "https://pixeldrain.com/u/abc".to_string() });
+        // Dropped without an outcome
+        drop(events.start(Direction::Download, "b.txt"));
+
+        let received: Vec<Event> = receiver.try_iter().collect();
+        assert_eq!(received, [
+            Event::Started { id: 1, direction: Direction::Upload, name: "a.txt".to_string() },
+            Event::Progress { id: 1, fraction: 0.5 },
+            Event::Finished { id: 1, direction: Direction::Upload, name: "a.txt".to_string(), link: "https://pixeldrain.com/u/abc".to_string() },
+            Event::Started { id: 2, direction: Direction::Download, name: "b.txt".to_string() },
+            Event::Failed { id: 2, direction: Direction::Download, name: "b.txt".to_string(), error: "Stopped before it finished".to_string() },
+        ]);
+        assert_eq!(*seen.lock().unwrap(), 5);
+    }
+
+    #[test]
+    fn client_progress_waits_for_a_drain_or_the_end_of_the_transfer() {
+        let events = Arc::new(Events::default());
+        let receiver = events.subscribe();
+        let upload = events.start(Direction::Upload, "a.txt");
+        let reporter = upload.progress();
+        let sender = upload.progress().sender(move |p| reporter.report(p.fraction));
+
+        sender.send(ClientEvent::Started { total: Some(10) }).unwrap();
+        sender.send(ClientEvent::Progress(Progress { fraction: 0.5, ..Default::default() })).unwrap();
+        assert_eq!(receiver.try_iter().count(), 1, "only Started before a drain");
+        assert!(events.drain_progress(), "the client still has its sender");
+        assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [Event::Progress { id: 1, fraction: 0.5 }]);
+
+        sender.send(ClientEvent::Retrying { attempt: 1, max_attempts: 3, reason: "timed out".to_string() }).unwrap();
+        sender.send(ClientEvent::Progress(Progress { fraction: 1.0, ..Default::default() })).unwrap();
+        upload.end(TransferOutcome::UploadFinished { name: "a.txt".to_string(), url: String::new() });
+        let received: Vec<Event> = receiver.try_iter().collect();
+        assert_eq!(received[..2], [
+            Event::Retrying { id: 1, attempt: 1, max_attempts: 3, reason: "timed out".to_string() },
+            Event::Progress { id: 1, fraction: 1.0 },
+        ]);
+        assert!(matches!(received[2], Event::Finished { .. }));
+        assert!(!events.drain_progress(), "the feeds of an ended transfer are gone");
+    }
+}
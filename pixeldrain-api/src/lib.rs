@@ -0,0 +1,2393 @@
+// pixeldrain-api - Robust PixelDrain API implementation
+// Based on actual API responses and patterns from go-pd and pixeldrain_api_client
+// The client has no GUI dependencies, so other programs can use it on its own.
+pub mod diagnostics;
+pub mod events;
+
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
+
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use reqwest::{blocking::multipart, blocking::Client, header, StatusCode};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{debug, trace, warn};
+use url::Url;
+
+use crate::diagnostics::TransferDiagnostics;
+pub use crate::events::{Direction, Event, Events, Transfer, TransferId, TransferOutcome, TransferProgress};
+
+/// Default server, `PixelDrainConfig::base_url` points elsewhere for self-hosted instances
+pub const BASE_URL: &str = "https://pixeldrain.com";
+pub const DEFAULT_USER_AGENT: &str = "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/79.0.3945.117 Safari/537.36";
+
+// ============================================================================
+// Error Response Structure
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+struct ApiErrorResponse {
+    pub _success: Option<bool>,
+    pub value: Option<String>,
+    pub message: Option<String>,
+    pub _errors: Option<Vec<ApiErrorResponse>>,
+}
+
+// ============================================================================
+// Configuration and Client
+// ============================================================================
+
+#[derive(Clone)]
+pub struct PixelDrainConfig {
+    pub api_key: Option<String>,
+    /// Username and password, only for logging in to get an API key
+    pub credentials: Option<(String, String)>,
+    pub timeout: Option<Duration>,
+    /// Wait between attempts of a failed upload or download
+    pub retry_delay: Duration,
+    pub user_agent: Option<String>,
+    pub real_ip: Option<String>,
+    pub real_agent: Option<String>,
+    /// Server root without a trailing slash, the API lives under `/api`
+    pub base_url: String,
+    /// Records requests, responses and chunk timings when set, see diagnostics.rs
+    pub diagnostics: Option<Arc<TransferDiagnostics>>,
+    /// Transfers stop with `PixelDrainError::Cancelled` at the next read once set
+    pub cancel: Option<Arc<AtomicBool>>,
+}
+
+impl Default for PixelDrainConfig {
+    fn default() -> Self {
+        Self {
+            api_key: None,
+            credentials: None,
+            timeout: Some(Duration::from_secs(3600)), // 1 hour like go-pd
+            retry_delay: Duration::from_secs(3),
+            user_agent: None,
+            real_ip: None,
+            real_agent: None,
+            base_url: BASE_URL.to_string(),
+            diagnostics: None,
+            cancel: None,
+        }
+    }
+}
+
+// The API key and password are left out, so a logged config can't leak them
+impl std::fmt::Debug for PixelDrainConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let redacted = |secret: bool| if secret { "<redacted>" } else { "None" };
+        f.debug_struct("PixelDrainConfig")
+            .field("api_key", &redacted(self.api_key.is_some()))
+            .field("credentials", &redacted(self.credentials.is_some()))
+            .field("timeout", &self.timeout)
+            .field("retry_delay", &self.retry_delay)
+            .field("user_agent", &self.user_agent)
+            .field("real_ip", &self.real_ip)
+            .field("real_agent", &self.real_agent)
+            .field("base_url", &self.base_url)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PixelDrainConfig {
+    pub fn with_api_key(mut self, api_key: String) -> Self {
+        self.api_key = Some(api_key);
+        self
+    }
+
+    pub fn with_credentials(mut self, username: &str, password: &str) -> Self {
+        self.credentials = Some((username.to_string(), password.to_string()));
+        self
+    }
+
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim().trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn with_retry_delay(mut self, retry_delay: Duration) -> Self {
+        self.retry_delay = retry_delay;
+        self
+    }
+
+    pub fn with_diagnostics(mut self, diagnostics: Option<Arc<TransferDiagnostics>>) -> Self {
+        self.diagnostics = diagnostics;
+        self
+    }
+
+    pub fn with_cancel(mut self, cancel: Arc<AtomicBool>) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    pub fn api_url(&self) -> String {
+        format!("{}/api", self.base_url)
+    }
+}
+
+/// Check a server URL entered by the user and bring it into the form `base_url`
+/// expects. A pasted API URL (ending in /api) is accepted as well.
+pub fn normalize_base_url(input: &str) -> Result<String, String> {
+    let trimmed = input.trim().trim_end_matches('/');
+    let trimmed = trimmed.strip_suffix("/api").unwrap_or(trimmed);
+    let url = Url::parse(trimmed).map_err(|e| format!("Invalid server URL: {}", e))?;
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+        return Err("Server URL must start with http:// or https://".to_string());
+    }
+    if url.query().is_some() || url.fragment().is_some() {
+        return Err("Server URL can't contain a query or fragment".to_string());
+    }
+    Ok(trimmed.to_string())
+}
+
+/// Where a download is written. A file is created again for every attempt,
+/// a writer can only be retried until the first byte was written to it. A
+/// resumed file keeps what it has and asks the server for the rest.
+enum DownloadTarget<'a> {
+    File(&'a Path),
+    Resume(&'a Path),
+    Writer(&'a mut dyn Write),
+}
+
+pub struct PixelDrainClient {
+    config: PixelDrainConfig,
+    client: Client,
+}
+
+impl PixelDrainClient {
+    pub fn new(config: PixelDrainConfig) -> Result<Self, PixelDrainError> {
+        let mut client_builder = Client::builder()
+            .user_agent(config.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT))
+            // Optimize for upload performance
+            .pool_max_idle_per_host(10)
+            .pool_idle_timeout(Some(Duration::from_secs(30)))
+            .tcp_keepalive(Some(Duration::from_secs(60)));
+        
+        if let Some(timeout) = config.timeout {
+            client_builder = client_builder.timeout(timeout);
+        }
+
+        let client = client_builder.build()?;
+        
+        Ok(Self { config, client })
+    }
+
+    // ============================================================================
+    // Core Request Methods
+    // ============================================================================
+
+    fn build_request(&self, method: reqwest::Method, endpoint: &str) -> reqwest::blocking::RequestBuilder {
+        let url = format!("{}/{}", self.config.api_url(), endpoint.trim_start_matches('/'));
+        if let Some(diagnostics) = self.diagnostics() {
+            diagnostics.request(method.as_str(), &url);
+        }
+        self.apply_headers(self.client.request(method, &url))
+    }
+
+    fn diagnostics(&self) -> Option<&TransferDiagnostics> {
+        self.config.diagnostics.as_deref()
+    }
+
+    fn check_cancelled(&self) -> Result<(), PixelDrainError> {
+        match &self.config.cancel {
+            Some(cancel) if cancel.load(Ordering::Relaxed) => Err(PixelDrainError::Cancelled),
+            _ => Ok(()),
+        }
+    }
+
+    fn record_response(&self, resp: &reqwest::blocking::Response) {
+        if let Some(diagnostics) = self.diagnostics() {
+            diagnostics.response(resp);
+        }
+    }
+
+    fn record_retry(&self, attempt: usize, max_attempts: usize, reason: &dyn std::fmt::Display) {
+        if let Some(diagnostics) = self.diagnostics() {
+            diagnostics.retry(attempt, max_attempts, reason);
+        }
+    }
+
+    /// Record a failed attempt that will be tried again, and tell the listener
+    fn retrying(&self, progress: &Option<ProgressSender>, attempt: usize, max_attempts: usize, reason: &dyn std::fmt::Display) {
+        self.record_retry(attempt, max_attempts, reason);
+        send_event(progress, TransferEvent::Retrying { attempt, max_attempts, reason: reason.to_string() });
+    }
+
+    /// Sleep for `wait` in short steps, stopping early with `Cancelled` once the
+    /// transfer is cancelled
+    fn sleep_unless_cancelled(&self, wait: Duration) -> Result<(), PixelDrainError> {
+        let deadline = Instant::now() + wait;
+        loop {
+            self.check_cancelled()?;
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                return Ok(());
+            }
+            std::thread::sleep(left.min(CANCEL_CHECK_INTERVAL));
+        }
+    }
+
+    /// Run `request` again after each 429 response, waiting as long as the
+    /// server asks. Rate limits don't use up the retries for other errors.
+    fn waiting_out_rate_limits<T>(
+        &self,
+        progress: &Option<ProgressSender>,
+        mut request: impl FnMut() -> Result<T, PixelDrainError>,
+    ) -> Result<T, PixelDrainError> {
+        let mut waits = 0;
+        loop {
+            match request() {
+                Err(PixelDrainError::RateLimited { retry_after, error }) if waits < MAX_RATE_LIMIT_WAITS => {
+                    waits += 1;
+                    let wait = retry_after.min(MAX_RATE_LIMIT_WAIT);
+                    self.retrying(progress, waits, MAX_RATE_LIMIT_WAITS, &format!("rate limited, waiting {} s: {}", wait.as_secs(), error));
+                    warn!("Rate limited, retrying in {} seconds...", wait.as_secs());
+                    self.sleep_unless_cancelled(wait)?;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    // Single place where auth, real IP and user agent headers are attached.
+    // Every request path must go through this so new endpoints can't forget auth.
+    fn apply_headers(&self, mut req: reqwest::blocking::RequestBuilder) -> reqwest::blocking::RequestBuilder {
+        if let Some(auth_header) = self.auth_header() {
+            req = req.header(header::AUTHORIZATION, auth_header);
+        }
+        if let Some(real_ip) = &self.config.real_ip {
+            req = req.header("X-Real-IP", real_ip);
+        }
+        if let Some(real_agent) = &self.config.real_agent {
+            req = req.header(header::USER_AGENT, real_agent);
+        }
+        req
+    }
+
+    // API keys go in the password field of HTTP Basic auth, the username is ignored.
+    // A login sends the username and password there instead.
+    fn auth_header(&self) -> Option<String> {
+        let (username, password) = match (&self.config.credentials, &self.config.api_key) {
+            (Some((username, password)), _) => (username.as_str(), password.as_str()),
+            (None, Some(api_key)) => ("", api_key.as_str()),
+            (None, None) => return None,
+        };
+        Some(format!("Basic {}", base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", username, password))))
+    }
+
+    // Enhanced error handling based on pixeldrain_api_client patterns
+    fn parse_json_response<T>(resp: reqwest::blocking::Response) -> Result<T, PixelDrainError>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let status = resp.status();
+        let (endpoint, request_id) = ApiError::response_context(&resp);
+        let retry_after = ApiError::retry_after(&resp);
+        
+        // Get the response body as text first for debugging
+        let response_text = resp.text().unwrap_or_default();
+        trace!("Response {}: {}", status, response_text);
+        
+        // Test for client side and server side errors
+        if status.as_u16() >= 400 {
+            // Try to parse as structured error first
+            if let Ok(api_error) = serde_json::from_str::<ApiErrorResponse>(&response_text) {
+                return Err(PixelDrainError::for_api_error(ApiError {
+                    status,
+                    value: api_error.value.unwrap_or_else(|| "error".to_string()),
+                    message: api_error.message.unwrap_or_else(|| "Unknown error".to_string()),
+                    endpoint,
+                    request_id,
+                }, retry_after));
+            }
+            
+            // Fall back to plain text error
+            return Err(PixelDrainError::for_api_error(ApiError {
+                status,
+                value: "error".to_string(),
+                message: response_text,
+                endpoint,
+                request_id,
+            }, retry_after));
+        }
+
+        // Parse successful response
+        let result: T = serde_json::from_str(&response_text)?;
+        Ok(result)
+    }
+
+    fn do_request<T>(&self, method: reqwest::Method, endpoint: &str, body: Option<reqwest::blocking::Body>) -> Result<T, PixelDrainError>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let method_str = method.as_str();
+        // Most API requests require authentication, so default to false for anonymous
+        let mut req = self.build_request(method.clone(), endpoint);
+        
+        if let Some(body) = body {
+            req = req.body(body);
+        }
+
+        let resp = req.send()?;
+        self.record_response(&resp);
+        
+        debug!("Request: {} {}", method_str, endpoint);
+        debug!("Response Status: {}", resp.status());
+
+        Self::parse_json_response(resp)
+    }
+
+    // Form-based request method for certain endpoints (like pixeldrain_api_client)
+    fn do_form_request<T>(&self, method: reqwest::Method, endpoint: &str, form_data: &[(&str, &str)]) -> Result<T, PixelDrainError>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let method_str = method.as_str();
+        // Form requests typically require authentication, so default to false for anonymous
+        let mut req = self.build_request(method.clone(), endpoint);
+        
+        // Build form data using reqwest's built-in form support
+        let mut form_params = Vec::new();
+        for (key, value) in form_data.iter() {
+            form_params.push((*key, *value));
+        }
+        
+        req = req.form(&form_params);
+
+        let resp = req.send()?;
+        self.record_response(&resp);
+        
+        debug!("Form Request: {} {}", method_str, endpoint);
+        debug!("Response Status: {}", resp.status());
+
+        Self::parse_json_response(resp)
+    }
+
+    fn do_multipart<T>(&self, endpoint: &str, form: multipart::Form) -> Result<T, PixelDrainError>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let req = self.build_request(reqwest::Method::POST, endpoint);
+        let resp = req.multipart(form).send()?;
+        self.record_response(&resp);
+        let status = resp.status();
+        debug!("Multipart Request: POST {}", endpoint);
+        debug!("Response Status: {}", status);
+        debug!("API Key present: {}", self.config.api_key.is_some());
+        if !status.is_success() {
+            return Err(PixelDrainError::from_response(resp));
+        }
+        let result: T = resp.json()?;
+        Ok(result)
+    }
+
+    // ============================================================================
+    // File Operations
+    // ============================================================================
+
+    /// Upload a file using POST /api/file. Without an API key the file is
+    /// uploaded anonymously and isn't part of any account.
+    ///
+    /// Always a single request: the file API takes the whole body at once and has
+    /// no ranged or chunked upload, so a file can't be spread over several
+    /// connections.
+    pub fn upload_file<P: AsRef<Path>>(
+        &self,
+        file_path: P,
+        progress: Option<ProgressSender>,
+    ) -> Result<UploadResponse, PixelDrainError> {
+        let file_path = file_path.as_ref();
+        let total = file_path.metadata().ok().map(|metadata| metadata.len());
+        reporting(&progress, total, || {
+            self.waiting_out_rate_limits(&progress, || self.upload_file_attempts(file_path, progress.clone()))
+        })
+    }
+
+    fn upload_file_attempts(&self, file_path: &Path, progress: Option<ProgressSender>) -> Result<UploadResponse, PixelDrainError> {
+        if !file_path.exists() {
+            return Err(PixelDrainError::FileNotFound(file_path.display().to_string()));
+        }
+
+        let file_name = file_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "file".to_string());
+
+        let file_size = file_path.metadata()?.len();
+
+        // Retry logic with progress reset
+        const MAX_RETRIES: usize = 3;
+        
+        for attempt in 1..=MAX_RETRIES {
+            self.check_cancelled()?;
+            debug!("Upload attempt {}/{}", attempt, MAX_RETRIES);
+            
+            // Reset progress at the start of each attempt
+            send_event(&progress, TransferEvent::Progress(Progress::at(0.0)));
+
+            // Create a progress reader that works for file uploads
+            let progress_reader = ProgressReader::new_file(
+                File::open(file_path)?,
+                file_size,
+                progress.clone(),
+            ).with_diagnostics(self.config.diagnostics.clone())
+            .with_cancel(self.config.cancel.clone());
+
+            let part = multipart::Part::reader(progress_reader)
+                .file_name(file_name.clone())
+                .mime_str("application/octet-stream")
+                .unwrap();
+
+            let form = multipart::Form::new().part("file", part);
+
+            match self.do_multipart("file", form) {
+                Ok(result) => {
+                    // Reset progress to 100% when complete
+                    send_event(&progress, TransferEvent::Progress(Progress::at(1.0)));
+                    return Ok(result);
+                }
+                Err(e) => {
+                    let should_retry = e.is_retryable();
+                    
+                    if should_retry && attempt < MAX_RETRIES {
+                        self.retrying(&progress, attempt, MAX_RETRIES, &e);
+                        warn!("Upload failed, retrying in {} seconds...", self.config.retry_delay.as_secs());
+                        std::thread::sleep(self.config.retry_delay);
+                        continue;
+                    } else {
+                        return Err(e);
+                    }
+                }
+            }
+        }
+        
+        // This should never be reached, but just in case
+        Err(PixelDrainError::Api(ApiError {
+            status: reqwest::StatusCode::INTERNAL_SERVER_ERROR,
+            value: "error".to_string(),
+            message: "Upload failed after all retry attempts".to_string(),
+            endpoint: None,
+            request_id: None,
+        }))
+    }
+
+    /// Download a file using GET /api/file/{id}
+    pub fn download_file(
+        &self,
+        file_id: &str,
+        save_path: &Path,
+        progress: Option<ProgressSender>,
+    ) -> Result<(), PixelDrainError> {
+        self.download_to_path(&format!("file/{}", file_id), save_path, progress)
+    }
+
+    /// Continue a download that stopped halfway, with a Range request for the
+    /// bytes `save_path` doesn't have yet. Servers that don't do ranges send
+    /// the whole file, which then replaces the partial one.
+    pub fn resume_download_file(
+        &self,
+        file_id: &str,
+        save_path: &Path,
+        progress: Option<ProgressSender>,
+    ) -> Result<(), PixelDrainError> {
+        self.download_to(&format!("file/{}", file_id), DownloadTarget::Resume(save_path), progress)
+    }
+
+    /// Stream a file into `out`, e.g. standard output. Output that was already
+    /// written can't be taken back, so a connection lost halfway fails the
+    /// download instead of retrying.
+    pub fn download_file_to_writer(
+        &self,
+        file_id: &str,
+        out: &mut dyn Write,
+        progress: Option<ProgressSender>,
+    ) -> Result<(), PixelDrainError> {
+        self.download_to(&format!("file/{}", file_id), DownloadTarget::Writer(out), progress)
+    }
+
+    /// Download all files of a list as a single zip archive using GET /api/list/{id}/zip
+    pub fn download_list_zip(
+        &self,
+        list_id: &str,
+        save_path: &Path,
+        progress: Option<ProgressSender>,
+    ) -> Result<(), PixelDrainError> {
+        self.download_to_path(&format!("list/{}/zip", list_id), save_path, progress)
+    }
+
+    /// Stream a GET endpoint to a local file
+    fn download_to_path(
+        &self,
+        endpoint: &str,
+        save_path: &Path,
+        progress: Option<ProgressSender>,
+    ) -> Result<(), PixelDrainError> {
+        self.download_to(endpoint, DownloadTarget::File(save_path), progress)
+    }
+
+    fn download_to(
+        &self,
+        endpoint: &str,
+        mut target: DownloadTarget,
+        progress: Option<ProgressSender>,
+    ) -> Result<(), PixelDrainError> {
+        reporting(&progress, None, || {
+            self.waiting_out_rate_limits(&progress, || self.download_attempts(endpoint, &mut target, progress.clone()))
+        })
+    }
+
+    fn download_attempts(
+        &self,
+        endpoint: &str,
+        target: &mut DownloadTarget,
+        progress: Option<ProgressSender>,
+    ) -> Result<(), PixelDrainError> {
+        let rewinds = !matches!(target, DownloadTarget::Writer(_));
+        // Retry logic similar to go-pd
+        const MAX_RETRIES: usize = 5;
+        
+        let mut last_error = None;
+        
+        for attempt in 1..=MAX_RETRIES {
+            self.check_cancelled()?;
+            debug!("Download attempt {}/{}", attempt, MAX_RETRIES);
+            
+            // Reset progress at the start of each attempt
+            send_event(&progress, TransferEvent::Progress(Progress::at(0.0)));
+            
+            // Authorization is only attached if an API key is set
+            let mut req = self.build_request(reqwest::Method::GET, endpoint);
+            // A resumed file continues where the last attempt or run stopped
+            let offset = match target {
+                DownloadTarget::Resume(save_path) => std::fs::metadata(save_path).map(|metadata| metadata.len()).unwrap_or(0),
+                _ => 0,
+            };
+            if offset > 0 {
+                req = req.header(header::RANGE, format!("bytes={}-", offset));
+            }
+            
+            let mut resp = match req.send() {
+                Ok(resp) => resp,
+                Err(e) => {
+                    self.record_retry(attempt, MAX_RETRIES, &e);
+                    last_error = Some(PixelDrainError::Reqwest(e));
+                    if attempt < MAX_RETRIES {
+                        send_event(&progress, TransferEvent::Retrying {
+                            attempt,
+                            max_attempts: MAX_RETRIES,
+                            reason: last_error.as_ref().map(ToString::to_string).unwrap_or_default(),
+                        });
+                        warn!("Download failed, retrying in {} seconds...", self.config.retry_delay.as_secs());
+                        std::thread::sleep(self.config.retry_delay);
+                        continue;
+                    } else {
+                        break;
+                    }
+                }
+            };
+            
+            self.record_response(&resp);
+            let status = resp.status();
+            // Nothing left past the end of the file, it was complete already
+            if status == StatusCode::RANGE_NOT_SATISFIABLE && offset > 0 {
+                send_event(&progress, TransferEvent::Progress(Progress::at(1.0)));
+                return Ok(());
+            }
+            if !status.is_success() {
+                let api_error = PixelDrainError::from_response(resp);
+                
+                // Retry on server errors
+                if status.is_server_error() && attempt < MAX_RETRIES {
+                    self.retrying(&progress, attempt, MAX_RETRIES, &api_error);
+                    last_error = Some(api_error);
+                    warn!("Download failed with server error, retrying in {} seconds...", self.config.retry_delay.as_secs());
+                    std::thread::sleep(self.config.retry_delay);
+                    continue;
+                } else {
+                    return Err(api_error);
+                }
+            }
+
+            // Only a partial response continues the file, anything else starts it over
+            let offset = if status == StatusCode::PARTIAL_CONTENT { offset } else { 0 };
+            let content_length = resp.content_length().map(|length| length + offset).unwrap_or(0);
+            if let Some(diagnostics) = self.diagnostics() {
+                diagnostics.begin_transfer(resp.content_length());
+            }
+            let mut file;
+            let out: &mut dyn Write = match target {
+                DownloadTarget::File(save_path) => {
+                    file = File::create(save_path)?;
+                    &mut file
+                }
+                DownloadTarget::Resume(save_path) if offset > 0 => {
+                    file = File::options().append(true).open(save_path)?;
+                    &mut file
+                }
+                DownloadTarget::Resume(save_path) => {
+                    file = File::create(save_path)?;
+                    &mut file
+                }
+                DownloadTarget::Writer(out) => &mut **out,
+            };
+            let mut downloaded = offset;
+            let mut meter = SpeedMeter::default();
+            let mut interrupted = false;
+            let mut buffer = [0; 8192];
+
+            loop {
+                let n = match resp.read(&mut buffer) {
+                    Ok(n) => n,
+                    Err(e) => {
+                        // Retry on read errors, a file is written again from the start or resumed
+                        self.record_retry(attempt, MAX_RETRIES, &e);
+                        if attempt < MAX_RETRIES && (downloaded == 0 || rewinds) {
+                            send_event(&progress, TransferEvent::Retrying { attempt, max_attempts: MAX_RETRIES, reason: e.to_string() });
+                            warn!("Download read failed, retrying in {} seconds...", self.config.retry_delay.as_secs());
+                            std::thread::sleep(self.config.retry_delay);
+                            last_error = Some(PixelDrainError::Io(e));
+                            interrupted = true;
+                            break;
+                        } else {
+                            return Err(PixelDrainError::Io(e));
+                        }
+                    }
+                };
+                
+                if n == 0 {
+                    break;
+                }
+                // A download cancelled while it hung doesn't write anything more
+                self.check_cancelled()?;
+                
+                out.write_all(&buffer[..n])?;
+                downloaded += n as u64;
+                if let Some(diagnostics) = self.diagnostics() {
+                    diagnostics.transferred(n);
+                }
+                
+                if let Some(progress) = &progress {
+                    let _ = progress.send(TransferEvent::Progress(meter.progress(downloaded, (content_length > 0).then_some(content_length))));
+                }
+            }
+            
+            if interrupted {
+                continue;
+            }
+            out.flush()?;
+            
+            // If we get here, download was successful
+            if let Some(diagnostics) = self.diagnostics() {
+                diagnostics.end_transfer();
+            }
+            // Reset progress to 100% when complete
+            send_event(&progress, TransferEvent::Progress(Progress::at(1.0)));
+            
+            return Ok(());
+        }
+        
+        // If we get here, all retries failed
+        Err(last_error.unwrap_or_else(|| PixelDrainError::Api(ApiError {
+            status: reqwest::StatusCode::INTERNAL_SERVER_ERROR,
+            value: "error".to_string(),
+            message: "Download failed after all retry attempts".to_string(),
+            endpoint: None,
+            request_id: None,
+        })))
+    }
+
+    /// Download a file thumbnail using GET /api/file/{id}/thumbnail?width=x&height=x
+    #[allow(dead_code)]
+    pub fn download_thumbnail(
+        &self,
+        file_id: &str,
+        width: u32,
+        height: u32,
+        save_path: &Path,
+    ) -> Result<(), PixelDrainError> {
+        let endpoint = format!("file/{}/thumbnail?width={}&height={}", file_id, width, height);
+        let mut resp = self.build_request(reqwest::Method::GET, &endpoint).send()?;
+        let status = resp.status();
+        if !status.is_success() {
+            return Err(PixelDrainError::from_response(resp));
+        }
+        let mut file = File::create(save_path)?;
+        io::copy(&mut resp, &mut file)?;
+        Ok(())
+    }
+
+    /// Fetch a file thumbnail as bytes (in memory) using GET /api/file/{id}/thumbnail?width=x&height=x
+    pub fn fetch_thumbnail_bytes(
+        &self,
+        file_id: &str,
+        width: u32,
+        height: u32,
+    ) -> Result<Vec<u8>, PixelDrainError> {
+        let endpoint = format!("file/{}/thumbnail?width={}&height={}", file_id, width, height);
+        let mut resp = self.build_request(reqwest::Method::GET, &endpoint).send()?;
+        let status = resp.status();
+        if !status.is_success() {
+            return Err(PixelDrainError::from_response(resp));
+        }
+        let mut buf = Vec::new();
+        resp.copy_to(&mut buf)?;
+        Ok(buf)
+    }
+
+    /// Get file information using GET /api/file/{id}
+    pub fn get_file_info(&self, file_id: &str) -> Result<FileInfo, PixelDrainError> {
+        self.do_request(reqwest::Method::GET, &format!("file/{}/info", file_id), None)
+    }
+
+    /// Compare the server-side metadata of an uploaded file against the local size and SHA-256
+    /// Returns Ok(false) when the upload was truncated or corrupted in transit
+    pub fn verify_upload(&self, file_id: &str, local_size: u64, local_sha256: &str) -> Result<bool, PixelDrainError> {
+        let info = self.get_file_info(file_id)?;
+        debug!("Verify upload {}: remote size {} / local size {}", file_id, info.size, local_size);
+        debug!("Verify upload {}: remote sha256 {} / local sha256 {}", file_id, info.hash_sha256, local_sha256);
+        Ok(info.size == local_size && info.hash_sha256.eq_ignore_ascii_case(local_sha256))
+    }
+
+    /// Get user files using GET /api/user/files
+    pub fn get_user_files(&self) -> Result<UserFilesResponse, PixelDrainError> {
+        self.do_request(reqwest::Method::GET, "user/files", None)
+    }
+
+    /// Delete a file using DELETE /api/file/{id}
+    pub fn delete_file(&self, file_id: &str) -> Result<(), PixelDrainError> {
+        if self.config.api_key.is_none() {
+            return Err(PixelDrainError::MissingApiKey);
+        }
+
+        let _: serde_json::Value = self.do_request(reqwest::Method::DELETE, &format!("file/{}", file_id), None)?;
+        Ok(())
+    }
+
+    /// Upload a file using PUT /api/file/{name} (with custom filename)
+    #[allow(dead_code)]
+    pub fn upload_file_put<P: AsRef<Path>>(
+        &self,
+        file_path: P,
+        custom_filename: &str,
+        progress: Option<ProgressSender>,
+    ) -> Result<UploadResponse, PixelDrainError> {
+        let file_path = file_path.as_ref();
+        let total = file_path.metadata().ok().map(|metadata| metadata.len());
+        reporting(&progress, total, || {
+            self.waiting_out_rate_limits(&progress, || self.upload_file_put_attempts(file_path, custom_filename, progress.clone()))
+        })
+    }
+
+    fn upload_file_put_attempts(
+        &self,
+        file_path: &Path,
+        custom_filename: &str,
+        progress: Option<ProgressSender>,
+    ) -> Result<UploadResponse, PixelDrainError> {
+        if !file_path.exists() {
+            return Err(PixelDrainError::FileNotFound(file_path.display().to_string()));
+        }
+
+        let file_size = file_path.metadata()?.len();
+
+        // Retry logic with progress reset
+        const MAX_RETRIES: usize = 3;
+        
+        for attempt in 1..=MAX_RETRIES {
+            self.check_cancelled()?;
+            debug!("PUT Upload attempt {}/{}", attempt, MAX_RETRIES);
+            
+            // Reset progress at the start of each attempt
+            send_event(&progress, TransferEvent::Progress(Progress::at(0.0)));
+
+            // Create a progress reader that works for file uploads
+            let progress_reader = ProgressReader::new_file(
+                File::open(file_path)?,
+                file_size,
+                progress.clone(),
+            ).with_diagnostics(self.config.diagnostics.clone())
+            .with_cancel(self.config.cancel.clone());
+
+            let body = reqwest::blocking::Body::sized(progress_reader, file_size);
+            
+            match self.do_request::<UploadResponse>(
+                reqwest::Method::PUT, 
+                &format!("file/{}", custom_filename), 
+                Some(body)
+            ) {
+                Ok(result) => {
+                    // Reset progress to 100% when complete
+                    send_event(&progress, TransferEvent::Progress(Progress::at(1.0)));
+                    return Ok(result);
+                }
+                Err(e) => {
+                    let should_retry = e.is_retryable();
+                    
+                    if should_retry && attempt < MAX_RETRIES {
+                        self.retrying(&progress, attempt, MAX_RETRIES, &e);
+                        warn!("PUT Upload failed, retrying in {} seconds...", self.config.retry_delay.as_secs());
+                        std::thread::sleep(self.config.retry_delay);
+                        continue;
+                    } else {
+                        return Err(e);
+                    }
+                }
+            }
+        }
+        
+        // This should never be reached, but just in case
+        Err(PixelDrainError::Api(ApiError {
+            status: reqwest::StatusCode::INTERNAL_SERVER_ERROR,
+            value: "error".to_string(),
+            message: "PUT Upload failed after all retry attempts".to_string(),
+            endpoint: None,
+            request_id: None,
+        }))
+    }
+
+    /// Upload a stream using PUT /api/file/{filename} (like Go CLI)
+    pub fn upload_stream_put<R: Read + Send + 'static>(
+        &self,
+        reader: R,
+        filename: &str,
+        progress: Option<ProgressSender>,
+    ) -> Result<UploadResponse, PixelDrainError> {
+        reporting(&progress.clone(), None, || {
+            // Create a progress reader that works for streaming uploads
+            let progress_reader = ProgressReader::new_stream(reader, progress).with_diagnostics(self.config.diagnostics.clone())
+                .with_cancel(self.config.cancel.clone());
+            self.put_stream(progress_reader, filename, None)
+        })
+    }
+
+    /// Upload file `file_id` again as `filename`, sending `mime_type` as its
+    /// Content-Type. The file API has no endpoint for changing the type of an
+    /// existing file, so this makes a new file with a new ID. The data is
+    /// streamed from the download into the upload without a local copy.
+    pub fn reupload_with_type(
+        &self,
+        file_id: &str,
+        filename: &str,
+        mime_type: &str,
+        progress: Option<ProgressSender>,
+    ) -> Result<UploadResponse, PixelDrainError> {
+        let size = self.get_file_info(file_id)?.size;
+        reporting(&progress.clone(), Some(size), || {
+            let source = self.build_request(reqwest::Method::GET, &format!("file/{}", file_id)).send()?;
+            self.record_response(&source);
+            if !source.status().is_success() {
+                return Err(PixelDrainError::from_response(source));
+            }
+            let progress_reader = ProgressReader::new_file(source, size, progress).with_diagnostics(self.config.diagnostics.clone())
+                .with_cancel(self.config.cancel.clone());
+            self.put_stream(progress_reader, filename, Some(mime_type))
+        })
+    }
+
+    fn put_stream<R: Read + Send + 'static>(
+        &self,
+        reader: ProgressReader<R>,
+        filename: &str,
+        content_type: Option<&str>,
+    ) -> Result<UploadResponse, PixelDrainError> {
+        // Build the PUT request with streaming body
+        let mut request = self.build_request(reqwest::Method::PUT, &format!("file/{}", urlencoding::encode(filename)));
+        if let Some(content_type) = content_type {
+            request = request.header(header::CONTENT_TYPE, content_type);
+        }
+        request = request.body(reqwest::blocking::Body::new(reader));
+        
+        // Send the request
+        let resp = request.send()?;
+        self.record_response(&resp);
+        let status = resp.status();
+        
+        if !status.is_success() {
+            return Err(PixelDrainError::from_response(resp));
+        }
+        
+        let response: UploadResponse = resp.json()?;
+        Ok(response)
+    }
+
+    /// Get rate limits from the server
+    pub fn get_rate_limits(&self) -> Result<RateLimits, PixelDrainError> {
+        self.do_request(reqwest::Method::GET, "misc/rate_limits", None)
+    }
+
+    /// Get cluster speed information
+    #[allow(dead_code)]
+    pub fn get_cluster_speed(&self) -> Result<ClusterSpeed, PixelDrainError> {
+        self.do_request(reqwest::Method::GET, "misc/cluster_speed", None)
+    }
+
+    /// Check if server is overloaded before uploading
+    #[allow(dead_code)]
+    pub fn check_server_status(&self) -> Result<bool, PixelDrainError> {
+        let rate_limits = self.get_rate_limits()?;
+        Ok(!rate_limits.server_overload)
+    }
+
+    /// Extract file ID from PixelDrain URL
+    pub fn extract_file_id(url: &str) -> Result<String, PixelDrainError> {
+        let url = Url::parse(url)?;
+        let path = url.path();
+        
+        // Handle different URL formats:
+        // - https://pixeldrain.com/u/{id}
+        // - https://pixeldrain.com/u/{id}?download
+        // - https://pixeldrain.com/api/file/{id}
+        
+        if path.starts_with("/u/") {
+            let id = path.strip_prefix("/u/").unwrap_or("");
+            if !id.is_empty() {
+                return Ok(id.to_string());
+            }
+        } else if path.starts_with("/api/file/") {
+            let id = path.strip_prefix("/api/file/").unwrap_or("");
+            if !id.is_empty() {
+                return Ok(id.to_string());
+            }
+        }
+        
+        Err(PixelDrainError::InvalidUrl("Could not extract file ID from URL".to_string()))
+    }
+
+    /// Extract list ID from PixelDrain list URL
+    pub fn extract_list_id(url: &str) -> Result<String, PixelDrainError> {
+        let url = Url::parse(url)?;
+        let path = url.path();
+        
+        // Handle different URL formats:
+        // - https://pixeldrain.com/l/{id}
+        // - https://pixeldrain.com/api/list/{id}
+        
+        for prefix in ["/l/", "/api/list/"] {
+            if let Some(id) = path.strip_prefix(prefix) {
+                let id = id.trim_end_matches('/');
+                if !id.is_empty() {
+                    return Ok(id.to_string());
+                }
+            }
+        }
+        
+        Err(PixelDrainError::InvalidUrl("Could not extract list ID from URL".to_string()))
+    }
+
+    /// Get all lists for the user
+    pub fn get_user_lists(&self) -> Result<UserListsResponse, PixelDrainError> {
+        // User lists require authentication, so pass false for anonymous
+        let request = self.build_request(reqwest::Method::GET, "user/lists");
+        let resp = request.send()?;
+        let status = resp.status();
+        
+        debug!("Get User Lists Request: GET /user/lists");
+        debug!("Response Status: {}", status);
+
+        if !status.is_success() {
+            let error = PixelDrainError::from_response(resp);
+            warn!("Error response: {}", error);
+            return Err(error);
+        }
+
+        let response_text = resp.text()?;
+        trace!("Response body: {}", response_text);
+
+        Self::parse_user_lists(status, &response_text)
+    }
+
+    /// Parse any of the known `user/lists` response shapes. On failure the
+    /// response body is kept in the error so unknown shapes can be diagnosed.
+    fn parse_user_lists(status: StatusCode, response_text: &str) -> Result<UserListsResponse, PixelDrainError> {
+        // Handle empty response
+        if response_text.trim().is_empty() {
+            return Ok(UserListsResponse { lists: Vec::new() });
+        }
+
+        serde_json::from_str::<UserListsShape>(response_text)
+            .map(UserListsResponse::from)
+            .map_err(|e| PixelDrainError::Api(ApiError {
+                status,
+                value: "unexpected_response".to_string(),
+                message: format!("Could not parse user lists ({}): {}", e, response_text),
+                endpoint: None,
+                request_id: None,
+            }))
+    }
+
+    /// Get details for a specific list
+    pub fn get_list(&self, list_id: &str) -> Result<DetailedListInfo, PixelDrainError> {
+        self.do_request(reqwest::Method::GET, &format!("list/{}", list_id), None)
+    }
+
+    /// Create a new list
+    pub fn create_list(&self, req: &CreateListRequest) -> Result<ListInfo, PixelDrainError> {
+        let body = serde_json::to_vec(req)?;
+        let req_body = reqwest::blocking::Body::from(body);
+        
+        // Creating lists requires authentication, so pass false for anonymous
+        let mut request = self.build_request(reqwest::Method::POST, "list");
+        request = request.header(header::CONTENT_TYPE, "application/json");
+        request = request.body(req_body);
+        
+        let resp = request.send()?;
+        let status = resp.status();
+        
+        debug!("Create List Request: POST /list");
+        debug!("Response Status: {}", status);
+
+        if !status.is_success() {
+            return Err(PixelDrainError::from_response(resp));
+        }
+
+        // Parse the creation response (just contains ID)
+        let creation_resp: ListCreationResponse = resp.json()?;
+        
+        // Now fetch the full list info and convert to simple ListInfo
+        let detailed = self.get_list(&creation_resp.id)?;
+        Ok(ListInfo {
+            id: detailed.id,
+            title: detailed.title,
+            date_created: detailed.date_created,
+            file_count: detailed.file_count as i64,
+            files: None, // Don't include files in simple view
+            can_edit: detailed.can_edit,
+        })
+    }
+
+    /// Update a list (change title/files)
+    pub fn update_list(&self, list_id: &str, req: &CreateListRequest) -> Result<ListInfo, PixelDrainError> {
+        let body = serde_json::to_vec(req)?;
+        let req_body = reqwest::blocking::Body::from(body);
+        
+        // Updating lists requires authentication, so pass false for anonymous
+        let mut request = self.build_request(reqwest::Method::PUT, &format!("list/{}", list_id));
+        request = request.header(header::CONTENT_TYPE, "application/json");
+        request = request.body(req_body);
+        
+        let resp = request.send()?;
+        let status = resp.status();
+        
+        debug!("Update List Request: PUT /list/{}", list_id);
+        debug!("Response Status: {}", status);
+
+        if !status.is_success() {
+            return Err(PixelDrainError::from_response(resp));
+        }
+
+        let detailed: DetailedListInfo = resp.json()?;
+        Ok(ListInfo {
+            id: detailed.id,
+            title: detailed.title,
+            date_created: detailed.date_created,
+            file_count: detailed.file_count as i64,
+            files: None,
+            can_edit: detailed.can_edit,
+        })
+    }
+
+    /// Delete a list
+    pub fn delete_list(&self, list_id: &str) -> Result<(), PixelDrainError> {
+        let _: serde_json::Value = self.do_request(reqwest::Method::DELETE, &format!("list/{}", list_id), None)?;
+        Ok(())
+    }
+
+    /// Add a view to a file (based on pixeldrain_api_client)
+    #[allow(dead_code)]
+    pub fn post_file_view(&self, file_id: &str, view_token: &str) -> Result<(), PixelDrainError> {
+        let form_data = [("token", view_token)];
+        self.do_form_request::<()>(reqwest::Method::POST, &format!("file/{}/view", file_id), &form_data)
+            .map(|_| ())
+    }
+
+    /// Get reCaptcha site key (based on pixeldrain_api_client)
+    #[allow(dead_code)]
+    pub fn get_misc_recaptcha(&self) -> Result<RecaptchaInfo, PixelDrainError> {
+        self.do_request(reqwest::Method::GET, "misc/recaptcha", None)
+    }
+
+    /// Get Sia cryptocurrency price (based on pixeldrain_api_client)
+    #[allow(dead_code)]
+    pub fn get_sia_price(&self) -> Result<SiaPrice, PixelDrainError> {
+        self.do_request(reqwest::Method::GET, "misc/sia_price", None)
+    }
+
+    /// Get user information (enhanced based on pixeldrain_api_client)
+    #[allow(dead_code)]
+    pub fn get_user(&self) -> Result<UserInfo, PixelDrainError> {
+        self.do_request(reqwest::Method::GET, "user", None)
+    }
+
+    /// Domains allowed to embed the account's files, separated by spaces
+    pub fn update_file_embed_domains(&self, domains: &str) -> Result<(), PixelDrainError> {
+        let _: serde_json::Value = self.do_form_request(reqwest::Method::PUT, "user/file_embed_domains", &[("domains", domains)])?;
+        Ok(())
+    }
+
+    /// Whether the account's files may be hotlinked, which serves direct links
+    /// and embeds from the account's transfer allowance
+    pub fn update_hotlinking(&self, enabled: bool) -> Result<(), PixelDrainError> {
+        let enabled = enabled.to_string();
+        let _: serde_json::Value = self.do_form_request(reqwest::Method::PUT, "user/hotlinking", &[("enabled", &enabled)])?;
+        Ok(())
+    }
+
+    /// File viewer branding of the account's files, see `BRANDING_FIELDS`.
+    /// Needs a subscription with `file_viewer_branding`.
+    pub fn update_file_viewer_branding(&self, branding: &HashMap<String, String>) -> Result<(), PixelDrainError> {
+        let form_data: Vec<(&str, &str)> = branding.iter().map(|(field, value)| (field.as_str(), value.as_str())).collect();
+        let _: serde_json::Value = self.do_form_request(reqwest::Method::PUT, "user/file_customization", &form_data)?;
+        Ok(())
+    }
+
+    /// Create a new user session (based on pixeldrain_api_client). With
+    /// credentials in the config this is a login, the session key is an API key.
+    pub fn post_user_session(&self, app_name: &str) -> Result<UserSession, PixelDrainError> {
+        let form_data = [("app_name", app_name)];
+        self.do_form_request(reqwest::Method::POST, "user/session", &form_data)
+    }
+
+    /// Get all user sessions (based on pixeldrain_api_client)
+    pub fn get_user_sessions(&self) -> Result<Vec<UserSession>, PixelDrainError> {
+        self.do_request(reqwest::Method::GET, "user/session", None)
+    }
+
+    /// Delete a user session (based on pixeldrain_api_client)
+    pub fn delete_user_session(&self, session_key: &str) -> Result<(), PixelDrainError> {
+        self.do_request::<()>(reqwest::Method::DELETE, &format!("user/session/{}", session_key), None)
+            .map(|_| ())
+    }
+
+    /// Get user activity log (based on pixeldrain_api_client)
+    #[allow(dead_code)]
+    pub fn get_user_activity(&self) -> Result<Vec<UserActivity>, PixelDrainError> {
+        self.do_request(reqwest::Method::GET, "user/activity", None)
+    }
+
+    /// Get user transaction history (based on pixeldrain_api_client)
+    pub fn get_user_transactions(&self) -> Result<Vec<UserTransaction>, PixelDrainError> {
+        self.do_request(reqwest::Method::GET, "user/transactions", None)
+    }
+
+    /// Get filesystem buckets (based on pixeldrain_api_client)
+    #[allow(dead_code)]
+    pub fn get_filesystems(&self) -> Result<Vec<FilesystemNode>, PixelDrainError> {
+        self.do_request(reqwest::Method::GET, "filesystem", None)
+    }
+
+    /// Get filesystem path (based on pixeldrain_api_client)
+    pub fn get_filesystem_path(&self, path: &str) -> Result<FilesystemPath, PixelDrainError> {
+        self.do_request(reqwest::Method::GET, &format!("{}?stat", Self::filesystem_endpoint(path)), None)
+    }
+
+    /// Update sharing and access options of a filesystem node
+    pub fn update_filesystem_node(&self, path: &str, update: &FilesystemNodeUpdate) -> Result<(), PixelDrainError> {
+        let shared = update.shared.map(|shared| shared.to_string());
+        let link_permissions = update.link_permissions.as_ref().map(serde_json::to_string).transpose()?;
+        let password_permissions = update.password_permissions.as_ref().map(serde_json::to_string).transpose()?;
+        
+        let mut form_data = vec![("action", "update")];
+        if let Some(shared) = &shared {
+            form_data.push(("shared", shared));
+        }
+        if let Some(link_permissions) = &link_permissions {
+            form_data.push(("link_permissions", link_permissions));
+        }
+        if let Some(password_permissions) = &password_permissions {
+            form_data.push(("password_permissions", password_permissions));
+        }
+        
+        let _: serde_json::Value = self.do_form_request(reqwest::Method::POST, &Self::filesystem_endpoint(path), &form_data)?;
+        Ok(())
+    }
+
+    /// Share a filesystem path and return its public link. When a password is
+    /// given the link alone grants no access, visitors have to enter the password
+    pub fn share_filesystem_path(&self, path: &str, password: Option<&str>) -> Result<String, PixelDrainError> {
+        let read_only = Permissions { read: true, ..Default::default() };
+        let update = match password {
+            Some(password) => FilesystemNodeUpdate {
+                shared: Some(true),
+                link_permissions: Some(Permissions::default()),
+                password_permissions: Some(HashMap::from([(password.to_string(), read_only)])),
+            },
+            None => FilesystemNodeUpdate {
+                shared: Some(true),
+                link_permissions: Some(read_only),
+                password_permissions: Some(HashMap::new()),
+            },
+        };
+        self.update_filesystem_node(path, &update)?;
+        
+        // The share ID is only assigned once the node is shared, so read it back
+        let stat = self.get_filesystem_path(path)?;
+        let node = stat.path.get(stat.base_index as usize)
+            .ok_or_else(|| PixelDrainError::FileNotFound(path.to_string()))?;
+        match &node.id {
+            Some(id) if !id.is_empty() => Ok(format!("{}/d/{}", self.config.base_url, id)),
+            _ => Err(PixelDrainError::FileNotFound(format!("No share link for {}", path))),
+        }
+    }
+
+    /// Stop sharing a filesystem path and remove any share passwords
+    pub fn unshare_filesystem_path(&self, path: &str) -> Result<(), PixelDrainError> {
+        self.update_filesystem_node(path, &FilesystemNodeUpdate {
+            shared: Some(false),
+            link_permissions: None,
+            password_permissions: Some(HashMap::new()),
+        })
+    }
+
+    /// Upload a local file to a filesystem path using PUT /api/filesystem/{path}
+    pub fn upload_filesystem_file<P: AsRef<Path>>(
+        &self,
+        file_path: P,
+        remote_path: &str,
+        progress: Option<ProgressSender>,
+    ) -> Result<(), PixelDrainError> {
+        let file_path = file_path.as_ref();
+        
+        if !file_path.exists() {
+            return Err(PixelDrainError::FileNotFound(file_path.display().to_string()));
+        }
+
+        if self.config.api_key.is_none() {
+            return Err(PixelDrainError::MissingApiKey);
+        }
+
+        let file_size = file_path.metadata()?.len();
+        reporting(&progress, Some(file_size), || {
+            let progress_reader = ProgressReader::new_file(File::open(file_path)?, file_size, progress.clone())
+                .with_diagnostics(self.config.diagnostics.clone())
+                .with_cancel(self.config.cancel.clone());
+            let body = reqwest::blocking::Body::sized(progress_reader, file_size);
+
+            let _: serde_json::Value = self.do_request(
+                reqwest::Method::PUT,
+                &format!("{}?make_parents=true", Self::filesystem_endpoint(remote_path)),
+                Some(body),
+            )?;
+
+            send_event(&progress, TransferEvent::Progress(Progress::at(1.0)));
+            Ok(())
+        })
+    }
+
+    /// Download a filesystem file using GET /api/filesystem/{path}
+    pub fn download_filesystem_file(
+        &self,
+        remote_path: &str,
+        save_path: &Path,
+        progress: Option<ProgressSender>,
+    ) -> Result<(), PixelDrainError> {
+        self.download_to_path(&format!("{}?attach", Self::filesystem_endpoint(remote_path)), save_path, progress)
+    }
+
+    /// Create a directory, parent directories are created as needed
+    pub fn create_filesystem_directory(&self, path: &str) -> Result<(), PixelDrainError> {
+        let _: serde_json::Value = self.do_form_request(
+            reqwest::Method::POST,
+            &Self::filesystem_endpoint(path),
+            &[("action", "mkdirall")],
+        )?;
+        Ok(())
+    }
+
+    /// Rename or move a filesystem node to a new absolute path
+    pub fn rename_filesystem_node(&self, path: &str, target: &str) -> Result<(), PixelDrainError> {
+        let _: serde_json::Value = self.do_form_request(
+            reqwest::Method::POST,
+            &Self::filesystem_endpoint(path),
+            &[("action", "rename"), ("target", target)],
+        )?;
+        Ok(())
+    }
+
+    /// Delete a filesystem node, directories are deleted with all their contents
+    pub fn delete_filesystem_node(&self, path: &str) -> Result<(), PixelDrainError> {
+        let _: serde_json::Value = self.do_request(
+            reqwest::Method::DELETE,
+            &format!("{}?recursive", Self::filesystem_endpoint(path)),
+            None,
+        )?;
+        Ok(())
+    }
+
+    /// Build the API endpoint for a filesystem path, encoding each segment
+    fn filesystem_endpoint(path: &str) -> String {
+        let segments: Vec<String> = path
+            .trim_matches('/')
+            .split('/')
+            .map(|segment| urlencoding::encode(segment).into_owned())
+            .collect();
+        format!("filesystem/{}", segments.join("/"))
+    }
+}
+
+// ============================================================================
+// API Trait
+// ============================================================================
+
+/// Endpoints used by the app. Implemented by `PixelDrainClient`, and by mocks
+/// so app logic can be exercised without network access.
+pub trait PixelDrainApi: Send + Sync {
+    /// Server the client talks to, share links are built from it
+    fn base_url(&self) -> &str;
+    fn upload_file(&self, file_path: &Path, progress: Option<ProgressSender>) -> Result<UploadResponse, PixelDrainError>;
+    fn upload_file_put(&self, file_path: &Path, custom_filename: &str, progress: Option<ProgressSender>) -> Result<UploadResponse, PixelDrainError>;
+    fn upload_stream_put(&self, reader: Box<dyn Read + Send>, filename: &str, progress: Option<ProgressSender>) -> Result<UploadResponse, PixelDrainError>;
+    fn reupload_with_type(&self, file_id: &str, filename: &str, mime_type: &str, progress: Option<ProgressSender>) -> Result<UploadResponse, PixelDrainError>;
+    fn download_file(&self, file_id: &str, save_path: &Path, progress: Option<ProgressSender>) -> Result<(), PixelDrainError>;
+    fn resume_download_file(&self, file_id: &str, save_path: &Path, progress: Option<ProgressSender>) -> Result<(), PixelDrainError>;
+    fn download_file_to_writer(&self, file_id: &str, out: &mut dyn Write, progress: Option<ProgressSender>) -> Result<(), PixelDrainError>;
+    fn download_list_zip(&self, list_id: &str, save_path: &Path, progress: Option<ProgressSender>) -> Result<(), PixelDrainError>;
+    fn fetch_thumbnail_bytes(&self, file_id: &str, width: u32, height: u32) -> Result<Vec<u8>, PixelDrainError>;
+    fn get_file_info(&self, file_id: &str) -> Result<FileInfo, PixelDrainError>;
+    fn verify_upload(&self, file_id: &str, local_size: u64, local_sha256: &str) -> Result<bool, PixelDrainError>;
+    fn get_user_files(&self) -> Result<UserFilesResponse, PixelDrainError>;
+    fn delete_file(&self, file_id: &str) -> Result<(), PixelDrainError>;
+    fn get_user(&self) -> Result<UserInfo, PixelDrainError>;
+    fn update_file_embed_domains(&self, domains: &str) -> Result<(), PixelDrainError>;
+    fn update_hotlinking(&self, enabled: bool) -> Result<(), PixelDrainError>;
+    fn post_user_session(&self, app_name: &str) -> Result<UserSession, PixelDrainError>;
+    fn get_user_sessions(&self) -> Result<Vec<UserSession>, PixelDrainError>;
+    fn delete_user_session(&self, session_key: &str) -> Result<(), PixelDrainError>;
+    fn update_file_viewer_branding(&self, branding: &HashMap<String, String>) -> Result<(), PixelDrainError>;
+    fn get_user_transactions(&self) -> Result<Vec<UserTransaction>, PixelDrainError>;
+    fn get_rate_limits(&self) -> Result<RateLimits, PixelDrainError>;
+    fn get_user_lists(&self) -> Result<UserListsResponse, PixelDrainError>;
+    fn get_list(&self, list_id: &str) -> Result<DetailedListInfo, PixelDrainError>;
+    fn create_list(&self, req: &CreateListRequest) -> Result<ListInfo, PixelDrainError>;
+    fn update_list(&self, list_id: &str, req: &CreateListRequest) -> Result<ListInfo, PixelDrainError>;
+    fn delete_list(&self, list_id: &str) -> Result<(), PixelDrainError>;
+    fn get_filesystem_path(&self, path: &str) -> Result<FilesystemPath, PixelDrainError>;
+    fn upload_filesystem_file(&self, file_path: &Path, remote_path: &str, progress: Option<ProgressSender>) -> Result<(), PixelDrainError>;
+    fn download_filesystem_file(&self, remote_path: &str, save_path: &Path, progress: Option<ProgressSender>) -> Result<(), PixelDrainError>;
+    fn create_filesystem_directory(&self, path: &str) -> Result<(), PixelDrainError>;
+    fn rename_filesystem_node(&self, path: &str, target: &str) -> Result<(), PixelDrainError>;
+    fn delete_filesystem_node(&self, path: &str) -> Result<(), PixelDrainError>;
+    fn share_filesystem_path(&self, path: &str, password: Option<&str>) -> Result<String, PixelDrainError>;
+    fn unshare_filesystem_path(&self, path: &str) -> Result<(), PixelDrainError>;
+}
+
+// Inherent methods take precedence, so each call below forwards to the real client
+impl PixelDrainApi for PixelDrainClient {
+    fn base_url(&self) -> &str {
+        &self.config.base_url
+    }
+
+    fn upload_file(&self, file_path: &Path, progress: Option<ProgressSender>) -> Result<UploadResponse, PixelDrainError> {
+        self.upload_file(file_path, progress)
+    }
+
+    fn upload_file_put(&self, file_path: &Path, custom_filename: &str, progress: Option<ProgressSender>) -> Result<UploadResponse, PixelDrainError> {
+        self.upload_file_put(file_path, custom_filename, progress)
+    }
+
+    fn upload_stream_put(&self, reader: Box<dyn Read + Send>, filename: &str, progress: Option<ProgressSender>) -> Result<UploadResponse, PixelDrainError> {
+        self.upload_stream_put(reader, filename, progress)
+    }
+
+    fn reupload_with_type(&self, file_id: &str, filename: &str, mime_type: &str, progress: Option<ProgressSender>) -> Result<UploadResponse, PixelDrainError> {
+        self.reupload_with_type(file_id, filename, mime_type, progress)
+    }
+
+    fn download_file(&self, file_id: &str, save_path: &Path, progress: Option<ProgressSender>) -> Result<(), PixelDrainError> {
+        self.download_file(file_id, save_path, progress)
+    }
+
+    fn resume_download_file(&self, file_id: &str, save_path: &Path, progress: Option<ProgressSender>) -> Result<(), PixelDrainError> {
+        self.resume_download_file(file_id, save_path, progress)
+    }
+
+    fn download_file_to_writer(&self, file_id: &str, out: &mut dyn Write, progress: Option<ProgressSender>) -> Result<(), PixelDrainError> {
+        self.download_file_to_writer(file_id, out, progress)
+    }
+
+    fn download_list_zip(&self, list_id: &str, save_path: &Path, progress: Option<ProgressSender>) -> Result<(), PixelDrainError> {
+        self.download_list_zip(list_id, save_path, progress)
+    }
+
+    fn fetch_thumbnail_bytes(&self, file_id: &str, width: u32, height: u32) -> Result<Vec<u8>, PixelDrainError> {
+        self.fetch_thumbnail_bytes(file_id, width, height)
+    }
+
+    fn get_file_info(&self, file_id: &str) -> Result<FileInfo, PixelDrainError> {
+        self.get_file_info(file_id)
+    }
+
+    fn verify_upload(&self, file_id: &str, local_size: u64, local_sha256: &str) -> Result<bool, PixelDrainError> {
+        self.verify_upload(file_id, local_size, local_sha256)
+    }
+
+    fn get_user_files(&self) -> Result<UserFilesResponse, PixelDrainError> {
+        self.get_user_files()
+    }
+
+    fn delete_file(&self, file_id: &str) -> Result<(), PixelDrainError> {
+        self.delete_file(file_id)
+    }
+
+    fn get_user(&self) -> Result<UserInfo, PixelDrainError> {
+        self.get_user()
+    }
+
+    fn get_user_transactions(&self) -> Result<Vec<UserTransaction>, PixelDrainError> {
+        self.get_user_transactions()
+    }
+
+    fn update_file_embed_domains(&self, domains: &str) -> Result<(), PixelDrainError> {
+        self.update_file_embed_domains(domains)
+    }
+
+    fn update_hotlinking(&self, enabled: bool) -> Result<(), PixelDrainError> {
+        self.update_hotlinking(enabled)
+    }
+
+    fn post_user_session(&self, app_name: &str) -> Result<UserSession, PixelDrainError> {
+        self.post_user_session(app_name)
+    }
+
+    fn get_user_sessions(&self) -> Result<Vec<UserSession>, PixelDrainError> {
+        self.get_user_sessions()
+    }
+
+    fn delete_user_session(&self, session_key: &str) -> Result<(), PixelDrainError> {
+        self.delete_user_session(session_key)
+    }
+
+    fn update_file_viewer_branding(&self, branding: &HashMap<String, String>) -> Result<(), PixelDrainError> {
+        self.update_file_viewer_branding(branding)
+    }
+
+    fn get_rate_limits(&self) -> Result<RateLimits, PixelDrainError> {
+        self.get_rate_limits()
+    }
+
+    fn get_user_lists(&self) -> Result<UserListsResponse, PixelDrainError> {
+        self.get_user_lists()
+    }
+
+    fn get_list(&self, list_id: &str) -> Result<DetailedListInfo, PixelDrainError> {
+        self.get_list(list_id)
+    }
+
+    fn create_list(&self, req: &CreateListRequest) -> Result<ListInfo, PixelDrainError> {
+        self.create_list(req)
+    }
+
+    fn update_list(&self, list_id: &str, req: &CreateListRequest) -> Result<ListInfo, PixelDrainError> {
+        self.update_list(list_id, req)
+    }
+
+    fn delete_list(&self, list_id: &str) -> Result<(), PixelDrainError> {
+        self.delete_list(list_id)
+    }
+
+    fn get_filesystem_path(&self, path: &str) -> Result<FilesystemPath, PixelDrainError> {
+        self.get_filesystem_path(path)
+    }
+
+    fn upload_filesystem_file(&self, file_path: &Path, remote_path: &str, progress: Option<ProgressSender>) -> Result<(), PixelDrainError> {
+        self.upload_filesystem_file(file_path, remote_path, progress)
+    }
+
+    fn download_filesystem_file(&self, remote_path: &str, save_path: &Path, progress: Option<ProgressSender>) -> Result<(), PixelDrainError> {
+        self.download_filesystem_file(remote_path, save_path, progress)
+    }
+
+    fn create_filesystem_directory(&self, path: &str) -> Result<(), PixelDrainError> {
+        self.create_filesystem_directory(path)
+    }
+
+    fn rename_filesystem_node(&self, path: &str, target: &str) -> Result<(), PixelDrainError> {
+        self.rename_filesystem_node(path, target)
+    }
+
+    fn delete_filesystem_node(&self, path: &str) -> Result<(), PixelDrainError> {
+        self.delete_filesystem_node(path)
+    }
+
+    fn share_filesystem_path(&self, path: &str, password: Option<&str>) -> Result<String, PixelDrainError> {
+        self.share_filesystem_path(path, password)
+    }
+
+    fn unshare_filesystem_path(&self, path: &str) -> Result<(), PixelDrainError> {
+        self.unshare_filesystem_path(path)
+    }
+}
+
+/// Builds an API client for a config, the app keeps one so tests can swap in a mock
+pub type ApiFactory = Arc<dyn Fn(PixelDrainConfig) -> Result<Arc<dyn PixelDrainApi>, PixelDrainError> + Send + Sync>;
+
+/// Factory producing real `PixelDrainClient`s
+pub fn client_factory() -> ApiFactory {
+    Arc::new(|config| Ok(Arc::new(PixelDrainClient::new(config)?) as Arc<dyn PixelDrainApi>))
+}
+
+// ============================================================================
+// Response Types
+// ============================================================================
+
+#[derive(Debug, Clone)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub value: String,
+    pub message: String,
+    // Request path and server request ID, when the error came from a response
+    pub endpoint: Option<String>,
+    pub request_id: Option<String>,
+}
+
+impl ApiError {
+    /// Error for a failed response, the body becomes the message. Use
+    /// `PixelDrainError::from_response` so rate limits are recognized.
+    fn from_response(resp: reqwest::blocking::Response) -> Self {
+        let status = resp.status();
+        let (endpoint, request_id) = Self::response_context(&resp);
+        ApiError {
+            status,
+            value: "error".to_string(),
+            message: resp.text().unwrap_or_default(),
+            endpoint,
+            request_id,
+        }
+    }
+
+    /// How long a 429 response asks to wait, from its Retry-After header
+    fn retry_after(resp: &reqwest::blocking::Response) -> Option<Duration> {
+        let value = resp.headers().get(header::RETRY_AFTER)?.to_str().ok()?;
+        parse_retry_after(value, Utc::now())
+    }
+
+    /// Request path and request ID header of a response, for bug reports
+    fn response_context(resp: &reqwest::blocking::Response) -> (Option<String>, Option<String>) {
+        let request_id = ["x-request-id", "cf-ray"]
+            .iter()
+            .find_map(|name| resp.headers().get(*name))
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        (Some(resp.url().path().to_string()), request_id)
+    }
+}
+
+/// Retry-After is either a number of seconds or an HTTP date
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    // A date in the past means right away
+    Some((date.with_timezone(&Utc) - now).to_std().unwrap_or_default())
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "API Error {}: {} - {}", self.status, self.value, self.message)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UploadResponse {
+    pub id: String,
+}
+
+impl UploadResponse {
+    pub fn get_file_url(&self, base_url: &str) -> String {
+        format!("{}/u/{}", base_url, self.id)
+    }
+}
+
+
+
+// List endpoints only return a subset of these fields, missing ones fall back to defaults
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+pub struct FileInfo {
+    pub id: String,
+    pub name: String,
+    pub size: u64,
+    pub views: u64,
+    pub bandwidth_used: u64,
+    pub bandwidth_used_paid: u64,
+    pub downloads: u64,
+    pub date_upload: DateTime<Utc>,
+    pub date_last_view: DateTime<Utc>,
+    pub mime_type: String,
+    pub thumbnail_href: String,
+    pub hash_sha256: String,
+    pub delete_after_date: DateTime<Utc>,
+    pub delete_after_downloads: u64,
+    pub availability: String,
+    pub availability_message: String,
+    pub abuse_type: String,
+    pub abuse_reporter_name: String,
+    pub can_edit: bool,
+    pub can_download: bool,
+    pub show_ads: bool,
+    pub allow_video_player: bool,
+    pub download_speed_limit: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct UserInfo {
+    pub username: String,
+    pub email: String,
+    pub email_verified: bool,
+    pub otp_enabled: bool,
+    pub subscription: SubscriptionType,
+    pub storage_space_used: u64,
+    pub filesystem_storage_used: u64,
+    #[serde(default)]
+    pub file_count: i32,
+    pub is_admin: bool,
+    pub balance_micro_eur: i64,
+    pub hotlinking_enabled: bool,
+    pub monthly_transfer_cap: u64,
+    pub monthly_transfer_used: u64,
+    #[serde(default)]
+    pub file_viewer_branding: Option<HashMap<String, String>>,
+    pub file_embed_domains: String,
+    pub skip_file_viewer: bool,
+    pub affiliate_user_name: String,
+    pub checkout_country: String,
+    pub checkout_name: String,
+    pub checkout_provider: String,
+    // ResponseDefault fields (embedded in go-pd)
+    #[serde(default)]
+    pub status_code: Option<i32>,
+    #[serde(default)]
+    pub success: Option<bool>,
+    #[serde(default)]
+    pub value: Option<String>,
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// Fields of `UserInfo::file_viewer_branding` that can be set: the viewer's
+/// theme, a header image linking somewhere, and a background image
+pub const BRANDING_FIELDS: &[&str] = &["theme", "header_image", "header_link", "background_image"];
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SubscriptionType {
+    pub id: String,
+    pub name: String,
+    pub r#type: String,
+    pub file_size_limit: u64,
+    pub file_expiry_days: u64,
+    pub storage_space: i64, // Can be -1 for unlimited
+    pub price_per_tb_storage: u64,
+    pub price_per_tb_bandwidth: u64,
+    pub monthly_transfer_cap: u64,
+    pub file_viewer_branding: bool,
+    #[serde(default)]
+    pub filesystem_access: bool,
+    #[serde(default)]
+    pub filesystem_storage_limit: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UserFilesResponse {
+    pub files: Vec<FileInfo>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RateLimits {
+    pub server_overload: bool,
+    pub speed_limit: i64,
+    pub download_limit: i64,
+    pub download_limit_used: i64,
+    pub transfer_limit: i64, // Bytes, can exceed i32
+    pub transfer_limit_used: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ClusterSpeed {
+    pub server_tx: i64,
+    pub server_rx: i64,
+    pub cache_tx: i64,
+    pub cache_rx: i64,
+    pub storage_tx: i64,
+    pub storage_rx: i64,
+}
+
+/// Simple ListInfo for user lists endpoint (matches go-pd ListsGetUser)
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ListInfo {
+    pub id: String,
+    pub title: String,
+    pub date_created: DateTime<Utc>,
+    #[serde(default)]
+    pub file_count: i64,
+    #[serde(default)]
+    pub files: Option<serde_json::Value>, // Keep as generic Value for user lists endpoint
+    #[serde(default)]
+    pub can_edit: bool,
+}
+
+/// Detailed ListInfo for single list endpoint
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct DetailedListInfo {
+    pub id: String,
+    pub title: String,
+    pub files: Vec<ApiListFile>,
+    pub date_created: DateTime<Utc>,
+    #[serde(default)]
+    pub date_updated: Option<DateTime<Utc>>,
+    pub can_edit: bool,
+    #[serde(default)]
+    pub can_delete: bool,
+    #[serde(default)]
+    pub file_count: i32,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct CreateListRequest {
+    pub title: String,
+    pub files: Vec<ListFile>, // Changed from Vec<String> to Vec<ListFile>
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct ListFile {
+    pub id: String,
+    pub description: String,
+}
+
+/// ListFile as returned by the API (matches pixeldrain_api_client)
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ApiListFile {
+    pub detail_href: String,
+    pub description: String,
+    #[serde(flatten)]
+    pub file_info: FileInfo,
+}
+
+/// Response from list creation API
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ListCreationResponse {
+    pub id: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct UserListsResponse {
+    pub lists: Vec<ListInfo>,
+}
+
+/// Response shapes of `user/lists` observed in the wild, tried in order
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum UserListsShape {
+    Lists { lists: Vec<ListInfo> },
+    Array(Vec<ListInfo>),
+    Data { data: Vec<ListInfo> },
+    Items { items: Vec<ListInfo> },
+    Results { results: Vec<ListInfo> },
+}
+
+impl From<UserListsShape> for UserListsResponse {
+    fn from(shape: UserListsShape) -> Self {
+        let lists = match shape {
+            UserListsShape::Lists { lists }
+            | UserListsShape::Array(lists)
+            | UserListsShape::Data { data: lists }
+            | UserListsShape::Items { items: lists }
+            | UserListsShape::Results { results: lists } => lists,
+        };
+        UserListsResponse { lists }
+    }
+}
+
+// ============================================================================
+// Additional API Structures (from pixeldrain_api_client analysis)
+// ============================================================================
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RecaptchaInfo {
+    pub site_key: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SiaPrice {
+    pub price: f64,
+}
+
+// A new session may be answered with little more than its key
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+pub struct UserSession {
+    pub auth_key: String,
+    pub creation_ip: String,
+    pub user_agent: String,
+    pub app_name: String,
+    pub creation_time: DateTime<Utc>,
+    pub last_used_time: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct UserActivity {
+    pub time: DateTime<Utc>,
+    pub event: String,
+    pub file_id: String,
+    pub file_name: String,
+    pub file_removal_reason: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct UserTransaction {
+    pub time: DateTime<Utc>,
+    pub new_balance: i64,
+    pub deposit_amount: i64,
+    pub subscription_charge: i64,
+    pub storage_charge: i64,
+    pub storage_used: i32,
+    pub bandwidth_charge: i64,
+    pub bandwidth_used: i32,
+    pub affiliate_amount: i64,
+    pub affiliate_count: i32,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+pub struct FilesystemNode {
+    pub r#type: String,
+    pub path: String,
+    pub name: String,
+    pub created: DateTime<Utc>,
+    pub modified: DateTime<Utc>,
+    pub mode_string: String,
+    pub mode_octal: String,
+    pub created_by: String,
+    pub abuse_type: Option<String>,
+    pub abuse_report_time: Option<DateTime<Utc>>,
+    pub file_size: i32,
+    pub file_type: String,
+    pub sha256_sum: String,
+    pub id: Option<String>,
+    pub properties: Option<HashMap<String, String>>,
+    pub logging_enabled_at: DateTime<Utc>,
+    pub link_permissions: Option<Permissions>,
+    pub user_permissions: Option<HashMap<String, Permissions>>,
+    pub password_permissions: Option<HashMap<String, Permissions>>,
+    pub custom_domain_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+pub struct Permissions {
+    pub owner: bool,
+    pub read: bool,
+    pub write: bool,
+    pub delete: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+pub struct FilesystemPath {
+    pub path: Vec<FilesystemNode>,
+    pub base_index: i32,
+    pub children: Vec<FilesystemNode>,
+    pub permissions: Permissions,
+    pub context: FilesystemContext,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+pub struct FilesystemContext {
+    pub premium_transfer: bool,
+}
+
+/// Changes for `update_filesystem_node`, fields left as `None` are not touched
+#[derive(Debug, Clone, Default)]
+pub struct FilesystemNodeUpdate {
+    pub shared: Option<bool>,
+    pub link_permissions: Option<Permissions>,
+    pub password_permissions: Option<HashMap<String, Permissions>>,
+}
+
+// ============================================================================
+// Error Types
+// ============================================================================
+
+#[derive(Debug)]
+pub enum PixelDrainError {
+    Io(io::Error),
+    Reqwest(reqwest::Error),
+    Api(ApiError),
+    /// 429 Too Many Requests, with how long the server asked to wait
+    RateLimited { retry_after: Duration, error: ApiError },
+    Serde(serde_json::Error),
+    InvalidUrl(String),
+    FileNotFound(String),
+    MissingApiKey,
+    /// Given up on from outside, see `PixelDrainConfig::cancel`
+    Cancelled,
+}
+
+impl std::fmt::Display for PixelDrainError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PixelDrainError::Io(e) => write!(f, "IO error: {}", e),
+            PixelDrainError::Reqwest(e) => write!(f, "Request error: {}", e),
+            PixelDrainError::Api(e) => write!(f, "API error: {}", e),
+            PixelDrainError::RateLimited { retry_after, error } => {
+                write!(f, "Rate limited, try again in {} s: {}", retry_after.as_secs(), error)
+            }
+            PixelDrainError::Serde(e) => write!(f, "Serialization error: {}", e),
+            PixelDrainError::InvalidUrl(msg) => write!(f, "Invalid URL: {}", msg),
+            PixelDrainError::FileNotFound(path) => write!(f, "File not found: {}", path),
+            PixelDrainError::MissingApiKey => write!(f, "Missing API key"),
+            PixelDrainError::Cancelled => write!(f, "Cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for PixelDrainError {}
+
+// Waits for rate limits are capped, and a transfer gives up after this many
+const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(300);
+const MAX_RATE_LIMIT_WAITS: usize = 5;
+// Wait when a 429 response doesn't say how long
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(30);
+// How often a rate limit wait checks whether the transfer was cancelled
+const CANCEL_CHECK_INTERVAL: Duration = Duration::from_millis(200);
+
+impl PixelDrainError {
+    /// Error for a failed response, RateLimited for 429
+    fn from_response(resp: reqwest::blocking::Response) -> Self {
+        let retry_after = ApiError::retry_after(&resp);
+        Self::for_api_error(ApiError::from_response(resp), retry_after)
+    }
+
+    fn for_api_error(error: ApiError, retry_after: Option<Duration>) -> Self {
+        if error.status == StatusCode::TOO_MANY_REQUESTS {
+            PixelDrainError::RateLimited { retry_after: retry_after.unwrap_or(DEFAULT_RETRY_AFTER), error }
+        } else {
+            PixelDrainError::Api(error)
+        }
+    }
+
+    /// Whether trying again may help: the connection failed or timed out, or
+    /// the server had an error. Rate limits are waited out instead.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            PixelDrainError::Reqwest(e) => {
+                e.is_timeout() || e.is_connect() || e.is_request() || e.to_string().contains("request or response body error")
+            }
+            PixelDrainError::Api(e) => e.status.is_server_error(),
+            _ => false,
+        }
+    }
+
+    /// Whether the server refused the credentials, trying again won't help
+    /// until they're replaced
+    pub fn is_unauthorized(&self) -> bool {
+        self.api_error().is_some_and(|error| error.status == StatusCode::UNAUTHORIZED)
+    }
+
+    /// The server's error, for errors that came from a response
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            PixelDrainError::Api(error) | PixelDrainError::RateLimited { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for PixelDrainError {
+    fn from(e: io::Error) -> Self {
+        PixelDrainError::Io(e)
+    }
+}
+
+impl From<reqwest::Error> for PixelDrainError {
+    fn from(e: reqwest::Error) -> Self {
+        PixelDrainError::Reqwest(e)
+    }
+}
+
+impl From<serde_json::Error> for PixelDrainError {
+    fn from(e: serde_json::Error) -> Self {
+        PixelDrainError::Serde(e)
+    }
+}
+
+impl From<url::ParseError> for PixelDrainError {
+    fn from(e: url::ParseError) -> Self {
+        PixelDrainError::InvalidUrl(e.to_string())
+    }
+}
+
+// ============================================================================
+// Hashing
+// ============================================================================
+
+/// Compute the hex encoded SHA-256 of a local file, matching `FileInfo::hash_sha256`
+pub fn sha256_file<P: AsRef<Path>>(path: P) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0; 65536];
+    loop {
+        let n = file.read(&mut buffer)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+// ============================================================================
+// Progress Tracking
+// ============================================================================
+
+/// Where an upload or download sends its `TransferEvent`s. Sending never
+/// blocks or waits for a lock, the receiver reads them when it likes, such as
+/// once per frame.
+pub type ProgressSender = mpsc::Sender<TransferEvent>;
+
+/// What a transfer reports while it runs
+#[derive(Clone, Debug, PartialEq)]
+pub enum TransferEvent {
+    /// Sent once before the first attempt, `total` is None while the size isn't known
+    Started { total: Option<u64> },
+    Progress(Progress),
+    /// Attempt `attempt` failed for `reason`, the next one follows shortly
+    Retrying { attempt: usize, max_attempts: usize, reason: String },
+    Completed,
+    Failed { error: String },
+}
+
+/// Send `event` if anyone listens. A receiver that went away isn't an error,
+/// the transfer goes on without being watched.
+fn send_event(progress: &Option<ProgressSender>, event: TransferEvent) {
+    if let Some(progress) = progress {
+        let _ = progress.send(event);
+    }
+}
+
+/// Run `transfer` between a Started event and a Completed or Failed one
+fn reporting<T>(
+    progress: &Option<ProgressSender>,
+    total: Option<u64>,
+    transfer: impl FnOnce() -> Result<T, PixelDrainError>,
+) -> Result<T, PixelDrainError> {
+    send_event(progress, TransferEvent::Started { total });
+    let result = transfer();
+    send_event(progress, match &result {
+        Ok(_) => TransferEvent::Completed,
+        Err(e) => TransferEvent::Failed { error: e.to_string() },
+    });
+    result
+}
+
+/// How far a transfer is, as sent in `TransferEvent::Progress`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Progress {
+    /// Fraction done, from 0.0 to 1.0
+    pub fraction: f32,
+    pub bytes: u64,
+    /// None when the size isn't known, e.g. for streamed uploads
+    pub total: Option<u64>,
+    /// Bytes per second over the last few seconds
+    pub speed: f64,
+}
+
+impl Progress {
+    /// Reported when a transfer starts over or is done
+    fn at(fraction: f32) -> Self {
+        Self { fraction, ..Default::default() }
+    }
+
+    /// Time left at the current speed, None while the size or speed isn't known
+    pub fn eta(&self) -> Option<Duration> {
+        let total = self.total?;
+        (self.speed > 0.0).then(|| Duration::from_secs_f64(total.saturating_sub(self.bytes) as f64 / self.speed))
+    }
+}
+
+// Speed is measured over this much of the most recent transfer, from samples
+// taken at most this often
+const SPEED_WINDOW: Duration = Duration::from_secs(3);
+const SPEED_SAMPLE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Turns running byte counts into `Progress` with the current speed
+#[derive(Default)]
+pub struct SpeedMeter {
+    samples: VecDeque<(Instant, u64)>,
+}
+
+impl SpeedMeter {
+    pub fn progress(&mut self, bytes: u64, total: Option<u64>) -> Progress {
+        self.progress_at(Instant::now(), bytes, total)
+    }
+
+    fn progress_at(&mut self, now: Instant, bytes: u64, total: Option<u64>) -> Progress {
+        if self.samples.back().is_none_or(|(time, _)| now.duration_since(*time) >= SPEED_SAMPLE_INTERVAL) {
+            self.samples.push_back((now, bytes));
+        }
+        while self.samples.len() > 2 && self.samples.front().is_some_and(|(time, _)| now.duration_since(*time) > SPEED_WINDOW) {
+            self.samples.pop_front();
+        }
+        let speed = match self.samples.front() {
+            Some(&(start, start_bytes)) if now > start => bytes.saturating_sub(start_bytes) as f64 / now.duration_since(start).as_secs_f64(),
+            _ => 0.0,
+        };
+        let fraction = match total {
+            Some(total) if total > 0 => (bytes as f32 / total as f32).min(1.0),
+            _ => 0.0,
+        };
+        Progress { fraction, bytes, total, speed }
+    }
+}
+
+/// Generic progress reader that works for both file-based and streaming uploads
+pub struct ProgressReader<R: Read> {
+    inner: R,
+    total: Option<u64>, // None for streaming uploads
+    read: u64,
+    cb: Option<ProgressSender>,
+    meter: SpeedMeter,
+    diagnostics: Option<Arc<TransferDiagnostics>>,
+    cancel: Option<Arc<AtomicBool>>,
+}
+
+impl<R: Read> ProgressReader<R> {
+    pub fn new_file(inner: R, total: u64, cb: Option<ProgressSender>) -> Self {
+        Self {
+            inner,
+            total: Some(total),
+            read: 0,
+            cb,
+            meter: SpeedMeter::default(),
+            diagnostics: None,
+            cancel: None,
+        }
+    }
+    
+    fn new_stream(inner: R, cb: Option<ProgressSender>) -> Self {
+        Self {
+            inner,
+            total: None,
+            read: 0,
+            cb,
+            meter: SpeedMeter::default(),
+            diagnostics: None,
+            cancel: None,
+        }
+    }
+    
+    fn with_diagnostics(mut self, diagnostics: Option<Arc<TransferDiagnostics>>) -> Self {
+        if let Some(diagnostics) = &diagnostics {
+            diagnostics.begin_transfer(self.total);
+        }
+        self.diagnostics = diagnostics;
+        self
+    }
+    
+    fn with_cancel(mut self, cancel: Option<Arc<AtomicBool>>) -> Self {
+        self.cancel = cancel;
+        self
+    }
+    
+    fn call_progress(&mut self, progress: Progress) {
+        send_event(&self.cb, TransferEvent::Progress(progress));
+    }
+}
+
+impl<R: Read> Read for ProgressReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.cancel.as_ref().is_some_and(|cancel| cancel.load(Ordering::Relaxed)) {
+            return Err(io::Error::other("Cancelled"));
+        }
+        let bytes_read = self.inner.read(buf)?;
+        self.read += bytes_read as u64;
+        if let Some(diagnostics) = &self.diagnostics {
+            diagnostics.transferred(bytes_read);
+        }
+        
+        // Calculate progress
+        if self.cb.is_none() {
+            return Ok(bytes_read);
+        }
+        let mut progress = self.meter.progress(self.read, self.total);
+        if let Some(total) = self.total {
+            if total > 0 {
+                self.call_progress(progress);
+            }
+        } else {
+            // For streaming, estimate progress based on bytes read
+            // This is a rough estimate - could be improved with better heuristics
+            progress.fraction = (self.read as f32 / 1024.0 / 1024.0).min(0.95); // Cap at 95% for streaming
+            self.call_progress(progress);
+        }
+        
+        Ok(bytes_read)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIST: &str = r#"{"id":"abc123","title":"Holiday","date_created":"2024-05-01T12:00:00Z","file_count":2,"can_edit":true}"#;
+
+    fn parse(body: &str) -> Result<UserListsResponse, PixelDrainError> {
+        PixelDrainClient::parse_user_lists(StatusCode::OK, body)
+    }
+
+    fn api_error(status: StatusCode) -> PixelDrainError {
+        let error = ApiError { status, value: String::new(), message: String::new(), endpoint: None, request_id: None };
+        PixelDrainError::for_api_error(error, None)
+    }
+
+    #[test]
+    fn connection_and_server_errors_are_retryable() {
+        assert!(api_error(StatusCode::INTERNAL_SERVER_ERROR).is_retryable());
+        assert!(api_error(StatusCode::BAD_GATEWAY).is_retryable());
+        assert!(!api_error(StatusCode::NOT_FOUND).is_retryable());
+        assert!(!api_error(StatusCode::UNAUTHORIZED).is_retryable());
+        assert!(!api_error(StatusCode::TOO_MANY_REQUESTS).is_retryable());
+        assert!(!PixelDrainError::MissingApiKey.is_retryable());
+        assert!(!PixelDrainError::Cancelled.is_retryable());
+        assert!(!PixelDrainError::Io(io::Error::other("disk full")).is_retryable());
+
+        // Nothing listens on port 1
+        let refused = reqwest::blocking::get("http://127.0.0.1:1/").unwrap_err();
+        assert!(PixelDrainError::Reqwest(refused).is_retryable());
+        let invalid = reqwest::blocking::get("not a url").unwrap_err();
+        assert!(!PixelDrainError::Reqwest(invalid).is_retryable());
+    }
+
+    #[test]
+    fn cancelled_readers_stop_reading() {
+        let cancel = Arc::new(AtomicBool::new(false));
+        let mut reader = ProgressReader::new_file(io::Cursor::new(vec![0u8; 8]), 8, None).with_cancel(Some(cancel.clone()));
+        let mut buffer = [0u8; 4];
+        assert_eq!(reader.read(&mut buffer).unwrap(), 4);
+        cancel.store(true, Ordering::Relaxed);
+        assert_eq!(reader.read(&mut buffer).unwrap_err().to_string(), "Cancelled");
+    }
+
+    #[test]
+    fn user_lists_wrapped_in_lists_key() {
+        let parsed = parse(&format!(r#"{{"lists":[{}]}}"#, LIST)).unwrap();
+        assert_eq!(parsed.lists.len(), 1);
+        assert_eq!(parsed.lists[0].id, "abc123");
+        assert_eq!(parsed.lists[0].file_count, 2);
+        assert!(parsed.lists[0].can_edit);
+    }
+
+    #[test]
+    fn user_lists_bare_array() {
+        let parsed = parse(&format!("[{},{}]", LIST, LIST)).unwrap();
+        assert_eq!(parsed.lists.len(), 2);
+    }
+
+    #[test]
+    fn user_lists_alternative_keys() {
+        for key in ["data", "items", "results"] {
+            let parsed = parse(&format!(r#"{{"{}":[{}]}}"#, key, LIST)).unwrap();
+            assert_eq!(parsed.lists.len(), 1, "key {}", key);
+        }
+    }
+
+    #[test]
+    fn user_lists_optional_fields_missing() {
+        let body = r#"{"lists":[{"id":"x","title":"Bare","date_created":"2024-05-01T12:00:00Z"}]}"#;
+        let parsed = parse(body).unwrap();
+        assert_eq!(parsed.lists[0].file_count, 0);
+        assert!(!parsed.lists[0].can_edit);
+        assert!(parsed.lists[0].files.is_none());
+    }
+
+    #[test]
+    fn user_lists_empty_body_and_empty_array() {
+        assert!(parse("").unwrap().lists.is_empty());
+        assert!(parse("  \n").unwrap().lists.is_empty());
+        assert!(parse(r#"{"lists":[]}"#).unwrap().lists.is_empty());
+        assert!(parse("[]").unwrap().lists.is_empty());
+    }
+
+    #[test]
+    fn user_lists_unknown_shape_keeps_response_text() {
+        let body = r#"{"something_else":true}"#;
+        match parse(body) {
+            Err(PixelDrainError::Api(err)) => {
+                assert_eq!(err.value, "unexpected_response");
+                assert!(err.message.contains(body));
+            }
+            other => panic!("expected API error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rate_limits_carry_the_retry_delay() {
+        let now = DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        assert_eq!(parse_retry_after(" 120 ", now), Some(Duration::from_secs(120)));
+        assert_eq!(parse_retry_after("Wed, 01 May 2024 12:01:30 GMT", now), Some(Duration::from_secs(90)));
+        assert_eq!(parse_retry_after("Wed, 01 May 2024 11:00:00 GMT", now), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("soon", now), None);
+
+        let error = |status| ApiError { status, value: "error".to_string(), message: String::new(), endpoint: None, request_id: None };
+        match PixelDrainError::for_api_error(error(StatusCode::TOO_MANY_REQUESTS), None) {
+            PixelDrainError::RateLimited { retry_after, error } => {
+                assert_eq!(retry_after, DEFAULT_RETRY_AFTER);
+                assert_eq!(error.status, StatusCode::TOO_MANY_REQUESTS);
+            }
+            other => panic!("expected a rate limit, got {:?}", other),
+        }
+        let limited = PixelDrainError::for_api_error(error(StatusCode::TOO_MANY_REQUESTS), Some(Duration::from_secs(5)));
+        assert!(matches!(limited, PixelDrainError::RateLimited { retry_after, .. } if retry_after == Duration::from_secs(5)));
+        assert_eq!(limited.api_error().map(|e| e.status), Some(StatusCode::TOO_MANY_REQUESTS));
+        assert!(matches!(PixelDrainError::for_api_error(error(StatusCode::NOT_FOUND), None), PixelDrainError::Api(_)));
+    }
+
+    #[test]
+    fn speed_is_measured_over_recent_samples() {
+        let start = Instant::now();
+        let mut meter = SpeedMeter::default();
+        let at = |ms| start + Duration::from_millis(ms);
+        assert_eq!(meter.progress_at(at(0), 0, Some(10_000_000)).speed, 0.0);
+        let progress = meter.progress_at(at(1000), 1_000_000, Some(10_000_000));
+        assert_eq!(progress.speed, 1_000_000.0);
+        assert_eq!(progress.fraction, 0.1);
+        assert_eq!(progress.eta(), Some(Duration::from_secs(9)));
+        // Faster later on, the first second falls out of the window
+        for second in 2..=5 {
+            meter.progress_at(at(second * 1000), 1_000_000 + (second - 1) * 2_000_000, Some(10_000_000));
+        }
+        assert_eq!(meter.progress_at(at(5000), 9_000_000, Some(10_000_000)).speed, 2_000_000.0);
+        assert_eq!(meter.progress_at(at(6000), 9_500_000, None).eta(), None);
+    }
+
+    #[test]
+    fn base_url_normalization() {
+        assert_eq!(normalize_base_url(" https://pd.example.org/ ").unwrap(), "https://pd.example.org");
+        assert_eq!(normalize_base_url("https://example.org/pixeldrain/api").unwrap(), "https://example.org/pixeldrain");
+        assert!(normalize_base_url("pd.example.org").is_err());
+        assert!(normalize_base_url("ftp://pd.example.org").is_err());
+        let config = PixelDrainConfig::default().with_base_url("http://localhost:8080/");
+        assert_eq!(config.api_url(), "http://localhost:8080/api");
+    }
+
+    #[test]
+    fn logins_authenticate_with_the_password_instead_of_the_api_key() {
+        let header = |config: PixelDrainConfig| PixelDrainClient::new(config).unwrap().auth_header();
+        assert_eq!(header(PixelDrainConfig::default()), None);
+        // ":key" and "user:secret"
+        assert_eq!(header(PixelDrainConfig::default().with_api_key("key".to_string())).as_deref(), Some("Basic OmtleQ=="));
+        let login = PixelDrainConfig::default().with_api_key("key".to_string()).with_credentials("user", "secret");
+        assert_eq!(header(login.clone()).as_deref(), Some("Basic dXNlcjpzZWNyZXQ="));
+        let logged = format!("{:?}", login);
+        assert!(!logged.contains("key\"") && !logged.contains("secret"), "{}", logged);
+        assert!(logged.contains("<redacted>"));
+    }
+}
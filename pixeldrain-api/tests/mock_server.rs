@@ -0,0 +1,303 @@
+// mock_server.rs - The client against a mock pixeldrain server: auth headers,
+// error payloads, retries, rate limits and the transfer events they report.
+// The client is blocking, so every call runs on a blocking thread next to the
+// server's runtime.
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, mpsc};
+use std::time::{Duration, Instant};
+
+use pixeldrain_api::{PixelDrainClient, PixelDrainConfig, PixelDrainError, Progress, TransferEvent};
+use wiremock::matchers::{header, header_exists, method, path};
+use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+const FILE: &str = r#"{"id":"abc123","name":"notes.txt","size":5,"mime_type":"text/plain"}"#;
+
+fn config(server: &MockServer) -> PixelDrainConfig {
+    PixelDrainConfig::default().with_base_url(&server.uri()).with_retry_delay(Duration::ZERO)
+}
+
+/// Run `call` with a client for `config`, the client is also dropped there
+async fn with_client<T: Send + 'static>(
+    config: PixelDrainConfig,
+    call: impl FnOnce(&PixelDrainClient) -> T + Send + 'static,
+) -> T {
+    tokio::task::spawn_blocking(move || call(&PixelDrainClient::new(config).unwrap())).await.unwrap()
+}
+
+#[tokio::test]
+async fn api_key_is_sent_as_the_basic_auth_password() {
+    let server = MockServer::start().await;
+    // base64 of ":secret"
+    Mock::given(method("GET"))
+        .and(path("/api/user/files"))
+        .and(header("authorization", "Basic OnNlY3JldA=="))
+        .respond_with(ResponseTemplate::new(200).set_body_string(format!(r#"{{"files":[{}]}}"#, FILE)))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let files = with_client(config(&server).with_api_key("secret".to_string()), |client| client.get_user_files())
+        .await
+        .unwrap();
+    assert_eq!(files.files.len(), 1);
+    assert_eq!(files.files[0].name, "notes.txt");
+}
+
+#[tokio::test]
+async fn anonymous_requests_have_no_authorization_header() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/file/abc123/info"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(FILE))
+        .mount(&server)
+        .await;
+
+    with_client(config(&server), |client| client.get_file_info("abc123")).await.unwrap();
+    let requests = server.received_requests().await.unwrap();
+    assert!(requests.iter().all(|request: &Request| !request.headers.contains_key("authorization")));
+}
+
+#[tokio::test]
+async fn error_payloads_become_api_errors() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/file/missing/info"))
+        .respond_with(
+            ResponseTemplate::new(404)
+                .insert_header("x-request-id", "req-42")
+                .set_body_string(r#"{"success":false,"value":"not_found","message":"The entity you requested could not be found"}"#),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/file/broken/info"))
+        .respond_with(ResponseTemplate::new(502).set_body_string("Bad Gateway"))
+        .mount(&server)
+        .await;
+
+    let error = with_client(config(&server), |client| client.get_file_info("missing")).await.unwrap_err();
+    let api_error = error.api_error().expect("an API error");
+    assert_eq!(api_error.status.as_u16(), 404);
+    assert_eq!(api_error.value, "not_found");
+    assert_eq!(api_error.message, "The entity you requested could not be found");
+    assert_eq!(api_error.endpoint.as_deref(), Some("/api/file/missing/info"));
+    assert_eq!(api_error.request_id.as_deref(), Some("req-42"));
+    assert!(!error.is_retryable());
+
+    // Not JSON, the body becomes the message
+    let error = with_client(config(&server), |client| client.get_file_info("broken")).await.unwrap_err();
+    assert_eq!(error.api_error().map(|e| e.message.as_str()), Some("Bad Gateway"));
+    assert!(error.is_retryable());
+}
+
+#[tokio::test]
+async fn refused_keys_are_reported_as_unauthorized() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/user/files"))
+        .and(header_exists("authorization"))
+        .respond_with(ResponseTemplate::new(401).set_body_string(r#"{"success":false,"value":"authentication_failed","message":"Key not valid"}"#))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("DELETE"))
+        .and(path("/api/file/someone-elses"))
+        .respond_with(ResponseTemplate::new(403).set_body_string(r#"{"success":false,"value":"forbidden","message":"You are not the owner of this file"}"#))
+        .mount(&server)
+        .await;
+
+    let error = with_client(config(&server).with_api_key("expired".to_string()), |client| client.get_user_files())
+        .await
+        .unwrap_err();
+    assert!(error.is_unauthorized());
+
+    // Not allowed for this one file, the key itself is fine
+    let error = with_client(config(&server).with_api_key("valid".to_string()), |client| client.delete_file("someone-elses"))
+        .await
+        .unwrap_err();
+    assert!(!error.is_unauthorized());
+}
+
+#[tokio::test]
+async fn downloads_retry_server_errors() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/file/abc123"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(2)
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/file/abc123"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("hello"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let target = std::env::temp_dir().join(format!("pixeldrain-api-retry-{}", std::process::id()));
+    let save_path = target.clone();
+    let (sender, events) = mpsc::channel();
+    with_client(config(&server), move |client| client.download_file("abc123", &save_path, Some(sender))).await.unwrap();
+    assert_eq!(std::fs::read_to_string(&target).unwrap(), "hello");
+    let _ = std::fs::remove_file(&target);
+
+    let events: Vec<TransferEvent> = events.try_iter().collect();
+    assert_eq!(events.first(), Some(&TransferEvent::Started { total: None }));
+    let retries: Vec<usize> = events.iter().filter_map(|event| match event {
+        TransferEvent::Retrying { attempt, max_attempts: 5, .. } => Some(*attempt),
+        _ => None,
+    }).collect();
+    assert_eq!(retries, [1, 2]);
+    assert!(events.contains(&TransferEvent::Progress(Progress { fraction: 1.0, ..Default::default() })));
+    assert_eq!(events.last(), Some(&TransferEvent::Completed));
+}
+
+#[tokio::test]
+async fn downloads_give_up_on_client_errors() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/file/gone"))
+        .respond_with(ResponseTemplate::new(404).set_body_string("not found"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut out = Vec::new();
+    let (sender, events) = mpsc::channel();
+    let result = with_client(config(&server), move |client| {
+        client.download_file_to_writer("gone", &mut out, Some(sender))
+    })
+    .await;
+    assert!(matches!(result, Err(PixelDrainError::Api(ref error)) if error.status.as_u16() == 404));
+    assert!(matches!(events.try_iter().last(), Some(TransferEvent::Failed { .. })));
+}
+
+#[tokio::test]
+async fn rate_limited_uploads_wait_and_try_again() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/file"))
+        .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "0"))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/file"))
+        .respond_with(ResponseTemplate::new(201).set_body_string(r#"{"success":true,"id":"new123"}"#))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let source = std::env::temp_dir().join(format!("pixeldrain-api-upload-{}", std::process::id()));
+    std::fs::write(&source, "hello").unwrap();
+    let upload_path = source.clone();
+    let uploaded = with_client(config(&server).with_api_key("secret".to_string()), move |client| {
+        client.upload_file(&upload_path, None)
+    })
+    .await
+    .unwrap();
+    assert_eq!(uploaded.id, "new123");
+    let _ = std::fs::remove_file(&source);
+}
+
+#[tokio::test]
+async fn rate_limit_waits_stop_when_the_transfer_is_cancelled() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/file"))
+        .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "300"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let source = std::env::temp_dir().join(format!("pixeldrain-api-cancel-{}", std::process::id()));
+    std::fs::write(&source, "hello").unwrap();
+    let cancel = Arc::new(AtomicBool::new(false));
+    let (events, received) = mpsc::channel();
+    let upload_path = source.clone();
+    let config = config(&server).with_api_key("secret".to_string()).with_cancel(cancel.clone());
+    let upload = tokio::spawn(with_client(config, move |client| client.upload_file(&upload_path, Some(events))));
+
+    // Cancelled once it's waiting
+    let waiting = tokio::task::spawn_blocking(move || received.iter().any(|event| matches!(event, TransferEvent::Retrying { .. })));
+    assert!(waiting.await.unwrap());
+    let cancelled_at = Instant::now();
+    cancel.store(true, Ordering::Relaxed);
+    let result = upload.await.unwrap();
+    assert!(matches!(result, Err(PixelDrainError::Cancelled)));
+    assert!(cancelled_at.elapsed() < Duration::from_secs(5));
+    let _ = std::fs::remove_file(&source);
+}
+
+#[tokio::test]
+async fn resumed_downloads_ask_for_the_missing_bytes() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/file/abc123"))
+        .and(header("range", "bytes=3-"))
+        .respond_with(ResponseTemplate::new(206).insert_header("content-range", "bytes 3-4/5").set_body_string("lo"))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/file/whole"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("hello"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let partial = std::env::temp_dir().join(format!("pixeldrain-api-resume-{}", std::process::id()));
+    std::fs::write(&partial, "hel").unwrap();
+    let save_path = partial.clone();
+    let (sender, events) = mpsc::channel();
+    with_client(config(&server), move |client| client.resume_download_file("abc123", &save_path, Some(sender))).await.unwrap();
+    assert_eq!(std::fs::read_to_string(&partial).unwrap(), "hello");
+    let progress: Vec<Progress> = events.try_iter().filter_map(|event| match event {
+        TransferEvent::Progress(progress) if progress.bytes > 0 => Some(progress),
+        _ => None,
+    }).collect();
+    assert_eq!(progress.first().map(|progress| (progress.bytes, progress.total)), Some((5, Some(5))));
+
+    // A server that ignores the range sends everything, which replaces the partial file
+    std::fs::write(&partial, "xyz").unwrap();
+    let save_path = partial.clone();
+    with_client(config(&server), move |client| client.resume_download_file("whole", &save_path, None)).await.unwrap();
+    assert_eq!(std::fs::read_to_string(&partial).unwrap(), "hello");
+    let _ = std::fs::remove_file(&partial);
+}
+
+#[tokio::test]
+async fn anonymous_uploads_are_sent_without_authorization() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/file"))
+        .respond_with(ResponseTemplate::new(201).set_body_string(r#"{"success":true,"id":"anon1"}"#))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path("/api/file/renamed.txt"))
+        .respond_with(ResponseTemplate::new(201).set_body_string(r#"{"success":true,"id":"anon2"}"#))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let source = std::env::temp_dir().join(format!("pixeldrain-api-anonymous-{}", std::process::id()));
+    std::fs::write(&source, "hello").unwrap();
+    let upload_path = source.clone();
+    let ids = with_client(config(&server), move |client| {
+        [
+            client.upload_file(&upload_path, None).unwrap().id,
+            client.upload_file_put(&upload_path, "renamed.txt", None).unwrap().id,
+            client.upload_stream_put(std::io::Cursor::new(b"hello".to_vec()), "renamed.txt", None).unwrap().id,
+        ]
+    })
+    .await;
+    assert_eq!(ids, ["anon1", "anon2", "anon2"]);
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 3);
+    assert!(requests.iter().all(|request: &Request| !request.headers.contains_key("authorization")));
+    let _ = std::fs::remove_file(&source);
+}
@@ -140,6 +140,11 @@ Section "Install"
     ; Create desktop shortcut
     CreateShortCut "`$DESKTOP\PixelDrain.lnk" "`$INSTDIR\$AppName.exe"
     
+    ; Handle pixeldrain:// deep links
+    WriteRegStr HKLM "Software\Classes\pixeldrain" "" "URL:PixelDrain Protocol"
+    WriteRegStr HKLM "Software\Classes\pixeldrain" "URL Protocol" ""
+    WriteRegStr HKLM "Software\Classes\pixeldrain\shell\open\command" "" '"`$INSTDIR\$AppName.exe" "%1"'
+    
     ; Registry information for add/remove programs
     WriteRegStr HKLM "Software\Microsoft\Windows\CurrentVersion\Uninstall\PixelDrain" "DisplayName" "PixelDrain"
     WriteRegStr HKLM "Software\Microsoft\Windows\CurrentVersion\Uninstall\PixelDrain" "UninstallString" "`$INSTDIR\Uninstall.exe"
@@ -163,6 +168,7 @@ Section "Uninstall"
     Delete "`$DESKTOP\PixelDrain.lnk"
     
     ; Remove registry keys
+    DeleteRegKey HKLM "Software\Classes\pixeldrain"
     DeleteRegKey HKLM "Software\Microsoft\Windows\CurrentVersion\Uninstall\PixelDrain"
     
     ; Remove install directory
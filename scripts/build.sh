@@ -237,6 +237,11 @@ Section "Install"
     ; Create desktop shortcut
     CreateShortCut "\$DESKTOP\\PixelDrain.lnk" "\$INSTDIR\\$APP_NAME.exe" "" "\$INSTDIR\\icon.ico"
     
+    ; Handle pixeldrain:// deep links
+    WriteRegStr HKLM "Software\\Classes\\pixeldrain" "" "URL:PixelDrain Protocol"
+    WriteRegStr HKLM "Software\\Classes\\pixeldrain" "URL Protocol" ""
+    WriteRegStr HKLM "Software\\Classes\\pixeldrain\\shell\\open\\command" "" '"\$INSTDIR\\$APP_NAME.exe" "%1"'
+    
     ; Registry information for add/remove programs
     WriteRegStr HKLM "Software\\Microsoft\\Windows\\CurrentVersion\\Uninstall\\PixelDrain" "DisplayName" "PixelDrain"
     WriteRegStr HKLM "Software\\Microsoft\\Windows\\CurrentVersion\\Uninstall\\PixelDrain" "UninstallString" "\$INSTDIR\\Uninstall.exe"
@@ -261,6 +266,7 @@ Section "Uninstall"
     Delete "\$DESKTOP\\PixelDrain.lnk"
     
     ; Remove registry keys
+    DeleteRegKey HKLM "Software\\Classes\\pixeldrain"
     DeleteRegKey HKLM "Software\\Microsoft\\Windows\\CurrentVersion\\Uninstall\\PixelDrain"
     
     ; Remove install directory
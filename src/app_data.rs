@@ -0,0 +1,155 @@
+// app_data.rs - Everything the app keeps in one ZIP archive, to back it up or
+// move it to another machine: the settings as a settings export (with the API
+// keys only when encrypted under a passphrase), the whole upload and download
+// history, and the cached file lists. A manifest lists the SHA-256 of each part
+// so a damaged archive is refused before anything is restored.
+use std::collections::BTreeMap;
+use std::io::{Read, Seek, Write};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::history::{DownloadHistoryEntry, UploadHistoryEntry};
+
+const FORMAT: &str = "pixeldrain-app-data";
+const VERSION: u32 = 1;
+
+const MANIFEST: &str = "manifest.json";
+const SETTINGS: &str = "settings.json";
+const HISTORY: &str = "history.json";
+const CACHE: &str = "cache.json";
+
+#[derive(Serialize, Deserialize)]
+struct Manifest {
+    format: String,
+    version: u32,
+    created: DateTime<Utc>,
+    app_version: String,
+    // SHA-256 of each part by name
+    parts: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct History {
+    /// Oldest first, the order they're imported in
+    pub uploads: Vec<UploadHistoryEntry>,
+    pub downloads: Vec<DownloadHistoryEntry>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct AppData {
+    /// A settings export, see settings_export
+    pub settings: String,
+    pub history: History,
+    /// Cached data of the storage by key
+    pub cache: BTreeMap<String, String>,
+}
+
+/// Write `data` as an archive to `out`
+pub fn write(out: impl Write + Seek, data: &AppData) -> Result<(), String> {
+    let parts = [
+        (SETTINGS, data.settings.clone().into_bytes()),
+        (HISTORY, serde_json::to_vec_pretty(&data.history).map_err(|e| e.to_string())?),
+        (CACHE, serde_json::to_vec_pretty(&data.cache).map_err(|e| e.to_string())?),
+    ];
+    let manifest = Manifest {
+        format: FORMAT.to_string(),
+        version: VERSION,
+        created: Utc::now(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        parts: parts.iter().map(|(name, bytes)| (name.to_string(), checksum(bytes))).collect(),
+    };
+    let manifest = serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?;
+
+    let mut zip = zip::ZipWriter::new(out);
+    let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for (name, bytes) in [(MANIFEST, manifest.as_slice())].into_iter().chain(parts.iter().map(|(name, bytes)| (*name, bytes.as_slice()))) {
+        zip.start_file(name, options).map_err(|e| e.to_string())?;
+        zip.write_all(bytes).map_err(|e| e.to_string())?;
+    }
+    zip.finish().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Read an archive written by `write`, checking every part against the manifest
+pub fn read(input: impl Read + Seek) -> Result<AppData, String> {
+    let mut zip = zip::ZipArchive::new(input).map_err(|e| format!("Not an app data archive: {}", e))?;
+    let mut part = |name: &str| -> Result<Vec<u8>, String> {
+        let mut file = zip.by_name(name).map_err(|_| format!("The archive has no {}", name))?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes).map_err(|e| e.to_string())?;
+        Ok(bytes)
+    };
+    let manifest: Manifest = serde_json::from_slice(&part(MANIFEST)?).map_err(|e| format!("Not an app data archive: {}", e))?;
+    if manifest.format != FORMAT {
+        return Err("Not a PixelDrain app data archive".to_string());
+    }
+    if manifest.version > VERSION {
+        return Err(format!("App data archive version {} is newer than this app supports", manifest.version));
+    }
+    let mut checked = |name: &str| -> Result<Vec<u8>, String> {
+        let bytes = part(name)?;
+        match manifest.parts.get(name) {
+            Some(expected) if *expected == checksum(&bytes) => Ok(bytes),
+            _ => Err(format!("Checksum mismatch in {}, the archive was modified or damaged", name)),
+        }
+    };
+    Ok(AppData {
+        settings: String::from_utf8(checked(SETTINGS)?).map_err(|e| e.to_string())?,
+        history: serde_json::from_slice(&checked(HISTORY)?).map_err(|e| format!("Damaged history: {}", e))?,
+        cache: serde_json::from_slice(&checked(CACHE)?).map_err(|e| format!("Damaged cache: {}", e))?,
+    })
+}
+
+fn checksum(data: &[u8]) -> String {
+    Sha256::digest(data).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn archives_round_trip_and_damage_is_refused() {
+        let data = AppData {
+            settings: "{\"format\": \"pixeldrain-settings\"}".to_string(),
+            history: History {
+                uploads: vec![UploadHistoryEntry {
+                    id: "abc".to_string(),
+                    url: "https://pixeldrain.com/u/abc".to_string(),
+                    filename: "a.txt".to_string(),
+                    size: 3,
+                    timestamp: Utc::now(),
+                    verified: Some(true),
+                    error: None,
+                    anonymous: false,
+                }],
+                downloads: Vec::new(),
+            },
+            cache: BTreeMap::from([("file_list".to_string(), "[]".to_string())]),
+        };
+        let mut archive = Cursor::new(Vec::new());
+        write(&mut archive, &data).unwrap();
+        assert_eq!(read(Cursor::new(archive.get_ref().clone())).unwrap(), data);
+
+        // The history swapped for another one that doesn't match the manifest
+        let mut zip = zip::ZipArchive::new(Cursor::new(archive.into_inner())).unwrap();
+        let mut tampered = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for index in 0..zip.len() {
+            let mut file = zip.by_index(index).unwrap();
+            let name = file.name().to_string();
+            let mut bytes = Vec::new();
+            file.read_to_end(&mut bytes).unwrap();
+            if name == HISTORY {
+                bytes = serde_json::to_vec(&History::default()).unwrap();
+            }
+            tampered.start_file(name, zip::write::SimpleFileOptions::default()).unwrap();
+            tampered.write_all(&bytes).unwrap();
+        }
+        let tampered = tampered.finish().unwrap().into_inner();
+        assert!(read(Cursor::new(tampered)).unwrap_err().starts_with("Checksum mismatch in history.json"));
+        assert!(read(Cursor::new(b"not a zip".to_vec())).is_err());
+    }
+}
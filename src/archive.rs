@@ -0,0 +1,417 @@
+// archive.rs - Directory archives streamed straight into an upload
+// The archive is built in-process on a background thread and handed to the
+// upload through a bounded buffer of chunks, so compressing and uploading
+// overlap without anything being written to disk and no tar program is needed.
+// On Windows the directory is opened as an extended-length path so trees
+// deeper than MAX_PATH (260 characters) can be archived.
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::thread::{self, JoinHandle};
+
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArchiveFormat {
+    #[default]
+    TarGz,
+    /// Opens without extra tools on Windows
+    Zip,
+    /// Compresses faster and smaller than gzip, needs a tar with zstd support
+    TarZst,
+}
+
+impl ArchiveFormat {
+    pub const ALL: [ArchiveFormat; 3] = [ArchiveFormat::TarGz, ArchiveFormat::Zip, ArchiveFormat::TarZst];
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ArchiveFormat::TarGz => "tar.gz",
+            ArchiveFormat::Zip => "zip",
+            ArchiveFormat::TarZst => "tar.zst",
+        }
+    }
+}
+
+/// Called with the bytes of file data archived so far and the total
+pub type ArchiveProgress = Box<dyn FnMut(u64, u64) + Send>;
+
+// Compressed data is handed over in chunks of this size, with up to
+// BUFFERED_CHUNKS waiting, so compression can run ahead of a slow upload by
+// 16 MiB and a fast upload rarely waits for a chunk
+const CHUNK_SIZE: usize = 1024 * 1024;
+const BUFFERED_CHUNKS: usize = 16;
+
+/// Compression threads for `format`, 0 compresses on the archiving thread.
+/// Only zstd can compress on several threads.
+pub fn compression_threads(format: ArchiveFormat, parallel: bool) -> u32 {
+    match format {
+        ArchiveFormat::TarZst if parallel => thread::available_parallelism().map_or(1, |n| n.get() as u32),
+        _ => 0,
+    }
+}
+
+/// Archive being written on a background thread, read from the pipe while it runs
+pub struct ArchiveWriter {
+    handle: JoinHandle<io::Result<u64>>,
+}
+
+/// Start archiving `entries` in `format`. They are relative to the parent of
+/// `dir`, as returned by `preflight::scan_directory`, so the archive extracts
+/// into a folder of the same name. `threads` is passed to
+/// `compression_threads`.
+pub fn spawn_archive(
+    dir: &Path,
+    entries: Vec<PathBuf>,
+    format: ArchiveFormat,
+    threads: u32,
+    progress: Option<ArchiveProgress>,
+) -> io::Result<(ArchiveReader, ArchiveWriter)> {
+    if dir.file_name().is_none() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("{} is not a directory", dir.display())));
+    }
+    let parent = dir.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let parent = long_path(parent)?;
+    let (sender, receiver) = mpsc::sync_channel(BUFFERED_CHUNKS);
+    let handle = thread::spawn(move || {
+        let writer = ChunkWriter { sender: sender.clone(), chunk: Vec::with_capacity(CHUNK_SIZE) };
+        let result = write_archive(&parent, &entries, format, threads, writer, progress)
+            .map_err(|e| io::Error::new(e.kind(), format!("Creating the archive failed: {}", e)));
+        // The upload fails too instead of sending what was archived so far
+        if let Err(e) = &result {
+            let _ = sender.send(Err(io::Error::new(e.kind(), e.to_string())));
+        }
+        result
+    });
+    Ok((ArchiveReader { receiver, chunk: Vec::new(), position: 0 }, ArchiveWriter { handle }))
+}
+
+/// Archive data as it's compressed. Reading fails when archiving does.
+pub struct ArchiveReader {
+    receiver: Receiver<io::Result<Vec<u8>>>,
+    chunk: Vec<u8>,
+    position: usize,
+}
+
+impl Read for ArchiveReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.position == self.chunk.len() {
+            match self.receiver.recv() {
+                Ok(chunk) => {
+                    self.chunk = chunk?;
+                    self.position = 0;
+                }
+                // The archive is complete
+                Err(_) => return Ok(0),
+            }
+        }
+        let read = buf.len().min(self.chunk.len() - self.position);
+        buf[..read].copy_from_slice(&self.chunk[self.position..self.position + read]);
+        self.position += read;
+        Ok(read)
+    }
+}
+
+/// Collects compressed data into chunks for the reader, blocking while the
+/// buffer is full
+struct ChunkWriter {
+    sender: SyncSender<io::Result<Vec<u8>>>,
+    chunk: Vec<u8>,
+}
+
+impl ChunkWriter {
+    fn send(&mut self) -> io::Result<()> {
+        let chunk = std::mem::replace(&mut self.chunk, Vec::with_capacity(CHUNK_SIZE));
+        self.sender
+            .send(Ok(chunk))
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "the upload stopped reading the archive"))
+    }
+}
+
+impl Write for ChunkWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = buf.len().min(CHUNK_SIZE - self.chunk.len());
+        self.chunk.extend_from_slice(&buf[..written]);
+        if self.chunk.len() == CHUNK_SIZE {
+            self.send()?;
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.chunk.is_empty() { Ok(()) } else { self.send() }
+    }
+}
+
+impl ArchiveWriter {
+    /// Wait for the archive to be written, returns its size. An error means the
+    /// archive that was read is incomplete.
+    pub fn finish(self) -> io::Result<u64> {
+        self.handle.join().unwrap_or_else(|_| Err(io::Error::other("Creating the archive failed: the archiving thread panicked")))
+    }
+}
+
+fn write_archive(
+    parent: &Path,
+    entries: &[PathBuf],
+    format: ArchiveFormat,
+    threads: u32,
+    out: impl Write,
+    progress: Option<ArchiveProgress>,
+) -> io::Result<u64> {
+    // Sizes are taken up front so progress has a total to count towards
+    let total = entries
+        .iter()
+        .filter_map(|entry| fs::symlink_metadata(parent.join(entry)).ok())
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .sum();
+    let mut tracker = Tracker { archived: 0, total, progress };
+    let out = Counting { inner: out, count: 0 };
+    let mut counting = match format {
+        ArchiveFormat::TarGz => {
+            let mut builder = tar::Builder::new(GzEncoder::new(out, Compression::default()));
+            append_tar(&mut builder, parent, entries, &mut tracker)?;
+            builder.into_inner()?.finish()?
+        }
+        ArchiveFormat::TarZst => {
+            let mut encoder = zstd::Encoder::new(out, 0)?;
+            if threads > 0 {
+                encoder.multithread(threads)?;
+            }
+            let mut builder = tar::Builder::new(encoder);
+            append_tar(&mut builder, parent, entries, &mut tracker)?;
+            builder.into_inner()?.finish()?
+        }
+        ArchiveFormat::Zip => write_zip(parent, entries, out, &mut tracker)?,
+    };
+    counting.flush()?;
+    Ok(counting.count)
+}
+
+fn append_tar<W: Write>(builder: &mut tar::Builder<W>, parent: &Path, entries: &[PathBuf], tracker: &mut Tracker) -> io::Result<()> {
+    // Symbolic links are archived as links, not as what they point to
+    builder.follow_symlinks(false);
+    for entry in entries {
+        let path = parent.join(entry);
+        let metadata = entry_metadata(&path, entry)?;
+        if metadata.is_file() {
+            let mut header = tar::Header::new_gnu();
+            header.set_metadata(&metadata);
+            builder.append_data(&mut header, entry, tracker.reader(open_entry(&path, entry)?))?;
+        } else {
+            builder.append_path_with_name(&path, entry)?;
+        }
+    }
+    Ok(())
+}
+
+/// Zip written without seeking back, sizes go in data descriptors after each file
+fn write_zip<W: Write>(parent: &Path, entries: &[PathBuf], out: W, tracker: &mut Tracker) -> io::Result<W> {
+    let mut zip = zip::ZipWriter::new_stream(out);
+    for entry in entries {
+        let path = parent.join(entry);
+        let metadata = entry_metadata(&path, entry)?;
+        let name = zip_name(entry);
+        let mut options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated)
+            .large_file(metadata.len() >= u32::MAX as u64);
+        if let Some(time) = metadata.modified().ok().and_then(zip_time) {
+            options = options.last_modified_time(time);
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            options = options.unix_permissions(metadata.permissions().mode());
+        }
+        if metadata.is_dir() {
+            zip.add_directory(name, options)?;
+        } else if metadata.is_symlink() {
+            let target = fs::read_link(&path)?;
+            zip.add_symlink(name, zip_name(&target), options)?;
+        } else {
+            zip.start_file(name, options)?;
+            io::copy(&mut tracker.reader(open_entry(&path, entry)?), &mut zip)?;
+        }
+    }
+    Ok(zip.finish()?.into_inner())
+}
+
+/// Zip entry names always use forward slashes
+fn zip_name(path: &Path) -> String {
+    path.components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part.to_string_lossy()),
+            Component::ParentDir => Some("..".into()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Zip timestamps are local time without a time zone, from 1980 on
+fn zip_time(time: std::time::SystemTime) -> Option<zip::DateTime> {
+    use chrono::{Datelike, Timelike};
+    let local = chrono::DateTime::<chrono::Local>::from(time);
+    zip::DateTime::from_date_and_time(
+        u16::try_from(local.year()).ok()?,
+        local.month() as u8,
+        local.day() as u8,
+        local.hour() as u8,
+        local.minute() as u8,
+        local.second() as u8,
+    )
+    .ok()
+}
+
+fn entry_metadata(path: &Path, entry: &Path) -> io::Result<fs::Metadata> {
+    fs::symlink_metadata(path).map_err(|e| io::Error::new(e.kind(), format!("{}: {}", entry.display(), e)))
+}
+
+fn open_entry(path: &Path, entry: &Path) -> io::Result<File> {
+    File::open(path).map_err(|e| io::Error::new(e.kind(), format!("{}: {}", entry.display(), e)))
+}
+
+/// Counts the compressed bytes written
+struct Counting<W> {
+    inner: W,
+    count: u64,
+}
+
+impl<W: Write> Write for Counting<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.count += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// File data archived so far, reported as it's read into the archive
+struct Tracker {
+    archived: u64,
+    total: u64,
+    progress: Option<ArchiveProgress>,
+}
+
+impl Tracker {
+    fn reader<R: Read>(&mut self, inner: R) -> Progress<'_, R> {
+        Progress { inner, tracker: self }
+    }
+}
+
+struct Progress<'a, R> {
+    inner: R,
+    tracker: &'a mut Tracker,
+}
+
+impl<R: Read> Read for Progress<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        let tracker = &mut *self.tracker;
+        tracker.archived += read as u64;
+        if let Some(progress) = tracker.progress.as_mut() {
+            progress(tracker.archived, tracker.total);
+        }
+        Ok(read)
+    }
+}
+
+/// `path` in a form that isn't limited to MAX_PATH. canonicalize returns a
+/// \\?\ path on Windows, elsewhere the path is used as it is.
+fn long_path(path: &Path) -> io::Result<PathBuf> {
+    if cfg!(windows) {
+        fs::canonicalize(path)
+    } else {
+        Ok(path.to_path_buf())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Archive of `entries` under `dir` and the last progress reported
+    fn archive(dir: &Path, entries: &[&str], format: ArchiveFormat, threads: u32) -> (Vec<u8>, Option<(u64, u64)>) {
+        let reported = Arc::new(Mutex::new(None));
+        let progress: ArchiveProgress = {
+            let reported = reported.clone();
+            Box::new(move |archived, total| *reported.lock().unwrap() = Some((archived, total)))
+        };
+        let entries = entries.iter().map(PathBuf::from).collect();
+        let (mut reader, writer) = spawn_archive(&dir.join("photos"), entries, format, threads, Some(progress)).unwrap();
+        let mut archive = Vec::new();
+        reader.read_to_end(&mut archive).unwrap();
+        assert_eq!(writer.finish().unwrap(), archive.len() as u64);
+        let last = *reported.lock().unwrap();
+        (archive, last)
+    }
+
+    fn tar_names(archive: impl Read) -> Vec<String> {
+        let mut tar = tar::Archive::new(archive);
+        tar.entries().unwrap().map(|e| e.unwrap().path().unwrap().display().to_string()).collect()
+    }
+
+    #[test]
+    fn streams_archives_with_progress_and_reports_failures() {
+        let dir = std::env::temp_dir().join(format!("pixeldrain-archive-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("photos").join("empty")).unwrap();
+        std::fs::write(dir.join("photos").join("a.txt"), b"hello").unwrap();
+        std::fs::write(dir.join("photos").join("b.txt"), b"world!").unwrap();
+        let entries = ["photos/a.txt", "photos/b.txt", "photos/empty"];
+
+        let (gzip, progress) = archive(&dir, &entries, ArchiveFormat::TarGz, 0);
+        assert_eq!(&gzip[..2], [0x1f, 0x8b], "gzip magic");
+        assert_eq!(progress, Some((11, 11)));
+        assert_eq!(tar_names(flate2::read::GzDecoder::new(&gzip[..])), entries);
+
+        let (zstd, _) = archive(&dir, &entries, ArchiveFormat::TarZst, 0);
+        assert_eq!(tar_names(zstd::Decoder::new(&zstd[..]).unwrap()), entries);
+        let (zstd, _) = archive(&dir, &entries, ArchiveFormat::TarZst, 4);
+        assert_eq!(tar_names(zstd::Decoder::new(&zstd[..]).unwrap()), entries);
+
+        let (zip, progress) = archive(&dir, &entries, ArchiveFormat::Zip, 0);
+        assert_eq!(progress, Some((11, 11)));
+        let mut zip = zip::ZipArchive::new(io::Cursor::new(zip)).unwrap();
+        let names: Vec<&str> = zip.file_names().collect();
+        assert_eq!(names, ["photos/a.txt", "photos/b.txt", "photos/empty/"]);
+        let mut text = String::new();
+        zip.by_name("photos/b.txt").unwrap().read_to_string(&mut text).unwrap();
+        assert_eq!(text, "world!");
+
+        // A file that disappears fails the archive and the read instead of ending it early
+        let (mut reader, writer) = spawn_archive(&dir.join("photos"), vec![PathBuf::from("photos/missing.txt")], ArchiveFormat::TarGz, 0, None).unwrap();
+        assert!(reader.read_to_end(&mut Vec::new()).is_err());
+        assert!(writer.finish().unwrap_err().to_string().starts_with("Creating the archive failed"));
+
+        // More than fits in the buffer, so compressing has to wait for reading
+        let mut seed: u64 = 0x9e37_79b9_7f4a_7c15;
+        let big: Vec<u8> = (0..CHUNK_SIZE * (BUFFERED_CHUNKS + 2))
+            .map(|_| {
+                // Random enough not to compress
+                seed ^= seed << 13;
+                seed ^= seed >> 7;
+                seed ^= seed << 17;
+                seed as u8
+            })
+            .collect();
+        std::fs::write(dir.join("photos").join("big.bin"), &big).unwrap();
+        let (mut reader, writer) = spawn_archive(&dir.join("photos"), vec![PathBuf::from("photos/big.bin")], ArchiveFormat::TarZst, 2, None).unwrap();
+        let mut tar = tar::Archive::new(zstd::Decoder::new(&mut reader).unwrap());
+        let mut unpacked = Vec::new();
+        tar.entries().unwrap().next().unwrap().unwrap().read_to_end(&mut unpacked).unwrap();
+        assert!(unpacked == big);
+        drop(tar);
+        let _ = io::copy(&mut reader, &mut io::sink());
+        writer.finish().unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
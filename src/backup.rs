@@ -0,0 +1,101 @@
+// backup.rs - Local backups of the whole account: every file downloaded into a
+// files folder, and a manifest.json next to it with the metadata of each file,
+// where it was saved, and the lists of the account with their files in order.
+// Backing up into the same folder again only downloads what isn't there yet.
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use pixeldrain_api::{DetailedListInfo, FileInfo, ListFile};
+use serde::{Deserialize, Serialize};
+
+pub const MANIFEST_NAME: &str = "manifest.json";
+pub const FILES_DIR: &str = "files";
+
+/// How a backup went, the manifest has the reason for each failed file
+#[derive(Debug, PartialEq)]
+pub struct Summary {
+    pub folder: PathBuf,
+    pub saved: usize,
+    pub failed: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Manifest {
+    pub created: DateTime<Utc>,
+    pub base_url: String,
+    pub files: Vec<BackedUpFile>,
+    pub lists: Vec<BackedUpList>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackedUpFile {
+    #[serde(flatten)]
+    pub info: FileInfo,
+    /// Where the file was saved, relative to the backup folder
+    pub path: String,
+    /// Why it couldn't be downloaded, the path is where it would have gone
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackedUpList {
+    pub id: String,
+    pub title: String,
+    pub date_created: DateTime<Utc>,
+    /// Files of the list in order, by ID with their descriptions
+    pub files: Vec<ListFile>,
+}
+
+impl BackedUpList {
+    pub fn of(list: &DetailedListInfo) -> Self {
+        BackedUpList {
+            id: list.id.clone(),
+            title: list.title.clone(),
+            date_created: list.date_created,
+            files: list
+                .files
+                .iter()
+                .map(|file| ListFile { id: file.file_info.id.clone(), description: file.description.clone() })
+                .collect(),
+        }
+    }
+}
+
+/// Name each file is saved under in the files folder: its own name, with its
+/// ID added when other files have that name too
+pub fn local_names(files: &[FileInfo]) -> Vec<String> {
+    let names: Vec<String> = files.iter().map(|file| crate::sanitize_file_name(&file.name)).collect();
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for name in &names {
+        // Case-insensitive file systems would put them on top of each other too
+        *counts.entry(name.to_lowercase()).or_default() += 1;
+    }
+    names
+        .into_iter()
+        .zip(files)
+        .map(|(name, file)| {
+            if counts[&name.to_lowercase()] == 1 {
+                return name;
+            }
+            let path = Path::new(&name);
+            match (path.file_stem(), path.extension()) {
+                (Some(stem), Some(extension)) => format!("{} ({}).{}", stem.to_string_lossy(), file.id, extension.to_string_lossy()),
+                _ => format!("{} ({})", name, file.id),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn files_with_the_same_name_get_their_id_added() {
+        let file = |id: &str, name: &str| FileInfo { id: id.to_string(), name: name.to_string(), ..Default::default() };
+        let files = [file("a1", "photo.jpg"), file("b2", "Photo.JPG"), file("c3", "notes"), file("d4", "notes"), file("e5", "cv.pdf")];
+        assert_eq!(local_names(&files), ["photo (a1).jpg", "Photo (b2).JPG", "notes (c3)", "notes (d4)", "cv.pdf"]);
+    }
+}
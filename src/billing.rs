@@ -0,0 +1,98 @@
+// billing.rs - The account's transactions summed up per month, so Pro users can
+// see what storage and bandwidth cost them. Amounts are in micro euros, like the
+// API reports them.
+use chrono::Datelike;
+use pixeldrain_api::UserTransaction;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MonthSummary {
+    pub year: i32,
+    pub month: u32,
+    pub deposits: i64,
+    pub subscription: i64,
+    pub storage: i64,
+    pub bandwidth: i64,
+    pub affiliate: i64,
+    // Balance after the last transaction of the month
+    pub balance: i64,
+}
+
+impl MonthSummary {
+    pub fn charges(&self) -> i64 {
+        self.subscription + self.storage + self.bandwidth
+    }
+
+    pub fn label(&self) -> String {
+        format!("{}-{:02}", self.year, self.month)
+    }
+}
+
+/// `transactions` summed up per month, oldest month first
+pub fn monthly(transactions: &[UserTransaction]) -> Vec<MonthSummary> {
+    let mut sorted: Vec<&UserTransaction> = transactions.iter().collect();
+    sorted.sort_by_key(|transaction| transaction.time);
+    let mut months: Vec<MonthSummary> = Vec::new();
+    for transaction in sorted {
+        let (year, month) = (transaction.time.year(), transaction.time.month());
+        if months.last().is_none_or(|last| (last.year, last.month) != (year, month)) {
+            months.push(MonthSummary { year, month, ..Default::default() });
+        }
+        if let Some(summary) = months.last_mut() {
+            summary.deposits += transaction.deposit_amount;
+            summary.subscription += transaction.subscription_charge;
+            summary.storage += transaction.storage_charge;
+            summary.bandwidth += transaction.bandwidth_charge;
+            summary.affiliate += transaction.affiliate_amount;
+            summary.balance = transaction.new_balance;
+        }
+    }
+    months
+}
+
+/// `micro_eur` as euros, like "€1.25" or "-€0.50"
+pub fn format_eur(micro_eur: i64) -> String {
+    let sign = if micro_eur < 0 { "-" } else { "" };
+    format!("{}€{:.2}", sign, micro_eur.unsigned_abs() as f64 / 1_000_000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn transaction(month: u32, day: u32, new_balance: i64, storage_charge: i64, deposit_amount: i64) -> UserTransaction {
+        UserTransaction {
+            time: Utc.with_ymd_and_hms(2024, month, day, 12, 0, 0).unwrap(),
+            new_balance,
+            deposit_amount,
+            subscription_charge: 0,
+            storage_charge,
+            storage_used: 0,
+            bandwidth_charge: 1_000,
+            bandwidth_used: 0,
+            affiliate_amount: 0,
+            affiliate_count: 0,
+        }
+    }
+
+    #[test]
+    fn transactions_are_summed_per_month_in_order() {
+        // Out of order, like nothing guarantees otherwise
+        let months = monthly(&[
+            transaction(2, 1, 4_000_000, 500_000, 0),
+            transaction(1, 5, 5_500_000, 0, 6_000_000),
+            transaction(1, 20, 5_000_000, 500_000, 0),
+        ]);
+        assert_eq!(months.len(), 2);
+        assert_eq!(months[0].label(), "2024-01");
+        assert_eq!(months[0].deposits, 6_000_000);
+        assert_eq!(months[0].charges(), 502_000);
+        assert_eq!(months[0].balance, 5_000_000);
+        assert_eq!(months[1].label(), "2024-02");
+        assert_eq!(months[1].balance, 4_000_000);
+
+        assert_eq!(format_eur(1_250_000), "€1.25");
+        assert_eq!(format_eur(-500_000), "-€0.50");
+        assert!(monthly(&[]).is_empty());
+    }
+}
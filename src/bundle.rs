@@ -0,0 +1,103 @@
+// bundle.rs - Link bundles: files uploaded together into a list, plus a local
+// index page with the link and thumbnail of each file, in HTML to host or open
+// anywhere or in Markdown to paste into a README, wiki or chat.
+use std::path::Path;
+
+use crate::link_format::LinkFormat;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IndexFormat {
+    Html,
+    Markdown,
+}
+
+impl IndexFormat {
+    /// Markdown for .md files, HTML otherwise
+    pub fn of(path: &Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()).map(str::to_lowercase).as_deref() {
+            Some("md" | "markdown") => IndexFormat::Markdown,
+            _ => IndexFormat::Html,
+        }
+    }
+}
+
+/// A file of the bundle, as it was uploaded
+#[derive(Clone, Debug, PartialEq)]
+pub struct BundleFile {
+    pub id: String,
+    pub name: String,
+}
+
+fn thumbnail_url(base_url: &str, id: &str) -> String {
+    format!("{}/api/file/{}/thumbnail?width=128&height=128", base_url, id)
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Index page titled `title` of `files`, linking the list too when there is one
+pub fn index(format: IndexFormat, base_url: &str, title: &str, list_id: Option<&str>, files: &[BundleFile]) -> String {
+    let list_url = list_id.map(|id| format!("{}/l/{}", base_url, id));
+    match format {
+        IndexFormat::Html => {
+            let mut page = format!(
+                "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{0}</title>\n</head>\n<body>\n<h1>{0}</h1>\n",
+                escape_html(title)
+            );
+            if let Some(url) = &list_url {
+                page.push_str(&format!("<p><a href=\"{}\">All files</a></p>\n", url));
+            }
+            page.push_str("<ul>\n");
+            for file in files {
+                page.push_str(&format!(
+                    "<li><img src=\"{}\" alt=\"\" width=\"64\" height=\"64\"> {}</li>\n",
+                    escape_html(&thumbnail_url(base_url, &file.id)),
+                    LinkFormat::Html.format(base_url, &file.id, &file.name)
+                ));
+            }
+            page.push_str("</ul>\n</body>\n</html>\n");
+            page
+        }
+        IndexFormat::Markdown => {
+            let mut page = format!("# {}\n\n", title);
+            if let Some(url) = &list_url {
+                page.push_str(&format!("[All files]({})\n\n", url));
+            }
+            for file in files {
+                page.push_str(&format!(
+                    "- ![]({}) {}\n",
+                    thumbnail_url(base_url, &file.id),
+                    LinkFormat::Markdown.format(base_url, &file.id, &file.name)
+                ));
+            }
+            page
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_pages_link_every_file_and_the_list() {
+        let files = [
+            BundleFile { id: "abc".to_string(), name: "a <b>.jpg".to_string() },
+            BundleFile { id: "def".to_string(), name: "notes.txt".to_string() },
+        ];
+        let html = index(IndexFormat::Html, "https://pixeldrain.com", "Trip", Some("list1"), &files);
+        assert!(html.contains("<title>Trip</title>"));
+        assert!(html.contains("<a href=\"https://pixeldrain.com/l/list1\">"));
+        assert!(html.contains("<a href=\"https://pixeldrain.com/u/abc\">a &lt;b&gt;.jpg</a>"));
+        assert!(html.contains("<img src=\"https://pixeldrain.com/api/file/def/thumbnail?width=128&amp;height=128\""));
+
+        let markdown = index(IndexFormat::Markdown, "https://pixeldrain.com", "Trip", None, &files);
+        assert!(markdown.starts_with("# Trip\n"));
+        assert!(markdown.contains("- ![](https://pixeldrain.com/api/file/abc/thumbnail?width=128&height=128) [a <b>.jpg](https://pixeldrain.com/u/abc)\n"));
+        assert!(!markdown.contains("/l/"));
+
+        assert_eq!(IndexFormat::of(Path::new("bundle.MD")), IndexFormat::Markdown);
+        assert_eq!(IndexFormat::of(Path::new("bundle.html")), IndexFormat::Html);
+    }
+}
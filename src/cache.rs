@@ -0,0 +1,131 @@
+// cache.rs - Files cached on disk, such as thumbnails
+// Everything lives under the app cache folder. Together with the journal of
+// unfinished transfers it's kept under a size limit set in Settings. When the
+// limit is exceeded the least recently used cached files go first, the journal
+// only shrinks by resuming or discarding its transfers. Partial downloads are
+// in the user's download folder, not the app's, so they don't count.
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+pub const DEFAULT_LIMIT: u64 = 200_000_000;
+
+pub fn cache_dir() -> PathBuf {
+    directories::ProjectDirs::from("com", "pixeldrain", "client")
+        .map(|proj_dirs| proj_dirs.cache_dir().to_path_buf())
+        .unwrap_or_else(|| PathBuf::from("cache"))
+}
+
+fn thumbnail_path(dir: &Path, file_id: &str) -> PathBuf {
+    // File IDs are alphanumeric, anything else is dropped to stay inside the folder
+    let name: String = file_id.chars().filter(|c| c.is_ascii_alphanumeric()).collect();
+    dir.join("thumbnails").join(name)
+}
+
+/// Cached thumbnail of `file_id`, marked as used so it is kept longer
+pub fn read_thumbnail(dir: &Path, file_id: &str) -> Option<Vec<u8>> {
+    let path = thumbnail_path(dir, file_id);
+    let bytes = fs::read(&path).ok()?;
+    if let Ok(file) = fs::File::options().write(true).open(&path) {
+        let _ = file.set_modified(SystemTime::now());
+    }
+    Some(bytes)
+}
+
+pub fn store_thumbnail(dir: &Path, file_id: &str, bytes: &[u8]) -> io::Result<()> {
+    let path = thumbnail_path(dir, file_id);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, bytes)
+}
+
+/// Every file in the cache with its size and last use
+fn entries(dir: &Path, found: &mut Vec<(PathBuf, u64, SystemTime)>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if metadata.is_dir() {
+            entries(&entry.path(), found)?;
+        } else {
+            found.push((entry.path(), metadata.len(), metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH)));
+        }
+    }
+    Ok(())
+}
+
+/// Total size of the cache in bytes
+pub fn usage(dir: &Path) -> u64 {
+    let mut found = Vec::new();
+    let _ = entries(dir, &mut found);
+    found.iter().map(|(_, size, _)| size).sum()
+}
+
+/// Delete the least recently used files until the cache and the `reserved`
+/// bytes kept elsewhere fit in `limit` bytes. Returns the number of bytes freed.
+pub fn enforce_limit(dir: &Path, limit: u64, reserved: u64) -> io::Result<u64> {
+    let mut found = Vec::new();
+    match entries(dir, &mut found) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e),
+    }
+    let mut total: u64 = found.iter().map(|(_, size, _)| size).sum::<u64>() + reserved;
+    let mut freed = 0;
+    found.sort_by_key(|(_, _, used)| *used);
+    for (path, size, _) in found {
+        if total <= limit {
+            break;
+        }
+        // Another worker may have removed it already
+        match fs::remove_file(&path) {
+            Ok(()) => freed += size,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        total -= size;
+    }
+    Ok(freed)
+}
+
+/// Remove everything in the cache
+pub fn clear(dir: &Path) -> io::Result<()> {
+    match fs::remove_dir_all(dir) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn least_recently_used_files_go_first() {
+        let dir = std::env::temp_dir().join(format!("pixeldrain-cache-{}", uuid::Uuid::new_v4()));
+        let start = SystemTime::now() - Duration::from_secs(60);
+        for (index, id) in ["old", "middle", "new"].iter().enumerate() {
+            store_thumbnail(&dir, id, &[0u8; 100]).unwrap();
+            let file = fs::File::options().write(true).open(thumbnail_path(&dir, id)).unwrap();
+            file.set_modified(start + Duration::from_secs(index as u64 * 10)).unwrap();
+        }
+        // Reading a thumbnail counts as using it
+        assert!(read_thumbnail(&dir, "old").is_some());
+        assert_eq!(usage(&dir), 300);
+
+        assert_eq!(enforce_limit(&dir, 250, 0).unwrap(), 100);
+        assert!(read_thumbnail(&dir, "middle").is_none());
+        assert!(read_thumbnail(&dir, "old").is_some());
+        assert_eq!(enforce_limit(&dir, 250, 0).unwrap(), 0);
+        // Resume data takes up the room of one more
+        assert_eq!(enforce_limit(&dir, 250, 100).unwrap(), 100);
+        assert!(read_thumbnail(&dir, "new").is_none());
+        assert_eq!(usage(&dir), 100);
+
+        clear(&dir).unwrap();
+        assert_eq!(usage(&dir), 0);
+        assert_eq!(enforce_limit(&dir, 0, 0).unwrap(), 0);
+    }
+}
@@ -0,0 +1,409 @@
+// cli.rs - Command line mode for scripts and pipes
+// `pixeldrain upload FILE` uploads a file and prints its link, with - as FILE
+// standard input is streamed to the server without a temporary file:
+//   pg_dump shop | pixeldrain upload - --name shop.sql
+// `pixeldrain download URL -o -` writes the file to standard output instead:
+//   pixeldrain download https://pixeldrain.com/u/abc123 -o - | tar xz
+// The API key, server, language and history of the desktop app are used. Sizes
+// are read and written in that language, so `--max-size 1,5GB` works in German.
+use crate::history::{DownloadHistoryEntry, UploadHistoryEntry};
+use crate::i18n::{self, Language, t};
+use crate::units;
+use chrono::Utc;
+use pixeldrain_api::{PixelDrainApi, PixelDrainClient, PixelDrainConfig, PixelDrainError};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+const USAGE: &str = "\
+Usage:
+  pixeldrain                              Open the app
+  pixeldrain upload FILE [--name NAME]    Upload FILE and print its link
+  pixeldrain upload - --name NAME         Upload standard input as NAME
+      --max-size SIZE                     Refuse files bigger than SIZE, like 1.5GB or 500MB
+  pixeldrain download URL [-o PATH]       Download a file into the current folder or PATH
+  pixeldrain download URL -o -            Write a file to standard output
+  pixeldrain tui                          Queue transfers in the terminal (--features tui builds)
+
+The API key is taken from the app settings or PIXELDRAIN_API_KEY.
+";
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    /// The maximum size is kept as typed, it's read in the language of the settings
+    Upload { source: Source, name: Option<String>, max_size: Option<String> },
+    Download { url: String, output: Output },
+    Tui,
+    Help,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Source {
+    Stdin,
+    File(PathBuf),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Output {
+    /// The current folder, under the name of the file
+    Default,
+    Stdout,
+    File(PathBuf),
+}
+
+/// Command given in `args` (without the program name). None starts the app window.
+pub fn parse(args: &[String]) -> Option<Result<Command, String>> {
+    let (command, rest) = args.split_first()?;
+    match command.as_str() {
+        "upload" => Some(parse_upload(rest)),
+        "download" => Some(parse_download(rest)),
+        "tui" if rest.is_empty() => Some(Ok(Command::Tui)),
+        "tui" => Some(Err(format!("Unknown option {}", rest[0]))),
+        "help" | "--help" | "-h" => Some(Ok(Command::Help)),
+        _ => None,
+    }
+}
+
+fn parse_upload(args: &[String]) -> Result<Command, String> {
+    let mut source = None;
+    let mut name = None;
+    let mut max_size = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let value = match arg.as_str() {
+            "--name" | "-n" => Some(args.next().ok_or("--name needs a value")?.clone()),
+            _ => arg.strip_prefix("--name=").map(str::to_string),
+        };
+        if value.is_some() {
+            name = value.filter(|n| !n.is_empty());
+            continue;
+        }
+        let value = match arg.as_str() {
+            "--max-size" => Some(args.next().ok_or("--max-size needs a value")?.clone()),
+            _ => arg.strip_prefix("--max-size=").map(str::to_string),
+        };
+        if value.is_some() {
+            max_size = value;
+            continue;
+        }
+        let next = match arg.as_str() {
+            "-" => Source::Stdin,
+            option if option.starts_with('-') => return Err(format!("Unknown option {}", option)),
+            path => Source::File(PathBuf::from(path)),
+        };
+        if source.replace(next).is_some() {
+            return Err("Upload one file at a time".to_string());
+        }
+    }
+    match source {
+        None => Err("Nothing to upload, give a file or - for standard input".to_string()),
+        Some(Source::Stdin) if name.is_none() => Err("Uploading standard input needs --name".to_string()),
+        Some(source) => Ok(Command::Upload { source, name, max_size }),
+    }
+}
+
+fn parse_download(args: &[String]) -> Result<Command, String> {
+    let mut url = None;
+    let mut output = Output::Default;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let value = match arg.as_str() {
+            "--output" | "-o" => Some(args.next().ok_or("--output needs a value")?.as_str()),
+            _ => arg.strip_prefix("--output="),
+        };
+        if let Some(value) = value {
+            output = match value {
+                "-" => Output::Stdout,
+                "" => Output::Default,
+                path => Output::File(PathBuf::from(path)),
+            };
+            continue;
+        }
+        if arg.starts_with('-') {
+            return Err(format!("Unknown option {}", arg));
+        }
+        if url.replace(arg.clone()).is_some() {
+            return Err("Download one file at a time".to_string());
+        }
+    }
+    let url = url.ok_or("Nothing to download, give the link of a file")?;
+    Ok(Command::Download { url, output })
+}
+
+/// Run `command`, returning the exit code of the process
+pub fn run(command: Result<Command, String>) -> i32 {
+    let command = match command {
+        Ok(command) => command,
+        Err(e) => {
+            eprint!("pixeldrain: {}\n\n{}", e, USAGE);
+            return 2;
+        }
+    };
+    match execute(command, &mut io::stdout().lock()) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("pixeldrain: {}", e);
+            1
+        }
+    }
+}
+
+fn execute(command: Command, out: &mut impl Write) -> Result<(), String> {
+    match command {
+        Command::Help => out.write_all(USAGE.as_bytes()).map_err(|e| e.to_string()),
+        #[cfg(feature = "tui")]
+        Command::Tui => crate::tui::run(),
+        #[cfg(not(feature = "tui"))]
+        Command::Tui => Err("This build has no terminal interface, build it with --features tui".to_string()),
+        Command::Upload { source, name, max_size } => {
+            let app = crate::PixelDrainApp::default();
+            if let (Some(max_size), Source::File(path)) = (max_size, &source) {
+                check_max_size(path, &max_size, i18n::language())?;
+            }
+            let client = client(&app)?;
+            let entry = upload(client.as_ref(), source, name);
+            app.state.lock().unwrap().record_upload(entry.clone());
+            match entry.error {
+                Some(error) => Err(error),
+                None => {
+                    eprintln!("{}", t!("cli-uploaded", name = entry.filename.clone(), size = units::format_size(entry.size, i18n::language())));
+                    writeln!(out, "{}", entry.url).map_err(|e| e.to_string())
+                }
+            }
+        }
+        Command::Download { url, output } => {
+            let app = crate::PixelDrainApp::default();
+            let client = client(&app)?;
+            let infer_extensions = app.state.lock().unwrap().infer_extensions;
+            let (entry, bytes) = download(client.as_ref(), &url, output, infer_extensions, out);
+            {
+                let mut state = app.state.lock().unwrap();
+                state.record_download(entry.clone());
+                state.download_usage.roll_over();
+                state.download_usage.record(bytes);
+            }
+            app.save_preferences();
+            match entry.error {
+                Some(error) => Err(error),
+                None => {
+                    eprintln!("{}", t!("cli-downloaded", name = entry.filename, size = units::format_size(bytes, i18n::language())));
+                    Ok(())
+                }
+            }
+        }
+    }
+}
+
+/// Refuse `path` when it's bigger than `max_size`, a size typed in `language`
+fn check_max_size(path: &Path, max_size: &str, language: Language) -> Result<(), String> {
+    let max = units::parse_size(max_size, language).ok_or_else(|| t!("cli-bad-size", size = max_size))?;
+    let size = path.metadata().map_err(|e| format!("{}: {}", path.display(), e))?.len();
+    if size > max {
+        return Err(t!(
+            "cli-too-big",
+            path = path.display().to_string(),
+            size = units::format_size(size, language),
+            max = units::format_size(max, language),
+        ));
+    }
+    Ok(())
+}
+
+/// Client with the API key and server of the app settings
+fn client(app: &crate::PixelDrainApp) -> Result<Arc<dyn PixelDrainApi>, String> {
+    let config = match app.get_api_key() {
+        Some(key) => PixelDrainConfig::default().with_api_key(key),
+        None => PixelDrainConfig::default(),
+    };
+    (app.api_factory)(config).map_err(|e| e.to_string())
+}
+
+/// Upload `source`, as a history entry that has the error when it failed
+fn upload(client: &dyn PixelDrainApi, source: Source, name: Option<String>) -> UploadHistoryEntry {
+    let (filename, size, result) = match source {
+        Source::Stdin => {
+            let filename = name.unwrap_or_default();
+            let counter = Counting::new(io::stdin());
+            let count = counter.count.clone();
+            let result = client.upload_stream_put(Box::new(counter), &filename, None);
+            (filename, count.load(Ordering::Relaxed), result)
+        }
+        Source::File(path) => {
+            let size = path.metadata().map(|m| m.len()).unwrap_or(0);
+            match name {
+                Some(name) => {
+                    let result = client.upload_file_put(&path, &name, None);
+                    (name, size, result)
+                }
+                None => {
+                    let filename = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                    (filename, size, client.upload_file(&path, None))
+                }
+            }
+        }
+    };
+    let (id, error) = match result {
+        Ok(response) => (response.id, None),
+        Err(e) => (String::new(), Some(e.to_string())),
+    };
+    UploadHistoryEntry {
+        url: if id.is_empty() { String::new() } else { format!("{}/u/{}", client.base_url(), id) },
+        id,
+        filename,
+        size,
+        timestamp: Utc::now(),
+        verified: None,
+        error,
+        anonymous: false,
+    }
+}
+
+/// Download the file behind `url` to `output`. Returns the history entry, which
+/// has the error when it failed, and the number of bytes downloaded.
+fn download(client: &dyn PixelDrainApi, url: &str, output: Output, infer_extensions: bool, out: &mut dyn Write) -> (DownloadHistoryEntry, u64) {
+    // A bare file ID is accepted too
+    let file_id = PixelDrainClient::extract_file_id(url).unwrap_or_else(|_| url.trim().to_string());
+    let result = match output {
+        Output::Stdout => {
+            let mut counter = Counting::new(out);
+            let result = client.download_file_to_writer(&file_id, &mut counter, None);
+            let bytes = counter.count.load(Ordering::Relaxed);
+            result.map(|()| (file_id.clone(), "-".to_string(), bytes))
+        }
+        Output::File(path) => download_to_path(client, &file_id, None, &path),
+        Output::Default => client
+            .get_file_info(&file_id)
+            .and_then(|info| {
+                let name = if infer_extensions { crate::extensions::with_inferred_extension(&info.name, &info.mime_type) } else { info.name };
+                download_to_path(client, &file_id, Some(name), Path::new("."))
+            }),
+    };
+    let (filename, local_path, bytes, error) = match result {
+        Ok((filename, local_path, bytes)) => (filename, local_path, bytes, None),
+        Err(e) => (file_id.clone(), String::new(), 0, Some(e.to_string())),
+    };
+    let entry = DownloadHistoryEntry {
+        url: format!("{}/u/{}", client.base_url(), file_id),
+        filename,
+        local_path,
+        timestamp: Utc::now(),
+        error,
+    };
+    (entry, bytes)
+}
+
+/// Save to `path`, or into the folder `path` under `name`. Returns the file name, path and size.
+fn download_to_path(client: &dyn PixelDrainApi, file_id: &str, name: Option<String>, path: &Path) -> Result<(String, String, u64), PixelDrainError> {
+    let save_path = match &name {
+        Some(name) => path.join(crate::sanitize_file_name(name)),
+        None => path.to_path_buf(),
+    };
+    client.download_file(file_id, &save_path, None)?;
+    let filename = name.unwrap_or_else(|| save_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default());
+    let bytes = save_path.metadata().map(|m| m.len()).unwrap_or(0);
+    Ok((filename, save_path.display().to_string(), bytes))
+}
+
+/// Counts the bytes going through a stream whose size isn't known up front
+struct Counting<T> {
+    inner: T,
+    count: Arc<AtomicU64>,
+}
+
+impl<T> Counting<T> {
+    fn new(inner: T) -> Self {
+        Counting { inner, count: Arc::new(AtomicU64::new(0)) }
+    }
+}
+
+impl<R: Read> Read for Counting<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.count.fetch_add(read as u64, Ordering::Relaxed);
+        Ok(read)
+    }
+}
+
+impl<W: Write> Write for Counting<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.count.fetch_add(written as u64, Ordering::Relaxed);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_uploads_and_leaves_other_arguments_to_the_app() {
+        assert_eq!(parse(&args(&[])), None);
+        assert_eq!(parse(&args(&["pixeldrain://upload?path=/a"])), None);
+        assert_eq!(parse(&args(&["tui"])), Some(Ok(Command::Tui)));
+        assert_eq!(
+            parse(&args(&["upload", "-", "--name", "out.log"])),
+            Some(Ok(Command::Upload { source: Source::Stdin, name: Some("out.log".to_string()), max_size: None }))
+        );
+        assert_eq!(
+            parse(&args(&["upload", "--name=db.sql", "dump"])),
+            Some(Ok(Command::Upload { source: Source::File(PathBuf::from("dump")), name: Some("db.sql".to_string()), max_size: None }))
+        );
+        assert_eq!(
+            parse(&args(&["upload", "photo.jpg"])),
+            Some(Ok(Command::Upload { source: Source::File(PathBuf::from("photo.jpg")), name: None, max_size: None }))
+        );
+        assert_eq!(
+            parse(&args(&["upload", "photo.jpg", "--max-size", "1,5 GB"])),
+            Some(Ok(Command::Upload { source: Source::File(PathBuf::from("photo.jpg")), name: None, max_size: Some("1,5 GB".to_string()) }))
+        );
+        assert!(parse(&args(&["upload", "-"])).unwrap().is_err());
+        assert!(parse(&args(&["upload"])).unwrap().is_err());
+        assert!(parse(&args(&["upload", "a", "b"])).unwrap().is_err());
+        assert!(parse(&args(&["upload", "--verbose", "a"])).unwrap().is_err());
+        assert!(parse(&args(&["upload", "a", "--name"])).unwrap().is_err());
+        assert!(parse(&args(&["upload", "a", "--max-size"])).unwrap().is_err());
+    }
+
+    #[test]
+    fn the_maximum_size_is_read_in_the_language() {
+        let path = std::env::temp_dir().join(format!("pixeldrain-cli-max-size-{}", std::process::id()));
+        std::fs::write(&path, vec![0u8; 1500]).unwrap();
+        assert!(check_max_size(&path, "1,5 KB", Language::English).is_ok());
+        // Read as fifteen hundred bytes in English and one and a half in German
+        assert!(check_max_size(&path, "1,500", Language::English).is_ok());
+        assert!(check_max_size(&path, "1,500", Language::German).is_err());
+        assert!(check_max_size(&path, "1 KB", Language::German).is_err());
+        assert!(check_max_size(&path, "lots", Language::English).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn parses_downloads_to_stdout_and_files() {
+        let url = "https://pixeldrain.com/u/abc123";
+        assert_eq!(
+            parse(&args(&["download", url, "-o", "-"])),
+            Some(Ok(Command::Download { url: url.to_string(), output: Output::Stdout }))
+        );
+        assert_eq!(
+            parse(&args(&["download", "--output=out.bin", url])),
+            Some(Ok(Command::Download { url: url.to_string(), output: Output::File(PathBuf::from("out.bin")) }))
+        );
+        assert_eq!(
+            parse(&args(&["download", url])),
+            Some(Ok(Command::Download { url: url.to_string(), output: Output::Default }))
+        );
+        assert!(parse(&args(&["download"])).unwrap().is_err());
+        assert!(parse(&args(&["download", url, url])).unwrap().is_err());
+        assert!(parse(&args(&["download", url, "-o"])).unwrap().is_err());
+    }
+}
@@ -0,0 +1,186 @@
+// crypto.rs - Client-side encryption, for keeping sensitive files on a public
+// host. Uploads are encrypted on the fly with ChaCha20-Poly1305 in 64 KiB
+// chunks (the STREAM construction, so a truncated or reordered file is
+// detected) under an Argon2id key derived from a passphrase, and get a .enc
+// extension. Downloads of .enc files are decrypted on the fly.
+use std::io::{self, Read, Write};
+
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::stream::{DecryptorBE32, EncryptorBE32};
+use chacha20poly1305::aead::{KeyInit, OsRng};
+use chacha20poly1305::ChaCha20Poly1305;
+
+use crate::settings_export::derive_key;
+
+pub const EXTENSION: &str = ".enc";
+const MAGIC: &[u8; 8] = b"PDENC\0\0\x01";
+const SALT_LEN: usize = 16;
+// The 12 byte nonce without the counter and last-chunk flag that STREAM adds
+const NONCE_LEN: usize = 7;
+const HEADER_LEN: usize = MAGIC.len() + SALT_LEN + NONCE_LEN;
+const CHUNK: usize = 64 * 1024;
+const TAG_LEN: usize = 16;
+
+/// Name of the encrypted upload of `name`
+pub fn encrypted_name(name: &str) -> String {
+    format!("{}{}", name, EXTENSION)
+}
+
+/// Name of the decrypted download of an encrypted file, None for other files
+pub fn decrypted_name(name: &str) -> Option<&str> {
+    name.strip_suffix(EXTENSION).filter(|name| !name.is_empty())
+}
+
+fn cipher(passphrase: &str, salt: &[u8]) -> io::Result<ChaCha20Poly1305> {
+    Ok(ChaCha20Poly1305::new(&derive_key(passphrase, salt).map_err(io::Error::other)?))
+}
+
+/// Up to a chunk of `reader`, shorter only at the end
+fn read_chunk(reader: &mut impl Read) -> io::Result<Vec<u8>> {
+    let mut chunk = Vec::with_capacity(CHUNK);
+    reader.by_ref().take(CHUNK as u64).read_to_end(&mut chunk)?;
+    Ok(chunk)
+}
+
+/// Reads `inner` encrypted
+pub struct Encryptor<R> {
+    inner: R,
+    // None once the last chunk was encrypted
+    stream: Option<EncryptorBE32<ChaCha20Poly1305>>,
+    // The chunk after the one being read, to tell which chunk is the last
+    next: Vec<u8>,
+    out: Vec<u8>,
+    pos: usize,
+}
+
+impl<R: Read> Encryptor<R> {
+    pub fn new(mut inner: R, passphrase: &str) -> io::Result<Self> {
+        let mut salt = [0u8; SALT_LEN];
+        let mut nonce = [0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut salt);
+        OsRng.fill_bytes(&mut nonce);
+        let stream = EncryptorBE32::from_aead(cipher(passphrase, &salt)?, (&nonce).into());
+        let next = read_chunk(&mut inner)?;
+        let out = [MAGIC.as_slice(), &salt, &nonce].concat();
+        Ok(Self { inner, stream: Some(stream), next, out, pos: 0 })
+    }
+}
+
+impl<R: Read> Read for Encryptor<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos == self.out.len() {
+            if self.stream.is_none() {
+                return Ok(0);
+            }
+            let next = read_chunk(&mut self.inner)?;
+            let chunk = std::mem::replace(&mut self.next, next);
+            let encrypted = if self.next.is_empty() {
+                self.stream.take().and_then(|stream| stream.encrypt_last(chunk.as_slice()).ok())
+            } else {
+                self.stream.as_mut().and_then(|stream| stream.encrypt_next(chunk.as_slice()).ok())
+            };
+            self.out = encrypted.ok_or_else(|| io::Error::other("Encryption failed"))?;
+            self.pos = 0;
+        }
+        let len = buf.len().min(self.out.len() - self.pos);
+        buf[..len].copy_from_slice(&self.out[self.pos..self.pos + len]);
+        self.pos += len;
+        Ok(len)
+    }
+}
+
+/// Writes what's written to it to `inner` decrypted. Call `finish` at the end,
+/// the last chunk is only checked and written then.
+pub struct Decryptor<W: Write> {
+    inner: W,
+    passphrase: String,
+    // None until the header was read
+    stream: Option<DecryptorBE32<ChaCha20Poly1305>>,
+    buffer: Vec<u8>,
+}
+
+impl<W: Write> Decryptor<W> {
+    pub fn new(inner: W, passphrase: &str) -> Self {
+        Self { inner, passphrase: passphrase.to_string(), stream: None, buffer: Vec::new() }
+    }
+
+    pub fn finish(mut self) -> io::Result<W> {
+        let stream = self.stream.take().ok_or_else(|| invalid("The encrypted file is incomplete"))?;
+        let plaintext = stream.decrypt_last(self.buffer.as_slice()).map_err(|_| wrong_passphrase())?;
+        self.inner.write_all(&plaintext)?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write> Write for Decryptor<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        if self.stream.is_none() {
+            if self.buffer.len() < HEADER_LEN {
+                return Ok(buf.len());
+            }
+            let (magic, rest) = self.buffer[..HEADER_LEN].split_at(MAGIC.len());
+            if magic != MAGIC {
+                return Err(invalid("Not a file encrypted by this app"));
+            }
+            let (salt, nonce) = rest.split_at(SALT_LEN);
+            self.stream = Some(DecryptorBE32::from_aead(cipher(&self.passphrase, salt)?, nonce.into()));
+            self.buffer.drain(..HEADER_LEN);
+        }
+        // A full chunk at the end may be the last one, which is decrypted differently
+        while self.buffer.len() > CHUNK + TAG_LEN {
+            let chunk: Vec<u8> = self.buffer.drain(..CHUNK + TAG_LEN).collect();
+            let stream = self.stream.as_mut().ok_or_else(|| invalid("Missing header"))?;
+            let plaintext = stream.decrypt_next(chunk.as_slice()).map_err(|_| wrong_passphrase())?;
+            self.inner.write_all(&plaintext)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn wrong_passphrase() -> io::Error {
+    invalid("Wrong passphrase, or the encrypted file was damaged")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decrypt(encrypted: &[u8], passphrase: &str) -> io::Result<Vec<u8>> {
+        let mut decryptor = Decryptor::new(Vec::new(), passphrase);
+        // Odd write sizes, like a download delivers them
+        for part in encrypted.chunks(10_000) {
+            decryptor.write_all(part)?;
+        }
+        decryptor.finish()
+    }
+
+    #[test]
+    fn files_round_trip_and_need_the_passphrase() {
+        for size in [0, CHUNK, 2 * CHUNK + 5] {
+            let plaintext: Vec<u8> = (0..size).map(|i| (i % 251) as u8).collect();
+            let mut encrypted = Vec::new();
+            Encryptor::new(plaintext.as_slice(), "correct horse").unwrap().read_to_end(&mut encrypted).unwrap();
+            assert_eq!(encrypted.len(), HEADER_LEN + size + size.div_ceil(CHUNK).max(1) * TAG_LEN);
+            assert_eq!(decrypt(&encrypted, "correct horse").unwrap(), plaintext);
+            if size > CHUNK {
+                // Cut off after the first chunk
+                assert!(decrypt(&encrypted[..HEADER_LEN + CHUNK + TAG_LEN], "correct horse").is_err());
+                assert!(decrypt(&encrypted, "battery staple").is_err());
+            }
+        }
+        assert!(decrypt(b"plain text, not encrypted at all", "correct horse").is_err());
+        assert_eq!(decrypted_name("report.pdf.enc"), Some("report.pdf"));
+        assert_eq!(decrypted_name(".enc"), None);
+        assert_eq!(encrypted_name("report.pdf"), "report.pdf.enc");
+    }
+}
@@ -0,0 +1,106 @@
+// extensions.rs - File name extensions for downloads that were uploaded without
+// one, taken from the MIME type the server detected, so they open in the right
+// program on Windows. Also the other way around, the type a file should have
+// going by its name, for fixing files the server detected wrongly.
+use std::path::Path;
+
+// The usual extension of common types. Types like application/octet-stream
+// that say nothing about the content are left out.
+const EXTENSIONS: &[(&str, &str)] = &[
+    ("application/gzip", "gz"),
+    ("application/json", "json"),
+    ("application/msword", "doc"),
+    ("application/pdf", "pdf"),
+    ("application/vnd.android.package-archive", "apk"),
+    ("application/vnd.ms-excel", "xls"),
+    ("application/vnd.oasis.opendocument.text", "odt"),
+    ("application/vnd.openxmlformats-officedocument.presentationml.presentation", "pptx"),
+    ("application/vnd.openxmlformats-officedocument.spreadsheetml.sheet", "xlsx"),
+    ("application/vnd.openxmlformats-officedocument.wordprocessingml.document", "docx"),
+    ("application/x-7z-compressed", "7z"),
+    ("application/x-bzip2", "bz2"),
+    ("application/x-iso9660-image", "iso"),
+    ("application/x-rar-compressed", "rar"),
+    ("application/x-tar", "tar"),
+    ("application/x-xz", "xz"),
+    ("application/xml", "xml"),
+    ("application/zip", "zip"),
+    ("application/zstd", "zst"),
+    ("audio/aac", "aac"),
+    ("audio/flac", "flac"),
+    ("audio/mp4", "m4a"),
+    ("audio/mpeg", "mp3"),
+    ("audio/ogg", "ogg"),
+    ("audio/wav", "wav"),
+    ("audio/x-wav", "wav"),
+    ("image/avif", "avif"),
+    ("image/bmp", "bmp"),
+    ("image/gif", "gif"),
+    ("image/heic", "heic"),
+    ("image/jpeg", "jpg"),
+    ("image/png", "png"),
+    ("image/svg+xml", "svg"),
+    ("image/tiff", "tiff"),
+    ("image/webp", "webp"),
+    ("text/csv", "csv"),
+    ("text/html", "html"),
+    ("text/markdown", "md"),
+    ("text/plain", "txt"),
+    ("text/xml", "xml"),
+    ("video/mp4", "mp4"),
+    ("video/mpeg", "mpg"),
+    ("video/ogg", "ogv"),
+    ("video/quicktime", "mov"),
+    ("video/webm", "webm"),
+    ("video/x-matroska", "mkv"),
+    ("video/x-msvideo", "avi"),
+];
+
+/// Extension for `mime_type`, which may have parameters such as a charset
+pub fn for_mime_type(mime_type: &str) -> Option<&'static str> {
+    let essence = mime_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+    EXTENSIONS.iter().find(|(mime, _)| *mime == essence).map(|(_, extension)| *extension)
+}
+
+/// MIME type that files named like `name` usually have
+pub fn mime_type_for_name(name: &str) -> Option<&'static str> {
+    let extension = Path::new(name).extension()?.to_str()?.to_ascii_lowercase();
+    let extension = if extension == "jpeg" { "jpg".to_string() } else { extension };
+    EXTENSIONS.iter().find(|(_, known)| *known == extension).map(|(mime, _)| *mime)
+}
+
+/// Every type with a known extension, for picking one
+pub fn known_mime_types() -> impl Iterator<Item = &'static str> {
+    EXTENSIONS.iter().map(|(mime, _)| *mime)
+}
+
+/// `name` with the extension of `mime_type` added when it has none. Hidden
+/// files such as .bashrc are left alone.
+pub fn with_inferred_extension(name: &str, mime_type: &str) -> String {
+    if name.starts_with('.') || Path::new(name).extension().is_some() {
+        return name.to_string();
+    }
+    match for_mime_type(mime_type) {
+        Some(extension) => format!("{}.{}", name, extension),
+        None => name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extension_is_added_only_when_missing_and_known() {
+        assert_eq!(with_inferred_extension("holiday", "image/jpeg"), "holiday.jpg");
+        assert_eq!(with_inferred_extension("notes", "text/plain; charset=utf-8"), "notes.txt");
+        assert_eq!(with_inferred_extension("report", "Application/PDF"), "report.pdf");
+        assert_eq!(with_inferred_extension("photo.png", "image/jpeg"), "photo.png");
+        assert_eq!(with_inferred_extension("blob", "application/octet-stream"), "blob");
+        assert_eq!(with_inferred_extension(".bashrc", "text/plain"), ".bashrc");
+
+        assert_eq!(mime_type_for_name("Holiday.JPEG"), Some("image/jpeg"));
+        assert_eq!(mime_type_for_name("clip.mkv"), Some("video/x-matroska"));
+        assert_eq!(mime_type_for_name("README"), None);
+    }
+}
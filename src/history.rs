@@ -0,0 +1,312 @@
+// history.rs - Upload and download history entries, views and exports
+// Where the history is kept is up to the storage backend (see storage.rs).
+// Every transfer is kept, there is no limit on the number of entries.
+use crate::storage::Storage;
+use chrono::{DateTime, Utc};
+use pixeldrain_api::FileInfo;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct UploadHistoryEntry {
+    pub id: String,
+    pub url: String,
+    pub filename: String,
+    pub size: u64,
+    pub timestamp: DateTime<Utc>,
+    // None when the upload was not verified (disabled, streamed or info lookup failed)
+    #[serde(default)]
+    pub verified: Option<bool>,
+    // Set for failed uploads, which have no ID or URL
+    #[serde(default)]
+    pub error: Option<String>,
+    // Uploaded without the API key, so it isn't part of the account
+    #[serde(default)]
+    pub anonymous: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DownloadHistoryEntry {
+    pub url: String,
+    pub filename: String,
+    pub local_path: String,
+    pub timestamp: DateTime<Utc>,
+    // Set for failed downloads, local_path is empty then
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// Filtering and grouping of the upload and download history views
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct HistoryViewOptions {
+    #[serde(skip)]
+    pub search: String,
+    pub group_by_day: bool,
+    pub collapse_duplicates: bool,
+    pub only_failures: bool,
+    // Date range of the History tab, `until` is exclusive
+    #[serde(skip)]
+    pub since: Option<DateTime<Utc>>,
+    #[serde(skip)]
+    pub until: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum HistoryKind {
+    Uploads,
+    Downloads,
+}
+
+#[derive(Clone, Copy)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+/// Pass as the limit of a view to get every matching entry
+pub const NO_LIMIT: usize = i64::MAX as usize;
+
+/// Common view of upload and download history entries
+pub trait HistoryEntry {
+    fn url(&self) -> &str;
+    fn filename(&self) -> &str;
+    fn timestamp(&self) -> DateTime<Utc>;
+    fn failed(&self) -> bool;
+
+    /// Key duplicates are collapsed by. Failed entries have no URL, those go by name instead.
+    fn duplicate_key(&self) -> &str {
+        if self.url().is_empty() { self.filename() } else { self.url() }
+    }
+
+    /// Same transfer, for skipping entries that were imported before
+    fn same_transfer(&self, other: &Self) -> bool {
+        self.timestamp() == other.timestamp() && self.url() == other.url() && self.filename() == other.filename()
+    }
+}
+
+impl HistoryEntry for UploadHistoryEntry {
+    fn url(&self) -> &str {
+        &self.url
+    }
+    fn filename(&self) -> &str {
+        &self.filename
+    }
+    fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+    // A verification mismatch counts as a failure too
+    fn failed(&self) -> bool {
+        self.error.is_some() || self.verified == Some(false)
+    }
+}
+
+impl HistoryEntry for DownloadHistoryEntry {
+    fn url(&self) -> &str {
+        &self.url
+    }
+    fn filename(&self) -> &str {
+        &self.filename
+    }
+    fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+    fn failed(&self) -> bool {
+        self.error.is_some()
+    }
+}
+
+/// Apply the view options to entries kept in memory, oldest first in `entries`.
+/// Returns the same as `Storage::uploads` and `Storage::downloads`.
+pub fn view<T: HistoryEntry + Clone>(entries: &[T], options: &HistoryViewOptions, limit: usize) -> Vec<(T, usize)> {
+    let search = options.search.trim().to_lowercase();
+    let mut view: Vec<(T, usize)> = Vec::new();
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+
+    for entry in entries.iter().rev() {
+        if options.only_failures && !entry.failed() {
+            continue;
+        }
+        if options.since.is_some_and(|since| entry.timestamp() < since)
+            || options.until.is_some_and(|until| entry.timestamp() >= until)
+        {
+            continue;
+        }
+        if !search.is_empty()
+            && !entry.filename().to_lowercase().contains(&search)
+            && !entry.url().to_lowercase().contains(&search)
+        {
+            continue;
+        }
+        if options.collapse_duplicates {
+            if let Some(&index) = seen.get(entry.duplicate_key()) {
+                view[index].1 += 1;
+                continue;
+            }
+            seen.insert(entry.duplicate_key(), view.len());
+        }
+        view.push((entry.clone(), 1));
+    }
+    view.truncate(limit);
+    view
+}
+
+/// Link offered while typing in the download field
+#[derive(Clone, Debug, PartialEq)]
+pub struct UrlSuggestion {
+    pub name: String,
+    pub url: String,
+}
+
+/// Links whose name or address contains `query`, ignoring case: recent
+/// downloads first, then uploads, then the account's files, each link once
+pub fn url_suggestions(storage: &dyn Storage, files: &[FileInfo], base_url: &str, query: &str, limit: usize) -> Vec<UrlSuggestion> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let options = HistoryViewOptions { search: query.to_string(), collapse_duplicates: true, ..Default::default() };
+    let downloads = storage.downloads(&options, limit).unwrap_or_default().into_iter().map(|(entry, _)| UrlSuggestion {
+        name: entry.filename,
+        url: entry.url,
+    });
+    // Failed uploads have no link
+    let uploads = storage
+        .uploads(&options, limit)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(entry, _)| !entry.url.is_empty())
+        .map(|(entry, _)| UrlSuggestion { name: entry.filename, url: entry.url });
+    let lowercase = query.to_lowercase();
+    let files = files
+        .iter()
+        .filter(|file| file.name.to_lowercase().contains(&lowercase) || file.id.to_lowercase().contains(&lowercase))
+        .map(|file| UrlSuggestion { name: file.name.clone(), url: format!("{}/u/{}", base_url, file.id) });
+
+    let mut seen = HashSet::new();
+    downloads
+        .chain(uploads)
+        .chain(files)
+        // Nothing to suggest for a link that's already typed out
+        .filter(|suggestion| suggestion.url != query && seen.insert(suggestion.url.clone()))
+        .take(limit)
+        .collect()
+}
+
+/// Every entry of `kind` matching the view options, newest first, as CSV or JSON
+pub fn export(storage: &dyn Storage, kind: HistoryKind, options: &HistoryViewOptions, format: ExportFormat) -> Result<String, String> {
+    let storage_error = |e: crate::storage::StorageError| e.to_string();
+    match (kind, format) {
+        (HistoryKind::Uploads, ExportFormat::Csv) => {
+            let mut csv = csv_row(&["timestamp", "filename", "size", "url", "id", "verified", "anonymous", "error"]);
+            for (entry, _) in storage.uploads(options, NO_LIMIT).map_err(storage_error)? {
+                csv.push_str(&csv_row(&[
+                    &entry.timestamp.to_rfc3339(),
+                    &entry.filename,
+                    &entry.size.to_string(),
+                    &entry.url,
+                    &entry.id,
+                    &entry.verified.map(|v| v.to_string()).unwrap_or_default(),
+                    &entry.anonymous.to_string(),
+                    entry.error.as_deref().unwrap_or_default(),
+                ]));
+            }
+            Ok(csv)
+        }
+        (HistoryKind::Downloads, ExportFormat::Csv) => {
+            let mut csv = csv_row(&["timestamp", "filename", "url", "local_path", "error"]);
+            for (entry, _) in storage.downloads(options, NO_LIMIT).map_err(storage_error)? {
+                csv.push_str(&csv_row(&[
+                    &entry.timestamp.to_rfc3339(),
+                    &entry.filename,
+                    &entry.url,
+                    &entry.local_path,
+                    entry.error.as_deref().unwrap_or_default(),
+                ]));
+            }
+            Ok(csv)
+        }
+        (HistoryKind::Uploads, ExportFormat::Json) => {
+            let uploads: Vec<_> = storage.uploads(options, NO_LIMIT).map_err(storage_error)?.into_iter().map(|(e, _)| e).collect();
+            serde_json::to_string_pretty(&uploads).map_err(|e| e.to_string())
+        }
+        (HistoryKind::Downloads, ExportFormat::Json) => {
+            let downloads: Vec<_> = storage.downloads(options, NO_LIMIT).map_err(storage_error)?.into_iter().map(|(e, _)| e).collect();
+            serde_json::to_string_pretty(&downloads).map_err(|e| e.to_string())
+        }
+    }
+}
+
+/// One CSV line, quoting fields that contain separators, quotes or line breaks
+fn csv_row(fields: &[&str]) -> String {
+    let quoted: Vec<String> = fields
+        .iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        })
+        .collect();
+    format!("{}\r\n", quoted.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_quotes_separators_and_quotes() {
+        assert_eq!(csv_row(&["a", "b c"]), "a,b c\r\n");
+        assert_eq!(csv_row(&["say \"hi\", bye", "x\ny"]), "\"say \"\"hi\"\", bye\",\"x\ny\"\r\n");
+    }
+
+    #[test]
+    fn url_suggestions_come_from_history_and_files() {
+        let mut storage = crate::storage::in_memory();
+        let download = |url: &str, filename: &str| DownloadHistoryEntry {
+            url: url.to_string(),
+            filename: filename.to_string(),
+            local_path: String::new(),
+            timestamp: Utc::now(),
+            error: None,
+        };
+        storage.add_download(&download("https://pixeldrain.com/u/aaa", "Holiday.jpg")).unwrap();
+        storage.add_download(&download("https://pixeldrain.com/u/aaa", "Holiday.jpg")).unwrap();
+        storage
+            .add_upload(&UploadHistoryEntry {
+                id: "bbb".to_string(),
+                url: "https://pixeldrain.com/u/bbb".to_string(),
+                filename: "holiday-2.jpg".to_string(),
+                size: 1,
+                timestamp: Utc::now(),
+                verified: None,
+                error: None,
+                anonymous: false,
+            })
+            .unwrap();
+        let files = [
+            FileInfo { id: "aaa".to_string(), name: "Holiday.jpg".to_string(), ..Default::default() },
+            FileInfo { id: "ccc".to_string(), name: "holiday.mp4".to_string(), ..Default::default() },
+            FileInfo { id: "ddd".to_string(), name: "notes.txt".to_string(), ..Default::default() },
+        ];
+
+        let urls = |query: &str, limit| -> Vec<String> {
+            url_suggestions(storage.as_ref(), &files, "https://pixeldrain.com", query, limit).into_iter().map(|s| s.url).collect()
+        };
+        assert_eq!(urls("HOLIDAY", 10), ["https://pixeldrain.com/u/aaa", "https://pixeldrain.com/u/bbb", "https://pixeldrain.com/u/ccc"]);
+        assert_eq!(urls("holiday", 2).len(), 2);
+        assert_eq!(urls("ddd", 10), ["https://pixeldrain.com/u/ddd"]);
+        assert!(urls("https://pixeldrain.com/u/ddd", 10).is_empty());
+        assert!(urls("  ", 10).is_empty());
+    }
+}
@@ -0,0 +1,143 @@
+// i18n.rs - Translated UI text
+// Messages live in Fluent files under locales/, one per language, compiled into
+// the binary. Use `t!("message-id")` or `t!("message-id", name = value)`.
+// Messages missing from a translation fall back to English.
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use serde::{Deserialize, Serialize};
+use std::sync::{LazyLock, RwLock};
+use tracing::warn;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    #[serde(rename = "en")]
+    English,
+    #[serde(rename = "de")]
+    German,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::German];
+
+    fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::German => "de",
+        }
+    }
+
+    /// Name of the language in that language, for the picker
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::German => "Deutsch",
+        }
+    }
+
+    /// What separates the decimals of a number, as in 1.5 or 1,5
+    pub fn decimal_separator(self) -> char {
+        match self {
+            Language::English => '.',
+            Language::German => ',',
+        }
+    }
+
+    fn source(self) -> &'static str {
+        match self {
+            Language::English => include_str!("../locales/en.ftl"),
+            Language::German => include_str!("../locales/de.ftl"),
+        }
+    }
+}
+
+static CURRENT: RwLock<Language> = RwLock::new(Language::English);
+
+static BUNDLES: LazyLock<Vec<FluentBundle<FluentResource>>> = LazyLock::new(|| Language::ALL.iter().map(|&language| bundle(language)).collect());
+
+fn bundle(language: Language) -> FluentBundle<FluentResource> {
+    let resource = FluentResource::try_new(language.source().to_string()).unwrap_or_else(|(resource, errors)| {
+        warn!("Errors in the {} translation: {:?}", language.code(), errors);
+        resource
+    });
+    let mut bundle = FluentBundle::new_concurrent(vec![language.code().parse().expect("valid language code")]);
+    // egui draws the Unicode isolation marks Fluent puts around arguments as boxes
+    bundle.set_use_isolating(false);
+    if let Err(errors) = bundle.add_resource(resource) {
+        warn!("Duplicate messages in the {} translation: {:?}", language.code(), errors);
+    }
+    bundle
+}
+
+pub fn set_language(language: Language) {
+    *CURRENT.write().unwrap() = language;
+}
+
+pub fn language() -> Language {
+    *CURRENT.read().unwrap()
+}
+
+/// Message `id` in the current language, in English when it isn't translated,
+/// and the id itself when it doesn't exist at all
+pub fn tr(id: &str, args: Option<&FluentArgs>) -> String {
+    let current = *CURRENT.read().unwrap();
+    [current, Language::English]
+        .iter()
+        .find_map(|&language| format(&BUNDLES[language as usize], id, args))
+        .unwrap_or_else(|| id.to_string())
+}
+
+fn format(bundle: &FluentBundle<FluentResource>, id: &str, args: Option<&FluentArgs>) -> Option<String> {
+    let pattern = bundle.get_message(id)?.value()?;
+    let mut errors = Vec::new();
+    let text = bundle.format_pattern(pattern, args, &mut errors);
+    if !errors.is_empty() {
+        warn!("Failed to format message {}: {:?}", id, errors);
+    }
+    Some(text.into_owned())
+}
+
+macro_rules! t {
+    ($id:expr) => {
+        $crate::i18n::tr($id, None)
+    };
+    ($id:expr, $($name:ident = $value:expr),+ $(,)?) => {{
+        let mut args = fluent_bundle::FluentArgs::new();
+        $(args.set(stringify!($name), $value);)+
+        $crate::i18n::tr($id, Some(&args))
+    }};
+}
+pub(crate) use t;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message_ids(language: Language) -> Vec<String> {
+        if let Err((_, errors)) = FluentResource::try_new(language.source().to_string()) {
+            panic!("{} translation doesn't parse: {:?}", language.code(), errors);
+        }
+        let mut ids: Vec<String> = language
+            .source()
+            .lines()
+            .filter(|line| line.starts_with(|c: char| c.is_ascii_alphabetic()))
+            .filter_map(|line| line.split_once(" =").map(|(id, _)| id.to_string()))
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn translations_cover_the_same_messages() {
+        let english = message_ids(Language::English);
+        for language in Language::ALL {
+            assert_eq!(message_ids(language), english, "{} translation is out of sync", language.code());
+        }
+    }
+
+    #[test]
+    fn formats_arguments_and_falls_back() {
+        assert_eq!(tr("files-selected-count", Some(&FluentArgs::from_iter([("count", 3)]))), "3 selected");
+        assert_eq!(tr("no-such-message", None), "no-such-message");
+    }
+}
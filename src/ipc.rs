@@ -0,0 +1,150 @@
+// ipc.rs - pixeldrain:// deep links and forwarding them to a running instance
+// The first instance listens on a loopback port and records it, with a random
+// token, in the config directory. Launching the app with a deep link hands the
+// link to that instance instead of opening a second window.
+use eframe::egui;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+pub const SCHEME: &str = "pixeldrain";
+
+/// Action requested through a pixeldrain:// link
+#[derive(Debug, PartialEq)]
+pub enum DeepLink {
+    /// pixeldrain://upload?path=/a&path=/b
+    Upload(Vec<PathBuf>),
+    /// pixeldrain://download?url=https://pixeldrain.com/u/abc
+    Download(String),
+}
+
+impl DeepLink {
+    pub fn parse(link: &str) -> Result<Self, String> {
+        let url = url::Url::parse(link).map_err(|e| format!("Invalid link {}: {}", link, e))?;
+        if url.scheme() != SCHEME {
+            return Err(format!("Not a {}:// link: {}", SCHEME, link));
+        }
+        // pixeldrain://upload parses "upload" as the host, pixeldrain:upload as the path
+        let action = url.host_str().unwrap_or_else(|| url.path().trim_matches('/'));
+        let params: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+        let values = |key: &str| params.iter().filter(|(k, _)| k == key).map(|(_, v)| v.clone()).collect::<Vec<_>>();
+
+        match action {
+            "upload" => {
+                let paths: Vec<PathBuf> = values("path").into_iter().filter(|p| !p.is_empty()).map(PathBuf::from).collect();
+                if paths.is_empty() {
+                    return Err("Upload link has no path parameter".to_string());
+                }
+                Ok(DeepLink::Upload(paths))
+            }
+            "download" => match values("url").into_iter().next() {
+                Some(url) if !url.is_empty() => Ok(DeepLink::Download(url)),
+                _ => Err("Download link has no url parameter".to_string()),
+            },
+            other => Err(format!("Unknown link action: {}", other)),
+        }
+    }
+}
+
+/// Deep link passed on the command line, if any
+pub fn link_from_args() -> Option<String> {
+    std::env::args().skip(1).find(|arg| arg.starts_with(&format!("{}:", SCHEME)))
+}
+
+fn instance_file() -> PathBuf {
+    directories::ProjectDirs::from("com", "pixeldrain", "client")
+        .map(|proj_dirs| proj_dirs.config_dir().join("instance"))
+        .unwrap_or_else(|| PathBuf::from("instance"))
+}
+
+/// Hand `link` to the running instance. Returns false when no instance answered.
+pub fn send_to_running_instance(link: &str) -> bool {
+    let Ok(data) = std::fs::read_to_string(instance_file()) else {
+        return false;
+    };
+    let Some((port, token)) = data.trim().split_once(' ') else {
+        return false;
+    };
+    let Ok(port) = port.parse::<u16>() else {
+        return false;
+    };
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let Ok(mut stream) = TcpStream::connect_timeout(&addr, Duration::from_millis(500)) else {
+        return false;
+    };
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(2)));
+    if writeln!(stream, "{}\n{}", token, link).is_err() {
+        return false;
+    }
+    // Wait for the acknowledgement so a stale port taken by another program doesn't count
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply).is_ok() && reply.trim() == "ok"
+}
+
+/// Start accepting links from later launches. Received links are queued on the
+/// returned channel and the UI is woken up to handle them.
+pub fn listen(ctx: egui::Context) -> Option<Receiver<String>> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).ok()?;
+    let port = listener.local_addr().ok()?.port();
+    let token = uuid::Uuid::new_v4().simple().to_string();
+
+    let path = instance_file();
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    std::fs::write(&path, format!("{} {}", port, token)).ok()?;
+
+    let (tx, rx) = mpsc::channel();
+    // Blocks in accept() for the lifetime of the process, so it isn't a tracked worker
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let _ = stream.set_read_timeout(Some(Duration::from_secs(2)));
+            let mut reader = BufReader::new(&stream);
+            let (mut received_token, mut link) = (String::new(), String::new());
+            if reader.read_line(&mut received_token).is_err() || received_token.trim() != token {
+                continue;
+            }
+            if reader.read_line(&mut link).is_err() || link.trim().is_empty() {
+                continue;
+            }
+            let _ = writeln!(&stream, "ok");
+            if tx.send(link.trim().to_string()).is_err() {
+                break;
+            }
+            ctx.request_repaint();
+        }
+    });
+    Some(rx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_upload_and_download_links() {
+        assert_eq!(
+            DeepLink::parse("pixeldrain://upload?path=%2Ftmp%2Fa.txt&path=/tmp/b c.png"),
+            Ok(DeepLink::Upload(vec![PathBuf::from("/tmp/a.txt"), PathBuf::from("/tmp/b c.png")]))
+        );
+        assert_eq!(
+            DeepLink::parse("pixeldrain://download?url=https%3A%2F%2Fpixeldrain.com%2Fu%2Fabc123"),
+            Ok(DeepLink::Download("https://pixeldrain.com/u/abc123".to_string()))
+        );
+        assert_eq!(
+            DeepLink::parse("pixeldrain:download?url=https://pixeldrain.com/l/xyz"),
+            Ok(DeepLink::Download("https://pixeldrain.com/l/xyz".to_string()))
+        );
+    }
+
+    #[test]
+    fn rejects_incomplete_links() {
+        assert!(DeepLink::parse("pixeldrain://upload").is_err());
+        assert!(DeepLink::parse("pixeldrain://download?url=").is_err());
+        assert!(DeepLink::parse("pixeldrain://delete?id=abc").is_err());
+        assert!(DeepLink::parse("https://pixeldrain.com/u/abc").is_err());
+    }
+}
@@ -0,0 +1,77 @@
+// keys.rs - Which key each encrypted upload was encrypted with, kept per account.
+// Only key identifiers are stored, never the keys, so the registry tells which
+// key a file needs and whether it's configured on this machine.
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct KeyRegistry {
+    // Key identifier by file ID
+    #[serde(default)]
+    files: BTreeMap<String, String>,
+    // Identifiers of the keys available here
+    #[serde(default)]
+    configured: BTreeSet<String>,
+}
+
+impl KeyRegistry {
+    /// Note that `file_id` was encrypted with `key_id`, an empty `key_id` forgets it
+    pub fn record(&mut self, file_id: &str, key_id: &str) {
+        let key_id = key_id.trim();
+        if key_id.is_empty() {
+            self.files.remove(file_id);
+        } else {
+            self.files.insert(file_id.to_string(), key_id.to_string());
+        }
+    }
+
+    pub fn key_for(&self, file_id: &str) -> Option<&str> {
+        self.files.get(file_id).map(String::as_str)
+    }
+
+    pub fn configure(&mut self, key_id: &str) {
+        let key_id = key_id.trim();
+        if !key_id.is_empty() {
+            self.configured.insert(key_id.to_string());
+        }
+    }
+
+    pub fn remove_key(&mut self, key_id: &str) {
+        self.configured.remove(key_id);
+    }
+
+    pub fn configured(&self) -> impl Iterator<Item = &str> {
+        self.configured.iter().map(String::as_str)
+    }
+
+    /// Key `file_id` was encrypted with when that key isn't configured here
+    pub fn missing_key(&self, file_id: &str) -> Option<&str> {
+        self.key_for(file_id).filter(|key_id| !self.configured.contains(*key_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn files_with_keys_that_are_not_configured_are_found() {
+        let mut registry = KeyRegistry::default();
+        registry.record("abc", " backup-2024 ");
+        registry.record("def", "photos");
+        registry.configure("photos");
+        assert_eq!(registry.key_for("abc"), Some("backup-2024"));
+        assert_eq!(registry.missing_key("abc"), Some("backup-2024"));
+        assert_eq!(registry.missing_key("def"), None);
+        assert_eq!(registry.missing_key("plain"), None);
+
+        registry.configure("backup-2024");
+        assert_eq!(registry.missing_key("abc"), None);
+        registry.remove_key("photos");
+        assert_eq!(registry.missing_key("def"), Some("photos"));
+        registry.record("def", "");
+        assert_eq!(registry.key_for("def"), None);
+        assert_eq!(registry.configured().collect::<Vec<_>>(), ["backup-2024"]);
+    }
+}
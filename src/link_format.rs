@@ -0,0 +1,109 @@
+// link_format.rs - File links in the formats they get pasted into: forums,
+// chat, web pages or download tools. Optionally the link type follows the
+// file: media opens in the viewer, archives and programs download directly.
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkFormat {
+    #[default]
+    Plain,
+    Markdown,
+    BbCode,
+    Html,
+    /// Downloads the file instead of opening the file viewer
+    Direct,
+}
+
+impl LinkFormat {
+    pub const ALL: [LinkFormat; 5] = [LinkFormat::Plain, LinkFormat::Markdown, LinkFormat::BbCode, LinkFormat::Html, LinkFormat::Direct];
+
+    /// Link to file `id` on `base_url`, with `name` as the text where the format has one
+    pub fn format(self, base_url: &str, id: &str, name: &str) -> String {
+        let url = format!("{}/u/{}", base_url, id);
+        match self {
+            LinkFormat::Plain => url,
+            LinkFormat::Markdown => {
+                let text = name.replace('\\', "\\\\").replace('[', "\\[").replace(']', "\\]");
+                format!("[{}]({})", text, url)
+            }
+            LinkFormat::BbCode => format!("[url={}]{}[/url]", url, name),
+            LinkFormat::Html => {
+                let text = name.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;");
+                format!("<a href=\"{}\">{}</a>", url, text)
+            }
+            LinkFormat::Direct => format!("{}/api/file/{}?download", base_url, id),
+        }
+    }
+
+    /// Format to copy a file of `mime_type` in, direct for the `direct` categories
+    pub fn for_mime_type(self, mime_type: &str, direct: &[FileCategory]) -> LinkFormat {
+        if direct.contains(&FileCategory::of(mime_type)) { LinkFormat::Direct } else { self }
+    }
+}
+
+/// Kinds of files, going by MIME type
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileCategory {
+    Image,
+    Video,
+    Audio,
+    Document,
+    Archive,
+    /// Programs and anything else
+    Other,
+}
+
+const DOCUMENT_TYPES: &[&str] = &["application/pdf", "application/json", "application/xml", "application/msword", "application/vnd.ms-", "application/vnd.oasis.opendocument.", "application/vnd.openxmlformats-officedocument."];
+const ARCHIVE_TYPES: &[&str] = &["application/zip", "application/gzip", "application/zstd", "application/x-tar", "application/x-7z-compressed", "application/x-rar", "application/vnd.rar", "application/x-bzip2", "application/x-xz", "application/x-iso9660-image"];
+
+impl FileCategory {
+    pub const ALL: [FileCategory; 6] = [FileCategory::Image, FileCategory::Video, FileCategory::Audio, FileCategory::Document, FileCategory::Archive, FileCategory::Other];
+
+    pub fn of(mime_type: &str) -> Self {
+        let essence = mime_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+        let (kind, _) = essence.split_once('/').unwrap_or((essence.as_str(), ""));
+        match kind {
+            "image" => FileCategory::Image,
+            "video" => FileCategory::Video,
+            "audio" => FileCategory::Audio,
+            "text" => FileCategory::Document,
+            _ if DOCUMENT_TYPES.iter().any(|prefix| essence.starts_with(prefix)) => FileCategory::Document,
+            _ if ARCHIVE_TYPES.iter().any(|prefix| essence.starts_with(prefix)) => FileCategory::Archive,
+            _ => FileCategory::Other,
+        }
+    }
+}
+
+/// Archives and programs are downloaded rather than viewed
+pub fn default_direct_categories() -> Vec<FileCategory> {
+    vec![FileCategory::Archive, FileCategory::Other]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_escape_the_file_name() {
+        let base = "https://pixeldrain.com";
+        assert_eq!(LinkFormat::Plain.format(base, "abc", "a.txt"), "https://pixeldrain.com/u/abc");
+        assert_eq!(LinkFormat::Markdown.format(base, "abc", "[draft] a.txt"), "[\\[draft\\] a.txt](https://pixeldrain.com/u/abc)");
+        assert_eq!(LinkFormat::BbCode.format(base, "abc", "a.txt"), "[url=https://pixeldrain.com/u/abc]a.txt[/url]");
+        assert_eq!(LinkFormat::Html.format(base, "abc", "<b> & \"c\""), "<a href=\"https://pixeldrain.com/u/abc\">&lt;b&gt; &amp; &quot;c&quot;</a>");
+        assert_eq!(LinkFormat::Direct.format(base, "abc", "a.txt"), "https://pixeldrain.com/api/file/abc?download");
+    }
+
+    #[test]
+    fn archives_and_programs_get_direct_links() {
+        let direct = default_direct_categories();
+        assert_eq!(LinkFormat::Markdown.for_mime_type("video/mp4", &direct), LinkFormat::Markdown);
+        assert_eq!(LinkFormat::Markdown.for_mime_type("application/x-7z-compressed", &direct), LinkFormat::Direct);
+        assert_eq!(LinkFormat::Plain.for_mime_type("application/octet-stream", &direct), LinkFormat::Direct);
+        assert_eq!(LinkFormat::Plain.for_mime_type("application/pdf", &direct), LinkFormat::Plain);
+        assert_eq!(FileCategory::of("Text/Plain; charset=utf-8"), FileCategory::Document);
+        assert_eq!(FileCategory::of("application/vnd.openxmlformats-officedocument.wordprocessingml.document"), FileCategory::Document);
+        assert_eq!(FileCategory::of("image/png"), FileCategory::Image);
+    }
+}
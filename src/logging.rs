@@ -0,0 +1,243 @@
+// logging.rs - Log output of the app through tracing. Every module logs under
+// its own target, such as pixeldrain_api or pixeldrain::storage, and
+// the filter set in settings picks a level for each, like
+// "info,pixeldrain_api=debug". What passes goes to stderr, to the log
+// view of the Debug panel, and to a log file in the data folder when that's
+// turned on. The file is rotated once it gets big, keeping a few old ones.
+use std::collections::VecDeque;
+use std::fmt::{self, Write as _};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use chrono::{DateTime, Local};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{Registry, reload};
+
+pub const DEFAULT_FILTER: &str = "info";
+// Lines kept for the log view, the oldest go first
+const MAX_LINES: usize = 2000;
+// The log file is rotated once it's this big, keeping this many old ones
+const MAX_FILE_SIZE: u64 = 5 * 1024 * 1024;
+const KEPT_FILES: usize = 3;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogLine {
+    pub time: DateTime<Local>,
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+}
+
+impl fmt::Display for LogLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {:>5} {}: {}", self.time.format("%Y-%m-%d %H:%M:%S%.3f"), self.level, self.target, self.message)
+    }
+}
+
+/// Where captured lines go, shared by the layer and the functions below
+#[derive(Default)]
+struct Sink {
+    lines: Mutex<VecDeque<LogLine>>,
+    file: Mutex<Option<RotatingFile>>,
+}
+
+impl Sink {
+    fn push(&self, line: LogLine) {
+        if let Some(file) = self.file.lock().unwrap().as_mut() {
+            // Nowhere left to report it, the log view still has the line
+            let _ = file.write_line(&line.to_string());
+        }
+        let mut lines = self.lines.lock().unwrap();
+        if lines.len() == MAX_LINES {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+}
+
+/// Captures every event that passed the filter into a `Sink`
+struct Capture(Arc<Sink>);
+
+impl<S: Subscriber> Layer<S> for Capture {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut message = Message::default();
+        event.record(&mut message);
+        let metadata = event.metadata();
+        self.0.push(LogLine { time: Local::now(), level: *metadata.level(), target: metadata.target().to_string(), message: message.0 });
+    }
+}
+
+/// The message of an event, with its other fields after it as key=value
+#[derive(Default)]
+struct Message(String);
+
+impl Visit for Message {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let fields = std::mem::take(&mut self.0);
+            let _ = write!(self.0, "{:?}{}", value, fields);
+        } else {
+            let _ = write!(self.0, " {}={:?}", field.name(), value);
+        }
+    }
+}
+
+struct Logging {
+    filter: reload::Handle<Targets, Registry>,
+    sink: Arc<Sink>,
+}
+
+static LOGGING: OnceLock<Logging> = OnceLock::new();
+
+/// Send log output to stderr and the log view, with `filter` until settings
+/// are loaded. Only the first call does anything.
+pub fn init(filter: &str) {
+    let (filter, handle) = reload::Layer::new(parse_filter(filter).unwrap_or_else(|_| default_filter()));
+    let sink = Arc::new(Sink::default());
+    if LOGGING.set(Logging { filter: handle, sink: sink.clone() }).is_err() {
+        return;
+    }
+    let _ = tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer().with_writer(io::stderr))
+        .with(Capture(sink))
+        .try_init();
+}
+
+/// Targets and levels from a filter like "info,pixeldrain::storage=debug"
+pub fn parse_filter(filter: &str) -> Result<Targets, String> {
+    let filter = filter.trim();
+    if filter.is_empty() {
+        return Ok(default_filter());
+    }
+    filter.parse().map_err(|e| format!("Invalid log filter: {}", e))
+}
+
+fn default_filter() -> Targets {
+    DEFAULT_FILTER.parse().unwrap()
+}
+
+pub fn set_filter(filter: &str) -> Result<(), String> {
+    let targets = parse_filter(filter)?;
+    if let Some(logging) = LOGGING.get() {
+        logging.filter.reload(targets).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Write log lines to `log_file()` as well, or stop writing them there
+pub fn set_file_logging(enabled: bool) -> io::Result<()> {
+    let Some(logging) = LOGGING.get() else {
+        return Ok(());
+    };
+    let file = if enabled { Some(RotatingFile::open(log_file(), MAX_FILE_SIZE)?) } else { None };
+    *logging.sink.file.lock().unwrap() = file;
+    Ok(())
+}
+
+/// Lines for the log view, oldest first
+pub fn lines() -> Vec<LogLine> {
+    LOGGING.get().map(|logging| logging.sink.lines.lock().unwrap().iter().cloned().collect()).unwrap_or_default()
+}
+
+pub fn clear() {
+    if let Some(logging) = LOGGING.get() {
+        logging.sink.lines.lock().unwrap().clear();
+    }
+}
+
+pub fn log_file() -> PathBuf {
+    directories::ProjectDirs::from("com", "pixeldrain", "client")
+        .map(|proj_dirs| proj_dirs.data_dir().join("logs"))
+        .unwrap_or_else(|| PathBuf::from("logs"))
+        .join("pixeldrain.log")
+}
+
+/// A log file that's moved to .1 once it reaches `max_size`, .1 to .2 and so on
+struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_size: u64,
+}
+
+impl RotatingFile {
+    fn open(path: PathBuf, max_size: u64) -> io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(RotatingFile { path, file, size, max_size })
+    }
+
+    fn write_line(&mut self, line: &str) -> io::Result<()> {
+        let length = line.len() as u64 + 1;
+        if self.size > 0 && self.size + length > self.max_size {
+            self.rotate()?;
+        }
+        writeln!(self.file, "{}", line)?;
+        self.size += length;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        let numbered = |n: usize| -> PathBuf {
+            let mut name = self.path.as_os_str().to_owned();
+            name.push(format!(".{}", n));
+            PathBuf::from(name)
+        };
+        let _ = fs::remove_file(numbered(KEPT_FILES));
+        for n in (1..KEPT_FILES).rev() {
+            if numbered(n).exists() {
+                fs::rename(numbered(n), numbered(n + 1))?;
+            }
+        }
+        fs::rename(&self.path, numbered(1))?;
+        *self = RotatingFile::open(self.path.clone(), self.max_size)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_are_captured_filtered_and_rotated_into_files() {
+        let dir = std::env::temp_dir().join(format!("pixeldrain-logging-{}", std::process::id()));
+        let path = dir.join("test.log");
+        let sink = Arc::new(Sink::default());
+        *sink.file.lock().unwrap() = Some(RotatingFile::open(path.clone(), 120).unwrap());
+        let subscriber = tracing_subscriber::registry()
+            .with(parse_filter("warn,pixeldrain::storage=debug").unwrap())
+            .with(Capture(sink.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::debug!(target: "pixeldrain::storage", id = "abc", "Cached the file list");
+            tracing::debug!(target: "pixeldrain_api", "Left out");
+            for attempt in 1..=4 {
+                tracing::warn!(target: "pixeldrain_api", "Upload failed, attempt {}", attempt);
+            }
+        });
+
+        let lines = sink.lines.lock().unwrap().clone();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[0].message, "Cached the file list id=\"abc\"");
+        assert_eq!((lines[1].level, lines[1].target.as_str()), (Level::WARN, "pixeldrain_api"));
+
+        // Each line is about 90 bytes, so every one after the first started a new file
+        let last = fs::read_to_string(&path).unwrap();
+        assert!(last.ends_with("Upload failed, attempt 4\n"), "{}", last);
+        assert!(fs::read_to_string(dir.join("test.log.3")).unwrap().contains("attempt 1"));
+        assert!(!dir.join("test.log.4").exists(), "only {} old files are kept", KEPT_FILES);
+
+        assert!(parse_filter("pixeldrain=loud").is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
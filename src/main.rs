@@ -7,23 +7,80 @@ use eframe::{egui, App, NativeOptions};
 use egui::IconData;
 use rfd::FileDialog;
 use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::env;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{SystemTime, UNIX_EPOCH};
-use std::process::{Command, Stdio};
-use webbrowser;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::mpsc::{self, Sender, Receiver};
 
 // Embed both icons as data bytes at compile time for future use
 const LIGHT_ICON_DATA: &[u8] = include_bytes!("../assets/light-icon.png");
 const DARK_ICON_DATA: &[u8] = include_bytes!("../assets/dark-icon.png");
 
+// Thumbnail size requested from the API (must be a multiple of 16, max 128)
+const THUMBNAIL_SIZE: u32 = 96;
+// Links suggested at most while typing in the download field
+const URL_SUGGESTIONS: usize = 8;
+// Files of a multi-file upload sent at once, at most
+const MAX_UPLOAD_CONCURRENCY: usize = 8;
+// Metadata requests (file info, thumbnails, listings) sent at once, at most
+const MAX_METADATA_CONCURRENCY: usize = 16;
+// Width of a file cell in the Files tab grid view
+const GRID_CELL_WIDTH: f32 = 110.0;
+// Estimated free-tier download allowance per month (6 GB a day), adjustable in the Download tab
+const DEFAULT_MONTHLY_DOWNLOAD_ALLOWANCE: u64 = 180_000_000_000;
+// Uploads at least this big are preceded by a test upload when that's enabled
+const CHECK_BEFORE_UPLOAD_SIZE: u64 = 1_000_000_000;
+// Name of the tiny file uploaded and deleted again by that check
+const CHECK_UPLOAD_NAME: &str = "pixeldrain-upload-check.txt";
+// Personal bucket of the filesystem API, the browser can't navigate above it
+const FILESYSTEM_ROOT: &str = "/me";
+// How sessions created by logging in are named in the account's session list
+const APP_NAME: &str = "PixelDrain Desktop";
+
+/// Replace characters that are invalid in file names on common platforms
+fn sanitize_file_name(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let sanitized = sanitized.trim().trim_end_matches('.').to_string();
+    if sanitized.is_empty() { "_".to_string() } else { sanitized }
+}
+
+/// Time left of a transfer, like "2m 14s"
+fn format_remaining(remaining: Duration) -> String {
+    let seconds = remaining.as_secs();
+    match (seconds / 3600, seconds / 60 % 60, seconds % 60) {
+        (0, 0, s) => format!("{}s", s),
+        (0, m, s) => format!("{}m {}s", m, s),
+        (h, m, _) => format!("{}h {}m", h, m),
+    }
+}
+
+/// Blend every visible pixel of the icon halfway towards the tint color
+fn tint_icon(mut icon: IconData, tint: [u8; 3]) -> IconData {
+    for pixel in icon.rgba.chunks_exact_mut(4) {
+        if pixel[3] == 0 {
+            continue;
+        }
+        for (channel, tint_channel) in pixel.iter_mut().zip(tint) {
+            *channel = ((*channel as u16 + tint_channel as u16) / 2) as u8;
+        }
+    }
+    icon
+}
+
 fn icon_data_from_png(dark_mode: bool) -> Option<IconData> {
     let icon_bytes = if dark_mode { DARK_ICON_DATA } else { LIGHT_ICON_DATA };
     if let Ok(img) = image::load_from_memory(icon_bytes) {
@@ -48,24 +105,630 @@ fn set_window_icon(_native_options: &mut eframe::NativeOptions, _dark_mode: bool
     // This function is kept as a placeholder for future eframe versions
 }
 
-mod pixeldrain_api;
+mod app_data;
+mod archive;
+mod backup;
+mod billing;
+mod bundle;
+mod cache;
+mod cli;
+mod crypto;
+mod extensions;
+mod history;
+mod i18n;
+mod ipc;
+mod keys;
+mod link_format;
+mod logging;
+mod notifications;
+mod paste;
+mod phash;
+mod preflight;
+mod qr;
+mod queue;
+mod resume;
+mod secrets;
+mod split;
+mod stats;
+mod sync;
+mod settings_export;
+mod storage;
+mod power;
+mod taskbar;
+mod theme;
+#[cfg(feature = "tui")]
+mod tui;
+mod undo;
+mod units;
+mod updates;
+mod workers;
+mod watchdog;
 use pixeldrain_api::{
-    FileInfo, PixelDrainConfig, PixelDrainClient,
-    UserInfo,
+    ApiFactory, FileInfo, PixelDrainApi, PixelDrainConfig, PixelDrainClient,
+    Progress, ProgressSender, ProgressReader, SpeedMeter, UploadResponse, UserInfo, UserSession, UserTransaction,
 };
+use pixeldrain_api::diagnostics::DiagnosticsCapture;
+use pixeldrain_api::events::{self, Direction, Events, TransferOutcome};
+use archive::ArchiveFormat;
+use link_format::{FileCategory, LinkFormat};
+use history::{DownloadHistoryEntry, ExportFormat, HistoryKind, HistoryViewOptions, UploadHistoryEntry, UrlSuggestion};
+use storage::{Storage, StorageBackend};
+use i18n::t;
+use ipc::DeepLink;
+use keys::KeyRegistry;
+use power::SleepInhibitor;
+use taskbar::TaskbarProgress;
+use workers::{Limit, Workers};
+
+// How long to wait for background workers to finish when the app exits
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
 
 #[derive(Serialize, Deserialize)]
 struct AppState {
     api_key: String,
     download_location: String,
-    upload_history: Vec<UploadHistoryEntry>,
-    download_history: Vec<DownloadHistoryEntry>,
+    // Upload and download history and cached data, kept apart from the settings
+    #[serde(skip, default = "storage::in_memory")]
+    storage: Box<dyn Storage>,
+    #[serde(default)]
+    storage_backend: StorageBackend,
+    // History saved in settings.json by older versions, moved to the storage on load
+    #[serde(rename = "upload_history", default, skip_serializing)]
+    legacy_upload_history: Vec<UploadHistoryEntry>,
+    #[serde(rename = "download_history", default, skip_serializing)]
+    legacy_download_history: Vec<DownloadHistoryEntry>,
     last_error: Option<String>,
+    // Restored from the storage cache at startup, until the first refresh
+    #[serde(skip)]
     file_list: Vec<FileInfo>,
     user_info: Option<UserInfo>,
     last_operation_time: Option<DateTime<Utc>>,
     // Theme
     dark_mode: bool,
+    // High-contrast variant of the light or dark theme
+    #[serde(default)]
+    high_contrast: bool,
+    // Format of copied file links
+    #[serde(default)]
+    link_format: LinkFormat,
+    // Copy direct links for files of these categories instead, when enabled
+    #[serde(default)]
+    auto_link_type: bool,
+    #[serde(default = "link_format::default_direct_categories")]
+    direct_link_categories: Vec<FileCategory>,
+    // Archive that directory uploads are packed into
+    #[serde(default)]
+    archive_format: ArchiveFormat,
+    // Compress tar.zst archives on every core
+    #[serde(default = "default_true")]
+    parallel_compression: bool,
+    // Gitignore-style patterns left out of directory uploads, one per line
+    #[serde(default)]
+    exclude_patterns: String,
+    // Compare size and SHA-256 with the server after each upload
+    #[serde(default = "default_true")]
+    verify_uploads: bool,
+    // Upload and delete a tiny file before large uploads, to find problems with
+    // the key, quota or connection before spending an hour on the upload
+    #[serde(default)]
+    check_before_large_uploads: bool,
+    // Hash files before uploading and offer the existing link when the account has them
+    #[serde(default = "default_true")]
+    skip_duplicate_uploads: bool,
+    // Encrypt uploads with the passphrase below, and decrypt .enc downloads with it
+    #[serde(default)]
+    encrypt_uploads: bool,
+    // Key identifier that encrypted uploads are recorded under in the key registry
+    #[serde(default = "default_encryption_key_id")]
+    encryption_key_id: String,
+    // Keep the passphrase in the OS keyring, otherwise it's entered every session
+    #[serde(default)]
+    remember_encryption_passphrase: bool,
+    #[serde(skip)]
+    encryption_passphrase: String,
+    // Files of a multi-file upload that are sent at the same time
+    #[serde(default = "default_upload_concurrency")]
+    upload_concurrency: usize,
+    // Metadata requests sent at the same time, separate from transfers so a page
+    // of thumbnails doesn't hold up uploads and downloads
+    #[serde(default = "default_metadata_concurrency")]
+    metadata_concurrency: usize,
+    // Seconds without progress after which a transfer is flagged in the Debug panel
+    #[serde(default = "default_stall_timeout_secs")]
+    stall_timeout_secs: u64,
+    // Seconds a deletion waits with an Undo toast before it's sent, 0 sends it right away
+    #[serde(default = "default_delete_undo_secs")]
+    delete_undo_secs: u64,
+    // Ask before deleting files and lists
+    #[serde(default = "default_true")]
+    confirm_deletes: bool,
+    // Look for a newer release on GitHub at startup
+    #[serde(default)]
+    check_for_updates: bool,
+    // Levels to log at, per module, see logging.rs
+    #[serde(default = "default_log_filter")]
+    log_filter: String,
+    #[serde(default)]
+    log_to_file: bool,
+    // Add an extension from the MIME type to downloads whose name has none
+    #[serde(default = "default_true")]
+    infer_extensions: bool,
+    // Download lists of a split file's parts as the file itself
+    #[serde(default = "default_true")]
+    join_split_parts: bool,
+    // Files tab layout
+    #[serde(default)]
+    files_grid_view: bool,
+    // Window identity, to tell several running instances apart in the taskbar
+    #[serde(default)]
+    window_label: String,
+    #[serde(default)]
+    icon_tint: Option<[u8; 3]>,
+    // Server to talk to, for self-hosted or mirrored instances
+    #[serde(default = "default_base_url")]
+    base_url: String,
+    // UI language
+    #[serde(default)]
+    language: i18n::Language,
+    // Notify when a transfer finishes or fails, where to is set per kind below
+    #[serde(default = "default_true")]
+    desktop_notifications: bool,
+    #[serde(default)]
+    notification_sinks: notifications::NotificationSettings,
+    // Local folders synced to the account or one of its lists, each with its rules
+    #[serde(default)]
+    sync_pairs: Vec<sync::SyncPair>,
+    // Keep the computer from sleeping while transfers run
+    #[serde(default = "default_true")]
+    prevent_sleep: bool,
+    // Recent Uploads / Recent Downloads view options
+    #[serde(default)]
+    upload_history_view: HistoryViewOptions,
+    #[serde(default)]
+    download_history_view: HistoryViewOptions,
+    // Download traffic counted locally, to estimate the remaining free-tier allowance
+    #[serde(default)]
+    download_usage: DownloadUsage,
+    // Local statistics of the Stats tab, never sent anywhere
+    #[serde(default)]
+    usage_stats: stats::UsageStats,
+    #[serde(default = "default_monthly_download_allowance")]
+    monthly_download_allowance: u64,
+    // Size limit of the disk cache (thumbnails) in bytes
+    #[serde(default = "default_cache_limit")]
+    cache_limit: u64,
+    // Set by workers when settings changed, such as usage or the key registry;
+    // the UI thread persists them
+    #[serde(skip)]
+    settings_dirty: bool,
+    // Shown in the error popup until dismissed
+    #[serde(skip)]
+    error_report: Option<ErrorReport>,
+    // Difference between the last two refreshes of file_list
+    #[serde(skip)]
+    file_changes: FileListChanges,
+    // Keys that encrypted files need, by identifier
+    #[serde(default)]
+    key_registry: KeyRegistry,
+    // Named accounts, the fields above hold the active one while it is in use
+    #[serde(default)]
+    profiles: Vec<Profile>,
+    #[serde(default)]
+    active_profile: String,
+    // Keep API keys in settings.json instead of the OS keyring
+    #[serde(default)]
+    plaintext_api_key: bool,
+    // API keys currently in the keyring, by profile name
+    #[serde(skip)]
+    keyring_keys: HashMap<String, String>,
+    // The server refused the API key (401), flagged on the Settings tab
+    #[serde(skip)]
+    api_key_rejected: bool,
+    // Uploads stopped because the key was refused, started again once it's replaced
+    #[serde(skip)]
+    paused_uploads: Vec<PathBuf>,
+}
+
+/// Account settings saved under a name, e.g. "Personal" and "Work"
+#[derive(Clone, Serialize, Deserialize)]
+struct Profile {
+    name: String,
+    api_key: String,
+    download_location: String,
+    #[serde(default = "default_true")]
+    verify_uploads: bool,
+    #[serde(default = "default_true")]
+    desktop_notifications: bool,
+    #[serde(default)]
+    window_label: String,
+    #[serde(default)]
+    icon_tint: Option<[u8; 3]>,
+    #[serde(default = "default_base_url")]
+    base_url: String,
+    #[serde(default)]
+    key_registry: KeyRegistry,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_upload_concurrency() -> usize {
+    1
+}
+
+fn default_metadata_concurrency() -> usize {
+    4
+}
+
+fn default_stall_timeout_secs() -> u64 {
+    watchdog::DEFAULT_STALL_SECS
+}
+
+fn default_delete_undo_secs() -> u64 {
+    undo::DEFAULT_UNDO_SECS
+}
+
+fn default_log_filter() -> String {
+    logging::DEFAULT_FILTER.to_string()
+}
+
+fn default_encryption_key_id() -> String {
+    "default".to_string()
+}
+
+fn default_base_url() -> String {
+    pixeldrain_api::BASE_URL.to_string()
+}
+
+fn default_monthly_download_allowance() -> u64 {
+    DEFAULT_MONTHLY_DOWNLOAD_ALLOWANCE
+}
+
+fn default_cache_limit() -> u64 {
+    cache::DEFAULT_LIMIT
+}
+
+/// Download traffic of the current month as counted by this app
+#[derive(Clone, Default, Serialize, Deserialize)]
+struct DownloadUsage {
+    month: String, // "YYYY-MM", counters start over when it changes
+    bytes: u64,
+    rate_limited: u32, // Downloads refused with 429 Too Many Requests
+    #[serde(default)]
+    server: Option<ServerTransferLimit>,
+}
+
+/// Transfer limit for this IP as last reported by misc/rate_limits
+#[derive(Clone, Serialize, Deserialize)]
+struct ServerTransferLimit {
+    limit: i64,
+    used: i64,
+    checked: DateTime<Utc>,
+}
+
+impl DownloadUsage {
+    fn roll_over(&mut self) {
+        let month = chrono::Local::now().format("%Y-%m").to_string();
+        if self.month != month {
+            *self = DownloadUsage {
+                month,
+                server: self.server.take(),
+                ..Default::default()
+            };
+        }
+    }
+
+    fn record(&mut self, bytes: u64) {
+        self.roll_over();
+        self.bytes += bytes;
+    }
+
+    fn record_error(&mut self, error: &pixeldrain_api::PixelDrainError) {
+        if let pixeldrain_api::PixelDrainError::RateLimited { .. } = error {
+            self.roll_over();
+            self.rate_limited += 1;
+        }
+    }
+}
+
+/// Files added, modified or deleted on the account between two refreshes,
+/// e.g. by uploads from another device
+#[derive(Clone, Default)]
+struct FileListChanges {
+    added: HashSet<String>,
+    changed: HashSet<String>,
+    removed: Vec<FileInfo>,
+}
+
+impl FileListChanges {
+    fn between(old: &[FileInfo], new: &[FileInfo]) -> Self {
+        let old_by_id: HashMap<&str, &FileInfo> = old.iter().map(|f| (f.id.as_str(), f)).collect();
+        let new_ids: HashSet<&str> = new.iter().map(|f| f.id.as_str()).collect();
+        let mut changes = Self::default();
+        for file in new {
+            match old_by_id.get(file.id.as_str()) {
+                None => {
+                    changes.added.insert(file.id.clone());
+                }
+                Some(previous) if previous.name != file.name || previous.size != file.size || previous.hash_sha256 != file.hash_sha256 => {
+                    changes.changed.insert(file.id.clone());
+                }
+                Some(_) => {}
+            }
+        }
+        changes.removed = old.iter().filter(|f| !new_ids.contains(f.id.as_str())).cloned().collect();
+        changes
+    }
+
+    fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Embed domains and file viewer branding being edited, taken from the user
+/// info of `username` so switching accounts starts over
+#[derive(Clone, Debug, PartialEq)]
+struct SharingSettingsForm {
+    username: String,
+    embed_domains: String,
+    branding: HashMap<String, String>,
+}
+
+impl SharingSettingsForm {
+    fn from_user(user: &UserInfo) -> Self {
+        let branding = pixeldrain_api::BRANDING_FIELDS
+            .iter()
+            .map(|field| {
+                let value = user.file_viewer_branding.as_ref().and_then(|branding| branding.get(*field)).cloned();
+                (field.to_string(), value.unwrap_or_default())
+            })
+            .collect();
+        Self { username: user.username.clone(), embed_domains: user.file_embed_domains.clone(), branding }
+    }
+}
+
+/// Technical details of a failed operation for the error popup
+#[derive(Clone)]
+struct ErrorReport {
+    context: String,
+    message: String,
+    status: Option<reqwest::StatusCode>,
+    endpoint: Option<String>,
+    request_id: Option<String>,
+    timestamp: DateTime<Utc>,
+}
+
+impl ErrorReport {
+    fn new(context: &str, error: &pixeldrain_api::PixelDrainError) -> Self {
+        let (status, endpoint, request_id) = match (error.api_error(), error) {
+            (Some(api_error), _) => (Some(api_error.status), api_error.endpoint.clone(), api_error.request_id.clone()),
+            (None, pixeldrain_api::PixelDrainError::Reqwest(reqwest_error)) => {
+                (reqwest_error.status(), reqwest_error.url().map(|url| url.path().to_string()), None)
+            }
+            _ => (None, None, None),
+        };
+        Self {
+            context: context.to_string(),
+            message: error.to_string(),
+            status,
+            endpoint,
+            request_id,
+            timestamp: Utc::now(),
+        }
+    }
+
+    /// Plain explanation of what usually causes this kind of error
+    fn hint(&self) -> Option<String> {
+        let status = self.status?;
+        Some(t!(match status.as_u16() {
+            401 | 403 => "error-hint-unauthorized",
+            404 => "error-hint-not-found",
+            413 => "error-hint-too-large",
+            429 => "error-hint-rate-limited",
+            500..=599 => "error-hint-server",
+            _ => return None,
+        }))
+    }
+
+    /// Markdown snippet ready to paste into a bug report
+    fn bug_report(&self) -> String {
+        let optional = |value: &Option<String>| value.clone().unwrap_or_else(|| "-".to_string());
+        format!(
+            "**{}**\n\n\
+             - Status: {}\n\
+             - Endpoint: {}\n\
+             - Request ID: {}\n\
+             - Time: {}\n\
+             - App: PixelDrain {} ({} {})\n\n\
+             ```\n{}\n```\n",
+            self.context,
+            self.status.map(|s| s.to_string()).unwrap_or_else(|| "-".to_string()),
+            optional(&self.endpoint),
+            optional(&self.request_id),
+            self.timestamp.format("%Y-%m-%d %H:%M:%S UTC"),
+            env!("CARGO_PKG_VERSION"),
+            env::consts::OS,
+            env::consts::ARCH,
+            self.message,
+        )
+    }
+}
+
+impl AppState {
+    /// Format of copied links and the file categories copied as direct links instead
+    fn copied_link_formats(&self) -> (LinkFormat, Vec<FileCategory>) {
+        let direct = if self.auto_link_type { self.direct_link_categories.clone() } else { Vec::new() };
+        (self.link_format, direct)
+    }
+
+    fn record_upload(&mut self, entry: UploadHistoryEntry) {
+        if let Err(e) = self.storage.add_upload(&entry) {
+            warn!("Failed to save upload history entry for {}: {}", entry.filename, e);
+        }
+    }
+
+    fn record_download(&mut self, entry: DownloadHistoryEntry) {
+        if let Err(e) = self.storage.add_download(&entry) {
+            warn!("Failed to save download history entry for {}: {}", entry.filename, e);
+        }
+    }
+
+    /// Record a failed operation for the inline error label and the error popup
+    fn report_error(&mut self, context: &str, error: &pixeldrain_api::PixelDrainError) {
+        self.last_error = Some(format!("{}: {}", context, error));
+        self.error_report = Some(ErrorReport::new(context, error));
+        self.note_auth_failure(error);
+    }
+
+    /// Remember when a request failed because the API key was refused
+    fn note_auth_failure(&mut self, error: &pixeldrain_api::PixelDrainError) {
+        if error.is_unauthorized() {
+            self.api_key_rejected = true;
+        }
+    }
+
+    /// Store a freshly fetched file list and remember what changed since the previous one.
+    /// The first load after a profile switch has nothing to compare against.
+    fn replace_file_list(&mut self, files: Vec<FileInfo>) {
+        self.file_changes = if self.file_list.is_empty() {
+            FileListChanges::default()
+        } else {
+            FileListChanges::between(&self.file_list, &files)
+        };
+        self.file_list = files;
+        let cached = serde_json::to_string(&self.file_list).map_err(storage::StorageError::from);
+        if let Err(e) = cached.and_then(|json| self.storage.cache_put(&self.file_list_cache_key(), &json)) {
+            warn!("Failed to cache the file list: {}", e);
+        }
+    }
+
+    /// File lists are cached per server and profile
+    fn file_list_cache_key(&self) -> String {
+        format!("file_list {} {}", self.base_url, self.active_profile)
+    }
+
+    /// Show the file list of the last session until it is refreshed
+    fn restore_cached_file_list(&mut self) {
+        if let Ok(Some(json)) = self.storage.cache_get(&self.file_list_cache_key())
+            && let Ok(files) = serde_json::from_str(&json)
+        {
+            self.file_list = files;
+        }
+    }
+
+    /// Create the "Default" profile from the current settings when none exist yet
+    fn ensure_profile(&mut self) {
+        if self.profiles.is_empty() {
+            self.active_profile = "Default".to_string();
+            self.profiles.push(self.current_profile());
+        } else if !self.profiles.iter().any(|p| p.name == self.active_profile) {
+            let first = self.profiles[0].clone();
+            self.apply_profile(&first);
+        }
+    }
+
+    fn current_profile(&self) -> Profile {
+        Profile {
+            name: self.active_profile.clone(),
+            api_key: self.api_key.clone(),
+            download_location: self.download_location.clone(),
+            verify_uploads: self.verify_uploads,
+            desktop_notifications: self.desktop_notifications,
+            window_label: self.window_label.clone(),
+            icon_tint: self.icon_tint,
+            base_url: self.base_url.clone(),
+            key_registry: self.key_registry.clone(),
+        }
+    }
+
+    fn apply_profile(&mut self, profile: &Profile) {
+        self.active_profile = profile.name.clone();
+        self.api_key = profile.api_key.clone();
+        self.download_location = profile.download_location.clone();
+        self.verify_uploads = profile.verify_uploads;
+        self.desktop_notifications = profile.desktop_notifications;
+        self.window_label = profile.window_label.clone();
+        self.icon_tint = profile.icon_tint;
+        self.base_url = profile.base_url.clone();
+        self.key_registry = profile.key_registry.clone();
+    }
+
+    /// Copy the current settings into the active profile before saving or switching
+    fn sync_active_profile(&mut self) {
+        let current = self.current_profile();
+        if let Some(profile) = self.profiles.iter_mut().find(|p| p.name == current.name) {
+            *profile = current;
+        }
+    }
+
+    /// Make `name` the active profile, dropping data that belonged to the previous account
+    fn switch_profile(&mut self, name: &str) -> bool {
+        let Some(profile) = self.profiles.iter().find(|p| p.name == name).cloned() else {
+            return false;
+        };
+        self.sync_active_profile();
+        self.apply_profile(&profile);
+        self.user_info = None;
+        self.file_list.clear();
+        self.file_changes = FileListChanges::default();
+        self.restore_cached_file_list();
+        self.last_error = None;
+        true
+    }
+
+    /// Add a profile with default preferences and no API key, and switch to it
+    fn add_profile(&mut self, name: &str) -> Result<(), String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(t!("profile-name-empty"));
+        }
+        if self.profiles.iter().any(|p| p.name == name) {
+            return Err(t!("profile-exists", name = name));
+        }
+        self.profiles.push(Profile {
+            name: name.to_string(),
+            api_key: String::new(),
+            download_location: PixelDrainApp::get_default_download_location(),
+            verify_uploads: true,
+            desktop_notifications: true,
+            window_label: name.to_string(),
+            icon_tint: None,
+            base_url: default_base_url(),
+            key_registry: KeyRegistry::default(),
+        });
+        self.switch_profile(name);
+        Ok(())
+    }
+
+    /// Fill in the API keys kept in the OS keyring. Returns true when a key was
+    /// found in settings.json instead, so it should be moved to the keyring.
+    fn load_keyring_keys(&mut self, secrets: &secrets::SecretStore) -> bool {
+        if self.plaintext_api_key {
+            return false;
+        }
+        for profile in &mut self.profiles {
+            if profile.api_key.is_empty()
+                && let Some(key) = secrets.load_api_key(&profile.name)
+            {
+                self.keyring_keys.insert(profile.name.clone(), key.clone());
+                profile.api_key = key;
+            }
+        }
+        if let Some(active) = self.profiles.iter().find(|p| p.name == self.active_profile) {
+            self.api_key = active.api_key.clone();
+        }
+        self.profiles
+            .iter()
+            .any(|p| !p.api_key.is_empty() && self.keyring_keys.get(&p.name) != Some(&p.api_key))
+    }
+
+    /// Remove an inactive profile
+    fn remove_profile(&mut self, name: &str) {
+        if name != self.active_profile {
+            self.profiles.retain(|p| p.name != name);
+        }
+    }
 }
 
 impl Default for AppState {
@@ -73,32 +736,113 @@ impl Default for AppState {
         Self {
             api_key: String::new(),
             download_location: PixelDrainApp::get_default_download_location(),
-            upload_history: Vec::new(),
-            download_history: Vec::new(),
+            storage: storage::in_memory(),
+            storage_backend: StorageBackend::default(),
+            legacy_upload_history: Vec::new(),
+            legacy_download_history: Vec::new(),
             last_error: None,
             file_list: Vec::new(),
             user_info: None,
             last_operation_time: None,
             dark_mode: false,
+            high_contrast: false,
+            link_format: LinkFormat::default(),
+            auto_link_type: false,
+            direct_link_categories: link_format::default_direct_categories(),
+            archive_format: ArchiveFormat::default(),
+            parallel_compression: true,
+            exclude_patterns: String::new(),
+            verify_uploads: true,
+            check_before_large_uploads: false,
+            skip_duplicate_uploads: true,
+            encrypt_uploads: false,
+            encryption_key_id: default_encryption_key_id(),
+            remember_encryption_passphrase: false,
+            encryption_passphrase: String::new(),
+            upload_concurrency: default_upload_concurrency(),
+            metadata_concurrency: default_metadata_concurrency(),
+            stall_timeout_secs: default_stall_timeout_secs(),
+            delete_undo_secs: default_delete_undo_secs(),
+            confirm_deletes: true,
+            check_for_updates: false,
+            log_filter: default_log_filter(),
+            log_to_file: false,
+            key_registry: KeyRegistry::default(),
+            infer_extensions: true,
+            join_split_parts: true,
+            files_grid_view: false,
+            window_label: String::new(),
+            icon_tint: None,
+            base_url: default_base_url(),
+            language: i18n::Language::default(),
+            desktop_notifications: true,
+            notification_sinks: notifications::NotificationSettings::default(),
+            sync_pairs: Vec::new(),
+            prevent_sleep: true,
+            upload_history_view: HistoryViewOptions::default(),
+            download_history_view: HistoryViewOptions::default(),
+            download_usage: DownloadUsage::default(),
+            usage_stats: stats::UsageStats::default(),
+            monthly_download_allowance: DEFAULT_MONTHLY_DOWNLOAD_ALLOWANCE,
+            cache_limit: cache::DEFAULT_LIMIT,
+            settings_dirty: false,
+            error_report: None,
+            file_changes: FileListChanges::default(),
+            profiles: Vec::new(),
+            active_profile: String::new(),
+            plaintext_api_key: false,
+            keyring_keys: HashMap::new(),
+            api_key_rejected: false,
+            paused_uploads: Vec::new(),
         }
     }
 }
 
-#[derive(Clone, Serialize, Deserialize)]
-struct UploadHistoryEntry {
+#[derive(Serialize)]
+struct BundleManifest {
+    created: DateTime<Utc>,
+    file_count: usize,
+    files: Vec<BundleManifestEntry>,
+}
+
+#[derive(Serialize)]
+struct BundleManifestEntry {
     id: String,
-    url: String,
-    filename: String,
+    name: String,
+    path: String, // Location inside the archive
     size: u64,
-    timestamp: DateTime<Utc>,
+    mime_type: String,
+    hash_sha256: String,
+    url: String,
+    date_upload: DateTime<Utc>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
-struct DownloadHistoryEntry {
-    url: String,
-    filename: String,
-    local_path: String,
-    timestamp: DateTime<Utc>,
+// Most entries loaded into a history view, older ones are found by searching
+const HISTORY_VIEW_LIMIT: usize = 500;
+
+/// Start of the local day written as YYYY-MM-DD in `text`, or of the day after
+/// it for the exclusive end of a range. None when `text` is empty.
+fn history_day_bound(text: &str, day_after: bool) -> Result<Option<DateTime<Utc>>, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    let date = chrono::NaiveDate::parse_from_str(text, "%Y-%m-%d").map_err(|_| text.to_string())?;
+    let date = if day_after { date.succ_opt().ok_or_else(|| text.to_string())? } else { date };
+    Ok(date
+        .and_hms_opt(0, 0, 0)
+        .and_then(|midnight| midnight.and_local_timezone(chrono::Local).earliest())
+        .map(|start| start.with_timezone(&Utc)))
+}
+
+/// Day heading to show before the entry at `index`, when it starts a new day
+fn history_day_heading<T>(view: &[(T, usize)], index: usize, timestamp: impl Fn(&T) -> DateTime<Utc>) -> Option<String> {
+    let day = |entry: &T| timestamp(entry).with_timezone(&chrono::Local).date_naive();
+    let current = day(&view[index].0);
+    if index > 0 && day(&view[index - 1].0) == current {
+        return None;
+    }
+    Some(current.format("%A, %Y-%m-%d").to_string())
 }
 
 struct PixelDrainApp {
@@ -106,22 +850,88 @@ struct PixelDrainApp {
     tab: Tab,
     // Upload
     upload_progress: Arc<Mutex<f32>>,
+    // Bytes, speed and time left of the running upload, None until it reports them
+    upload_rate: Arc<Mutex<Option<Progress>>>,
     upload_file: Option<PathBuf>,
     upload_custom_filename: String,
+    // Applies to the next upload only, cleared once it starts
+    upload_anonymously: bool,
+    // List the uploads are added to when they finish, by ID
+    upload_target_list: Option<String>,
+    // Index page to write for the next multi-file upload, which then goes into a new list
+    upload_bundle: Option<PathBuf>,
+    // ID and name of each file of the last multi-file upload, offered to be made a list
+    last_batch: Arc<Mutex<Vec<(String, String)>>>,
+    batch_list_title: String,
+    // Existing list to add the batch to instead, None for a new one
+    batch_list_target: Option<String>,
+    batch_list_running: Arc<Mutex<bool>>,
+    // Link of the list, or why it failed
+    batch_list_result: Arc<Mutex<Option<Result<String, String>>>>,
+    // Record network diagnostics for the next transfer, and where the last recording went
+    capture_diagnostics: bool,
+    diagnostics_saved: Arc<Mutex<Option<Result<PathBuf, String>>>>,
     upload_files: Vec<PathBuf>, // Multiple files for upload
+    // Names typed for files of a multi-file upload, empty keeps the file's own
+    upload_file_names: HashMap<PathBuf, String>,
     upload_directory: Option<PathBuf>, // Directory for upload
     upload_directory_name: String, // Custom name for directory archive
     upload_thread_running: Arc<Mutex<bool>>,
+    upload_prepare_progress: Arc<Mutex<Option<f32>>>, // Some while batch files are being hashed
+    upload_checking: Arc<Mutex<bool>>, // True during the test upload before a large upload
+    // Name and progress of each file of the running multi-file upload
+    upload_batch_progress: Arc<Mutex<Vec<(String, f32)>>>,
+    // Files the last upload left out because the account has them already
+    upload_duplicates: Arc<Mutex<Vec<(PathBuf, FileInfo)>>>,
+    // Upload the next files even if the account has them, cleared once it starts
+    upload_duplicates_allowed: bool,
+    // File bigger than the account may upload, with the limit, offered to be split into parts
+    upload_split_offer: Option<(PathBuf, u64)>,
+    // Why the last upload would go over the account's quota, started anyway on request
+    upload_quota_warning: Option<(String, HeldUpload)>,
+    // Start the next upload despite the quota, cleared once it starts
+    upload_quota_confirmed: bool,
+    // Order of the selected batch, chosen per batch
+    upload_order: UploadOrder,
+    // Files the last upload left out
+    upload_skipped: Arc<Mutex<Vec<preflight::Skipped>>>,
+    // Files and size of the selected directory after exclusions, with the
+    // directory and patterns it was counted for
+    directory_preview: Option<(PathBuf, String, usize, u64)>,
     // Download
     download_url: String,
+    // Links matching what's typed in the download field, empty when closed
+    download_suggestions: Vec<UrlSuggestion>,
     download_progress: Arc<Mutex<f32>>,
+    download_rate: Arc<Mutex<Option<Progress>>>,
     download_thread_running: Arc<Mutex<bool>>,
+    download_status: Arc<Mutex<Option<String>>>, // Per-file status while downloading a list
+    // Key the last download was encrypted with when it isn't configured
+    download_missing_key: Option<String>,
     // Settings input state
     settings_api_key: String,
+    // The API key field is masked unless this is set
+    show_api_key: bool,
+    // Login form of the API key setting, the password is never stored
+    login_username: String,
+    login_password: String,
+    login_pending: Arc<Mutex<bool>>,
+    // API key the last login got, or why it failed, until the UI takes it
+    login_result: Arc<Mutex<Option<Result<String, String>>>>,
+    settings_base_url: String,
     settings_download_location: String,
+    new_profile_name: String,
+    export_include_api_keys: bool,
+    export_passphrase: String,
+    settings_transfer_status: Option<Result<String, String>>,
+    // History tab
+    history_kind: HistoryKind,
+    history_options: HistoryViewOptions,
+    history_from: String,
+    history_to: String,
+    history_export_status: Option<Result<String, String>>,
+    stats_export_status: Option<Result<String, String>>,
     // UI State
-    show_error: bool,
-    error_message: String,
     lists: Arc<Mutex<Vec<pixeldrain_api::ListInfo>>>,
     selected_list_id: Option<String>,
     new_list_title: String,
@@ -130,6 +940,11 @@ struct PixelDrainApp {
     // Add fields for editing
     edit_list_title: String,
     edit_list_files: Vec<pixeldrain_api::ListFile>,
+    // Names of the files in the list being edited, by ID
+    edit_list_names: HashMap<String, String>,
+    // Files and descriptions of the selected list once fetched, or why that failed
+    list_details: Arc<Mutex<Option<Result<pixeldrain_api::DetailedListInfo, String>>>>,
+    list_details_loading: Arc<Mutex<bool>>,
     // Loading states
     files_loading: Arc<Mutex<bool>>,
     file_delete_loading: Arc<Mutex<bool>>,
@@ -138,18 +953,227 @@ struct PixelDrainApp {
     list_update_loading: Arc<Mutex<bool>>,
     list_delete_loading: Arc<Mutex<bool>>,
     user_info_loading: Arc<Mutex<bool>>,
+    // Embed domains and branding of the account being edited, for Pro subscriptions
+    sharing_form: Option<SharingSettingsForm>,
+    sharing_saving: Arc<Mutex<bool>>,
+    hotlinking_saving: Arc<Mutex<bool>>,
+    // Sessions of the account, loaded when their section is opened
+    sessions: Arc<Mutex<Option<Vec<UserSession>>>>,
+    sessions_loading: Arc<Mutex<bool>>,
+    // Key of the session of this app, asked to confirm before it's revoked
+    session_revoke_confirm: Option<String>,
+    // New API key typed when the old one was refused, and whether that prompt was put off
+    reauth_key: String,
+    reauth_dismissed: bool,
     thumbnail_cache: HashMap<String, egui::TextureHandle>,
     thumbnail_loading: HashMap<String, bool>,
     thumbnail_rx: Option<Receiver<(String, Vec<u8>)>>,
     thumbnail_tx: Option<Sender<(String, Vec<u8>)>>,
+    // Size of the disk cache shown in Settings, measured again when None
+    cache_usage: Option<(u64, u64)>, // (cache, transfer journal) bytes
+    // Link shown as a QR code in a window, with its texture once created
+    qr_link: Option<String>,
+    qr_texture: Option<(String, egui::TextureHandle)>,
+    // Files tab search/filter/sort
+    file_search: String,
+    file_mime_filter: Option<String>,
+    file_sort: FileSort,
+    file_sort_ascending: bool,
+    // File IDs already shown in the Files tab, None until the first list arrives
+    seen_file_ids: Option<HashSet<String>>,
+    // Files tab multi-selection
+    selected_files: HashSet<String>,
+    // File shown in the Files tab details pane
+    file_details: Option<String>,
+    // ID of the file in the details pane and the type to upload it again with
+    fix_type: Option<(String, String)>,
+    fix_type_delete_original: bool,
+    fix_type_running: Arc<Mutex<bool>>,
+    // Link of the new file, or why it failed
+    fix_type_result: Arc<Mutex<Option<Result<String, String>>>>,
+    // ID of the file in the details pane and the key identifier typed for it
+    file_key: Option<(String, String)>,
+    // Key identifier being added in Settings
+    new_key_id: String,
+    files_zip_progress: Arc<Mutex<Option<f32>>>, // Some while a ZIP bundle is being built
+    files_zip_status: Arc<Mutex<Option<String>>>,
+    batch_delete_progress: Arc<Mutex<Option<(usize, usize)>>>, // (done, total) while deleting a selection
+    pending_deletes: undo::PendingDeletes, // Deletions waiting out their Undo toast
+    // Transfers written down while they run, to be resumed after a restart
+    journal: resume::Journal,
+    // Where the settings are saved and the API keys kept, elsewhere in tests
+    settings_path: PathBuf,
+    secrets: secrets::SecretStore,
+    // Transfers the last run left unfinished, offered until the offer is answered
+    interrupted: Vec<resume::Transfer>,
+    // Interrupted transfers to start again once nothing of their kind is running
+    resuming: Vec<resume::Transfer>,
+    // Newer release found by the update check, shown in the About tab
+    available_update: Arc<Mutex<Option<updates::Update>>>,
+    delete_confirm: Option<undo::Deletion>, // Deletion asked about in the confirmation dialog
+    delete_confirm_dont_ask: bool,
+    log_level: tracing::Level, // Least severe level shown in the log view
+    log_search: String,
+    // Files downloaded at once by an account backup
+    account_export_concurrency: usize,
+    account_export_progress: Arc<Mutex<Option<(usize, usize)>>>, // (done, total) while backing up the account
+    account_export_result: Arc<Mutex<Option<Result<backup::Summary, String>>>>,
+    sync_selected: usize, // Index of the sync pair shown
+    sync_plan: Arc<Mutex<Option<Result<sync::Plan, String>>>>, // Last dry run or sync of the shown pair
+    sync_progress: Arc<Mutex<Option<(usize, usize)>>>, // (done, total) changes while syncing
+    sync_report: Arc<Mutex<Option<sync::Report>>>,
+    // Images that look the same, file IDs oldest first, None until a search ran
+    visual_duplicates: Arc<Mutex<Option<Vec<Vec<String>>>>>,
+    visual_duplicates_progress: Arc<Mutex<Option<(usize, usize)>>>, // (hashed, total) while searching
+    // Last title/icon tint sent to the window, so viewport commands are only sent on change
+    applied_window_identity: Option<(String, Option<[u8; 3]>)>,
+    // Private sharing dialog for filesystem paths
+    share_dialog_open: bool,
+    share_path: String,
+    share_password: String,
+    share_loading: Arc<Mutex<bool>>,
+    share_result: Arc<Mutex<Option<Result<String, String>>>>, // Share link or error message
+    // Background worker threads, joined on exit
+    workers: Workers,
+    // Metadata requests running at once, sized by the setting
+    metadata_limit: Limit,
+    // Creates the API client for each worker
+    api_factory: ApiFactory,
+    // Started, progress, finished and failed events of every transfer
+    events: Arc<Events>,
+    // Delivers notifications, and holds the toasts shown in the window
+    notifier: notifications::Notifier,
+    // Heartbeats of the running transfers, for the Debug panel
+    watchdog: watchdog::Watchdog,
+    // Transfer progress shown on the taskbar / dock
+    taskbar: TaskbarProgress,
+    // Keeps the system awake while transfers run
+    sleep_inhibitor: SleepInhibitor,
+    // Put the computer to sleep when the running transfers are done, asked for each time
+    sleep_when_done: bool,
+    // Whether a transfer was running in the last frame
+    was_transferring: bool,
+    // Uploads and downloads as one queue, for its time left and a summary when it's done
+    queue_meter: queue::QueueMeter,
+    queue_summary: Option<(String, Instant)>,
+    // Filesystem browser state
+    fs_path: String,
+    fs_listing: Arc<Mutex<Option<pixeldrain_api::FilesystemPath>>>,
+    fs_loading: Arc<Mutex<bool>>,
+    fs_error: Arc<Mutex<Option<String>>>,
+    fs_transfer_progress: Arc<Mutex<Option<f32>>>,
+    fs_new_folder: String,
+    fs_rename: Option<(String, String)>, // (node path, new name)
+    // Billing tab, loaded the first time it's shown
+    transactions: Arc<Mutex<Option<Vec<UserTransaction>>>>,
+    transactions_loading: Arc<Mutex<bool>>,
+    transactions_error: Arc<Mutex<Option<String>>>,
+    // pixeldrain:// links forwarded by later launches
+    deep_links: Option<Receiver<String>>,
+    // Upload selected by a deep link, waiting for the user to confirm it
+    link_upload_pending: bool,
+}
+
+#[derive(PartialEq, Clone, Copy)]
+enum FileSort {
+    Name,
+    Size,
+    Views,
+    Downloads,
+    UploadDate,
+}
+
+impl FileSort {
+    const ALL: [FileSort; 5] = [
+        FileSort::Name,
+        FileSort::Size,
+        FileSort::Views,
+        FileSort::Downloads,
+        FileSort::UploadDate,
+    ];
+
+    fn label(self) -> String {
+        t!(match self {
+            FileSort::Name => "sort-name",
+            FileSort::Size => "sort-size",
+            FileSort::Views => "sort-views",
+            FileSort::Downloads => "sort-downloads",
+            FileSort::UploadDate => "sort-upload-date",
+        })
+    }
+}
+
+/// Order the files of a batch are uploaded in
+#[derive(PartialEq, Clone, Copy, Default)]
+enum UploadOrder {
+    #[default]
+    AsSelected,
+    // Links to the small files are ready quickly
+    SmallestFirst,
+    // The long uploads run first, for unattended batches
+    LargestFirst,
+}
+
+impl UploadOrder {
+    const ALL: [UploadOrder; 3] = [UploadOrder::AsSelected, UploadOrder::SmallestFirst, UploadOrder::LargestFirst];
+
+    fn label(self) -> String {
+        t!(match self {
+            UploadOrder::AsSelected => "upload-order-as-selected",
+            UploadOrder::SmallestFirst => "upload-order-smallest-first",
+            UploadOrder::LargestFirst => "upload-order-largest-first",
+        })
+    }
+
+    /// Sort `paths` by file size. The sort is stable, equal sizes keep the order they were selected in.
+    fn apply(self, paths: &mut [PathBuf]) {
+        let size = |path: &PathBuf| path.metadata().map(|m| m.len()).unwrap_or(0);
+        match self {
+            UploadOrder::AsSelected => {}
+            UploadOrder::SmallestFirst => paths.sort_by_cached_key(size),
+            UploadOrder::LargestFirst => paths.sort_by_cached_key(|path| std::cmp::Reverse(size(path))),
+        }
+    }
+}
+
+/// Overall progress of a transfer made of several files, from 0.0 to 1.0
+#[derive(Clone)]
+struct ProgressReport {
+    transfer: events::TransferProgress,
+    report: Arc<dyn Fn(f32) + Send + Sync>,
+}
+
+impl ProgressReport {
+    fn new(transfer: events::TransferProgress, report: impl Fn(f32) + Send + Sync + 'static) -> Self {
+        Self { transfer, report: Arc::new(report) }
+    }
+
+    /// Sender for file `index` of `total`, its progress is that share of the whole
+    fn part(&self, index: usize, total: usize) -> ProgressSender {
+        let report = self.report.clone();
+        self.transfer.sender(move |p: Progress| report((index as f32 + p.fraction) / total as f32))
+    }
+}
+
+/// Upload held back because it would go over the account's quota
+#[derive(Clone, Debug, PartialEq)]
+enum HeldUpload {
+    File(PathBuf),
+    Files(Vec<PathBuf>),
+    Directory(PathBuf),
 }
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Default)]
 enum Tab {
+    #[default]
     Upload,
     Download,
     List,
     Lists, // New Lists tab
+    Filesystem,
+    History,
+    Stats,
+    Billing,
     Settings,
     About,
 }
@@ -158,32 +1182,78 @@ enum Tab {
 
 
 
-impl Default for Tab {
+
+impl Default for PixelDrainApp {
     fn default() -> Self {
-        Tab::Upload
+        Self::with_settings(Self::default_settings_path(), secrets::SecretStore::Keyring)
     }
 }
 
-impl Default for PixelDrainApp {
-    fn default() -> Self {
+impl PixelDrainApp {
+    /// The app with the settings saved at `settings_path` and the API keys kept in `secrets`
+    fn with_settings(settings_path: PathBuf, secrets: secrets::SecretStore) -> Self {
         let (tx, rx) = mpsc::channel();
+        let state = Arc::new(Mutex::new(AppState::default()));
+        let notifier = notifications::Notifier::default();
+        let watchdog = watchdog::Watchdog::default();
         let mut app = Self {
-            state: Arc::new(Mutex::new(AppState::default())),
+            state: state.clone(),
             tab: Tab::default(),
             upload_progress: Arc::new(Mutex::new(0.0)),
+            upload_rate: Arc::new(Mutex::new(None)),
             upload_file: None,
             upload_custom_filename: String::new(),
+            upload_anonymously: false,
+            upload_target_list: None,
+            upload_bundle: None,
+            last_batch: Arc::new(Mutex::new(Vec::new())),
+            batch_list_title: String::new(),
+            batch_list_target: None,
+            batch_list_running: Arc::new(Mutex::new(false)),
+            batch_list_result: Arc::new(Mutex::new(None)),
+            capture_diagnostics: false,
+            diagnostics_saved: Arc::new(Mutex::new(None)),
             upload_files: Vec::new(),
+            upload_file_names: HashMap::new(),
             upload_directory: None,
             upload_directory_name: String::new(),
             upload_thread_running: Arc::new(Mutex::new(false)),
+            upload_prepare_progress: Arc::new(Mutex::new(None)),
+            upload_checking: Arc::new(Mutex::new(false)),
+            upload_batch_progress: Arc::new(Mutex::new(Vec::new())),
+            upload_duplicates: Arc::new(Mutex::new(Vec::new())),
+            upload_duplicates_allowed: false,
+            upload_split_offer: None,
+            upload_quota_warning: None,
+            upload_quota_confirmed: false,
+            upload_order: UploadOrder::default(),
+            upload_skipped: Arc::new(Mutex::new(Vec::new())),
+            directory_preview: None,
             download_url: String::new(),
+            download_suggestions: Vec::new(),
             download_progress: Arc::new(Mutex::new(0.0)),
+            download_rate: Arc::new(Mutex::new(None)),
             download_thread_running: Arc::new(Mutex::new(false)),
+            download_status: Arc::new(Mutex::new(None)),
+            download_missing_key: None,
             settings_api_key: String::new(),
+            show_api_key: false,
+            login_username: String::new(),
+            login_password: String::new(),
+            login_pending: Arc::new(Mutex::new(false)),
+            login_result: Arc::new(Mutex::new(None)),
+            settings_base_url: String::new(),
             settings_download_location: String::new(),
-            show_error: false,
-            error_message: String::new(),
+            new_profile_name: String::new(),
+            export_include_api_keys: false,
+            export_passphrase: String::new(),
+            settings_transfer_status: None,
+            history_kind: HistoryKind::Uploads,
+            history_options: HistoryViewOptions::default(),
+            history_from: String::new(),
+            history_to: String::new(),
+            history_export_status: None,
+            stats_export_status: None,
             lists: Arc::new(Mutex::new(Vec::new())),
             selected_list_id: None,
             new_list_title: String::new(),
@@ -192,6 +1262,9 @@ impl Default for PixelDrainApp {
             // Add fields for editing
             edit_list_title: String::new(),
             edit_list_files: Vec::new(),
+            edit_list_names: HashMap::new(),
+            list_details: Arc::new(Mutex::new(None)),
+            list_details_loading: Arc::new(Mutex::new(false)),
             // Loading states
             files_loading: Arc::new(Mutex::new(false)),
             file_delete_loading: Arc::new(Mutex::new(false)),
@@ -200,10 +1273,87 @@ impl Default for PixelDrainApp {
             list_update_loading: Arc::new(Mutex::new(false)),
             list_delete_loading: Arc::new(Mutex::new(false)),
             user_info_loading: Arc::new(Mutex::new(false)),
+            sharing_form: None,
+            sharing_saving: Arc::new(Mutex::new(false)),
+            hotlinking_saving: Arc::new(Mutex::new(false)),
+            sessions: Arc::new(Mutex::new(None)),
+            sessions_loading: Arc::new(Mutex::new(false)),
+            session_revoke_confirm: None,
+            reauth_key: String::new(),
+            reauth_dismissed: false,
             thumbnail_cache: HashMap::new(),
             thumbnail_loading: HashMap::new(),
             thumbnail_rx: Some(rx),
             thumbnail_tx: Some(tx),
+            cache_usage: None,
+            qr_link: None,
+            qr_texture: None,
+            file_search: String::new(),
+            file_mime_filter: None,
+            file_sort: FileSort::UploadDate,
+            file_sort_ascending: false,
+            seen_file_ids: None,
+            selected_files: HashSet::new(),
+            file_details: None,
+            fix_type: None,
+            file_key: None,
+            new_key_id: String::new(),
+            fix_type_delete_original: false,
+            fix_type_running: Arc::new(Mutex::new(false)),
+            fix_type_result: Arc::new(Mutex::new(None)),
+            files_zip_progress: Arc::new(Mutex::new(None)),
+            files_zip_status: Arc::new(Mutex::new(None)),
+            batch_delete_progress: Arc::new(Mutex::new(None)),
+            pending_deletes: undo::PendingDeletes::default(),
+            journal: resume::Journal::default(),
+            settings_path,
+            secrets,
+            interrupted: Vec::new(),
+            resuming: Vec::new(),
+            available_update: Arc::new(Mutex::new(None)),
+            delete_confirm: None,
+            delete_confirm_dont_ask: false,
+            log_level: tracing::Level::TRACE,
+            log_search: String::new(),
+            account_export_concurrency: 3,
+            account_export_progress: Arc::new(Mutex::new(None)),
+            account_export_result: Arc::new(Mutex::new(None)),
+            sync_selected: 0,
+            sync_plan: Arc::new(Mutex::new(None)),
+            sync_progress: Arc::new(Mutex::new(None)),
+            sync_report: Arc::new(Mutex::new(None)),
+            visual_duplicates: Arc::new(Mutex::new(None)),
+            visual_duplicates_progress: Arc::new(Mutex::new(None)),
+            applied_window_identity: None,
+            share_dialog_open: false,
+            share_path: String::new(),
+            share_password: String::new(),
+            share_loading: Arc::new(Mutex::new(false)),
+            share_result: Arc::new(Mutex::new(None)),
+            workers: Workers::new(),
+            metadata_limit: Limit::new(default_metadata_concurrency()),
+            events: Self::transfer_events(state.clone(), notifier.clone(), watchdog.clone()),
+            notifier,
+            watchdog,
+            api_factory: Self::configured_factory(state, pixeldrain_api::client_factory()),
+            taskbar: TaskbarProgress::new(),
+            sleep_inhibitor: SleepInhibitor::new(),
+            sleep_when_done: false,
+            was_transferring: false,
+            queue_meter: queue::QueueMeter::default(),
+            queue_summary: None,
+            fs_path: FILESYSTEM_ROOT.to_string(),
+            fs_listing: Arc::new(Mutex::new(None)),
+            fs_loading: Arc::new(Mutex::new(false)),
+            fs_error: Arc::new(Mutex::new(None)),
+            fs_transfer_progress: Arc::new(Mutex::new(None)),
+            fs_new_folder: String::new(),
+            fs_rename: None,
+            transactions: Arc::new(Mutex::new(None)),
+            transactions_loading: Arc::new(Mutex::new(false)),
+            transactions_error: Arc::new(Mutex::new(None)),
+            deep_links: None,
+            link_upload_pending: false,
         };
         
         // Load settings on startup
@@ -214,7 +1364,7 @@ impl Default for PixelDrainApp {
 }
 
 impl App for PixelDrainApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
         // Apply theme on first update
         static mut FIRST_UPDATE: bool = true;
         unsafe {
@@ -224,15 +1374,103 @@ impl App for PixelDrainApp {
             }
         }
         
+        self.apply_window_identity(ctx);
+        
+        // Hand the progress the API clients sent since the last frame to the
+        // workers' handlers, and keep drawing while more can come
+        let sending = self.events.drain_progress();
+        let progress = self.aggregate_transfer_progress();
+        if sending || progress.is_some() {
+            ctx.request_repaint_after(Duration::from_millis(16));
+        }
+        self.taskbar.update(ctx, frame, progress);
+        self.update_power_state(progress.is_some());
+        self.update_queue();
+        self.start_resumed(ctx);
+        self.run_due_deletes(Instant::now());
+        self.metadata_limit.set_max(self.state.lock().unwrap().metadata_concurrency.clamp(1, MAX_METADATA_CONCURRENCY));
+        
+        let links: Vec<String> = self.deep_links.as_ref().map(|rx| rx.try_iter().collect()).unwrap_or_default();
+        for link in links {
+            self.handle_deep_link(ctx, &link);
+        }
+        
+        // Workers can't persist settings themselves, save what they changed here
+        let settings_dirty = std::mem::take(&mut self.state.lock().unwrap().settings_dirty);
+        if settings_dirty {
+            self.save_preferences();
+        }
+        
+        self.render_status_bar(ctx);
         egui::CentralPanel::default().show(ctx, |ui| {
             self.render_ui(ui, ctx);
         });
+        self.render_queue_summary(ctx);
+        self.render_toasts(ctx);
+        self.render_undo_toasts(ctx);
+    }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        // Closing the window doesn't undo them, they're sent before the workers are joined
+        for deletion in self.pending_deletes.take_all() {
+            match deletion {
+                // In full, the shutdown below would stop the batch after the file it's on
+                undo::Deletion::Files(ids) if ids.len() > 1 => self.start_batch_delete(ids, false),
+                deletion => self.run_deletion(deletion),
+            }
+        }
+        let remaining = self.workers.shutdown(SHUTDOWN_TIMEOUT);
+        if remaining > 0 {
+            warn!("{} background task(s) still running at exit", remaining);
+        }
+        // With the bytes the unfinished downloads have, for the offer at the next start
+        self.journal.save();
     }
 }
 
 impl PixelDrainApp {
 
 
+    /// Wrap `factory` so every client talks to the server configured in settings.
+    /// Workers call the factory before taking the state lock, so this can't deadlock.
+    fn configured_factory(state: Arc<Mutex<AppState>>, factory: ApiFactory) -> ApiFactory {
+        Arc::new(move |config: PixelDrainConfig| {
+            let base_url = state.lock().unwrap().base_url.clone();
+            factory(config.with_base_url(&base_url))
+        })
+    }
+
+    /// Transfer events, with notifications for finished transfers when they're
+    /// turned on, and heartbeats for the watchdog
+    fn transfer_events(state: Arc<Mutex<AppState>>, notifier: notifications::Notifier, watchdog: watchdog::Watchdog) -> Arc<Events> {
+        let events = Arc::new(Events::default());
+        events.on_event(move |event| watchdog.observe(event, Instant::now()));
+        events.on_event(move |event| {
+            if let Some(notice) = notifications::Notice::of(event) {
+                let settings = {
+                    let state = state.lock().unwrap();
+                    state.desktop_notifications.then(|| state.notification_sinks.clone())
+                };
+                if let Some(settings) = settings {
+                    notifier.notify(&settings, &notice);
+                }
+            }
+        });
+        events
+    }
+
+    /// Send `notice` to its sink from a worker, when notifications are turned on
+    fn notify(&self, notice: notifications::Notice) {
+        let settings = {
+            let state = self.state.lock().unwrap();
+            state.desktop_notifications.then(|| state.notification_sinks.clone())
+        };
+        if let Some(settings) = settings {
+            let notifier = self.notifier.clone();
+            self.workers.spawn(move || notifier.notify(&settings, &notice));
+        }
+    }
+
     /// Get API key with settings priority
     /// Returns the stored API key if set, otherwise the environment variable
     fn get_api_key(&self) -> Option<String> {
@@ -243,10 +1481,10 @@ impl PixelDrainApp {
         }
         
         // Fall back to environment variable
-        if let Ok(env_key) = env::var("PIXELDRAIN_API_KEY") {
-            if !env_key.is_empty() {
-                return Some(env_key);
-            }
+        if let Ok(env_key) = env::var("PIXELDRAIN_API_KEY")
+            && !env_key.is_empty()
+        {
+            return Some(env_key);
         }
         
         None
@@ -288,58 +1526,458 @@ impl PixelDrainApp {
         });
     }
 
-    fn render_ui(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
-        // Header with title and tabs
+    /// One line summing up what the last refresh changed, deleted files can be expanded
+    fn render_file_changes_summary(&self, ui: &mut egui::Ui, changes: &FileListChanges) {
+        if changes.is_empty() {
+            return;
+        }
         ui.horizontal(|ui| {
-            //ui.add_space(20.0);
-            
-            for (tab, label) in [
-                (Tab::Upload, "📤 Upload"),
-                (Tab::Download, "📥 Download"),
-                (Tab::List, "📋 Files"),
-                (Tab::Lists, "📚 Lists"), // New Lists tab
-                (Tab::Settings, "⚙ Settings"),
-                (Tab::About, "ℹ About"),
-            ] {
-                if ui.selectable_label(self.tab == tab, label).clicked() {
-                    self.tab = tab;
-                }
+            ui.label(t!("since-last-refresh"));
+            if !changes.added.is_empty() {
+                ui.colored_label(egui::Color32::from_rgb(0, 150, 70), t!("changes-new", count = changes.added.len()));
+            }
+            if !changes.changed.is_empty() {
+                ui.colored_label(egui::Color32::from_rgb(200, 140, 0), t!("changes-changed", count = changes.changed.len()));
+            }
+            if !changes.removed.is_empty() {
+                ui.colored_label(egui::Color32::from_rgb(200, 60, 60), t!("changes-deleted", count = changes.removed.len()));
+            }
+            if ui.small_button("✖").on_hover_text(t!("dismiss")).clicked() {
+                self.state.lock().unwrap().file_changes = FileListChanges::default();
             }
-            
-            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                // Theme toggle button
-                let dark_mode = {
-                    let state = self.state.lock().unwrap();
-                    state.dark_mode
-                };
-                
-                let theme_button_text = if dark_mode { "☀" } else { "🌙" };
-                let tooltip_text = if dark_mode { "Switch to Light Theme" } else { "Switch to Dark Theme" };
-                
-                if ui.button(theme_button_text).on_hover_text(tooltip_text).clicked() {
-                    let new_dark_mode = !dark_mode;
-                    
-                    // Update the theme state
-                    {
-                        let mut state = self.state.lock().unwrap();
-                        state.dark_mode = new_dark_mode;
-                    }
-                    
-                    // Apply theme to the context
-                    if new_dark_mode {
-                        ctx.set_visuals(egui::Visuals::dark());
-                    } else {
-                        ctx.set_visuals(egui::Visuals::light());
-                    }
-                    
-                    // Save settings
-                    self.save_theme_settings(new_dark_mode);
-                }
-                
-
-            });
+        });
+        if !changes.removed.is_empty() {
+            egui::CollapsingHeader::new(t!("changes-deleted-files", count = changes.removed.len()))
+                .id_salt("deleted_since_refresh")
+                .show(ui, |ui| {
+                    for file in &changes.removed {
+                        ui.label(format!("{} ({}) · {}", file.name, self.format_file_size_bytes(file.size), file.id));
+                    }
+                });
+        }
+    }
+
+    fn render_file_change_badge(ui: &mut egui::Ui, changes: &FileListChanges, file_id: &str) {
+        if changes.added.contains(file_id) {
+            ui.colored_label(egui::Color32::from_rgb(0, 150, 70), t!("file-badge-new"));
+        } else if changes.changed.contains(file_id) {
+            ui.colored_label(egui::Color32::from_rgb(200, 140, 0), t!("file-badge-changed"));
+        }
+    }
+
+    /// Suggestions under the download field, clicking one fills it in
+    fn render_download_suggestions(&mut self, ui: &mut egui::Ui) {
+        if self.download_suggestions.is_empty() {
+            return;
+        }
+        let mut picked = None;
+        egui::Frame::group(ui.style()).show(ui, |ui| {
+            for suggestion in &self.download_suggestions {
+                let label = format!("{}  ·  {}", suggestion.name, suggestion.url);
+                if ui.selectable_label(false, label).clicked() {
+                    picked = Some(suggestion.url.clone());
+                }
+            }
+        });
+        if let Some(url) = picked {
+            self.download_url = url;
+            self.download_suggestions.clear();
+        }
+    }
+
+    /// Details pane of the Files tab, every field of the file with a copy button
+    fn render_file_details(&mut self, ui: &mut egui::Ui, file: &FileInfo, base_url: &str) {
+        ui.horizontal(|ui| {
+            ui.heading(t!("file-details"));
+            if ui.small_button("✖").on_hover_text(t!("file-details-close")).clicked() {
+                self.file_details = None;
+            }
+            if ui.small_button(t!("qr-button")).on_hover_text(t!("qr-show")).clicked() {
+                self.qr_link = Some(format!("{}/u/{}", base_url, file.id));
+            }
+        });
+        self.render_file_thumbnail(ui, file, 96.0);
+        
+        // The API reports dates and limits that aren't set as the zero value
+        let date = |date: DateTime<Utc>| {
+            if date.timestamp() <= 0 {
+                t!("file-details-never")
+            } else {
+                date.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string()
+            }
+        };
+        let yes_no = |value: bool| if value { t!("file-details-yes") } else { t!("file-details-no") };
+        let expiry_days = self.state.lock().unwrap().user_info.as_ref().map(|user| user.subscription.file_expiry_days);
+        let mut rows = vec![
+            (t!("file-details-name"), file.name.clone()),
+            (t!("file-details-id"), file.id.clone()),
+            (t!("file-details-url"), format!("{}/u/{}", base_url, file.id)),
+            (t!("file-details-size"), format!("{} ({} bytes)", self.format_file_size_bytes(file.size), file.size)),
+            (t!("file-details-type"), file.mime_type.clone()),
+            (t!("file-details-uploaded"), date(file.date_upload)),
+            (t!("file-details-last-viewed"), date(file.date_last_view)),
+            (t!("file-details-views"), file.views.to_string()),
+            (t!("file-details-downloads"), file.downloads.to_string()),
+            (t!("file-details-bandwidth"), self.format_file_size_bytes(file.bandwidth_used)),
+            (t!("file-details-paid-bandwidth"), self.format_file_size_bytes(file.bandwidth_used_paid)),
+            (t!("file-details-expires"), date(file.delete_after_date)),
+            (
+                t!("file-details-expires-after-downloads"),
+                if file.delete_after_downloads == 0 { t!("file-details-never") } else { file.delete_after_downloads.to_string() },
+            ),
+        ];
+        // Known once the account info is loaded on the Settings tab
+        if let Some(days) = expiry_days {
+            rows.push((
+                t!("file-details-inactive-expiry"),
+                Self::inactivity_expiry(file, days).map(date).unwrap_or_else(|| t!("file-details-never")),
+            ));
+        }
+        rows.extend([
+            (
+                t!("file-details-availability"),
+                if file.availability.is_empty() { t!("file-details-available") } else { file.availability.clone() },
+            ),
+        ]);
+        if !file.availability_message.is_empty() {
+            rows.push((t!("file-details-availability-message"), file.availability_message.clone()));
+        }
+        rows.push((
+            t!("file-details-abuse"),
+            match (file.abuse_type.is_empty(), file.abuse_reporter_name.is_empty()) {
+                (true, _) => t!("file-details-no-abuse-report"),
+                (false, true) => file.abuse_type.clone(),
+                (false, false) => format!("{} ({})", file.abuse_type, file.abuse_reporter_name),
+            },
+        ));
+        rows.extend([
+            (t!("file-details-sha256"), file.hash_sha256.clone()),
+            (
+                t!("file-details-speed-limit"),
+                if file.download_speed_limit == 0 {
+                    t!("file-details-unlimited")
+                } else {
+                    format!("{}/s", self.format_file_size_bytes(file.download_speed_limit))
+                },
+            ),
+            (t!("file-details-can-edit"), yes_no(file.can_edit)),
+            (t!("file-details-can-download"), yes_no(file.can_download)),
+            (t!("file-details-show-ads"), yes_no(file.show_ads)),
+            (t!("file-details-video-player"), yes_no(file.allow_video_player)),
+        ]);
+        
+        egui::ScrollArea::vertical().id_salt("file_details_scroll").show(ui, |ui| {
+            egui::Grid::new("file_details_grid").num_columns(3).striped(true).show(ui, |ui| {
+                for (label, value) in rows {
+                    ui.strong(label);
+                    ui.add(egui::Label::new(&value).wrap());
+                    if ui.small_button("📋").on_hover_text(t!("copy")).clicked() {
+                        let _ = Clipboard::new().and_then(|mut c| c.set_text(value));
+                    }
+                    ui.end_row();
+                }
+            });
+            if expiry_days.is_some_and(|days| days > 0) {
+                ui.add(egui::Label::new(egui::RichText::new(t!("file-details-expiry-note")).small()).wrap());
+            }
+            self.render_file_key(ui, file);
+            if file.can_edit {
+                ui.separator();
+                self.render_fix_type(ui, file);
+            }
+        });
+    }
+
+    /// Which key the file was encrypted with, kept locally
+    fn render_file_key(&mut self, ui: &mut egui::Ui, file: &FileInfo) {
+        if self.file_key.as_ref().is_none_or(|(id, _)| *id != file.id) {
+            let key_id = self.state.lock().unwrap().key_registry.key_for(&file.id).unwrap_or_default().to_string();
+            self.file_key = Some((file.id.clone(), key_id));
+        }
+        let Some((_, key_id)) = self.file_key.as_mut() else {
+            return;
+        };
+        let mut save = false;
+        egui::CollapsingHeader::new(t!("file-key")).id_salt("file_key").show(ui, |ui| {
+            ui.add(egui::Label::new(egui::RichText::new(t!("file-key-note")).small()).wrap());
+            ui.horizontal(|ui| {
+                let response = ui.add(egui::TextEdit::singleline(key_id).hint_text(t!("key-id-hint")).desired_width(200.0));
+                save = response.lost_focus() || ui.button(t!("file-key-save")).clicked();
+            });
+        });
+        if save {
+            self.state.lock().unwrap().key_registry.record(&file.id, key_id);
+            self.save_preferences();
+        }
+        if let Some(missing) = self.state.lock().unwrap().key_registry.missing_key(&file.id) {
+            ui.colored_label(egui::Color32::ORANGE, t!("key-not-configured", key = missing));
+        }
+    }
+
+    /// Upload a file again with another type, for files the server detected wrongly
+    /// and the web viewer doesn't show properly
+    fn render_fix_type(&mut self, ui: &mut egui::Ui, file: &FileInfo) {
+        if self.fix_type.as_ref().is_none_or(|(id, _)| *id != file.id) {
+            let suggested = extensions::mime_type_for_name(&file.name).unwrap_or(&file.mime_type);
+            self.fix_type = Some((file.id.clone(), suggested.to_string()));
+            *self.fix_type_result.lock().unwrap() = None;
+        }
+        let running = *self.fix_type_running.lock().unwrap();
+        let mut start = None;
+        egui::CollapsingHeader::new(t!("fix-type")).id_salt("fix_type").show(ui, |ui| {
+            ui.add(egui::Label::new(egui::RichText::new(t!("fix-type-note")).small()).wrap());
+            let Some((_, mime_type)) = self.fix_type.as_mut() else {
+                return;
+            };
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(mime_type).hint_text("image/jpeg").desired_width(200.0));
+                ui.menu_button("⏷", |ui| {
+                    egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                        for known in extensions::known_mime_types() {
+                            if ui.button(known).clicked() {
+                                *mime_type = known.to_string();
+                                ui.close();
+                            }
+                        }
+                    });
+                });
+            });
+            let mime_type = mime_type.trim().to_string();
+            let name = extensions::with_inferred_extension(&file.name, &mime_type);
+            ui.label(t!("will-upload-as", name = name.as_str()));
+            ui.checkbox(&mut self.fix_type_delete_original, t!("fix-type-delete-original"));
+            let enabled = !running && !mime_type.is_empty() && mime_type != file.mime_type;
+            if ui.add_enabled(enabled, egui::Button::new(t!("fix-type-button"))).clicked() {
+                start = Some((name, mime_type));
+            }
+            if running {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(t!("fix-type-running"));
+                });
+                ui.ctx().request_repaint_after(std::time::Duration::from_millis(100));
+            }
+            match self.fix_type_result.lock().unwrap().as_ref() {
+                Some(Ok(url)) => {
+                    ui.horizontal_wrapped(|ui| {
+                        ui.label(t!("fix-type-done"));
+                        ui.hyperlink(url);
+                    });
+                }
+                Some(Err(e)) => {
+                    ui.colored_label(egui::Color32::RED, e);
+                }
+                None => {}
+            }
+        });
+        if let Some((name, mime_type)) = start {
+            self.fix_file_type(file, name, mime_type);
+        }
+    }
+
+    /// Upload `file` again as `name` with `mime_type` in the background, deleting
+    /// the original afterwards when that was asked for
+    fn fix_file_type(&self, file: &FileInfo, name: String, mime_type: String) {
+        let api_key = self.get_api_key();
+        let state = self.state.clone();
+        let running = self.fix_type_running.clone();
+        let result = self.fix_type_result.clone();
+        let delete_original = self.fix_type_delete_original;
+        let file_id = file.id.clone();
+        let size = file.size;
+        *running.lock().unwrap() = true;
+        *result.lock().unwrap() = None;
+        
+        let transfer = self.events.start(Direction::Upload, name.clone());
+        let api_factory = self.api_factory.clone();
+        self.workers.spawn(move || {
+            let config = if let Some(key) = api_key {
+                PixelDrainConfig::default().with_api_key(key)
+            } else {
+                PixelDrainConfig::default()
+            };
+            let client = match api_factory(config) {
+                Ok(client) => client,
+                Err(e) => {
+                    *result.lock().unwrap() = Some(Err(format!("Failed to create client: {}", e)));
+                    *running.lock().unwrap() = false;
+                    return;
+                }
+            };
+            let progress_cb = {
+                let reporter = transfer.progress();
+                transfer.progress().sender(move |p: Progress| reporter.report(p.fraction))
+            };
+            let outcome = match client.reupload_with_type(&file_id, &name, &mime_type, Some(progress_cb)) {
+                Ok(response) => {
+                    let url = response.get_file_url(client.base_url());
+                    state.lock().unwrap().record_upload(UploadHistoryEntry {
+                        id: response.id,
+                        url: url.clone(),
+                        filename: name.clone(),
+                        size,
+                        timestamp: Utc::now(),
+                        verified: None,
+                        error: None,
+                        anonymous: false,
+                    });
+                    // The new file exists either way, so a failed delete is only reported
+                    match delete_original.then(|| client.delete_file(&file_id)) {
+                        Some(Err(e)) => Err(format!("Uploaded as {}, but deleting the original failed: {}", url, e)),
+                        _ => Ok(url),
+                    }
+                }
+                Err(e) => {
+                    state.lock().unwrap().note_auth_failure(&e);
+                    Err(format!("Failed to upload {} again: {}", name, e))
+                }
+            };
+            if let Ok(files) = client.get_user_files() {
+                state.lock().unwrap().replace_file_list(files.files);
+            }
+            transfer.end(match &outcome {
+                Ok(url) => TransferOutcome::UploadFinished { name, url: url.clone() },
+                Err(error) => TransferOutcome::UploadFailed { name, error: error.clone() },
+            });
+            *result.lock().unwrap() = Some(outcome);
+            *running.lock().unwrap() = false;
+        });
+    }
+
+    /// When a file is removed for not being viewed, going by the plan's expiry period.
+    /// None when the plan keeps files forever.
+    fn inactivity_expiry(file: &FileInfo, expiry_days: u64) -> Option<DateTime<Utc>> {
+        if expiry_days == 0 {
+            return None;
+        }
+        // Files that were never viewed count from the upload
+        let last_activity = if file.date_last_view.timestamp() > 0 { file.date_last_view } else { file.date_upload };
+        Some(last_activity + chrono::Duration::days(expiry_days as i64))
+    }
+
+    fn link_format_label(format: LinkFormat) -> String {
+        match format {
+            LinkFormat::Plain => t!("link-format-plain"),
+            LinkFormat::Markdown => t!("link-format-markdown"),
+            LinkFormat::BbCode => t!("link-format-bbcode"),
+            LinkFormat::Html => t!("link-format-html"),
+            LinkFormat::Direct => t!("link-format-direct"),
+        }
+    }
+
+    /// Menu copying the link of `file` in any format, the choice ends up in `copy`
+    fn render_copy_as_menu(ui: &mut egui::Ui, file: &FileInfo, copy: &mut Option<(String, String, LinkFormat)>) {
+        ui.menu_button("⏷", |ui| {
+            ui.label(t!("copy-as"));
+            for format in LinkFormat::ALL {
+                if ui.button(Self::link_format_label(format)).clicked() {
+                    *copy = Some((file.id.clone(), file.name.clone(), format));
+                    ui.close();
+                }
+            }
+        })
+        .response
+        .on_hover_text(t!("copy-as"));
+    }
+
+    /// Small counter or warning next to a tab label, so changes are noticed from other tabs
+    fn tab_badge(&self, tab: &Tab) -> Option<(String, egui::Color32)> {
+        let transfers = |count: usize| (count > 0).then(|| (count.to_string(), egui::Color32::from_rgb(0, 120, 215)));
+        match tab {
+            Tab::Upload => transfers(*self.upload_thread_running.lock().unwrap() as usize),
+            Tab::Download => transfers(*self.download_thread_running.lock().unwrap() as usize),
+            Tab::List => {
+                let seen = self.seen_file_ids.as_ref()?;
+                let new_files = self.state.lock().unwrap().file_list.iter().filter(|f| !seen.contains(&f.id)).count();
+                (new_files > 0).then(|| (format!("+{}", new_files), egui::Color32::from_rgb(0, 150, 70)))
+            }
+            Tab::Filesystem => transfers(self.fs_transfer_progress.lock().unwrap().is_some() as usize),
+            Tab::Settings => {
+                let (api_key_rejected, stall_timeout) = {
+                    let state = self.state.lock().unwrap();
+                    (state.api_key_rejected, Duration::from_secs(state.stall_timeout_secs))
+                };
+                if api_key_rejected {
+                    Some(("!".to_string(), egui::Color32::RED))
+                } else {
+                    self.watchdog.any_stalled(Instant::now(), stall_timeout).then(|| ("⚠".to_string(), egui::Color32::from_rgb(255, 140, 0)))
+                }
+            }
+            _ => None,
+        }
+    }
+    
+    /// Files on screen in the Files tab count as seen, anything added by a later refresh is new
+    fn update_seen_files(&mut self) {
+        if self.tab != Tab::List && self.seen_file_ids.is_some() {
+            return;
+        }
+        let ids: HashSet<String> = self.state.lock().unwrap().file_list.iter().map(|f| f.id.clone()).collect();
+        if self.tab == Tab::List || !ids.is_empty() {
+            self.seen_file_ids = Some(ids);
+        }
+    }
+    
+    fn render_ui(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        self.update_seen_files();
+        
+        // Header with title and tabs
+        ui.horizontal(|ui| {
+            //ui.add_space(20.0);
+            
+            for (tab, label) in [
+                (Tab::Upload, t!("tab-upload")),
+                (Tab::Download, t!("tab-download")),
+                (Tab::List, t!("tab-files")),
+                (Tab::Lists, t!("tab-lists")), // New Lists tab
+                (Tab::Filesystem, t!("tab-filesystem")),
+                (Tab::History, t!("tab-history")),
+                (Tab::Stats, t!("tab-stats")),
+                (Tab::Billing, t!("tab-billing")),
+                (Tab::Settings, t!("tab-settings")),
+                (Tab::About, t!("tab-about")),
+            ] {
+                let badge = self.tab_badge(&tab);
+                if ui.selectable_label(self.tab == tab, label).clicked() {
+                    self.tab = tab;
+                }
+                if let Some((text, color)) = badge {
+                    ui.label(egui::RichText::new(text).small().strong().color(egui::Color32::WHITE).background_color(color));
+                }
+            }
+            
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                // Theme toggle button
+                let dark_mode = {
+                    let state = self.state.lock().unwrap();
+                    state.dark_mode
+                };
+                
+                let theme_button_text = if dark_mode { "☀" } else { "🌙" };
+                let tooltip_text = if dark_mode { t!("theme-switch-light") } else { t!("theme-switch-dark") };
+                
+                if ui.button(theme_button_text).on_hover_text(tooltip_text).clicked() {
+                    let new_dark_mode = !dark_mode;
+                    
+                    // Update the theme state
+                    {
+                        let mut state = self.state.lock().unwrap();
+                        state.dark_mode = new_dark_mode;
+                    }
+                    
+                    // Apply theme to the context
+                    theme::apply(ctx, new_dark_mode, self.state.lock().unwrap().high_contrast);
+                    
+                    // Save settings
+                    self.save_theme_settings(new_dark_mode);
+                }
+                
+                self.render_profile_switcher(ui);
+
+            });
         });
         
+        self.render_queue_eta(ui);
         ui.separator();
 
         // Main content area
@@ -348,6 +1986,10 @@ impl PixelDrainApp {
             Tab::Download => self.download_tab(ctx, ui),
             Tab::List => self.list_tab(ui),
             Tab::Lists => self.lists_tab(ui), // New Lists tab
+            Tab::Filesystem => self.filesystem_tab(ui),
+            Tab::History => self.history_tab(ui),
+            Tab::Stats => self.stats_tab(ui),
+            Tab::Billing => self.billing_tab(ui),
             Tab::Settings => self.settings_tab(ui),
             Tab::About => self.about_tab(ui),
         }
@@ -355,9 +1997,26 @@ impl PixelDrainApp {
 
 
         // Error popup
-        if self.show_error {
-            self.render_error_popup(ctx);
+        self.render_error_popup(ctx);
+        self.render_reauth_prompt(ctx);
+        
+        if self.share_dialog_open {
+            self.render_share_dialog(ctx);
+        }
+        
+        if self.link_upload_pending {
+            self.render_link_upload_confirm(ctx);
+        }
+        
+        if self.delete_confirm.is_some() {
+            self.render_delete_confirm(ctx);
+        }
+        
+        if !self.interrupted.is_empty() {
+            self.render_interrupted_offer(ctx);
         }
+        
+        self.render_qr_window(ctx);
     }
 
 
@@ -370,7 +2029,7 @@ impl PixelDrainApp {
         };
         
         if let Some(error_msg) = error {
-            ui.colored_label(egui::Color32::RED, format!("❌ Error: {}", error_msg));
+            ui.colored_label(egui::Color32::RED, t!("error-message", error = error_msg.as_str()));
             ui.separator();
         }
 
@@ -382,19 +2041,26 @@ impl PixelDrainApp {
                     let env_key_set = self.has_env_api_key();
                     (api_key_set, env_key_set)
                 };
-                if api_key_set || env_key_set {
-                    ui.colored_label(egui::Color32::GREEN, "🔐 Authenticated Upload");
+                if self.upload_anonymously {
+                    ui.colored_label(egui::Color32::YELLOW, t!("anonymous-upload"));
+                } else if api_key_set || env_key_set {
+                    ui.colored_label(egui::Color32::GREEN, t!("authenticated-upload"));
                     if env_key_set {
-                        ui.label("Using API key from environment variable");
+                        ui.label(t!("using-api-key-from-environment-variable"));
                     } else {
-                        ui.label("Using API key from settings");
+                        ui.label(t!("using-api-key-from-settings"));
                     }
                 } else {
-                    ui.colored_label(egui::Color32::RED, "❌ No API key configured - upload is not possible");
-                    ui.label("Please enter your API key in settings to enable uploads.");
+                    ui.colored_label(egui::Color32::YELLOW, t!("no-api-key-configured-upload-is"));
+                    ui.label(t!("please-enter-your-api-key-in"));
+                }
+                ui.checkbox(&mut self.upload_anonymously, t!("upload-anonymously-ignore-my-api-key"))
+                    .on_hover_text(t!("applies-to-the-next-upload-only"));
+                if self.upload_anonymously {
+                    ui.colored_label(egui::Color32::YELLOW, t!("anonymous-upload-consequences"));
                 }
                 ui.separator();
-                ui.label("File to upload:");
+                ui.label(t!("file-to-upload"));
                 
                 if let Some(path) = &self.upload_file {
                     // Better file path display with proper wrapping
@@ -404,39 +2070,70 @@ impl PixelDrainApp {
                         // Constrain width for proper wrapping in narrow windows
                         ui.add(egui::Label::new(path.display().to_string()).wrap());
                     });
-                    ui.label(format!("📏 Size: {}", self.format_file_size(path)));
+                    ui.label(t!("upload-file-size", size = self.format_file_size(path)));
                     
                     // File rename option
                     ui.separator();
-                    ui.label("📝 Rename file (optional):");
+                    ui.label(t!("rename-file-optional"));
                     let original_name = path.file_name().unwrap_or_default().to_string_lossy();
                     ui.horizontal(|ui| {
                         if self.upload_custom_filename.is_empty() {
                             ui.text_edit_singleline(&mut self.upload_custom_filename);
-                            if ui.button("Use original").clicked() {
+                            if ui.button(t!("use-original")).clicked() {
                                 self.upload_custom_filename = original_name.to_string();
                             }
                         } else {
                             ui.text_edit_singleline(&mut self.upload_custom_filename);
-                            if ui.button("Clear").clicked() {
+                            if ui.button(t!("clear")).clicked() {
                                 self.upload_custom_filename.clear();
                             }
                         }
                     });
                     if !self.upload_custom_filename.is_empty() {
-                        ui.label(format!("Will upload as: {}", self.upload_custom_filename));
+                        ui.label(t!("will-upload-as", name = self.upload_custom_filename.as_str()));
                     } else {
-                        ui.label(format!("Will upload as: {}", original_name));
+                        ui.label(t!("will-upload-as", name = original_name));
                     }
                 } else if !self.upload_files.is_empty() {
                     // Display multiple files
                     ui.add_space(5.0);
-                    ui.label(format!("📁 {} files selected:", self.upload_files.len()));
+                    ui.label(t!("upload-files-selected", count = self.upload_files.len()));
+                    ui.horizontal(|ui| {
+                        ui.label(t!("upload-order"));
+                        egui::ComboBox::from_id_salt("upload_order")
+                            .selected_text(self.upload_order.label())
+                            .show_ui(ui, |ui| {
+                                for order in UploadOrder::ALL {
+                                    ui.selectable_value(&mut self.upload_order, order, order.label());
+                                }
+                            });
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label(t!("upload-concurrency"));
+                        let mut concurrency = self.state.lock().unwrap().upload_concurrency;
+                        if ui.add(egui::DragValue::new(&mut concurrency).range(1..=MAX_UPLOAD_CONCURRENCY))
+                            .on_hover_text(t!("upload-concurrency-tooltip"))
+                            .changed()
+                        {
+                            self.state.lock().unwrap().upload_concurrency = concurrency;
+                            self.save_preferences();
+                        }
+                    });
+                    // Listed in the order they will be uploaded, each can be renamed
+                    ui.label(t!("rename-files-optional"));
+                    let mut ordered = self.upload_files.clone();
+                    self.upload_order.apply(&mut ordered);
                     egui::ScrollArea::vertical().max_height(100.0).id_salt("upload_files_scroll").show(ui, |ui| {
-                        for (i, path) in self.upload_files.iter().enumerate() {
+                        for (i, path) in ordered.iter().enumerate() {
                             ui.horizontal(|ui| {
-                                ui.label(format!("{}. {}", i + 1, path.file_name().unwrap_or_default().to_string_lossy()));
+                                ui.label(format!("{}.", i + 1));
+                                let original_name = path.file_name().unwrap_or_default().to_string_lossy();
+                                let name = self.upload_file_names.entry(path.clone()).or_default();
+                                ui.add(egui::TextEdit::singleline(name).hint_text(original_name.as_ref()).desired_width(200.0));
                                 ui.label(format!("({})", self.format_file_size(path)));
+                                if let Some(problem) = preflight::check_file(path) {
+                                    ui.colored_label(egui::Color32::ORANGE, "⚠").on_hover_text(Self::problem_label(&problem));
+                                }
                             });
                         }
                     });
@@ -448,110 +2145,202 @@ impl PixelDrainApp {
                         ui.add(egui::Label::new(dir_path.display().to_string()).wrap());
                     });
                     
+                    let dir_path = dir_path.clone();
+                    self.render_archive_format(ui);
+                    self.render_exclude_patterns(ui, &dir_path);
+                    let extension = self.state.lock().unwrap().archive_format.extension();
+                    
                     // Directory rename option
                     ui.separator();
-                    ui.label("📝 Rename archive (optional):");
+                    ui.label(t!("rename-archive-optional"));
                     let original_name = dir_path.file_name().unwrap_or_default().to_string_lossy();
                     ui.horizontal(|ui| {
                         if self.upload_directory_name.is_empty() {
                             ui.text_edit_singleline(&mut self.upload_directory_name);
-                            if ui.button("Use original").clicked() {
-                                self.upload_directory_name = format!("{}.tar.gz", original_name);
+                            if ui.button(t!("use-original")).clicked() {
+                                self.upload_directory_name = format!("{}.{}", original_name, extension);
                             }
                         } else {
                             ui.text_edit_singleline(&mut self.upload_directory_name);
-                            if ui.button("Clear").clicked() {
+                            if ui.button(t!("clear")).clicked() {
                                 self.upload_directory_name.clear();
                             }
                         }
                     });
                     if !self.upload_directory_name.is_empty() {
-                        ui.label(format!("Will upload as: {}", self.upload_directory_name));
+                        ui.label(t!("will-upload-as", name = self.upload_directory_name.as_str()));
                     } else {
-                        ui.label(format!("Will upload as: {}.tar.gz", original_name));
+                        ui.label(t!("will-upload-as", name = format!("{}.{}", original_name, extension)));
                     }
                 } else {
-                    ui.label("📁 No file or directory selected");
+                    ui.label(t!("no-file-or-directory-selected"));
                 }
                 
                 ui.horizontal(|ui| {
-                    if ui.button("📁 Select Files").clicked() {
-                        if let Some(paths) = FileDialog::new().pick_files() {
-                            if paths.len() == 1 {
-                                // Single file selected
-                                self.upload_file = Some(paths[0].clone());
-                                self.upload_files.clear();
-                                self.upload_directory = None;
-                            } else {
-                                // Multiple files selected
-                                self.upload_files = paths;
-                                self.upload_file = None;
-                                self.upload_directory = None;
-                            }
-                            self.upload_custom_filename.clear();
-                            self.upload_directory_name.clear();
-                            // Reset progress
-                            *self.upload_progress.lock().unwrap() = 0.0;
-                            // Clear any previous errors
-                            self.state.lock().unwrap().last_error = None;
+                    if ui.button(t!("select-files")).clicked()
+                        && let Some(paths) = FileDialog::new().pick_files()
+                    {
+                        if paths.len() == 1 {
+                            // Single file selected
+                            self.upload_file = Some(paths[0].clone());
+                            self.upload_files.clear();
+                            self.upload_directory = None;
+                        } else {
+                            // Multiple files selected
+                            self.upload_files = paths;
+                            self.upload_file = None;
+                            self.upload_directory = None;
                         }
+                        self.upload_custom_filename.clear();
+                        self.upload_file_names.clear();
+                        self.upload_directory_name.clear();
+                        // Reset progress
+                        *self.upload_progress.lock().unwrap() = 0.0;
+                        // Clear any previous errors
+                        self.state.lock().unwrap().last_error = None;
                     }
                     
-                    if ui.button("📂 Select Directory").clicked() {
-                        if let Some(path) = FileDialog::new().pick_folder() {
-                            self.upload_directory = Some(path);
-                            self.upload_file = None;
-                            self.upload_files.clear();
-                            self.upload_custom_filename.clear();
-                            self.upload_directory_name.clear();
-                            // Reset progress
-                            *self.upload_progress.lock().unwrap() = 0.0;
-                            // Clear any previous errors
-                            self.state.lock().unwrap().last_error = None;
-                        }
+                    if ui.button(t!("select-directory")).clicked()
+                        && let Some(path) = FileDialog::new().pick_folder()
+                    {
+                        self.upload_directory = Some(path);
+                        self.upload_file = None;
+                        self.upload_files.clear();
+                        self.upload_custom_filename.clear();
+                        self.upload_file_names.clear();
+                        self.upload_directory_name.clear();
+                        // Reset progress
+                        *self.upload_progress.lock().unwrap() = 0.0;
+                        // Clear any previous errors
+                        self.state.lock().unwrap().last_error = None;
+                    }
+                    
+                    let is_running = *self.upload_thread_running.lock().unwrap();
+                    if ui.add_enabled(!is_running, egui::Button::new(t!("paste-image"))).on_hover_text(t!("paste-image-hover")).clicked() {
+                        self.paste_image(false);
                     }
                 });
+                
+                // Ctrl+V pastes an image when no text field takes it
+                let paste_pressed = ctx.input(|i| {
+                    i.events.iter().any(|e| matches!(e, egui::Event::Paste(_)) || matches!(e, egui::Event::Key { key: egui::Key::V, pressed: true, modifiers, .. } if modifiers.command))
+                });
+                if paste_pressed && !ctx.wants_keyboard_input() && !*self.upload_thread_running.lock().unwrap() {
+                    self.paste_image(true);
+                }
+
+                let mut verify_uploads = self.state.lock().unwrap().verify_uploads;
+                if ui.checkbox(&mut verify_uploads, t!("verify-upload-size-sha-256"))
+                    .on_hover_text(t!("re-hash-the-local-file-after"))
+                    .changed()
+                {
+                    self.state.lock().unwrap().verify_uploads = verify_uploads;
+                    self.save_preferences();
+                }
+                let mut skip_duplicates = self.state.lock().unwrap().skip_duplicate_uploads;
+                if ui.checkbox(&mut skip_duplicates, t!("skip-duplicate-uploads"))
+                    .on_hover_text(t!("skip-duplicate-uploads-tooltip"))
+                    .changed()
+                {
+                    self.state.lock().unwrap().skip_duplicate_uploads = skip_duplicates;
+                    self.save_preferences();
+                }
+                let mut encrypt = self.state.lock().unwrap().encrypt_uploads;
+                if ui.checkbox(&mut encrypt, t!("encrypt-uploads"))
+                    .on_hover_text(t!("encrypt-uploads-tooltip"))
+                    .changed()
+                {
+                    self.state.lock().unwrap().encrypt_uploads = encrypt;
+                    self.save_preferences();
+                }
+                if encrypt && self.state.lock().unwrap().encryption_passphrase.is_empty() {
+                    ui.colored_label(egui::Color32::ORANGE, t!("encryption-no-passphrase"));
+                }
+                let mut check_before = self.state.lock().unwrap().check_before_large_uploads;
+                if ui.checkbox(&mut check_before, t!("check-before-large-uploads"))
+                    .on_hover_text(t!("check-before-large-uploads-tooltip"))
+                    .changed()
+                {
+                    self.state.lock().unwrap().check_before_large_uploads = check_before;
+                    self.save_preferences();
+                }
+                self.render_diagnostics_toggle(ui);
+                self.render_upload_target_list(ui);
 
                 let is_running = *self.upload_thread_running.lock().unwrap();
                 if let Some(_path) = &self.upload_file {
-                    if ui.add_enabled(!is_running, egui::Button::new(if is_running { "⏳ Uploading..." } else { "🚀 Upload" })).clicked() {
-                        self.start_upload(self.upload_file.clone().unwrap(), ctx.clone());
+                    if ui.add_enabled(!is_running, egui::Button::new(if is_running { t!("upload-running") } else { t!("upload-button") })).clicked() {
+                        self.start_upload(self.upload_file.clone().unwrap());
                     }
                 } else if !self.upload_files.is_empty() {
-                    let button_text = if is_running { "⏳ Uploading..." } else { &format!("🚀 Upload {} Files", self.upload_files.len()) };
-                    if ui.add_enabled(!is_running, egui::Button::new(button_text)).clicked() {
-                        self.start_multiple_upload(self.upload_files.clone(), ctx.clone());
-                    }
+                    let button_text = if is_running { t!("upload-running") } else { t!("upload-files-button", count = self.upload_files.len()) };
+                    ui.horizontal(|ui| {
+                        if ui.add_enabled(!is_running, egui::Button::new(button_text)).clicked() {
+                            self.start_multiple_upload(self.upload_files.clone(), ctx.clone());
+                        }
+                        if ui.add_enabled(!is_running, egui::Button::new(t!("upload-bundle-button")))
+                            .on_hover_text(t!("upload-bundle-tooltip"))
+                            .clicked()
+                            && let Some(index_path) = FileDialog::new()
+                                .set_file_name("bundle.html")
+                                .add_filter("HTML", &["html"])
+                                .add_filter("Markdown", &["md"])
+                                .save_file()
+                        {
+                            self.upload_bundle = Some(index_path);
+                            self.start_multiple_upload(self.upload_files.clone(), ctx.clone());
+                        }
+                    });
                 } else if let Some(_dir_path) = &self.upload_directory {
-                    let button_text = if is_running { "⏳ Compressing & Uploading..." } else { "🚀 Upload Directory" };
+                    let button_text = if is_running { t!("upload-directory-running") } else { t!("upload-directory-button") };
                     if ui.add_enabled(!is_running, egui::Button::new(button_text)).clicked() {
                         self.start_directory_upload(self.upload_directory.clone().unwrap(), ctx.clone());
                     }
                 } else {
                     ui.add_enabled_ui(false, |ui| {
-                        let _ = ui.button("🚀 Upload");
+                        let _ = ui.button(t!("upload-button"));
                     });
                 }
                 
                 // Show upload progress
                 let progress = *self.upload_progress.lock().unwrap();
                 let is_running = *self.upload_thread_running.lock().unwrap();
-                if let Some(_dir_path) = &self.upload_directory {
-                    if is_running {
-                        ui.horizontal(|ui| {
-                            ui.add(egui::Spinner::new());
-                            ui.label("Uploading directory...");
-                        });
-                        ctx.request_repaint_after(std::time::Duration::from_millis(100));
-                    }
+                let prepare_progress = *self.upload_prepare_progress.lock().unwrap();
+                if *self.upload_checking.lock().unwrap() {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(t!("checking-before-upload"));
+                    });
+                    ctx.request_repaint_after(std::time::Duration::from_millis(100));
+                } else if let Some(prepare_progress) = prepare_progress {
+                    ui.label(t!("preparing-hashing-files"));
+                    ui.add(egui::ProgressBar::new(prepare_progress).show_percentage());
+                    ctx.request_repaint_after(std::time::Duration::from_millis(100));
+                } else if self.upload_directory.is_some() && is_running {
+                    ui.label(t!("uploading-directory"));
+                    ui.add(egui::ProgressBar::new(progress).show_percentage());
+                    self.render_transfer_rate(ui, &self.upload_rate);
+                    self.render_sleep_when_done(ui);
+                    ctx.request_repaint_after(std::time::Duration::from_millis(100));
                 } else if progress > 0.0 && progress < 1.0 {
-                    ui.label("📤 Uploading...");
+                    ui.label(t!("uploading"));
                     ui.add(egui::ProgressBar::new(progress).show_percentage());
-                    ui.label(format!("Progress: {:.1}%", progress * 100.0));
+                    ui.label(t!("upload-progress", percent = format!("{:.1}", progress * 100.0)));
+                    self.render_transfer_rate(ui, &self.upload_rate);
+                    self.render_sleep_when_done(ui);
+                    if is_running {
+                        self.render_batch_progress(ui);
+                    }
                     ctx.request_repaint_after(std::time::Duration::from_millis(16));
                 } else if progress >= 1.0 {
-                    ui.label("✅ Upload complete! URL copied to clipboard.");
+                    ui.label(t!("upload-complete-url-copied-to-clipboard"));
                 }
+                self.render_upload_skipped(ui);
+                self.render_upload_duplicates(ui, ctx);
+                self.render_split_offer(ui);
+                self.render_quota_warning(ui, ctx);
+                self.render_batch_list_offer(ui);
+                self.render_folder_sync(ui);
             });
         });
 
@@ -571,6 +2360,7 @@ impl PixelDrainApp {
             }
             
             self.upload_custom_filename.clear();
+            self.upload_file_names.clear();
             // Reset progress
             *self.upload_progress.lock().unwrap() = 0.0;
             // Clear any previous errors
@@ -580,20 +2370,73 @@ impl PixelDrainApp {
         ui.separator();
 
         // Recent uploads with text wrapping for URLs
-        ui.label("Recent Uploads");
+        ui.label(t!("recent-uploads"));
         
-        let state = self.state.lock().unwrap();
-        if state.upload_history.is_empty() {
-            ui.label("No uploads yet");
+        let mut options = self.state.lock().unwrap().upload_history_view.clone();
+        let toggled = Self::render_history_options(ui, "upload_history_search", &mut options);
+        self.state.lock().unwrap().upload_history_view = options.clone();
+        if toggled {
+            self.save_preferences();
+        }
+        
+        let (view, has_entries) = {
+            let state = self.state.lock().unwrap();
+            (state.storage.uploads(&options, HISTORY_VIEW_LIMIT), state.storage.has_uploads().unwrap_or(false))
+        };
+        let view = match view {
+            Ok(view) => view,
+            Err(e) => {
+                ui.colored_label(egui::Color32::RED, t!("history-unavailable", error = e.to_string()));
+                return;
+            }
+        };
+        if !has_entries {
+            ui.label(t!("no-uploads-yet"));
+        } else if view.is_empty() {
+            ui.label(t!("no-uploads-match-the-current-filters"));
         } else {
             egui::ScrollArea::vertical().max_height(200.0).id_salt("upload_history_scroll").show(ui, |ui| {
-                for entry in state.upload_history.iter().rev().take(5) {
+                for (index, (entry, count)) in view.iter().enumerate() {
+                    if options.group_by_day
+                        && let Some(day) = history_day_heading(&view, index, |entry| entry.timestamp)
+                    {
+                        ui.strong(day);
+                    }
+                    if let Some(error) = &entry.error {
+                        ui.horizontal(|ui| {
+                            ui.label(format!("📄 {}", entry.filename));
+                            ui.colored_label(egui::Color32::RED, t!("failed")).on_hover_text(error);
+                            Self::render_history_count(ui, *count);
+                        });
+                        ui.label(format!("🕐 {}", entry.timestamp.format("%Y-%m-%d %H:%M:%S")));
+                        ui.separator();
+                        continue;
+                    }
                     ui.horizontal(|ui| {
                         ui.label(format!("📄 {}", entry.filename));
                         ui.label(format!("({})", self.format_file_size_bytes(entry.size)));
-                        if ui.button("📋 Copy").clicked() {
+                        Self::render_history_count(ui, *count);
+                        match entry.verified {
+                            Some(true) => {
+                                ui.colored_label(egui::Color32::GREEN, t!("verified"))
+                                    .on_hover_text(t!("size-and-sha-256-match-the"));
+                            }
+                            Some(false) => {
+                                ui.colored_label(egui::Color32::RED, t!("mismatch"))
+                                    .on_hover_text(t!("the-uploaded-file-does-not-match"));
+                            }
+                            None => {}
+                        }
+                        if entry.anonymous {
+                            ui.label(t!("anonymous"))
+                                .on_hover_text(t!("not-linked-to-your-account-it"));
+                        }
+                        if ui.button(t!("copy")).clicked() {
                             let _ = Clipboard::new().and_then(|mut c| c.set_text(entry.url.clone()));
                         }
+                        if !entry.url.is_empty() && ui.button(t!("qr-button")).on_hover_text(t!("qr-show")).clicked() {
+                            self.qr_link = Some(entry.url.clone());
+                        }
                     });
                     // Use text wrapping for URLs
                     ui.horizontal_wrapped(|ui| {
@@ -604,6 +2447,9 @@ impl PixelDrainApp {
                     ui.separator();
                 }
             });
+            if view.len() == HISTORY_VIEW_LIMIT {
+                ui.weak(t!("history-view-limited", count = HISTORY_VIEW_LIMIT));
+            }
         }
     }
 
@@ -615,27 +2461,49 @@ impl PixelDrainApp {
         };
         
         if let Some(error_msg) = error {
-            ui.colored_label(egui::Color32::RED, format!("❌ Error: {}", error_msg));
+            ui.colored_label(egui::Color32::RED, t!("error-message", error = error_msg.as_str()));
             ui.separator();
         }
 
         ui.vertical(|ui| {
             // Show download mode
-            ui.colored_label(egui::Color32::BLUE, "⬇ Public File Download");
-            ui.label("Download any public PixelDrain file (no API key required)");
+            ui.colored_label(egui::Color32::BLUE, t!("public-file-download"));
+            ui.label(t!("download-any-public-pixeldrain-file-or"));
             
             ui.separator();
             
             // URL input
             ui.horizontal(|ui| {
-                ui.label("URL:");
-                ui.add(egui::TextEdit::singleline(&mut self.download_url).desired_width(120.0));
+                ui.label(t!("download-url"));
+                let response = ui.add(egui::TextEdit::singleline(&mut self.download_url).desired_width(120.0).hint_text(t!("download-url-hint")));
+                if response.changed() {
+                    let state = self.state.lock().unwrap();
+                    self.download_suggestions =
+                        history::url_suggestions(state.storage.as_ref(), &state.file_list, &state.base_url, &self.download_url, URL_SUGGESTIONS);
+                }
+                if response.has_focus() && ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                    self.download_suggestions.clear();
+                }
             });
+            self.render_download_suggestions(ui);
             
             // Download button
             let can_download = !self.download_url.is_empty();
-            if ui.add_enabled(can_download, egui::Button::new("⬇ Download")).clicked() && !*self.download_thread_running.lock().unwrap() {
-                self.start_download();
+            let list_id = PixelDrainClient::extract_list_id(&self.download_url).ok();
+            ui.horizontal(|ui| {
+                if ui.add_enabled(can_download, egui::Button::new(t!("download-button"))).clicked() && !*self.download_thread_running.lock().unwrap() {
+                    self.start_download();
+                }
+                if let Some(list_id) = &list_id
+                    && ui.button(t!("download-as-zip")).clicked()
+                    && !*self.download_thread_running.lock().unwrap()
+                {
+                    self.pick_list_zip_destination(list_id, list_id);
+                }
+            });
+            self.render_diagnostics_toggle(ui);
+            if let Some(key_id) = &self.download_missing_key {
+                ui.colored_label(egui::Color32::ORANGE, t!("key-not-configured", key = key_id.as_str()));
             }
 
             // Show download location info
@@ -644,22 +2512,29 @@ impl PixelDrainApp {
                 if !state.download_location.is_empty() {
                     state.download_location.clone()
                 } else {
-                    "Default download location not set".to_string()
+                    t!("download-location-not-set")
                 }
             };
-            ui.label(format!("📁 Download location: {}", download_location));
+            ui.label(t!("download-location", path = download_location.as_str()));
+            
+            self.render_download_quota(ui);
             
             // Progress/status
             let progress = *self.download_progress.lock().unwrap();
             let is_running = *self.download_thread_running.lock().unwrap();
             if is_running {
+                if let Some(status) = self.download_status.lock().unwrap().as_ref() {
+                    ui.label(format!("📚 {}", status));
+                }
                 if progress > 0.0 && progress < 1.0 {
                     ui.add(egui::ProgressBar::new(progress).show_percentage());
+                    self.render_transfer_rate(ui, &self.download_rate);
+                    self.render_sleep_when_done(ui);
                 } else if progress == 0.0 {
                     ui.add(egui::Spinner::new());
-                    ui.label("Downloading...");
+                    ui.label(t!("downloading"));
                 } else if progress >= 1.0 {
-                    ui.label("✅ Done");
+                    ui.label(t!("done"));
                 }
             }
         });
@@ -667,21 +2542,404 @@ impl PixelDrainApp {
         ui.separator();
 
         // Recent downloads
-        ui.label("Recent Downloads");
-        let state = self.state.lock().unwrap();
-        if state.download_history.is_empty() {
-            ui.label("No downloads yet");
-        } else {
-            egui::ScrollArea::vertical().max_height(200.0).id_salt("download_history_scroll").show(ui, |ui| {
-                for entry in state.download_history.iter().rev().take(5) {
-                    ui.horizontal(|ui| {
-                        ui.label(format!("📄 {}", entry.filename));
-                    });
-                    ui.label(format!("📍 {}", entry.local_path));
-                    ui.label(format!("🕐 {}", entry.timestamp.format("%Y-%m-%d %H:%M:%S")));
+        ui.label(t!("recent-downloads"));
+        
+        let mut options = self.state.lock().unwrap().download_history_view.clone();
+        let toggled = Self::render_history_options(ui, "download_history_search", &mut options);
+        self.state.lock().unwrap().download_history_view = options.clone();
+        if toggled {
+            self.save_preferences();
+        }
+        
+        let (view, has_entries) = {
+            let state = self.state.lock().unwrap();
+            (state.storage.downloads(&options, HISTORY_VIEW_LIMIT), state.storage.has_downloads().unwrap_or(false))
+        };
+        let view = match view {
+            Ok(view) => view,
+            Err(e) => {
+                ui.colored_label(egui::Color32::RED, t!("history-unavailable", error = e.to_string()));
+                return;
+            }
+        };
+        if !has_entries {
+            ui.label(t!("no-downloads-yet"));
+        } else if view.is_empty() {
+            ui.label(t!("no-downloads-match-the-current-filters"));
+        } else {
+            egui::ScrollArea::vertical().max_height(200.0).id_salt("download_history_scroll").show(ui, |ui| {
+                for (index, (entry, count)) in view.iter().enumerate() {
+                    if options.group_by_day
+                        && let Some(day) = history_day_heading(&view, index, |entry| entry.timestamp)
+                    {
+                        ui.strong(day);
+                    }
+                    ui.horizontal(|ui| {
+                        ui.label(format!("📄 {}", entry.filename));
+                        if let Some(error) = &entry.error {
+                            ui.colored_label(egui::Color32::RED, t!("failed")).on_hover_text(error);
+                        }
+                        Self::render_history_count(ui, *count);
+                    });
+                    if entry.error.is_some() {
+                        ui.label(format!("🔗 {}", entry.url));
+                    } else {
+                        ui.label(format!("📍 {}", entry.local_path));
+                    }
+                    ui.label(format!("🕐 {}", entry.timestamp.format("%Y-%m-%d %H:%M:%S")));
                     ui.separator();
                 }
             });
+            if view.len() == HISTORY_VIEW_LIMIT {
+                ui.weak(t!("history-view-limited", count = HISTORY_VIEW_LIMIT));
+            }
+        }
+    }
+
+    fn history_tab(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.history_kind, HistoryKind::Uploads, t!("history-uploads"));
+            ui.selectable_value(&mut self.history_kind, HistoryKind::Downloads, t!("history-downloads"));
+        });
+        ui.horizontal_wrapped(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.history_options.search)
+                .id_salt("history_tab_search")
+                .hint_text(t!("search-names-and-urls"))
+                .desired_width(180.0));
+            ui.label(t!("history-from"));
+            ui.add(egui::TextEdit::singleline(&mut self.history_from).hint_text("YYYY-MM-DD").desired_width(90.0));
+            ui.label(t!("history-to"));
+            ui.add(egui::TextEdit::singleline(&mut self.history_to).hint_text("YYYY-MM-DD").desired_width(90.0));
+            ui.checkbox(&mut self.history_options.collapse_duplicates, t!("collapse-duplicates"));
+            ui.checkbox(&mut self.history_options.only_failures, t!("only-failures"));
+        });
+        
+        match (history_day_bound(&self.history_from, false), history_day_bound(&self.history_to, true)) {
+            (Ok(since), Ok(until)) => {
+                self.history_options.since = since;
+                self.history_options.until = until;
+            }
+            (Err(date), _) | (_, Err(date)) => {
+                ui.colored_label(egui::Color32::RED, t!("history-invalid-date", date = date));
+                return;
+            }
+        }
+        
+        ui.horizontal(|ui| {
+            for (format, label) in [(ExportFormat::Csv, t!("history-export-csv")), (ExportFormat::Json, t!("history-export-json"))] {
+                if ui.button(label).clicked() {
+                    self.export_history(format);
+                }
+            }
+            match &self.history_export_status {
+                Some(Ok(message)) => {
+                    ui.colored_label(egui::Color32::GREEN, message);
+                }
+                Some(Err(e)) => {
+                    ui.colored_label(egui::Color32::RED, format!("❌ {}", e));
+                }
+                None => {}
+            }
+        });
+        ui.separator();
+        
+        let options = self.history_options.clone();
+        let mut show_qr = None;
+        let state = self.state.lock().unwrap();
+        let shown = match self.history_kind {
+            HistoryKind::Uploads => state.storage.uploads(&options, HISTORY_VIEW_LIMIT).map(|view| {
+                egui::ScrollArea::vertical().id_salt("history_tab_scroll").show(ui, |ui| {
+                    egui::Grid::new("history_tab_uploads").striped(true).num_columns(5).show(ui, |ui| {
+                        for header in [t!("history-column-date"), t!("history-column-name"), t!("history-column-size"), t!("history-column-status"), t!("history-column-link")] {
+                            ui.strong(header);
+                        }
+                        ui.end_row();
+                        for (entry, count) in &view {
+                            ui.label(entry.timestamp.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string());
+                            ui.horizontal(|ui| {
+                                ui.label(&entry.filename);
+                                Self::render_history_count(ui, *count);
+                            });
+                            ui.label(self.format_file_size_bytes(entry.size));
+                            ui.horizontal(|ui| {
+                                if let Some(error) = &entry.error {
+                                    ui.colored_label(egui::Color32::RED, t!("failed")).on_hover_text(error);
+                                } else if entry.verified == Some(false) {
+                                    ui.colored_label(egui::Color32::RED, t!("mismatch"));
+                                } else if entry.verified == Some(true) {
+                                    ui.colored_label(egui::Color32::GREEN, t!("verified"));
+                                }
+                                if entry.anonymous {
+                                    ui.label(t!("anonymous"));
+                                }
+                            });
+                            ui.horizontal(|ui| {
+                                if !entry.url.is_empty() {
+                                    ui.hyperlink_to(&entry.url, &entry.url);
+                                    if ui.small_button("📋").on_hover_text(t!("copy")).clicked() {
+                                        let _ = Clipboard::new().and_then(|mut c| c.set_text(entry.url.clone()));
+                                    }
+                                    if ui.small_button(t!("qr-button")).on_hover_text(t!("qr-show")).clicked() {
+                                        show_qr = Some(entry.url.clone());
+                                    }
+                                }
+                            });
+                            ui.end_row();
+                        }
+                    });
+                });
+                view.len()
+            }),
+            HistoryKind::Downloads => state.storage.downloads(&options, HISTORY_VIEW_LIMIT).map(|view| {
+                egui::ScrollArea::vertical().id_salt("history_tab_scroll").show(ui, |ui| {
+                    egui::Grid::new("history_tab_downloads").striped(true).num_columns(4).show(ui, |ui| {
+                        for header in [t!("history-column-date"), t!("history-column-name"), t!("history-column-saved-to"), t!("history-column-link")] {
+                            ui.strong(header);
+                        }
+                        ui.end_row();
+                        for (entry, count) in &view {
+                            ui.label(entry.timestamp.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string());
+                            ui.horizontal(|ui| {
+                                ui.label(&entry.filename);
+                                Self::render_history_count(ui, *count);
+                            });
+                            if let Some(error) = &entry.error {
+                                ui.colored_label(egui::Color32::RED, t!("failed")).on_hover_text(error);
+                            } else {
+                                ui.label(&entry.local_path);
+                            }
+                            ui.hyperlink_to(&entry.url, &entry.url);
+                            ui.end_row();
+                        }
+                    });
+                });
+                view.len()
+            }),
+        };
+        if show_qr.is_some() {
+            self.qr_link = show_qr;
+        }
+        match shown {
+            Ok(0) => {
+                ui.label(t!("history-no-matches"));
+            }
+            Ok(count) if count == HISTORY_VIEW_LIMIT => {
+                ui.weak(t!("history-view-limited", count = HISTORY_VIEW_LIMIT));
+            }
+            Ok(_) => {}
+            Err(e) => {
+                ui.colored_label(egui::Color32::RED, t!("history-unavailable", error = e.to_string()));
+            }
+        }
+    }
+    
+    /// Save the entries matching the History tab filters, all of them rather than only those on screen
+    fn stats_tab(&mut self, ui: &mut egui::Ui) {
+        let stats = self.state.lock().unwrap().usage_stats.clone();
+        ui.heading(t!("stats-heading"));
+        ui.label(egui::RichText::new(t!("stats-local-only")).small().weak());
+        ui.separator();
+        
+        let Some(since) = stats.since else {
+            ui.label(t!("stats-empty"));
+            return;
+        };
+        egui::Grid::new("stats_totals").num_columns(2).show(ui, |ui| {
+            ui.label(t!("stats-since"));
+            ui.label(since.with_timezone(&chrono::Local).format("%Y-%m-%d").to_string());
+            ui.end_row();
+            ui.label(t!("stats-uploads"));
+            ui.label(t!("stats-files-and-size", files = stats.uploads, size = self.format_file_size_bytes(stats.upload_bytes)));
+            ui.end_row();
+            ui.label(t!("stats-downloads"));
+            ui.label(t!("stats-files-and-size", files = stats.downloads, size = self.format_file_size_bytes(stats.download_bytes)));
+            ui.end_row();
+            ui.label(t!("stats-average-speed"));
+            ui.label(format!("{}/s", self.format_file_size_bytes(stats.average_speed() as u64)));
+            ui.end_row();
+            ui.label(t!("stats-most-active"));
+            let hours: Vec<String> = stats.most_active_hours(3).iter().map(|hour| format!("{:02}:00–{:02}:00", hour, (hour + 1) % 24)).collect();
+            ui.label(hours.join(", "));
+            ui.end_row();
+        });
+        ui.add_space(8.0);
+        
+        ui.strong(t!("stats-by-hour"));
+        Self::render_hour_chart(ui, &stats.hours);
+        ui.add_space(8.0);
+        
+        ui.horizontal(|ui| {
+            if ui.button(t!("stats-export")).clicked() {
+                self.export_stats(&stats);
+            }
+            if ui.button(t!("stats-reset")).clicked() {
+                self.state.lock().unwrap().usage_stats = stats::UsageStats::default();
+                self.save_preferences();
+                self.stats_export_status = None;
+            }
+            match &self.stats_export_status {
+                Some(Ok(message)) => {
+                    ui.colored_label(egui::Color32::GREEN, message);
+                }
+                Some(Err(e)) => {
+                    ui.colored_label(egui::Color32::RED, format!("❌ {}", e));
+                }
+                None => {}
+            }
+        });
+    }
+    
+    /// Bar per hour of the day, scaled to the busiest hour
+    fn render_hour_chart(ui: &mut egui::Ui, hours: &[u64; 24]) {
+        let (rect, response) = ui.allocate_exact_size(egui::vec2(ui.available_width().min(480.0), 100.0), egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        let visuals = ui.visuals();
+        painter.rect_filled(rect, 4.0, visuals.extreme_bg_color);
+        let plot = rect.shrink2(egui::vec2(4.0, 14.0));
+        let max = hours.iter().copied().max().unwrap_or(0).max(1) as f32;
+        let width = plot.width() / 24.0;
+        for (hour, count) in hours.iter().enumerate() {
+            let left = plot.left() + hour as f32 * width;
+            let height = *count as f32 / max * plot.height();
+            let bar = egui::Rect::from_min_max(egui::pos2(left + 1.0, plot.bottom() - height), egui::pos2(left + width - 1.0, plot.bottom()));
+            painter.rect_filled(bar, 1.0, egui::Color32::from_rgb(0, 120, 215));
+            if hour % 6 == 0 {
+                painter.text(egui::pos2(left, rect.bottom()), egui::Align2::LEFT_BOTTOM, format!("{:02}", hour), egui::FontId::proportional(10.0), visuals.weak_text_color());
+            }
+        }
+        if let Some(hover) = response.hover_pos() {
+            let hour = (((hover.x - plot.left()) / width).floor().max(0.0) as usize).min(23);
+            response.on_hover_text(t!("stats-hour-tooltip", hour = format!("{:02}:00", hour), files = hours[hour]));
+        }
+    }
+    
+    fn export_stats(&mut self, stats: &stats::UsageStats) {
+        let Some(path) = FileDialog::new()
+            .set_file_name(format!("pixeldrain-stats-{}.json", Utc::now().format("%Y%m%d")))
+            .add_filter("JSON", &["json"])
+            .save_file()
+        else {
+            return;
+        };
+        self.stats_export_status = Some(
+            serde_json::to_string_pretty(stats)
+                .map_err(|e| e.to_string())
+                .and_then(|data| fs::write(&path, data).map_err(|e| e.to_string()))
+                .map(|()| t!("history-exported", path = path.display().to_string())),
+        );
+    }
+    
+    fn export_history(&mut self, format: ExportFormat) {
+        let kind = match self.history_kind {
+            HistoryKind::Uploads => "uploads",
+            HistoryKind::Downloads => "downloads",
+        };
+        let Some(path) = FileDialog::new()
+            .set_file_name(format!("pixeldrain-{}-{}.{}", kind, Utc::now().format("%Y%m%d"), format.extension()))
+            .add_filter(format.extension().to_uppercase(), &[format.extension()])
+            .save_file()
+        else {
+            return;
+        };
+        let data = history::export(self.state.lock().unwrap().storage.as_ref(), self.history_kind, &self.history_options, format);
+        self.history_export_status = Some(
+            data.and_then(|data| fs::write(&path, data).map_err(|e| e.to_string()))
+                .map(|()| t!("history-exported", path = path.display().to_string())),
+        );
+    }
+    
+    /// Locally counted monthly download traffic against the estimated free-tier allowance
+    fn render_download_quota(&mut self, ui: &mut egui::Ui) {
+        let (usage, allowance) = {
+            let mut state = self.state.lock().unwrap();
+            state.download_usage.roll_over();
+            (state.download_usage.clone(), state.monthly_download_allowance)
+        };
+        
+        egui::CollapsingHeader::new(t!("download-quota-estimate")).id_salt("download_quota").show(ui, |ui| {
+            let remaining = allowance.saturating_sub(usage.bytes);
+            let fraction = if allowance > 0 { usage.bytes as f32 / allowance as f32 } else { 1.0 };
+            ui.add(egui::ProgressBar::new(fraction.min(1.0)).text(t!(
+                "quota-used-this-month",
+                used = self.format_file_size_bytes(usage.bytes),
+                allowance = self.format_file_size_bytes(allowance),
+            )));
+            ui.label(t!("quota-remaining", remaining = self.format_file_size_bytes(remaining)));
+            if usage.rate_limited > 0 {
+                ui.colored_label(egui::Color32::YELLOW, t!("quota-rate-limited", count = usage.rate_limited));
+            }
+            
+            ui.horizontal(|ui| {
+                ui.label(t!("monthly-allowance"));
+                if let Some(allowance) = Self::size_field(ui, "monthly_allowance", allowance).filter(|&allowance| allowance > 0) {
+                    self.state.lock().unwrap().monthly_download_allowance = allowance;
+                    self.save_preferences();
+                }
+            });
+            
+            ui.horizontal(|ui| {
+                match &usage.server {
+                    Some(server) if server.limit > 0 => {
+                        ui.label(t!(
+                            "quota-server",
+                            used = self.format_file_size_bytes(server.used.max(0) as u64),
+                            limit = self.format_file_size_bytes(server.limit as u64),
+                            checked = server.checked.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string(),
+                        ));
+                    }
+                    Some(_) => {
+                        ui.label(t!("server-reports-no-transfer-limit-for"));
+                    }
+                    None => {
+                        ui.label(t!("server-limits-not-checked-yet"));
+                    }
+                }
+                if ui.small_button("🔄").on_hover_text(t!("check-rate-limits")).clicked() {
+                    let state = self.state.clone();
+                    let api_factory = self.api_factory.clone();
+                    self.workers.spawn(move || {
+                        if let Ok(client) = api_factory(PixelDrainConfig::default()) {
+                            Self::refresh_rate_limits(client.as_ref(), &state);
+                        }
+                    });
+                }
+            });
+            ui.weak(t!("counted-by-this-app-only-downloads"));
+        });
+    }
+
+    /// Store the server-reported transfer limit, best effort
+    fn refresh_rate_limits(client: &dyn PixelDrainApi, state: &Arc<Mutex<AppState>>) {
+        if let Ok(limits) = client.get_rate_limits() {
+            let mut state = state.lock().unwrap();
+            state.download_usage.server = Some(ServerTransferLimit {
+                limit: limits.transfer_limit,
+                used: limits.transfer_limit_used,
+                checked: Utc::now(),
+            });
+            state.settings_dirty = true;
+        }
+    }
+
+    /// Search box and view toggles above a history list.
+    /// Returns true when a toggle changed, so the caller can persist them.
+    fn render_history_options(ui: &mut egui::Ui, search_id: &str, options: &mut HistoryViewOptions) -> bool {
+        let mut toggled = false;
+        ui.horizontal_wrapped(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut options.search)
+                .id_salt(search_id)
+                .hint_text(t!("search-names-and-urls"))
+                .desired_width(160.0));
+            toggled |= ui.checkbox(&mut options.group_by_day, t!("group-by-day")).changed();
+            toggled |= ui.checkbox(&mut options.collapse_duplicates, t!("collapse-duplicates")).changed();
+            toggled |= ui.checkbox(&mut options.only_failures, t!("only-failures")).changed();
+        });
+        toggled
+    }
+
+    fn render_history_count(ui: &mut egui::Ui, count: usize) {
+        if count > 1 {
+            ui.weak(format!("×{}", count)).on_hover_text(t!("history-duplicates", count = count));
         }
     }
 
@@ -693,7 +2951,7 @@ impl PixelDrainApp {
         };
         
         if let Some(error_msg) = error {
-            ui.colored_label(egui::Color32::RED, format!("❌ Error: {}", error_msg));
+            ui.colored_label(egui::Color32::RED, t!("error-message", error = error_msg.as_str()));
             ui.separator();
         }
 
@@ -702,18 +2960,18 @@ impl PixelDrainApp {
         let file_delete_loading = *self.file_delete_loading.lock().unwrap();
         
         ui.horizontal(|ui| {
-            ui.label("Your Files");
+            ui.label(t!("your-files"));
             if files_loading {
-                self.render_loading_spinner(ui, "Loading files...");
+                self.render_loading_spinner(ui, &t!("loading-files"));
             } else {
-                if ui.button("🔄 Refresh").clicked() {
+                if ui.button(t!("refresh")).clicked() {
                     refresh_clicked = true;
                 }
             }
         });
 
         if file_delete_loading {
-            self.render_loading_spinner(ui, "Deleting file...");
+            self.render_loading_spinner(ui, &t!("deleting-file"));
         }
 
         // Check API key status
@@ -725,60 +2983,139 @@ impl PixelDrainApp {
 
         let state = self.state.lock().unwrap();
         let file_list = state.file_list.clone();
+        let base_url = state.base_url.clone();
+        let file_changes = state.file_changes.clone();
         drop(state); // Release the lock early
         
         if file_list.is_empty() && !files_loading {
             if !api_key_set && !env_key_set {
-                ui.colored_label(egui::Color32::YELLOW, "⚠ No API key configured");
-                ui.label("Set your API key in Settings or use PIXELDRAIN_API_KEY environment variable (settings override environment)");
-                ui.label("Get your API key from https://pixeldrain.com/user/settings");
+                ui.colored_label(egui::Color32::YELLOW, t!("no-api-key-configured"));
+                ui.label(t!("set-your-api-key-in-settings"));
+                ui.label(t!("api-key-get-from", url = format!("{}/user/settings", base_url)));
             } else if api_key_set || env_key_set {
-                ui.label("No files found. Click 'Refresh' to load your files.");
+                ui.label(t!("no-files-found-click-refresh-to"));
             }
         } else if !file_list.is_empty() {
+            self.render_file_filters(ui, &file_list);
+            let all_files = file_list;
+            let total_files = all_files.len();
+            let file_list = self.filtered_files(all_files.clone());
+            if file_list.is_empty() {
+                ui.label(t!("no-files-match-the-current-search"));
+            } else if file_list.len() != total_files {
+                ui.label(t!("files-showing", shown = file_list.len(), total = total_files));
+            }
+            self.render_file_changes_summary(ui, &file_changes);
+            
+            // Selection toolbar
+            let mut zip_clicked = false;
+            let mut copy_selected_clicked = false;
+            let mut delete_selected_clicked = false;
+            let selected_count = all_files.iter().filter(|f| self.selected_files.contains(&f.id)).count();
+            let zip_progress = *self.files_zip_progress.lock().unwrap();
+            let batch_delete_progress = *self.batch_delete_progress.lock().unwrap();
+            ui.horizontal(|ui| {
+                if ui.button(t!("select-all")).on_hover_text(t!("select-all-files-matching-the-current")).clicked() {
+                    self.selected_files.extend(file_list.iter().map(|f| f.id.clone()));
+                }
+                if ui.button(t!("clear-selection")).clicked() {
+                    self.selected_files.clear();
+                }
+                ui.label(t!("files-selected-count", count = selected_count));
+                if zip_progress.is_none()
+                    && ui.add_enabled(selected_count > 0, egui::Button::new(t!("download-selected-as-zip"))).clicked()
+                {
+                    zip_clicked = true;
+                }
+                if ui.add_enabled(selected_count > 0, egui::Button::new(t!("copy-selected-urls"))).clicked() {
+                    copy_selected_clicked = true;
+                }
+                if batch_delete_progress.is_none()
+                    && !file_delete_loading
+                    && ui.add_enabled(selected_count > 0, egui::Button::new(t!("delete-selected"))).clicked()
+                {
+                    delete_selected_clicked = true;
+                }
+            });
+            if let Some((done, total)) = batch_delete_progress {
+                ui.label(t!("files-deleting", done = done, total = total));
+                ui.add(egui::ProgressBar::new(done as f32 / total.max(1) as f32).show_percentage());
+                ui.ctx().request_repaint_after(std::time::Duration::from_millis(100));
+            }
+            if let Some(progress) = zip_progress {
+                ui.label(t!("building-zip-bundle"));
+                ui.add(egui::ProgressBar::new(progress).show_percentage());
+                ui.ctx().request_repaint_after(std::time::Duration::from_millis(100));
+            } else if let Some(status) = self.files_zip_status.lock().unwrap().as_ref() {
+                ui.label(status);
+            }
+            self.render_visual_duplicates(ui, &all_files, batch_delete_progress.is_none() && !file_delete_loading);
+            self.render_account_export(ui);
+            
+            if let Some(id) = self.file_details.clone() {
+                match all_files.iter().find(|f| f.id == id) {
+                    Some(file) => {
+                        egui::SidePanel::right("file_details_panel")
+                            .default_width(320.0)
+                            .show_inside(ui, |ui| self.render_file_details(ui, file, &base_url));
+                    }
+                    // Deleted, or gone after a refresh
+                    None => self.file_details = None,
+                }
+            }
+            
             let mut copy_clicked = None;
+            let (link_format, direct_categories) = self.state.lock().unwrap().copied_link_formats();
             let mut delete_clicked = None;
+            let mut details_clicked = None;
             let _ctx = ui.ctx().clone();
+            let grid_view = self.state.lock().unwrap().files_grid_view;
             egui::ScrollArea::vertical().show(ui, |ui| {
-                for file in &file_list {
-                    ui.horizontal(|ui| {
-                        // Thumbnail logic
-                        let has_thumb = !file.thumbnail_href.is_empty();
-                        if has_thumb {
-                            if let Some(tex) = self.thumbnail_cache.get(&file.id) {
-                                ui.add(egui::Image::from_texture(tex).max_size(egui::Vec2::splat(48.0)));
-                            } else if self.thumbnail_loading.get(&file.id).is_none() {
-                                // Start background fetch
-                                self.thumbnail_loading.insert(file.id.clone(), true);
-                                if let Some(tx) = &self.thumbnail_tx {
-                                    let tx = tx.clone();
-                                    let file_id = file.id.clone();
-                                    let api_key = self.get_api_key();
-                                    std::thread::spawn(move || {
-                                        let config = if let Some(key) = api_key {
-                                            pixeldrain_api::PixelDrainConfig::default().with_api_key(key)
-                                        } else {
-                                            pixeldrain_api::PixelDrainConfig::default()
-                                        };
-                                        if let Ok(client) = pixeldrain_api::PixelDrainClient::new(config) {
-                                            if let Ok(bytes) = client.fetch_thumbnail_bytes(&file_id, 48, 48) {
-                                                let _ = tx.send((file_id, bytes));
-                                            }
+                if grid_view {
+                    ui.horizontal_wrapped(|ui| {
+                        for file in &file_list {
+                            egui::Frame::group(ui.style()).show(ui, |ui| {
+                                ui.set_width(GRID_CELL_WIDTH);
+                                ui.vertical_centered(|ui| {
+                                    self.render_file_checkbox(ui, &file.id);
+                                    self.render_file_thumbnail(ui, file, 96.0);
+                                    Self::render_file_change_badge(ui, &file_changes, &file.id);
+                                    if ui.add(egui::Label::new(&file.name).truncate().sense(egui::Sense::click()))
+                                        .on_hover_text(t!("file-show-details-of", name = file.name.as_str()))
+                                        .clicked()
+                                    {
+                                        details_clicked = Some(file.id.clone());
+                                    }
+                                    ui.label(self.format_file_size_bytes(file.size));
+                                    ui.horizontal(|ui| {
+                                        if ui.small_button("📋").on_hover_text(t!("copy-url")).clicked() {
+                                            copy_clicked = Some((file.id.clone(), file.name.clone(), link_format.for_mime_type(&file.mime_type, &direct_categories)));
+                                        }
+                                        Self::render_copy_as_menu(ui, file, &mut copy_clicked);
+                                        if !file_delete_loading && ui.small_button("🗑").on_hover_text(t!("delete")).clicked() {
+                                            delete_clicked = Some(file.id.clone());
                                         }
                                     });
-                                }
-                                ui.label("🖼");
-                            } else {
-                                ui.label("🖼");
-                            }
-                        } else {
-                            ui.label("📄");
+                                });
+                            });
                         }
+                    });
+                    return;
+                }
+                
+                for file in &file_list {
+                    ui.horizontal(|ui| {
+                        self.render_file_checkbox(ui, &file.id);
+                        self.render_file_thumbnail(ui, file, 48.0);
                         // File name and stats
-                        ui.label(format!("{}", file.name));
+                        Self::render_file_change_badge(ui, &file_changes, &file.id);
+                        let selected = self.file_details.as_ref() == Some(&file.id);
+                        if ui.selectable_label(selected, &file.name).on_hover_text(t!("file-show-details")).clicked() {
+                            details_clicked = Some(file.id.clone());
+                        }
                         ui.label(format!("({})", self.format_file_size_bytes(file.size)));
-                        ui.label(format!("👁 {} views", file.views));
-                        ui.label(format!("⬇ {} downloads", file.downloads));
+                        ui.label(t!("file-views", count = file.views));
+                        ui.label(t!("file-downloads", count = file.downloads));
                     });
                     
                     // Second line: File ID and date
@@ -789,11 +3126,12 @@ impl PixelDrainApp {
                     
                     // Third line: Action buttons
                     ui.horizontal(|ui| {
-                        if ui.button("📋 Copy URL").clicked() {
-                            copy_clicked = Some(file.id.clone());
+                        if ui.button(t!("copy-url-button")).clicked() {
+                            copy_clicked = Some((file.id.clone(), file.name.clone(), link_format.for_mime_type(&file.mime_type, &direct_categories)));
                         }
+                        Self::render_copy_as_menu(ui, file, &mut copy_clicked);
                         
-                        if !file_delete_loading && ui.button("🗑 Delete").clicked() {
+                        if !file_delete_loading && ui.button(t!("delete-button")).clicked() {
                             delete_clicked = Some(file.id.clone());
                         }
                     });
@@ -803,13 +3141,52 @@ impl PixelDrainApp {
             });
             
             // Handle actions outside the closure to avoid borrowing issues
-            if let Some(file_id) = copy_clicked {
-                let url = format!("https://pixeldrain.com/u/{}", file_id);
-                let _ = Clipboard::new().and_then(|mut c| c.set_text(url));
+            if let Some(file_id) = details_clicked {
+                // Clicking the open file again closes the pane
+                self.file_details = (self.file_details.as_ref() != Some(&file_id)).then_some(file_id);
+            }
+            
+            if let Some((file_id, name, format)) = copy_clicked {
+                let link = format.format(&base_url, &file_id, &name);
+                let _ = Clipboard::new().and_then(|mut c| c.set_text(link));
             }
             
             if let Some(file_id) = delete_clicked {
-                self.delete_file(&file_id);
+                self.request_delete(undo::Deletion::Files(vec![file_id]));
+            }
+            
+            if copy_selected_clicked {
+                let (link_format, direct_categories) = self.state.lock().unwrap().copied_link_formats();
+                let urls: Vec<String> = all_files
+                    .iter()
+                    .filter(|f| self.selected_files.contains(&f.id))
+                    .map(|f| link_format.for_mime_type(&f.mime_type, &direct_categories).format(&base_url, &f.id, &f.name))
+                    .collect();
+                let _ = Clipboard::new().and_then(|mut c| c.set_text(urls.join("\n")));
+            }
+            
+            if delete_selected_clicked {
+                let ids: Vec<String> = all_files
+                    .iter()
+                    .filter(|f| self.selected_files.contains(&f.id))
+                    .map(|f| f.id.clone())
+                    .collect();
+                self.request_delete(undo::Deletion::Files(ids));
+            }
+            
+            if zip_clicked {
+                let selected: Vec<FileInfo> = all_files
+                    .into_iter()
+                    .filter(|f| self.selected_files.contains(&f.id))
+                    .collect();
+                let default_name = format!("pixeldrain-bundle-{}.zip", Utc::now().format("%Y%m%d-%H%M%S"));
+                if let Some(dest) = FileDialog::new()
+                    .set_file_name(&default_name)
+                    .add_filter("ZIP archive", &["zip"])
+                    .save_file()
+                {
+                    self.start_zip_download(selected, dest);
+                }
             }
         }
         
@@ -839,130 +3216,428 @@ impl PixelDrainApp {
         }
     }
 
-    fn lists_tab(&mut self, ui: &mut egui::Ui) {
-        // Collect all actions to perform after UI rendering
-        let mut refresh_lists = false;
-        let mut create_list = false;
-        let mut delete_list_id: Option<String> = None;
-        let mut select_list_data: Option<(String, String, Vec<pixeldrain_api::ListFile>)> = None;
-        let mut new_list_file_changes: Vec<(String, bool)> = Vec::new(); // (file_id, add_or_remove)
-        let mut edit_list_file_changes: Vec<(String, bool)> = Vec::new();
-        let mut update_list_id: Option<String> = None;
-        let remove_from_existing: Vec<(String, String)> = Vec::new(); // (list_id, file_id)
-        
-        // Get loading states
-        let lists_loading = *self.lists_loading.lock().unwrap();
-        let list_create_loading = *self.list_create_loading.lock().unwrap();
-        let list_update_loading = *self.list_update_loading.lock().unwrap();
-        let list_delete_loading = *self.list_delete_loading.lock().unwrap();
-        
-        // Read current lists and error state
-        let lists = self.lists.lock().unwrap().clone();
-        let list_error = self.list_error.lock().unwrap().clone();
-        
-        ui.heading("Your Lists");
-        ui.horizontal(|ui| {
-            if lists_loading {
-                self.render_loading_spinner(ui, "Refreshing lists...");
-            } else {
-                if ui.button("🔄 Refresh Lists").clicked() {
-                    refresh_lists = true;
-                }
-            }
+    /// Images of the account that look the same, with the copies to clean up
+    fn render_visual_duplicates(&mut self, ui: &mut egui::Ui, files: &[FileInfo], can_delete: bool) {
+        let progress = *self.visual_duplicates_progress.lock().unwrap();
+        let groups = self.visual_duplicates.lock().unwrap().clone();
+        // Deleted files drop out, and groups left with one file
+        let groups: Option<Vec<Vec<&FileInfo>>> = groups.map(|groups| {
+            groups
+                .iter()
+                .map(|group| group.iter().filter_map(|id| files.iter().find(|f| &f.id == id)).collect::<Vec<_>>())
+                .filter(|group| group.len() > 1)
+                .collect()
         });
-        
-        if let Some(err) = &list_error {
-            ui.colored_label(egui::Color32::RED, err);
-        }
-        
-        // Create section
-        ui.separator();
-        ui.heading("Create New List");
-        
-        if list_create_loading {
-            self.render_loading_spinner(ui, "Creating list...");
-        } else {
+        egui::CollapsingHeader::new(t!("visual-duplicates")).show(ui, |ui| {
+            ui.label(t!("visual-duplicates-help"));
+            let mut delete_copies = None;
             ui.horizontal(|ui| {
-                ui.label("Title:");
-                ui.text_edit_singleline(&mut self.new_list_title);
-            });
-            ui.label("Select files to add to the list:");
-            let file_list = self.state.lock().unwrap().file_list.clone();
-            
-            egui::ScrollArea::vertical().max_height(100.0).id_salt("new_list_files_scroll").show(ui, |ui| {
-                for file in &file_list {
-                    let mut selected = self.new_list_files.iter().any(|f| f.id == file.id);
-                    if ui.checkbox(&mut selected, &file.name).clicked() {
-                        new_list_file_changes.push((file.id.clone(), selected));
-                    }
+                if let Some((done, total)) = progress {
+                    self.render_loading_spinner(ui, &t!("visual-duplicates-hashing", done = done, total = total));
+                    ui.ctx().request_repaint_after(std::time::Duration::from_millis(100));
+                } else if ui.button(t!("visual-duplicates-find")).clicked() {
+                    self.find_visual_duplicates(files);
                 }
-            });
-            
-            if ui.button("Create List").clicked() {
-                create_list = true;
-            }
-        }
-        
-        // Lists section
-        ui.separator();
-        ui.heading("Your Lists");
-        
-        if list_delete_loading {
-            self.render_loading_spinner(ui, "Deleting list...");
-        }
-        
-        if lists.is_empty() && !lists_loading {
-            ui.label("No lists found. Click 'Refresh Lists' or create a new list.");
-        } else if !lists.is_empty() {
-            egui::ScrollArea::vertical().max_height(200.0).id_salt("user_lists_scroll").show(ui, |ui| {
-                for list in &lists {
-                    let selected = self.selected_list_id.as_ref().map_or(false, |id| id == &list.id);
-                    if ui.selectable_label(selected, &list.title).clicked() {
-                        select_list_data = Some((list.id.clone(), list.title.clone(), Vec::new())); // Empty files for now
-                    }
-                    ui.label(format!("Files: {} | Created: {}", list.file_count, list.date_created.format("%Y-%m-%d %H:%M:%S")));
-                    if list.can_edit && !list_delete_loading && ui.button("🗑 Delete").clicked() {
-                        delete_list_id = Some(list.id.clone());
-                    }
-                    ui.separator();
+                if let Some(groups) = &groups
+                    && !groups.is_empty()
+                    && ui.button(t!("visual-duplicates-select-copies")).on_hover_text(t!("visual-duplicates-copies-tooltip")).clicked()
+                {
+                    self.selected_files.extend(groups.iter().flat_map(|group| group[1..].iter().map(|f| f.id.clone())));
                 }
             });
-        }
-        
-        // Edit section
-        if let Some(selected_id) = &self.selected_list_id {
-            if let Some(list) = lists.iter().find(|l| &l.id == selected_id) {
-                ui.separator();
-                ui.heading(format!("Edit List: {}", list.title));
-                
-                if list_update_loading {
-                    self.render_loading_spinner(ui, "Updating list...");
-                } else {
-                    ui.horizontal(|ui| {
-                        ui.label("Title:");
-                        ui.text_edit_singleline(&mut self.edit_list_title);
-                    });
-                    ui.label("Add/remove files:");
-                    
-                    let file_list = self.state.lock().unwrap().file_list.clone();
-                    egui::ScrollArea::vertical().max_height(100.0).id_salt("edit_list_files_scroll").show(ui, |ui| {
-                        for file in &file_list {
-                            let mut selected = self.edit_list_files.iter().any(|f| f.id == file.id);
-                            if ui.checkbox(&mut selected, &file.name).clicked() {
-                                edit_list_file_changes.push((file.id.clone(), selected));
+            match &groups {
+                Some(groups) if groups.is_empty() => {
+                    ui.label(t!("visual-duplicates-none"));
+                }
+                Some(groups) => {
+                    for group in groups {
+                        egui::Frame::group(ui.style()).show(ui, |ui| {
+                            for (index, file) in group.iter().enumerate() {
+                                ui.horizontal(|ui| {
+                                    self.render_file_checkbox(ui, &file.id);
+                                    self.render_file_thumbnail(ui, file, 32.0);
+                                    ui.label(&file.name);
+                                    ui.weak(format!("📅 {}", file.date_upload.format("%Y-%m-%d %H:%M")));
+                                    if index == 0 {
+                                        ui.weak(t!("visual-duplicates-oldest"));
+                                    }
+                                });
                             }
-                        }
-                    });
-                    
-                    if ui.button("Save Changes").clicked() {
-                        update_list_id = Some(selected_id.clone());
+                            // One group at a time, each is looked at before its copies go
+                            if ui.add_enabled(can_delete, egui::Button::new(t!("visual-duplicates-delete-copies", count = group.len() - 1)))
+                                .on_hover_text(t!("visual-duplicates-copies-tooltip"))
+                                .clicked()
+                            {
+                                delete_copies = Some(group[1..].iter().map(|f| f.id.clone()).collect());
+                            }
+                        });
                     }
                 }
-                
-                ui.label(format!("Files in this list: {}", list.file_count));
-                ui.label("Note: Edit individual files by fetching the detailed list view.");
-                // TODO: Implement detailed list view when needed
+                None => {}
             }
+            if let Some(ids) = delete_copies {
+                self.request_delete(undo::Deletion::Files(ids));
+            }
+        });
+    }
+
+    /// Hash the thumbnails of the images among `files` in the background and group the ones that look the same
+    fn find_visual_duplicates(&self, files: &[FileInfo]) {
+        let images: Vec<FileInfo> = files.iter().filter(|f| f.mime_type.starts_with("image/")).cloned().collect();
+        let result = self.visual_duplicates.clone();
+        let progress = self.visual_duplicates_progress.clone();
+        let client = self.make_api_client();
+        let workers = self.workers.clone();
+        let cache_limit = self.state.lock().unwrap().cache_limit;
+        let metadata_limit = self.metadata_limit.clone();
+        let journal = self.journal.clone();
+        *progress.lock().unwrap() = Some((0, images.len()));
+        self.workers.spawn(move || {
+            let cache_dir = cache::cache_dir();
+            let groups = Self::visual_duplicate_groups(client.as_ref(), &images, Some(&cache_dir), &metadata_limit, || workers.is_shutting_down(), |done| {
+                *progress.lock().unwrap() = Some((done, images.len()));
+            });
+            if let Err(e) = cache::enforce_limit(&cache_dir, cache_limit, journal.disk_usage()) {
+                warn!("Failed to trim the thumbnail cache: {}", e);
+            }
+            *result.lock().unwrap() = Some(groups);
+            *progress.lock().unwrap() = None;
+        });
+    }
+
+    /// IDs of `images` that look the same, grouped and oldest first. Thumbnails are
+    /// taken from `cache_dir` when they're there and stored there otherwise.
+    fn visual_duplicate_groups(
+        client: &dyn PixelDrainApi,
+        images: &[FileInfo],
+        cache_dir: Option<&Path>,
+        metadata_limit: &Limit,
+        cancelled: impl Fn() -> bool,
+        on_progress: impl Fn(usize),
+    ) -> Vec<Vec<String>> {
+        let mut hashed: Vec<(&FileInfo, u64)> = Vec::new();
+        for (index, file) in images.iter().enumerate() {
+            if cancelled() {
+                break;
+            }
+            let bytes = match cache_dir.and_then(|dir| cache::read_thumbnail(dir, &file.id)) {
+                Some(bytes) => Some(bytes),
+                None => {
+                    let _permit = metadata_limit.acquire();
+                    match client.fetch_thumbnail_bytes(&file.id, THUMBNAIL_SIZE, THUMBNAIL_SIZE) {
+                        Ok(bytes) => {
+                            if let Some(dir) = cache_dir
+                                && let Err(e) = cache::store_thumbnail(dir, &file.id, &bytes)
+                            {
+                                warn!("Failed to cache thumbnail {}: {}", file.id, e);
+                            }
+                            Some(bytes)
+                        }
+                        Err(e) => {
+                            warn!("Failed to fetch the thumbnail of {}: {}", file.id, e);
+                            None
+                        }
+                    }
+                }
+            };
+            if let Some(image) = bytes.and_then(|bytes| image::load_from_memory(&bytes).ok()) {
+                hashed.push((file, phash::difference_hash(&image)));
+            }
+            on_progress(index + 1);
+        }
+        let hashes: Vec<u64> = hashed.iter().map(|(_, hash)| *hash).collect();
+        phash::group(&hashes, phash::MAX_DISTANCE)
+            .into_iter()
+            .map(|group| {
+                let mut files: Vec<&FileInfo> = group.into_iter().map(|index| hashed[index].0).collect();
+                files.sort_by_key(|f| f.date_upload);
+                files.into_iter().map(|f| f.id.clone()).collect()
+            })
+            .collect()
+    }
+
+    fn render_file_checkbox(&mut self, ui: &mut egui::Ui, file_id: &str) {
+        let mut selected = self.selected_files.contains(file_id);
+        if ui.checkbox(&mut selected, "").changed() {
+            if selected {
+                self.selected_files.insert(file_id.to_string());
+            } else {
+                self.selected_files.remove(file_id);
+            }
+        }
+    }
+    
+    fn render_file_thumbnail(&mut self, ui: &mut egui::Ui, file: &FileInfo, size: f32) {
+        if file.thumbnail_href.is_empty() {
+            ui.label("📄");
+            return;
+        }
+        if let Some(tex) = self.thumbnail_cache.get(&file.id) {
+            ui.add(egui::Image::from_texture(tex).max_size(egui::Vec2::splat(size)));
+            return;
+        }
+        if !self.thumbnail_loading.contains_key(&file.id) {
+            // Start background fetch
+            self.thumbnail_loading.insert(file.id.clone(), true);
+            if let Some(tx) = &self.thumbnail_tx {
+                let tx = tx.clone();
+                let file_id = file.id.clone();
+                let api_key = self.get_api_key();
+                let api_factory = self.api_factory.clone();
+                let cache_limit = self.state.lock().unwrap().cache_limit;
+                let metadata_limit = self.metadata_limit.clone();
+                let journal = self.journal.clone();
+                self.workers.spawn(move || {
+                    let cache_dir = cache::cache_dir();
+                    if let Some(bytes) = cache::read_thumbnail(&cache_dir, &file_id) {
+                        let _ = tx.send((file_id, bytes));
+                        return;
+                    }
+                    let _permit = metadata_limit.acquire();
+                    let config = if let Some(key) = api_key {
+                        pixeldrain_api::PixelDrainConfig::default().with_api_key(key)
+                    } else {
+                        pixeldrain_api::PixelDrainConfig::default()
+                    };
+                    // Fetch at grid size, the list view scales it down
+                    if let Ok(client) = api_factory(config)
+                        && let Ok(bytes) = client.fetch_thumbnail_bytes(&file_id, THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+                    {
+                        if let Err(e) = cache::store_thumbnail(&cache_dir, &file_id, &bytes)
+                            .and_then(|()| cache::enforce_limit(&cache_dir, cache_limit, journal.disk_usage()))
+                        {
+                            warn!("Failed to cache thumbnail {}: {}", file_id, e);
+                        }
+                        let _ = tx.send((file_id, bytes));
+                    }
+                });
+            }
+        }
+        ui.label("🖼");
+    }
+    
+    fn render_file_filters(&mut self, ui: &mut egui::Ui, files: &[FileInfo]) {
+        let mut mime_types: Vec<&str> = files.iter().map(|f| f.mime_type.as_str()).collect();
+        mime_types.sort_unstable();
+        mime_types.dedup();
+        
+        ui.horizontal(|ui| {
+            ui.label("🔍");
+            ui.add(egui::TextEdit::singleline(&mut self.file_search)
+                .hint_text(t!("search-by-name-or-id"))
+                .desired_width(150.0));
+            
+            egui::ComboBox::from_id_salt("file_mime_filter")
+                .selected_text(self.file_mime_filter.clone().unwrap_or_else(|| t!("filter-all-types")))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.file_mime_filter, None, t!("filter-all-types"));
+                    for mime in &mime_types {
+                        ui.selectable_value(&mut self.file_mime_filter, Some(mime.to_string()), *mime);
+                    }
+                });
+            
+            ui.label(t!("sort-by"));
+            egui::ComboBox::from_id_salt("file_sort")
+                .selected_text(self.file_sort.label())
+                .show_ui(ui, |ui| {
+                    for sort in FileSort::ALL {
+                        ui.selectable_value(&mut self.file_sort, sort, sort.label());
+                    }
+                });
+            let (arrow, tooltip) = if self.file_sort_ascending {
+                ("⬆", t!("sort-ascending"))
+            } else {
+                ("⬇", t!("sort-descending"))
+            };
+            if ui.button(arrow).on_hover_text(tooltip).clicked() {
+                self.file_sort_ascending = !self.file_sort_ascending;
+            }
+            
+            let grid_view = self.state.lock().unwrap().files_grid_view;
+            let (view_icon, view_tooltip) = if grid_view {
+                ("☰", t!("view-switch-list"))
+            } else {
+                ("▦", t!("view-switch-grid"))
+            };
+            if ui.button(view_icon).on_hover_text(view_tooltip).clicked() {
+                self.state.lock().unwrap().files_grid_view = !grid_view;
+                self.save_preferences();
+            }
+        });
+    }
+    
+    /// Apply the Files tab search, MIME filter and sort order
+    fn filtered_files(&self, mut files: Vec<FileInfo>) -> Vec<FileInfo> {
+        let query = self.file_search.trim().to_lowercase();
+        files.retain(|f| {
+            let matches_query = query.is_empty()
+                || f.name.to_lowercase().contains(&query)
+                || f.id.to_lowercase().contains(&query);
+            let matches_mime = self.file_mime_filter.as_ref().is_none_or(|m| &f.mime_type == m);
+            matches_query && matches_mime && !self.pending_deletes.hides_file(&f.id)
+        });
+        
+        files.sort_by(|a, b| {
+            let ordering = match self.file_sort {
+                FileSort::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+                FileSort::Size => a.size.cmp(&b.size),
+                FileSort::Views => a.views.cmp(&b.views),
+                FileSort::Downloads => a.downloads.cmp(&b.downloads),
+                FileSort::UploadDate => a.date_upload.cmp(&b.date_upload),
+            };
+            if self.file_sort_ascending { ordering } else { ordering.reverse() }
+        });
+        files
+    }
+
+    fn lists_tab(&mut self, ui: &mut egui::Ui) {
+        // Collect all actions to perform after UI rendering
+        let mut refresh_lists = false;
+        let mut create_list = false;
+        let mut delete_list_id: Option<String> = None;
+        let mut select_list_data: Option<(String, String)> = None;
+        let mut new_list_file_changes: Vec<(String, bool)> = Vec::new(); // (file_id, add_or_remove)
+        let mut edit_list_file_changes: Vec<(String, bool)> = Vec::new();
+        let mut update_list_id: Option<String> = None;
+        let mut zip_list: Option<(String, String)> = None; // (list_id, title)
+        
+        // Get loading states
+        let lists_loading = *self.lists_loading.lock().unwrap();
+        let list_create_loading = *self.list_create_loading.lock().unwrap();
+        let list_update_loading = *self.list_update_loading.lock().unwrap();
+        let list_delete_loading = *self.list_delete_loading.lock().unwrap();
+        let download_running = *self.download_thread_running.lock().unwrap();
+        
+        // Read current lists and error state, lists about to be deleted are gone already
+        let mut lists = self.lists.lock().unwrap().clone();
+        lists.retain(|list| !self.pending_deletes.hides_list(&list.id));
+        let list_error = self.list_error.lock().unwrap().clone();
+        
+        ui.heading(t!("your-lists"));
+        ui.horizontal(|ui| {
+            if lists_loading {
+                self.render_loading_spinner(ui, &t!("refreshing-lists"));
+            } else {
+                if ui.button(t!("refresh-lists")).clicked() {
+                    refresh_lists = true;
+                }
+            }
+            if ui.button(t!("share-privately"))
+                .on_hover_text(t!("lists-are-always-public-share-a"))
+                .clicked()
+            {
+                self.share_dialog_open = true;
+            }
+        });
+        
+        if let Some(err) = &list_error {
+            ui.colored_label(egui::Color32::RED, err);
+        }
+        
+        if download_running {
+            let progress = *self.download_progress.lock().unwrap();
+            ui.add(egui::ProgressBar::new(progress).show_percentage().text(t!("list-downloading")));
+        }
+        
+        // Create section
+        ui.separator();
+        ui.heading(t!("create-new-list"));
+        
+        if list_create_loading {
+            self.render_loading_spinner(ui, &t!("creating-list"));
+        } else {
+            ui.horizontal(|ui| {
+                ui.label(t!("list-title"));
+                ui.text_edit_singleline(&mut self.new_list_title);
+            });
+            ui.label(t!("select-files-to-add-to-the"));
+            let file_list = self.state.lock().unwrap().file_list.clone();
+            
+            egui::ScrollArea::vertical().max_height(100.0).id_salt("new_list_files_scroll").show(ui, |ui| {
+                for file in &file_list {
+                    let mut selected = self.new_list_files.iter().any(|f| f.id == file.id);
+                    if ui.checkbox(&mut selected, &file.name).clicked() {
+                        new_list_file_changes.push((file.id.clone(), selected));
+                    }
+                }
+            });
+            
+            if ui.button(t!("create-list")).clicked() {
+                create_list = true;
+            }
+        }
+        
+        // Lists section
+        ui.separator();
+        ui.heading(t!("your-lists"));
+        
+        if list_delete_loading {
+            self.render_loading_spinner(ui, &t!("deleting-list"));
+        }
+        
+        if lists.is_empty() && !lists_loading {
+            ui.label(t!("no-lists-found-click-refresh-lists"));
+        } else if !lists.is_empty() {
+            egui::ScrollArea::vertical().max_height(200.0).id_salt("user_lists_scroll").show(ui, |ui| {
+                for list in &lists {
+                    let selected = self.selected_list_id.as_ref() == Some(&list.id);
+                    if ui.selectable_label(selected, &list.title).clicked() {
+                        select_list_data = Some((list.id.clone(), list.title.clone()));
+                    }
+                    ui.label(t!("list-summary", files = list.file_count, created = list.date_created.format("%Y-%m-%d %H:%M:%S").to_string()));
+                    ui.horizontal(|ui| {
+                        if ui.add_enabled(!download_running, egui::Button::new(t!("download-as-zip"))).clicked() {
+                            zip_list = Some((list.id.clone(), list.title.clone()));
+                        }
+                        if list.can_edit && !list_delete_loading && ui.button(t!("delete-button")).clicked() {
+                            delete_list_id = Some(list.id.clone());
+                        }
+                        if ui.button(t!("qr-button")).on_hover_text(t!("qr-show")).clicked() {
+                            self.qr_link = Some(format!("{}/l/{}", self.state.lock().unwrap().base_url, list.id));
+                        }
+                    });
+                    ui.separator();
+                }
+            });
+        }
+        
+        // Edit section
+        self.apply_list_details();
+        let list_details_loading = *self.list_details_loading.lock().unwrap();
+        if let Some(selected_id) = self.selected_list_id.clone()
+            && let Some(list) = lists.iter().find(|l| l.id == selected_id)
+        {
+            ui.separator();
+            ui.heading(t!("list-edit-heading", title = list.title.as_str()));
+            
+            if list_update_loading {
+                self.render_loading_spinner(ui, &t!("updating-list"));
+            } else if list_details_loading {
+                self.render_loading_spinner(ui, &t!("list-loading-files"));
+            } else {
+                ui.horizontal(|ui| {
+                    ui.label(t!("list-title"));
+                    ui.text_edit_singleline(&mut self.edit_list_title);
+                });
+                self.render_list_files_editor(ui);
+                ui.label(t!("add-remove-files"));
+                
+                let file_list = self.state.lock().unwrap().file_list.clone();
+                egui::ScrollArea::vertical().max_height(100.0).id_salt("edit_list_files_scroll").show(ui, |ui| {
+                    for file in &file_list {
+                        let mut selected = self.edit_list_files.iter().any(|f| f.id == file.id);
+                        if ui.checkbox(&mut selected, &file.name).clicked() {
+                            edit_list_file_changes.push((file.id.clone(), selected));
+                        }
+                    }
+                });
+                
+                if ui.button(t!("save-changes")).clicked() {
+                    update_list_id = Some(selected_id.clone());
+                }
+            }
+            
+            ui.label(t!("list-file-count", count = list.file_count));
         }
         
         // Apply all collected actions
@@ -975,13 +3650,15 @@ impl PixelDrainApp {
         }
         
         if let Some(list_id) = delete_list_id {
-            self.delete_list(&list_id);
+            self.request_delete(undo::Deletion::List(list_id));
         }
         
-        if let Some((list_id, title, files)) = select_list_data {
-            self.selected_list_id = Some(list_id);
-            self.edit_list_title = title;
-            self.edit_list_files = files;
+        if let Some((list_id, title)) = zip_list {
+            self.pick_list_zip_destination(&list_id, &title);
+        }
+        
+        if let Some((list_id, title)) = select_list_data {
+            self.select_list(list_id, title);
         }
         
         // Apply file changes to new list
@@ -1015,13 +3692,104 @@ impl PixelDrainApp {
         if let Some(list_id) = update_list_id {
             self.update_list(&list_id);
         }
-        
-        // Handle removing files from existing lists
-        for (list_id, file_id) in remove_from_existing {
-            self.edit_list_files.retain(|f| f.id != file_id);
-            self.update_list(&list_id);
+    }
+
+    /// Files of the list being edited in their order, each with its description.
+    /// Dragging a file by its handle onto another row moves it there.
+    fn render_list_files_editor(&mut self, ui: &mut egui::Ui) {
+        let file_list = self.state.lock().unwrap().file_list.clone();
+        let mut remove = None;
+        let mut moved = None;
+        egui::ScrollArea::vertical().max_height(200.0).id_salt("edit_list_descriptions_scroll").show(ui, |ui| {
+            egui::Grid::new("edit_list_descriptions").num_columns(4).striped(true).show(ui, |ui| {
+                for (index, file) in self.edit_list_files.iter_mut().enumerate() {
+                    let handle = ui
+                        .dnd_drag_source(egui::Id::new(("edit_list_file", index)), index, |ui| ui.label("☰"))
+                        .response
+                        .on_hover_text(t!("list-drag-to-reorder"));
+                    // Files added from the account aren't in the fetched list yet
+                    let name = self.edit_list_names.get(&file.id)
+                        .or_else(|| file_list.iter().find(|f| f.id == file.id).map(|f| &f.name))
+                        .unwrap_or(&file.id);
+                    let label = ui.label(name);
+                    let row = handle.union(label);
+                    if let Some(from) = row.dnd_hover_payload::<usize>()
+                        && *from != index
+                    {
+                        // Above the row when moving up, below it when moving down
+                        let y = if *from > index { row.rect.top() } else { row.rect.bottom() };
+                        ui.painter().hline(row.rect.x_range(), y, ui.visuals().selection.stroke);
+                    }
+                    if let Some(from) = row.dnd_release_payload::<usize>() {
+                        moved = Some((*from, index));
+                    }
+                    ui.add(egui::TextEdit::singleline(&mut file.description)
+                        .hint_text(t!("list-file-description"))
+                        .desired_width(240.0));
+                    if ui.small_button("🗑").on_hover_text(t!("list-remove-file")).clicked() {
+                        remove = Some(index);
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+        if let Some(index) = remove {
+            self.edit_list_files.remove(index);
+        }
+        if let Some((from, to)) = moved {
+            Self::move_list_file(&mut self.edit_list_files, from, to);
+        }
+    }
+
+    /// Move the file at `from` to `to`, shifting the ones in between
+    fn move_list_file(files: &mut Vec<pixeldrain_api::ListFile>, from: usize, to: usize) {
+        if from < files.len() && to < files.len() {
+            let file = files.remove(from);
+            files.insert(to, file);
+        }
+    }
+
+    /// Take the fetched files of the selected list into the editor
+    fn apply_list_details(&mut self) {
+        let details = self.list_details.lock().unwrap().take();
+        match details {
+            Some(Ok(details)) if self.selected_list_id.as_ref() == Some(&details.id) => {
+                self.edit_list_title = details.title;
+                self.edit_list_files = details.files.iter().map(|file| pixeldrain_api::ListFile {
+                    id: file.file_info.id.clone(),
+                    description: file.description.clone(),
+                }).collect();
+                self.edit_list_names = details.files.into_iter().map(|file| (file.file_info.id, file.file_info.name)).collect();
+            }
+            Some(Err(e)) => *self.list_error.lock().unwrap() = Some(e),
+            // Another list was selected since
+            _ => {}
         }
     }
+
+    /// Select a list for editing and fetch its files with their descriptions
+    fn select_list(&mut self, list_id: String, title: String) {
+        self.selected_list_id = Some(list_id.clone());
+        self.edit_list_title = title;
+        self.edit_list_files.clear();
+        self.edit_list_names.clear();
+
+        let details = self.list_details.clone();
+        let loading = self.list_details_loading.clone();
+        let metadata_limit = self.metadata_limit.clone();
+        let client = self.make_api_client();
+        *loading.lock().unwrap() = true;
+        self.workers.spawn(move || {
+            let fetch = || {
+                let _permit = metadata_limit.acquire();
+                client.get_list(&list_id)
+            };
+            let result = Self::retry_pixeldrain_operation(fetch, 3, std::time::Duration::from_secs(3))
+                .map_err(|e| format!("Failed to load the files of the list: {}", e));
+            *details.lock().unwrap() = Some(result);
+            *loading.lock().unwrap() = false;
+        });
+    }
     
 
     fn refresh_lists(&mut self) {
@@ -1032,8 +3800,10 @@ impl PixelDrainApp {
         let lists = self.lists.clone();
         let list_error = self.list_error.clone();
         let api_key = self.get_api_key();
+        let metadata_limit = self.metadata_limit.clone();
         
-        thread::spawn(move || {
+        let api_factory = self.api_factory.clone();
+        self.workers.spawn(move || {
             // Use the retry utility
             let result = Self::retry_pixeldrain_operation(
                 || {
@@ -1043,7 +3813,8 @@ impl PixelDrainApp {
                     } else {
                         pixeldrain_api::PixelDrainConfig::default()
                     };
-                    let client = pixeldrain_api::PixelDrainClient::new(config)?;
+                    let client = api_factory(config)?;
+                    let _permit = metadata_limit.acquire();
                     client.get_user_lists()
                 },
                 3,
@@ -1091,19 +3862,7 @@ impl PixelDrainApp {
                 Err(e) => {
                     last_error = Some(e);
                     
-                    // Check if this is a retryable error
-                    let should_retry = match &last_error.as_ref().unwrap() {
-                        pixeldrain_api::PixelDrainError::Reqwest(reqwest_err) => {
-                            reqwest_err.is_timeout() || 
-                            reqwest_err.is_connect() || 
-                            reqwest_err.is_request() ||
-                            reqwest_err.to_string().contains("request or response body error")
-                        }
-                        pixeldrain_api::PixelDrainError::Api(api_err) => {
-                            api_err.status.is_server_error()
-                        }
-                        _ => false,
-                    };
+                    let should_retry = last_error.as_ref().unwrap().is_retryable();
                     
                     if should_retry && attempt < MAX_RETRIES {
                         std::thread::sleep(RETRY_DELAY);
@@ -1143,19 +3902,7 @@ impl PixelDrainApp {
                 Err(e) => {
                     last_error = Some(e);
                     
-                    // Check if this is a retryable error
-                    let should_retry = match &last_error.as_ref().unwrap() {
-                        pixeldrain_api::PixelDrainError::Reqwest(reqwest_err) => {
-                            reqwest_err.is_timeout() || 
-                            reqwest_err.is_connect() || 
-                            reqwest_err.is_request() ||
-                            reqwest_err.to_string().contains("request or response body error")
-                        }
-                        pixeldrain_api::PixelDrainError::Api(api_err) => {
-                            api_err.status.is_server_error()
-                        }
-                        _ => false,
-                    };
+                    let should_retry = last_error.as_ref().unwrap().is_retryable();
                     
                     if should_retry && attempt < MAX_RETRIES {
                         std::thread::sleep(RETRY_DELAY);
@@ -1200,19 +3947,7 @@ impl PixelDrainApp {
                 Err(e) => {
                     last_error = Some(e);
                     
-                    // Check if this is a retryable error
-                    let should_retry = match &last_error.as_ref().unwrap() {
-                        pixeldrain_api::PixelDrainError::Reqwest(reqwest_err) => {
-                            reqwest_err.is_timeout() || 
-                            reqwest_err.is_connect() || 
-                            reqwest_err.is_request() ||
-                            reqwest_err.to_string().contains("request or response body error")
-                        }
-                        pixeldrain_api::PixelDrainError::Api(api_err) => {
-                            api_err.status.is_server_error()
-                        }
-                        _ => false,
-                    };
+                    let should_retry = last_error.as_ref().unwrap().is_retryable();
                     
                     if should_retry && attempt < MAX_RETRIES {
                         std::thread::sleep(RETRY_DELAY);
@@ -1229,1109 +3964,7199 @@ impl PixelDrainApp {
             MAX_RETRIES, last_error.unwrap()));
         *self.list_update_loading.lock().unwrap() = false;
     }
-    fn make_api_client(&self) -> pixeldrain_api::PixelDrainClient {
-        let config = if let Some(key) = self.get_api_key() {
-            pixeldrain_api::PixelDrainConfig::default().with_api_key(key)
-        } else {
-            pixeldrain_api::PixelDrainConfig::default()
-        };
+    fn filesystem_tab(&mut self, ui: &mut egui::Ui) {
+        if !self.has_api_key() {
+            ui.label(t!("an-api-key-is-required-to"));
+            return;
+        }
         
-
+        let mut navigate_to: Option<String> = None;
+        let mut refresh = false;
+        let mut upload = false;
+        let mut create_folder = false;
+        let mut download: Option<(String, String)> = None; // (path, name)
+        let mut rename: Option<(String, String)> = None; // (path, target)
+        let mut delete_path: Option<String> = None;
+        let mut share_path: Option<String> = None;
         
-        pixeldrain_api::PixelDrainClient::new(config).unwrap()
-    }
-
-    fn settings_tab(&mut self, ui: &mut egui::Ui) {
-        // Initialize settings fields if they're empty
-        if self.settings_api_key.is_empty() || self.settings_download_location.is_empty() {
-            let state = self.state.lock().unwrap();
-            if self.settings_api_key.is_empty() {
-                self.settings_api_key = state.api_key.clone();
-            }
-            if self.settings_download_location.is_empty() {
-                self.settings_download_location = state.download_location.clone();
-            }
-        }
-
-        // Get current state for display
-        let (user_info, last_error) = {
-            let state = self.state.lock().unwrap();
-            (state.user_info.clone(), state.last_error.clone())
-        };
-
-        let mut settings_saved = false;
-
-        // Show error if any
-        if let Some(error_msg) = &last_error {
-            ui.colored_label(egui::Color32::RED, format!("❌ Error: {}", error_msg));
-            ui.separator();
+        let loading = *self.fs_loading.lock().unwrap();
+        let listing = self.fs_listing.lock().unwrap().clone();
+        let fs_error = self.fs_error.lock().unwrap().clone();
+        let transfer_progress = *self.fs_transfer_progress.lock().unwrap();
+        
+        // Load the current directory the first time the tab is shown
+        if listing.is_none() && !loading && fs_error.is_none() {
+            refresh = true;
         }
-
-        ui.label("PixelDrain API Key:");
+        
+        ui.heading(t!("filesystem"));
         ui.horizontal(|ui| {
-            ui.text_edit_singleline(&mut self.settings_api_key);
-            if ui.button("📋 Paste").clicked() {
-                if let Ok(mut clipboard) = Clipboard::new() {
-                    if let Ok(text) = clipboard.get_text() {
-                        self.settings_api_key = text;
-                    }
-                }
+            let at_root = self.fs_path.trim_end_matches('/') == FILESYSTEM_ROOT;
+            if ui.add_enabled(!at_root, egui::Button::new(t!("up"))).clicked()
+                && let Some((parent, _)) = self.fs_path.trim_end_matches('/').rsplit_once('/')
+            {
+                navigate_to = Some(parent.to_string());
+            }
+            ui.label("📂");
+            let response = ui.add(egui::TextEdit::singleline(&mut self.fs_path).desired_width(250.0));
+            if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                refresh = true;
+            }
+            if loading {
+                self.render_loading_spinner(ui, &t!("loading"));
+            } else if ui.button(t!("refresh")).clicked() {
+                refresh = true;
             }
         });
-        ui.label("Get your API key from https://pixeldrain.com/user/settings");
         
-        // Show if API key is set from environment
-        if let Ok(env_key) = env::var("PIXELDRAIN_API_KEY") {
-            if !env_key.is_empty() {
-                ui.horizontal(|ui| {
-                    ui.label(format!("🔑 API Key from environment: {}...", &env_key[..8.min(env_key.len())]));
-                    if ui.button("📋 Copy").clicked() {
-                        let _ = Clipboard::new().and_then(|mut c| c.set_text(env_key.clone()));
-                    }
-                });
-                if self.settings_api_key.is_empty() {
-                    ui.colored_label(egui::Color32::from_rgb(255, 140, 0), "💡 Environment API key will be used as fallback");
-                } else {
-                    ui.colored_label(egui::Color32::GREEN, "✅ Settings API key will be used (overrides environment)");
-                }
+        ui.horizontal(|ui| {
+            if ui.add_enabled(transfer_progress.is_none(), egui::Button::new(t!("upload-files-here"))).clicked() {
+                upload = true;
+            }
+            ui.separator();
+            ui.add(egui::TextEdit::singleline(&mut self.fs_new_folder).hint_text(t!("new-folder-name")).desired_width(150.0));
+            if ui.add_enabled(!self.fs_new_folder.trim().is_empty(), egui::Button::new(t!("create-folder"))).clicked() {
+                create_folder = true;
             }
+        });
+        
+        if let Some(progress) = transfer_progress {
+            ui.add(egui::ProgressBar::new(progress).show_percentage());
+        }
+        
+        if let Some(err) = &fs_error {
+            ui.colored_label(egui::Color32::RED, format!("❌ {}", err));
         }
         
         ui.separator();
-
-        ui.label("Default Download Location:");
-        ui.horizontal(|ui| {
-            if ui.button("📁 Choose Folder").clicked() {
-                if let Some(folder) = FileDialog::new().pick_folder() {
-                    self.settings_download_location = folder.display().to_string();
-                }
+        
+        if let Some(listing) = &listing {
+            if listing.children.is_empty() {
+                ui.label(t!("this-folder-is-empty"));
             }
-            ui.text_edit_singleline(&mut self.settings_download_location);
-        });
-
-        ui.separator();
-
-        // User info section with refresh button
-        let user_info_loading = *self.user_info_loading.lock().unwrap();
-        ui.horizontal(|ui| {
-            ui.label("Account Information");
-            if user_info_loading {
-                self.render_loading_spinner(ui, "Loading user info...");
-            } else {
-                if ui.button("🔄 Refresh").clicked() {
-                    self.fetch_user_info();
+            egui::ScrollArea::vertical().id_salt("filesystem_scroll").show(ui, |ui| {
+                for node in &listing.children {
+                    let is_dir = node.r#type == "dir";
+                    ui.horizontal(|ui| {
+                        if is_dir {
+                            if ui.link(format!("📁 {}", node.name)).clicked() {
+                                navigate_to = Some(node.path.clone());
+                            }
+                        } else {
+                            ui.label(format!("📄 {}", node.name));
+                            ui.label(format!("({})", self.format_file_size_bytes(node.file_size.max(0) as u64)));
+                        }
+                        
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if ui.button("🗑").on_hover_text(t!("delete")).clicked() {
+                                delete_path = Some(node.path.clone());
+                            }
+                            if ui.button("✏").on_hover_text(t!("rename")).clicked() {
+                                self.fs_rename = Some((node.path.clone(), node.name.clone()));
+                            }
+                            if ui.button("🔒").on_hover_text(t!("share")).clicked() {
+                                share_path = Some(node.path.clone());
+                            }
+                            if !is_dir && ui.add_enabled(transfer_progress.is_none(), egui::Button::new("⬇")).on_hover_text(t!("download")).clicked() {
+                                download = Some((node.path.clone(), node.name.clone()));
+                            }
+                        });
+                    });
+                    
+                    // Inline rename editor for the selected node
+                    if let Some((rename_path, new_name)) = &mut self.fs_rename
+                        && *rename_path == node.path
+                    {
+                        ui.horizontal(|ui| {
+                            ui.label(t!("new-name"));
+                            ui.text_edit_singleline(new_name);
+                            if ui.button(t!("rename-confirm")).clicked() && !new_name.trim().is_empty() {
+                                let parent = node.path.rsplit_once('/').map(|(parent, _)| parent).unwrap_or("");
+                                rename = Some((node.path.clone(), format!("{}/{}", parent, new_name.trim())));
+                            }
+                            if ui.button(t!("cancel")).clicked() {
+                                rename = Some((String::new(), String::new()));
+                            }
+                        });
+                    }
+                    ui.separator();
                 }
-            }
-        });
-
-        // User info if available
-        if let Some(user_info) = &user_info {
-            let storage_space_str = if user_info.subscription.storage_space < 0 {
-                "Unlimited".to_string()
-            } else {
-                self.format_file_size_bytes(user_info.subscription.storage_space as u64)
-            };
-            ui.label(format!("👤 Username: {}", user_info.username));
-            ui.label(format!("📧 Email: {}", user_info.email));
-            ui.label(format!("📁 Files: {}", user_info.file_count));
-            ui.label(format!("💾 Storage: {} / {}", 
-                self.format_file_size_bytes(user_info.storage_space_used),
-                storage_space_str
-            ));
-            ui.label(format!("📊 Monthly Transfer: {} / {}", 
-                self.format_file_size_bytes(user_info.monthly_transfer_used),
-                self.format_file_size_bytes(user_info.monthly_transfer_cap)
-            ));
-            ui.label(format!("⏰ Files Expiry Days: {}", user_info.subscription.file_expiry_days));
-            ui.label(format!("💳 Balance: {} micro EUR", user_info.balance_micro_eur));
-        } else {
-            ui.colored_label(egui::Color32::GRAY, "No account information available. Set API key in settings or PIXELDRAIN_API_KEY environment variable, then click Refresh.");
+            });
         }
-
-        ui.separator();
-
-        if ui.button("💾 Save Settings").clicked() {
-            self.save_settings(self.settings_api_key.clone(), self.settings_download_location.clone());
-            settings_saved = true;
-            // Try to fetch user info after saving settings
-            self.fetch_user_info();
+        
+        // Apply all collected actions
+        if let Some(path) = navigate_to {
+            self.fs_path = path;
+            refresh = true;
         }
         
-        // Show success message only after actually saving
-        if settings_saved {
-            ui.colored_label(egui::Color32::GREEN, "✅ Settings saved successfully!");
+        if refresh {
+            self.refresh_filesystem();
         }
-    }
-
-    fn about_tab(&mut self, ui: &mut egui::Ui) {
-        egui::ScrollArea::vertical().show(ui, |ui| {
-            // Display the app icon at 48x48 size, switching based on theme
-            let dark_mode = self.state.lock().unwrap().dark_mode;
-            if let Some(icon_data) = icon_data_from_png(dark_mode) {
-                let texture_id = ui.ctx().load_texture(
-                    "app_icon",
-                    egui::ColorImage::from_rgba_unmultiplied(
-                        [icon_data.width as usize, icon_data.height as usize],
-                        &icon_data.rgba,
-                    ),
-                    Default::default(),
-                );
-                ui.add(egui::Image::new((texture_id.id(), egui::Vec2::new(48.0, 48.0))));
-            }
-            
-            ui.label("PixelDrain Client");
-            ui.label("Copyright (c) 2025 Genxster1998");
-            ui.label("A modern unofficial desktop client for PixelDrain file sharing service.");
-            ui.label("Built with Rust and egui.");
-            ui.label("Version: 0.1.2");
-            if ui.link("🐙 GitHub: https://www.github.com/Genxster1998/Pixeldrain-Rust").clicked() {
-                let _ = webbrowser::open("https://www.github.com/Genxster1998/Pixeldrain-Rust");
-            }
-            
-            ui.separator();
-            
-            ui.label("Features:");
-            ui.label("• 📤 Upload files and folders with progress tracking (API key required)");
-            ui.label("• 📥 Download files from PixelDrain URLs (no API key required)");
-            ui.label("• 📋 Copy shareable links to clipboard");
-            ui.label("• 📁 Manage your uploaded files");
-            ui.label("• ⚙ Configure API key and settings");
-            ui.label("• 🔑 Environment variable support (PIXELDRAIN_API_KEY)");
-            
-            ui.separator();
-            
-            if ui.link("PixelDrain: https://pixeldrain.com").clicked() {
-                let _ = webbrowser::open("https://pixeldrain.com");
-            }
-            if ui.link("API Documentation: https://pixeldrain.com/api").clicked() {
-                let _ = webbrowser::open("https://pixeldrain.com/api");
-            }
-            if ui.link("Official Go Implementation: https://github.com/Fornaxian/pixeldrain_api_client").clicked() {
-                let _ = webbrowser::open("https://github.com/Fornaxian/pixeldrain_api_client");
+        
+        if upload && let Some(paths) = FileDialog::new().pick_files() {
+            let directory = self.fs_path.trim_end_matches('/').to_string();
+            let progress = self.fs_transfer_progress.clone();
+            let events = self.events.clone();
+            self.run_filesystem_operation(move |client| {
+                let total = paths.len();
+                for (index, path) in paths.iter().enumerate() {
+                    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                    let progress_cb = {
+                        let progress = progress.clone();
+                        events.progress_sender(move |p: Progress| {
+                            *progress.lock().unwrap() = Some((index as f32 + p.fraction) / total as f32);
+                        })
+                    };
+                    client.upload_filesystem_file(path, &format!("{}/{}", directory, name), Some(progress_cb))?;
+                }
+                Ok(())
+            });
+        }
+        
+        if create_folder {
+            let path = format!("{}/{}", self.fs_path.trim_end_matches('/'), self.fs_new_folder.trim());
+            self.fs_new_folder.clear();
+            self.run_filesystem_operation(move |client| client.create_filesystem_directory(&path));
+        }
+        
+        if let Some((path, name)) = download {
+            let download_location = self.state.lock().unwrap().download_location.clone();
+            let mut dialog = FileDialog::new().set_file_name(&name);
+            if !download_location.is_empty() {
+                dialog = dialog.set_directory(&download_location);
             }
-            if ui.link("Based on go-pd: https://github.com/ManuelReschke/go-pd").clicked() {
-                let _ = webbrowser::open("https://github.com/ManuelReschke/go-pd");
+            if let Some(dest) = dialog.save_file() {
+                let progress = self.fs_transfer_progress.clone();
+                let events = self.events.clone();
+                self.run_filesystem_operation(move |client| {
+                    let progress_cb = events.progress_sender(move |p: Progress| {
+                        *progress.lock().unwrap() = Some(p.fraction);
+                    });
+                    client.download_filesystem_file(&path, &dest, Some(progress_cb))
+                });
             }
-            if ui.link("Based on go-pd: https://github.com/jkawamoto/go-pixeldrain").clicked() {
-                let _ = webbrowser::open("https://github.com/jkawamoto/go-pixeldrain");
+        }
+        
+        if let Some((path, target)) = rename {
+            self.fs_rename = None;
+            if !path.is_empty() {
+                self.run_filesystem_operation(move |client| client.rename_filesystem_node(&path, &target));
             }
-        });
+        }
+        
+        if let Some(path) = delete_path {
+            self.run_filesystem_operation(move |client| client.delete_filesystem_node(&path));
+        }
+        
+        if let Some(path) = share_path {
+            self.share_path = path;
+            *self.share_result.lock().unwrap() = None;
+            self.share_dialog_open = true;
+        }
     }
 
-
-
-    fn render_error_popup(&mut self, ctx: &egui::Context) {
-        let mut show_error = self.show_error;
-        egui::Window::new("Error")
-            .open(&mut show_error)
-            .resizable(false)
-            .collapsible(false)
-            .show(ctx, |ui| {
-                ui.label("❌ An error occurred:");
-                ui.label(&self.error_message);
-                ui.add_space(10.0);
-                if ui.button("OK").clicked() {
-                    self.show_error = false;
-                }
-            });
-        self.show_error = show_error;
+    fn refresh_filesystem(&mut self) {
+        self.run_filesystem_operation(|_| Ok(()));
     }
 
-    fn start_upload(&mut self, path: PathBuf, ctx: egui::Context) {
-        // Get API key with settings priority
-        let api_key = self.get_api_key();
-        if api_key.is_none() {
-            let mut state = self.state.lock().unwrap();
-            state.last_error = Some("API key required for upload. Please set your API key in settings.".to_string());
+    /// Run a filesystem operation in the background, then reload the current directory
+    fn run_filesystem_operation<F>(&mut self, operation: F)
+    where
+        F: FnOnce(&dyn PixelDrainApi) -> Result<(), pixeldrain_api::PixelDrainError> + Send + 'static,
+    {
+        let Some(api_key) = self.get_api_key() else {
+            *self.fs_error.lock().unwrap() = Some("API key required".to_string());
             return;
-        }
+        };
+        let path = self.fs_path.trim_end_matches('/').to_string();
+        let loading = self.fs_loading.clone();
+        let listing = self.fs_listing.clone();
+        let fs_error = self.fs_error.clone();
+        let transfer_progress = self.fs_transfer_progress.clone();
+        let events = self.events.clone();
+        let metadata_limit = self.metadata_limit.clone();
         
-        let progress = self.upload_progress.clone();
-        let state = self.state.clone();
-        let thread_running = self.upload_thread_running.clone();
-        let ctx = ctx.clone();
-        let last_update = Arc::new(AtomicU64::new(0));
-        let custom_filename = self.upload_custom_filename.clone();
-        // Reset progress at start
-        *self.upload_progress.lock().unwrap() = 0.0;
-        *thread_running.lock().unwrap() = true;
-        thread::spawn(move || {
-            println!("[DEBUG] start_upload - api_key present: {}", api_key.is_some());
-            let config = if let Some(key) = api_key {
-                println!("[DEBUG] Creating config with API key");
-                PixelDrainConfig::default().with_api_key(key)
-            } else {
-                println!("[DEBUG] Creating config without API key (anonymous)");
-                PixelDrainConfig::default()
-            };
-            let client = match PixelDrainClient::new(config) {
+        *loading.lock().unwrap() = true;
+        *fs_error.lock().unwrap() = None;
+        
+        let api_factory = self.api_factory.clone();
+        self.workers.spawn(move || {
+            let config = PixelDrainConfig::default().with_api_key(api_key);
+            let client = match api_factory(config) {
                 Ok(client) => client,
                 Err(e) => {
-                    let mut state = state.lock().unwrap();
-                    state.last_error = Some(format!("Failed to create client: {}", e));
-                    *thread_running.lock().unwrap() = false;
+                    *fs_error.lock().unwrap() = Some(format!("Failed to create client: {}", e));
+                    *loading.lock().unwrap() = false;
                     return;
                 }
             };
-            let progress_cb = {
-                let progress = progress.clone();
-                let ctx = ctx.clone();
-                let last_update = last_update.clone();
-                Arc::new(Mutex::new(move |p: f32| {
-                    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
-                    let last = last_update.load(Ordering::Relaxed);
-                    if now - last >= 16 || p >= 1.0 {
-                        last_update.store(now, Ordering::Relaxed);
-                        let mut progress = progress.lock().unwrap();
-                        *progress = p;
-                        ctx.request_repaint();
-                    }
-                }))
-            };
-            let result = if !custom_filename.is_empty() {
-                client.upload_file_put(&path, &custom_filename, Some(progress_cb))
-            } else {
-                client.upload_file(&path, Some(progress_cb))
+            
+            if let Err(e) = operation(client.as_ref()) {
+                *fs_error.lock().unwrap() = Some(e.to_string());
+            }
+            // Progress still waiting for the next frame would bring the bar back
+            events.drain_progress();
+            *transfer_progress.lock().unwrap() = None;
+            
+            let fetch = || {
+                let _permit = metadata_limit.acquire();
+                client.get_filesystem_path(&path)
             };
-            let mut state = state.lock().unwrap();
-            match result {
-                Ok(response) => {
-                    let url = response.get_file_url();
-                    let entry = UploadHistoryEntry {
-                        id: response.id,
-                        url: url.clone(),
-                        filename: if !custom_filename.is_empty() {
-                            custom_filename.clone()
-                        } else {
-                            path.file_name().unwrap().to_string_lossy().to_string()
-                        },
-                        size: path.metadata().map(|m| m.len()).unwrap_or(0),
-                        timestamp: Utc::now(),
-                    };
-                    state.upload_history.push(entry);
-                    // Copy to clipboard
-                    let _ = Clipboard::new().and_then(|mut c| c.set_text(url));
-                    state.last_error = None;
-                }
+            match Self::retry_pixeldrain_operation(fetch, 3, std::time::Duration::from_secs(3)) {
+                Ok(result) => *listing.lock().unwrap() = Some(result),
                 Err(e) => {
-                    state.last_error = Some(format!("Upload error: {}", e));
+                    *listing.lock().unwrap() = None;
+                    fs_error.lock().unwrap().get_or_insert(format!("Failed to load {}: {}", path, e));
                 }
             }
-            *thread_running.lock().unwrap() = false;
+            *loading.lock().unwrap() = false;
         });
     }
 
-    fn start_multiple_upload(&mut self, paths: Vec<PathBuf>, ctx: egui::Context) {
-        let api_key = self.get_api_key();
-        if api_key.is_none() {
-            let mut state = self.state.lock().unwrap();
-            state.last_error = Some("API key required for upload. Please set your API key in settings.".to_string());
+    fn billing_tab(&mut self, ui: &mut egui::Ui) {
+        if !self.has_api_key() {
+            ui.label(t!("an-api-key-is-required-to"));
             return;
         }
         
-        let progress = self.upload_progress.clone();
-        let state = self.state.clone();
-        let thread_running = self.upload_thread_running.clone();
-        let ctx = ctx.clone();
-        let last_update = Arc::new(AtomicU64::new(0));
+        let loading = *self.transactions_loading.lock().unwrap();
+        let transactions = self.transactions.lock().unwrap().clone();
+        let error = self.transactions_error.lock().unwrap().clone();
+        if transactions.is_none() && !loading && error.is_none() {
+            self.refresh_transactions();
+        }
         
-        // Reset progress at start
-        *self.upload_progress.lock().unwrap() = 0.0;
-        *thread_running.lock().unwrap() = true;
+        ui.horizontal(|ui| {
+            ui.heading(t!("billing-heading"));
+            if loading {
+                ui.spinner();
+            } else if ui.button(t!("refresh")).clicked() {
+                self.refresh_transactions();
+            }
+        });
+        if let Some(err) = &error {
+            ui.colored_label(egui::Color32::RED, format!("❌ {}", err));
+        }
+        let Some(mut transactions) = transactions else {
+            return;
+        };
+        if transactions.is_empty() {
+            ui.label(t!("billing-no-transactions"));
+            return;
+        }
+        transactions.sort_by_key(|transaction| transaction.time);
+        ui.separator();
         
-        thread::spawn(move || {
-            let config = if let Some(key) = api_key {
-                PixelDrainConfig::default().with_api_key(key)
-            } else {
-                PixelDrainConfig::default()
-            };
+        egui::ScrollArea::vertical().id_salt("billing_scroll").show(ui, |ui| {
+            ui.strong(t!("billing-balance-chart"));
+            Self::render_balance_chart(ui, &transactions);
+            ui.add_space(8.0);
             
-            let client = match PixelDrainClient::new(config) {
-                Ok(client) => client,
-                Err(e) => {
-                    let mut state = state.lock().unwrap();
-                    state.last_error = Some(format!("Failed to create client: {}", e));
-                    *thread_running.lock().unwrap() = false;
-                    return;
+            ui.strong(t!("billing-per-month"));
+            egui::Grid::new("billing_months").striped(true).num_columns(7).show(ui, |ui| {
+                for header in [t!("billing-month"), t!("billing-subscription"), t!("billing-storage"), t!("billing-bandwidth"), t!("billing-total"), t!("billing-deposits"), t!("billing-balance")] {
+                    ui.strong(header);
                 }
-            };
-            
-            let total_files = paths.len();
-            let mut uploaded_count = 0;
-            
-            for (index, path) in paths.iter().enumerate() {
-                let progress_cb = {
-                    let progress = progress.clone();
-                    let ctx = ctx.clone();
-                    let last_update = last_update.clone();
-                    let file_index = index;
-                    let total = total_files;
-                    Arc::new(Mutex::new(move |p: f32| {
-                        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
-                        let last = last_update.load(Ordering::Relaxed);
-                        if now - last >= 16 || p >= 1.0 {
-                            last_update.store(now, Ordering::Relaxed);
-                            let mut progress = progress.lock().unwrap();
-                            // Calculate overall progress across all files
-                            let file_progress = (file_index as f32 + p) / total as f32;
-                            *progress = file_progress;
-                            ctx.request_repaint();
-                        }
-                    }))
-                };
-                
-                let result = client.upload_file(path, Some(progress_cb));
-                let mut state = state.lock().unwrap();
-                
-                match result {
-                    Ok(response) => {
-                        let url = response.get_file_url();
-                        let entry = UploadHistoryEntry {
-                            id: response.id,
-                            url: url.clone(),
-                            filename: path.file_name().unwrap().to_string_lossy().to_string(),
-                            size: path.metadata().map(|m| m.len()).unwrap_or(0),
-                            timestamp: Utc::now(),
-                        };
-                        state.upload_history.push(entry);
-                        uploaded_count += 1;
-                        
-
-                    }
-                    Err(e) => {
-                        state.last_error = Some(format!("Upload error for {}: {}", path.file_name().unwrap().to_string_lossy(), e));
-
-                        break;
-                    }
+                ui.end_row();
+                for month in billing::monthly(&transactions).iter().rev() {
+                    ui.label(month.label());
+                    ui.label(billing::format_eur(month.subscription));
+                    ui.label(billing::format_eur(month.storage));
+                    ui.label(billing::format_eur(month.bandwidth));
+                    ui.strong(billing::format_eur(month.charges()));
+                    ui.label(billing::format_eur(month.deposits + month.affiliate));
+                    ui.label(billing::format_eur(month.balance));
+                    ui.end_row();
                 }
-            }
+            });
+            ui.add_space(8.0);
             
-            // Copy the last uploaded file URL to clipboard
-            if uploaded_count > 0 {
-                let state = state.lock().unwrap();
-                if let Some(last_entry) = state.upload_history.last() {
-                    let _ = Clipboard::new().and_then(|mut c| c.set_text(last_entry.url.clone()));
+            ui.strong(t!("billing-transactions"));
+            egui::Grid::new("billing_transactions").striped(true).num_columns(6).show(ui, |ui| {
+                for header in [t!("billing-date"), t!("billing-change"), t!("billing-storage"), t!("billing-bandwidth"), t!("billing-used"), t!("billing-balance")] {
+                    ui.strong(header);
                 }
-            }
-            
-            *thread_running.lock().unwrap() = false;
+                ui.end_row();
+                for transaction in transactions.iter().rev() {
+                    let change = transaction.deposit_amount + transaction.affiliate_amount
+                        - transaction.subscription_charge - transaction.storage_charge - transaction.bandwidth_charge;
+                    ui.label(transaction.time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string());
+                    let color = if change < 0 { egui::Color32::from_rgb(200, 60, 60) } else { egui::Color32::from_rgb(0, 150, 70) };
+                    ui.colored_label(color, billing::format_eur(change));
+                    ui.label(billing::format_eur(transaction.storage_charge));
+                    ui.label(billing::format_eur(transaction.bandwidth_charge));
+                    // Storage and bandwidth used are reported in bytes
+                    ui.label(format!(
+                        "{} / {}",
+                        self.format_file_size_bytes(transaction.storage_used.max(0) as u64),
+                        self.format_file_size_bytes(transaction.bandwidth_used.max(0) as u64)
+                    ));
+                    ui.label(billing::format_eur(transaction.new_balance));
+                    ui.end_row();
+                }
+            });
         });
     }
-
-    fn start_directory_upload(&mut self, dir_path: PathBuf, _ctx: egui::Context) {
-        let api_key = self.get_api_key();
-        if api_key.is_none() {
-            let mut state = self.state.lock().unwrap();
-            state.last_error = Some("API key required for upload. Please set your API key in settings.".to_string());
-            return;
-        }
+    
+    /// Line chart of the balance after each transaction, oldest on the left
+    fn render_balance_chart(ui: &mut egui::Ui, transactions: &[UserTransaction]) {
+        let (rect, response) = ui.allocate_exact_size(egui::vec2(ui.available_width().min(600.0), 120.0), egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        let visuals = ui.visuals();
+        painter.rect_filled(rect, 4.0, visuals.extreme_bg_color);
         
-        let progress = self.upload_progress.clone();
-        let state = self.state.clone();
-        let thread_running = self.upload_thread_running.clone();
-        let directory_name = self.upload_directory_name.clone();
+        let balances: Vec<i64> = transactions.iter().map(|transaction| transaction.new_balance).collect();
+        let (Some(&min), Some(&max)) = (balances.iter().min(), balances.iter().max()) else {
+            return;
+        };
+        // Zero stays in view, so the line shows how far the balance is from running out
+        let (min, max) = (min.min(0), max.max(0));
+        let range = (max - min).max(1) as f32;
+        let plot = rect.shrink(8.0);
+        let point = |index: usize, balance: i64| {
+            let x = if balances.len() > 1 { index as f32 / (balances.len() - 1) as f32 } else { 0.5 };
+            egui::pos2(plot.left() + x * plot.width(), plot.bottom() - (balance - min) as f32 / range * plot.height())
+        };
+        let zero = point(0, 0).y;
+        painter.hline(plot.x_range(), zero, egui::Stroke::new(1.0, visuals.weak_text_color()));
+        let points: Vec<egui::Pos2> = balances.iter().enumerate().map(|(index, balance)| point(index, *balance)).collect();
+        painter.add(egui::Shape::line(points, egui::Stroke::new(2.0, egui::Color32::from_rgb(0, 120, 215))));
         
-        // Reset progress at start
-        *self.upload_progress.lock().unwrap() = 0.0;
-        *thread_running.lock().unwrap() = true;
+        let text_color = visuals.text_color();
+        let font = egui::FontId::proportional(11.0);
+        painter.text(plot.left_top(), egui::Align2::LEFT_TOP, billing::format_eur(max), font.clone(), text_color);
+        painter.text(plot.left_bottom(), egui::Align2::LEFT_BOTTOM, billing::format_eur(min), font, text_color);
         
-        // Get API key with settings priority
-        let api_key = self.get_api_key();
+        // Balance at the transaction under the pointer
+        if let Some(hover) = response.hover_pos() {
+            let index = (((hover.x - plot.left()) / plot.width().max(1.0)) * (balances.len() - 1) as f32).round();
+            if let Some(transaction) = transactions.get(index.max(0.0) as usize) {
+                response.on_hover_text(format!(
+                    "{}: {}",
+                    transaction.time.with_timezone(&chrono::Local).format("%Y-%m-%d"),
+                    billing::format_eur(transaction.new_balance)
+                ));
+            }
+        }
+    }
+    
+    fn refresh_transactions(&mut self) {
+        let Some(api_key) = self.get_api_key() else {
+            return;
+        };
+        let transactions = self.transactions.clone();
+        let loading = self.transactions_loading.clone();
+        let error = self.transactions_error.clone();
+        *loading.lock().unwrap() = true;
+        *error.lock().unwrap() = None;
         
-        thread::spawn(move || {
-            let config = if let Some(key) = api_key {
-                PixelDrainConfig::default().with_api_key(key)
-            } else {
-                PixelDrainConfig::default()
-            };
-            
-            let client = match PixelDrainClient::new(config) {
-                Ok(client) => client,
-                Err(e) => {
-                    let mut state = state.lock().unwrap();
-                    state.last_error = Some(format!("Failed to create client: {}", e));
-                    *thread_running.lock().unwrap() = false;
-                    return;
-                }
-            };
-            
-            // Determine the archive filename
-            let archive_name = if !directory_name.is_empty() {
-                directory_name
-            } else {
-                let dir_name = dir_path.file_name().unwrap_or_default().to_string_lossy();
-                format!("{}.tar.gz", dir_name)
-            };
-            
-            // Create tar command that compresses to stdout
-            let mut tar_cmd = Command::new("tar");
-            tar_cmd
-                .arg("czf")
-                .arg("-")  // Output to stdout
-                .arg("-C")
-                .arg(dir_path.parent().unwrap_or(&dir_path))
-                .arg(dir_path.file_name().unwrap());
-            
-            // Set up the command with stdout piped
-            tar_cmd.stdout(Stdio::piped());
-            tar_cmd.stderr(Stdio::piped());
-            
-            // Start the tar process
-            let mut tar_process = match tar_cmd.spawn() {
-                Ok(process) => process,
-                Err(e) => {
-                    let mut state = state.lock().unwrap();
-                    state.last_error = Some(format!("Failed to start tar process: {}", e));
-                    *thread_running.lock().unwrap() = false;
-                    return;
-                }
-            };
-            
-            // Get stdout from tar process
-            let tar_stdout = match tar_process.stdout.take() {
-                Some(stdout) => stdout,
-                None => {
-                    let mut state = state.lock().unwrap();
-                    state.last_error = Some("Failed to get tar stdout".to_string());
-                    *thread_running.lock().unwrap() = false;
-                    return;
-                }
-            };
-            
-            // Create a progress callback that simulates progress
-            let progress_cb = Arc::new(Mutex::new(move |p: f32| {
-                let mut progress = progress.lock().unwrap();
-                *progress = p;
-            }));
-            
-            // Upload the compressed data directly from tar stdout (streaming)
-            eprintln!("[DEBUG] Starting streaming upload of tar.gz to {}", archive_name);
-            let result = client.upload_stream_put(tar_stdout, &archive_name, Some(progress_cb));
-            
-            // Wait for tar process to finish
-            let tar_result = tar_process.wait();
-
-            // Print tar stderr if upload fails
-            if let Some(mut tar_stderr) = tar_process.stderr {
-                let mut stderr_output = String::new();
-                use std::io::Read;
-                let _ = tar_stderr.read_to_string(&mut stderr_output);
-                if !stderr_output.trim().is_empty() {
-                    eprintln!("[DEBUG] tar stderr: {}", stderr_output);
-                }
-            }
-            
-            let mut state = state.lock().unwrap();
+        let api_factory = self.api_factory.clone();
+        self.workers.spawn(move || {
+            let result = api_factory(PixelDrainConfig::default().with_api_key(api_key))
+                .map_err(|e| format!("Failed to create client: {}", e))
+                .and_then(|client| {
+                    Self::retry_pixeldrain_operation(|| client.get_user_transactions(), 3, Duration::from_secs(3))
+                        .map_err(|e| e.to_string())
+                });
             match result {
-                Ok(response) => {
-                    let url = response.get_file_url();
-                    let entry = UploadHistoryEntry {
-                        id: response.id,
-                        url: url.clone(),
-                        filename: archive_name.clone(),
-                        size: 0, // We don't know the exact size since it's streamed
-                        timestamp: Utc::now(),
-                    };
-                    state.upload_history.push(entry);
-                    state.last_error = None;
-                    
-                    // Copy URL to clipboard
-                    let _ = Clipboard::new().and_then(|mut c| c.set_text(url));
-                    
-
-                }
-                Err(e) => {
-                    eprintln!("[DEBUG] Directory upload error: {}", e);
-                    state.last_error = Some(format!("Directory upload error: {}", e));
-                }
-            }
-            
-            // Check if tar process had any errors
-            if let Err(e) = tar_result {
-                eprintln!("[DEBUG] Tar process error: {}", e);
+                Ok(result) => *transactions.lock().unwrap() = Some(result),
+                Err(e) => *error.lock().unwrap() = Some(e),
             }
-            
-            *thread_running.lock().unwrap() = false;
+            *loading.lock().unwrap() = false;
         });
     }
 
-    fn start_download(&mut self) {
-        let url = self.download_url.clone();
-        let progress = self.download_progress.clone();
-        let state = self.state.clone();
-        let thread_running = self.download_thread_running.clone();
+    fn make_api_client(&self) -> Arc<dyn PixelDrainApi> {
+        let config = if let Some(key) = self.get_api_key() {
+            pixeldrain_api::PixelDrainConfig::default().with_api_key(key)
+        } else {
+            pixeldrain_api::PixelDrainConfig::default()
+        };
         
-        // Get download location from settings
-        let download_location = {
+
+        
+        (self.api_factory)(config).unwrap()
+    }
+
+    fn settings_tab(&mut self, ui: &mut egui::Ui) {
+        // Initialize settings fields if they're empty
+        if self.settings_api_key.is_empty() || self.settings_download_location.is_empty() {
+            let state = self.state.lock().unwrap();
+            if self.settings_api_key.is_empty() {
+                self.settings_api_key = state.api_key.clone();
+            }
+            if self.settings_download_location.is_empty() {
+                self.settings_download_location = state.download_location.clone();
+            }
+        }
+
+        // Get current state for display
+        let (user_info, last_error) = {
             let state = self.state.lock().unwrap();
-            state.download_location.clone()
+            (state.user_info.clone(), state.last_error.clone())
         };
+
+        let mut settings_saved = false;
+
+        // Show error if any
+        if let Some(error_msg) = &last_error {
+            ui.colored_label(egui::Color32::RED, t!("error-message", error = error_msg.as_str()));
+            ui.separator();
+        }
+
+        self.render_language_setting(ui);
+        self.render_theme_setting(ui);
+        self.render_link_format_setting(ui);
+        self.render_storage_setting(ui);
+        self.render_cache_setting(ui);
+        ui.horizontal(|ui| {
+            ui.label(t!("metadata-concurrency"));
+            let mut concurrency = self.state.lock().unwrap().metadata_concurrency;
+            if ui.add(egui::DragValue::new(&mut concurrency).range(1..=MAX_METADATA_CONCURRENCY))
+                .on_hover_text(t!("metadata-concurrency-tooltip"))
+                .changed()
+            {
+                self.state.lock().unwrap().metadata_concurrency = concurrency;
+                self.save_preferences();
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label(t!("delete-undo-secs"));
+            let mut seconds = self.state.lock().unwrap().delete_undo_secs;
+            if ui.add(egui::DragValue::new(&mut seconds).range(0..=60).suffix(" s"))
+                .on_hover_text(t!("delete-undo-secs-tooltip"))
+                .changed()
+            {
+                self.state.lock().unwrap().delete_undo_secs = seconds;
+                self.save_preferences();
+            }
+        });
+        let mut confirm_deletes = self.state.lock().unwrap().confirm_deletes;
+        if ui.checkbox(&mut confirm_deletes, t!("confirm-deletes")).changed() {
+            self.state.lock().unwrap().confirm_deletes = confirm_deletes;
+            self.save_preferences();
+        }
+        let mut check_for_updates = self.state.lock().unwrap().check_for_updates;
+        if ui.checkbox(&mut check_for_updates, t!("check-for-updates")).on_hover_text(t!("check-for-updates-tooltip")).changed() {
+            self.state.lock().unwrap().check_for_updates = check_for_updates;
+            self.save_preferences();
+            if check_for_updates {
+                self.check_for_updates(ui.ctx().clone());
+            }
+        }
+
+        ui.separator();
+
+        self.render_profile_settings(ui);
+
+        ui.separator();
+
+        self.render_server_settings(ui);
+
+        ui.separator();
+
+        ui.label(t!("pixeldrain-api-key"));
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.settings_api_key).password(!self.show_api_key));
+            let (icon, tooltip) = if self.show_api_key { ("🙈", t!("hide-api-key")) } else { ("👁", t!("show-api-key")) };
+            if ui.button(icon).on_hover_text(tooltip).clicked() {
+                self.show_api_key = !self.show_api_key;
+            }
+            if ui.button(t!("paste")).clicked()
+                && let Ok(mut clipboard) = Clipboard::new()
+                && let Ok(text) = clipboard.get_text()
+            {
+                self.settings_api_key = text;
+            }
+        });
+        ui.label(t!("api-key-get-from", url = format!("{}/user/settings", self.state.lock().unwrap().base_url)));
+        self.render_login(ui);
         
-        // Reset progress at start
-        *self.download_progress.lock().unwrap() = 0.0;
-        *thread_running.lock().unwrap() = true;
+        let mut plaintext_api_key = self.state.lock().unwrap().plaintext_api_key;
+        if ui.checkbox(&mut plaintext_api_key, t!("store-api-key-in-the-settings"))
+            .on_hover_text(t!("only-needed-when-no-system-keyring"))
+            .changed()
+        {
+            self.state.lock().unwrap().plaintext_api_key = plaintext_api_key;
+            self.save_preferences();
+        }
+        if plaintext_api_key {
+            ui.colored_label(egui::Color32::from_rgb(255, 140, 0), t!("the-api-key-is-saved-unencrypted"));
+        } else {
+            ui.label(t!("the-api-key-is-kept-in"));
+        }
         
-        thread::spawn(move || {
-            let file_id = match PixelDrainClient::extract_file_id(&url) {
-                Ok(id) => id,
-                Err(e) => {
-                    let mut state = state.lock().unwrap();
-                    state.last_error = Some(format!("Invalid URL: {}", e));
-                    *thread_running.lock().unwrap() = false;
-                    return;
-                }
-            };
-            
-            let config = PixelDrainConfig::default();
-            let client = match PixelDrainClient::new(config) {
-                Ok(client) => client,
-                Err(e) => {
-                    let mut state = state.lock().unwrap();
-                    state.last_error = Some(format!("Failed to create client: {}", e));
-                    *thread_running.lock().unwrap() = false;
-                    return;
+        // Show if API key is set from environment
+        if let Ok(env_key) = env::var("PIXELDRAIN_API_KEY")
+            && !env_key.is_empty()
+        {
+            ui.horizontal(|ui| {
+                ui.label(t!("api-key-from-environment", prefix = &env_key[..8.min(env_key.len())]));
+                if ui.button(t!("copy")).clicked() {
+                    let _ = Clipboard::new().and_then(|mut c| c.set_text(env_key.clone()));
                 }
-            };
-            
-            // Get file info first
-            let file_info = match client.get_file_info(&file_id) {
-                Ok(info) => info,
-                Err(e) => {
-                    let mut state = state.lock().unwrap();
-                    state.last_error = Some(format!("Failed to get file info: {}", e));
-                    *thread_running.lock().unwrap() = false;
-                    return;
+            });
+            if self.settings_api_key.is_empty() {
+                ui.colored_label(egui::Color32::from_rgb(255, 140, 0), t!("environment-api-key-will-be-used"));
+            } else {
+                ui.colored_label(egui::Color32::GREEN, t!("settings-api-key-will-be-used"));
+            }
+        }
+        
+        ui.separator();
+
+        ui.label(t!("default-download-location"));
+        ui.horizontal(|ui| {
+            if ui.button(t!("choose-folder")).clicked()
+                && let Some(folder) = FileDialog::new().pick_folder()
+            {
+                self.settings_download_location = folder.display().to_string();
+            }
+            ui.text_edit_singleline(&mut self.settings_download_location);
+        });
+        let mut infer_extensions = self.state.lock().unwrap().infer_extensions;
+        if ui.checkbox(&mut infer_extensions, t!("settings-infer-extensions"))
+            .on_hover_text(t!("settings-infer-extensions-tooltip"))
+            .changed()
+        {
+            self.state.lock().unwrap().infer_extensions = infer_extensions;
+            self.save_preferences();
+        }
+        let mut join_split_parts = self.state.lock().unwrap().join_split_parts;
+        if ui.checkbox(&mut join_split_parts, t!("settings-join-split-parts"))
+            .on_hover_text(t!("settings-join-split-parts-tooltip"))
+            .changed()
+        {
+            self.state.lock().unwrap().join_split_parts = join_split_parts;
+            self.save_preferences();
+        }
+
+        ui.separator();
+
+        self.render_window_identity_settings(ui);
+
+        ui.separator();
+
+        self.render_encryption_keys(ui);
+
+        ui.separator();
+
+        self.render_settings_transfer(ui);
+        self.render_debug_panel(ui);
+
+        ui.separator();
+
+        // User info section with refresh button
+        let user_info_loading = *self.user_info_loading.lock().unwrap();
+        ui.horizontal(|ui| {
+            ui.label(t!("account-information"));
+            if user_info_loading {
+                self.render_loading_spinner(ui, &t!("loading-user-info"));
+            } else {
+                if ui.button(t!("refresh")).clicked() {
+                    self.fetch_user_info();
                 }
-            };
-            
-            let save_path = if !download_location.is_empty() {
-                PathBuf::from(&download_location).join(&file_info.name)
+            }
+        });
+
+        // User info if available
+        if let Some(user_info) = &user_info {
+            let storage_space_str = if user_info.subscription.storage_space < 0 {
+                t!("account-unlimited")
             } else {
-                PathBuf::from(&file_info.name)
+                self.format_file_size_bytes(user_info.subscription.storage_space as u64)
             };
-            
-            let progress_cb = Arc::new(Mutex::new(move |p: f32| {
-                let mut progress = progress.lock().unwrap();
-                *progress = p;
-            }));
-            let result = client.download_file(&file_id, &save_path, Some(progress_cb));
-            
-            let mut state = state.lock().unwrap();
-            match result {
-                Ok(_) => {
-                    let entry = DownloadHistoryEntry {
-                        url,
-                        filename: file_info.name,
-                        local_path: save_path.display().to_string(),
-                        timestamp: Utc::now(),
-                    };
-                    state.download_history.push(entry);
-                    state.last_error = None;
+            ui.label(t!("account-username", username = user_info.username.as_str()));
+            ui.label(t!("account-email", email = user_info.email.as_str()));
+            ui.label(t!("account-files", count = user_info.file_count));
+            ui.label(t!(
+                "account-storage",
+                used = self.format_file_size_bytes(user_info.storage_space_used),
+                total = storage_space_str,
+            ));
+            ui.label(t!(
+                "account-monthly-transfer",
+                used = self.format_file_size_bytes(user_info.monthly_transfer_used),
+                cap = self.format_file_size_bytes(user_info.monthly_transfer_cap),
+            ));
+            ui.label(t!("account-expiry-days", days = user_info.subscription.file_expiry_days));
+            ui.label(t!("account-balance", balance = user_info.balance_micro_eur));
+            self.render_hotlinking_toggle(ui, user_info);
+            if user_info.subscription.file_viewer_branding {
+                self.render_sharing_settings(ui, user_info);
+            }
+            self.render_sessions(ui);
+        } else {
+            ui.colored_label(egui::Color32::GRAY, t!("no-account-information-available-set-api"));
+        }
+
+        ui.separator();
+
+        if ui.button(t!("save-settings")).clicked() {
+            self.save_settings(self.settings_api_key.clone(), self.settings_download_location.clone());
+            settings_saved = true;
+            // Try to fetch user info after saving settings
+            self.fetch_user_info();
+        }
+        
+        // Show success message only after actually saving
+        if settings_saved {
+            ui.colored_label(egui::Color32::GREEN, t!("settings-saved-successfully"));
+        }
+    }
+
+    /// Header combo box to switch between named account profiles
+    fn render_profile_switcher(&mut self, ui: &mut egui::Ui) {
+        let (names, active) = {
+            let state = self.state.lock().unwrap();
+            (state.profiles.iter().map(|p| p.name.clone()).collect::<Vec<_>>(), state.active_profile.clone())
+        };
+        if names.len() < 2 {
+            return;
+        }
+        let mut selected = active.clone();
+        egui::ComboBox::from_id_salt("profile_switcher")
+            .selected_text(format!("👤 {}", active))
+            .show_ui(ui, |ui| {
+                for name in &names {
+                    ui.selectable_value(&mut selected, name.clone(), name);
                 }
-                Err(e) => {
-                    state.last_error = Some(format!("Download error: {}", e));
+            });
+        if selected != active {
+            self.switch_profile(&selected);
+        }
+    }
+
+    fn render_profile_settings(&mut self, ui: &mut egui::Ui) {
+        let (names, active) = {
+            let state = self.state.lock().unwrap();
+            (state.profiles.iter().map(|p| p.name.clone()).collect::<Vec<_>>(), state.active_profile.clone())
+        };
+        ui.label(t!("profile-active", name = active.as_str()));
+        ui.label(t!("api-key-download-location-and-preferences"));
+        
+        let mut switch_to = None;
+        let mut remove = None;
+        for name in &names {
+            ui.horizontal(|ui| {
+                if ui.selectable_label(*name == active, name).clicked() && *name != active {
+                    switch_to = Some(name.clone());
+                }
+                if *name != active && ui.small_button("🗑").on_hover_text(t!("remove-profile")).clicked() {
+                    remove = Some(name.clone());
+                }
+            });
+        }
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.new_profile_name).hint_text(t!("new-profile-name")));
+            if ui.button(t!("add-profile")).clicked() {
+                let result = self.state.lock().unwrap().add_profile(&self.new_profile_name);
+                match result {
+                    Ok(()) => {
+                        self.new_profile_name.clear();
+                        self.on_profile_switched();
+                    }
+                    Err(e) => self.state.lock().unwrap().last_error = Some(e),
                 }
             }
-            *thread_running.lock().unwrap() = false;
         });
+        
+        if let Some(name) = remove {
+            self.state.lock().unwrap().remove_profile(&name);
+            self.save_preferences();
+        }
+        if let Some(name) = switch_to {
+            self.switch_profile(&name);
+        }
     }
 
-    fn refresh_file_list(&self) {
-        // Set loading state
-        *self.files_loading.lock().unwrap() = true;
-        
-        // Get API key with settings priority
-        let api_key = self.get_api_key();
-        
-        let state = self.state.clone();
-        let files_loading = self.files_loading.clone();
-        
-        // Clear any previous errors when starting
-        state.lock().unwrap().last_error = None;
-        
-        thread::spawn(move || {
-            let config = if let Some(key) = api_key {
-                PixelDrainConfig::default().with_api_key(key)
-            } else {
-                PixelDrainConfig::default()
-            };
-            
-            let client = match PixelDrainClient::new(config) {
-                Ok(client) => client,
-                Err(e) => {
-                    let mut state = state.lock().unwrap();
-                    state.last_error = Some(format!("Failed to create client: {}", e));
-                    *files_loading.lock().unwrap() = false;
-                    return;
-                }
-            };
-            
-            // Add retry logic similar to Lists tab functions
-            const MAX_RETRIES: usize = 3;
-            const RETRY_DELAY: std::time::Duration = std::time::Duration::from_secs(3);
-            
-            let mut last_error = None;
-            
-            for attempt in 1..=MAX_RETRIES {
-                match client.get_user_files() {
-                    Ok(response) => {
-                        let mut state = state.lock().unwrap();
-                        state.file_list = response.files.clone();
-                        state.last_error = None;
-                        // Prefetch thumbnails for all files with a thumbnail_href
-                        let _thumbnail_cache: HashMap<String, Vec<u8>> = HashMap::new();
-                        for file in &response.files {
-                            if !file.thumbnail_href.is_empty() {
-                                if let Ok(bytes) = reqwest::blocking::get(&file.thumbnail_href).and_then(|r| r.bytes()) {
-                                    if let Ok(img) = image::load_from_memory(&bytes) {
-                                        let rgba = img.to_rgba8();
-                                        let (w, h) = rgba.dimensions();
-                                        let _color_img = egui::ColorImage::from_rgba_unmultiplied([
-                                            w as usize, h as usize
-                                        ], &rgba);
-                                        // Note: TextureHandle must be created on the UI thread, so here we just cache the bytes or ColorImage if needed
-                                        // For now, just store the bytes in a HashMap<String, Vec<u8>> or similar if you want to use it later
-                                    }
-                                }
-                            }
-                        }
-                        *files_loading.lock().unwrap() = false;
-                        return;
+    fn switch_profile(&mut self, name: &str) {
+        if self.state.lock().unwrap().switch_profile(name) {
+            self.on_profile_switched();
+        }
+    }
+
+    /// Reset per-account UI state after the active profile changed
+    fn on_profile_switched(&mut self) {
+        self.settings_api_key.clear();
+        self.settings_base_url.clear();
+        self.settings_download_location.clear();
+        self.lists.lock().unwrap().clear();
+        self.selected_list_id = None;
+        self.upload_target_list = None;
+        self.selected_files.clear();
+        self.seen_file_ids = None;
+        *self.fs_listing.lock().unwrap() = None;
+        self.fs_path = FILESYSTEM_ROOT.to_string();
+        self.save_preferences();
+        if self.has_api_key() {
+            self.fetch_user_info();
+        }
+    }
+
+    fn render_language_setting(&mut self, ui: &mut egui::Ui) {
+        let current = self.state.lock().unwrap().language;
+        let mut language = current;
+        ui.horizontal(|ui| {
+            ui.label(t!("settings-language"));
+            egui::ComboBox::from_id_salt("language")
+                .selected_text(language.native_name())
+                .show_ui(ui, |ui| {
+                    for option in i18n::Language::ALL {
+                        ui.selectable_value(&mut language, option, option.native_name());
                     }
-                    Err(e) => {
-                        last_error = Some(e);
-                        
-                        // Check if this is a retryable error
-                        let should_retry = match &last_error.as_ref().unwrap() {
-                            pixeldrain_api::PixelDrainError::Reqwest(reqwest_err) => {
-                                reqwest_err.is_timeout() || 
-                                reqwest_err.is_connect() || 
-                                reqwest_err.is_request() ||
-                                reqwest_err.to_string().contains("request or response body error")
-                            }
-                            pixeldrain_api::PixelDrainError::Api(api_err) => {
-                                api_err.status.is_server_error()
-                            }
-                            _ => false,
-                        };
-                        
-                        if should_retry && attempt < MAX_RETRIES {
-                            std::thread::sleep(RETRY_DELAY);
-                            continue;
+                });
+        });
+        if language != current {
+            self.state.lock().unwrap().language = language;
+            i18n::set_language(language);
+            self.save_preferences();
+        }
+    }
+
+    fn render_theme_setting(&mut self, ui: &mut egui::Ui) {
+        let (dark_mode, mut high_contrast) = {
+            let state = self.state.lock().unwrap();
+            (state.dark_mode, state.high_contrast)
+        };
+        if ui.checkbox(&mut high_contrast, t!("settings-high-contrast"))
+            .on_hover_text(t!("settings-high-contrast-tooltip"))
+            .changed()
+        {
+            self.state.lock().unwrap().high_contrast = high_contrast;
+            theme::apply(ui.ctx(), dark_mode, high_contrast);
+            self.save_preferences();
+        }
+    }
+
+    fn archive_format_label(format: ArchiveFormat) -> String {
+        match format {
+            ArchiveFormat::TarGz => t!("archive-format-tar-gz"),
+            ArchiveFormat::Zip => t!("archive-format-zip"),
+            ArchiveFormat::TarZst => t!("archive-format-tar-zst"),
+        }
+    }
+
+    fn render_archive_format(&mut self, ui: &mut egui::Ui) {
+        let current = self.state.lock().unwrap().archive_format;
+        let mut format = current;
+        ui.horizontal(|ui| {
+            ui.label(t!("archive-format"));
+            egui::ComboBox::from_id_salt("archive_format")
+                .selected_text(Self::archive_format_label(format))
+                .show_ui(ui, |ui| {
+                    for option in ArchiveFormat::ALL {
+                        ui.selectable_value(&mut format, option, Self::archive_format_label(option));
+                    }
+                });
+        });
+        if format != current {
+            self.state.lock().unwrap().archive_format = format;
+            self.save_preferences();
+        }
+        if format == ArchiveFormat::TarZst {
+            let mut parallel = self.state.lock().unwrap().parallel_compression;
+            if ui.checkbox(&mut parallel, t!("archive-parallel")).on_hover_text(t!("archive-parallel-tooltip")).changed() {
+                self.state.lock().unwrap().parallel_compression = parallel;
+                self.save_preferences();
+            }
+        }
+    }
+
+    fn render_exclude_patterns(&mut self, ui: &mut egui::Ui, dir: &Path) {
+        let mut patterns = self.state.lock().unwrap().exclude_patterns.clone();
+        ui.label(t!("exclude-patterns"));
+        let response = ui.add(
+            egui::TextEdit::multiline(&mut patterns)
+                .hint_text("node_modules/\n*.tmp")
+                .desired_rows(3)
+                .code_editor(),
+        )
+        .on_hover_text(t!("exclude-patterns-tooltip"));
+        self.state.lock().unwrap().exclude_patterns = patterns.clone();
+        if response.lost_focus() {
+            self.save_preferences();
+        }
+
+        // Counted again only when the directory or the patterns change
+        let stale = !matches!(&self.directory_preview, Some((counted_dir, counted_patterns, ..)) if counted_dir == dir && *counted_patterns == patterns);
+        if stale {
+            self.directory_preview = preflight::scan_directory(dir, &preflight::Excludes::parse(&patterns)).ok().map(|(entries, _)| {
+                let (files, size) = preflight::files_and_size(dir, &entries);
+                (dir.to_path_buf(), patterns, files, size)
+            });
+        }
+        if let Some((_, _, files, size)) = &self.directory_preview {
+            ui.label(t!("directory-after-exclusions", count = *files, size = self.format_file_size_bytes(*size)));
+        }
+    }
+
+    fn render_link_format_setting(&mut self, ui: &mut egui::Ui) {
+        let current = self.state.lock().unwrap().link_format;
+        let mut format = current;
+        ui.horizontal(|ui| {
+            ui.label(t!("settings-link-format"));
+            egui::ComboBox::from_id_salt("link_format")
+                .selected_text(Self::link_format_label(format))
+                .show_ui(ui, |ui| {
+                    for option in LinkFormat::ALL {
+                        ui.selectable_value(&mut format, option, Self::link_format_label(option));
+                    }
+                });
+        });
+        if format != current {
+            self.state.lock().unwrap().link_format = format;
+            self.save_preferences();
+        }
+
+        let (mut auto, mut direct) = {
+            let state = self.state.lock().unwrap();
+            (state.auto_link_type, state.direct_link_categories.clone())
+        };
+        let mut changed = ui.checkbox(&mut auto, t!("settings-auto-link-type"))
+            .on_hover_text(t!("settings-auto-link-type-tooltip"))
+            .changed();
+        if auto {
+            ui.horizontal_wrapped(|ui| {
+                ui.label(t!("settings-direct-links-for"));
+                for category in FileCategory::ALL {
+                    let mut checked = direct.contains(&category);
+                    if ui.checkbox(&mut checked, Self::file_category_label(category)).changed() {
+                        if checked {
+                            direct.push(category);
                         } else {
-                            break;
+                            direct.retain(|c| *c != category);
                         }
+                        changed = true;
                     }
                 }
+            });
+        }
+        if changed {
+            let mut state = self.state.lock().unwrap();
+            state.auto_link_type = auto;
+            state.direct_link_categories = direct;
+            drop(state);
+            self.save_preferences();
+        }
+    }
+
+    fn render_hotlinking_toggle(&mut self, ui: &mut egui::Ui, user_info: &UserInfo) {
+        let saving = *self.hotlinking_saving.lock().unwrap();
+        let mut enabled = user_info.hotlinking_enabled;
+        ui.horizontal(|ui| {
+            let toggled = ui.add_enabled(!saving, egui::Checkbox::new(&mut enabled, t!("hotlinking-enabled")))
+                .on_hover_text(t!("hotlinking-tooltip"))
+                .changed();
+            if saving {
+                ui.spinner();
+            }
+            if toggled {
+                self.set_hotlinking(enabled);
             }
-            
-            // If we get here, all retries failed
-            let mut state = state.lock().unwrap();
-            state.last_error = Some(format!("Failed to list files after {} attempts: {}", 
-                MAX_RETRIES, last_error.unwrap()));
-            *files_loading.lock().unwrap() = false;
         });
+        ui.add(egui::Label::new(egui::RichText::new(t!("hotlinking-note")).small().weak()).wrap());
     }
 
-    fn delete_file(&self, file_id: &str) {
-        // Set loading state
-        *self.file_delete_loading.lock().unwrap() = true;
-        
-        // Get API key with settings priority
-        let api_key = self.get_api_key();
-
+    /// Turn hotlinking on or off, shown right away and confirmed by reloading the user info
+    fn set_hotlinking(&mut self, enabled: bool) {
+        if let Some(user) = self.state.lock().unwrap().user_info.as_mut() {
+            user.hotlinking_enabled = enabled;
+        }
+        let client = self.make_api_client();
         let state = self.state.clone();
-        let file_id = file_id.to_string();
-        let file_delete_loading = self.file_delete_loading.clone();
-
-        // Clear any previous errors when starting
-        state.lock().unwrap().last_error = None;
+        let saving = self.hotlinking_saving.clone();
+        *saving.lock().unwrap() = true;
+        self.workers.spawn(move || {
+            let result = client.update_hotlinking(enabled);
+            let user = client.get_user();
+            let mut state = state.lock().unwrap();
+            if let Err(e) = result {
+                state.report_error(&t!("error-context-hotlinking"), &e);
+            }
+            if let Ok(user) = user {
+                state.user_info = Some(user);
+            }
+            drop(state);
+            *saving.lock().unwrap() = false;
+        });
+    }
 
-        thread::spawn(move || {
-            let start_time = Instant::now();
-            
-            let config = if let Some(key) = api_key.clone() {
-                PixelDrainConfig::default().with_api_key(key)
-            } else {
-                PixelDrainConfig::default()
+    /// Embed domains and file viewer branding, for subscriptions that include branding
+    fn render_sharing_settings(&mut self, ui: &mut egui::Ui, user_info: &UserInfo) {
+        if self.sharing_form.as_ref().is_none_or(|form| form.username != user_info.username) {
+            self.sharing_form = Some(SharingSettingsForm::from_user(user_info));
+        }
+        let saving = *self.sharing_saving.lock().unwrap();
+        let mut save = false;
+        egui::CollapsingHeader::new(t!("sharing-settings")).id_salt("sharing_settings").show(ui, |ui| {
+            let Some(form) = self.sharing_form.as_mut() else {
+                return;
             };
-
-            let client = match PixelDrainClient::new(config) {
-                Ok(client) => client,
-                Err(e) => {
-                    let mut state = state.lock().unwrap();
-                    state.last_error = Some(format!("Failed to create client: {}", e));
-                    *file_delete_loading.lock().unwrap() = false;
+            ui.label(t!("embed-domains"));
+            ui.add(egui::TextEdit::singleline(&mut form.embed_domains).hint_text(t!("embed-domains-hint")).desired_width(f32::INFINITY));
+            egui::Grid::new("branding_fields").num_columns(2).show(ui, |ui| {
+                for field in pixeldrain_api::BRANDING_FIELDS {
+                    ui.label(Self::branding_field_label(field));
+                    ui.add(egui::TextEdit::singleline(form.branding.entry(field.to_string()).or_default()).desired_width(300.0));
+                    ui.end_row();
+                }
+            });
+            let saved = SharingSettingsForm::from_user(user_info);
+            ui.horizontal(|ui| {
+                if saving {
+                    ui.spinner();
+                    ui.label(t!("sharing-settings-saving"));
                     return;
                 }
-            };
+                save = ui.add_enabled(*form != saved, egui::Button::new(t!("sharing-settings-save"))).clicked();
+                if ui.add_enabled(*form != saved, egui::Button::new(t!("sharing-settings-reset"))).clicked() {
+                    *form = saved.clone();
+                }
+            });
+        });
+        if save {
+            self.save_sharing_settings();
+        }
+    }
 
-            // Add retry logic similar to Lists tab functions
-            const MAX_RETRIES: usize = 3;
-            const RETRY_DELAY: std::time::Duration = std::time::Duration::from_secs(3);
-            
-            let mut last_error = None;
-            
-            for attempt in 1..=MAX_RETRIES {
-                                    match client.delete_file(&file_id) {
-                        Ok(_) => {
-                            let _duration = start_time.elapsed();
-                            {
-                            let mut state = state.lock().unwrap();
-                            state.last_error = None;
+    /// Send what changed in the sharing form, then reload the user info
+    fn save_sharing_settings(&mut self) {
+        let user_info = self.state.lock().unwrap().user_info.clone();
+        let (Some(form), Some(user_info)) = (self.sharing_form.clone(), user_info) else {
+            return;
+        };
+        let saved = SharingSettingsForm::from_user(&user_info);
+        let client = self.make_api_client();
+        let state = self.state.clone();
+        let saving = self.sharing_saving.clone();
+        *saving.lock().unwrap() = true;
+        self.workers.spawn(move || {
+            let mut result = Ok(());
+            if form.embed_domains != saved.embed_domains {
+                result = client.update_file_embed_domains(form.embed_domains.trim());
+            }
+            if result.is_ok() && form.branding != saved.branding {
+                result = client.update_file_viewer_branding(&form.branding);
+            }
+            let user = client.get_user();
+            let mut state = state.lock().unwrap();
+            match result {
+                Ok(()) => state.last_error = None,
+                Err(e) => state.report_error(&t!("error-context-sharing-settings"), &e),
+            }
+            if let Ok(user) = user {
+                state.user_info = Some(user);
+            }
+            drop(state);
+            *saving.lock().unwrap() = false;
+        });
+    }
 
-                            state.last_operation_time = Some(chrono::Utc::now());
-                        } // Release lock here
-                        
-                        *file_delete_loading.lock().unwrap() = false;
-                        
-                        // Refresh the file list after successful deletion
-                        let state_clone = state.clone();
-                        let api_key_clone = api_key.clone();
-                        thread::spawn(move || {
-                            thread::sleep(std::time::Duration::from_millis(500)); // Small delay
-                            
-                            let config = if let Some(key) = api_key_clone {
-                                PixelDrainConfig::default().with_api_key(key)
-                            } else {
-                                PixelDrainConfig::default()
-                            };
-                            
-                            if let Ok(client) = PixelDrainClient::new(config) {
-                                if let Ok(response) = client.get_user_files() {
-                                    let mut state = state_clone.lock().unwrap();
-                                    state.file_list = response.files;
+    /// Username and password form that gets an API key, as an alternative to copying one
+    fn render_login(&mut self, ui: &mut egui::Ui) {
+        let result = self.login_result.lock().unwrap().take();
+        if let Some(result) = result {
+            match result {
+                Ok(api_key) => {
+                    self.login_password.clear();
+                    self.settings_api_key = api_key.clone();
+                    self.save_settings(api_key, self.settings_download_location.clone());
+                    self.fetch_user_info();
+                }
+                Err(e) => self.state.lock().unwrap().last_error = Some(e),
+            }
+        }
+        let pending = *self.login_pending.lock().unwrap();
+        egui::CollapsingHeader::new(t!("login-heading")).id_salt("login").show(ui, |ui| {
+            egui::Grid::new("login_grid").num_columns(2).show(ui, |ui| {
+                ui.label(t!("login-username"));
+                ui.add(egui::TextEdit::singleline(&mut self.login_username).desired_width(200.0));
+                ui.end_row();
+                ui.label(t!("login-password"));
+                let response = ui.add(egui::TextEdit::singleline(&mut self.login_password).password(true).desired_width(200.0));
+                ui.end_row();
+                let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                let ready = !pending && !self.login_username.trim().is_empty() && !self.login_password.is_empty();
+                ui.label("");
+                ui.horizontal(|ui| {
+                    if (ui.add_enabled(ready, egui::Button::new(t!("login-button"))).clicked() || (submitted && ready))
+                        && !pending
+                    {
+                        self.log_in();
+                    }
+                    if pending {
+                        ui.spinner();
+                    }
+                });
+                ui.end_row();
+            });
+            ui.label(egui::RichText::new(t!("login-note")).small().weak());
+        });
+    }
 
-                                }
-                            }
-                        });
-                        return;
+    /// Create a session for the username and password, its key becomes the API key
+    fn log_in(&mut self) {
+        let config = PixelDrainConfig::default().with_credentials(self.login_username.trim(), &self.login_password);
+        let api_factory = self.api_factory.clone();
+        let pending = self.login_pending.clone();
+        let result = self.login_result.clone();
+        *pending.lock().unwrap() = true;
+        self.workers.spawn(move || {
+            let session = api_factory(config)
+                .and_then(|client| client.post_user_session(APP_NAME))
+                .map_err(|e| format!("Login failed: {}", e))
+                .and_then(|session| {
+                    if session.auth_key.is_empty() {
+                        Err("Login failed: the server sent no session key".to_string())
+                    } else {
+                        Ok(session.auth_key)
                     }
-                    Err(e) => {
-                        last_error = Some(e);
-                        
-                        // Check if this is a retryable error
-                        let should_retry = match &last_error.as_ref().unwrap() {
-                            pixeldrain_api::PixelDrainError::Reqwest(reqwest_err) => {
-                                reqwest_err.is_timeout() || 
-                                reqwest_err.is_connect() || 
-                                reqwest_err.is_request() ||
-                                reqwest_err.to_string().contains("request or response body error")
+                });
+            *result.lock().unwrap() = Some(session);
+            *pending.lock().unwrap() = false;
+        });
+    }
+
+    /// Sessions of the account with where they were created, each can be revoked
+    fn render_sessions(&mut self, ui: &mut egui::Ui) {
+        let loading = *self.sessions_loading.lock().unwrap();
+        let sessions = self.sessions.lock().unwrap().clone();
+        let current_key = self.get_api_key().unwrap_or_default();
+        let mut refresh = false;
+        let mut revoke: Option<String> = None;
+        egui::CollapsingHeader::new(t!("sessions-heading")).id_salt("sessions").show(ui, |ui| {
+            refresh = sessions.is_none() && !loading;
+            ui.horizontal(|ui| {
+                ui.label(t!("sessions-description"));
+                if loading {
+                    ui.spinner();
+                } else if ui.button(t!("refresh")).clicked() {
+                    refresh = true;
+                }
+            });
+            let Some(mut sessions) = sessions else {
+                return;
+            };
+            sessions.sort_by_key(|session| std::cmp::Reverse(session.last_used_time));
+            egui::Grid::new("sessions_grid").striped(true).num_columns(5).show(ui, |ui| {
+                for header in [t!("sessions-key"), t!("sessions-app"), t!("sessions-created"), t!("sessions-last-used"), String::new()] {
+                    ui.strong(header);
+                }
+                ui.end_row();
+                for session in &sessions {
+                    let current = session.auth_key == current_key;
+                    // Enough of the key to tell sessions apart, the rest stays hidden
+                    let short_key: String = session.auth_key.chars().take(8).collect();
+                    ui.label(format!("{}…", short_key)).on_hover_text(&session.user_agent);
+                    let app = if session.app_name.is_empty() { session.user_agent.as_str() } else { session.app_name.as_str() };
+                    ui.add(egui::Label::new(app).truncate()).on_hover_text(&session.user_agent);
+                    ui.label(format!(
+                        "{} ({})",
+                        session.creation_time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"),
+                        session.creation_ip
+                    ));
+                    ui.label(session.last_used_time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string());
+                    ui.horizontal(|ui| {
+                        if current && self.session_revoke_confirm.as_deref() == Some(session.auth_key.as_str()) {
+                            ui.colored_label(egui::Color32::from_rgb(200, 120, 0), t!("sessions-revoke-current-warning"));
+                            if ui.button(t!("sessions-revoke-confirm")).clicked() {
+                                revoke = Some(session.auth_key.clone());
                             }
-                            pixeldrain_api::PixelDrainError::Api(api_err) => {
-                                api_err.status.is_server_error()
+                            if ui.button(t!("cancel")).clicked() {
+                                self.session_revoke_confirm = None;
                             }
-                            _ => false,
-                        };
-                        
-                        if should_retry && attempt < MAX_RETRIES {
-                            std::thread::sleep(RETRY_DELAY);
-                            continue;
                         } else {
-                            break;
+                            if current {
+                                ui.label(t!("sessions-this-app"));
+                            }
+                            if ui.add_enabled(!loading, egui::Button::new(t!("sessions-revoke"))).clicked() {
+                                if current {
+                                    self.session_revoke_confirm = Some(session.auth_key.clone());
+                                } else {
+                                    revoke = Some(session.auth_key.clone());
+                                }
+                            }
                         }
-                    }
+                    });
+                    ui.end_row();
                 }
+            });
+        });
+        if let Some(session_key) = revoke {
+            self.session_revoke_confirm = None;
+            self.revoke_session(session_key);
+        } else if refresh {
+            self.refresh_sessions();
+        }
+    }
+
+    fn refresh_sessions(&mut self) {
+        self.run_session_operation(|_| Ok(()));
+    }
+
+    /// Log out `session_key` wherever it's used, then reload the sessions
+    fn revoke_session(&mut self, session_key: String) {
+        self.run_session_operation(move |client| client.delete_user_session(&session_key));
+    }
+
+    fn run_session_operation<F>(&mut self, operation: F)
+    where
+        F: FnOnce(&dyn PixelDrainApi) -> Result<(), pixeldrain_api::PixelDrainError> + Send + 'static,
+    {
+        let client = self.make_api_client();
+        let state = self.state.clone();
+        let sessions = self.sessions.clone();
+        let loading = self.sessions_loading.clone();
+        *loading.lock().unwrap() = true;
+        self.workers.spawn(move || {
+            if let Err(e) = operation(client.as_ref()) {
+                state.lock().unwrap().report_error(&t!("error-context-revoke-session"), &e);
             }
-            
-            // If we get here, all retries failed
+            match client.get_user_sessions() {
+                Ok(result) => *sessions.lock().unwrap() = Some(result),
+                Err(e) => {
+                    // An empty list instead of None, so the section doesn't keep retrying
+                    sessions.lock().unwrap().get_or_insert_with(Vec::new);
+                    state.lock().unwrap().report_error(&t!("error-context-load-sessions"), &e);
+                }
+            }
+            *loading.lock().unwrap() = false;
+        });
+    }
+
+    fn branding_field_label(field: &str) -> String {
+        match field {
+            "theme" => t!("branding-theme"),
+            "header_image" => t!("branding-header-image"),
+            "header_link" => t!("branding-header-link"),
+            "background_image" => t!("branding-background-image"),
+            _ => field.to_string(),
+        }
+    }
+
+    fn file_category_label(category: FileCategory) -> String {
+        match category {
+            FileCategory::Image => t!("file-category-image"),
+            FileCategory::Video => t!("file-category-video"),
+            FileCategory::Audio => t!("file-category-audio"),
+            FileCategory::Document => t!("file-category-document"),
+            FileCategory::Archive => t!("file-category-archive"),
+            FileCategory::Other => t!("file-category-other"),
+        }
+    }
+
+    fn render_storage_setting(&mut self, ui: &mut egui::Ui) {
+        let current = self.state.lock().unwrap().storage_backend;
+        let label = |backend: StorageBackend| match backend {
+            StorageBackend::Sqlite => t!("storage-sqlite"),
+            StorageBackend::Json => t!("storage-json"),
+        };
+        let mut backend = current;
+        ui.horizontal(|ui| {
+            ui.label(t!("settings-history-storage"));
+            egui::ComboBox::from_id_salt("storage_backend")
+                .selected_text(label(backend))
+                .show_ui(ui, |ui| {
+                    for option in StorageBackend::ALL {
+                        ui.selectable_value(&mut backend, option, label(option));
+                    }
+                });
+        });
+        if backend != current
+            && let Err(e) = self.switch_storage_backend(backend)
+        {
+            self.state.lock().unwrap().last_error = Some(format!("Failed to switch the history storage: {}", e));
+        }
+    }
+
+    /// Text field for a size, typed like "1,5 GB" or "500 MB" in the selected
+    /// language. Returns the new size once it was entered, sizes that don't
+    /// parse go back to the current one.
+    fn size_field(ui: &mut egui::Ui, id_salt: &str, bytes: u64) -> Option<u64> {
+        let id = ui.make_persistent_id(id_salt);
+        let language = i18n::language();
+        let mut text = ui.data_mut(|data| data.get_temp::<String>(id)).unwrap_or_else(|| units::format_size(bytes, language));
+        let response = ui
+            .add(egui::TextEdit::singleline(&mut text).desired_width(90.0))
+            .on_hover_text(t!("size-field-tooltip"));
+        if response.lost_focus() {
+            ui.data_mut(|data| data.remove::<String>(id));
+            return units::parse_size(&text, language).filter(|&entered| entered != bytes);
+        }
+        if response.has_focus() {
+            ui.data_mut(|data| data.insert_temp(id, text));
+        }
+        None
+    }
+
+    /// Size limit of the disk cache and transfer journal, the cache is trimmed right
+    /// away when it is lowered
+    fn render_cache_setting(&mut self, ui: &mut egui::Ui) {
+        let cache_dir = cache::cache_dir();
+        let (usage, resume) = *self.cache_usage.get_or_insert_with(|| (cache::usage(&cache_dir), self.journal.disk_usage()));
+        let limit = self.state.lock().unwrap().cache_limit;
+        ui.horizontal(|ui| {
+            ui.label(t!("settings-cache-limit")).on_hover_text(t!("settings-cache-limit-tooltip"));
+            if let Some(limit) = Self::size_field(ui, "cache_limit", limit) {
+                self.state.lock().unwrap().cache_limit = limit;
+                self.save_preferences();
+                if let Err(e) = cache::enforce_limit(&cache_dir, limit, resume) {
+                    self.state.lock().unwrap().last_error = Some(format!("Failed to clean up the cache: {}", e));
+                }
+                self.cache_usage = None;
+            }
+            ui.label(t!("settings-cache-used", used = self.format_file_size_bytes(usage + resume), resume = self.format_file_size_bytes(resume)));
+            if ui.button(t!("settings-cache-clear")).clicked() {
+                if let Err(e) = cache::clear(&cache_dir) {
+                    self.state.lock().unwrap().last_error = Some(format!("Failed to clear the cache: {}", e));
+                }
+                self.cache_usage = None;
+            }
+        });
+    }
+
+    fn render_server_settings(&mut self, ui: &mut egui::Ui) {
+        let current = self.state.lock().unwrap().base_url.clone();
+        if self.settings_base_url.is_empty() {
+            self.settings_base_url = current.clone();
+        }
+        
+        ui.label(t!("server-url"));
+        let mut apply = None;
+        ui.horizontal(|ui| {
+            let response = ui.add(egui::TextEdit::singleline(&mut self.settings_base_url).hint_text(pixeldrain_api::BASE_URL));
+            if response.lost_focus() && self.settings_base_url != current {
+                apply = Some(self.settings_base_url.clone());
+            }
+            if current != pixeldrain_api::BASE_URL && ui.button(t!("server-url-reset")).clicked() {
+                apply = Some(pixeldrain_api::BASE_URL.to_string());
+            }
+        });
+        ui.label(t!("change-this-only-for-a-self"));
+        
+        if let Some(input) = apply {
+            match pixeldrain_api::normalize_base_url(&input) {
+                Ok(base_url) => {
+                    self.settings_base_url = base_url.clone();
+                    let mut state = self.state.lock().unwrap();
+                    state.base_url = base_url;
+                    // Files, lists and user info belong to the previous server
+                    state.file_list.clear();
+                    state.file_changes = FileListChanges::default();
+                    state.restore_cached_file_list();
+                    state.user_info = None;
+                    state.api_key_rejected = false;
+                    drop(state);
+                    self.lists.lock().unwrap().clear();
+                    self.seen_file_ids = None;
+                    self.save_preferences();
+                    if self.has_api_key() {
+                        self.fetch_user_info();
+                    }
+                }
+                Err(e) => {
+                    self.state.lock().unwrap().last_error = Some(e);
+                }
+            }
+        }
+    }
+
+    /// Identifiers of the encryption keys available here, files needing others are flagged
+    fn render_encryption_keys(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new(t!("encryption-keys")).id_salt("encryption_keys").show(ui, |ui| {
+            ui.add(egui::Label::new(egui::RichText::new(t!("encryption-keys-note")).small()).wrap());
+            self.render_encryption_passphrase(ui);
+            ui.separator();
+            let configured: Vec<String> = self.state.lock().unwrap().key_registry.configured().map(str::to_string).collect();
+            let mut removed = None;
+            for key_id in &configured {
+                ui.horizontal(|ui| {
+                    ui.label(format!("🔑 {}", key_id));
+                    if ui.small_button("🗑").on_hover_text(t!("encryption-key-remove")).clicked() {
+                        removed = Some(key_id.clone());
+                    }
+                });
+            }
+            let mut added = false;
+            ui.horizontal(|ui| {
+                let response = ui.add(egui::TextEdit::singleline(&mut self.new_key_id).hint_text(t!("key-id-hint")).desired_width(200.0));
+                added = (response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter))) || ui.button(t!("encryption-key-add")).clicked();
+            });
+            if added && !self.new_key_id.trim().is_empty() {
+                self.state.lock().unwrap().key_registry.configure(&self.new_key_id);
+                self.new_key_id.clear();
+                self.save_preferences();
+            }
+            if let Some(key_id) = removed {
+                self.state.lock().unwrap().key_registry.remove_key(&key_id);
+                self.save_preferences();
+            }
+        });
+    }
+
+    /// Passphrase that uploads are encrypted with and .enc downloads decrypted with
+    fn render_encryption_passphrase(&mut self, ui: &mut egui::Ui) {
+        let (mut passphrase, mut key_id, mut remember) = {
+            let state = self.state.lock().unwrap();
+            (state.encryption_passphrase.clone(), state.encryption_key_id.clone(), state.remember_encryption_passphrase)
+        };
+        let mut changed = false;
+        egui::Grid::new("encryption_passphrase").num_columns(2).show(ui, |ui| {
+            ui.label(t!("encryption-passphrase"));
+            changed |= ui.add(egui::TextEdit::singleline(&mut passphrase).password(true).desired_width(200.0)).lost_focus();
+            ui.end_row();
+            ui.label(t!("encryption-key-id"));
+            changed |= ui.add(egui::TextEdit::singleline(&mut key_id).hint_text(t!("key-id-hint")).desired_width(200.0)).lost_focus();
+            ui.end_row();
+        });
+        changed |= ui.checkbox(&mut remember, t!("remember-encryption-passphrase"))
+            .on_hover_text(t!("remember-encryption-passphrase-tooltip"))
+            .changed();
+        
+        let mut state = self.state.lock().unwrap();
+        state.encryption_passphrase = passphrase.clone();
+        state.encryption_key_id = key_id;
+        state.remember_encryption_passphrase = remember;
+        if changed && !passphrase.is_empty() {
+            // Files encrypted with this passphrase can be opened here
+            let key_id = state.encryption_key_id.clone();
+            state.key_registry.configure(&key_id);
+        }
+        drop(state);
+        if changed {
+            let stored = (remember && !passphrase.is_empty()).then_some(passphrase.as_str());
+            if let Err(e) = self.secrets.store_passphrase(stored) {
+                self.state.lock().unwrap().last_error = Some(t!("encryption-passphrase-not-stored", error = e.to_string()));
+            }
+            self.save_preferences();
+        }
+    }
+
+    fn render_window_identity_settings(&mut self, ui: &mut egui::Ui) {
+        let (mut window_label, mut icon_tint) = {
+            let state = self.state.lock().unwrap();
+            (state.window_label.clone(), state.icon_tint)
+        };
+        let mut changed = false;
+        
+        ui.label(t!("window-name-shown-in-the-title"));
+        let response = ui.add(egui::TextEdit::singleline(&mut window_label).hint_text(t!("window-name-hint")));
+        changed |= response.lost_focus();
+        
+        ui.horizontal(|ui| {
+            let mut tint_enabled = icon_tint.is_some();
+            if ui.checkbox(&mut tint_enabled, t!("tint-taskbar-icon")).changed() {
+                icon_tint = tint_enabled.then_some([0, 150, 255]);
+                changed = true;
+            }
+            if let Some(tint) = icon_tint.as_mut() {
+                changed |= ui.color_edit_button_srgb(tint).changed();
+            }
+        });
+        
+        let mut desktop_notifications = self.state.lock().unwrap().desktop_notifications;
+        changed |= ui.checkbox(&mut desktop_notifications, t!("desktop-notification-when-a-transfer-finishes")).changed();
+        if desktop_notifications {
+            changed |= self.render_notification_sinks(ui);
+        }
+        
+        let mut prevent_sleep = self.state.lock().unwrap().prevent_sleep;
+        changed |= ui.checkbox(&mut prevent_sleep, t!("prevent-sleep-during-transfers"))
+            .on_hover_text(t!("prevent-sleep-during-transfers-tooltip"))
+            .changed();
+        
+        let mut state = self.state.lock().unwrap();
+        state.window_label = window_label;
+        state.icon_tint = icon_tint;
+        state.desktop_notifications = desktop_notifications;
+        state.prevent_sleep = prevent_sleep;
+        drop(state);
+        if changed {
+            self.save_preferences();
+        }
+    }
+
+    /// Where each kind of notification goes. Returns whether anything changed.
+    fn render_notification_sinks(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut sinks = self.state.lock().unwrap().notification_sinks.clone();
+        let mut changed = false;
+        ui.indent("notification_sinks", |ui| {
+            egui::Grid::new("notification_sinks_grid").num_columns(2).show(ui, |ui| {
+                for kind in notifications::NoticeKind::ALL {
+                    ui.label(Self::notice_kind_label(kind));
+                    let sink = sinks.sink_mut(kind);
+                    egui::ComboBox::from_id_salt(("notification_sink", kind as usize))
+                        .selected_text(Self::sink_label(*sink))
+                        .show_ui(ui, |ui| {
+                            for choice in notifications::SinkChoice::ALL {
+                                changed |= ui.selectable_value(sink, choice, Self::sink_label(choice)).changed();
+                            }
+                        });
+                    ui.end_row();
+                }
+            });
+            if notifications::NoticeKind::ALL.iter().any(|kind| sinks.sink(*kind) == notifications::SinkChoice::Webhook) {
+                ui.horizontal(|ui| {
+                    ui.label(t!("notification-webhook-url"));
+                    changed |= ui.add(egui::TextEdit::singleline(&mut sinks.webhook_url).hint_text("https://").desired_width(280.0))
+                        .on_hover_text(t!("notification-webhook-url-tooltip"))
+                        .lost_focus();
+                });
+            }
+        });
+        self.state.lock().unwrap().notification_sinks = sinks;
+        changed
+    }
+
+    fn notice_kind_label(kind: notifications::NoticeKind) -> String {
+        match kind {
+            notifications::NoticeKind::UploadDone => t!("notice-upload-done"),
+            notifications::NoticeKind::UploadFailed => t!("notice-upload-failed"),
+            notifications::NoticeKind::DownloadDone => t!("notice-download-done"),
+            notifications::NoticeKind::DownloadFailed => t!("notice-download-failed"),
+            notifications::NoticeKind::QuotaWarning => t!("notice-quota-warning"),
+        }
+    }
+
+    fn sink_label(sink: notifications::SinkChoice) -> String {
+        match sink {
+            notifications::SinkChoice::Off => t!("sink-off"),
+            notifications::SinkChoice::Desktop => t!("sink-desktop"),
+            notifications::SinkChoice::Toast => t!("sink-toast"),
+            notifications::SinkChoice::Webhook => t!("sink-webhook"),
+            notifications::SinkChoice::Log => t!("sink-log"),
+        }
+    }
+
+    /// Delete files or a list, after asking when that's turned on
+    fn request_delete(&mut self, deletion: undo::Deletion) {
+        if self.state.lock().unwrap().confirm_deletes {
+            self.delete_confirm_dont_ask = false;
+            self.delete_confirm = Some(deletion);
+        } else {
+            self.queue_delete(deletion);
+        }
+    }
+
+    /// What is about to be deleted, with sizes and views so it isn't a surprise
+    fn render_delete_confirm(&mut self, ctx: &egui::Context) {
+        let Some(deletion) = self.delete_confirm.clone() else {
+            return;
+        };
+        let mut confirmed = false;
+        let mut cancelled = false;
+        egui::Window::new(t!("delete-confirm-title"))
+            .resizable(false)
+            .collapsible(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                match &deletion {
+                    undo::Deletion::Files(ids) => {
+                        let files: Vec<FileInfo> = {
+                            let state = self.state.lock().unwrap();
+                            ids.iter().filter_map(|id| state.file_list.iter().find(|f| &f.id == id).cloned()).collect()
+                        };
+                        let size: u64 = files.iter().map(|f| f.size).sum();
+                        let views: u64 = files.iter().map(|f| f.views).sum();
+                        ui.label(t!("delete-confirm-files", count = ids.len(), size = self.format_file_size_bytes(size), views = views));
+                        egui::ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+                            egui::Grid::new("delete_confirm_files").num_columns(3).striped(true).show(ui, |ui| {
+                                for file in &files {
+                                    ui.label(format!("📄 {}", file.name));
+                                    ui.label(self.format_file_size_bytes(file.size));
+                                    ui.label(t!("delete-confirm-views", views = file.views));
+                                    ui.end_row();
+                                }
+                            });
+                        });
+                    }
+                    undo::Deletion::List(id) => {
+                        let list = self.lists.lock().unwrap().iter().find(|l| &l.id == id).cloned();
+                        let (title, count) = list.map_or_else(|| (id.clone(), 0), |l| (l.title, l.file_count));
+                        ui.label(t!("delete-confirm-list", title = title.as_str(), count = count));
+                    }
+                }
+                ui.label(t!("delete-confirm-permanent"));
+                ui.checkbox(&mut self.delete_confirm_dont_ask, t!("delete-confirm-dont-ask"));
+                ui.horizontal(|ui| {
+                    confirmed = ui.button(t!("delete-button")).clicked();
+                    cancelled = ui.button(t!("cancel")).clicked();
+                });
+            });
+        if confirmed {
+            self.delete_confirm = None;
+            if self.delete_confirm_dont_ask {
+                self.state.lock().unwrap().confirm_deletes = false;
+                self.save_preferences();
+            }
+            self.queue_delete(deletion);
+        } else if cancelled {
+            self.delete_confirm = None;
+        }
+    }
+
+    /// Delete files or a list once the Undo window set in settings has passed
+    fn queue_delete(&mut self, deletion: undo::Deletion) {
+        // Kept selected until then, so cancelling the confirmation loses nothing
+        if let undo::Deletion::Files(ids) = &deletion {
+            for id in ids {
+                self.selected_files.remove(id);
+            }
+        }
+        let delay = Duration::from_secs(self.state.lock().unwrap().delete_undo_secs);
+        if delay.is_zero() {
+            self.run_deletion(deletion);
+            return;
+        }
+        let label = match &deletion {
+            undo::Deletion::Files(ids) if ids.len() == 1 => {
+                let state = self.state.lock().unwrap();
+                state.file_list.iter().find(|f| f.id == ids[0]).map_or_else(|| ids[0].clone(), |f| f.name.clone())
+            }
+            undo::Deletion::Files(ids) => t!("undo-files", count = ids.len()),
+            undo::Deletion::List(id) => {
+                let lists = self.lists.lock().unwrap();
+                lists.iter().find(|l| &l.id == id).map_or_else(|| id.clone(), |l| l.title.clone())
+            }
+        };
+        self.pending_deletes.queue(deletion, label, delay, Instant::now());
+    }
+
+    fn run_due_deletes(&mut self, now: Instant) {
+        for deletion in self.pending_deletes.take_due(now) {
+            self.run_deletion(deletion);
+        }
+    }
+
+    fn run_deletion(&mut self, deletion: undo::Deletion) {
+        match deletion {
+            undo::Deletion::Files(ids) if ids.len() == 1 => self.delete_file(&ids[0]),
+            undo::Deletion::Files(ids) => self.start_batch_delete(ids, true),
+            undo::Deletion::List(id) => self.delete_list(&id),
+        }
+    }
+
+    /// Deletions waiting to be sent, bottom center with a countdown and Undo
+    fn render_undo_toasts(&mut self, ctx: &egui::Context) {
+        if self.pending_deletes.pending().is_empty() {
+            return;
+        }
+        let now = Instant::now();
+        let mut undone = None;
+        egui::Area::new(egui::Id::new("undo_toasts"))
+            .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -36.0))
+            .show(ctx, |ui| {
+                for pending in self.pending_deletes.pending() {
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            let seconds = pending.due.saturating_duration_since(now).as_secs_f32().ceil() as u64;
+                            ui.label(t!("undo-deleting", name = pending.label.as_str(), seconds = seconds));
+                            if ui.button(t!("undo-button")).clicked() {
+                                undone = Some(pending.id);
+                            }
+                        });
+                    });
+                }
+            });
+        if let Some(id) = undone {
+            self.pending_deletes.undo(id);
+        }
+        // The countdown ticks, and what's due is sent without waiting for input
+        ctx.request_repaint_after(Duration::from_millis(250));
+    }
+
+    /// Notifications sent to the window, stacked in the top right corner for a few seconds
+    fn render_toasts(&self, ctx: &egui::Context) {
+        const SHOWN_FOR: Duration = Duration::from_secs(6);
+        let toasts = self.notifier.toasts.current(SHOWN_FOR);
+        let Some(oldest) = toasts.iter().map(|(_, arrived)| *arrived).min() else {
+            return;
+        };
+        let mut dismissed = None;
+        egui::Area::new(egui::Id::new("toasts"))
+            .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-12.0, 48.0))
+            .show(ctx, |ui| {
+                for (index, (notice, _)) in toasts.iter().enumerate() {
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.set_max_width(320.0);
+                        let response = ui.vertical(|ui| {
+                            ui.strong(&notice.summary);
+                            ui.add(egui::Label::new(&notice.body).wrap());
+                        }).response.interact(egui::Sense::click());
+                        if response.on_hover_text(t!("toast-dismiss")).clicked() {
+                            dismissed = Some(index);
+                        }
+                    });
+                }
+            });
+        if let Some(index) = dismissed {
+            self.notifier.toasts.dismiss(index);
+        }
+        ctx.request_repaint_after(SHOWN_FOR.saturating_sub(oldest.elapsed()));
+    }
+
+    fn about_tab(&mut self, ui: &mut egui::Ui) {
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            // Display the app icon at 48x48 size, switching based on theme
+            let dark_mode = self.state.lock().unwrap().dark_mode;
+            if let Some(icon_data) = icon_data_from_png(dark_mode) {
+                let texture_id = ui.ctx().load_texture(
+                    "app_icon",
+                    egui::ColorImage::from_rgba_unmultiplied(
+                        [icon_data.width as usize, icon_data.height as usize],
+                        &icon_data.rgba,
+                    ),
+                    Default::default(),
+                );
+                ui.add(egui::Image::new((texture_id.id(), egui::Vec2::new(48.0, 48.0))));
+            }
+            
+            ui.label("PixelDrain Client");
+            ui.label("Copyright (c) 2025 Genxster1998");
+            ui.label(t!("a-modern-unofficial-desktop-client-for"));
+            ui.label(t!("built-with-rust-and-egui"));
+            ui.label(t!("about-version", version = "0.1.2"));
+            if ui.link("🐙 GitHub: https://www.github.com/Genxster1998/Pixeldrain-Rust").clicked() {
+                let _ = webbrowser::open("https://www.github.com/Genxster1998/Pixeldrain-Rust");
+            }
+            self.render_update_banner(ui);
+            
+            ui.separator();
+            
+            ui.label(t!("about-features"));
+            ui.label(t!("upload-files-and-folders-with-progress"));
+            ui.label(t!("download-files-from-pixeldrain-urls-no"));
+            ui.label(t!("copy-shareable-links-to-clipboard"));
+            ui.label(t!("manage-your-uploaded-files"));
+            ui.label(t!("configure-api-key-and-settings"));
+            ui.label(t!("environment-variable-support-pixeldrain-api-key"));
+            
+            ui.separator();
+            
+            if ui.link("PixelDrain: https://pixeldrain.com").clicked() {
+                let _ = webbrowser::open("https://pixeldrain.com");
+            }
+            if ui.link("API Documentation: https://pixeldrain.com/api").clicked() {
+                let _ = webbrowser::open("https://pixeldrain.com/api");
+            }
+            if ui.link("Official Go Implementation: https://github.com/Fornaxian/pixeldrain_api_client").clicked() {
+                let _ = webbrowser::open("https://github.com/Fornaxian/pixeldrain_api_client");
+            }
+            if ui.link("Based on go-pd: https://github.com/ManuelReschke/go-pd").clicked() {
+                let _ = webbrowser::open("https://github.com/ManuelReschke/go-pd");
+            }
+            if ui.link("Based on go-pd: https://github.com/jkawamoto/go-pixeldrain").clicked() {
+                let _ = webbrowser::open("https://github.com/jkawamoto/go-pixeldrain");
+            }
+        });
+    }
+
+
+
+    /// Newer release found at startup, with its page and release notes
+    fn render_update_banner(&self, ui: &mut egui::Ui) {
+        let Some(update) = self.available_update.lock().unwrap().clone() else {
+            return;
+        };
+        egui::Frame::group(ui.style()).show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(t!("update-available", version = update.version.as_str(), current = env!("CARGO_PKG_VERSION")));
+                if ui.link(t!("update-download")).on_hover_text(update.url.as_str()).clicked() {
+                    let _ = webbrowser::open(&update.url);
+                }
+            });
+            if !update.changelog.is_empty() {
+                ui.collapsing(t!("update-changelog"), |ui| {
+                    egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                        ui.label(update.changelog.as_str());
+                    });
+                });
+            }
+        });
+    }
+
+    /// Look for a newer release in the background, turned on in Settings
+    fn check_for_updates(&self, ctx: egui::Context) {
+        let available_update = self.available_update.clone();
+        self.workers.spawn(move || {
+            match updates::check(env!("CARGO_PKG_VERSION")) {
+                Ok(update) => {
+                    if let Some(update) = &update {
+                        debug!("Version {} is available", update.version);
+                    }
+                    *available_update.lock().unwrap() = update;
+                    ctx.request_repaint();
+                }
+                Err(e) => warn!("Failed to check for updates: {}", e),
+            }
+        });
+    }
+
+    /// QR code of `qr_link`, for scanning a share link onto a phone
+    fn render_qr_window(&mut self, ctx: &egui::Context) {
+        let Some(link) = self.qr_link.clone() else {
+            return;
+        };
+        if self.qr_texture.as_ref().is_none_or(|(texture_link, _)| *texture_link != link) {
+            self.qr_texture = match qr::image(&link) {
+                Ok(image) => Some((link.clone(), ctx.load_texture("qr_code", image, egui::TextureOptions::NEAREST))),
+                Err(e) => {
+                    self.state.lock().unwrap().last_error = Some(format!("Failed to create a QR code: {}", e));
+                    self.qr_link = None;
+                    return;
+                }
+            };
+        }
+        let mut open = true;
+        egui::Window::new(t!("qr-window-title"))
+            .open(&mut open)
+            .resizable(false)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                if let Some((_, texture)) = &self.qr_texture {
+                    ui.add(egui::Image::from_texture(texture).fit_to_exact_size(egui::Vec2::splat(240.0)));
+                }
+                ui.horizontal(|ui| {
+                    ui.hyperlink_to(&link, &link);
+                    if ui.small_button("📋").on_hover_text(t!("copy")).clicked() {
+                        let _ = Clipboard::new().and_then(|mut c| c.set_text(link.clone()));
+                    }
+                });
+            });
+        if !open {
+            self.qr_link = None;
+        }
+    }
+
+    fn render_error_popup(&mut self, ctx: &egui::Context) {
+        let Some(report) = self.state.lock().unwrap().error_report.clone() else {
+            return;
+        };
+        let mut open = true;
+        let mut dismissed = false;
+        egui::Window::new(t!("error-window-title"))
+            .open(&mut open)
+            .resizable(false)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.label(format!("❌ {}", report.context));
+                if let Some(hint) = report.hint() {
+                    ui.label(hint);
+                }
+                egui::CollapsingHeader::new(t!("technical-details")).id_salt("error_details").show(ui, |ui| {
+                    egui::Grid::new("error_details_grid").num_columns(2).show(ui, |ui| {
+                        ui.label(t!("error-status"));
+                        ui.label(report.status.map(|s| s.to_string()).unwrap_or_else(|| "-".to_string()));
+                        ui.end_row();
+                        ui.label(t!("error-endpoint"));
+                        ui.label(report.endpoint.as_deref().unwrap_or("-"));
+                        ui.end_row();
+                        ui.label(t!("error-request-id"));
+                        ui.label(report.request_id.as_deref().unwrap_or("-"));
+                        ui.end_row();
+                        ui.label(t!("error-time"));
+                        ui.label(report.timestamp.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string());
+                        ui.end_row();
+                    });
+                    ui.add(egui::Label::new(egui::RichText::new(&report.message).monospace()).wrap());
+                });
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    if ui.button(t!("copy-details")).on_hover_text(t!("copy-a-snippet-for-a-bug")).clicked() {
+                        let _ = Clipboard::new().and_then(|mut c| c.set_text(report.bug_report()));
+                    }
+                    if ui.button(t!("ok")).clicked() {
+                        dismissed = true;
+                    }
+                });
+            });
+        if !open || dismissed {
+            self.state.lock().unwrap().error_report = None;
+        }
+    }
+
+    /// Act on a pixeldrain:// link. Downloads start right away, uploads select the
+    /// files and wait for confirmation so a link can't send local files on its own.
+    fn handle_deep_link(&mut self, ctx: &egui::Context, link: &str) {
+        ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+        match DeepLink::parse(link) {
+            Ok(DeepLink::Download(url)) => {
+                self.tab = Tab::Download;
+                self.download_url = url;
+                if !*self.download_thread_running.lock().unwrap() {
+                    self.start_download();
+                }
+            }
+            Ok(DeepLink::Upload(paths)) => {
+                self.tab = Tab::Upload;
+                self.upload_custom_filename.clear();
+                self.upload_file_names.clear();
+                self.upload_directory_name.clear();
+                self.upload_file = None;
+                self.upload_files.clear();
+                self.upload_directory = None;
+                if paths.len() == 1 && paths[0].is_dir() {
+                    self.upload_directory = paths.into_iter().next();
+                } else if paths.len() == 1 {
+                    self.upload_file = paths.into_iter().next();
+                } else {
+                    self.upload_files = paths;
+                }
+                *self.upload_progress.lock().unwrap() = 0.0;
+                self.link_upload_pending = true;
+            }
+            Err(e) => self.state.lock().unwrap().last_error = Some(e),
+        }
+    }
+
+    fn render_link_upload_confirm(&mut self, ctx: &egui::Context) {
+        let paths: Vec<PathBuf> = self.upload_file.iter()
+            .chain(self.upload_files.iter())
+            .chain(self.upload_directory.iter())
+            .cloned()
+            .collect();
+        let mut confirmed = false;
+        let mut cancelled = false;
+        egui::Window::new(t!("link-upload-title"))
+            .resizable(false)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.label(t!("another-application-asked-to-upload"));
+                for path in &paths {
+                    ui.label(format!("📄 {}", path.display()));
+                }
+                ui.horizontal(|ui| {
+                    let is_running = *self.upload_thread_running.lock().unwrap();
+                    if ui.add_enabled(!is_running, egui::Button::new(t!("upload-button"))).clicked() {
+                        confirmed = true;
+                    }
+                    if ui.button(t!("cancel")).clicked() {
+                        cancelled = true;
+                    }
+                });
+            });
+        if confirmed {
+            self.link_upload_pending = false;
+            if let Some(path) = self.upload_file.clone() {
+                self.start_upload(path);
+            } else if let Some(dir) = self.upload_directory.clone() {
+                self.start_directory_upload(dir, ctx.clone());
+            } else if !self.upload_files.is_empty() {
+                self.start_multiple_upload(self.upload_files.clone(), ctx.clone());
+            }
+        } else if cancelled {
+            self.link_upload_pending = false;
+        }
+    }
+
+    fn render_share_dialog(&mut self, ctx: &egui::Context) {
+        let mut open = self.share_dialog_open;
+        let loading = *self.share_loading.lock().unwrap();
+        let result = self.share_result.lock().unwrap().clone();
+        let mut share = false;
+        let mut unshare = false;
+        
+        egui::Window::new(t!("share-window-title"))
+            .open(&mut open)
+            .resizable(false)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.label(t!("lists-have-no-access-control-so"));
+                ui.add_space(5.0);
+                egui::Grid::new("share_dialog_grid").num_columns(2).show(ui, |ui| {
+                    ui.label(t!("share-path"));
+                    ui.add(egui::TextEdit::singleline(&mut self.share_path).hint_text("/me/folder"));
+                    ui.end_row();
+                    ui.label(t!("password"));
+                    ui.add(egui::TextEdit::singleline(&mut self.share_password)
+                        .password(true)
+                        .hint_text(t!("leave-empty-for-a-public-link")));
+                    ui.end_row();
+                });
+                ui.add_space(5.0);
+                
+                if loading {
+                    self.render_loading_spinner(ui, &t!("updating-share"));
+                } else {
+                    ui.horizontal(|ui| {
+                        let has_path = !self.share_path.trim().is_empty();
+                        if ui.add_enabled(has_path, egui::Button::new(t!("share-button"))).clicked() {
+                            share = true;
+                        }
+                        if ui.add_enabled(has_path, egui::Button::new(t!("stop-sharing"))).clicked() {
+                            unshare = true;
+                        }
+                    });
+                }
+                
+                match &result {
+                    Some(Ok(link)) if !link.is_empty() => {
+                        ui.separator();
+                        ui.horizontal(|ui| {
+                            ui.hyperlink_to(link, link);
+                            if ui.button(t!("copy")).clicked() {
+                                let _ = Clipboard::new().and_then(|mut c| c.set_text(link.clone()));
+                            }
+                        });
+                    }
+                    Some(Ok(_)) => {
+                        ui.separator();
+                        ui.label(t!("sharing-disabled"));
+                    }
+                    Some(Err(err)) => {
+                        ui.separator();
+                        ui.colored_label(egui::Color32::RED, err);
+                    }
+                    None => {}
+                }
+            });
+        self.share_dialog_open = open;
+        
+        if share || unshare {
+            self.start_share_update(ctx.clone(), share);
+        }
+    }
+
+    fn start_share_update(&mut self, ctx: egui::Context, share: bool) {
+        let Some(api_key) = self.get_api_key() else {
+            *self.share_result.lock().unwrap() = Some(Err("API key required to share files".to_string()));
+            return;
+        };
+        let path = self.share_path.trim().to_string();
+        let password = self.share_password.clone();
+        let loading = self.share_loading.clone();
+        let result = self.share_result.clone();
+        
+        *loading.lock().unwrap() = true;
+        *result.lock().unwrap() = None;
+        
+        let api_factory = self.api_factory.clone();
+        self.workers.spawn(move || {
+            let config = PixelDrainConfig::default().with_api_key(api_key);
+            let outcome = api_factory(config).and_then(|client| {
+                if share {
+                    let password = Some(password.as_str()).filter(|p| !p.is_empty());
+                    client.share_filesystem_path(&path, password)
+                } else {
+                    client.unshare_filesystem_path(&path).map(|_| String::new())
+                }
+            });
+            *result.lock().unwrap() = Some(outcome.map_err(|e| format!("Failed to update share: {}", e)));
+            *loading.lock().unwrap() = false;
+            ctx.request_repaint();
+        });
+    }
+
+    /// Pick a list the next uploads are added to, from the lists loaded on the Lists tab
+    fn render_upload_target_list(&mut self, ui: &mut egui::Ui) {
+        let lists = self.lists.lock().unwrap().clone();
+        let loading = *self.lists_loading.lock().unwrap();
+        let selected = match &self.upload_target_list {
+            Some(id) => lists.iter().find(|l| &l.id == id).map(|l| l.title.clone()).unwrap_or_else(|| id.clone()),
+            None => t!("upload-no-list"),
+        };
+        let mut refresh = false;
+        ui.add_enabled_ui(!self.upload_anonymously, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(t!("upload-add-to-list"));
+                egui::ComboBox::from_id_salt("upload_target_list")
+                    .selected_text(selected)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.upload_target_list, None, t!("upload-no-list"));
+                        for list in &lists {
+                            ui.selectable_value(&mut self.upload_target_list, Some(list.id.clone()), &list.title);
+                        }
+                    });
+                refresh = ui.add_enabled(!loading, egui::Button::new("⟳")).on_hover_text(t!("upload-reload-lists")).clicked();
+            });
+        });
+        if refresh {
+            self.refresh_lists();
+        }
+    }
+
+    /// Offer to put the files of the last multi-file upload in a new or existing list
+    fn render_batch_list_offer(&mut self, ui: &mut egui::Ui) {
+        let result = self.batch_list_result.lock().unwrap().clone();
+        match &result {
+            Some(Ok(url)) => {
+                ui.horizontal(|ui| {
+                    ui.label(t!("batch-list-done"));
+                    ui.hyperlink(url);
+                });
+            }
+            Some(Err(e)) => {
+                ui.colored_label(egui::Color32::RED, e);
+            }
+            None => {}
+        }
+        let count = self.last_batch.lock().unwrap().len();
+        if count == 0 {
+            return;
+        }
+        if *self.batch_list_running.lock().unwrap() {
+            self.render_loading_spinner(ui, &t!("batch-list-running"));
+            return;
+        }
+        let lists = self.lists.lock().unwrap().clone();
+        let mut start = false;
+        let mut dismiss = false;
+        egui::Frame::group(ui.style()).show(ui, |ui| {
+            ui.label(t!("batch-list-offer", count = count));
+            ui.horizontal(|ui| {
+                let selected = match &self.batch_list_target {
+                    Some(id) => lists.iter().find(|l| &l.id == id).map(|l| l.title.clone()).unwrap_or_else(|| id.clone()),
+                    None => t!("batch-list-new"),
+                };
+                egui::ComboBox::from_id_salt("batch_list_target")
+                    .selected_text(selected)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.batch_list_target, None, t!("batch-list-new"));
+                        for list in lists.iter().filter(|list| list.can_edit) {
+                            ui.selectable_value(&mut self.batch_list_target, Some(list.id.clone()), &list.title);
+                        }
+                    });
+                if self.batch_list_target.is_none() {
+                    ui.add(egui::TextEdit::singleline(&mut self.batch_list_title)
+                        .hint_text(t!("batch-list-title-hint"))
+                        .desired_width(180.0));
+                }
+                let ready = self.batch_list_target.is_some() || !self.batch_list_title.trim().is_empty();
+                let label = if self.batch_list_target.is_some() { t!("batch-list-add") } else { t!("batch-list-create") };
+                start = ui.add_enabled(ready, egui::Button::new(label)).clicked();
+                dismiss = ui.small_button("✖").on_hover_text(t!("batch-list-dismiss")).clicked();
+            });
+        });
+        if start {
+            self.list_from_batch();
+        } else if dismiss {
+            self.last_batch.lock().unwrap().clear();
+        }
+    }
+
+    /// Put the files of the last multi-file upload in the chosen list, or a new
+    /// one with the typed title, and copy the list's link
+    fn list_from_batch(&mut self) {
+        let files = self.last_batch.lock().unwrap().clone();
+        let target = self.batch_list_target.clone();
+        let title = self.batch_list_title.trim().to_string();
+        let client = self.make_api_client();
+        let lists = self.lists.clone();
+        let last_batch = self.last_batch.clone();
+        let running = self.batch_list_running.clone();
+        let result = self.batch_list_result.clone();
+        *running.lock().unwrap() = true;
+        self.workers.spawn(move || {
+            let ids: Vec<String> = files.iter().map(|(id, _)| id.clone()).collect();
+            let outcome = match &target {
+                Some(list_id) => Self::add_uploads_to_list(client.as_ref(), &lists, Some(list_id), &ids)
+                    .map(|()| list_id.clone())
+                    .map_err(|e| format!("Failed to add the uploads to the list: {}", e)),
+                None => client
+                    .create_list(&pixeldrain_api::CreateListRequest {
+                        title,
+                        files: ids.into_iter().map(|id| pixeldrain_api::ListFile { id, description: String::new() }).collect(),
+                    })
+                    .map(|list| {
+                        let id = list.id.clone();
+                        lists.lock().unwrap().push(list);
+                        id
+                    })
+                    .map_err(|e| format!("Failed to create the list: {}", e)),
+            };
+            let outcome = outcome.map(|list_id| {
+                let url = format!("{}/l/{}", client.base_url(), list_id);
+                let _ = Clipboard::new().and_then(|mut c| c.set_text(url.clone()));
+                last_batch.lock().unwrap().clear();
+                url
+            });
+            *result.lock().unwrap() = Some(outcome);
+            *running.lock().unwrap() = false;
+        });
+    }
+
+    /// List to add the next upload to. Anonymous uploads aren't part of the account, those aren't added.
+    /// Upload the image on the clipboard. The shortcut leaves the clipboard alone
+    /// when it holds something else, it may have been meant for a text field.
+    fn paste_image(&mut self, from_shortcut: bool) {
+        match paste::save_clipboard_image() {
+            Ok(Some((path, name))) => {
+                self.upload_file = Some(path.clone());
+                self.upload_files.clear();
+                self.upload_directory = None;
+                self.upload_custom_filename = name;
+                self.upload_directory_name.clear();
+                self.state.lock().unwrap().last_error = None;
+                self.start_upload(path);
+            }
+            Ok(None) if from_shortcut => {}
+            Ok(None) => self.state.lock().unwrap().last_error = Some(t!("paste-image-none")),
+            Err(e) => self.state.lock().unwrap().last_error = Some(format!("Failed to read the clipboard: {}", e)),
+        }
+    }
+
+    fn render_upload_skipped(&self, ui: &mut egui::Ui) {
+        let skipped = self.upload_skipped.lock().unwrap();
+        if skipped.is_empty() {
+            return;
+        }
+        egui::CollapsingHeader::new(egui::RichText::new(t!("upload-skipped", count = skipped.len())).color(egui::Color32::ORANGE))
+            .id_salt("upload_skipped")
+            .show(ui, |ui| {
+                egui::ScrollArea::vertical().max_height(100.0).id_salt("upload_skipped_scroll").show(ui, |ui| {
+                    for file in skipped.iter() {
+                        ui.horizontal_wrapped(|ui| {
+                            ui.add(egui::Label::new(file.path.display().to_string()).wrap());
+                            ui.weak(Self::problem_label(&file.problem));
+                        });
+                    }
+                });
+            });
+    }
+
+    /// Files the account has already, with their links and a way to upload them anyway
+    /// Offer to split a file that's bigger than the account may upload
+    fn render_split_offer(&mut self, ui: &mut egui::Ui) {
+        let Some((path, limit)) = self.upload_split_offer.clone() else {
+            return;
+        };
+        let size = path.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+        ui.colored_label(egui::Color32::ORANGE, t!(
+            "upload-too-large",
+            name = path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+            limit = self.format_file_size_bytes(limit),
+            parts = split::part_count(size, limit),
+        ));
+        let running = *self.upload_thread_running.lock().unwrap();
+        ui.horizontal(|ui| {
+            if ui.add_enabled(!running, egui::Button::new(t!("upload-split"))).clicked() {
+                self.upload_split_offer = None;
+                self.start_split_upload(path.clone(), limit);
+            }
+            if ui.button(t!("cancel")).clicked() {
+                self.upload_split_offer = None;
+            }
+        });
+    }
+
+    /// Why uploading files of these sizes would go over the account's file size
+    /// limit or storage, going by the last user info. Anonymous uploads don't count.
+    fn quota_warning(&self, sizes: &[u64]) -> Option<String> {
+        if self.upload_anonymously {
+            return None;
+        }
+        let state = self.state.lock().unwrap();
+        let user = state.user_info.as_ref()?;
+        let limit = user.subscription.file_size_limit;
+        let too_big = sizes.iter().filter(|size| limit > 0 && **size > limit).count();
+        if too_big > 0 {
+            return Some(t!("quota-warning-file-size", count = too_big, limit = self.format_file_size_bytes(limit)));
+        }
+        let total: u64 = sizes.iter().sum();
+        let remaining = u64::try_from(user.subscription.storage_space).ok()?.saturating_sub(user.storage_space_used);
+        (total > remaining).then(|| t!(
+            "quota-warning-storage",
+            size = self.format_file_size_bytes(total),
+            remaining = self.format_file_size_bytes(remaining)
+        ))
+    }
+
+    /// Hold `upload` back with a warning when it would go over the quota, unless
+    /// the user already chose to go ahead. `sizes` is only worked out when the quota is known.
+    fn hold_for_quota(&mut self, sizes: impl FnOnce() -> Vec<u64>, upload: HeldUpload) -> bool {
+        self.upload_quota_warning = None;
+        if std::mem::take(&mut self.upload_quota_confirmed) || self.state.lock().unwrap().user_info.is_none() {
+            return false;
+        }
+        match self.quota_warning(&sizes()) {
+            Some(warning) => {
+                self.notify(notifications::Notice {
+                    kind: notifications::NoticeKind::QuotaWarning,
+                    summary: t!("quota-warning-notice"),
+                    body: warning.clone(),
+                });
+                self.upload_quota_warning = Some((warning, upload));
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn render_quota_warning(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        let Some((warning, upload)) = self.upload_quota_warning.clone() else {
+            return;
+        };
+        ui.colored_label(egui::Color32::ORANGE, format!("⚠ {}", warning));
+        let running = *self.upload_thread_running.lock().unwrap();
+        ui.horizontal(|ui| {
+            if ui.add_enabled(!running, egui::Button::new(t!("quota-upload-anyway"))).clicked() {
+                self.upload_quota_confirmed = true;
+                match upload {
+                    HeldUpload::File(path) => self.start_upload(path),
+                    HeldUpload::Files(paths) => self.start_multiple_upload(paths, ctx.clone()),
+                    HeldUpload::Directory(path) => self.start_directory_upload(path, ctx.clone()),
+                }
+            }
+            if ui.button(t!("cancel")).clicked() {
+                self.upload_quota_warning = None;
+                self.upload_bundle = None;
+            }
+        });
+    }
+
+    /// Ask for a new API key once the server refused the current one mid-session.
+    /// Uploads it stopped are paused until then and started again after.
+    fn render_reauth_prompt(&mut self, ctx: &egui::Context) {
+        let (rejected, paused) = {
+            let state = self.state.lock().unwrap();
+            (state.api_key_rejected, state.paused_uploads.len())
+        };
+        if !rejected {
+            self.reauth_dismissed = false;
+            if paused > 0 {
+                self.resume_paused_uploads(ctx);
+            }
+            return;
+        }
+        if self.reauth_dismissed {
+            return;
+        }
+        let mut save = false;
+        egui::Window::new(t!("reauth-title"))
+            .resizable(false)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.label(t!("reauth-message"));
+                if paused > 0 {
+                    ui.colored_label(egui::Color32::ORANGE, t!("reauth-paused", count = paused));
+                }
+                ui.horizontal(|ui| {
+                    ui.label(t!("reauth-new-key"));
+                    let response = ui.add(egui::TextEdit::singleline(&mut self.reauth_key).password(true).desired_width(260.0));
+                    save = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                });
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(!self.reauth_key.trim().is_empty(), egui::Button::new(t!("reauth-save"))).clicked() {
+                        save = true;
+                    }
+                    if ui.button(t!("reauth-log-in")).on_hover_text(t!("reauth-log-in-tooltip")).clicked() {
+                        self.tab = Tab::Settings;
+                        self.reauth_dismissed = true;
+                    }
+                    if ui.button(t!("reauth-not-now")).clicked() {
+                        self.reauth_dismissed = true;
+                    }
+                });
+            });
+        if save && !self.reauth_key.trim().is_empty() {
+            let api_key = std::mem::take(&mut self.reauth_key).trim().to_string();
+            let download_location = self.state.lock().unwrap().download_location.clone();
+            self.settings_api_key = api_key.clone();
+            self.save_settings(api_key, download_location);
+            self.fetch_user_info();
+        }
+    }
+
+    /// Start the uploads paused by a refused key again, when nothing is uploading
+    fn resume_paused_uploads(&mut self, ctx: &egui::Context) {
+        if *self.upload_thread_running.lock().unwrap() {
+            return;
+        }
+        let paths = std::mem::take(&mut self.state.lock().unwrap().paused_uploads);
+        if !paths.is_empty() {
+            self.start_multiple_upload(paths, ctx.clone());
+        }
+    }
+
+    /// Transfers the app was in the middle of when it closed last time, to resume or forget
+    fn render_interrupted_offer(&mut self, ctx: &egui::Context) {
+        let mut answer = None;
+        egui::Window::new(t!("resume-title"))
+            .resizable(false)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.label(t!("resume-message", count = self.interrupted.len()));
+                egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                    for transfer in &self.interrupted {
+                        match transfer {
+                            resume::Transfer::Upload { path } => {
+                                ui.label(t!("resume-upload", name = path.display().to_string()));
+                            }
+                            resume::Transfer::Download { path: Some(path), bytes_done, .. } if *bytes_done > 0 => {
+                                let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+                                ui.label(t!("resume-download-partial", name = name, size = self.format_file_size_bytes(*bytes_done)));
+                            }
+                            resume::Transfer::Download { url, .. } => {
+                                ui.label(t!("resume-download", url = url.as_str()));
+                            }
+                        }
+                    }
+                });
+                ui.horizontal(|ui| {
+                    if ui.button(t!("resume-button")).clicked() {
+                        answer = Some(true);
+                    }
+                    if ui.button(t!("resume-discard")).clicked() {
+                        answer = Some(false);
+                    }
+                    if ui.button(t!("resume-not-now")).on_hover_text(t!("resume-not-now-tooltip")).clicked() {
+                        self.interrupted.clear();
+                    }
+                });
+            });
+        if let Some(resume) = answer {
+            self.interrupted.clear();
+            let transfers = self.journal.take_interrupted();
+            if resume {
+                self.resuming.extend(transfers);
+            }
+        }
+    }
+
+    /// Start the interrupted transfers that were resumed, uploads all at once
+    /// and downloads one after the other, once nothing of their kind is running
+    fn start_resumed(&mut self, ctx: &egui::Context) {
+        if self.resuming.is_empty() {
+            return;
+        }
+        if !*self.upload_thread_running.lock().unwrap() {
+            let mut paths = Vec::new();
+            self.resuming.retain(|transfer| match transfer {
+                resume::Transfer::Upload { path } => {
+                    paths.push(path.clone());
+                    false
+                }
+                resume::Transfer::Download { .. } => true,
+            });
+            if !paths.is_empty() {
+                self.start_multiple_upload(paths, ctx.clone());
+            }
+        }
+        if !*self.download_thread_running.lock().unwrap()
+            && let Some(index) = self.resuming.iter().position(|transfer| matches!(transfer, resume::Transfer::Download { .. }))
+            && let resume::Transfer::Download { url, path, .. } = self.resuming.remove(index)
+        {
+            self.download_url = url;
+            self.start_download_into(path);
+        }
+    }
+
+    /// Storage and monthly transfer of the account as gauges, along the bottom of the window
+    fn render_status_bar(&self, ctx: &egui::Context) {
+        let Some(user) = self.state.lock().unwrap().user_info.clone() else {
+            return;
+        };
+        let gauge = |ui: &mut egui::Ui, label: String, used: u64, total: Option<u64>| {
+            ui.label(label);
+            match total.filter(|total| *total > 0) {
+                Some(total) => {
+                    let fraction = (used as f64 / total as f64).min(1.0) as f32;
+                    let color = match fraction {
+                        f if f >= 0.95 => egui::Color32::from_rgb(200, 60, 60),
+                        f if f >= 0.8 => egui::Color32::from_rgb(230, 150, 0),
+                        _ => egui::Color32::from_rgb(0, 120, 215),
+                    };
+                    let text = format!("{} / {}", self.format_file_size_bytes(used), self.format_file_size_bytes(total));
+                    ui.add(egui::ProgressBar::new(fraction).desired_width(160.0).fill(color).text(text));
+                }
+                None => {
+                    ui.label(t!("status-unlimited", used = self.format_file_size_bytes(used)));
+                }
+            }
+        };
+        egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                gauge(ui, t!("status-storage"), user.storage_space_used, u64::try_from(user.subscription.storage_space).ok());
+                ui.separator();
+                gauge(ui, t!("status-transfer"), user.monthly_transfer_used, Some(user.monthly_transfer_cap));
+            });
+        });
+    }
+
+    fn render_upload_duplicates(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        let duplicates = self.upload_duplicates.lock().unwrap().clone();
+        if duplicates.is_empty() {
+            return;
+        }
+        let (base_url, link_format, direct_categories) = {
+            let state = self.state.lock().unwrap();
+            let (link_format, direct_categories) = state.copied_link_formats();
+            (state.base_url.clone(), link_format, direct_categories)
+        };
+        ui.colored_label(egui::Color32::from_rgb(0, 120, 215), t!("upload-duplicates", count = duplicates.len()));
+        egui::ScrollArea::vertical().max_height(100.0).id_salt("upload_duplicates_scroll").show(ui, |ui| {
+            for (path, file) in &duplicates {
+                ui.horizontal_wrapped(|ui| {
+                    ui.label(path.file_name().unwrap_or_default().to_string_lossy());
+                    ui.label("→");
+                    ui.hyperlink_to(&file.name, format!("{}/u/{}", base_url, file.id));
+                });
+            }
+        });
+        let running = *self.upload_thread_running.lock().unwrap();
+        ui.horizontal(|ui| {
+            if ui.button(t!("upload-duplicates-copy")).clicked() {
+                let links: Vec<String> = duplicates
+                    .iter()
+                    .map(|(_, file)| link_format.for_mime_type(&file.mime_type, &direct_categories).format(&base_url, &file.id, &file.name))
+                    .collect();
+                let _ = Clipboard::new().and_then(|mut c| c.set_text(links.join("\n")));
+            }
+            if ui.add_enabled(!running, egui::Button::new(t!("upload-duplicates-anyway"))).clicked() {
+                let paths: Vec<PathBuf> = duplicates.into_iter().map(|(path, _)| path).collect();
+                self.upload_duplicates_allowed = true;
+                if let [path] = paths.as_slice() {
+                    self.start_upload(path.clone());
+                } else {
+                    self.start_multiple_upload(paths, ctx.clone());
+                }
+            }
+        });
+    }
+
+    /// Files of the account with the same content as each of `hashes`. None where
+    /// there's no such file, or for all when the account's files can't be listed.
+    fn find_in_account(client: &dyn PixelDrainApi, hashes: &[Option<String>]) -> Vec<Option<FileInfo>> {
+        let files = match client.get_user_files() {
+            Ok(response) => response.files,
+            Err(e) => {
+                warn!("Failed to list the account's files for the duplicate check: {}", e);
+                Vec::new()
+            }
+        };
+        hashes
+            .iter()
+            .map(|hash| {
+                let hash = hash.as_deref()?;
+                files.iter().find(|file| file.hash_sha256.eq_ignore_ascii_case(hash)).cloned()
+            })
+            .collect()
+    }
+
+    fn problem_label(problem: &preflight::Problem) -> String {
+        match problem {
+            preflight::Problem::Empty => t!("problem-empty"),
+            preflight::Problem::Special => t!("problem-special"),
+            preflight::Problem::Unreadable(error) => t!("problem-unreadable", error = error.as_str()),
+        }
+    }
+
+    fn upload_target_list(&self, anonymous: bool) -> Option<String> {
+        if anonymous { None } else { self.upload_target_list.clone() }
+    }
+
+    /// Append uploaded files to the end of the target list, if one was chosen
+    fn add_uploads_to_list(
+        client: &dyn PixelDrainApi,
+        lists: &Mutex<Vec<pixeldrain_api::ListInfo>>,
+        list_id: Option<&str>,
+        file_ids: &[String],
+    ) -> Result<(), pixeldrain_api::PixelDrainError> {
+        let Some(list_id) = list_id.filter(|_| !file_ids.is_empty()) else {
+            return Ok(());
+        };
+        let list = client.get_list(list_id)?;
+        let mut files: Vec<pixeldrain_api::ListFile> = list.files.into_iter()
+            .map(|f| pixeldrain_api::ListFile { id: f.file_info.id, description: f.description })
+            .collect();
+        files.extend(file_ids.iter().map(|id| pixeldrain_api::ListFile { id: id.clone(), description: String::new() }));
+        let updated = client.update_list(list_id, &pixeldrain_api::CreateListRequest { title: list.title, files })?;
+        if let Some(list) = lists.lock().unwrap().iter_mut().find(|l| l.id == list_id) {
+            *list = updated;
+        }
+        Ok(())
+    }
+
+    fn start_upload(&mut self, path: PathBuf) {
+        self.upload_skipped.lock().unwrap().clear();
+        self.upload_split_offer = None;
+        // An empty file is uploaded when it's the only one, it was picked on purpose
+        if let Some(problem @ (preflight::Problem::Special | preflight::Problem::Unreadable(_))) = preflight::check_file(&path) {
+            self.state.lock().unwrap().last_error = Some(format!("Can't upload {}: {}", path.display(), Self::problem_label(&problem)));
+            return;
+        }
+        // Too big for the account, it can be uploaded in parts instead
+        if let Some(limit) = self.upload_size_limit()
+            && path.metadata().is_ok_and(|metadata| metadata.len() > limit)
+        {
+            self.upload_split_offer = Some((path, limit));
+            return;
+        }
+        if self.hold_for_quota(|| vec![path.metadata().map(|metadata| metadata.len()).unwrap_or(0)], HeldUpload::File(path.clone())) {
+            return;
+        }
+        let encryption = match self.upload_encryption() {
+            Ok(encryption) => encryption,
+            Err(e) => {
+                self.state.lock().unwrap().last_error = Some(e);
+                return;
+            }
+        };
+        // Get API key with settings priority
+        let (api_key, anonymous) = match self.take_upload_api_key() {
+            Ok(api_key) => (api_key.clone(), api_key.is_none()),
+            Err(e) => {
+                self.state.lock().unwrap().last_error = Some(e);
+                return;
+            }
+        };
+        
+        let progress = self.upload_progress.clone();
+        let rate = self.upload_rate.clone();
+        let state = self.state.clone();
+        let thread_running = self.upload_thread_running.clone();
+        let custom_filename = self.upload_custom_filename.clone();
+        // The server has the encrypted file, there's nothing to compare with or find
+        let verify_uploads = self.state.lock().unwrap().verify_uploads && encryption.is_none();
+        let check_before = self.state.lock().unwrap().check_before_large_uploads && !anonymous;
+        let checking = self.upload_checking.clone();
+        let skip_duplicates = self.state.lock().unwrap().skip_duplicate_uploads && !anonymous && !std::mem::take(&mut self.upload_duplicates_allowed) && encryption.is_none();
+        let duplicates = self.upload_duplicates.clone();
+        duplicates.lock().unwrap().clear();
+        let target_list = self.upload_target_list(anonymous);
+        let lists = self.lists.clone();
+        let name = if custom_filename.is_empty() { path.file_name().unwrap_or_default().to_string_lossy().to_string() } else { custom_filename.clone() };
+        // Encrypted uploads always get a name of their own, with .enc added
+        let custom_filename = if encryption.is_some() { crypto::encrypted_name(&name) } else { custom_filename };
+        let transfer = self.events.start(Direction::Upload, name.clone());
+        let cancel = self.watchdog.watch(transfer.id(), watchdog::Retry::Upload(path.clone()));
+        // Reset progress at start
+        *self.upload_progress.lock().unwrap() = 0.0;
+        *rate.lock().unwrap() = None;
+        *thread_running.lock().unwrap() = true;
+        let diagnostics = self.take_diagnostics(&path.display().to_string());
+        let api_factory = self.api_factory.clone();
+        let recorded = self.journal.record(vec![resume::Transfer::Upload { path: path.clone() }]);
+        self.workers.spawn(move || {
+            // Out of the journal once the worker ends, however it ends
+            let _recorded = recorded;
+            debug!("start_upload - api_key present: {}", api_key.is_some());
+            let config = if let Some(key) = api_key {
+                debug!("Creating config with API key");
+                PixelDrainConfig::default().with_api_key(key)
+            } else {
+                debug!("Creating config without API key (anonymous)");
+                PixelDrainConfig::default()
+            };
+            let config = config.with_diagnostics(diagnostics.as_ref().map(DiagnosticsCapture::recorder)).with_cancel(cancel.clone());
+            let client = match api_factory(config) {
+                Ok(client) => client,
+                Err(e) => {
+                    let mut state = state.lock().unwrap();
+                    state.last_error = Some(format!("Failed to create client: {}", e));
+                    *thread_running.lock().unwrap() = false;
+                    return;
+                }
+            };
+            let size = path.metadata().map(|m| m.len()).unwrap_or(0);
+            if check_before && let Err(error) = Self::check_before_upload(client.as_ref(), size, &checking) {
+                state.lock().unwrap().last_error = Some(error.clone());
+                *thread_running.lock().unwrap() = false;
+                transfer.end(TransferOutcome::UploadFailed { name, error });
+                return;
+            }
+            // Hashed once for both the duplicate check and verification
+            let local_hash = if skip_duplicates {
+                pixeldrain_api::sha256_file(&path).ok()
+            } else {
+                None
+            };
+            let existing = if local_hash.is_some() {
+                Self::find_in_account(client.as_ref(), std::slice::from_ref(&local_hash)).pop().flatten()
+            } else {
+                None
+            };
+            if let Some(existing) = existing {
+                let url = format!("{}/u/{}", client.base_url(), existing.id);
+                duplicates.lock().unwrap().push((path, existing));
+                *thread_running.lock().unwrap() = false;
+                transfer.end(TransferOutcome::UploadFinished { name, url });
+                return;
+            }
+            let progress_cb = {
+                let progress = progress.clone();
+                let reporter = transfer.progress();
+                transfer.progress().sender(move |p: Progress| {
+                    *progress.lock().unwrap() = p.fraction;
+                    *rate.lock().unwrap() = Some(p);
+                    reporter.report(p.fraction);
+                })
+            };
+            let result = if let Some((passphrase, _)) = &encryption {
+                Self::upload_encrypted(client.as_ref(), &path, &custom_filename, passphrase, progress_cb)
+            } else if !custom_filename.is_empty() {
+                client.upload_file_put(&path, &custom_filename, Some(progress_cb))
+            } else {
+                client.upload_file(&path, Some(progress_cb))
+            };
+            // Cancelled from the Debug panel, which already started it again
+            if cancel.load(Ordering::Relaxed) {
+                transfer.end(TransferOutcome::UploadFailed { name, error: pixeldrain_api::PixelDrainError::Cancelled.to_string() });
+                return;
+            }
+            let verified = match &result {
+                Ok(response) if verify_uploads => Self::verify_uploaded_file(client.as_ref(), &response.id, &path, local_hash),
+                _ => None,
+            };
+            let uploaded_ids: Vec<String> = result.iter().map(|response| response.id.clone()).collect();
+            let list_result = Self::add_uploads_to_list(client.as_ref(), &lists, target_list.as_deref(), &uploaded_ids);
+            let mut state = state.lock().unwrap();
+            let event = match result {
+                Ok(response) => {
+                    let url = response.get_file_url(client.base_url());
+                    if let Some((_, key_id)) = &encryption {
+                        state.key_registry.record(&response.id, key_id);
+                        state.settings_dirty = true;
+                    }
+                    let filename = if !custom_filename.is_empty() {
+                        custom_filename.clone()
+                    } else {
+                        path.file_name().unwrap().to_string_lossy().to_string()
+                    };
+                    let entry = UploadHistoryEntry {
+                        id: response.id,
+                        url: url.clone(),
+                        filename: filename.clone(),
+                        size: path.metadata().map(|m| m.len()).unwrap_or(0),
+                        timestamp: Utc::now(),
+                        verified,
+                        error: None,
+                        anonymous,
+                    };
+                    state.record_upload(entry);
+                    // Copy to clipboard
+                    let _ = Clipboard::new().and_then(|mut c| c.set_text(url.clone()));
+                    state.last_error = if verified == Some(false) {
+                        Some(format!("Upload verification failed for {}: size or SHA-256 does not match the local file", filename))
+                    } else {
+                        None
+                    };
+                    TransferOutcome::UploadFinished { name: filename, url }
+                }
+                Err(e) => {
+                    let entry = Self::failed_upload_entry(&path, &custom_filename, &e);
+                    let event = TransferOutcome::UploadFailed { name: entry.filename.clone(), error: e.to_string() };
+                    state.record_upload(entry);
+                    state.report_error(&t!("error-context-upload"), &e);
+                    if e.is_unauthorized() {
+                        state.paused_uploads.push(path.clone());
+                    }
+                    event
+                }
+            };
+            if let Err(e) = list_result {
+                state.report_error(&t!("error-context-add-upload-to-list"), &e);
+            }
+            drop(state);
+            *thread_running.lock().unwrap() = false;
+            transfer.end(event);
+        });
+    }
+
+    fn start_multiple_upload(&mut self, mut paths: Vec<PathBuf>, ctx: egui::Context) {
+        // Only ever for the upload it was chosen for, even when that doesn't start
+        let bundle = self.upload_bundle.take();
+        self.upload_order.apply(&mut paths);
+        let (paths, skipped) = preflight::filter_files(paths);
+        *self.upload_skipped.lock().unwrap() = skipped;
+        if paths.is_empty() {
+            self.state.lock().unwrap().last_error = Some(t!("upload-nothing-to-upload"));
+            return;
+        }
+        let sizes = || paths.iter().map(|path| path.metadata().map(|metadata| metadata.len()).unwrap_or(0)).collect();
+        if self.hold_for_quota(sizes, HeldUpload::Files(paths.clone())) {
+            // Still a bundle when it's started anyway
+            self.upload_bundle = bundle;
+            return;
+        }
+        let encryption = match self.upload_encryption() {
+            Ok(encryption) => encryption,
+            Err(e) => {
+                self.state.lock().unwrap().last_error = Some(e);
+                return;
+            }
+        };
+        let (api_key, anonymous) = match self.take_upload_api_key() {
+            Ok(api_key) => (api_key.clone(), api_key.is_none()),
+            Err(e) => {
+                self.state.lock().unwrap().last_error = Some(e);
+                return;
+            }
+        };
+        
+        let progress = self.upload_progress.clone();
+        let rate = self.upload_rate.clone();
+        let state = self.state.clone();
+        let thread_running = self.upload_thread_running.clone();
+        let ctx = ctx.clone();
+        // The server has the encrypted file, there's nothing to compare with or find
+        let verify_uploads = self.state.lock().unwrap().verify_uploads && encryption.is_none();
+        let check_before = self.state.lock().unwrap().check_before_large_uploads && !anonymous;
+        let checking = self.upload_checking.clone();
+        let skip_duplicates = self.state.lock().unwrap().skip_duplicate_uploads && !anonymous && !std::mem::take(&mut self.upload_duplicates_allowed) && encryption.is_none();
+        let duplicates = self.upload_duplicates.clone();
+        duplicates.lock().unwrap().clear();
+        let prepare_progress = self.upload_prepare_progress.clone();
+        let workers = self.workers.clone();
+        let target_list = self.upload_target_list(anonymous);
+        let lists = self.lists.clone();
+        let file_names = self.upload_file_names.clone();
+        let concurrency = self.state.lock().unwrap().upload_concurrency.clamp(1, MAX_UPLOAD_CONCURRENCY);
+        let batch_progress = self.upload_batch_progress.clone();
+        let last_batch = self.last_batch.clone();
+        last_batch.lock().unwrap().clear();
+        *self.batch_list_result.lock().unwrap() = None;
+        
+        // Reset progress at start
+        *self.upload_progress.lock().unwrap() = 0.0;
+        *rate.lock().unwrap() = None;
+        *batch_progress.lock().unwrap() = paths.iter().map(|path| (Self::batch_file_name(&file_names, path), 0.0)).collect();
+        *thread_running.lock().unwrap() = true;
+        
+        let transfer = self.events.start(Direction::Upload, format!("{} files", paths.len()));
+        let diagnostics = self.take_diagnostics(&format!("{} files", paths.len()));
+        let api_factory = self.api_factory.clone();
+        let mut recorded = self.journal.record(paths.iter().map(|path| resume::Transfer::Upload { path: path.clone() }).collect());
+        self.workers.spawn(move || {
+            let config = if let Some(key) = api_key {
+                PixelDrainConfig::default().with_api_key(key)
+            } else {
+                PixelDrainConfig::default()
+            };
+            
+            let client = match api_factory(config.with_diagnostics(diagnostics.as_ref().map(DiagnosticsCapture::recorder))) {
+                Ok(client) => client,
+                Err(e) => {
+                    let mut state = state.lock().unwrap();
+                    state.last_error = Some(format!("Failed to create client: {}", e));
+                    *thread_running.lock().unwrap() = false;
+                    return;
+                }
+            };
+            
+            let selected_files = paths.len();
+            // Hash the whole batch up front so verification doesn't serialize behind each upload
+            let local_hashes = if verify_uploads || skip_duplicates {
+                *prepare_progress.lock().unwrap() = Some(0.0);
+                ctx.request_repaint();
+                let hashes = Self::hash_files_parallel(&paths, &prepare_progress, &ctx);
+                *prepare_progress.lock().unwrap() = None;
+                hashes
+            } else {
+                vec![None; selected_files]
+            };
+            // Files the account has already are left out and offered instead.
+            // The index of each file left in the journal is kept with it.
+            let (paths, local_hashes, recorded_index) = if skip_duplicates {
+                let existing = Self::find_in_account(client.as_ref(), &local_hashes);
+                let mut remaining = (Vec::new(), Vec::new(), Vec::new());
+                let mut duplicates = duplicates.lock().unwrap();
+                for (index, ((path, hash), existing)) in paths.into_iter().zip(local_hashes).zip(existing).enumerate() {
+                    match existing {
+                        Some(file) => {
+                            recorded.done(index);
+                            duplicates.push((path, file));
+                        }
+                        None => {
+                            remaining.0.push(path);
+                            remaining.1.push(hash);
+                            remaining.2.push(index);
+                        }
+                    }
+                }
+                *batch_progress.lock().unwrap() = remaining.0.iter().map(|path| (Self::batch_file_name(&file_names, path), 0.0)).collect();
+                remaining
+            } else {
+                let recorded_index = (0..paths.len()).collect();
+                (paths, local_hashes, recorded_index)
+            };
+            let recorded = Mutex::new(recorded);
+            let skipped_duplicates = selected_files - paths.len();
+            
+            let total_files = paths.len();
+            // Size of each file, for the bytes and time left of the whole batch
+            let sizes: Vec<u64> = paths.iter().map(|path| fs::metadata(path).map(|m| m.len()).unwrap_or(0)).collect();
+            let total_bytes: u64 = sizes.iter().sum();
+            if check_before && let Err(error) = Self::check_before_upload(client.as_ref(), total_bytes, &checking) {
+                state.lock().unwrap().last_error = Some(error.clone());
+                *thread_running.lock().unwrap() = false;
+                transfer.end(TransferOutcome::UploadFailed { name: format!("{} files", selected_files), error });
+                return;
+            }
+            
+            // Up to `concurrency` files at once, each worker takes the next file when it's done
+            let next = std::sync::atomic::AtomicUsize::new(0);
+            let stop = std::sync::atomic::AtomicBool::new(false);
+            // Latest progress of each file, the batch's is their sum
+            let file_progress = Arc::new(Mutex::new(vec![Progress::default(); total_files]));
+            // ID, URL and name of each uploaded file, by index so the list keeps the batch order
+            let uploaded: Mutex<Vec<Option<(String, String, String)>>> = Mutex::new(vec![None; total_files]);
+            let failure = Mutex::new(None);
+            // The key was refused, the files not uploaded yet wait for a new one
+            let unauthorized = std::sync::atomic::AtomicBool::new(false);
+            thread::scope(|scope| {
+                for _ in 0..concurrency.min(total_files) {
+                    scope.spawn(|| loop {
+                        // Stop taking files after a failure or when the app is closing,
+                        // uploads already running are finished
+                        if stop.load(Ordering::Relaxed) || workers.is_shutting_down() {
+                            break;
+                        }
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(path) = paths.get(index) else {
+                            break;
+                        };
+                        
+                        let progress_cb = {
+                            let progress = progress.clone();
+                            let rate = rate.clone();
+                            let file_progress = file_progress.clone();
+                            let batch_progress = batch_progress.clone();
+                            let reporter = transfer.progress();
+                            transfer.progress().sender(move |p: Progress| {
+                                let batch = {
+                                    let mut files = file_progress.lock().unwrap();
+                                    files[index] = p;
+                                    // Calculate overall progress across all files
+                                    Progress {
+                                        fraction: files.iter().map(|file| file.fraction).sum::<f32>() / total_files as f32,
+                                        bytes: files.iter().map(|file| file.bytes).sum(),
+                                        total: Some(total_bytes),
+                                        speed: files.iter().filter(|file| file.fraction < 1.0).map(|file| file.speed).sum(),
+                                    }
+                                };
+                                if let Some(row) = batch_progress.lock().unwrap().get_mut(index) {
+                                    row.1 = p.fraction;
+                                }
+                                *progress.lock().unwrap() = batch.fraction;
+                                *rate.lock().unwrap() = Some(batch);
+                                reporter.report(batch.fraction);
+                            })
+                        };
+                        
+                        if let Some(diagnostics) = &diagnostics {
+                            diagnostics.recorder().note(&format!("file {}/{}: {}", index + 1, total_files, path.display()));
+                        }
+                        let custom_filename = match &encryption {
+                            Some(_) => crypto::encrypted_name(&Self::batch_file_name(&file_names, path)),
+                            None => file_names.get(path).map(|name| name.trim().to_string()).unwrap_or_default(),
+                        };
+                        let result = if let Some((passphrase, _)) = &encryption {
+                            Self::upload_encrypted(client.as_ref(), path, &custom_filename, passphrase, progress_cb)
+                        } else if !custom_filename.is_empty() {
+                            client.upload_file_put(path, &custom_filename, Some(progress_cb))
+                        } else {
+                            client.upload_file(path, Some(progress_cb))
+                        };
+                        let verified = match &result {
+                            Ok(response) if verify_uploads => {
+                                Self::verify_uploaded_file(client.as_ref(), &response.id, path, local_hashes[index].clone())
+                            }
+                            _ => None,
+                        };
+                        recorded.lock().unwrap().done(recorded_index[index]);
+                        let mut state = state.lock().unwrap();
+                        
+                        match result {
+                            Ok(response) => {
+                                let url = response.get_file_url(client.base_url());
+                                let filename = if custom_filename.is_empty() { Self::batch_file_name(&file_names, path) } else { custom_filename.clone() };
+                                if let Some((_, key_id)) = &encryption {
+                                    state.key_registry.record(&response.id, key_id);
+                                    state.settings_dirty = true;
+                                }
+
+                                if verified == Some(false) {
+                                    state.last_error = Some(format!("Upload verification failed for {}: size or SHA-256 does not match the local file", filename));
+                                }
+                                uploaded.lock().unwrap()[index] = Some((response.id.clone(), url.clone(), filename.clone()));
+                                let entry = UploadHistoryEntry {
+                                    id: response.id,
+                                    url,
+                                    filename,
+                                    size: path.metadata().map(|m| m.len()).unwrap_or(0),
+                                    timestamp: Utc::now(),
+                                    verified,
+                                    error: None,
+                                    anonymous,
+                                };
+                                state.record_upload(entry);
+                            }
+                            Err(e) => {
+                                let entry = Self::failed_upload_entry(path, &custom_filename, &e);
+                                failure.lock().unwrap().get_or_insert(TransferOutcome::UploadFailed { name: entry.filename.clone(), error: e.to_string() });
+                                state.record_upload(entry);
+                                state.last_error = Some(format!("Upload error for {}: {}", path.file_name().unwrap().to_string_lossy(), e));
+                                state.note_auth_failure(&e);
+                                if e.is_unauthorized() {
+                                    unauthorized.store(true, Ordering::Relaxed);
+                                }
+                                stop.store(true, Ordering::Relaxed);
+                                break;
+                            }
+                        }
+                    });
+                }
+            });
+            // Files the closing app didn't get to are uploaded after the next start
+            if workers.is_shutting_down() {
+                recorded.into_inner().unwrap().keep();
+            }
+            let uploaded = uploaded.into_inner().unwrap();
+            if unauthorized.into_inner() {
+                let remaining = paths.iter().zip(&uploaded).filter(|(_, uploaded)| uploaded.is_none()).map(|(path, _)| path.clone());
+                state.lock().unwrap().paused_uploads.extend(remaining);
+            }
+            let uploaded_ids: Vec<String> = uploaded.iter().flatten().map(|(id, _, _)| id.clone()).collect();
+            let mut last_url = uploaded.iter().flatten().next_back().map(|(_, url, _)| url.clone()).unwrap_or_default();
+            let failure = failure.into_inner().unwrap();
+            
+            // A bundle's link is its list, the index page has every file
+            if let Some(index_path) = &bundle
+                && !uploaded_ids.is_empty()
+            {
+                let files: Vec<bundle::BundleFile> = uploaded.iter().flatten()
+                    .map(|(id, _, name)| bundle::BundleFile { id: id.clone(), name: name.clone() })
+                    .collect();
+                match Self::write_link_bundle(client.as_ref(), index_path, &files) {
+                    Ok(url) => last_url = url,
+                    Err(e) => state.lock().unwrap().last_error = Some(e),
+                }
+            }
+            
+            // Copy the last uploaded file URL to clipboard
+            if !last_url.is_empty() {
+                let _ = Clipboard::new().and_then(|mut c| c.set_text(last_url.clone()));
+            }
+            
+            // Files uploaded before a failure are added too
+            if let Err(e) = Self::add_uploads_to_list(client.as_ref(), &lists, target_list.as_deref(), &uploaded_ids) {
+                state.lock().unwrap().report_error(&t!("error-context-add-uploads-to-list"), &e);
+            }
+            // Not yet in a list, offered to be put in one
+            if uploaded_ids.len() > 1 && target_list.is_none() && bundle.is_none() && !anonymous {
+                *last_batch.lock().unwrap() = uploaded.iter().flatten().map(|(id, _, name)| (id.clone(), name.clone())).collect();
+            }
+            let uploaded_count = uploaded_ids.len();
+            
+            *thread_running.lock().unwrap() = false;
+            // Stopped before the first file when the app closes, the transfer reports that itself
+            if uploaded_count > 0 || failure.is_some() || skipped_duplicates > 0 {
+                transfer.end(failure.unwrap_or(TransferOutcome::UploadFinished {
+                    name: format!("{} of {} files", uploaded_count, selected_files),
+                    url: last_url,
+                }));
+            }
+        });
+    }
+
+    /// Put the files of a bundle in a new list named after its index page, then
+    /// write the index page. Returns the list's URL.
+    fn write_link_bundle(client: &dyn PixelDrainApi, index_path: &Path, files: &[bundle::BundleFile]) -> Result<String, String> {
+        let title = index_path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+        let list = client.create_list(&pixeldrain_api::CreateListRequest {
+            title: title.clone(),
+            files: files.iter().map(|file| pixeldrain_api::ListFile { id: file.id.clone(), description: String::new() }).collect(),
+        });
+        // The index is still worth having without the list, its links work either way
+        let list_id = list.as_ref().ok().map(|list| list.id.as_str());
+        let page = bundle::index(bundle::IndexFormat::of(index_path), client.base_url(), &title, list_id, files);
+        fs::write(index_path, page).map_err(|e| format!("Failed to write {}: {}", index_path.display(), e))?;
+        list.map(|list| format!("{}/l/{}", client.base_url(), list.id))
+            .map_err(|e| format!("Failed to create the bundle's list: {}", e))
+    }
+
+    /// Largest file the account may upload, when known
+    fn upload_size_limit(&self) -> Option<u64> {
+        let state = self.state.lock().unwrap();
+        state.user_info.as_ref().map(|user| user.subscription.file_size_limit).filter(|limit| *limit > 0)
+    }
+
+    /// Upload `path` in parts that each fit in `limit` bytes, and put them in a list
+    /// named after the file, which is the link that's copied
+    fn start_split_upload(&mut self, path: PathBuf, limit: u64) {
+        if self.state.lock().unwrap().encrypt_uploads {
+            self.state.lock().unwrap().last_error = Some(t!("split-upload-not-encrypted"));
+            return;
+        }
+        // The parts are kept together in a list, which needs an account
+        let api_key = match self.take_upload_api_key() {
+            Ok(Some(api_key)) => api_key,
+            Ok(None) => {
+                self.state.lock().unwrap().last_error = Some(t!("split-upload-needs-account"));
+                return;
+            }
+            Err(e) => {
+                self.state.lock().unwrap().last_error = Some(e);
+                return;
+            }
+        };
+        
+        let progress = self.upload_progress.clone();
+        let rate = self.upload_rate.clone();
+        let state = self.state.clone();
+        let thread_running = self.upload_thread_running.clone();
+        let workers = self.workers.clone();
+        let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let transfer = self.events.start(Direction::Upload, name.clone());
+        *progress.lock().unwrap() = 0.0;
+        *rate.lock().unwrap() = None;
+        *thread_running.lock().unwrap() = true;
+        let diagnostics = self.take_diagnostics(&path.display().to_string());
+        let api_factory = self.api_factory.clone();
+        self.workers.spawn(move || {
+            let config = PixelDrainConfig::default().with_api_key(api_key);
+            let client = match api_factory(config.with_diagnostics(diagnostics.as_ref().map(DiagnosticsCapture::recorder))) {
+                Ok(client) => client,
+                Err(e) => {
+                    state.lock().unwrap().last_error = Some(format!("Failed to create client: {}", e));
+                    *thread_running.lock().unwrap() = false;
+                    return;
+                }
+            };
+            
+            let size = path.metadata().map(|m| m.len()).unwrap_or(0);
+            let part_size = split::part_size(size, limit);
+            let mut part_ids = Vec::new();
+            let mut result = Ok(());
+            for index in 0..split::part_count(size, limit) {
+                // The transfer reports that it stopped when it's dropped
+                if workers.is_shutting_down() {
+                    *thread_running.lock().unwrap() = false;
+                    return;
+                }
+                let done = index * part_size;
+                let progress_cb = {
+                    let progress = progress.clone();
+                    let rate = rate.clone();
+                    let reporter = transfer.progress();
+                    transfer.progress().sender(move |p: Progress| {
+                        // Progress of the whole file
+                        let bytes = done + p.bytes;
+                        let p = Progress { fraction: bytes as f32 / size.max(1) as f32, bytes, total: Some(size), speed: p.speed };
+                        *progress.lock().unwrap() = p.fraction;
+                        *rate.lock().unwrap() = Some(p);
+                        reporter.report(p.fraction);
+                    })
+                };
+                let part_name = split::part_name(&name, index);
+                let uploaded = split::open_part(&path, index, part_size)
+                    .map_err(pixeldrain_api::PixelDrainError::Io)
+                    .and_then(|(part, len)| {
+                        let reader = ProgressReader::new_file(part, len, Some(progress_cb));
+                        client.upload_stream_put(Box::new(reader), &part_name, None)
+                    });
+                match uploaded {
+                    Ok(response) => {
+                        state.lock().unwrap().record_upload(UploadHistoryEntry {
+                            id: response.id.clone(),
+                            url: response.get_file_url(client.base_url()),
+                            filename: part_name,
+                            size: part_size.min(size - done),
+                            timestamp: Utc::now(),
+                            verified: None,
+                            error: None,
+                            anonymous: false,
+                        });
+                        part_ids.push(response.id);
+                    }
+                    Err(e) => {
+                        result = Err(e);
+                        break;
+                    }
+                }
+            }
+            let result = result.and_then(|()| {
+                client.create_list(&pixeldrain_api::CreateListRequest {
+                    title: name.clone(),
+                    files: part_ids.iter().map(|id| pixeldrain_api::ListFile { id: id.clone(), description: String::new() }).collect(),
+                })
+            });
+            
+            let mut state = state.lock().unwrap();
+            let event = match result {
+                Ok(list) => {
+                    let url = format!("{}/l/{}", client.base_url(), list.id);
+                    let _ = Clipboard::new().and_then(|mut c| c.set_text(url.clone()));
+                    state.last_error = None;
+                    TransferOutcome::UploadFinished { name, url }
+                }
+                Err(e) => {
+                    state.report_error(&t!("error-context-split-upload"), &e);
+                    TransferOutcome::UploadFailed { name, error: e.to_string() }
+                }
+            };
+            drop(state);
+            *thread_running.lock().unwrap() = false;
+            transfer.end(event);
+        });
+    }
+
+    /// Name a file of a multi-file upload is uploaded under
+    fn batch_file_name(file_names: &HashMap<PathBuf, String>, path: &Path) -> String {
+        match file_names.get(path).map(|name| name.trim()) {
+            Some(name) if !name.is_empty() => name.to_string(),
+            _ => path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+        }
+    }
+
+    fn start_directory_upload(&mut self, dir_path: PathBuf, ctx: egui::Context) {
+        let excludes = preflight::Excludes::parse(&self.state.lock().unwrap().exclude_patterns);
+        let directory_size = || {
+            // The archive can't be bigger than the files by much, so their size decides
+            let entries = preflight::scan_directory(&dir_path, &excludes).map(|(entries, _)| entries).unwrap_or_default();
+            vec![preflight::files_and_size(&dir_path, &entries).1]
+        };
+        if self.hold_for_quota(directory_size, HeldUpload::Directory(dir_path.clone())) {
+            return;
+        }
+        let encryption = match self.upload_encryption() {
+            Ok(encryption) => encryption,
+            Err(e) => {
+                self.state.lock().unwrap().last_error = Some(e);
+                return;
+            }
+        };
+        let (api_key, anonymous) = match self.take_upload_api_key() {
+            Ok(api_key) => (api_key.clone(), api_key.is_none()),
+            Err(e) => {
+                self.state.lock().unwrap().last_error = Some(e);
+                return;
+            }
+        };
+        
+        let progress = self.upload_progress.clone();
+        let rate = self.upload_rate.clone();
+        let state = self.state.clone();
+        let thread_running = self.upload_thread_running.clone();
+        let directory_name = self.upload_directory_name.clone();
+        let (archive_format, threads, excludes) = {
+            let state = self.state.lock().unwrap();
+            let threads = archive::compression_threads(state.archive_format, state.parallel_compression);
+            (state.archive_format, threads, preflight::Excludes::parse(&state.exclude_patterns))
+        };
+        let check_before = self.state.lock().unwrap().check_before_large_uploads && !anonymous;
+        let checking = self.upload_checking.clone();
+        let target_list = self.upload_target_list(anonymous);
+        let lists = self.lists.clone();
+        let upload_skipped = self.upload_skipped.clone();
+        upload_skipped.lock().unwrap().clear();
+        
+        // Reset progress at start
+        *self.upload_progress.lock().unwrap() = 0.0;
+        *rate.lock().unwrap() = None;
+        *thread_running.lock().unwrap() = true;
+        
+        let transfer = self.events.start(Direction::Upload, dir_path.file_name().unwrap_or_default().to_string_lossy());
+        let diagnostics = self.take_diagnostics(&dir_path.display().to_string());
+        let api_factory = self.api_factory.clone();
+        self.workers.spawn(move || {
+            let config = if let Some(key) = api_key {
+                PixelDrainConfig::default().with_api_key(key)
+            } else {
+                PixelDrainConfig::default()
+            };
+            
+            let client = match api_factory(config.with_diagnostics(diagnostics.as_ref().map(DiagnosticsCapture::recorder))) {
+                Ok(client) => client,
+                Err(e) => {
+                    let mut state = state.lock().unwrap();
+                    state.last_error = Some(format!("Failed to create client: {}", e));
+                    *thread_running.lock().unwrap() = false;
+                    return;
+                }
+            };
+            
+            // Determine the archive filename
+            let archive_name = if !directory_name.is_empty() {
+                directory_name
+            } else {
+                let dir_name = dir_path.file_name().unwrap_or_default().to_string_lossy();
+                format!("{}.{}", dir_name, archive_format.extension())
+            };
+            let archive_name = if encryption.is_some() { crypto::encrypted_name(&archive_name) } else { archive_name };
+            
+            // Leave out what's excluded and what the archive would fail on halfway through
+            let entries = match preflight::scan_directory(&dir_path, &excludes) {
+                Ok((entries, skipped)) => {
+                    *upload_skipped.lock().unwrap() = skipped;
+                    entries
+                }
+                Err(e) => {
+                    let mut state = state.lock().unwrap();
+                    state.last_error = Some(format!("Failed to read {}: {}", dir_path.display(), e));
+                    *thread_running.lock().unwrap() = false;
+                    return;
+                }
+            };
+            // The archive can't be bigger than the files by much, so their size decides
+            let (_, size) = preflight::files_and_size(&dir_path, &entries);
+            if check_before && let Err(error) = Self::check_before_upload(client.as_ref(), size, &checking) {
+                state.lock().unwrap().last_error = Some(error.clone());
+                *thread_running.lock().unwrap() = false;
+                transfer.end(TransferOutcome::UploadFailed { name: archive_name, error });
+                return;
+            }
+            
+            // At most 16 MiB are buffered, so the archive's progress stays close to the upload's
+            let last_update = AtomicU64::new(0);
+            let mut meter = SpeedMeter::default();
+            let reporter = transfer.progress();
+            let archive_progress: archive::ArchiveProgress = Box::new(move |archived, total| {
+                let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
+                if now - last_update.load(Ordering::Relaxed) >= 16 || archived == total {
+                    last_update.store(now, Ordering::Relaxed);
+                    let fraction = if total == 0 { 1.0 } else { archived as f32 / total as f32 };
+                    *progress.lock().unwrap() = fraction;
+                    *rate.lock().unwrap() = Some(meter.progress(archived, Some(total)));
+                    reporter.report(fraction);
+                    ctx.request_repaint();
+                }
+            });
+            
+            // Archive into a buffer that is read by the upload, no temporary file
+            let (archive_reader, archive_writer) = match archive::spawn_archive(&dir_path, entries, archive_format, threads, Some(archive_progress)) {
+                Ok(archive) => archive,
+                Err(e) => {
+                    let mut state = state.lock().unwrap();
+                    state.last_error = Some(e.to_string());
+                    *thread_running.lock().unwrap() = false;
+                    return;
+                }
+            };
+            
+            debug!("Starting streaming upload of {} to {}", archive_format.extension(), archive_name);
+            let result = match &encryption {
+                Some((passphrase, _)) => crypto::Encryptor::new(archive_reader, passphrase)
+                    .map_err(pixeldrain_api::PixelDrainError::Io)
+                    .and_then(|reader| client.upload_stream_put(Box::new(reader), &archive_name, None)),
+                None => client.upload_stream_put(Box::new(archive_reader), &archive_name, None),
+            };
+            
+            // If archiving failed, what was uploaded is a truncated archive
+            let archive_result = archive_writer.finish();
+            let (result, archive_size) = match (result, archive_result) {
+                (Ok(response), Err(e)) => {
+                    debug!("Deleting incomplete archive {}", response.id);
+                    let _ = client.delete_file(&response.id);
+                    (Err(pixeldrain_api::PixelDrainError::Io(e)), 0)
+                }
+                (result, size) => (result, size.unwrap_or(0)),
+            };
+            let uploaded_ids: Vec<String> = result.iter().map(|response| response.id.clone()).collect();
+            let list_result = Self::add_uploads_to_list(client.as_ref(), &lists, target_list.as_deref(), &uploaded_ids);
+            
+            let mut state = state.lock().unwrap();
+            let event = match result {
+                Ok(response) => {
+                    let url = response.get_file_url(client.base_url());
+                    if let Some((_, key_id)) = &encryption {
+                        state.key_registry.record(&response.id, key_id);
+                        state.settings_dirty = true;
+                    }
+                    let entry = UploadHistoryEntry {
+                        id: response.id,
+                        url: url.clone(),
+                        filename: archive_name.clone(),
+                        size: archive_size,
+                        timestamp: Utc::now(),
+                        verified: None, // No local file to compare against
+                        error: None,
+                        anonymous,
+                    };
+                    state.record_upload(entry);
+                    state.last_error = None;
+                    
+                    // Copy URL to clipboard
+                    let _ = Clipboard::new().and_then(|mut c| c.set_text(url.clone()));
+                    
+                    TransferOutcome::UploadFinished { name: archive_name.clone(), url }
+                }
+                Err(e) => {
+                    warn!("Directory upload error: {}", e);
+                    state.record_upload(UploadHistoryEntry {
+                        id: String::new(),
+                        url: String::new(),
+                        filename: archive_name.clone(),
+                        size: 0,
+                        timestamp: Utc::now(),
+                        verified: None,
+                        error: Some(e.to_string()),
+                        anonymous,
+                    });
+                    state.report_error(&t!("error-context-directory-upload"), &e);
+                    TransferOutcome::UploadFailed { name: archive_name.clone(), error: e.to_string() }
+                }
+            };
+            if let Err(e) = list_result {
+                state.report_error(&t!("error-context-add-upload-to-list"), &e);
+            }
+            drop(state);
+            
+            *thread_running.lock().unwrap() = false;
+            transfer.end(event);
+        });
+    }
+
+    fn start_download(&mut self) {
+        self.start_download_into(None);
+    }
+
+    /// Download `download_url`, continuing the partial file at `resume` when
+    /// it's a single file that isn't decrypted on the way
+    fn start_download_into(&mut self, resume: Option<PathBuf>) {
+        self.download_suggestions.clear();
+        let url = self.download_url.clone();
+        // Downloaded anyway, the key may be at hand elsewhere
+        self.download_missing_key = PixelDrainClient::extract_file_id(&url)
+            .ok()
+            .and_then(|id| self.state.lock().unwrap().key_registry.missing_key(&id).map(str::to_string));
+        let progress = self.download_progress.clone();
+        let rate = self.download_rate.clone();
+        let state = self.state.clone();
+        let thread_running = self.download_thread_running.clone();
+        let status = self.download_status.clone();
+        let workers = self.workers.clone();
+        let transfer = self.events.start(Direction::Download, url.clone());
+        let cancel = self.watchdog.watch(transfer.id(), watchdog::Retry::Download(url.clone()));
+        
+        // Get download location from settings
+        let (download_location, infer_extensions, passphrase) = {
+            let state = self.state.lock().unwrap();
+            (state.download_location.clone(), state.infer_extensions, state.encryption_passphrase.clone())
+        };
+        
+        // Reset progress at start
+        *self.download_progress.lock().unwrap() = 0.0;
+        *rate.lock().unwrap() = None;
+        *thread_running.lock().unwrap() = true;
+        
+        let diagnostics = self.take_diagnostics(&url);
+        let api_factory = self.api_factory.clone();
+        let recorded = self.journal.record(vec![resume::Transfer::Download { url: url.clone(), path: None, bytes_done: 0 }]);
+        self.workers.spawn(move || {
+            let config = PixelDrainConfig::default()
+                .with_diagnostics(diagnostics.as_ref().map(DiagnosticsCapture::recorder))
+                .with_cancel(cancel.clone());
+            let client = match api_factory(config) {
+                Ok(client) => client,
+                Err(e) => {
+                    let mut state = state.lock().unwrap();
+                    state.last_error = Some(format!("Failed to create client: {}", e));
+                    *thread_running.lock().unwrap() = false;
+                    return;
+                }
+            };
+            
+            // List URLs download every file of the list into its own folder
+            if let Ok(list_id) = PixelDrainClient::extract_list_id(&url) {
+                let report = {
+                    let progress = progress.clone();
+                    let reporter = transfer.progress();
+                    ProgressReport::new(transfer.progress(), move |fraction| {
+                        *progress.lock().unwrap() = fraction;
+                        reporter.report(fraction);
+                    })
+                };
+                let result = Self::download_list(client.as_ref(), &list_id, &download_location, &report, &status, &state, &workers);
+                if cancel.load(Ordering::Relaxed) {
+                    transfer.end(TransferOutcome::DownloadFailed { name: list_id, error: pixeldrain_api::PixelDrainError::Cancelled.to_string() });
+                    return;
+                }
+                Self::refresh_rate_limits(client.as_ref(), &state);
+                let mut state = state.lock().unwrap();
+                let event = match result {
+                    Ok(folder) => {
+                        state.last_error = None;
+                        TransferOutcome::DownloadFinished {
+                            name: folder.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or(list_id),
+                            path: folder.display().to_string(),
+                        }
+                    }
+                    Err(e) => {
+                        state.report_error(&t!("error-context-list-download"), &e);
+                        TransferOutcome::DownloadFailed { name: list_id, error: e.to_string() }
+                    }
+                };
+                drop(state);
+                *status.lock().unwrap() = None;
+                *thread_running.lock().unwrap() = false;
+                transfer.end(event);
+                return;
+            }
+            
+            let file_id = match PixelDrainClient::extract_file_id(&url) {
+                Ok(id) => id,
+                Err(e) => {
+                    let mut state = state.lock().unwrap();
+                    state.last_error = Some(format!("Invalid URL: {}", e));
+                    *thread_running.lock().unwrap() = false;
+                    return;
+                }
+            };
+            
+            // Get file info first
+            let file_info = match client.get_file_info(&file_id) {
+                Ok(info) => info,
+                Err(e) => {
+                    let mut state = state.lock().unwrap();
+                    state.report_error(&t!("error-context-file-info"), &e);
+                    *thread_running.lock().unwrap() = false;
+                    return;
+                }
+            };
+            
+            let mut local_name = Self::local_file_name(&file_info, infer_extensions);
+            // Encrypted files are decrypted on the way when the passphrase is set
+            let decrypt = !passphrase.is_empty() && crypto::decrypted_name(&local_name).is_some();
+            if decrypt {
+                local_name.truncate(local_name.len() - crypto::EXTENSION.len());
+            }
+            // A decrypted file can't be continued, it's downloaded again
+            let resume = resume.filter(|_| !decrypt);
+            let save_path = if let Some(partial) = &resume {
+                partial.clone()
+            } else if !download_location.is_empty() {
+                PathBuf::from(&download_location).join(&local_name)
+            } else {
+                PathBuf::from(&local_name)
+            };
+            if !decrypt {
+                recorded.set_path(0, save_path.clone());
+            }
+            
+            let reporter = transfer.progress();
+            let progress_cb = transfer.progress().sender(move |p: Progress| {
+                *progress.lock().unwrap() = p.fraction;
+                *rate.lock().unwrap() = Some(p);
+                reporter.report(p.fraction);
+            });
+            let result = if decrypt {
+                Self::download_decrypted(client.as_ref(), &file_id, &save_path, &passphrase, progress_cb)
+            } else if resume.is_some() {
+                client.resume_download_file(&file_id, &save_path, Some(progress_cb))
+            } else {
+                client.download_file(&file_id, &save_path, Some(progress_cb))
+            };
+            // Cancelled from the Debug panel, which already started it again
+            if cancel.load(Ordering::Relaxed) {
+                transfer.end(TransferOutcome::DownloadFailed { name: file_info.name, error: pixeldrain_api::PixelDrainError::Cancelled.to_string() });
+                return;
+            }
+            Self::refresh_rate_limits(client.as_ref(), &state);
+            
+            let mut state = state.lock().unwrap();
+            let event = match result {
+                Ok(_) => {
+                    let entry = DownloadHistoryEntry {
+                        url,
+                        filename: file_info.name.clone(),
+                        local_path: save_path.display().to_string(),
+                        timestamp: Utc::now(),
+                        error: None,
+                    };
+                    state.record_download(entry);
+                    state.download_usage.record(file_info.size);
+                    state.settings_dirty = true;
+                    state.last_error = None;
+                    TransferOutcome::DownloadFinished { name: file_info.name, path: save_path.display().to_string() }
+                }
+                Err(e) => {
+                    state.record_download(DownloadHistoryEntry {
+                        url,
+                        filename: file_info.name.clone(),
+                        local_path: String::new(),
+                        timestamp: Utc::now(),
+                        error: Some(e.to_string()),
+                    });
+                    state.download_usage.record_error(&e);
+                    state.settings_dirty = true;
+                    state.report_error(&t!("error-context-download"), &e);
+                    TransferOutcome::DownloadFailed { name: file_info.name, error: e.to_string() }
+                }
+            };
+            drop(state);
+            *thread_running.lock().unwrap() = false;
+            transfer.end(event);
+        });
+    }
+
+    /// Name a file is saved under, with an extension from its MIME type when
+    /// `infer_extension` is set and the name has none
+    fn local_file_name(file_info: &FileInfo, infer_extension: bool) -> String {
+        if infer_extension {
+            extensions::with_inferred_extension(&file_info.name, &file_info.mime_type)
+        } else {
+            file_info.name.clone()
+        }
+    }
+
+    /// Download every file of a list into a subfolder named after the list,
+    /// reporting progress across the whole list
+    fn download_list(
+        client: &dyn PixelDrainApi,
+        list_id: &str,
+        download_location: &str,
+        report: &ProgressReport,
+        status: &Arc<Mutex<Option<String>>>,
+        state: &Arc<Mutex<AppState>>,
+        workers: &Workers,
+    ) -> Result<PathBuf, pixeldrain_api::PixelDrainError> {
+        *status.lock().unwrap() = Some("Fetching list...".to_string());
+        let list = client.get_list(list_id)?;
+        
+        // The parts of a split file are joined into it instead
+        let names: Vec<&str> = list.files.iter().map(|file| file.file_info.name.as_str()).collect();
+        let join_split_parts = state.lock().unwrap().join_split_parts;
+        if join_split_parts && let Some(name) = split::joined_name(&names) {
+            let save_path = PathBuf::from(download_location).join(sanitize_file_name(&name));
+            return Self::download_joined_parts(client, &list, &save_path, report, status, state, workers).map(|()| save_path);
+        }
+        
+        let folder_name = if list.title.trim().is_empty() {
+            list.id.clone()
+        } else {
+            sanitize_file_name(&list.title)
+        };
+        let folder = if download_location.is_empty() {
+            PathBuf::from(folder_name)
+        } else {
+            PathBuf::from(download_location).join(folder_name)
+        };
+        fs::create_dir_all(&folder)?;
+        
+        let total = list.files.len();
+        for (index, file) in list.files.iter().enumerate() {
+            if workers.is_shutting_down() {
+                break;
+            }
+            let file_info = &file.file_info;
+            *status.lock().unwrap() = Some(format!("File {}/{}: {}", index + 1, total, file_info.name));
+            
+            let infer_extensions = state.lock().unwrap().infer_extensions;
+            let save_path = folder.join(sanitize_file_name(&Self::local_file_name(file_info, infer_extensions)));
+            let progress_cb = report.part(index, total);
+            let result = client.download_file(&file_info.id, &save_path, Some(progress_cb));
+            
+            let mut state = state.lock().unwrap();
+            state.record_download(DownloadHistoryEntry {
+                url: format!("{}/u/{}", client.base_url(), file_info.id),
+                filename: file_info.name.clone(),
+                local_path: if result.is_ok() { save_path.display().to_string() } else { String::new() },
+                timestamp: Utc::now(),
+                error: result.as_ref().err().map(|e| e.to_string()),
+            });
+            match &result {
+                Ok(()) => state.download_usage.record(file_info.size),
+                Err(e) => state.download_usage.record_error(e),
+            }
+            state.settings_dirty = true;
+            drop(state);
+            result?;
+        }
+        Ok(folder)
+    }
+
+    /// Download the parts of a split file one after another into `save_path`
+    fn download_joined_parts(
+        client: &dyn PixelDrainApi,
+        list: &pixeldrain_api::DetailedListInfo,
+        save_path: &Path,
+        report: &ProgressReport,
+        status: &Arc<Mutex<Option<String>>>,
+        state: &Arc<Mutex<AppState>>,
+        workers: &Workers,
+    ) -> Result<(), pixeldrain_api::PixelDrainError> {
+        let total = list.files.len();
+        let mut out = std::io::BufWriter::new(fs::File::create(save_path)?);
+        let mut result = Ok(());
+        for (index, file) in list.files.iter().enumerate() {
+            if workers.is_shutting_down() {
+                result = Err(std::io::Error::other("Stopped before it finished").into());
+                break;
+            }
+            let file_info = &file.file_info;
+            *status.lock().unwrap() = Some(format!("Part {}/{}: {}", index + 1, total, file_info.name));
+            let progress_cb = report.part(index, total);
+            result = client.download_file_to_writer(&file_info.id, &mut out, Some(progress_cb));
+            let mut state = state.lock().unwrap();
+            match &result {
+                Ok(()) => state.download_usage.record(file_info.size),
+                Err(e) => state.download_usage.record_error(e),
+            }
+            state.settings_dirty = true;
+            drop(state);
+            if result.is_err() {
+                break;
+            }
+        }
+        let result = result.and_then(|()| Ok(std::io::Write::flush(&mut out)?));
+        drop(out);
+        // Half a file is of no use
+        if result.is_err() {
+            let _ = fs::remove_file(save_path);
+        }
+        state.lock().unwrap().record_download(DownloadHistoryEntry {
+            url: format!("{}/l/{}", client.base_url(), list.id),
+            filename: save_path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+            local_path: if result.is_ok() { save_path.display().to_string() } else { String::new() },
+            timestamp: Utc::now(),
+            error: result.as_ref().err().map(|e| e.to_string()),
+        });
+        result
+    }
+
+    /// Ask where to save a list archive, then start downloading it
+    fn pick_list_zip_destination(&mut self, list_id: &str, title: &str) {
+        let download_location = self.state.lock().unwrap().download_location.clone();
+        let mut dialog = FileDialog::new()
+            .set_file_name(format!("{}.zip", sanitize_file_name(title)))
+            .add_filter("ZIP archive", &["zip"]);
+        if !download_location.is_empty() {
+            dialog = dialog.set_directory(&download_location);
+        }
+        if let Some(dest) = dialog.save_file() {
+            self.start_list_zip_download(list_id.to_string(), dest);
+        }
+    }
+
+    fn start_list_zip_download(&mut self, list_id: String, dest: PathBuf) {
+        let state = self.state.clone();
+        let progress = self.download_progress.clone();
+        let thread_running = self.download_thread_running.clone();
+        let transfer = self.events.start(Direction::Download, dest.file_name().unwrap_or_default().to_string_lossy());
+        
+        *thread_running.lock().unwrap() = true;
+        *progress.lock().unwrap() = 0.0;
+        
+        let api_factory = self.api_factory.clone();
+        self.workers.spawn(move || {
+            let progress_cb = {
+                let progress = progress.clone();
+                let reporter = transfer.progress();
+                transfer.progress().sender(move |p: Progress| {
+                    *progress.lock().unwrap() = p.fraction;
+                    reporter.report(p.fraction);
+                })
+            };
+            
+            let result = api_factory(PixelDrainConfig::default()).and_then(|client| {
+                client.download_list_zip(&list_id, &dest, Some(progress_cb))?;
+                Ok(client.base_url().to_string())
+            });
+            
+            let filename = dest.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            let mut state = state.lock().unwrap();
+            let event = match result {
+                Ok(base_url) => {
+                    state.last_error = None;
+                    state.download_usage.record(fs::metadata(&dest).map(|m| m.len()).unwrap_or(0));
+                    state.settings_dirty = true;
+                    state.record_download(DownloadHistoryEntry {
+                        url: format!("{}/l/{}", base_url, list_id),
+                        filename: filename.clone(),
+                        local_path: dest.display().to_string(),
+                        timestamp: Utc::now(),
+                        error: None,
+                    });
+                    TransferOutcome::DownloadFinished { name: filename, path: dest.display().to_string() }
+                }
+                Err(e) => {
+                    state.report_error(&t!("error-context-list-zip"), &e);
+                    TransferOutcome::DownloadFailed { name: filename, error: e.to_string() }
+                }
+            };
+            drop(state);
+            *thread_running.lock().unwrap() = false;
+            transfer.end(event);
+        });
+    }
+
+    fn start_zip_download(&mut self, files: Vec<FileInfo>, dest: PathBuf) {
+        let api_key = self.get_api_key();
+        let state = self.state.clone();
+        let progress = self.files_zip_progress.clone();
+        let status = self.files_zip_status.clone();
+        let transfer = self.events.start(Direction::Download, format!("{} files as ZIP", files.len()));
+        
+        *progress.lock().unwrap() = Some(0.0);
+        *status.lock().unwrap() = None;
+        
+        let api_factory = self.api_factory.clone();
+        self.workers.spawn(move || {
+            let config = if let Some(key) = api_key {
+                PixelDrainConfig::default().with_api_key(key)
+            } else {
+                PixelDrainConfig::default()
+            };
+            
+            let result = match api_factory(config) {
+                Ok(client) => {
+                    let report = {
+                        let progress = progress.clone();
+                        let reporter = transfer.progress();
+                        ProgressReport::new(transfer.progress(), move |fraction| {
+                            *progress.lock().unwrap() = Some(fraction);
+                            reporter.report(fraction);
+                        })
+                    };
+                    Self::build_zip_bundle(client.as_ref(), &files, &dest, &report)
+                }
+                Err(e) => Err(e.to_string().into()),
+            };
+            
+            let name = format!("{} files as ZIP", files.len());
+            let event = match result {
+                Ok(()) => {
+                    let mut state = state.lock().unwrap();
+                    state.download_usage.record(files.iter().map(|f| f.size).sum());
+                    state.settings_dirty = true;
+                    drop(state);
+                    *status.lock().unwrap() = Some(format!("✅ Saved {} files to {}", files.len(), dest.display()));
+                    TransferOutcome::DownloadFinished { name, path: dest.display().to_string() }
+                }
+                Err(e) => {
+                    let _ = fs::remove_file(&dest);
+                    state.lock().unwrap().last_error = Some(format!("ZIP download error: {}", e));
+                    TransferOutcome::DownloadFailed { name, error: e.to_string() }
+                }
+            };
+            *progress.lock().unwrap() = None;
+            transfer.end(event);
+        });
+    }
+    
+    /// Download each file into a scratch directory and pack them into one ZIP archive
+    /// with a manifest.json describing every entry
+    fn build_zip_bundle(
+        client: &dyn PixelDrainApi,
+        files: &[FileInfo],
+        dest: &Path,
+        report: &ProgressReport,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = env::temp_dir().join(format!("pixeldrain-zip-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&temp_dir)?;
+        let result = Self::write_zip_bundle(client, files, dest, &temp_dir, report);
+        let _ = fs::remove_dir_all(&temp_dir);
+        result
+    }
+    
+    fn write_zip_bundle(
+        client: &dyn PixelDrainApi,
+        files: &[FileInfo],
+        dest: &Path,
+        temp_dir: &Path,
+        report: &ProgressReport,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut zip = zip::ZipWriter::new(fs::File::create(dest)?);
+        // Most uploads are already compressed media, so store them as-is
+        let stored = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored)
+            .large_file(true);
+        let mut used_names = HashSet::new();
+        let mut manifest = BundleManifest {
+            created: Utc::now(),
+            file_count: files.len(),
+            files: Vec::new(),
+        };
+        
+        for (index, file) in files.iter().enumerate() {
+            let temp_path = temp_dir.join(&file.id);
+            let progress_cb = report.part(index, files.len());
+            client.download_file(&file.id, &temp_path, Some(progress_cb))?;
+            
+            let archive_path = Self::unique_archive_name(&file.name, &mut used_names);
+            zip.start_file(archive_path.as_str(), stored)?;
+            std::io::copy(&mut fs::File::open(&temp_path)?, &mut zip)?;
+            fs::remove_file(&temp_path)?;
+            
+            manifest.files.push(BundleManifestEntry {
+                id: file.id.clone(),
+                name: file.name.clone(),
+                path: archive_path,
+                size: file.size,
+                mime_type: file.mime_type.clone(),
+                hash_sha256: file.hash_sha256.clone(),
+                url: format!("{}/u/{}", client.base_url(), file.id),
+                date_upload: file.date_upload,
+            });
+        }
+        
+        let manifest_name = Self::unique_archive_name("manifest.json", &mut used_names);
+        zip.start_file(manifest_name, zip::write::SimpleFileOptions::default())?;
+        serde_json::to_writer_pretty(&mut zip, &manifest)?;
+        zip.finish()?;
+        Ok(())
+    }
+    
+    /// Pick a name that doesn't collide with earlier entries ("photo.jpg" -> "photo (1).jpg")
+    fn unique_archive_name(name: &str, used: &mut HashSet<String>) -> String {
+        let path = Path::new(name);
+        let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_else(|| "file".to_string());
+        let extension = path.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+        let mut candidate = format!("{}{}", stem, extension);
+        let mut counter = 1;
+        while !used.insert(candidate.clone()) {
+            candidate = format!("{} ({}){}", stem, counter, extension);
+            counter += 1;
+        }
+        candidate
+    }
+
+    fn refresh_file_list(&self) {
+        // Set loading state
+        *self.files_loading.lock().unwrap() = true;
+        
+        // Get API key with settings priority
+        let api_key = self.get_api_key();
+        
+        let state = self.state.clone();
+        let files_loading = self.files_loading.clone();
+        let metadata_limit = self.metadata_limit.clone();
+        
+        // Clear any previous errors when starting
+        state.lock().unwrap().last_error = None;
+        
+        let api_factory = self.api_factory.clone();
+        self.workers.spawn(move || {
+            let config = if let Some(key) = api_key {
+                PixelDrainConfig::default().with_api_key(key)
+            } else {
+                PixelDrainConfig::default()
+            };
+            
+            let client = match api_factory(config) {
+                Ok(client) => client,
+                Err(e) => {
+                    let mut state = state.lock().unwrap();
+                    state.last_error = Some(format!("Failed to create client: {}", e));
+                    *files_loading.lock().unwrap() = false;
+                    return;
+                }
+            };
+            
+            // Add retry logic similar to Lists tab functions
+            const MAX_RETRIES: usize = 3;
+            const RETRY_DELAY: std::time::Duration = std::time::Duration::from_secs(3);
+            
+            let mut last_error = None;
+            
+            for attempt in 1..=MAX_RETRIES {
+                let response = {
+                    let _permit = metadata_limit.acquire();
+                    client.get_user_files()
+                };
+                match response {
+                    Ok(response) => {
+                        let mut state = state.lock().unwrap();
+                        state.replace_file_list(response.files.clone());
+                        state.last_error = None;
+                        // Prefetch thumbnails for all files with a thumbnail_href
+                        let _thumbnail_cache: HashMap<String, Vec<u8>> = HashMap::new();
+                        for file in &response.files {
+                            if !file.thumbnail_href.is_empty()
+                                && let Ok(bytes) = reqwest::blocking::get(&file.thumbnail_href).and_then(|r| r.bytes())
+                                && let Ok(img) = image::load_from_memory(&bytes)
+                            {
+                                let rgba = img.to_rgba8();
+                                let (w, h) = rgba.dimensions();
+                                let _color_img = egui::ColorImage::from_rgba_unmultiplied([
+                                    w as usize, h as usize
+                                ], &rgba);
+                                // Note: TextureHandle must be created on the UI thread, so here we just cache the bytes or ColorImage if needed
+                                // For now, just store the bytes in a HashMap<String, Vec<u8>> or similar if you want to use it later
+                            }
+                        }
+                        *files_loading.lock().unwrap() = false;
+                        return;
+                    }
+                    Err(e) => {
+                        last_error = Some(e);
+                        
+                        let should_retry = last_error.as_ref().unwrap().is_retryable();
+                        
+                        if should_retry && attempt < MAX_RETRIES {
+                            std::thread::sleep(RETRY_DELAY);
+                            continue;
+                        } else {
+                            break;
+                        }
+                    }
+                }
+            }
+            
+            // If we get here, all retries failed
+            let mut state = state.lock().unwrap();
+            let error = last_error.unwrap();
+            state.note_auth_failure(&error);
+            state.last_error = Some(format!("Failed to list files after {} attempts: {}", 
+                MAX_RETRIES, error));
+            *files_loading.lock().unwrap() = false;
+        });
+    }
+
+    fn delete_file(&self, file_id: &str) {
+        // Set loading state
+        *self.file_delete_loading.lock().unwrap() = true;
+        
+        // Get API key with settings priority
+        let api_key = self.get_api_key();
+
+        let state = self.state.clone();
+        let file_id = file_id.to_string();
+        let file_delete_loading = self.file_delete_loading.clone();
+        let workers = self.workers.clone();
+
+        // Clear any previous errors when starting
+        state.lock().unwrap().last_error = None;
+
+        let api_factory = self.api_factory.clone();
+        self.workers.spawn(move || {
+            let start_time = Instant::now();
+            
+            let config = if let Some(key) = api_key.clone() {
+                PixelDrainConfig::default().with_api_key(key)
+            } else {
+                PixelDrainConfig::default()
+            };
+
+            let client = match api_factory(config) {
+                Ok(client) => client,
+                Err(e) => {
+                    let mut state = state.lock().unwrap();
+                    state.last_error = Some(format!("Failed to create client: {}", e));
+                    *file_delete_loading.lock().unwrap() = false;
+                    return;
+                }
+            };
+
+            // Add retry logic similar to Lists tab functions
+            const MAX_RETRIES: usize = 3;
+            const RETRY_DELAY: std::time::Duration = std::time::Duration::from_secs(3);
+            
+            let mut last_error = None;
+            
+            for attempt in 1..=MAX_RETRIES {
+                                    match client.delete_file(&file_id) {
+                        Ok(_) => {
+                            let _duration = start_time.elapsed();
+                            {
+                            let mut state = state.lock().unwrap();
+                            state.last_error = None;
+
+                            state.last_operation_time = Some(chrono::Utc::now());
+                        } // Release lock here
+                        
+                        *file_delete_loading.lock().unwrap() = false;
+                        
+                        // Refresh the file list after successful deletion
+                        let state_clone = state.clone();
+                        let api_key_clone = api_key.clone();
+                        let api_factory = api_factory.clone();
+                        workers.spawn(move || {
+                            thread::sleep(std::time::Duration::from_millis(500)); // Small delay
+                            
+                            let config = if let Some(key) = api_key_clone {
+                                PixelDrainConfig::default().with_api_key(key)
+                            } else {
+                                PixelDrainConfig::default()
+                            };
+                            
+                            if let Ok(client) = api_factory(config)
+                                && let Ok(response) = client.get_user_files()
+                            {
+                                let mut state = state_clone.lock().unwrap();
+                                state.replace_file_list(response.files);
+                            }
+                        });
+                        return;
+                    }
+                    Err(e) => {
+                        last_error = Some(e);
+                        
+                        let should_retry = last_error.as_ref().unwrap().is_retryable();
+                        
+                        if should_retry && attempt < MAX_RETRIES {
+                            std::thread::sleep(RETRY_DELAY);
+                            continue;
+                        } else {
+                            break;
+                        }
+                    }
+                }
+            }
+            
+            // If we get here, all retries failed
             let duration = start_time.elapsed();
             let error_msg = last_error.unwrap();
             let mut state = state.lock().unwrap();
             state.last_error = Some(format!("Failed to delete file after {} attempts: {} (took {:?})", 
                 MAX_RETRIES, error_msg, duration));
 
-            *file_delete_loading.lock().unwrap() = false;
+            *file_delete_loading.lock().unwrap() = false;
+        });
+    }
+
+    /// Delete several files one after another, reporting (done, total) progress
+    /// Back up every file of the account with a manifest into a folder
+    fn render_account_export(&mut self, ui: &mut egui::Ui) {
+        let progress = *self.account_export_progress.lock().unwrap();
+        egui::CollapsingHeader::new(t!("account-export")).id_salt("account_export").show(ui, |ui| {
+            ui.add(egui::Label::new(egui::RichText::new(t!("account-export-note")).small()).wrap());
+            ui.horizontal(|ui| {
+                ui.label(t!("account-export-concurrency"));
+                ui.add(egui::DragValue::new(&mut self.account_export_concurrency).range(1..=MAX_UPLOAD_CONCURRENCY));
+                if progress.is_none()
+                    && ui.button(t!("account-export-button")).clicked()
+                    && let Some(folder) = FileDialog::new().pick_folder()
+                {
+                    self.start_account_export(folder);
+                }
+            });
+            if let Some((done, total)) = progress {
+                ui.label(t!("account-export-progress", done = done, total = total));
+                ui.add(egui::ProgressBar::new(done as f32 / total.max(1) as f32).show_percentage());
+                ui.ctx().request_repaint_after(std::time::Duration::from_millis(100));
+            }
+            match self.account_export_result.lock().unwrap().as_ref() {
+                Some(Ok(summary)) => {
+                    ui.label(t!("account-export-done", count = summary.saved, folder = summary.folder.display().to_string()));
+                    if summary.failed > 0 {
+                        ui.colored_label(egui::Color32::YELLOW, t!("account-export-failed", count = summary.failed));
+                    }
+                }
+                Some(Err(e)) => {
+                    ui.colored_label(egui::Color32::RED, e);
+                }
+                None => {}
+            }
+        });
+    }
+
+    fn start_account_export(&mut self, folder: PathBuf) {
+        let api_key = self.get_api_key();
+        let state = self.state.clone();
+        let progress = self.account_export_progress.clone();
+        let result = self.account_export_result.clone();
+        let concurrency = self.account_export_concurrency.clamp(1, MAX_UPLOAD_CONCURRENCY);
+        let metadata_limit = self.metadata_limit.clone();
+        let workers = self.workers.clone();
+        let transfer = self.events.start(Direction::Download, "Account backup");
+        
+        *progress.lock().unwrap() = Some((0, 0));
+        *result.lock().unwrap() = None;
+        
+        let api_factory = self.api_factory.clone();
+        self.workers.spawn(move || {
+            let config = if let Some(key) = api_key {
+                PixelDrainConfig::default().with_api_key(key)
+            } else {
+                PixelDrainConfig::default()
+            };
+            let reporter = transfer.progress();
+            let on_progress = |done: usize, total: usize| {
+                *progress.lock().unwrap() = Some((done, total));
+                reporter.report(done as f32 / total.max(1) as f32);
+            };
+            let outcome = api_factory(config).and_then(|client| {
+                Self::export_account(client.as_ref(), &folder, concurrency, &metadata_limit, &state, &workers, &on_progress)
+            });
+            let name = "Account backup".to_string();
+            let event = match &outcome {
+                Ok(_) => TransferOutcome::DownloadFinished { name, path: folder.display().to_string() },
+                Err(e) => {
+                    state.lock().unwrap().report_error(&t!("error-context-account-backup"), e);
+                    TransferOutcome::DownloadFailed { name, error: e.to_string() }
+                }
+            };
+            *result.lock().unwrap() = Some(outcome.map_err(|e| e.to_string()));
+            *progress.lock().unwrap() = None;
+            transfer.end(event);
+        });
+    }
+
+    /// Download every file of the account into the files folder of `folder`,
+    /// `concurrency` at a time, and write the manifest next to it
+    fn export_account(
+        client: &dyn PixelDrainApi,
+        folder: &Path,
+        concurrency: usize,
+        metadata_limit: &Limit,
+        state: &Arc<Mutex<AppState>>,
+        workers: &Workers,
+        on_progress: &(dyn Fn(usize, usize) + Sync),
+    ) -> Result<backup::Summary, pixeldrain_api::PixelDrainError> {
+        let files = {
+            let _permit = metadata_limit.acquire();
+            client.get_user_files()?.files
+        };
+        let list_ids: Vec<String> = {
+            let _permit = metadata_limit.acquire();
+            client.get_user_lists()?.lists.into_iter().map(|list| list.id).collect()
+        };
+        let mut lists = Vec::new();
+        for id in list_ids {
+            let _permit = metadata_limit.acquire();
+            lists.push(backup::BackedUpList::of(&client.get_list(&id)?));
+        }
+        
+        let files_dir = folder.join(backup::FILES_DIR);
+        fs::create_dir_all(&files_dir)?;
+        let names = backup::local_names(&files);
+        let total = files.len();
+        on_progress(0, total);
+        let next = std::sync::atomic::AtomicUsize::new(0);
+        let done = std::sync::atomic::AtomicUsize::new(0);
+        let errors: Mutex<Vec<Option<String>>> = Mutex::new(vec![None; total]);
+        thread::scope(|scope| {
+            for _ in 0..concurrency.min(total) {
+                scope.spawn(|| loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let (Some(file), Some(name)) = (files.get(index), names.get(index)) else {
+                        break;
+                    };
+                    let save_path = files_dir.join(name);
+                    let result = if workers.is_shutting_down() {
+                        Err("Stopped before it was downloaded".to_string())
+                    } else if save_path.metadata().is_ok_and(|metadata| metadata.len() == file.size) {
+                        // Saved by an earlier backup into this folder
+                        Ok(())
+                    } else {
+                        let result = client.download_file(&file.id, &save_path, None);
+                        let mut state = state.lock().unwrap();
+                        match &result {
+                            Ok(()) => state.download_usage.record(file.size),
+                            Err(e) => state.download_usage.record_error(e),
+                        }
+                        state.settings_dirty = true;
+                        drop(state);
+                        if result.is_err() {
+                            let _ = fs::remove_file(&save_path);
+                        }
+                        result.map_err(|e| e.to_string())
+                    };
+                    errors.lock().unwrap()[index] = result.err();
+                    on_progress(done.fetch_add(1, Ordering::Relaxed) + 1, total);
+                });
+            }
+        });
+        
+        let errors = errors.into_inner().unwrap();
+        let failed = errors.iter().filter(|error| error.is_some()).count();
+        let manifest = backup::Manifest {
+            created: Utc::now(),
+            base_url: client.base_url().to_string(),
+            files: files
+                .into_iter()
+                .zip(names)
+                .zip(errors)
+                .map(|((info, name), error)| backup::BackedUpFile { info, path: format!("{}/{}", backup::FILES_DIR, name), error })
+                .collect(),
+            lists,
+        };
+        let json = serde_json::to_string_pretty(&manifest).map_err(std::io::Error::other)?;
+        fs::write(folder.join(backup::MANIFEST_NAME), json)?;
+        Ok(backup::Summary { folder: folder.to_path_buf(), saved: total - failed, failed })
+    }
+
+    /// Folders kept in sync with the account, the rules of the pair shown, and
+    /// what a sync of it would do
+    fn render_folder_sync(&mut self, ui: &mut egui::Ui) {
+        let progress = *self.sync_progress.lock().unwrap();
+        egui::CollapsingHeader::new(t!("folder-sync")).id_salt("folder_sync").show(ui, |ui| {
+            ui.add(egui::Label::new(egui::RichText::new(t!("folder-sync-note")).small()).wrap());
+            let pairs = self.state.lock().unwrap().sync_pairs.clone();
+            let lists = self.lists.lock().unwrap().clone();
+            let target_name = |list: &Option<String>| match list {
+                Some(id) => lists.iter().find(|l| &l.id == id).map(|l| l.title.clone()).unwrap_or_else(|| id.clone()),
+                None => t!("folder-sync-whole-account"),
+            };
+            let pair_name = |pair: &sync::SyncPair| t!("folder-sync-pair", folder = pair.folder.as_str(), target = target_name(&pair.list));
+            let mut selected = self.sync_selected;
+            let mut added = None;
+            let mut removed = false;
+            ui.add_enabled_ui(progress.is_none(), |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(t!("folder-sync-folder"));
+                    egui::ComboBox::from_id_salt("sync_pair")
+                        .selected_text(pairs.get(selected).map(pair_name).unwrap_or_else(|| t!("folder-sync-no-folder")))
+                        .show_ui(ui, |ui| {
+                            for (i, pair) in pairs.iter().enumerate() {
+                                ui.selectable_value(&mut selected, i, pair_name(pair));
+                            }
+                        });
+                    if ui.button(t!("folder-sync-add")).clicked() {
+                        added = FileDialog::new().pick_folder();
+                    }
+                    removed = ui.add_enabled(!pairs.is_empty(), egui::Button::new(t!("folder-sync-remove"))).clicked();
+                });
+            });
+            if let Some(folder) = added {
+                self.add_sync_pair(&folder);
+                return;
+            }
+            if removed {
+                self.remove_sync_pair();
+                return;
+            }
+            if selected != self.sync_selected {
+                self.select_sync_pair(selected);
+            }
+            let Some(mut pair) = pairs.get(self.sync_selected).cloned() else {
+                return;
+            };
+            let mut changed = false;
+            let mut refresh = false;
+            ui.add_enabled_ui(progress.is_none(), |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(t!("folder-sync-target"));
+                    egui::ComboBox::from_id_salt("sync_target")
+                        .selected_text(target_name(&pair.list))
+                        .show_ui(ui, |ui| {
+                            changed |= ui.selectable_value(&mut pair.list, None, t!("folder-sync-whole-account")).changed();
+                            for list in &lists {
+                                changed |= ui.selectable_value(&mut pair.list, Some(list.id.clone()), &list.title).changed();
+                            }
+                        });
+                    let loading = *self.lists_loading.lock().unwrap();
+                    refresh = ui.add_enabled(!loading, egui::Button::new("⟳")).on_hover_text(t!("upload-reload-lists")).clicked();
+                });
+            });
+            if refresh {
+                self.refresh_lists();
+            }
+            let rules = &mut pair.rules;
+            ui.horizontal(|ui| {
+                let mut capped = rules.max_file_size.is_some();
+                if ui.checkbox(&mut capped, t!("folder-sync-max-size")).changed() {
+                    rules.max_file_size = capped.then_some(1 << 30);
+                    changed = true;
+                }
+                if let Some(max) = rules.max_file_size
+                    && let Some(max) = Self::size_field(ui, "sync-max-size", max)
+                {
+                    rules.max_file_size = Some(max);
+                    changed = true;
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label(t!("folder-sync-include"));
+                changed |= Self::extensions_field(ui, "sync-include", &mut rules.include_extensions);
+                ui.label(t!("folder-sync-exclude"));
+                changed |= Self::extensions_field(ui, "sync-exclude", &mut rules.exclude_extensions);
+            });
+            changed |= ui.checkbox(&mut rules.propagate_deletes, t!("folder-sync-propagate-deletes"))
+                .on_hover_text(t!("folder-sync-propagate-deletes-tooltip"))
+                .changed();
+            ui.horizontal(|ui| {
+                ui.label(t!("folder-sync-conflicts"));
+                changed |= ui.radio_value(&mut rules.conflict_policy, sync::ConflictPolicy::NewestWins, t!("folder-sync-newest-wins")).changed();
+                changed |= ui.radio_value(&mut rules.conflict_policy, sync::ConflictPolicy::Ask, t!("folder-sync-ask")).changed();
+            });
+            if changed {
+                if let Some(stored) = self.state.lock().unwrap().sync_pairs.get_mut(self.sync_selected) {
+                    // Files uploaded for the old target aren't this one's to delete
+                    if stored.list != pair.list {
+                        stored.uploaded.clear();
+                    }
+                    stored.list = pair.list;
+                    stored.rules = pair.rules;
+                }
+                self.save_preferences();
+                // The plan was for the old rules
+                *self.sync_plan.lock().unwrap() = None;
+            }
+
+            ui.horizontal(|ui| {
+                let idle = progress.is_none();
+                if ui.add_enabled(idle, egui::Button::new(t!("folder-sync-preview"))).clicked() {
+                    self.start_folder_sync(false);
+                }
+                if ui.add_enabled(idle, egui::Button::new(t!("folder-sync-run"))).clicked() {
+                    self.start_folder_sync(true);
+                }
+            });
+            if let Some((done, total)) = progress {
+                ui.label(t!("folder-sync-progress", done = done, total = total));
+                ui.add(egui::ProgressBar::new(done as f32 / total.max(1) as f32).show_percentage());
+                ui.ctx().request_repaint_after(std::time::Duration::from_millis(100));
+            }
+            let mut delete_offered = false;
+            match self.sync_plan.lock().unwrap().as_ref() {
+                Some(Ok(plan)) => {
+                    Self::render_sync_plan(ui, plan);
+                    let count = plan.deletions().len();
+                    delete_offered = count > 0
+                        && ui.add_enabled(progress.is_none(), egui::Button::new(t!("folder-sync-delete-gone", count = count))).clicked();
+                }
+                Some(Err(e)) => {
+                    ui.colored_label(egui::Color32::RED, e);
+                }
+                None => {}
+            }
+            if delete_offered {
+                self.delete_sync_removed_files();
+            }
+            if let Some(report) = self.sync_report.lock().unwrap().as_ref() {
+                ui.label(t!("folder-sync-done", uploaded = report.uploaded));
+                for failure in &report.failures {
+                    ui.colored_label(egui::Color32::YELLOW, failure);
+                }
+            }
+        });
+    }
+
+    fn render_sync_plan(ui: &mut egui::Ui, plan: &sync::Plan) {
+        ui.label(t!("folder-sync-plan", changes = plan.changes(), unchanged = plan.unchanged, skipped = plan.skipped, excluded = plan.excluded));
+        let name = |path: &Path| path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        egui::ScrollArea::vertical().id_salt("sync_plan").max_height(200.0).show(ui, |ui| {
+            for action in &plan.actions {
+                match action {
+                    sync::Action::Upload(path) => ui.label(t!("folder-sync-upload", name = name(path))),
+                    sync::Action::Replace { path, .. } => ui.label(t!("folder-sync-replace", name = name(path))),
+                    sync::Action::Delete { name, .. } => ui.colored_label(egui::Color32::from_rgb(255, 140, 0), t!("folder-sync-delete", name = name.as_str())),
+                    sync::Action::Conflict(path) => ui.colored_label(egui::Color32::YELLOW, t!("folder-sync-conflict", name = name(path))),
+                };
+            }
+        });
+    }
+
+    /// Text field for a list of extensions, like "jpg, png". Returns true once a
+    /// changed list was entered.
+    fn extensions_field(ui: &mut egui::Ui, id_salt: &str, extensions: &mut Vec<String>) -> bool {
+        let id = ui.make_persistent_id(id_salt);
+        let mut text = ui.data_mut(|data| data.get_temp::<String>(id)).unwrap_or_else(|| extensions.join(", "));
+        let response = ui.add(egui::TextEdit::singleline(&mut text).hint_text(t!("folder-sync-extensions-hint")).desired_width(120.0));
+        if response.lost_focus() {
+            ui.data_mut(|data| data.remove::<String>(id));
+            let entered = sync::parse_extensions(&text);
+            if entered != *extensions {
+                *extensions = entered;
+                return true;
+            }
+        }
+        if response.has_focus() {
+            ui.data_mut(|data| data.insert_temp(id, text));
+        }
+        false
+    }
+
+    /// Show the pair of `folder` and the whole account, added with the default
+    /// rules unless it's there already
+    fn add_sync_pair(&mut self, folder: &Path) {
+        let folder = folder.display().to_string();
+        let index = {
+            let mut state = self.state.lock().unwrap();
+            match state.sync_pairs.iter().position(|pair| pair.is(&folder, None)) {
+                Some(index) => index,
+                None => {
+                    state.sync_pairs.push(sync::SyncPair { folder, ..Default::default() });
+                    state.sync_pairs.len() - 1
+                }
+            }
+        };
+        self.save_preferences();
+        self.select_sync_pair(index);
+    }
+
+    /// Stop syncing the pair shown, nothing is deleted from the account
+    fn remove_sync_pair(&mut self) {
+        {
+            let mut state = self.state.lock().unwrap();
+            if self.sync_selected < state.sync_pairs.len() {
+                state.sync_pairs.remove(self.sync_selected);
+            }
+        }
+        self.save_preferences();
+        self.select_sync_pair(self.sync_selected.saturating_sub(1));
+    }
+
+    fn select_sync_pair(&mut self, index: usize) {
+        self.sync_selected = index;
+        // Plan and report were for the pair shown before
+        *self.sync_plan.lock().unwrap() = None;
+        *self.sync_report.lock().unwrap() = None;
+    }
+
+    /// Delete the files the plan has gone from the folder the same way as ones
+    /// deleted by hand, with the confirmation and the undo window
+    fn delete_sync_removed_files(&mut self) {
+        let ids: Vec<String> = {
+            let mut plan = self.sync_plan.lock().unwrap();
+            let Some(Ok(plan)) = plan.as_mut() else {
+                return;
+            };
+            let ids = plan.deletions().into_iter().map(|(id, _)| id).collect();
+            plan.actions.retain(|action| !matches!(action, sync::Action::Delete { .. }));
+            ids
+        };
+        if !ids.is_empty() {
+            self.request_delete(undo::Deletion::Files(ids));
+        }
+    }
+
+    /// Compare the folder of the pair shown with its target, and when `apply` is
+    /// set upload what changed. Either way the plan is kept to be shown, files gone
+    /// from the folder are only deleted when the user asks for it from there.
+    fn start_folder_sync(&mut self, apply: bool) {
+        let api_key = self.get_api_key();
+        let state = self.state.clone();
+        let Some(pair) = state.lock().unwrap().sync_pairs.get(self.sync_selected).cloned() else {
+            return;
+        };
+        let folder = PathBuf::from(&pair.folder);
+        let lists = self.lists.clone();
+        let plan_slot = self.sync_plan.clone();
+        let progress = self.sync_progress.clone();
+        let report_slot = self.sync_report.clone();
+        let metadata_limit = self.metadata_limit.clone();
+        let workers = self.workers.clone();
+        let transfer = apply.then(|| self.events.start(Direction::Upload, folder.display().to_string()));
+        
+        *progress.lock().unwrap() = Some((0, 0));
+        *plan_slot.lock().unwrap() = None;
+        *report_slot.lock().unwrap() = None;
+        
+        let api_factory = self.api_factory.clone();
+        self.workers.spawn(move || {
+            let config = if let Some(key) = api_key {
+                PixelDrainConfig::default().with_api_key(key)
+            } else {
+                PixelDrainConfig::default()
+            };
+            let planned = api_factory(config).and_then(|client| {
+                let remote = {
+                    let _permit = metadata_limit.acquire();
+                    match &pair.list {
+                        Some(list) => client.get_list(list)?.files.into_iter().map(|file| file.file_info).collect(),
+                        None => client.get_user_files()?.files,
+                    }
+                };
+                let uploaded = {
+                    let mut state = state.lock().unwrap();
+                    match state.sync_pairs.iter_mut().find(|stored| stored.is(&pair.folder, pair.list.as_deref())) {
+                        Some(stored) => {
+                            // Forget the ones that were deleted or taken out of the list meanwhile
+                            stored.uploaded.retain(|id| remote.iter().any(|info| &info.id == id));
+                            stored.uploaded.clone()
+                        }
+                        None => Vec::new(),
+                    }
+                };
+                let local = sync::local_files(&folder)?;
+                let plan = sync::plan(&local, &remote, &uploaded, &pair.rules, |path| pixeldrain_api::sha256_file(path).ok());
+                Ok((client, plan))
+            });
+            let (client, plan) = match planned {
+                Ok(planned) => planned,
+                Err(e) => {
+                    state.lock().unwrap().note_auth_failure(&e);
+                    *plan_slot.lock().unwrap() = Some(Err(format!("Folder sync failed: {}", e)));
+                    *progress.lock().unwrap() = None;
+                    return;
+                }
+            };
+            *plan_slot.lock().unwrap() = Some(Ok(plan.clone()));
+            if let Some(transfer) = transfer {
+                let reporter = transfer.progress();
+                let on_progress = |done: usize, total: usize| {
+                    *progress.lock().unwrap() = Some((done, total));
+                    reporter.report(done as f32 / total.max(1) as f32);
+                };
+                let report = Self::apply_sync_plan(client.as_ref(), &pair, &plan, &state, &lists, &workers, &on_progress);
+                if let Ok(files) = client.get_user_files() {
+                    state.lock().unwrap().replace_file_list(files.files);
+                }
+                let name = folder.file_name().unwrap_or_default().to_string_lossy().to_string();
+                transfer.end(if report.failures.is_empty() {
+                    TransferOutcome::UploadFinished { name, url: folder.display().to_string() }
+                } else {
+                    TransferOutcome::UploadFailed { name, error: report.failures.join("; ") }
+                });
+                *report_slot.lock().unwrap() = Some(report);
+            }
+            *progress.lock().unwrap() = None;
+        });
+    }
+
+    /// Upload and replace files as `plan` says, new files are put in the list of
+    /// the pair. Deletions wait for the user and conflicts are left alone.
+    #[allow(clippy::too_many_arguments)]
+    fn apply_sync_plan(
+        client: &dyn PixelDrainApi,
+        pair: &sync::SyncPair,
+        plan: &sync::Plan,
+        state: &Arc<Mutex<AppState>>,
+        lists: &Mutex<Vec<pixeldrain_api::ListInfo>>,
+        workers: &Workers,
+        on_progress: &dyn Fn(usize, usize),
+    ) -> sync::Report {
+        let mut report = sync::Report::default();
+        let total = plan.uploads();
+        let mut done = 0;
+        on_progress(done, total);
+        let upload = |path: &Path| -> Result<(), pixeldrain_api::PixelDrainError> {
+            let response = client.upload_file(path, None)?;
+            {
+                let mut state = state.lock().unwrap();
+                if let Some(stored) = state.sync_pairs.iter_mut().find(|stored| stored.is(&pair.folder, pair.list.as_deref())) {
+                    stored.uploaded.push(response.id.clone());
+                }
+                state.settings_dirty = true;
+                state.record_upload(UploadHistoryEntry {
+                    url: response.get_file_url(client.base_url()),
+                    id: response.id.clone(),
+                    filename: path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+                    size: path.metadata().map(|m| m.len()).unwrap_or(0),
+                    timestamp: Utc::now(),
+                    verified: None,
+                    error: None,
+                    anonymous: false,
+                });
+            }
+            // In the list before an old version goes
+            Self::add_uploads_to_list(client, lists, pair.list.as_deref(), &[response.id])
+        };
+        for action in &plan.actions {
+            if workers.is_shutting_down() {
+                break;
+            }
+            let (name, result) = match action {
+                sync::Action::Upload(path) => (path.display().to_string(), upload(path).map(|_| report.uploaded += 1)),
+                sync::Action::Replace { path, old_id } => {
+                    // The new version is up before the old one goes
+                    let result = upload(path).and_then(|_| {
+                        report.uploaded += 1;
+                        client.delete_file(old_id)
+                    });
+                    if result.is_ok()
+                        && let Some(stored) = state.lock().unwrap().sync_pairs.iter_mut().find(|stored| stored.is(&pair.folder, pair.list.as_deref()))
+                    {
+                        stored.uploaded.retain(|id| id != old_id);
+                    }
+                    (path.display().to_string(), result)
+                }
+                sync::Action::Delete { .. } | sync::Action::Conflict(_) => continue,
+            };
+            if let Err(e) = result {
+                state.lock().unwrap().note_auth_failure(&e);
+                report.failures.push(format!("{}: {}", name, e));
+            }
+            done += 1;
+            on_progress(done, total);
+        }
+        report
+    }
+
+    /// Delete the files one by one in a worker. With `stop_at_shutdown` the
+    /// files left when the app closes are kept.
+    fn start_batch_delete(&self, file_ids: Vec<String>, stop_at_shutdown: bool) {
+        let api_key = self.get_api_key();
+        let state = self.state.clone();
+        let progress = self.batch_delete_progress.clone();
+        let total = file_ids.len();
+        let workers = self.workers.clone();
+        
+        *progress.lock().unwrap() = Some((0, total));
+        state.lock().unwrap().last_error = None;
+        
+        let api_factory = self.api_factory.clone();
+        self.workers.spawn(move || {
+            let config = if let Some(key) = api_key {
+                PixelDrainConfig::default().with_api_key(key)
+            } else {
+                PixelDrainConfig::default()
+            };
+            let client = match api_factory(config) {
+                Ok(client) => client,
+                Err(e) => {
+                    state.lock().unwrap().last_error = Some(format!("Failed to create client: {}", e));
+                    *progress.lock().unwrap() = None;
+                    return;
+                }
+            };
+            
+            let mut deleted = Vec::new();
+            let mut failures = Vec::new();
+            for (index, file_id) in file_ids.iter().enumerate() {
+                if stop_at_shutdown && workers.is_shutting_down() {
+                    break;
+                }
+                let result = Self::retry_pixeldrain_operation(
+                    || client.delete_file(file_id),
+                    3,
+                    std::time::Duration::from_secs(3),
+                );
+                match result {
+                    Ok(()) => deleted.push(file_id.clone()),
+                    Err(e) => failures.push(format!("{}: {}", file_id, e)),
+                }
+                *progress.lock().unwrap() = Some((index + 1, total));
+            }
+            
+            {
+                let mut state = state.lock().unwrap();
+                state.file_list.retain(|f| !deleted.contains(&f.id));
+                state.last_operation_time = Some(Utc::now());
+                if !failures.is_empty() {
+                    state.last_error = Some(format!(
+                        "Deleted {} of {} files. Failed: {}",
+                        deleted.len(),
+                        total,
+                        failures.join("; ")
+                    ));
+                }
+            }
+            *progress.lock().unwrap() = None;
+        });
+    }
+
+    fn save_settings(&self, api_key: String, download_location: String) {
+        let mut state = self.state.lock().unwrap();
+        state.api_key = api_key;
+        state.download_location = download_location;
+        state.last_error = None;
+        state.api_key_rejected = false;
+        
+        // Try to save settings to file
+        if let Err(e) = self.persist_settings(&mut state) {
+            state.last_error = Some(format!("Failed to save settings: {}", e));
+        } else {
+            state.last_error = None;
+        }
+    }
+    
+    fn save_theme_settings(&self, dark_mode: bool) {
+        let mut state = self.state.lock().unwrap();
+        state.dark_mode = dark_mode;
+        
+        // Try to save settings to file
+        if let Err(e) = self.persist_settings(&mut state) {
+            state.last_error = Some(format!("Failed to save theme settings: {}", e));
+        } else {
+            state.last_error = None;
+        }
+    }
+    
+    /// Persist the current state after a preference toggle in the UI
+    fn save_preferences(&self) {
+        let mut state = self.state.lock().unwrap();
+        if let Err(e) = self.persist_settings(&mut state) {
+            state.last_error = Some(format!("Failed to save settings: {}", e));
+        }
+    }
+    
+    fn persist_settings(&self, state: &mut AppState) -> Result<(), Box<dyn std::error::Error>> {
+        use std::fs;
+        use serde_json;
+        
+        state.sync_active_profile();
+        
+        // API keys go to the keyring unless plaintext storage was chosen. If that
+        // fails, keep the old settings file rather than losing the key.
+        if !state.plaintext_api_key {
+            let keys = state.profiles.iter().map(|p| (p.name.clone(), p.api_key.clone())).collect();
+            self.secrets.sync_api_keys(&keys, &mut state.keyring_keys).map_err(|e| {
+                format!(
+                    "Couldn't store the API key in the system keyring ({}). \
+                     Enable \"Store API key in the settings file\" to save it in plaintext instead.",
+                    e
+                )
+            })?;
+        }
+        let settings = Self::settings_json(state, state.plaintext_api_key)?;
+        
+        // Create settings directory if it doesn't exist
+        if let Some(settings_dir) = self.settings_path.parent() {
+            fs::create_dir_all(settings_dir)?;
+        }
+        
+        // Save settings to JSON file
+        let settings_data = serde_json::to_string_pretty(&settings)?;
+        fs::write(&self.settings_path, settings_data)?;
+        
+        Ok(())
+    }
+    
+    fn get_default_download_location() -> String {
+        use std::env;
+        
+        #[cfg(target_os = "windows")]
+        {
+            // Windows: %USERPROFILE%\Downloads
+            if let Ok(userprofile) = env::var("USERPROFILE") {
+                return format!("{}\\Downloads", userprofile);
+            }
+        }
+        
+        #[cfg(target_os = "macos")]
+        {
+            // macOS: /Users/$USER/Downloads
+            if let Ok(home) = env::var("HOME") {
+                return format!("{}/Downloads", home);
+            }
+        }
+        
+        #[cfg(target_os = "linux")]
+        {
+            // Linux: $HOME/Downloads
+            if let Ok(home) = env::var("HOME") {
+                return format!("{}/Downloads", home);
+            }
+        }
+        
+        // Fallback: current directory
+        ".".to_string()
+    }
+
+    fn default_settings_path() -> PathBuf {
+        directories::ProjectDirs::from("com", "pixeldrain", "client")
+            .map(|proj_dirs| proj_dirs.config_dir().join("settings.json"))
+            .unwrap_or_else(|| PathBuf::from("settings.json"))
+    }
+
+    fn load_settings(&mut self) {
+        use std::fs;
+        use serde_json;
+        
+        let mut legacy_history = (Vec::new(), Vec::new());
+        if let Ok(data) = fs::read_to_string(&self.settings_path) {
+            if let Ok(mut loaded_state) = serde_json::from_str::<AppState>(&data) {
+                legacy_history = (
+                    std::mem::take(&mut loaded_state.legacy_upload_history),
+                    std::mem::take(&mut loaded_state.legacy_download_history),
+                );
+                let mut state = self.state.lock().unwrap();
+                Self::apply_loaded_settings(&mut state, loaded_state, &self.secrets);
+                // Don't overwrite history and other runtime data
+            } else {
+                // If settings file is corrupted, set default download location
+                let mut state = self.state.lock().unwrap();
+                state.download_location = Self::get_default_download_location();
+            }
+        } else {
+            // If no settings file exists, set default download location
+            let mut state = self.state.lock().unwrap();
+            state.download_location = Self::get_default_download_location();
+        }
+        let migrate_history = self.open_storage(legacy_history);
+        let migrate_keys = {
+            let mut state = self.state.lock().unwrap();
+            state.ensure_profile();
+            state.load_keyring_keys(&self.secrets)
+        };
+        // Move API keys and history saved by older versions out of settings.json
+        if migrate_keys || migrate_history {
+            self.save_preferences();
+        }
+        self.apply_log_settings();
+    }
+
+    /// Hand the log filter and file setting to the logger
+    fn apply_log_settings(&self) {
+        let mut state = self.state.lock().unwrap();
+        // RUST_LOG wins over the setting, for debugging a build without opening settings
+        if env::var_os("RUST_LOG").is_none()
+            && let Err(e) = logging::set_filter(&state.log_filter)
+        {
+            state.last_error = Some(e);
+        }
+        if let Err(e) = logging::set_file_logging(state.log_to_file) {
+            state.last_error = Some(format!("Failed to open the log file: {}", e));
+        }
+    }
+    
+    /// Open the configured storage, importing the history that older versions kept
+    /// in settings.json. Returns true when settings.json should be rewritten without it.
+    fn open_storage(&self, (uploads, downloads): (Vec<UploadHistoryEntry>, Vec<DownloadHistoryEntry>)) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let mut store = match state.storage_backend.open() {
+            Ok(store) => store,
+            Err(e) => {
+                state.last_error = Some(format!("Failed to open the history storage, history won't be kept: {}", e));
+                return false;
+            }
+        };
+        let mut migrate = !uploads.is_empty() || !downloads.is_empty();
+        if migrate && let Err(e) = store.import(&uploads, &downloads) {
+            state.last_error = Some(format!("Failed to import the history from settings.json: {}", e));
+            migrate = false;
+        }
+        state.storage = store;
+        state.restore_cached_file_list();
+        migrate
+    }
+    
+    /// Move the history to another backend, keeping what the new one already had
+    fn switch_storage_backend(&mut self, backend: StorageBackend) -> Result<(), String> {
+        let mut store = backend.open().map_err(|e| e.to_string())?;
+        let mut state = self.state.lock().unwrap();
+        let history = Self::all_history(&state)?;
+        store.import(&history.uploads, &history.downloads).map_err(|e| e.to_string())?;
+        state.storage = store;
+        state.storage_backend = backend;
+        drop(state);
+        self.save_preferences();
+        Ok(())
+    }
+    
+    /// The whole upload and download history, oldest first
+    fn all_history(state: &AppState) -> Result<app_data::History, String> {
+        let all = HistoryViewOptions::default();
+        let mut uploads: Vec<UploadHistoryEntry> =
+            state.storage.uploads(&all, history::NO_LIMIT).map_err(|e| e.to_string())?.into_iter().map(|(e, _)| e).collect();
+        let mut downloads: Vec<DownloadHistoryEntry> =
+            state.storage.downloads(&all, history::NO_LIMIT).map_err(|e| e.to_string())?.into_iter().map(|(e, _)| e).collect();
+        // Views are newest first, import oldest first
+        uploads.reverse();
+        downloads.reverse();
+        Ok(app_data::History { uploads, downloads })
+    }
+    
+    /// Copy the persisted settings of a loaded state, leaving history and other runtime data alone
+    fn apply_loaded_settings(state: &mut AppState, loaded_state: AppState, secrets: &secrets::SecretStore) {
+        state.api_key = loaded_state.api_key;
+        // Use loaded download location if it's not empty, otherwise use default
+        if !loaded_state.download_location.is_empty() {
+            state.download_location = loaded_state.download_location;
+        } else {
+            state.download_location = Self::get_default_download_location();
+        }
+        // Load theme preference
+        state.dark_mode = loaded_state.dark_mode;
+        state.high_contrast = loaded_state.high_contrast;
+        state.link_format = loaded_state.link_format;
+        state.auto_link_type = loaded_state.auto_link_type;
+        state.direct_link_categories = loaded_state.direct_link_categories;
+        state.archive_format = loaded_state.archive_format;
+        state.parallel_compression = loaded_state.parallel_compression;
+        state.exclude_patterns = loaded_state.exclude_patterns;
+        state.verify_uploads = loaded_state.verify_uploads;
+        state.check_before_large_uploads = loaded_state.check_before_large_uploads;
+        state.skip_duplicate_uploads = loaded_state.skip_duplicate_uploads;
+        state.encrypt_uploads = loaded_state.encrypt_uploads;
+        state.encryption_key_id = loaded_state.encryption_key_id;
+        state.remember_encryption_passphrase = loaded_state.remember_encryption_passphrase;
+        if state.remember_encryption_passphrase {
+            state.encryption_passphrase = secrets.load_passphrase().unwrap_or_default();
+        }
+        state.upload_concurrency = loaded_state.upload_concurrency;
+        state.metadata_concurrency = loaded_state.metadata_concurrency;
+        state.stall_timeout_secs = loaded_state.stall_timeout_secs;
+        state.delete_undo_secs = loaded_state.delete_undo_secs;
+        state.confirm_deletes = loaded_state.confirm_deletes;
+        state.check_for_updates = loaded_state.check_for_updates;
+        state.log_filter = loaded_state.log_filter;
+        state.log_to_file = loaded_state.log_to_file;
+        state.key_registry = loaded_state.key_registry;
+        state.infer_extensions = loaded_state.infer_extensions;
+        state.join_split_parts = loaded_state.join_split_parts;
+        state.files_grid_view = loaded_state.files_grid_view;
+        state.window_label = loaded_state.window_label;
+        state.icon_tint = loaded_state.icon_tint;
+        state.base_url = loaded_state.base_url;
+        state.language = loaded_state.language;
+        state.storage_backend = loaded_state.storage_backend;
+        i18n::set_language(state.language);
+        state.desktop_notifications = loaded_state.desktop_notifications;
+        state.notification_sinks = loaded_state.notification_sinks;
+        state.sync_pairs = loaded_state.sync_pairs;
+        state.prevent_sleep = loaded_state.prevent_sleep;
+        state.upload_history_view = loaded_state.upload_history_view;
+        state.download_history_view = loaded_state.download_history_view;
+        state.download_usage = loaded_state.download_usage;
+        state.usage_stats = loaded_state.usage_stats;
+        state.monthly_download_allowance = loaded_state.monthly_download_allowance;
+        state.cache_limit = loaded_state.cache_limit;
+        state.profiles = loaded_state.profiles;
+        state.active_profile = loaded_state.active_profile;
+        state.plaintext_api_key = loaded_state.plaintext_api_key;
+    }
+    
+    /// Settings as written to disk. API keys are left out unless `include_api_keys` is set.
+    fn settings_json(state: &AppState, include_api_keys: bool) -> Result<serde_json::Value, serde_json::Error> {
+        let mut settings = serde_json::to_value(state)?;
+        if !include_api_keys {
+            settings["api_key"] = serde_json::Value::String(String::new());
+            if let Some(profiles) = settings["profiles"].as_array_mut() {
+                for profile in profiles {
+                    profile["api_key"] = serde_json::Value::String(String::new());
+                }
+            }
+        }
+        Ok(settings)
+    }
+    
+    /// The settings as an export file, encrypted when the API keys are included.
+    /// Webhook URLs often carry a token, so they're only included with the keys.
+    fn settings_export_data(&self) -> Result<String, String> {
+        let passphrase = if self.export_include_api_keys {
+            if self.export_passphrase.is_empty() {
+                return Err("Enter a passphrase to export API keys".to_string());
+            }
+            Some(self.export_passphrase.as_str())
+        } else {
+            None
+        };
+        let mut settings = {
+            let mut state = self.state.lock().unwrap();
+            state.sync_active_profile();
+            Self::settings_json(&state, passphrase.is_some()).map_err(|e| e.to_string())?
+        };
+        // Account data doesn't belong in a settings export
+        for key in ["user_info", "last_error", "last_operation_time"] {
+            settings[key] = serde_json::Value::Null;
+        }
+        if passphrase.is_none() {
+            settings["notification_sinks"]["webhook_url"] = serde_json::Value::String(String::new());
+        }
+        settings_export::export(&settings, passphrase)
+    }
+    
+    fn export_settings(&self, path: &Path) -> Result<(), String> {
+        let data = self.settings_export_data()?;
+        fs::write(path, data).map_err(|e| e.to_string())
+    }
+    
+    fn import_settings(&mut self, path: &Path) -> Result<(), String> {
+        let data = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        self.import_settings_data(&data)
+    }
+    
+    /// Replace the current settings with the ones from an export file. Profiles
+    /// exported without API keys keep the keys already in the keyring, and an
+    /// export without a webhook URL keeps the one already set.
+    fn import_settings_data(&mut self, data: &str) -> Result<(), String> {
+        let passphrase = Some(self.export_passphrase.as_str()).filter(|p| !p.is_empty());
+        let settings = settings_export::import(data, passphrase)?;
+        let mut loaded_state: AppState = serde_json::from_value(settings).map_err(|e| format!("Invalid settings: {}", e))?;
+        {
+            let mut state = self.state.lock().unwrap();
+            state.sync_active_profile();
+            let previous_keys: HashMap<String, String> =
+                state.profiles.iter().map(|p| (p.name.clone(), p.api_key.clone())).collect();
+            if loaded_state.notification_sinks.webhook_url.is_empty() {
+                loaded_state.notification_sinks.webhook_url = state.notification_sinks.webhook_url.clone();
+            }
+            Self::apply_loaded_settings(&mut state, loaded_state, &self.secrets);
+            state.ensure_profile();
+            for profile in &mut state.profiles {
+                if profile.api_key.is_empty()
+                    && let Some(key) = previous_keys.get(&profile.name)
+                {
+                    profile.api_key = key.clone();
+                }
+            }
+            if let Some(active) = state.profiles.iter().find(|p| p.name == state.active_profile).cloned() {
+                state.apply_profile(&active);
+            }
+            state.load_keyring_keys(&self.secrets);
+        }
+        self.on_profile_switched();
+        self.apply_log_settings();
+        Ok(())
+    }
+    
+    /// Write the settings, the whole history and the cached data into one archive
+    fn export_app_data(&self, path: &Path) -> Result<(), String> {
+        let settings = self.settings_export_data()?;
+        let (history, cache) = {
+            let state = self.state.lock().unwrap();
+            let cache = state.storage.cache_entries().map_err(|e| e.to_string())?;
+            (Self::all_history(&state)?, cache.into_iter().collect())
+        };
+        let file = fs::File::create(path).map_err(|e| e.to_string())?;
+        app_data::write(std::io::BufWriter::new(file), &app_data::AppData { settings, history, cache })
+    }
+    
+    /// Restore an archive of `export_app_data`: its settings replace the current
+    /// ones, its history and cached data are added to what's here. Returns how
+    /// many history entries were new.
+    fn import_app_data(&mut self, path: &Path) -> Result<usize, String> {
+        let file = fs::File::open(path).map_err(|e| e.to_string())?;
+        let data = app_data::read(std::io::BufReader::new(file))?;
+        // The history goes into the store in use, whichever the other setup used
+        let backend = self.state.lock().unwrap().storage_backend;
+        self.import_settings_data(&data.settings)?;
+        let added = {
+            let mut state = self.state.lock().unwrap();
+            state.storage_backend = backend;
+            let added = state.storage.import(&data.history.uploads, &data.history.downloads).map_err(|e| e.to_string())?;
+            for (key, value) in &data.cache {
+                state.storage.cache_put(key, value).map_err(|e| e.to_string())?;
+            }
+            state.restore_cached_file_list();
+            added
+        };
+        self.save_preferences();
+        Ok(added)
+    }
+    
+    fn render_settings_transfer(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new(t!("export-import-settings")).id_salt("settings_transfer").show(ui, |ui| {
+            ui.checkbox(&mut self.export_include_api_keys, t!("include-api-keys"));
+            ui.horizontal(|ui| {
+                ui.label(t!("passphrase"));
+                ui.add(egui::TextEdit::singleline(&mut self.export_passphrase).password(true).desired_width(160.0));
+            });
+            if self.export_include_api_keys {
+                ui.label(t!("the-export-is-encrypted-with-this"));
+            } else {
+                ui.label(t!("api-keys-are-left-out-the"));
+            }
+            
+            ui.horizontal(|ui| {
+                if ui.button(t!("export-button")).clicked()
+                    && let Some(path) = FileDialog::new()
+                        .set_file_name("pixeldrain-settings.json")
+                        .add_filter("Settings export", &["json"])
+                        .save_file()
+                {
+                    self.settings_transfer_status = Some(
+                        self.export_settings(&path)
+                            .map(|()| format!("✅ Settings exported to {}", path.display())),
+                    );
+                }
+                if ui.button(t!("import-button")).clicked()
+                    && let Some(path) = FileDialog::new().add_filter("Settings export", &["json"]).pick_file()
+                {
+                    self.settings_transfer_status = Some(
+                        self.import_settings(&path).map(|()| "✅ Settings imported".to_string()),
+                    );
+                }
+            });
+            
+            ui.label(t!("app-data-note"));
+            ui.horizontal(|ui| {
+                if ui.button(t!("app-data-export-button")).clicked()
+                    && let Some(path) = FileDialog::new()
+                        .set_file_name(format!("pixeldrain-app-data-{}.zip", Utc::now().format("%Y-%m-%d")))
+                        .add_filter("App data archive", &["zip"])
+                        .save_file()
+                {
+                    self.settings_transfer_status = Some(
+                        self.export_app_data(&path)
+                            .map(|()| format!("✅ App data exported to {}", path.display())),
+                    );
+                }
+                if ui.button(t!("app-data-import-button")).clicked()
+                    && let Some(path) = FileDialog::new().add_filter("App data archive", &["zip"]).pick_file()
+                {
+                    self.settings_transfer_status = Some(
+                        self.import_app_data(&path).map(|added| format!("✅ App data restored, {} history entries added", added)),
+                    );
+                }
+            });
+            match &self.settings_transfer_status {
+                Some(Ok(message)) => {
+                    ui.colored_label(egui::Color32::GREEN, message);
+                }
+                Some(Err(e)) => {
+                    ui.colored_label(egui::Color32::RED, format!("❌ {}", e));
+                }
+                None => {}
+            }
+        });
+    }
+    
+    /// Keep the window title and taskbar icon in sync with the configured window label and tint
+    fn apply_window_identity(&mut self, ctx: &egui::Context) {
+        let identity = {
+            let state = self.state.lock().unwrap();
+            (state.window_label.trim().to_string(), state.icon_tint)
+        };
+        if self.applied_window_identity.as_ref() == Some(&identity) {
+            return;
+        }
+        
+        let (label, tint) = &identity;
+        let title = if label.is_empty() {
+            "PixelDrain".to_string()
+        } else {
+            format!("PixelDrain — {}", label)
+        };
+        ctx.send_viewport_cmd(egui::ViewportCommand::Title(title));
+        
+        // The taskbar icon only needs replacing once a tint has been applied
+        if tint.is_some() || self.applied_window_identity.as_ref().is_some_and(|(_, t)| t.is_some()) {
+            let icon = icon_data_from_png(true).map(|icon| match tint {
+                Some(tint) => tint_icon(icon, *tint),
+                None => icon,
+            });
+            ctx.send_viewport_cmd(egui::ViewportCommand::Icon(icon.map(Arc::new)));
+        }
+        self.applied_window_identity = Some(identity);
+    }
+
+    /// Average progress over every running transfer, None when nothing is running
+    /// Hold off sleep while transfers run, and sleep once they're done when asked to
+    fn update_power_state(&mut self, transferring: bool) {
+        let prevent_sleep = self.state.lock().unwrap().prevent_sleep;
+        self.sleep_inhibitor.update(transferring && prevent_sleep);
+        if self.was_transferring && !transferring && std::mem::take(&mut self.sleep_when_done) {
+            // Let the history and settings reach the disk first
+            self.save_preferences();
+            if let Err(e) = power::sleep_now() {
+                self.state.lock().unwrap().last_error = Some(t!("sleep-failed", error = e));
+            }
+        }
+        self.was_transferring = transferring;
+    }
+
+    /// Follow the running uploads and downloads, and sum up the queue once it's empty
+    fn update_queue(&mut self) {
+        let source = |running: &Mutex<bool>, rate: &Mutex<Option<Progress>>| {
+            (*running.lock().unwrap()).then(|| rate.lock().unwrap().unwrap_or_default())
+        };
+        let sources = [
+            source(&self.upload_thread_running, &self.upload_rate),
+            source(&self.download_thread_running, &self.download_rate),
+        ];
+        let Some(run) = self.queue_meter.update(Instant::now(), &sources) else {
+            return;
+        };
+        // The history has every file of the run, also those of multi-file uploads and lists
+        let (uploads, downloads) = {
+            let state = self.state.lock().unwrap();
+            let options = HistoryViewOptions { since: Some(run.started), ..Default::default() };
+            let uploads: Vec<DateTime<Utc>> = state.storage.uploads(&options, history::NO_LIMIT).unwrap_or_default()
+                .into_iter().filter(|(entry, _)| entry.error.is_none()).map(|(entry, _)| entry.timestamp).collect();
+            let downloads: Vec<DateTime<Utc>> = state.storage.downloads(&options, history::NO_LIMIT).unwrap_or_default()
+                .into_iter().filter(|(entry, _)| entry.error.is_none()).map(|(entry, _)| entry.timestamp).collect();
+            (uploads, downloads)
+        };
+        let files = uploads.len() + downloads.len();
+        if files > 0 {
+            let summary = t!(
+                "queue-summary",
+                files = files,
+                size = self.format_file_size_bytes(run.total_bytes()),
+                duration = format_remaining(run.elapsed)
+            );
+            self.queue_summary = Some((summary, Instant::now()));
+            // Sources are the uploads and the downloads, in that order
+            self.state.lock().unwrap().usage_stats.record(run.bytes[0], run.bytes[1], run.elapsed, &uploads, &downloads);
+            self.save_preferences();
+        }
+    }
+    
+    /// "All transfers done in 4m 10s, around 14:32" while the queue runs
+    fn render_queue_eta(&self, ui: &mut egui::Ui) {
+        let Some(eta) = self.queue_meter.eta() else {
+            return;
+        };
+        let done_at = chrono::Local::now() + chrono::Duration::from_std(eta).unwrap_or_default();
+        ui.label(t!("queue-eta", eta = format_remaining(eta), time = done_at.format("%H:%M").to_string()))
+            .on_hover_text(t!("queue-eta-tooltip", speed = self.format_file_size_bytes(self.queue_meter.throughput() as u64)));
+    }
+    
+    /// What the last queue run did, for a few seconds in the corner of the window
+    fn render_queue_summary(&mut self, ctx: &egui::Context) {
+        const SHOWN_FOR: Duration = Duration::from_secs(8);
+        let Some((summary, shown)) = &self.queue_summary else {
+            return;
+        };
+        if shown.elapsed() >= SHOWN_FOR {
+            self.queue_summary = None;
+            return;
+        }
+        let mut dismissed = false;
+        egui::Area::new(egui::Id::new("queue_summary"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-12.0, -12.0))
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    dismissed = ui.add(egui::Label::new(format!("✅ {}", summary)).sense(egui::Sense::click())).clicked();
+                });
+            });
+        if dismissed {
+            self.queue_summary = None;
+        } else {
+            ctx.request_repaint_after(SHOWN_FOR.saturating_sub(shown.elapsed()));
+        }
+    }
+
+    fn render_sleep_when_done(&mut self, ui: &mut egui::Ui) {
+        ui.checkbox(&mut self.sleep_when_done, t!("sleep-when-transfers-finish"))
+            .on_hover_text(t!("sleep-when-transfers-finish-tooltip"));
+    }
+
+    fn aggregate_transfer_progress(&self) -> Option<f32> {
+        let mut active = Vec::new();
+        if *self.upload_thread_running.lock().unwrap() {
+            active.push(*self.upload_progress.lock().unwrap());
+        }
+        if *self.download_thread_running.lock().unwrap() {
+            active.push(*self.download_progress.lock().unwrap());
+        }
+        if let Some(progress) = *self.files_zip_progress.lock().unwrap() {
+            active.push(progress);
+        }
+        if let Some(progress) = *self.fs_transfer_progress.lock().unwrap() {
+            active.push(progress);
+        }
+        if let Some((done, total)) = *self.batch_delete_progress.lock().unwrap()
+            && total > 0
+        {
+            active.push(done as f32 / total as f32);
+        }
+        
+        if active.is_empty() {
+            None
+        } else {
+            Some(active.iter().sum::<f32>() / active.len() as f32)
+        }
+    }
+
+    fn apply_theme_on_startup(&self, ctx: &egui::Context) {
+        let (dark_mode, high_contrast) = {
+            let state = self.state.lock().unwrap();
+            (state.dark_mode, state.high_contrast)
+        };
+        theme::apply(ctx, dark_mode, high_contrast);
+        // Window icon setting at runtime is not supported in this eframe version
+    }
+
+    /// A row per file of the running multi-file upload
+    fn render_batch_progress(&self, ui: &mut egui::Ui) {
+        let files = self.upload_batch_progress.lock().unwrap();
+        if files.len() < 2 {
+            return;
+        }
+        egui::ScrollArea::vertical().max_height(150.0).id_salt("upload_batch_progress").show(ui, |ui| {
+            for (name, fraction) in files.iter() {
+                ui.horizontal(|ui| {
+                    ui.add(egui::ProgressBar::new(*fraction).desired_width(120.0).show_percentage());
+                    ui.label(name);
+                });
+            }
+        });
+    }
+
+    /// "12.3 MB/s, 2m 14s remaining" for the running transfer
+    fn render_transfer_rate(&self, ui: &mut egui::Ui, rate: &Mutex<Option<Progress>>) {
+        let Some(rate) = *rate.lock().unwrap() else {
+            return;
+        };
+        if rate.speed <= 0.0 {
+            return;
+        }
+        let speed = self.format_file_size_bytes(rate.speed as u64);
+        ui.label(match rate.eta() {
+            Some(eta) => t!("transfer-rate", speed = speed, eta = format_remaining(eta)),
+            None => t!("transfer-rate-no-eta", speed = speed),
+        });
+    }
+
+    fn format_file_size(&self, path: &PathBuf) -> String {
+        if let Ok(metadata) = fs::metadata(path) {
+            self.format_file_size_bytes(metadata.len())
+        } else {
+            "Unknown size".to_string()
+        }
+    }
+
+    fn format_file_size_bytes(&self, bytes: u64) -> String {
+        units::format_size(bytes, i18n::language())
+    }
+
+    fn fetch_user_info(&mut self) {
+        // Set loading state
+        *self.user_info_loading.lock().unwrap() = true;
+        
+        // Get API key with settings priority
+        let api_key = self.get_api_key();
+
+        if let Some(_key) = api_key {
+            let client = self.make_api_client();
+            let state = self.state.clone();
+            let user_info_loading = self.user_info_loading.clone();
+
+            self.workers.spawn(move || {
+                let start_time = Instant::now();
+                let mut last_error = None;
+
+                for attempt in 1..=3 { // Retry up to 3 times
+                    match client.get_user() {
+                        Ok(user_info) => {
+                            let _duration = start_time.elapsed();
+                            {
+                                let mut state = state.lock().unwrap();
+                                state.user_info = Some(user_info);
+                                state.last_error = None;
+                                state.api_key_rejected = false;
+
+                                state.last_operation_time = Some(chrono::Utc::now());
+                            }
+                            *user_info_loading.lock().unwrap() = false;
+                            break;
+                        }
+                        Err(e) => {
+                            last_error = Some(e);
+                            let should_retry = last_error.as_ref().unwrap().is_retryable();
+                            if should_retry && attempt < 3 {
+                                std::thread::sleep(std::time::Duration::from_secs(3)); // Retry after 3 seconds
+                            } else {
+                                break;
+                            }
+                        }
+                    }
+                }
+
+                if let Some(error_msg) = last_error {
+                    let _duration = start_time.elapsed();
+                    let mut state = state.lock().unwrap();
+                    state.note_auth_failure(&error_msg);
+                    state.last_error = Some(format!("Failed to fetch user info after {} attempts: {}", 
+                        3, error_msg));
+
+                }
+                *user_info_loading.lock().unwrap() = false;
+            });
+        } else {
+            self.state.lock().unwrap().last_error = Some("No API key available (check settings or environment variable PIXELDRAIN_API_KEY). Cannot fetch user info.".to_string());
+        }
+    }
+
+    /// Passphrase and key identifier to encrypt uploads with, None when they aren't encrypted
+    fn upload_encryption(&self) -> Result<Option<(String, String)>, String> {
+        let state = self.state.lock().unwrap();
+        if !state.encrypt_uploads {
+            return Ok(None);
+        }
+        if state.encryption_passphrase.is_empty() {
+            return Err(t!("encryption-no-passphrase"));
+        }
+        Ok(Some((state.encryption_passphrase.clone(), state.encryption_key_id.trim().to_string())))
+    }
+
+    /// Upload `path` encrypted as `name`. Progress is that of reading the file,
+    /// so it looks the same as for a plain upload.
+    fn upload_encrypted(
+        client: &dyn PixelDrainApi,
+        path: &Path,
+        name: &str,
+        passphrase: &str,
+        progress: ProgressSender,
+    ) -> Result<UploadResponse, pixeldrain_api::PixelDrainError> {
+        let file = fs::File::open(path)?;
+        let size = file.metadata()?.len();
+        let reader = crypto::Encryptor::new(ProgressReader::new_file(file, size, Some(progress)), passphrase)?;
+        client.upload_stream_put(Box::new(reader), name, None)
+    }
+
+    /// Download an encrypted file to `save_path` decrypted. The partly written
+    /// file is removed when that fails, such as with a wrong passphrase.
+    fn download_decrypted(
+        client: &dyn PixelDrainApi,
+        file_id: &str,
+        save_path: &Path,
+        passphrase: &str,
+        progress: ProgressSender,
+    ) -> Result<(), pixeldrain_api::PixelDrainError> {
+        let file = fs::File::create(save_path)?;
+        let mut decryptor = crypto::Decryptor::new(std::io::BufWriter::new(file), passphrase);
+        let result = client
+            .download_file_to_writer(file_id, &mut decryptor, Some(progress))
+            .and_then(|()| Ok(decryptor.finish().map(drop)?));
+        if result.is_err() {
+            let _ = fs::remove_file(save_path);
+        }
+        result
+    }
+
+    /// Before an upload of `size` bytes that's large enough to be worth it, confirm
+    /// the key works, the account has room, and a tiny file can be uploaded and
+    /// deleted again. `checking` is set while this runs.
+    fn check_before_upload(client: &dyn PixelDrainApi, size: u64, checking: &Mutex<bool>) -> Result<(), String> {
+        if size < CHECK_BEFORE_UPLOAD_SIZE {
+            return Ok(());
+        }
+        *checking.lock().unwrap() = true;
+        let result = (|| {
+            let user = client.get_user().map_err(|e| t!("check-key-failed", error = e.to_string()))?;
+            let storage_space = user.subscription.storage_space;
+            if storage_space >= 0 && user.storage_space_used.saturating_add(size) > storage_space as u64 {
+                return Err(t!("check-no-space"));
+            }
+            let check = Box::new(std::io::Cursor::new(b"PixelDrain upload check, deleted right away".to_vec()));
+            let response = client.upload_stream_put(check, CHECK_UPLOAD_NAME, None).map_err(|e| t!("check-upload-failed", error = e.to_string()))?;
+            client.delete_file(&response.id).map_err(|e| t!("check-delete-failed", id = response.id.as_str(), error = e.to_string()))
+        })();
+        *checking.lock().unwrap() = false;
+        result
+    }
+
+    /// Diagnostics for the transfer about to start if capture was ticked, which only applies once
+    fn take_diagnostics(&mut self, label: &str) -> Option<DiagnosticsCapture> {
+        std::mem::take(&mut self.capture_diagnostics).then(|| DiagnosticsCapture::new(label, Self::diagnostics_dir(), self.diagnostics_saved.clone()))
+    }
+
+    /// Where diagnostics of transfers are saved, in the app data folder
+    fn diagnostics_dir() -> PathBuf {
+        directories::ProjectDirs::from("com", "pixeldrain", "client")
+            .map(|proj_dirs| proj_dirs.data_dir().join("diagnostics"))
+            .unwrap_or_else(|| PathBuf::from("diagnostics"))
+    }
+
+    /// Running transfers with how long each went without progress, from the watchdog
+    fn render_debug_panel(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new(t!("debug-panel")).id_salt("debug_panel").show(ui, |ui| {
+            self.render_log_view(ui);
+            ui.horizontal(|ui| {
+                ui.label(t!("stall-timeout"));
+                let mut seconds = self.state.lock().unwrap().stall_timeout_secs;
+                if ui.add(egui::DragValue::new(&mut seconds).range(10..=3600).suffix(" s"))
+                    .on_hover_text(t!("stall-timeout-tooltip"))
+                    .changed()
+                {
+                    self.state.lock().unwrap().stall_timeout_secs = seconds;
+                    self.save_preferences();
+                }
+            });
+            let stall_timeout = Duration::from_secs(self.state.lock().unwrap().stall_timeout_secs);
+            let beats = self.watchdog.beats(Instant::now());
+            if beats.is_empty() {
+                ui.label(t!("debug-no-transfers"));
+                return;
+            }
+            // Idle times count up while the panel is open
+            ui.ctx().request_repaint_after(Duration::from_secs(1));
+            let mut cancel = None;
+            egui::Grid::new("debug_transfers").num_columns(4).striped(true).show(ui, |ui| {
+                for beat in &beats {
+                    let icon = match beat.direction {
+                        Direction::Upload => "⬆",
+                        Direction::Download => "⬇",
+                    };
+                    ui.label(format!("{} {}", icon, beat.name));
+                    match &beat.retrying {
+                        Some(retrying) => {
+                            let attempt = t!("debug-retrying", attempt = retrying.attempt, max = retrying.max_attempts);
+                            ui.label(format!("{:.0}% ({})", beat.fraction * 100.0, attempt)).on_hover_text(&retrying.reason);
+                        }
+                        None => {
+                            ui.label(format!("{:.0}%", beat.fraction * 100.0));
+                        }
+                    }
+                    let idle = t!("debug-idle", seconds = beat.idle.as_secs());
+                    if beat.idle >= stall_timeout {
+                        ui.colored_label(egui::Color32::from_rgb(255, 140, 0), format!("⚠ {}", idle));
+                    } else {
+                        ui.label(idle);
+                    }
+                    if beat.can_retry && ui.button(t!("debug-cancel-retry")).clicked() {
+                        cancel = Some(beat.id);
+                    }
+                    ui.end_row();
+                }
+            });
+            if let Some(id) = cancel {
+                self.cancel_stalled(id);
+            }
+        });
+    }
+
+    /// Log filter and file settings, and the lines logged since the app started
+    fn render_log_view(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new(t!("log-view")).id_salt("log_view").show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(t!("log-filter"));
+                let id = ui.make_persistent_id("log-filter");
+                let saved = self.state.lock().unwrap().log_filter.clone();
+                let mut text = ui.data_mut(|data| data.get_temp::<String>(id)).unwrap_or(saved.clone());
+                let response = ui.add(egui::TextEdit::singleline(&mut text).desired_width(260.0)).on_hover_text(t!("log-filter-tooltip"));
+                if response.lost_focus() {
+                    ui.data_mut(|data| data.remove::<String>(id));
+                    if text != saved {
+                        match logging::parse_filter(&text) {
+                            Ok(_) => {
+                                self.state.lock().unwrap().log_filter = text;
+                                self.save_preferences();
+                                self.apply_log_settings();
+                            }
+                            Err(e) => self.state.lock().unwrap().last_error = Some(e),
+                        }
+                    }
+                } else if response.has_focus() {
+                    ui.data_mut(|data| data.insert_temp(id, text));
+                }
+            });
+            let mut to_file = self.state.lock().unwrap().log_to_file;
+            if ui.checkbox(&mut to_file, t!("log-to-file")).on_hover_text(logging::log_file().display().to_string()).changed() {
+                self.state.lock().unwrap().log_to_file = to_file;
+                self.save_preferences();
+                self.apply_log_settings();
+            }
+            let lines = logging::lines();
+            let search = self.log_search.to_lowercase();
+            let shown: Vec<&logging::LogLine> = lines
+                .iter()
+                .filter(|line| line.level <= self.log_level)
+                .filter(|line| search.is_empty() || line.message.to_lowercase().contains(&search) || line.target.contains(&search))
+                .collect();
+            ui.horizontal(|ui| {
+                egui::ComboBox::from_id_salt("log_level")
+                    .selected_text(self.log_level.to_string())
+                    .show_ui(ui, |ui| {
+                        for level in [tracing::Level::ERROR, tracing::Level::WARN, tracing::Level::INFO, tracing::Level::DEBUG, tracing::Level::TRACE] {
+                            ui.selectable_value(&mut self.log_level, level, level.to_string());
+                        }
+                    });
+                ui.add(egui::TextEdit::singleline(&mut self.log_search).hint_text(t!("log-search")).desired_width(160.0));
+                if ui.button(t!("log-copy")).clicked() {
+                    let text: Vec<String> = shown.iter().map(|line| line.to_string()).collect();
+                    let _ = Clipboard::new().and_then(|mut c| c.set_text(text.join("\n")));
+                }
+                if ui.button(t!("log-clear")).clicked() {
+                    logging::clear();
+                }
+            });
+            egui::ScrollArea::vertical().id_salt("log_lines").max_height(240.0).stick_to_bottom(true).show(ui, |ui| {
+                for line in &shown {
+                    let color = match line.level {
+                        tracing::Level::ERROR => egui::Color32::RED,
+                        tracing::Level::WARN => egui::Color32::from_rgb(255, 140, 0),
+                        _ => ui.visuals().text_color(),
+                    };
+                    ui.label(egui::RichText::new(line.to_string()).monospace().small().color(color));
+                }
+            });
+            ui.ctx().request_repaint_after(Duration::from_secs(1));
+        });
+    }
+
+    /// Give up on transfer `id` and start it over. The old worker stops at its
+    /// next read, or stays blocked on its connection until that times out, but
+    /// either way it leaves the progress and history to the new one.
+    fn cancel_stalled(&mut self, id: events::TransferId) {
+        match self.watchdog.cancel(id) {
+            Some(watchdog::Retry::Upload(path)) => {
+                *self.upload_thread_running.lock().unwrap() = false;
+                self.start_upload(path);
+            }
+            Some(watchdog::Retry::Download(url)) => {
+                *self.download_thread_running.lock().unwrap() = false;
+                self.download_url = url;
+                self.start_download();
+            }
+            None => {}
+        }
+    }
+
+    fn render_diagnostics_toggle(&mut self, ui: &mut egui::Ui) {
+        ui.checkbox(&mut self.capture_diagnostics, t!("diagnostics-capture"))
+            .on_hover_text(t!("diagnostics-capture-hint"));
+        match self.diagnostics_saved.lock().unwrap().as_ref() {
+            Some(Ok(path)) => {
+                ui.horizontal(|ui| {
+                    ui.label(t!("diagnostics-saved", path = path.display().to_string()));
+                    if ui.small_button("📋").on_hover_text(t!("diagnostics-copy-path")).clicked() {
+                        let _ = Clipboard::new().and_then(|mut c| c.set_text(path.display().to_string()));
+                    }
+                });
+            }
+            Some(Err(e)) => {
+                ui.colored_label(egui::Color32::RED, t!("diagnostics-save-failed", error = e.as_str()));
+            }
+            None => {}
+        }
+    }
+
+    fn take_upload_api_key(&mut self) -> Result<Option<String>, String> {
+        if std::mem::take(&mut self.upload_anonymously) {
+            return Ok(None);
+        }
+        match self.get_api_key() {
+            Some(key) => Ok(Some(key)),
+            None => Err("API key required for upload. Please set your API key in settings or upload anonymously.".to_string()),
+        }
+    }
+
+    /// History entry for an upload that failed, so it shows up in the failures view
+    fn failed_upload_entry(path: &Path, custom_filename: &str, error: &pixeldrain_api::PixelDrainError) -> UploadHistoryEntry {
+        let filename = if custom_filename.is_empty() {
+            path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+        } else {
+            custom_filename.to_string()
+        };
+        UploadHistoryEntry {
+            id: String::new(),
+            url: String::new(),
+            filename,
+            size: path.metadata().map(|m| m.len()).unwrap_or(0),
+            timestamp: Utc::now(),
+            verified: None,
+            error: Some(error.to_string()),
+            anonymous: false,
+        }
+    }
+
+    /// Hash the local file (unless a precomputed hash is given) and compare it against
+    /// the server-side metadata of the upload
+    /// Returns None when verification could not be performed
+    fn verify_uploaded_file(client: &dyn PixelDrainApi, file_id: &str, path: &Path, local_hash: Option<String>) -> Option<bool> {
+        let local_size = path.metadata().ok()?.len();
+        let local_hash = match local_hash.map(Ok).unwrap_or_else(|| pixeldrain_api::sha256_file(path)) {
+            Ok(hash) => hash,
+            Err(e) => {
+                warn!("Failed to hash {} for verification: {}", path.display(), e);
+                return None;
+            }
+        };
+        match client.verify_upload(file_id, local_size, &local_hash) {
+            Ok(verified) => Some(verified),
+            Err(e) => {
+                warn!("Upload verification failed for {}: {}", file_id, e);
+                None
+            }
+        }
+    }
+
+    /// Hash a batch of files on a small worker pool, reporting completed files as progress
+    /// Files that can't be read get None and are verified (or skipped) later on their own
+    fn hash_files_parallel(paths: &[PathBuf], progress: &Arc<Mutex<Option<f32>>>, ctx: &egui::Context) -> Vec<Option<String>> {
+        let workers = thread::available_parallelism().map(|n| n.get()).unwrap_or(2).clamp(1, 4);
+        let next = std::sync::atomic::AtomicUsize::new(0);
+        let done = std::sync::atomic::AtomicUsize::new(0);
+        let results: Mutex<Vec<Option<String>>> = Mutex::new(vec![None; paths.len()]);
+        
+        thread::scope(|scope| {
+            for _ in 0..workers.min(paths.len()) {
+                scope.spawn(|| loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(path) = paths.get(index) else {
+                        break;
+                    };
+                    match pixeldrain_api::sha256_file(path) {
+                        Ok(hash) => results.lock().unwrap()[index] = Some(hash),
+                        Err(e) => warn!("Failed to hash {}: {}", path.display(), e),
+                    }
+                    let completed = done.fetch_add(1, Ordering::Relaxed) + 1;
+                    *progress.lock().unwrap() = Some(completed as f32 / paths.len() as f32);
+                    ctx.request_repaint();
+                });
+            }
+        });
+        
+        results.into_inner().unwrap()
+    }
+
+    // Retry utility specifically for PixelDrainError to eliminate code duplication
+    fn retry_pixeldrain_operation<F, T>(
+        operation: F,
+        max_retries: usize,
+        retry_delay: std::time::Duration,
+    ) -> Result<T, pixeldrain_api::PixelDrainError>
+    where
+        F: Fn() -> Result<T, pixeldrain_api::PixelDrainError>,
+    {
+        let mut last_error = None;
+        
+        for attempt in 1..=max_retries {
+            match operation() {
+                Ok(result) => return Ok(result),
+                Err(e) => {
+                    let should_retry = e.is_retryable();
+                    
+                    if should_retry && attempt < max_retries {
+                        std::thread::sleep(retry_delay);
+                        continue;
+                    } else {
+                        last_error = Some(e);
+                        break;
+                    }
+                }
+            }
+        }
+        
+        Err(last_error.unwrap())
+    }
+
+    #[allow(dead_code)]
+    fn toggle_theme(&mut self, ctx: &egui::Context) {
+        // Toggle dark_mode and update visuals
+        {
+            let mut state = self.state.lock().unwrap();
+            state.dark_mode = !state.dark_mode;
+        }
+        self.apply_theme_on_startup(ctx);
+        // Runtime window icon update is not supported in this eframe version
+    }
+
+}
+
+fn main() -> Result<(), eframe::Error> {
+    logging::init(&env::var("RUST_LOG").unwrap_or_default());
+
+    // Subcommands such as `pixeldrain upload -` run without a window
+    let args: Vec<String> = env::args().skip(1).collect();
+    if let Some(command) = cli::parse(&args) {
+        std::process::exit(cli::run(command));
+    }
+
+    // A deep link goes to the running instance if there is one
+    let link = ipc::link_from_args();
+    if let Some(link) = &link
+        && ipc::send_to_running_instance(link)
+    {
+        return Ok(());
+    }
+
+    let mut viewport = egui::ViewportBuilder::default()
+        .with_inner_size([600.0, 400.0])
+        .with_min_inner_size([400.0, 300.0]);
+    if let Some(icon) = icon_data_from_png(true) {
+        viewport = viewport.with_icon(icon);
+    }
+    let options = NativeOptions {
+        viewport,
+        ..Default::default()
+    };
+
+    eframe::run_native(
+        "PixelDrain",
+        options,
+        Box::new(move |cc| {
+            let journal = resume::Journal::open(resume::journal_path());
+            let mut app = PixelDrainApp {
+                deep_links: ipc::listen(cc.egui_ctx.clone()),
+                interrupted: journal.interrupted(),
+                journal,
+                ..PixelDrainApp::default()
+            };
+            if let Some(link) = link {
+                app.handle_deep_link(&cc.egui_ctx, &link);
+            }
+            if app.state.lock().unwrap().check_for_updates {
+                app.check_for_updates(cc.egui_ctx.clone());
+            }
+            Ok(Box::new(app))
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pixeldrain_api::{
+        ApiListFile, CreateListRequest, DetailedListInfo, FilesystemPath, ListInfo, PixelDrainError,
+        ProgressSender, RateLimits, UploadResponse, UserFilesResponse, UserListsResponse,
+    };
+    use std::io::Read;
+
+    mod gui;
+
+    /// In-memory stand-in for the PixelDrain API
+    #[derive(Default)]
+    struct MockApi {
+        verify_matches: bool,
+        list_files: Vec<FileInfo>,
+        missing_files: Vec<String>,
+        uploads: Mutex<Vec<PathBuf>>,
+        // Names given to uploads made with a custom name
+        put_names: Mutex<Vec<String>>,
+        // File ID, name and type of files uploaded again with another type
+        retyped: Mutex<Vec<(String, String, String)>>,
+        deleted: Mutex<Vec<String>>,
+        list_updates: Mutex<Vec<(String, Vec<String>)>>,
+        // Descriptions of the files of each list update
+        list_descriptions: Mutex<Vec<Vec<String>>>,
+        // Account the API key belongs to, None when the key is refused
+        user: Option<UserInfo>,
+        // Files of the account
+        account_files: Vec<FileInfo>,
+        // Lists of the account, each with `list_files` as its files
+        account_lists: Vec<ListInfo>,
+        // Content of stream uploads by ID, downloaded again from there
+        streamed: Mutex<HashMap<String, Vec<u8>>>,
+        // Account settings endpoints called, with what was sent
+        user_updates: Mutex<Vec<(String, String)>>,
+        // Title and file IDs of lists that were created
+        created_lists: Mutex<Vec<(String, Vec<String>)>>,
+        // Sessions of the account, revoked ones are removed
+        sessions: Mutex<Vec<UserSession>>,
+        // Thumbnails of files by ID
+        thumbnails: HashMap<String, Vec<u8>>,
+        // Uploads after this many are refused with a 401, like a key revoked mid-session
+        refuse_uploads_after: Mutex<Option<usize>>,
+    }
+
+    /// Account with `used` of `space` bytes taken, -1 for unlimited space
+    fn user_with_space(used: u64, space: i64) -> UserInfo {
+        serde_json::from_value(serde_json::json!({
+            "username": "tester", "email": "", "email_verified": true, "otp_enabled": false,
+            "subscription": {
+                "id": "", "name": "", "type": "", "file_size_limit": 0, "file_expiry_days": 0,
+                "storage_space": space, "price_per_tb_storage": 0, "price_per_tb_bandwidth": 0,
+                "monthly_transfer_cap": 0, "file_viewer_branding": false
+            },
+            "storage_space_used": used, "filesystem_storage_used": 0, "is_admin": false,
+            "balance_micro_eur": 0, "hotlinking_enabled": true, "monthly_transfer_cap": 0,
+            "monthly_transfer_used": 0, "file_embed_domains": "", "skip_file_viewer": false,
+            "affiliate_user_name": "", "checkout_country": "", "checkout_name": "", "checkout_provider": ""
+        }))
+        .unwrap()
+    }
+
+    fn unsupported<T>() -> Result<T, PixelDrainError> {
+        Err(PixelDrainError::FileNotFound("not supported by mock".to_string()))
+    }
+
+    impl PixelDrainApi for MockApi {
+        fn upload_file(&self, file_path: &Path, progress: Option<ProgressSender>) -> Result<UploadResponse, PixelDrainError> {
+            if let Some(progress) = progress {
+                let _ = progress.send(pixeldrain_api::TransferEvent::Progress(Progress { fraction: 1.0, ..Default::default() }));
+            }
+            let mut uploads = self.uploads.lock().unwrap();
+            if self.refuse_uploads_after.lock().unwrap().is_some_and(|after| uploads.len() >= after) {
+                return Err(PixelDrainError::Api(pixeldrain_api::ApiError {
+                    status: reqwest::StatusCode::UNAUTHORIZED,
+                    value: "unauthorized".to_string(),
+                    message: "The key is not valid".to_string(),
+                    endpoint: None,
+                    request_id: None,
+                }));
+            }
+            uploads.push(file_path.to_path_buf());
+            Ok(UploadResponse { id: format!("up{}", uploads.len()) })
+        }
+        fn upload_file_put(&self, file_path: &Path, custom_filename: &str, progress: Option<ProgressSender>) -> Result<UploadResponse, PixelDrainError> {
+            self.put_names.lock().unwrap().push(custom_filename.to_string());
+            self.upload_file(file_path, progress)
+        }
+        fn upload_stream_put(&self, mut reader: Box<dyn Read + Send>, filename: &str, _progress: Option<ProgressSender>) -> Result<UploadResponse, PixelDrainError> {
+            let mut content = Vec::new();
+            reader.read_to_end(&mut content)?;
+            let mut names = self.put_names.lock().unwrap();
+            names.push(filename.to_string());
+            let id = format!("stream{}", names.len());
+            self.streamed.lock().unwrap().insert(id.clone(), content);
+            Ok(UploadResponse { id })
+        }
+        fn reupload_with_type(&self, file_id: &str, filename: &str, mime_type: &str, _progress: Option<ProgressSender>) -> Result<UploadResponse, PixelDrainError> {
+            self.retyped.lock().unwrap().push((file_id.to_string(), filename.to_string(), mime_type.to_string()));
+            Ok(UploadResponse { id: format!("retyped{}", self.retyped.lock().unwrap().len()) })
+        }
+        fn download_file(&self, file_id: &str, save_path: &Path, _progress: Option<ProgressSender>) -> Result<(), PixelDrainError> {
+            fs::write(save_path, file_id)?;
+            Ok(())
+        }
+        fn resume_download_file(&self, file_id: &str, save_path: &Path, _progress: Option<ProgressSender>) -> Result<(), PixelDrainError> {
+            // The rest of the file is its ID, after what the partial file has
+            let mut file = fs::OpenOptions::new().append(true).create(true).open(save_path)?;
+            std::io::Write::write_all(&mut file, file_id.as_bytes())?;
+            Ok(())
+        }
+        fn download_file_to_writer(&self, file_id: &str, out: &mut dyn std::io::Write, _progress: Option<ProgressSender>) -> Result<(), PixelDrainError> {
+            match self.streamed.lock().unwrap().get(file_id) {
+                Some(content) => out.write_all(content)?,
+                None => out.write_all(file_id.as_bytes())?,
+            }
+            Ok(())
+        }
+        fn download_list_zip(&self, _list_id: &str, _save_path: &Path, _progress: Option<ProgressSender>) -> Result<(), PixelDrainError> {
+            unsupported()
+        }
+        fn fetch_thumbnail_bytes(&self, file_id: &str, _width: u32, _height: u32) -> Result<Vec<u8>, PixelDrainError> {
+            match self.thumbnails.get(file_id) {
+                Some(bytes) => Ok(bytes.clone()),
+                None => unsupported(),
+            }
+        }
+        fn get_file_info(&self, file_id: &str) -> Result<FileInfo, PixelDrainError> {
+            match self.account_files.iter().find(|file| file.id == file_id) {
+                Some(file) => Ok(file.clone()),
+                None => unsupported(),
+            }
+        }
+        fn verify_upload(&self, _file_id: &str, _local_size: u64, _local_sha256: &str) -> Result<bool, PixelDrainError> {
+            Ok(self.verify_matches)
+        }
+        fn get_user_files(&self) -> Result<UserFilesResponse, PixelDrainError> {
+            Ok(UserFilesResponse { files: self.account_files.clone() })
+        }
+        fn delete_file(&self, file_id: &str) -> Result<(), PixelDrainError> {
+            if self.missing_files.iter().any(|id| id == file_id) {
+                return Err(PixelDrainError::FileNotFound(file_id.to_string()));
+            }
+            self.deleted.lock().unwrap().push(file_id.to_string());
+            Ok(())
+        }
+        fn get_user(&self) -> Result<UserInfo, PixelDrainError> {
+            self.user.clone().map_or_else(unsupported, Ok)
+        }
+        fn get_user_transactions(&self) -> Result<Vec<UserTransaction>, PixelDrainError> {
+            unsupported()
+        }
+        fn update_file_embed_domains(&self, domains: &str) -> Result<(), PixelDrainError> {
+            self.user_updates.lock().unwrap().push(("file_embed_domains".to_string(), domains.to_string()));
+            Ok(())
+        }
+        fn update_hotlinking(&self, enabled: bool) -> Result<(), PixelDrainError> {
+            self.user_updates.lock().unwrap().push(("hotlinking".to_string(), enabled.to_string()));
+            Ok(())
+        }
+        fn post_user_session(&self, app_name: &str) -> Result<UserSession, PixelDrainError> {
+            let session = UserSession { auth_key: format!("session{}", self.sessions.lock().unwrap().len() + 1), app_name: app_name.to_string(), ..UserSession::default() };
+            self.sessions.lock().unwrap().push(session.clone());
+            Ok(session)
+        }
+        fn get_user_sessions(&self) -> Result<Vec<UserSession>, PixelDrainError> {
+            Ok(self.sessions.lock().unwrap().clone())
+        }
+        fn delete_user_session(&self, session_key: &str) -> Result<(), PixelDrainError> {
+            self.sessions.lock().unwrap().retain(|session| session.auth_key != session_key);
+            Ok(())
+        }
+        fn update_file_viewer_branding(&self, branding: &HashMap<String, String>) -> Result<(), PixelDrainError> {
+            let mut fields: Vec<String> = branding.iter().filter(|(_, value)| !value.is_empty()).map(|(field, value)| format!("{}={}", field, value)).collect();
+            fields.sort();
+            self.user_updates.lock().unwrap().push(("file_customization".to_string(), fields.join("&")));
+            Ok(())
+        }
+        fn get_rate_limits(&self) -> Result<RateLimits, PixelDrainError> {
+            unsupported()
+        }
+        fn base_url(&self) -> &str {
+            pixeldrain_api::BASE_URL
+        }
+        fn get_user_lists(&self) -> Result<UserListsResponse, PixelDrainError> {
+            Ok(UserListsResponse { lists: self.account_lists.clone() })
+        }
+        fn get_list(&self, list_id: &str) -> Result<DetailedListInfo, PixelDrainError> {
+            Ok(DetailedListInfo {
+                id: list_id.to_string(),
+                title: "Holiday: photos".to_string(),
+                files: self.list_files.iter().map(|file| ApiListFile {
+                    detail_href: String::new(),
+                    description: String::new(),
+                    file_info: file.clone(),
+                }).collect(),
+                date_created: Utc::now(),
+                date_updated: None,
+                can_edit: false,
+                can_delete: false,
+                file_count: self.list_files.len() as i32,
+            })
+        }
+        fn create_list(&self, req: &CreateListRequest) -> Result<ListInfo, PixelDrainError> {
+            let mut created = self.created_lists.lock().unwrap();
+            created.push((req.title.clone(), req.files.iter().map(|f| f.id.clone()).collect()));
+            Ok(ListInfo {
+                id: format!("list{}", created.len()),
+                title: req.title.clone(),
+                date_created: Utc::now(),
+                file_count: req.files.len() as i64,
+                files: None,
+                can_edit: true,
+            })
+        }
+        fn update_list(&self, list_id: &str, req: &CreateListRequest) -> Result<ListInfo, PixelDrainError> {
+            let ids = req.files.iter().map(|f| f.id.clone()).collect();
+            self.list_updates.lock().unwrap().push((list_id.to_string(), ids));
+            self.list_descriptions.lock().unwrap().push(req.files.iter().map(|f| f.description.clone()).collect());
+            Ok(ListInfo {
+                id: list_id.to_string(),
+                title: req.title.clone(),
+                date_created: Utc::now(),
+                file_count: req.files.len() as i64,
+                files: None,
+                can_edit: true,
+            })
+        }
+        fn delete_list(&self, _list_id: &str) -> Result<(), PixelDrainError> {
+            unsupported()
+        }
+        fn get_filesystem_path(&self, _path: &str) -> Result<FilesystemPath, PixelDrainError> {
+            unsupported()
+        }
+        fn upload_filesystem_file(&self, _file_path: &Path, _remote_path: &str, _progress: Option<ProgressSender>) -> Result<(), PixelDrainError> {
+            unsupported()
+        }
+        fn download_filesystem_file(&self, _remote_path: &str, _save_path: &Path, _progress: Option<ProgressSender>) -> Result<(), PixelDrainError> {
+            unsupported()
+        }
+        fn create_filesystem_directory(&self, _path: &str) -> Result<(), PixelDrainError> {
+            unsupported()
+        }
+        fn rename_filesystem_node(&self, _path: &str, _target: &str) -> Result<(), PixelDrainError> {
+            unsupported()
+        }
+        fn delete_filesystem_node(&self, _path: &str) -> Result<(), PixelDrainError> {
+            unsupported()
+        }
+        fn share_filesystem_path(&self, _path: &str, _password: Option<&str>) -> Result<String, PixelDrainError> {
+            unsupported()
+        }
+        fn unshare_filesystem_path(&self, _path: &str) -> Result<(), PixelDrainError> {
+            unsupported()
+        }
+    }
+
+    fn file(id: &str, name: &str) -> FileInfo {
+        FileInfo { id: id.to_string(), name: name.to_string(), ..Default::default() }
+    }
+
+    /// App wired to the mock, with fresh state instead of the user's settings
+    /// The app with `mock` as the server, settings saved in `temp_dir` and the API keys in memory
+    fn app_with(mock: Arc<MockApi>, temp_dir: &Path) -> PixelDrainApp {
+        let mut app = PixelDrainApp::with_settings(temp_dir.join("settings.json"), secrets::SecretStore::memory());
+        *app.state.lock().unwrap() = AppState {
+            api_key: "test-key".to_string(),
+            download_location: temp_dir.display().to_string(),
+            desktop_notifications: false,
+            ..AppState::default()
+        };
+        app.api_factory = Arc::new(move |_| Ok(mock.clone() as Arc<dyn PixelDrainApi>));
+        app
+    }
+
+    /// Upload history of the app, oldest first
+    fn upload_history(state: &AppState) -> Vec<UploadHistoryEntry> {
+        let mut history = state.storage.uploads(&HistoryViewOptions::default(), 100).unwrap();
+        history.reverse();
+        history.into_iter().map(|(entry, _)| entry).collect()
+    }
+
+    fn temp_dir() -> PathBuf {
+        let dir = env::temp_dir().join(format!("pixeldrain-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn wait_for_workers(app: &PixelDrainApp) {
+        assert_eq!(app.workers.wait(Duration::from_secs(10)), 0, "workers did not finish");
+    }
+
+    #[test]
+    fn upload_adds_verified_history_entry() {
+        let dir = temp_dir();
+        let path = dir.join("photo.jpg");
+        fs::write(&path, b"jpeg bytes").unwrap();
+        let mock = Arc::new(MockApi { verify_matches: true, ..Default::default() });
+        let mut app = app_with(mock.clone(), &dir);
+
+        app.start_upload(path.clone());
+        wait_for_workers(&app);
+
+        let state = app.state.lock().unwrap();
+        let history = upload_history(&state);
+        assert_eq!(history.len(), 1);
+        let entry = &history[0];
+        assert_eq!(entry.filename, "photo.jpg");
+        assert_eq!(entry.url, format!("{}/u/up1", pixeldrain_api::BASE_URL));
+        assert_eq!(entry.size, 10);
+        assert_eq!(entry.verified, Some(true));
+        assert!(state.last_error.is_none());
+        assert!(!*app.upload_thread_running.lock().unwrap());
+        assert_eq!(*mock.uploads.lock().unwrap(), vec![path]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn remaining_time_is_shown_in_its_two_largest_units() {
+        assert_eq!(format_remaining(Duration::from_secs(42)), "42s");
+        assert_eq!(format_remaining(Duration::from_secs(134)), "2m 14s");
+        assert_eq!(format_remaining(Duration::from_secs(3 * 3600 + 5 * 60 + 9)), "3h 5m");
+    }
+
+    #[test]
+    fn large_uploads_are_preceded_by_a_test_upload() {
+        let checking = Mutex::new(false);
+        let check = |mock: &MockApi, size| PixelDrainApp::check_before_upload(mock, size, &checking);
+
+        // Small uploads and refused keys
+        let refused = MockApi::default();
+        assert_eq!(check(&refused, CHECK_BEFORE_UPLOAD_SIZE - 1), Ok(()));
+        assert!(check(&refused, CHECK_BEFORE_UPLOAD_SIZE).is_err());
+
+        let full = MockApi { user: Some(user_with_space(500, CHECK_BEFORE_UPLOAD_SIZE as i64)), ..Default::default() };
+        assert_eq!(check(&full, CHECK_BEFORE_UPLOAD_SIZE), Err(t!("check-no-space")));
+        assert!(full.put_names.lock().unwrap().is_empty());
+
+        let unlimited = MockApi { user: Some(user_with_space(500, -1)), ..Default::default() };
+        assert_eq!(check(&unlimited, CHECK_BEFORE_UPLOAD_SIZE), Ok(()));
+        assert_eq!(*unlimited.put_names.lock().unwrap(), [CHECK_UPLOAD_NAME]);
+        assert_eq!(*unlimited.deleted.lock().unwrap(), ["stream1"]);
+        assert!(!*checking.lock().unwrap());
+    }
+
+    #[test]
+    fn downloads_of_files_with_a_missing_key_are_flagged() {
+        let dir = temp_dir();
+        let mut app = app_with(Arc::new(MockApi::default()), &dir);
+        app.state.lock().unwrap().key_registry.record("abc", "backup");
+        app.download_url = "https://pixeldrain.com/u/abc".to_string();
+        app.start_download();
+        wait_for_workers(&app);
+        assert_eq!(app.download_missing_key.as_deref(), Some("backup"));
+
+        app.state.lock().unwrap().key_registry.configure("backup");
+        app.start_download();
+        wait_for_workers(&app);
+        assert_eq!(app.download_missing_key, None);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn encrypted_uploads_are_decrypted_on_download() {
+        let dir = temp_dir();
+        let path = dir.join("secret.txt");
+        fs::write(&path, b"launch codes").unwrap();
+        let mock = Arc::new(MockApi { account_files: vec![file("stream1", "secret.txt.enc")], ..MockApi::default() });
+        let mut app = app_with(mock.clone(), &dir);
+        {
+            let mut state = app.state.lock().unwrap();
+            state.encrypt_uploads = true;
+            state.encryption_key_id = "vault".to_string();
+        }
+        // Refused without a passphrase
+        app.start_upload(path.clone());
+        wait_for_workers(&app);
+        assert!(mock.put_names.lock().unwrap().is_empty());
+
+        app.state.lock().unwrap().encryption_passphrase = "correct horse".to_string();
+        app.start_upload(path.clone());
+        wait_for_workers(&app);
+        assert_eq!(*mock.put_names.lock().unwrap(), ["secret.txt.enc"]);
+        assert!(!mock.streamed.lock().unwrap()["stream1"].windows(6).any(|w| w == b"launch"));
+        assert_eq!(app.state.lock().unwrap().key_registry.key_for("stream1"), Some("vault"));
+        assert_eq!(upload_history(&app.state.lock().unwrap())[0].filename, "secret.txt.enc");
+
+        fs::remove_file(&path).unwrap();
+        app.download_url = "https://pixeldrain.com/u/stream1".to_string();
+        app.start_download();
+        wait_for_workers(&app);
+        assert_eq!(fs::read(&path).unwrap(), b"launch codes");
+
+        // A wrong passphrase leaves nothing behind
+        fs::remove_file(&path).unwrap();
+        app.state.lock().unwrap().encryption_passphrase = "battery staple".to_string();
+        app.start_download();
+        wait_for_workers(&app);
+        assert!(!path.exists());
+        assert!(app.state.lock().unwrap().last_error.is_some());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn only_changed_sharing_settings_are_sent() {
+        let dir = temp_dir();
+        let mut user = user_with_space(0, -1);
+        user.subscription.file_viewer_branding = true;
+        user.file_embed_domains = "example.com".to_string();
+        let mock = Arc::new(MockApi { user: Some(user.clone()), ..MockApi::default() });
+        let mut app = app_with(mock.clone(), &dir);
+        app.state.lock().unwrap().user_info = Some(user.clone());
+
+        let mut form = SharingSettingsForm::from_user(&user);
+        form.branding.insert("header_link".to_string(), "https://example.com".to_string());
+        app.sharing_form = Some(form.clone());
+        app.save_sharing_settings();
+        wait_for_workers(&app);
+        assert_eq!(*mock.user_updates.lock().unwrap(), [("file_customization".to_string(), "header_link=https://example.com".to_string())]);
+
+        form.embed_domains = " example.com blog.example.com ".to_string();
+        app.sharing_form = Some(form);
+        app.save_sharing_settings();
+        wait_for_workers(&app);
+        assert_eq!(mock.user_updates.lock().unwrap()[1], ("file_embed_domains".to_string(), "example.com blog.example.com".to_string()));
+        assert!(!*app.sharing_saving.lock().unwrap());
+
+        app.set_hotlinking(false);
+        wait_for_workers(&app);
+        assert_eq!(mock.user_updates.lock().unwrap().last(), Some(&("hotlinking".to_string(), "false".to_string())));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn sessions_are_listed_revoked_and_created_by_logging_in() {
+        let dir = temp_dir();
+        let session = |key: &str, app: &str| UserSession {
+            auth_key: key.to_string(),
+            creation_ip: "192.0.2.1".to_string(),
+            user_agent: "Mozilla/5.0".to_string(),
+            app_name: app.to_string(),
+            creation_time: Utc::now(),
+            last_used_time: Utc::now(),
+        };
+        let sessions = vec![session("test-key", "PixelDrain"), session("browser-key", "")];
+        let mock = Arc::new(MockApi { sessions: Mutex::new(sessions), ..MockApi::default() });
+        let mut app = app_with(mock.clone(), &dir);
+
+        app.refresh_sessions();
+        wait_for_workers(&app);
+        assert_eq!(app.sessions.lock().unwrap().as_ref().map(Vec::len), Some(2));
+
+        app.revoke_session("browser-key".to_string());
+        wait_for_workers(&app);
+        let keys: Vec<String> = app.sessions.lock().unwrap().iter().flatten().map(|session| session.auth_key.clone()).collect();
+        assert_eq!(keys, ["test-key"]);
+        assert!(!*app.sessions_loading.lock().unwrap());
+
+        app.login_username = "tester".to_string();
+        app.login_password = "hunter2".to_string();
+        app.log_in();
+        wait_for_workers(&app);
+        assert_eq!(*app.login_result.lock().unwrap(), Some(Ok("session2".to_string())));
+        assert_eq!(mock.sessions.lock().unwrap()[1].app_name, APP_NAME);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn oversized_files_are_uploaded_in_parts_and_joined_on_download() {
+        let dir = temp_dir();
+        let path = dir.join("video.mkv");
+        fs::write(&path, b"0123456789").unwrap();
+        let mut user = user_with_space(0, -1);
+        user.subscription.file_size_limit = 4;
+        let parts = vec![file("stream1", "video.mkv.001"), file("stream2", "video.mkv.002"), file("stream3", "video.mkv.003")];
+        let mock = Arc::new(MockApi { list_files: parts, ..MockApi::default() });
+        let mut app = app_with(mock.clone(), &dir);
+        app.state.lock().unwrap().user_info = Some(user);
+
+        app.start_upload(path.clone());
+        wait_for_workers(&app);
+        assert!(mock.put_names.lock().unwrap().is_empty());
+        assert_eq!(app.upload_split_offer, Some((path.clone(), 4)));
+
+        app.start_split_upload(path.clone(), 4);
+        wait_for_workers(&app);
+        assert_eq!(*mock.put_names.lock().unwrap(), ["video.mkv.001", "video.mkv.002", "video.mkv.003"]);
+        assert_eq!(*mock.created_lists.lock().unwrap(), [("video.mkv".to_string(), vec!["stream1".to_string(), "stream2".to_string(), "stream3".to_string()])]);
+
+        fs::remove_file(&path).unwrap();
+        app.download_url = "https://pixeldrain.com/l/list1".to_string();
+        app.start_download();
+        wait_for_workers(&app);
+        assert_eq!(fs::read(&path).unwrap(), b"0123456789");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn anonymous_upload_skips_api_key_once() {
+        let dir = temp_dir();
+        let path = dir.join("note.txt");
+        fs::write(&path, b"hello").unwrap();
+        let mock = Arc::new(MockApi::default());
+        let mut app = app_with(mock.clone(), &dir);
+        let used_keys = Arc::new(Mutex::new(Vec::new()));
+        let keys = used_keys.clone();
+        app.api_factory = Arc::new(move |config: PixelDrainConfig| {
+            keys.lock().unwrap().push(config.api_key);
+            Ok(mock.clone() as Arc<dyn PixelDrainApi>)
+        });
+
+        app.upload_anonymously = true;
+        app.start_upload(path.clone());
+        wait_for_workers(&app);
+        assert!(!app.upload_anonymously);
+        app.start_upload(path);
+        wait_for_workers(&app);
+
+        assert_eq!(*used_keys.lock().unwrap(), vec![None, Some("test-key".to_string())]);
+        let state = app.state.lock().unwrap();
+        let history = upload_history(&state);
+        assert!(history[0].anonymous);
+        assert!(!history[1].anonymous);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn upload_verification_mismatch_is_reported() {
+        let dir = temp_dir();
+        let path = dir.join("archive.tar");
+        fs::write(&path, b"data").unwrap();
+        let mut app = app_with(Arc::new(MockApi::default()), &dir);
+
+        app.start_upload(path);
+        wait_for_workers(&app);
+
+        let state = app.state.lock().unwrap();
+        assert_eq!(upload_history(&state)[0].verified, Some(false));
+        assert!(state.last_error.as_deref().unwrap().contains("verification failed"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn multiple_upload_queue_uploads_every_file_in_order() {
+        let dir = temp_dir();
+        let paths: Vec<PathBuf> = ["a.txt", "b.txt", "c.txt"].iter().map(|name| {
+            let path = dir.join(name);
+            fs::write(&path, name).unwrap();
+            path
+        }).collect();
+        let mock = Arc::new(MockApi { verify_matches: true, ..Default::default() });
+        let mut app = app_with(mock.clone(), &dir);
+        // Empty files are left out and reported instead
+        let empty = dir.join("empty.txt");
+        fs::write(&empty, b"").unwrap();
+        let mut selected = paths.clone();
+        selected.insert(1, empty.clone());
+
+        app.start_multiple_upload(selected, egui::Context::default());
+        wait_for_workers(&app);
+
+        assert_eq!(*mock.uploads.lock().unwrap(), paths);
+        assert_eq!(*app.upload_skipped.lock().unwrap(), [preflight::Skipped { path: empty, problem: preflight::Problem::Empty }]);
+        let state = app.state.lock().unwrap();
+        let history = upload_history(&state);
+        let names: Vec<&str> = history.iter().map(|e| e.filename.as_str()).collect();
+        assert_eq!(names, ["a.txt", "b.txt", "c.txt"]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn batch_order_sorts_by_size() {
+        let dir = temp_dir();
+        let paths: Vec<PathBuf> = [("a.txt", 30), ("b.txt", 10), ("c.txt", 20), ("d.txt", 10)].iter().map(|(name, size)| {
+            let path = dir.join(name);
+            fs::write(&path, vec![b'x'; *size]).unwrap();
+            path
+        }).collect();
+        let names = |order: UploadOrder| {
+            let mut ordered = paths.clone();
+            order.apply(&mut ordered);
+            ordered.iter().map(|p| p.file_name().unwrap().to_string_lossy().into_owned()).collect::<Vec<_>>()
+        };
+        assert_eq!(names(UploadOrder::AsSelected), ["a.txt", "b.txt", "c.txt", "d.txt"]);
+        assert_eq!(names(UploadOrder::SmallestFirst), ["b.txt", "d.txt", "c.txt", "a.txt"]);
+        assert_eq!(names(UploadOrder::LargestFirst), ["a.txt", "c.txt", "b.txt", "d.txt"]);
+
+        let mock = Arc::new(MockApi { verify_matches: true, ..Default::default() });
+        let mut app = app_with(mock.clone(), &dir);
+        app.upload_order = UploadOrder::LargestFirst;
+        app.start_multiple_upload(paths.clone(), egui::Context::default());
+        wait_for_workers(&app);
+        let uploaded: Vec<PathBuf> = ["a.txt", "c.txt", "b.txt", "d.txt"].iter().map(|name| dir.join(name)).collect();
+        assert_eq!(*mock.uploads.lock().unwrap(), uploaded);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn batch_files_are_uploaded_under_their_new_names() {
+        let dir = temp_dir();
+        let paths: Vec<PathBuf> = ["a.txt", "b.txt"].iter().map(|name| {
+            let path = dir.join(name);
+            fs::write(&path, name).unwrap();
+            path
+        }).collect();
+        let mock = Arc::new(MockApi { verify_matches: true, ..Default::default() });
+        let mut app = app_with(mock.clone(), &dir);
+        app.upload_file_names.insert(paths[0].clone(), " report.txt ".to_string());
+        app.upload_file_names.insert(paths[1].clone(), String::new());
+        app.start_multiple_upload(paths, egui::Context::default());
+        wait_for_workers(&app);
+
+        assert_eq!(*mock.put_names.lock().unwrap(), ["report.txt"]);
+        let names: Vec<String> = upload_history(&app.state.lock().unwrap()).iter().map(|entry| entry.filename.clone()).collect();
+        assert!(names.contains(&"report.txt".to_string()) && names.contains(&"b.txt".to_string()), "{:?}", names);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn fixing_the_type_uploads_again_and_deletes_the_original() {
+        let dir = temp_dir();
+        let mock = Arc::new(MockApi::default());
+        let mut app = app_with(mock.clone(), &dir);
+        let mut photo = file("abc", "holiday");
+        photo.mime_type = "application/octet-stream".to_string();
+        app.fix_type_delete_original = true;
+        app.fix_file_type(&photo, extensions::with_inferred_extension(&photo.name, "image/jpeg"), "image/jpeg".to_string());
+        wait_for_workers(&app);
+
+        assert_eq!(*mock.retyped.lock().unwrap(), [("abc".to_string(), "holiday.jpg".to_string(), "image/jpeg".to_string())]);
+        assert_eq!(*mock.deleted.lock().unwrap(), ["abc"]);
+        let result = app.fix_type_result.lock().unwrap().clone();
+        assert!(result.is_some_and(|url| url.unwrap().ends_with("/u/retyped1")));
+        assert_eq!(upload_history(&app.state.lock().unwrap())[0].filename, "holiday.jpg");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn parallel_uploads_keep_the_batch_order_in_the_list() {
+        let dir = temp_dir();
+        let paths: Vec<PathBuf> = ["a.txt", "b.txt", "c.txt", "d.txt", "e.txt"].iter().map(|name| {
+            let path = dir.join(name);
+            fs::write(&path, name).unwrap();
+            path
+        }).collect();
+        let mock = Arc::new(MockApi { verify_matches: true, ..Default::default() });
+        let mut app = app_with(mock.clone(), &dir);
+        app.state.lock().unwrap().upload_concurrency = 3;
+        app.upload_target_list = Some("list1".to_string());
+
+        app.start_multiple_upload(paths.clone(), egui::Context::default());
+        wait_for_workers(&app);
+
+        // The mock numbers uploads in the order they happened
+        let uploads = mock.uploads.lock().unwrap().clone();
+        let mut sorted = uploads.clone();
+        sorted.sort();
+        assert_eq!(sorted, paths);
+        let ids: Vec<String> = paths.iter().map(|path| format!("up{}", uploads.iter().position(|p| p == path).unwrap() + 1)).collect();
+        assert_eq!(*mock.list_updates.lock().unwrap(), [("list1".to_string(), ids)]);
+        assert_eq!(upload_history(&app.state.lock().unwrap()).len(), 5);
+        assert_eq!(*app.upload_progress.lock().unwrap(), 1.0);
+        assert!(app.upload_batch_progress.lock().unwrap().iter().all(|(_, fraction)| *fraction == 1.0));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn files_already_in_the_account_are_offered_instead_of_uploaded() {
+        let dir = temp_dir();
+        let paths: Vec<PathBuf> = ["a.txt", "b.txt"].iter().map(|name| {
+            let path = dir.join(name);
+            fs::write(&path, name).unwrap();
+            path
+        }).collect();
+        let existing = FileInfo { hash_sha256: pixeldrain_api::sha256_file(&paths[0]).unwrap(), ..file("old", "copy of a.txt") };
+        let mock = Arc::new(MockApi { verify_matches: true, account_files: vec![existing], ..Default::default() });
+        let mut app = app_with(mock.clone(), &dir);
+
+        app.start_multiple_upload(paths.clone(), egui::Context::default());
+        wait_for_workers(&app);
+        assert_eq!(*mock.uploads.lock().unwrap(), [paths[1].clone()]);
+        let duplicates: Vec<(PathBuf, String)> = app.upload_duplicates.lock().unwrap().iter().map(|(path, file)| (path.clone(), file.id.clone())).collect();
+        assert_eq!(duplicates, [(paths[0].clone(), "old".to_string())]);
+
+        app.start_upload(paths[0].clone());
+        wait_for_workers(&app);
+        assert_eq!(mock.uploads.lock().unwrap().len(), 1);
+        assert_eq!(app.upload_duplicates.lock().unwrap().len(), 1);
+
+        // Uploaded anyway when asked, once
+        app.upload_duplicates_allowed = true;
+        app.start_upload(paths[0].clone());
+        wait_for_workers(&app);
+        assert_eq!(*mock.uploads.lock().unwrap(), [paths[1].clone(), paths[0].clone()]);
+        assert!(app.upload_duplicates.lock().unwrap().is_empty());
+        assert!(!app.upload_duplicates_allowed);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn uploads_are_appended_to_the_target_list() {
+        let dir = temp_dir();
+        let paths: Vec<PathBuf> = ["a.txt", "b.txt"].iter().map(|name| {
+            let path = dir.join(name);
+            fs::write(&path, name).unwrap();
+            path
+        }).collect();
+        let mock = Arc::new(MockApi { verify_matches: true, list_files: vec![file("old", "old.txt")], ..Default::default() });
+        let mut app = app_with(mock.clone(), &dir);
+        app.upload_target_list = Some("list1".to_string());
+
+        app.start_multiple_upload(paths, egui::Context::default());
+        wait_for_workers(&app);
+        assert_eq!(*mock.list_updates.lock().unwrap(), [("list1".to_string(), vec!["old".to_string(), "up1".to_string(), "up2".to_string()])]);
+
+        // Anonymous uploads aren't part of the account and stay out of lists
+        app.upload_anonymously = true;
+        app.start_upload(dir.join("a.txt"));
+        wait_for_workers(&app);
+        assert_eq!(mock.list_updates.lock().unwrap().len(), 1);
+        assert!(app.state.lock().unwrap().last_error.is_none());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn uploads_over_the_quota_wait_for_confirmation() {
+        let dir = temp_dir();
+        let path = dir.join("big.bin");
+        fs::write(&path, [0u8; 20]).unwrap();
+        let mock = Arc::new(MockApi { verify_matches: true, ..Default::default() });
+        let mut app = app_with(mock.clone(), &dir);
+        app.state.lock().unwrap().user_info = Some(user_with_space(90, 100));
+
+        app.start_multiple_upload(vec![path.clone()], egui::Context::default());
+        wait_for_workers(&app);
+        assert!(mock.uploads.lock().unwrap().is_empty());
+        assert_eq!(app.upload_quota_warning.as_ref().map(|(_, upload)| upload), Some(&HeldUpload::Files(vec![path.clone()])));
+
+        app.upload_quota_confirmed = true;
+        app.start_multiple_upload(vec![path.clone()], egui::Context::default());
+        wait_for_workers(&app);
+        assert_eq!(*mock.uploads.lock().unwrap(), [path]);
+        assert!(app.upload_quota_warning.is_none() && !app.upload_quota_confirmed);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn a_refused_key_pauses_the_remaining_uploads_until_it_is_replaced() {
+        let dir = temp_dir();
+        let paths: Vec<PathBuf> = ["a.txt", "b.txt", "c.txt"].iter().map(|name| {
+            let path = dir.join(name);
+            fs::write(&path, name).unwrap();
+            path
+        }).collect();
+        let mock = Arc::new(MockApi { refuse_uploads_after: Mutex::new(Some(1)), ..Default::default() });
+        let mut app = app_with(mock.clone(), &dir);
+        let ctx = egui::Context::default();
+
+        app.start_multiple_upload(paths.clone(), ctx.clone());
+        wait_for_workers(&app);
+        assert_eq!(*mock.uploads.lock().unwrap(), [paths[0].clone()]);
+        {
+            let state = app.state.lock().unwrap();
+            assert!(state.api_key_rejected);
+            assert_eq!(state.paused_uploads, paths[1..]);
+        }
+        // Nothing is retried while the key is still the refused one
+        let _ = ctx.run(Default::default(), |ctx| app.render_reauth_prompt(ctx));
+        wait_for_workers(&app);
+        assert_eq!(mock.uploads.lock().unwrap().len(), 1);
+
+        *mock.refuse_uploads_after.lock().unwrap() = None;
+        app.state.lock().unwrap().api_key_rejected = false;
+        let _ = ctx.run(Default::default(), |ctx| app.render_reauth_prompt(ctx));
+        wait_for_workers(&app);
+        assert_eq!(*mock.uploads.lock().unwrap(), paths);
+        assert!(app.state.lock().unwrap().paused_uploads.is_empty());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn interrupted_transfers_are_resumed_after_a_restart() {
+        let dir = temp_dir();
+        let upload = dir.join("notes.txt");
+        fs::write(&upload, b"notes").unwrap();
+        let mock = Arc::new(MockApi { account_files: vec![file("abc", "movie.mkv")], ..Default::default() });
+        let journal_path = dir.join("transfers.json");
+        let mut app = app_with(mock.clone(), &dir);
+        app.journal = resume::Journal::open(journal_path.clone());
+
+        // Closed before the worker got to either of them
+        app.workers.shutdown(Duration::ZERO);
+        app.start_multiple_upload(vec![upload.clone()], egui::Context::default());
+        app.download_url = "https://pixeldrain.com/u/abc".to_string();
+        let download = app.journal.record(vec![resume::Transfer::Download { url: app.download_url.clone(), path: None, bytes_done: 0 }]);
+        let partial = dir.join("movie.mkv");
+        fs::write(&partial, b"partial-").unwrap();
+        download.set_path(0, partial.clone());
+        std::mem::forget(download);
+        wait_for_workers(&app);
+
+        let mut app = app_with(mock.clone(), &dir);
+        app.journal = resume::Journal::open(journal_path);
+        app.interrupted = app.journal.interrupted();
+        assert_eq!(app.interrupted, [
+            resume::Transfer::Upload { path: upload.clone() },
+            resume::Transfer::Download { url: "https://pixeldrain.com/u/abc".to_string(), path: Some(partial.clone()), bytes_done: 8 },
+        ]);
+        app.resuming = app.journal.take_interrupted();
+        app.start_resumed(&egui::Context::default());
+        wait_for_workers(&app);
+        assert_eq!(*mock.uploads.lock().unwrap(), [upload]);
+        assert_eq!(fs::read_to_string(&partial).unwrap(), "partial-abc");
+        assert!(app.resuming.is_empty());
+        assert!(app.journal.interrupted().is_empty());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn uploaded_batches_can_be_made_a_list_afterwards() {
+        let dir = temp_dir();
+        let paths: Vec<PathBuf> = ["a.txt", "b.txt"].iter().map(|name| {
+            let path = dir.join(name);
+            fs::write(&path, name).unwrap();
+            path
+        }).collect();
+        let mock = Arc::new(MockApi { list_files: vec![file("old", "old.txt")], ..Default::default() });
+        let mut app = app_with(mock.clone(), &dir);
+
+        app.start_multiple_upload(paths.clone(), egui::Context::default());
+        wait_for_workers(&app);
+        assert_eq!(*app.last_batch.lock().unwrap(), [("up1".to_string(), "a.txt".to_string()), ("up2".to_string(), "b.txt".to_string())]);
+        app.batch_list_title = "Album".to_string();
+        app.list_from_batch();
+        wait_for_workers(&app);
+        assert_eq!(*mock.created_lists.lock().unwrap(), [("Album".to_string(), vec!["up1".to_string(), "up2".to_string()])]);
+        assert_eq!(*app.batch_list_result.lock().unwrap(), Some(Ok(format!("{}/l/list1", pixeldrain_api::BASE_URL))));
+        assert!(app.last_batch.lock().unwrap().is_empty());
+
+        // Or added to a list that exists already
+        app.start_multiple_upload(paths, egui::Context::default());
+        wait_for_workers(&app);
+        app.batch_list_target = Some("list1".to_string());
+        app.list_from_batch();
+        wait_for_workers(&app);
+        assert_eq!(*mock.list_updates.lock().unwrap(), [("list1".to_string(), vec!["old".to_string(), "up3".to_string(), "up4".to_string()])]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn account_backups_download_every_file_with_a_manifest() {
+        let dir = temp_dir();
+        let list = ListInfo { id: "list1".to_string(), title: "Notes".to_string(), date_created: Utc::now(), file_count: 1, files: None, can_edit: true };
+        let mock = Arc::new(MockApi {
+            account_files: vec![file("a1", "photo.jpg"), file("b2", "photo.jpg"), FileInfo { size: 2, ..file("c3", "notes.txt") }],
+            account_lists: vec![list],
+            list_files: vec![file("c3", "notes.txt")],
+            ..Default::default()
         });
+        let mut app = app_with(mock, &dir);
+        let folder = dir.join("backup");
+        // Left by an earlier backup, it isn't downloaded again
+        fs::create_dir_all(folder.join("files")).unwrap();
+        fs::write(folder.join("files/notes.txt"), "hi").unwrap();
+
+        app.account_export_concurrency = 2;
+        app.start_account_export(folder.clone());
+        wait_for_workers(&app);
+        assert_eq!(*app.account_export_result.lock().unwrap(), Some(Ok(backup::Summary { folder: folder.clone(), saved: 3, failed: 0 })));
+        assert_eq!(fs::read_to_string(folder.join("files/photo (a1).jpg")).unwrap(), "a1");
+        assert_eq!(fs::read_to_string(folder.join("files/photo (b2).jpg")).unwrap(), "b2");
+        assert_eq!(fs::read_to_string(folder.join("files/notes.txt")).unwrap(), "hi");
+
+        let manifest: backup::Manifest = serde_json::from_str(&fs::read_to_string(folder.join("manifest.json")).unwrap()).unwrap();
+        let paths: Vec<&str> = manifest.files.iter().map(|file| file.path.as_str()).collect();
+        assert_eq!(paths, ["files/photo (a1).jpg", "files/photo (b2).jpg", "files/notes.txt"]);
+        assert_eq!(manifest.files[2].info.size, 2);
+        assert_eq!(manifest.lists.len(), 1);
+        assert_eq!(manifest.lists[0].files, [pixeldrain_api::ListFile { id: "c3".to_string(), description: String::new() }]);
+        assert!(app.account_export_progress.lock().unwrap().is_none());
+        let _ = fs::remove_dir_all(&dir);
     }
 
-    fn save_settings(&self, api_key: String, download_location: String) {
-        let mut state = self.state.lock().unwrap();
-        state.api_key = api_key;
-        state.download_location = download_location;
-        state.last_error = None;
-        
-        // Try to save settings to file
-        if let Err(e) = self.persist_settings(&state) {
-            state.last_error = Some(format!("Failed to save settings: {}", e));
-        } else {
-            state.last_error = None;
+    #[test]
+    fn folder_sync_previews_then_uploads_changes_and_offers_to_delete_removed_files() {
+        let dir = temp_dir();
+        let folder = dir.join("synced");
+        fs::create_dir_all(folder.join("sub")).unwrap();
+        fs::write(folder.join("same.txt"), "same").unwrap();
+        fs::write(folder.join("new.txt"), "new").unwrap();
+        fs::write(folder.join("edited.txt"), "v2").unwrap();
+        fs::write(folder.join("sub/nested.txt"), "nested").unwrap();
+        let same_hash = pixeldrain_api::sha256_file(folder.join("same.txt")).unwrap();
+        let mock = Arc::new(MockApi {
+            account_files: vec![
+                FileInfo { size: 4, hash_sha256: same_hash, ..file("s1", "same.txt") },
+                FileInfo { size: 2, hash_sha256: "00".to_string(), ..file("e1", "edited.txt") },
+                file("g1", "gone.txt"),
+                file("u1", "unrelated.txt"),
+            ],
+            ..Default::default()
+        });
+        let mut app = app_with(mock.clone(), &dir);
+        app.add_sync_pair(&folder);
+        {
+            let mut state = app.state.lock().unwrap();
+            let pair = &mut state.sync_pairs[0];
+            pair.rules.propagate_deletes = true;
+            // g1 came from an earlier sync, d1 was deleted since
+            pair.uploaded = vec!["e1".to_string(), "g1".to_string(), "d1".to_string()];
         }
+
+        app.start_folder_sync(false);
+        wait_for_workers(&app);
+        let plan = app.sync_plan.lock().unwrap().clone().unwrap().unwrap();
+        assert_eq!(plan.actions, [
+            sync::Action::Replace { path: folder.join("edited.txt"), old_id: "e1".to_string() },
+            sync::Action::Upload(folder.join("new.txt")),
+            sync::Action::Delete { id: "g1".to_string(), name: "gone.txt".to_string() },
+        ]);
+        assert_eq!(plan.unchanged, 1);
+        assert!(mock.uploads.lock().unwrap().is_empty(), "a preview changes nothing");
+        assert!(app.sync_report.lock().unwrap().is_none());
+
+        app.start_folder_sync(true);
+        wait_for_workers(&app);
+        assert_eq!(*mock.uploads.lock().unwrap(), [folder.join("edited.txt"), folder.join("new.txt")]);
+        // Only the old version of the replaced file, gone.txt waits for the user
+        assert_eq!(*mock.deleted.lock().unwrap(), ["e1"]);
+        assert_eq!(*app.sync_report.lock().unwrap(), Some(sync::Report { uploaded: 2, failures: Vec::new() }));
+        assert!(app.sync_progress.lock().unwrap().is_none());
+        assert_eq!(app.state.lock().unwrap().sync_pairs[0].uploaded, ["g1", "up1", "up2"]);
+
+        app.delete_sync_removed_files();
+        assert_eq!(app.delete_confirm, Some(undo::Deletion::Files(vec!["g1".to_string()])));
+        let plan = app.sync_plan.lock().unwrap().clone().unwrap().unwrap();
+        assert!(plan.deletions().is_empty());
+        app.delete_confirm = None;
+        app.queue_delete(undo::Deletion::Files(vec!["g1".to_string()]));
+        app.run_due_deletes(Instant::now() + Duration::from_secs(3600));
+        wait_for_workers(&app);
+        assert_eq!(*mock.deleted.lock().unwrap(), ["e1", "g1"]);
+        let _ = fs::remove_dir_all(&dir);
     }
-    
-    fn save_theme_settings(&self, dark_mode: bool) {
-        let mut state = self.state.lock().unwrap();
-        state.dark_mode = dark_mode;
-        
-        // Try to save settings to file
-        if let Err(e) = self.persist_settings(&state) {
-            state.last_error = Some(format!("Failed to save theme settings: {}", e));
-        } else {
-            state.last_error = None;
+
+    #[test]
+    fn folder_sync_pairs_keep_their_own_rules_and_sync_to_their_list() {
+        let dir = temp_dir();
+        let folder = dir.join("synced");
+        fs::create_dir_all(&folder).unwrap();
+        fs::write(folder.join("listed.txt"), "listed").unwrap();
+        fs::write(folder.join("new.txt"), "new").unwrap();
+        let listed_hash = pixeldrain_api::sha256_file(folder.join("listed.txt")).unwrap();
+        let mock = Arc::new(MockApi {
+            // The account has new.txt, but not in the list
+            account_files: vec![file("n1", "new.txt")],
+            list_files: vec![FileInfo { size: 6, hash_sha256: listed_hash, ..file("l1", "listed.txt") }],
+            ..Default::default()
+        });
+        let mut app = app_with(mock.clone(), &dir);
+        app.add_sync_pair(&folder);
+        {
+            let mut state = app.state.lock().unwrap();
+            state.sync_pairs[0].list = Some("L1".to_string());
+            state.sync_pairs[0].uploaded = vec!["l1".to_string()];
         }
+        // The same folder to the whole account, only its pictures
+        app.add_sync_pair(&folder);
+        assert_eq!(app.sync_selected, 1);
+        app.state.lock().unwrap().sync_pairs[1].rules.include_extensions = vec!["jpg".to_string()];
+
+        app.start_folder_sync(false);
+        wait_for_workers(&app);
+        let plan = app.sync_plan.lock().unwrap().clone().unwrap().unwrap();
+        assert!(plan.actions.is_empty());
+        assert_eq!(plan.excluded, 2);
+
+        app.select_sync_pair(0);
+        app.start_folder_sync(true);
+        wait_for_workers(&app);
+        assert_eq!(*mock.uploads.lock().unwrap(), [folder.join("new.txt")]);
+        assert_eq!(*mock.list_updates.lock().unwrap(), [("L1".to_string(), vec!["l1".to_string(), "up1".to_string()])]);
+        let state = app.state.lock().unwrap();
+        assert_eq!(state.sync_pairs[0].uploaded, ["l1", "up1"]);
+        assert!(state.sync_pairs[1].uploaded.is_empty());
+        drop(state);
+
+        app.remove_sync_pair();
+        let state = app.state.lock().unwrap();
+        assert_eq!(state.sync_pairs.len(), 1);
+        assert_eq!(state.sync_pairs[0].list, None);
+        drop(state);
+        let _ = fs::remove_dir_all(&dir);
     }
-    
-    fn persist_settings(&self, state: &AppState) -> Result<(), Box<dyn std::error::Error>> {
-        use std::fs;
-        use serde_json;
-        
-        // Create settings directory if it doesn't exist
-        let settings_dir = directories::ProjectDirs::from("com", "pixeldrain", "client")
-            .map(|proj_dirs| proj_dirs.config_dir().to_path_buf())
-            .unwrap_or_else(|| PathBuf::from("."));
-        fs::create_dir_all(&settings_dir)?;
-        
-        // Save settings to JSON file
-        let settings_file = settings_dir.join("settings.json");
-        let settings_data = serde_json::to_string_pretty(&state)?;
-        fs::write(settings_file, settings_data)?;
-        
-        Ok(())
+
+    #[test]
+    fn settings_exports_leave_out_webhook_urls_unless_encrypted() {
+        let dir = temp_dir();
+        let mut app = app_with(Arc::new(MockApi::default()), &dir);
+        app.state.lock().unwrap().notification_sinks.webhook_url = "https://hooks.example.com/T0/secret-token".to_string();
+
+        let plain = app.settings_export_data().unwrap();
+        assert!(!plain.contains("secret-token"));
+        app.export_include_api_keys = true;
+        app.export_passphrase = "correct horse".to_string();
+        let encrypted = app.settings_export_data().unwrap();
+        assert!(!encrypted.contains("secret-token"));
+
+        // Importing an export without it keeps the URL already set
+        app.state.lock().unwrap().notification_sinks.webhook_url = "https://hooks.example.com/T1/current".to_string();
+        app.export_passphrase.clear();
+        app.import_settings_data(&plain).unwrap();
+        assert_eq!(app.state.lock().unwrap().notification_sinks.webhook_url, "https://hooks.example.com/T1/current");
+        app.export_passphrase = "correct horse".to_string();
+        app.import_settings_data(&encrypted).unwrap();
+        assert_eq!(app.state.lock().unwrap().notification_sinks.webhook_url, "https://hooks.example.com/T0/secret-token");
+        // Saved in the test's folder, not over the user's settings
+        assert!(fs::read_to_string(dir.join("settings.json")).unwrap().contains("T0/secret-token"));
+        let _ = fs::remove_dir_all(&dir);
     }
-    
-    fn get_default_download_location() -> String {
-        use std::env;
-        
-        #[cfg(target_os = "windows")]
-        {
-            // Windows: %USERPROFILE%\Downloads
-            if let Ok(userprofile) = env::var("USERPROFILE") {
-                return format!("{}\\Downloads", userprofile);
-            }
-        }
-        
-        #[cfg(target_os = "macos")]
+
+    #[test]
+    fn app_data_archives_restore_settings_history_and_cache() {
+        let dir = temp_dir();
+        let archive = dir.join("app-data.zip");
+        let app = app_with(Arc::new(MockApi::default()), &dir);
         {
-            // macOS: /Users/$USER/Downloads
-            if let Ok(home) = env::var("HOME") {
-                return format!("{}/Downloads", home);
-            }
+            let mut state = app.state.lock().unwrap();
+            state.storage = storage::in_memory();
+            state.plaintext_api_key = true;
+            state.dark_mode = false;
+            state.ensure_profile();
+            state.record_download(DownloadHistoryEntry {
+                url: "https://pixeldrain.com/u/abc".to_string(),
+                filename: "a.txt".to_string(),
+                local_path: "/tmp/a.txt".to_string(),
+                timestamp: Utc::now(),
+                error: None,
+            });
+            state.replace_file_list(vec![file("abc", "a.txt")]);
         }
-        
-        #[cfg(target_os = "linux")]
+        app.export_app_data(&archive).unwrap();
+
+        // Another setup, with a settings file of its own
+        let restored_dir = temp_dir();
+        let mut restored = app_with(Arc::new(MockApi::default()), &restored_dir);
         {
-            // Linux: $HOME/Downloads
-            if let Ok(home) = env::var("HOME") {
-                return format!("{}/Downloads", home);
-            }
+            let mut state = restored.state.lock().unwrap();
+            state.storage = storage::in_memory();
+            state.storage_backend = StorageBackend::Json;
+            state.dark_mode = true;
+            state.ensure_profile();
         }
-        
-        // Fallback: current directory
-        ".".to_string()
+        assert_eq!(restored.import_app_data(&archive).unwrap(), 1);
+        let state = restored.state.lock().unwrap();
+        assert!(!state.dark_mode);
+        assert_eq!(state.storage_backend, StorageBackend::Json, "the store in use is kept");
+        assert_eq!(state.file_list.len(), 1);
+        assert!(state.storage.has_downloads().unwrap());
+        // The API key is only in the archive when it's encrypted
+        assert_eq!(state.api_key, "test-key");
+        drop(state);
+        let saved: serde_json::Value = serde_json::from_str(&fs::read_to_string(restored_dir.join("settings.json")).unwrap()).unwrap();
+        assert_eq!(saved["dark_mode"], false);
+        let _ = fs::remove_dir_all(&dir);
+        let _ = fs::remove_dir_all(&restored_dir);
     }
 
-    fn load_settings(&mut self) {
-        use std::fs;
-        use serde_json;
-        
-        let settings_file = directories::ProjectDirs::from("com", "pixeldrain", "client")
-            .map(|proj_dirs| proj_dirs.config_dir().join("settings.json"))
-            .unwrap_or_else(|| PathBuf::from("settings.json"));
-            
-        if let Ok(data) = fs::read_to_string(settings_file) {
-            if let Ok(loaded_state) = serde_json::from_str::<AppState>(&data) {
-                let mut state = self.state.lock().unwrap();
-                state.api_key = loaded_state.api_key;
-                // Use loaded download location if it's not empty, otherwise use default
-                if !loaded_state.download_location.is_empty() {
-                    state.download_location = loaded_state.download_location;
-                } else {
-                    state.download_location = Self::get_default_download_location();
-                }
-                // Load theme preference
-                state.dark_mode = loaded_state.dark_mode;
-                // Don't overwrite history and other runtime data
-            } else {
-                // If settings file is corrupted, set default download location
-                let mut state = self.state.lock().unwrap();
-                state.download_location = Self::get_default_download_location();
-            }
-        } else {
-            // If no settings file exists, set default download location
-            let mut state = self.state.lock().unwrap();
-            state.download_location = Self::get_default_download_location();
-        }
+    #[test]
+    fn link_bundles_get_a_list_and_an_index_page() {
+        let dir = temp_dir();
+        let paths: Vec<PathBuf> = ["a.txt", "b.txt"].iter().map(|name| {
+            let path = dir.join(name);
+            fs::write(&path, name).unwrap();
+            path
+        }).collect();
+        let mock = Arc::new(MockApi { verify_matches: true, ..Default::default() });
+        let mut app = app_with(mock.clone(), &dir);
+        let index_path = dir.join("holiday.md");
+        app.upload_bundle = Some(index_path.clone());
+
+        app.start_multiple_upload(paths, egui::Context::default());
+        wait_for_workers(&app);
+        assert_eq!(*mock.created_lists.lock().unwrap(), [("holiday".to_string(), vec!["up1".to_string(), "up2".to_string()])]);
+        let page = fs::read_to_string(&index_path).unwrap();
+        assert!(page.starts_with("# holiday\n"));
+        assert!(page.contains("/l/list1)") && page.contains("[a.txt](") && page.contains("[b.txt]("));
+        assert!(app.upload_bundle.is_none());
+        let _ = fs::remove_dir_all(&dir);
     }
-    
-    fn apply_theme_on_startup(&self, ctx: &egui::Context) {
-        let dark_mode = {
-            let state = self.state.lock().unwrap();
-            state.dark_mode
+
+    #[test]
+    fn images_that_look_the_same_are_grouped_oldest_first() {
+        use chrono::TimeZone;
+        let dir = temp_dir();
+        let png = |flip: bool, width: u32| {
+            let image = image::DynamicImage::ImageLuma8(image::GrayImage::from_fn(width, 32, |x, _| {
+                let value = (x * 255 / (width - 1)) as u8;
+                image::Luma([if flip { 255 - value } else { value }])
+            }));
+            let mut bytes = Vec::new();
+            image.write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageOutputFormat::Png).unwrap();
+            bytes
         };
-        if dark_mode {
-            ctx.set_visuals(egui::Visuals::dark());
-        } else {
-            ctx.set_visuals(egui::Visuals::light());
-        }
-        // Window icon setting at runtime is not supported in this eframe version
+        let day = |day: u32| Utc.with_ymd_and_hms(2024, 1, day, 0, 0, 0).unwrap();
+        let images = [
+            FileInfo { date_upload: day(3), ..file("new", "copy.png") },
+            FileInfo { date_upload: day(2), ..file("other", "other.png") },
+            FileInfo { date_upload: day(1), ..file("old", "original.png") },
+            file("broken", "broken.png"),
+        ];
+        let mock = MockApi {
+            thumbnails: HashMap::from([
+                ("new".to_string(), png(false, 128)),
+                ("other".to_string(), png(true, 64)),
+                ("old".to_string(), png(false, 64)),
+                ("broken".to_string(), b"not an image".to_vec()),
+            ]),
+            ..Default::default()
+        };
+        let hashed = Mutex::new(0);
+
+        let groups = PixelDrainApp::visual_duplicate_groups(&mock, &images, Some(&dir), &Limit::new(1), || false, |done| *hashed.lock().unwrap() = done);
+        assert_eq!(groups, [vec!["old".to_string(), "new".to_string()]]);
+        assert_eq!(*hashed.lock().unwrap(), 4);
+        // Thumbnails are cached for the next search
+        assert!(cache::read_thumbnail(&dir, "old").is_some());
+        let _ = fs::remove_dir_all(&dir);
     }
 
-    fn format_file_size(&self, path: &PathBuf) -> String {
-        if let Ok(metadata) = fs::metadata(path) {
-            self.format_file_size_bytes(metadata.len())
-        } else {
-            "Unknown size".to_string()
-        }
+    #[test]
+    fn inactivity_expiry_counts_from_the_last_view() {
+        use chrono::TimeZone;
+        let uploaded = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let mut info = FileInfo { date_upload: uploaded, ..file("f1", "one.txt") };
+        assert_eq!(PixelDrainApp::inactivity_expiry(&info, 0), None);
+        assert_eq!(PixelDrainApp::inactivity_expiry(&info, 60), Some(uploaded + chrono::Duration::days(60)));
+        info.date_last_view = uploaded + chrono::Duration::days(10);
+        assert_eq!(PixelDrainApp::inactivity_expiry(&info, 60), Some(uploaded + chrono::Duration::days(70)));
     }
 
-    fn format_file_size_bytes(&self, bytes: u64) -> String {
-        const KB: f64 = 1024.0;
-        const MB: f64 = KB * 1024.0;
-        const GB: f64 = MB * 1024.0;
-        
-        let bytes_f = bytes as f64;
-        
-        if bytes_f >= GB {
-            format!("{:.2} GB", bytes_f / GB)
-        } else if bytes_f >= MB {
-            format!("{:.2} MB", bytes_f / MB)
-        } else if bytes_f >= KB {
-            format!("{:.2} KB", bytes_f / KB)
-        } else {
-            format!("{} B", bytes)
-        }
+    #[test]
+    fn list_files_are_fetched_for_editing_and_saved_with_descriptions() {
+        let dir = temp_dir();
+        let mock = Arc::new(MockApi {
+            list_files: vec![file("f1", "one.txt"), file("f2", "two.txt"), file("f3", "three.txt")],
+            ..Default::default()
+        });
+        let mut app = app_with(mock.clone(), &dir);
+
+        app.select_list("list1".to_string(), "Holiday".to_string());
+        wait_for_workers(&app);
+        app.apply_list_details();
+        assert_eq!(app.edit_list_title, "Holiday: photos");
+        assert_eq!(app.edit_list_names["f2"], "two.txt");
+        let ids: Vec<&str> = app.edit_list_files.iter().map(|f| f.id.as_str()).collect();
+        assert_eq!(ids, ["f1", "f2", "f3"]);
+
+        app.edit_list_files[0].description = "The beach".to_string();
+        app.edit_list_files.remove(1);
+        app.update_list("list1");
+        assert_eq!(*mock.list_updates.lock().unwrap(), [("list1".to_string(), vec!["f1".to_string(), "f3".to_string()])]);
+        assert_eq!(*mock.list_descriptions.lock().unwrap(), [vec!["The beach".to_string(), String::new()]]);
+
+        // Dragged into a new order, which is saved as is
+        PixelDrainApp::move_list_file(&mut app.edit_list_files, 0, 1);
+        PixelDrainApp::move_list_file(&mut app.edit_list_files, 0, 5);
+        app.update_list("list1");
+        assert_eq!(mock.list_updates.lock().unwrap()[1].1, ["f3", "f1"]);
+        assert_eq!(mock.list_descriptions.lock().unwrap()[1], ["", "The beach"]);
+        let _ = fs::remove_dir_all(&dir);
     }
 
-    fn fetch_user_info(&mut self) {
-        // Set loading state
-        *self.user_info_loading.lock().unwrap() = true;
-        
-        // Get API key with settings priority
-        let api_key = self.get_api_key();
+    #[test]
+    fn list_download_saves_files_and_history() {
+        let dir = temp_dir();
+        let mock = Arc::new(MockApi {
+            list_files: vec![file("f1", "one.txt"), file("f2", "two.txt")],
+            ..Default::default()
+        });
+        let mut app = app_with(mock, &dir);
+        app.download_url = format!("{}/l/list1", pixeldrain_api::BASE_URL);
 
-        if let Some(_key) = api_key {
-            let client = self.make_api_client();
-            let state = self.state.clone();
-            let user_info_loading = self.user_info_loading.clone();
+        app.start_download();
+        wait_for_workers(&app);
 
-            thread::spawn(move || {
-                let start_time = Instant::now();
-                let mut last_error = None;
+        let folder = dir.join("Holiday_ photos");
+        assert_eq!(fs::read_to_string(folder.join("one.txt")).unwrap(), "f1");
+        assert_eq!(fs::read_to_string(folder.join("two.txt")).unwrap(), "f2");
+        let state = app.state.lock().unwrap();
+        assert!(state.last_error.is_none());
+        let history = state.storage.downloads(&HistoryViewOptions::default(), 10).unwrap();
+        let urls: Vec<&str> = history.iter().rev().map(|(e, _)| e.url.as_str()).collect();
+        assert_eq!(urls, [
+            format!("{}/u/f1", pixeldrain_api::BASE_URL),
+            format!("{}/u/f2", pixeldrain_api::BASE_URL),
+        ]);
+        let _ = fs::remove_dir_all(&dir);
+    }
 
-                for attempt in 1..=3 { // Retry up to 3 times
-                    match client.get_user() {
-                        Ok(user_info) => {
-                            let _duration = start_time.elapsed();
-                            {
-                                let mut state = state.lock().unwrap();
-                                state.user_info = Some(user_info);
-                                state.last_error = None;
+    #[test]
+    fn history_date_range_covers_whole_local_days() {
+        let since = history_day_bound("2024-05-01", false).unwrap().unwrap();
+        let until = history_day_bound(" 2024-05-01 ", true).unwrap().unwrap();
+        assert_eq!(until - since, chrono::Duration::days(1));
+        assert_eq!(since.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string(), "2024-05-01 00:00");
+        assert_eq!(history_day_bound("", true), Ok(None));
+        assert_eq!(history_day_bound("May 1st", false), Err("May 1st".to_string()));
+    }
 
-                                state.last_operation_time = Some(chrono::Utc::now());
-                            }
-                            *user_info_loading.lock().unwrap() = false;
-                            break;
-                        }
-                        Err(e) => {
-                            last_error = Some(e);
-                            let should_retry = match &last_error.as_ref().unwrap() {
-                                pixeldrain_api::PixelDrainError::Reqwest(reqwest_err) => {
-                                    reqwest_err.is_timeout() || 
-                                    reqwest_err.is_connect() || 
-                                    reqwest_err.is_request() ||
-                                    reqwest_err.to_string().contains("request or response body error")
-                                }
-                                pixeldrain_api::PixelDrainError::Api(api_err) => {
-                                    api_err.status.is_server_error()
-                                }
-                                _ => false,
-                            };
-                            if should_retry && attempt < 3 {
-                                std::thread::sleep(std::time::Duration::from_secs(3)); // Retry after 3 seconds
-                            } else {
-                                break;
-                            }
-                        }
-                    }
-                }
+    #[test]
+    fn history_in_old_settings_is_read_but_not_written() {
+        let settings = serde_json::json!({
+            "api_key": "",
+            "download_location": "",
+            "upload_history": [{
+                "id": "abc",
+                "url": "https://pixeldrain.com/u/abc",
+                "filename": "a.txt",
+                "size": 3,
+                "timestamp": "2024-05-01T10:00:00Z",
+            }],
+            "download_history": [],
+            "last_error": null,
+            "file_list": [],
+            "user_info": null,
+            "last_operation_time": null,
+            "dark_mode": true,
+        });
+        let state: AppState = serde_json::from_value(settings).unwrap();
+        assert_eq!(state.legacy_upload_history.len(), 1);
+        assert_eq!(state.legacy_upload_history[0].filename, "a.txt");
 
-                if let Some(error_msg) = last_error {
-                    let _duration = start_time.elapsed();
-                    let mut state = state.lock().unwrap();
-                    state.last_error = Some(format!("Failed to fetch user info after {} attempts: {}", 
-                        3, error_msg));
+        let saved = PixelDrainApp::settings_json(&state, false).unwrap();
+        assert!(saved.get("upload_history").is_none());
+        assert!(saved.get("download_history").is_none());
+    }
 
-                }
-                *user_info_loading.lock().unwrap() = false;
-            });
-        } else {
-            self.state.lock().unwrap().last_error = Some("No API key available (check settings or environment variable PIXELDRAIN_API_KEY). Cannot fetch user info.".to_string());
-        }
+    #[test]
+    fn batch_delete_keeps_files_that_failed() {
+        let dir = temp_dir();
+        let mock = Arc::new(MockApi { missing_files: vec!["b".to_string()], ..Default::default() });
+        let app = app_with(mock.clone(), &dir);
+        app.state.lock().unwrap().file_list = vec![file("a", "a.txt"), file("b", "b.txt"), file("c", "c.txt")];
+
+        app.start_batch_delete(vec!["a".to_string(), "b".to_string(), "c".to_string()], true);
+        wait_for_workers(&app);
+
+        assert_eq!(*mock.deleted.lock().unwrap(), ["a", "c"]);
+        let state = app.state.lock().unwrap();
+        let remaining: Vec<&str> = state.file_list.iter().map(|f| f.id.as_str()).collect();
+        assert_eq!(remaining, ["b"]);
+        assert!(state.last_error.as_deref().unwrap().starts_with("Deleted 2 of 3 files"));
+        assert!(app.batch_delete_progress.lock().unwrap().is_none());
+        let _ = fs::remove_dir_all(&dir);
     }
 
-    // Retry utility specifically for PixelDrainError to eliminate code duplication
-    fn retry_pixeldrain_operation<F, T>(
-        operation: F,
-        max_retries: usize,
-        retry_delay: std::time::Duration,
-    ) -> Result<T, pixeldrain_api::PixelDrainError>
-    where
-        F: Fn() -> Result<T, pixeldrain_api::PixelDrainError>,
-    {
-        let mut last_error = None;
-        
-        for attempt in 1..=max_retries {
-            match operation() {
-                Ok(result) => return Ok(result),
-                Err(e) => {
-                    // Check if this is a retryable error
-                    let should_retry = match &e {
-                        pixeldrain_api::PixelDrainError::Reqwest(reqwest_err) => {
-                            reqwest_err.is_timeout() || 
-                            reqwest_err.is_connect() || 
-                            reqwest_err.is_request() ||
-                            reqwest_err.to_string().contains("request or response body error")
-                        }
-                        pixeldrain_api::PixelDrainError::Api(api_err) => {
-                            api_err.status.is_server_error()
-                        }
-                        _ => false,
-                    };
-                    
-                    if should_retry && attempt < max_retries {
-                        std::thread::sleep(retry_delay);
-                        continue;
-                    } else {
-                        last_error = Some(e);
-                        break;
-                    }
-                }
-            }
-        }
+    #[test]
+    fn batch_deletions_pending_at_exit_are_sent_in_full() {
+        let dir = temp_dir();
+        let mock = Arc::new(MockApi::default());
+        let mut app = app_with(mock.clone(), &dir);
+        app.state.lock().unwrap().file_list = vec![file("a", "a.txt"), file("b", "b.txt"), file("c", "c.txt")];
+
+        app.queue_delete(undo::Deletion::Files(vec!["a".to_string(), "b".to_string(), "c".to_string()]));
+        assert!(mock.deleted.lock().unwrap().is_empty());
+        eframe::App::on_exit(&mut app, None);
+
+        assert_eq!(*mock.deleted.lock().unwrap(), ["a", "b", "c"]);
+        assert!(app.state.lock().unwrap().file_list.is_empty());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn deletions_are_confirmed_and_wait_for_the_undo_window() {
+        let dir = temp_dir();
+        let mock = Arc::new(MockApi::default());
+        let mut app = app_with(mock.clone(), &dir);
+        app.state.lock().unwrap().file_list = vec![file("a", "a.txt"), file("b", "b.txt")];
+
+        app.queue_delete(undo::Deletion::Files(vec!["a".to_string()]));
+        assert_eq!(app.pending_deletes.pending()[0].label, "a.txt");
+        assert_eq!(app.filtered_files(app.state.lock().unwrap().file_list.clone()).len(), 1, "hidden while it waits");
+        let undone = app.pending_deletes.pending()[0].id;
+        app.pending_deletes.undo(undone);
+        app.queue_delete(undo::Deletion::Files(vec!["b".to_string()]));
+        app.run_due_deletes(Instant::now());
+        wait_for_workers(&app);
+        assert!(mock.deleted.lock().unwrap().is_empty(), "not due yet");
+
+        app.run_due_deletes(Instant::now() + Duration::from_secs(undo::DEFAULT_UNDO_SECS));
+        wait_for_workers(&app);
+        assert_eq!(*mock.deleted.lock().unwrap(), ["b"]);
+        assert!(app.pending_deletes.pending().is_empty());
+
+        // Without an undo window they go right away, once confirmed
+        app.state.lock().unwrap().delete_undo_secs = 0;
+        app.selected_files.insert("a".to_string());
+        app.request_delete(undo::Deletion::Files(vec!["a".to_string()]));
+        assert_eq!(app.delete_confirm, Some(undo::Deletion::Files(vec!["a".to_string()])));
+        wait_for_workers(&app);
+        assert_eq!(*mock.deleted.lock().unwrap(), ["b"], "waits for the confirmation");
+        // Cancelled, the file is still selected
+        app.delete_confirm = None;
+        assert!(app.selected_files.contains("a"));
+        app.state.lock().unwrap().confirm_deletes = false;
+        app.request_delete(undo::Deletion::Files(vec!["a".to_string()]));
+        wait_for_workers(&app);
+        assert_eq!(*mock.deleted.lock().unwrap(), ["b", "a"]);
+        assert!(app.selected_files.is_empty());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn download_usage_starts_over_each_month() {
+        let mut usage = DownloadUsage {
+            month: "2000-01".to_string(),
+            bytes: 5_000,
+            rate_limited: 2,
+            server: None,
+        };
+        usage.record(1_000);
+        assert_eq!(usage.bytes, 1_000);
+        assert_eq!(usage.rate_limited, 0);
         
-        Err(last_error.unwrap())
+        usage.record(500);
+        usage.record_error(&PixelDrainError::RateLimited {
+            retry_after: std::time::Duration::from_secs(30),
+            error: pixeldrain_api::ApiError {
+                status: reqwest::StatusCode::TOO_MANY_REQUESTS,
+                value: "rate_limited".to_string(),
+                message: String::new(),
+                endpoint: None,
+                request_id: None,
+            },
+        });
+        assert_eq!(usage.bytes, 1_500);
+        assert_eq!(usage.rate_limited, 1);
     }
 
-    #[allow(dead_code)]
-    fn toggle_theme(&mut self, ctx: &egui::Context) {
-        // Toggle dark_mode and update visuals
-        {
-            let mut state = self.state.lock().unwrap();
-            state.dark_mode = !state.dark_mode;
-        }
-        self.apply_theme_on_startup(ctx);
-        // Runtime window icon update is not supported in this eframe version
+    #[test]
+    fn error_report_includes_request_details() {
+        let error = PixelDrainError::Api(pixeldrain_api::ApiError {
+            status: reqwest::StatusCode::UNAUTHORIZED,
+            value: "unauthorized".to_string(),
+            message: "API key invalid".to_string(),
+            endpoint: Some("/api/user/files".to_string()),
+            request_id: Some("abc123".to_string()),
+        });
+        let mut state = AppState::default();
+        state.report_error("Failed to load files", &error);
+
+        assert!(state.last_error.as_deref().unwrap().starts_with("Failed to load files: "));
+        let report = state.error_report.unwrap();
+        assert_eq!(report.hint().as_deref(), Some("The API key was rejected. Check it in Settings."));
+        let snippet = report.bug_report();
+        assert!(snippet.starts_with("**Failed to load files**"));
+        assert!(snippet.contains("- Status: 401 Unauthorized"));
+        assert!(snippet.contains("- Endpoint: /api/user/files"));
+        assert!(snippet.contains("- Request ID: abc123"));
+        assert!(snippet.contains("API key invalid"));
     }
 
-}
+    #[test]
+    fn profiles_keep_settings_separate() {
+        let mut state = AppState {
+            api_key: "personal-key".to_string(),
+            download_location: "/home/me/Downloads".to_string(),
+            ..AppState::default()
+        };
+        state.ensure_profile();
+        assert_eq!(state.active_profile, "Default");
 
-fn main() -> Result<(), eframe::Error> {
-    env_logger::init();
+        state.add_profile("Work").unwrap();
+        assert_eq!(state.active_profile, "Work");
+        assert!(state.api_key.is_empty());
+        state.api_key = "work-key".to_string();
+        assert!(state.add_profile("Work").is_err());
 
-    let mut viewport = egui::ViewportBuilder::default()
-        .with_inner_size([600.0, 400.0])
-        .with_min_inner_size([400.0, 300.0]);
-    if let Some(icon) = icon_data_from_png(true) {
-        viewport = viewport.with_icon(icon);
+        assert!(state.switch_profile("Default"));
+        assert_eq!(state.api_key, "personal-key");
+        assert_eq!(state.download_location, "/home/me/Downloads");
+        assert!(state.switch_profile("Work"));
+        assert_eq!(state.api_key, "work-key");
+
+        state.remove_profile("Work");
+        assert_eq!(state.profiles.len(), 2, "the active profile can't be removed");
+        state.remove_profile("Default");
+        assert_eq!(state.profiles.len(), 1);
+        assert!(!state.switch_profile("Default"));
     }
-    let options = NativeOptions {
-        viewport,
-        ..Default::default()
-    };
 
-    eframe::run_native(
-        "PixelDrain",
-        options,
-        Box::new(|_cc| Ok(Box::new(PixelDrainApp::default()))),
-    )
+    #[test]
+    fn clients_use_the_configured_server() {
+        let state = Arc::new(Mutex::new(AppState { base_url: "https://pd.example.org".to_string(), ..AppState::default() }));
+        let seen = Arc::new(Mutex::new(String::new()));
+        let seen_by_factory = seen.clone();
+        let factory = PixelDrainApp::configured_factory(state.clone(), Arc::new(move |config: PixelDrainConfig| {
+            *seen_by_factory.lock().unwrap() = config.api_url();
+            unsupported()
+        }));
+        let _ = factory(PixelDrainConfig::default());
+        assert_eq!(*seen.lock().unwrap(), "https://pd.example.org/api");
+    }
+
+    #[test]
+    fn file_list_refresh_reports_changes() {
+        let mut state = AppState::default();
+        state.replace_file_list(vec![file("a", "a.txt"), file("b", "b.txt"), file("c", "c.txt")]);
+        assert!(state.file_changes.is_empty(), "first load has nothing to compare against");
+
+        let renamed = file("b", "b-renamed.txt");
+        state.replace_file_list(vec![file("a", "a.txt"), renamed, file("d", "d.txt")]);
+        let changes = &state.file_changes;
+        assert_eq!(changes.added, HashSet::from(["d".to_string()]));
+        assert_eq!(changes.changed, HashSet::from(["b".to_string()]));
+        assert_eq!(changes.removed.iter().map(|f| f.id.as_str()).collect::<Vec<_>>(), ["c"]);
+
+        state.replace_file_list(state.file_list.clone());
+        assert!(state.file_changes.is_empty());
+    }
 }
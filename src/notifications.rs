@@ -0,0 +1,259 @@
+// notifications.rs - Notifications for finished transfers and quota warnings.
+// Each kind of notification goes to the sink chosen for it in settings: the
+// desktop, a toast in the window, a webhook, or only the log.
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use notify_rust::Notification;
+use pixeldrain_api::{Direction, Event};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+// Webhooks that don't answer within this long are given up on
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum NoticeKind {
+    UploadDone,
+    UploadFailed,
+    DownloadDone,
+    DownloadFailed,
+    QuotaWarning,
+}
+
+impl NoticeKind {
+    pub const ALL: [NoticeKind; 5] = [
+        NoticeKind::UploadDone,
+        NoticeKind::UploadFailed,
+        NoticeKind::DownloadDone,
+        NoticeKind::DownloadFailed,
+        NoticeKind::QuotaWarning,
+    ];
+
+    fn id(self) -> &'static str {
+        match self {
+            NoticeKind::UploadDone => "upload_done",
+            NoticeKind::UploadFailed => "upload_failed",
+            NoticeKind::DownloadDone => "download_done",
+            NoticeKind::DownloadFailed => "download_failed",
+            NoticeKind::QuotaWarning => "quota_warning",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Notice {
+    pub kind: NoticeKind,
+    pub summary: String,
+    pub body: String,
+}
+
+impl Notice {
+    /// Notice of a finished or failed transfer, None for other events
+    pub fn of(event: &Event) -> Option<Notice> {
+        let (kind, summary, body) = match event {
+            Event::Finished { direction: Direction::Upload, name, link, .. } => (NoticeKind::UploadDone, format!("Uploaded {}", name), link),
+            Event::Failed { direction: Direction::Upload, name, error, .. } => (NoticeKind::UploadFailed, format!("Upload of {} failed", name), error),
+            Event::Finished { direction: Direction::Download, name, link, .. } => (NoticeKind::DownloadDone, format!("Downloaded {}", name), link),
+            Event::Failed { direction: Direction::Download, name, error, .. } => (NoticeKind::DownloadFailed, format!("Download of {} failed", name), error),
+            Event::Started { .. } | Event::Progress { .. } | Event::Retrying { .. } => return None,
+        };
+        Some(Notice { kind, summary, body: body.clone() })
+    }
+}
+
+/// Somewhere notices are shown or sent. Sinks may block briefly, so notices
+/// are delivered from worker threads.
+pub trait NotificationSink: Send + Sync {
+    fn deliver(&self, notice: &Notice);
+}
+
+pub struct DesktopSink;
+
+impl NotificationSink for DesktopSink {
+    fn deliver(&self, notice: &Notice) {
+        if let Err(e) = Notification::new()
+            .appname("PixelDrain")
+            .summary(&notice.summary)
+            .body(&notice.body)
+            .show()
+        {
+            warn!("Failed to show notification: {}", e);
+        }
+    }
+}
+
+/// Toasts waiting to be shown in the window, with when they arrived
+#[derive(Clone, Default)]
+pub struct ToastSink {
+    toasts: Arc<Mutex<Vec<(Notice, Instant)>>>,
+}
+
+impl ToastSink {
+    /// Toasts younger than `shown_for`, the older ones are dropped
+    pub fn current(&self, shown_for: Duration) -> Vec<(Notice, Instant)> {
+        let mut toasts = self.toasts.lock().unwrap();
+        toasts.retain(|(_, arrived)| arrived.elapsed() < shown_for);
+        toasts.clone()
+    }
+
+    pub fn dismiss(&self, index: usize) {
+        let mut toasts = self.toasts.lock().unwrap();
+        if index < toasts.len() {
+            toasts.remove(index);
+        }
+    }
+}
+
+impl NotificationSink for ToastSink {
+    fn deliver(&self, notice: &Notice) {
+        self.toasts.lock().unwrap().push((notice.clone(), Instant::now()));
+    }
+}
+
+/// Posts each notice as JSON, for chat bots and home automation
+pub struct WebhookSink {
+    pub url: String,
+}
+
+impl WebhookSink {
+    fn payload(notice: &Notice) -> serde_json::Value {
+        serde_json::json!({ "event": notice.kind.id(), "summary": notice.summary, "body": notice.body })
+    }
+}
+
+impl NotificationSink for WebhookSink {
+    fn deliver(&self, notice: &Notice) {
+        let result = reqwest::blocking::Client::new()
+            .post(&self.url)
+            .timeout(WEBHOOK_TIMEOUT)
+            .json(&Self::payload(notice))
+            .send()
+            .and_then(|response| response.error_for_status());
+        if let Err(e) = result {
+            warn!("Failed to post notification to the webhook: {}", e);
+        }
+    }
+}
+
+pub struct LogSink;
+
+impl NotificationSink for LogSink {
+    fn deliver(&self, notice: &Notice) {
+        info!("{}: {}", notice.summary, notice.body);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum SinkChoice {
+    Off,
+    Desktop,
+    Toast,
+    Webhook,
+    Log,
+}
+
+impl SinkChoice {
+    pub const ALL: [SinkChoice; 5] = [SinkChoice::Off, SinkChoice::Desktop, SinkChoice::Toast, SinkChoice::Webhook, SinkChoice::Log];
+}
+
+/// Sink chosen for each kind of notice
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationSettings {
+    pub upload_done: SinkChoice,
+    pub upload_failed: SinkChoice,
+    pub download_done: SinkChoice,
+    pub download_failed: SinkChoice,
+    pub quota_warning: SinkChoice,
+    pub webhook_url: String,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            upload_done: SinkChoice::Desktop,
+            upload_failed: SinkChoice::Desktop,
+            download_done: SinkChoice::Desktop,
+            download_failed: SinkChoice::Desktop,
+            quota_warning: SinkChoice::Toast,
+            webhook_url: String::new(),
+        }
+    }
+}
+
+impl NotificationSettings {
+    pub fn sink_mut(&mut self, kind: NoticeKind) -> &mut SinkChoice {
+        match kind {
+            NoticeKind::UploadDone => &mut self.upload_done,
+            NoticeKind::UploadFailed => &mut self.upload_failed,
+            NoticeKind::DownloadDone => &mut self.download_done,
+            NoticeKind::DownloadFailed => &mut self.download_failed,
+            NoticeKind::QuotaWarning => &mut self.quota_warning,
+        }
+    }
+
+    pub fn sink(&self, kind: NoticeKind) -> SinkChoice {
+        match kind {
+            NoticeKind::UploadDone => self.upload_done,
+            NoticeKind::UploadFailed => self.upload_failed,
+            NoticeKind::DownloadDone => self.download_done,
+            NoticeKind::DownloadFailed => self.download_failed,
+            NoticeKind::QuotaWarning => self.quota_warning,
+        }
+    }
+}
+
+/// Hands each notice to the sink chosen for its kind
+#[derive(Clone, Default)]
+pub struct Notifier {
+    pub toasts: ToastSink,
+}
+
+impl Notifier {
+    pub fn notify(&self, settings: &NotificationSettings, notice: &Notice) {
+        let sink: Box<dyn NotificationSink> = match settings.sink(notice.kind) {
+            SinkChoice::Off => return,
+            SinkChoice::Desktop => Box::new(DesktopSink),
+            SinkChoice::Toast => Box::new(self.toasts.clone()),
+            SinkChoice::Webhook if settings.webhook_url.trim().is_empty() => return,
+            SinkChoice::Webhook => Box::new(WebhookSink { url: settings.webhook_url.trim().to_string() }),
+            SinkChoice::Log => Box::new(LogSink),
+        };
+        sink.deliver(notice);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notices_go_to_the_sink_chosen_for_their_kind() {
+        let event = Event::Failed { id: 1, direction: Direction::Download, name: "a.txt".to_string(), error: "gone".to_string() };
+        let notice = Notice::of(&event).unwrap();
+        assert_eq!(notice.kind, NoticeKind::DownloadFailed);
+        assert_eq!(notice.summary, "Download of a.txt failed");
+        assert_eq!(Notice::of(&Event::Progress { id: 1, fraction: 0.5 }), None);
+
+        let notifier = Notifier::default();
+        let mut settings = NotificationSettings { download_failed: SinkChoice::Toast, ..Default::default() };
+        notifier.notify(&settings, &notice);
+        *settings.sink_mut(NoticeKind::DownloadFailed) = SinkChoice::Off;
+        notifier.notify(&settings, &notice);
+        // Without a URL the webhook is skipped
+        settings.download_failed = SinkChoice::Webhook;
+        notifier.notify(&settings, &notice);
+
+        let toasts = notifier.toasts.current(Duration::from_secs(60));
+        assert_eq!(toasts.len(), 1);
+        assert_eq!(toasts[0].0, notice);
+        notifier.toasts.dismiss(0);
+        assert!(notifier.toasts.current(Duration::from_secs(60)).is_empty());
+
+        assert_eq!(
+            WebhookSink::payload(&notice),
+            serde_json::json!({ "event": "download_failed", "summary": "Download of a.txt failed", "body": "gone" })
+        );
+    }
+}
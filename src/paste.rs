@@ -0,0 +1,60 @@
+// paste.rs - Images pasted from the clipboard, saved as PNG so they upload like a file
+use std::fs;
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use arboard::Clipboard;
+use chrono::{DateTime, Local};
+
+/// PNG of an RGBA image
+pub fn encode_png(width: usize, height: usize, rgba: &[u8]) -> Result<Vec<u8>, String> {
+    let image = image::RgbaImage::from_raw(width as u32, height as u32, rgba.to_vec())
+        .ok_or_else(|| "Clipboard image has the wrong size".to_string())?;
+    let mut png = Vec::new();
+    image.write_to(&mut Cursor::new(&mut png), image::ImageOutputFormat::Png).map_err(|e| e.to_string())?;
+    Ok(png)
+}
+
+/// Name a pasted image is uploaded as
+pub fn file_name(time: DateTime<Local>) -> String {
+    format!("pasted-image-{}.png", time.format("%Y%m%d-%H%M%S"))
+}
+
+/// Save the image on the clipboard as a PNG, returns where it was saved and the
+/// name to upload it as. None when there's no image on the clipboard.
+pub fn save_clipboard_image() -> Result<Option<(PathBuf, String)>, String> {
+    let mut clipboard = Clipboard::new().map_err(|e| e.to_string())?;
+    let image = match clipboard.get_image() {
+        Ok(image) => image,
+        Err(arboard::Error::ContentNotAvailable) => return Ok(None),
+        Err(e) => return Err(e.to_string()),
+    };
+    let png = encode_png(image.width, image.height, &image.bytes)?;
+    // Only the last pasted image is kept, earlier ones have been uploaded
+    let dir = std::env::temp_dir().join("pixeldrain-paste");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let name = file_name(Local::now());
+    let path = dir.join(&name);
+    fs::write(&path, png).map_err(|e| format!("Failed to save the pasted image: {}", e))?;
+    Ok(Some((path, name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn pasted_image_is_a_timestamped_png() {
+        let rgba = [255, 0, 0, 255, 0, 0, 255, 128];
+        let png = encode_png(2, 1, &rgba).unwrap();
+        let decoded = image::load_from_memory(&png).unwrap().to_rgba8();
+        assert_eq!(decoded.dimensions(), (2, 1));
+        assert_eq!(decoded.into_raw(), rgba);
+        assert!(encode_png(3, 1, &rgba).is_err());
+
+        let time = Local.with_ymd_and_hms(2024, 3, 9, 14, 5, 7).unwrap();
+        assert_eq!(file_name(time), "pasted-image-20240309-140507.png");
+    }
+}
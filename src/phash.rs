@@ -0,0 +1,82 @@
+// phash.rs - Perceptual hashes of thumbnails to find uploads that look the same
+// even when their bytes differ, like an image saved again at another quality or
+// size. Computed locally, nothing but the thumbnails is downloaded.
+use image::DynamicImage;
+use image::imageops::FilterType;
+
+/// Hashes this many bits apart or less count as the same picture
+pub const MAX_DISTANCE: u32 = 6;
+
+/// Difference hash: each bit tells whether a pixel of the image shrunk to 9x8
+/// grayscale pixels is brighter than its right neighbour
+pub fn difference_hash(image: &DynamicImage) -> u64 {
+    let small = image.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash <<= 1;
+            if small.get_pixel(x, y)[0] > small.get_pixel(x + 1, y)[0] {
+                hash |= 1;
+            }
+        }
+    }
+    hash
+}
+
+pub fn distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// Indices of `hashes` grouped with the first hash of the group when they're
+/// within `max_distance` of it, only groups of two or more, in the order of
+/// their first hash. Hashes aren't chained through each other, so no two of a
+/// group are more than twice `max_distance` apart.
+pub fn group(hashes: &[u64], max_distance: u32) -> Vec<Vec<usize>> {
+    let mut grouped = vec![false; hashes.len()];
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    for start in 0..hashes.len() {
+        if grouped[start] {
+            continue;
+        }
+        let mut members = vec![start];
+        for other in start + 1..hashes.len() {
+            if !grouped[other] && distance(hashes[start], hashes[other]) <= max_distance {
+                grouped[other] = true;
+                members.push(other);
+            }
+        }
+        if members.len() > 1 {
+            groups.push(members);
+        }
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    fn gradient(width: u32, height: u32, flip: bool) -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_fn(width, height, |x, _| {
+            let value = (x * 255 / (width - 1)) as u8;
+            let value = if flip { 255 - value } else { value };
+            Rgb([value, value, value])
+        }))
+    }
+
+    #[test]
+    fn resized_copies_group_together_and_other_pictures_dont() {
+        let original = difference_hash(&gradient(64, 64, false));
+        let resized = difference_hash(&gradient(200, 150, false));
+        let flipped = difference_hash(&gradient(64, 64, true));
+        assert!(distance(original, resized) <= MAX_DISTANCE);
+        assert!(distance(original, flipped) > MAX_DISTANCE);
+
+        assert_eq!(group(&[original, flipped, resized], MAX_DISTANCE), [vec![0, 2]]);
+        assert_eq!(group(&[0, u64::MAX], MAX_DISTANCE), Vec::<Vec<usize>>::new());
+        // Not chained through the middle hash, the last one is too far from the first
+        assert_eq!(group(&[0b0, 0b11_1111, 0b1111_1111_1111], MAX_DISTANCE), [vec![0, 1]]);
+        assert_eq!(group(&[0b0, 0b1111_1111_1111, 0b11_1111, 0b1111_1111_1100], MAX_DISTANCE), [vec![0, 2], vec![1, 3]]);
+    }
+}
@@ -0,0 +1,123 @@
+// power.rs - Keep the computer awake while transfers run, so an overnight upload
+// isn't cut off by suspend, and put it to sleep once they're done if asked to
+// Windows: SetThreadExecutionState / SetSuspendState
+// macOS: a caffeinate process / pmset
+// Linux: a systemd-inhibit lock held by a child process / systemctl suspend
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+use std::process::{Child, Command, Stdio};
+
+pub struct SleepInhibitor {
+    active: bool,
+    // Holds the inhibitor lock until it's killed, and exits with the app otherwise
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    child: Option<Child>,
+}
+
+impl SleepInhibitor {
+    pub fn new() -> Self {
+        Self {
+            active: false,
+            #[cfg(any(target_os = "linux", target_os = "macos"))]
+            child: None,
+        }
+    }
+
+    /// Keep the system from sleeping while `active`. Called every frame, only
+    /// changes reach the OS.
+    pub fn update(&mut self, active: bool) {
+        if active != self.active {
+            self.active = active;
+            self.set(active);
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn set(&mut self, active: bool) {
+        self.release_child();
+        if active {
+            // tail exits with the app, so a crash doesn't leave the lock behind
+            let pid = std::process::id().to_string();
+            self.child = Command::new("systemd-inhibit")
+                .args(["--what=idle:sleep", "--who=PixelDrain", "--why=Transfers in progress", "--mode=block"])
+                .args(["tail", "--pid", &pid, "-f", "/dev/null"])
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .ok();
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    fn set(&mut self, active: bool) {
+        self.release_child();
+        if active {
+            let pid = std::process::id().to_string();
+            self.child = Command::new("caffeinate")
+                .args(["-i", "-w", &pid])
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .ok();
+        }
+    }
+
+    #[cfg(windows)]
+    fn set(&mut self, active: bool) {
+        use windows::Win32::System::Power::{SetThreadExecutionState, ES_CONTINUOUS, ES_SYSTEM_REQUIRED};
+
+        // The state belongs to the calling thread, which is the UI thread for every call
+        let flags = if active { ES_CONTINUOUS | ES_SYSTEM_REQUIRED } else { ES_CONTINUOUS };
+        unsafe {
+            SetThreadExecutionState(flags);
+        }
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+    fn set(&mut self, _active: bool) {}
+
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    fn release_child(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+impl Drop for SleepInhibitor {
+    fn drop(&mut self) {
+        self.update(false);
+    }
+}
+
+/// Put the computer to sleep
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+pub fn sleep_now() -> Result<(), String> {
+    let (program, args): (&str, &[&str]) = if cfg!(target_os = "macos") {
+        ("pmset", &["sleepnow"])
+    } else {
+        ("systemctl", &["suspend"])
+    };
+    let status = Command::new(program).args(args).status().map_err(|e| format!("{}: {}", program, e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("{} failed with {}", program, status))
+    }
+}
+
+#[cfg(windows)]
+pub fn sleep_now() -> Result<(), String> {
+    use windows::Win32::System::Power::SetSuspendState;
+
+    if unsafe { SetSuspendState(false, false, false) } {
+        Ok(())
+    } else {
+        Err(windows::core::Error::from_win32().to_string())
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+pub fn sleep_now() -> Result<(), String> {
+    Err("Not supported on this system".to_string())
+}
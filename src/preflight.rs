@@ -0,0 +1,249 @@
+// preflight.rs - Checks on local files before they are uploaded
+// Empty files, sockets, FIFOs, devices and files that can't be read are found
+// up front, so a batch can leave them out instead of failing halfway. Directory
+// uploads can also leave out whatever matches the user's exclude patterns.
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Problem {
+    Empty,
+    /// Socket, FIFO, device or anything else that isn't a regular file
+    Special,
+    Unreadable(String),
+}
+
+/// File left out of an upload and why
+#[derive(Clone, Debug, PartialEq)]
+pub struct Skipped {
+    pub path: PathBuf,
+    pub problem: Problem,
+}
+
+/// Gitignore-style patterns for directory uploads, one per line. `*.tmp`
+/// matches at any depth, `node_modules/` matches directories only, a pattern
+/// with a slash such as `build/*.o` is relative to the uploaded directory, `**`
+/// matches any number of directories and `!keep.tmp` brings back what an
+/// earlier line left out. Blank lines and lines starting with # are ignored.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Excludes {
+    patterns: Vec<Pattern>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Pattern {
+    glob: Vec<char>,
+    negated: bool,
+    dir_only: bool,
+    // Matched against the whole relative path instead of just the name
+    anchored: bool,
+}
+
+impl Excludes {
+    pub fn parse(text: &str) -> Self {
+        let patterns = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let (negated, line) = match line.strip_prefix('!') {
+                    Some(rest) => (true, rest),
+                    None => (false, line),
+                };
+                let (dir_only, line) = match line.strip_suffix('/') {
+                    Some(rest) => (true, rest),
+                    None => (false, line),
+                };
+                let anchored = line.contains('/');
+                let line = line.strip_prefix('/').unwrap_or(line);
+                (!line.is_empty()).then(|| Pattern { glob: line.chars().collect(), negated, dir_only, anchored })
+            })
+            .collect();
+        Self { patterns }
+    }
+
+    /// Whether `relative`, a path inside the uploaded directory, is left out.
+    /// The last pattern that matches decides.
+    pub fn is_excluded(&self, relative: &Path, is_dir: bool) -> bool {
+        let parts: Vec<String> = relative.components().map(|c| c.as_os_str().to_string_lossy().into_owned()).collect();
+        let path: Vec<char> = parts.join("/").chars().collect();
+        let name: Vec<char> = parts.last().map(|name| name.chars().collect()).unwrap_or_default();
+        let mut excluded = false;
+        for pattern in &self.patterns {
+            if pattern.dir_only && !is_dir {
+                continue;
+            }
+            if glob_match(&pattern.glob, if pattern.anchored { &path } else { &name }) {
+                excluded = !pattern.negated;
+            }
+        }
+        excluded
+    }
+}
+
+/// `*` and `?` stop at slashes, `**` doesn't
+fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        ['*', '*', '/', rest @ ..] => (0..=text.len()).any(|i| (i == 0 || text[i - 1] == '/') && glob_match(rest, &text[i..])),
+        ['*', '*', rest @ ..] => (0..=text.len()).any(|i| glob_match(rest, &text[i..])),
+        ['*', rest @ ..] => (0..=text.len()).take_while(|&i| i == 0 || text[i - 1] != '/').any(|i| glob_match(rest, &text[i..])),
+        ['?', rest @ ..] => matches!(text, [c, ..] if *c != '/') && glob_match(rest, &text[1..]),
+        [c, rest @ ..] => text.first() == Some(c) && glob_match(rest, &text[1..]),
+    }
+}
+
+/// Why `path` can't be uploaded as a file, None when it can
+pub fn check_file(path: &Path) -> Option<Problem> {
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) => return Some(Problem::Unreadable(e.to_string())),
+    };
+    if !metadata.is_file() {
+        return Some(Problem::Special);
+    }
+    if let Err(e) = fs::File::open(path) {
+        return Some(Problem::Unreadable(e.to_string()));
+    }
+    (metadata.len() == 0).then_some(Problem::Empty)
+}
+
+/// Split `paths` into the files that can be uploaded and the ones that are skipped
+pub fn filter_files(paths: Vec<PathBuf>) -> (Vec<PathBuf>, Vec<Skipped>) {
+    let mut files = Vec::new();
+    let mut skipped = Vec::new();
+    for path in paths {
+        match check_file(&path) {
+            None => files.push(path),
+            Some(problem) => skipped.push(Skipped { path, problem }),
+        }
+    }
+    (files, skipped)
+}
+
+/// Everything under `dir` that can be archived and isn't excluded, as paths
+/// relative to the parent of `dir`, and what was skipped. Empty files are
+/// archived, symbolic links are archived as links. Excluded files aren't
+/// reported as skipped.
+pub fn scan_directory(dir: &Path, excludes: &Excludes) -> io::Result<(Vec<PathBuf>, Vec<Skipped>)> {
+    let name = dir
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("{} is not a directory", dir.display())))?;
+    let mut entries = Vec::new();
+    let mut skipped = Vec::new();
+    // Only what's inside may be skipped, not the directory itself
+    fs::read_dir(dir)?;
+    scan(dir, Path::new(name), excludes, &mut entries, &mut skipped);
+    Ok((entries, skipped))
+}
+
+/// Number of files among `entries` from `scan_directory(dir, ..)` and their total size
+pub fn files_and_size(dir: &Path, entries: &[PathBuf]) -> (usize, u64) {
+    let parent = dir.parent().unwrap_or(Path::new(""));
+    entries
+        .iter()
+        .filter_map(|entry| fs::symlink_metadata(parent.join(entry)).ok())
+        .filter(|metadata| metadata.is_file())
+        .fold((0, 0), |(count, size), metadata| (count + 1, size + metadata.len()))
+}
+
+fn scan(dir: &Path, relative: &Path, excludes: &Excludes, entries: &mut Vec<PathBuf>, skipped: &mut Vec<Skipped>) {
+    let children = match fs::read_dir(dir).and_then(|children| children.collect::<io::Result<Vec<_>>>()) {
+        Ok(children) => children,
+        Err(e) => {
+            skipped.push(Skipped { path: dir.to_path_buf(), problem: Problem::Unreadable(e.to_string()) });
+            return;
+        }
+    };
+    let listed = entries.len();
+    for child in children {
+        let path = child.path();
+        let child_relative = relative.join(child.file_name());
+        let kind = child.file_type();
+        // Paths inside the uploaded directory, without its own name
+        let inside: PathBuf = child_relative.components().skip(1).collect();
+        if excludes.is_excluded(&inside, kind.as_ref().is_ok_and(|kind| kind.is_dir())) {
+            continue;
+        }
+        match kind {
+            Ok(kind) if kind.is_dir() => scan(&path, &child_relative, excludes, entries, skipped),
+            Ok(kind) if kind.is_symlink() => entries.push(child_relative),
+            Ok(kind) if kind.is_file() => match fs::File::open(&path) {
+                Ok(_) => entries.push(child_relative),
+                Err(e) => skipped.push(Skipped { path, problem: Problem::Unreadable(e.to_string()) }),
+            },
+            Ok(_) => skipped.push(Skipped { path, problem: Problem::Special }),
+            Err(e) => skipped.push(Skipped { path, problem: Problem::Unreadable(e.to_string()) }),
+        }
+    }
+    // Directories are archived with their contents, so one is only listed itself
+    // when nothing inside it was, to keep it in the archive
+    if entries.len() == listed {
+        entries.push(relative.to_path_buf());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_and_special_files_are_left_out() {
+        let dir = std::env::temp_dir().join(format!("pixeldrain-preflight-{}", uuid::Uuid::new_v4()));
+        let root = dir.join("photos");
+        fs::create_dir_all(root.join("empty_dir")).unwrap();
+        fs::write(root.join("a.txt"), b"hello").unwrap();
+        fs::write(root.join("empty.txt"), b"").unwrap();
+
+        let (files, skipped) = filter_files(vec![root.join("a.txt"), root.join("empty.txt"), root.join("missing.txt"), root.clone()]);
+        assert_eq!(files, [root.join("a.txt")]);
+        let problems: Vec<&Problem> = skipped.iter().map(|s| &s.problem).collect();
+        assert!(matches!(problems[..], [Problem::Empty, Problem::Unreadable(_), Problem::Special]));
+
+        #[cfg(unix)]
+        {
+            let _listener = std::os::unix::net::UnixListener::bind(root.join("socket")).unwrap();
+            let (mut entries, skipped) = scan_directory(&root, &Excludes::default()).unwrap();
+            entries.sort();
+            let expected: Vec<PathBuf> = ["photos/a.txt", "photos/empty.txt", "photos/empty_dir"].iter().map(PathBuf::from).collect();
+            assert_eq!(entries, expected);
+            assert_eq!(skipped, [Skipped { path: root.join("socket"), problem: Problem::Special }]);
+        }
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn exclude_patterns_work_like_gitignore() {
+        let excludes = Excludes::parse("# build output\n*.tmp\nnode_modules/\n/dist\ndocs/**/*.pdf\n!keep.tmp\n\n");
+        let excluded = |path: &str, is_dir| excludes.is_excluded(Path::new(path), is_dir);
+        assert!(excluded("a.tmp", false));
+        assert!(excluded("src/deep/b.tmp", false));
+        assert!(!excluded("keep.tmp", false));
+        assert!(excluded("web/node_modules", true));
+        assert!(!excluded("node_modules", false));
+        assert!(excluded("dist", true));
+        assert!(!excluded("src/dist", true));
+        assert!(excluded("docs/manual.pdf", false));
+        assert!(excluded("docs/a/b/manual.pdf", false));
+        assert!(!excluded("docsmanual.pdf", false));
+        assert!(!excluded("src/main.rs", false));
+
+        let dir = std::env::temp_dir().join(format!("pixeldrain-excludes-{}", uuid::Uuid::new_v4()));
+        let root = dir.join("project");
+        fs::create_dir_all(root.join("node_modules").join("lib")).unwrap();
+        fs::create_dir_all(root.join("cache")).unwrap();
+        fs::write(root.join("node_modules").join("lib").join("index.js"), b"module").unwrap();
+        fs::write(root.join("cache").join("a.tmp"), b"temporary").unwrap();
+        fs::write(root.join("main.rs"), b"fn main() {}").unwrap();
+
+        let (mut entries, skipped) = scan_directory(&root, &excludes).unwrap();
+        entries.sort();
+        // The cache directory is kept, empty
+        let expected: Vec<PathBuf> = ["project/cache", "project/main.rs"].iter().map(PathBuf::from).collect();
+        assert_eq!(entries, expected);
+        assert!(skipped.is_empty());
+        assert_eq!(files_and_size(&root, &entries), (1, 12));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,47 @@
+// qr.rs - QR codes of share links, so a link can be scanned onto a phone
+use eframe::egui::{Color32, ColorImage};
+use qrcode::{Color, QrCode};
+
+// Light border around the code that scanners need, in modules
+const QUIET_ZONE: usize = 4;
+// Pixels per module
+const SCALE: usize = 6;
+
+/// QR code of `text`, black on white
+pub fn image(text: &str) -> Result<ColorImage, String> {
+    let code = QrCode::new(text.as_bytes()).map_err(|e| e.to_string())?;
+    let modules = code.width();
+    let colors = code.to_colors();
+    let size = (modules + 2 * QUIET_ZONE) * SCALE;
+    let mut pixels = vec![Color32::WHITE; size * size];
+    for (index, color) in colors.iter().enumerate() {
+        if *color != Color::Dark {
+            continue;
+        }
+        let (x, y) = ((index % modules + QUIET_ZONE) * SCALE, (index / modules + QUIET_ZONE) * SCALE);
+        for row in y..y + SCALE {
+            pixels[row * size + x..row * size + x + SCALE].fill(Color32::BLACK);
+        }
+    }
+    Ok(ColorImage::new([size, size], pixels))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn code_has_a_white_border_and_dark_finder_patterns() {
+        let image = image("https://pixeldrain.com/u/abc123").unwrap();
+        let [width, height] = image.size;
+        assert_eq!(width, height);
+        assert_eq!(width % SCALE, 0);
+        let pixel = |x: usize, y: usize| image.pixels[y * width + x];
+        let border = QUIET_ZONE * SCALE;
+        assert_eq!(pixel(0, 0), Color32::WHITE);
+        assert_eq!(pixel(border - 1, border - 1), Color32::WHITE);
+        // Top left corner of the top left finder pattern
+        assert_eq!(pixel(border, border), Color32::BLACK);
+        assert_eq!(pixel(width - border - 1, border), Color32::BLACK);
+    }
+}
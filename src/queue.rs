@@ -0,0 +1,129 @@
+// queue.rs - The running transfers as one queue: when it should be done at the
+// average throughput of the last half minute, which is steadier than the speed
+// of the current file, and how much it moved once it's empty.
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use pixeldrain_api::Progress;
+
+// Throughput is averaged over this much of the run, from samples taken at most this often
+const AVERAGE_WINDOW: Duration = Duration::from_secs(30);
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// A queue run that ended, from the first transfer until the last one finished
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueueRun {
+    pub started: DateTime<Utc>,
+    /// Bytes moved by each source, in the order they were passed to `update`
+    pub bytes: Vec<u64>,
+    pub elapsed: Duration,
+}
+
+impl QueueRun {
+    pub fn total_bytes(&self) -> u64 {
+        self.bytes.iter().sum()
+    }
+}
+
+#[derive(Default)]
+pub struct QueueMeter {
+    started: Option<(Instant, DateTime<Utc>)>,
+    // Bytes each source reported last, a drop means it moved on to its next transfer
+    last_bytes: Vec<u64>,
+    // Bytes of each source's transfers that moved on or ended
+    done_bytes: Vec<u64>,
+    samples: VecDeque<(Instant, u64)>,
+    remaining: Option<u64>,
+}
+
+impl QueueMeter {
+    /// Take the progress of every transfer source, None for the idle ones.
+    /// Called every frame, returns the run when the queue just emptied.
+    pub fn update(&mut self, now: Instant, sources: &[Option<Progress>]) -> Option<QueueRun> {
+        if sources.iter().all(Option::is_none) {
+            let (start, started) = self.started.take()?;
+            let bytes = self.done_bytes.iter().zip(&self.last_bytes).map(|(done, last)| done + last).collect();
+            *self = Self::default();
+            return Some(QueueRun { started, bytes, elapsed: now.duration_since(start) });
+        }
+        if self.started.is_none() {
+            self.started = Some((now, Utc::now()));
+        }
+        self.last_bytes.resize(sources.len(), 0);
+        self.done_bytes.resize(sources.len(), 0);
+        for ((last, done), source) in self.last_bytes.iter_mut().zip(&mut self.done_bytes).zip(sources) {
+            let bytes = source.map_or(0, |progress| progress.bytes);
+            if bytes < *last {
+                *done += *last;
+            }
+            *last = bytes;
+        }
+        // Only known when every running transfer knows its size
+        self.remaining = sources.iter().flatten().map(|progress| progress.total.map(|total| total.saturating_sub(progress.bytes))).sum();
+
+        let moved = self.done_bytes.iter().chain(&self.last_bytes).sum::<u64>();
+        if self.samples.back().is_none_or(|(time, _)| now.duration_since(*time) >= SAMPLE_INTERVAL) {
+            self.samples.push_back((now, moved));
+        }
+        while self.samples.len() > 2 && self.samples.front().is_some_and(|(time, _)| now.duration_since(*time) > AVERAGE_WINDOW) {
+            self.samples.pop_front();
+        }
+        None
+    }
+
+    /// Bytes per second over the last half minute of the run
+    pub fn throughput(&self) -> f64 {
+        match (self.samples.front(), self.samples.back()) {
+            (Some(&(start, start_bytes)), Some(&(end, end_bytes))) if end > start => {
+                end_bytes.saturating_sub(start_bytes) as f64 / end.duration_since(start).as_secs_f64()
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// Time until the queue is empty, None while it's idle or that can't be told yet
+    pub fn eta(&self) -> Option<Duration> {
+        let remaining = self.remaining?;
+        let throughput = self.throughput();
+        (self.started.is_some() && throughput > 0.0).then(|| Duration::from_secs_f64(remaining as f64 / throughput))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(bytes: u64, total: u64) -> Option<Progress> {
+        Some(Progress { bytes, total: Some(total), ..Default::default() })
+    }
+
+    #[test]
+    fn the_queue_eta_uses_the_average_throughput_and_the_run_is_summed_up() {
+        let mut meter = QueueMeter::default();
+        let start = Instant::now();
+        let at = |seconds: u64| start + Duration::from_secs(seconds);
+        assert_eq!(meter.update(start, &[None, None]), None);
+        assert_eq!(meter.eta(), None);
+
+        // An upload at 100 B/s next to a download of unknown size
+        assert_eq!(meter.update(at(0), &[progress(0, 1_000), None]), None);
+        meter.update(at(2), &[progress(200, 1_000), None]);
+        assert_eq!(meter.throughput(), 100.0);
+        assert_eq!(meter.eta(), Some(Duration::from_secs(8)));
+        meter.update(at(3), &[progress(300, 1_000), Some(Progress::default())]);
+        assert_eq!(meter.eta(), None);
+
+        // The upload moves on to a second file, its bytes so far still count
+        meter.update(at(4), &[progress(100, 500), None]);
+        assert_eq!(meter.throughput(), 100.0);
+        assert_eq!(meter.eta(), Some(Duration::from_secs(4)));
+        meter.update(at(8), &[progress(500, 500), None]);
+
+        let run = meter.update(at(9), &[None, None]).unwrap();
+        assert_eq!(run.bytes, [800, 0]);
+        assert_eq!(run.total_bytes(), 800);
+        assert_eq!(run.elapsed, Duration::from_secs(9));
+        assert_eq!(meter.update(at(10), &[None, None]), None);
+    }
+}
@@ -0,0 +1,257 @@
+// resume.rs - Transfers that were queued or running when the app closed, kept
+// in a journal in the app data folder so the next start can offer to pick them
+// up again. Every upload and download is written down when it starts and taken
+// out when it ends, whatever the outcome. What's left at the next start was
+// interrupted: downloads continue where their partial file stopped, uploads
+// are queued again from the start.
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum Transfer {
+    Upload { path: PathBuf },
+    Download {
+        url: String,
+        /// File being written, None until it's known or when it can't be
+        /// continued (lists, decrypted files)
+        #[serde(default)]
+        path: Option<PathBuf>,
+        /// Size of the partial file when the journal was last written
+        #[serde(default)]
+        bytes_done: u64,
+    },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Entry {
+    id: u64,
+    transfer: Transfer,
+}
+
+#[derive(Default)]
+struct JournalInner {
+    /// None keeps the journal in memory only, as in tests
+    path: Option<PathBuf>,
+    next_id: u64,
+    entries: Vec<Entry>,
+    /// IDs of the entries left over from the last run
+    interrupted: Vec<u64>,
+}
+
+impl JournalInner {
+    fn remove(&mut self, ids: &[u64]) {
+        self.entries.retain(|entry| !ids.contains(&entry.id));
+        self.interrupted.retain(|id| !ids.contains(id));
+        self.save();
+    }
+
+    /// Write the journal, with the bytes each download has on disk by now
+    fn save(&mut self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        for entry in &mut self.entries {
+            if let Transfer::Download { path: Some(file), bytes_done, .. } = &mut entry.transfer {
+                *bytes_done = fs::metadata(file).map(|metadata| metadata.len()).unwrap_or(0);
+            }
+        }
+        if let Err(e) = write(path, &self.entries) {
+            warn!("Failed to save the transfer journal: {}", e);
+        }
+    }
+}
+
+fn write(path: &Path, entries: &[Entry]) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, serde_json::to_vec_pretty(entries)?)
+}
+
+pub fn journal_path() -> PathBuf {
+    directories::ProjectDirs::from("com", "pixeldrain", "client")
+        .map(|proj_dirs| proj_dirs.data_dir().join("transfers.json"))
+        .unwrap_or_else(|| PathBuf::from("transfers.json"))
+}
+
+/// Shared by the app and its workers, the default one is kept in memory only
+#[derive(Clone, Default)]
+pub struct Journal {
+    inner: Arc<Mutex<JournalInner>>,
+}
+
+impl Journal {
+    /// The journal at `path`, whatever it has from the last run counts as interrupted
+    pub fn open(path: PathBuf) -> Self {
+        let entries: Vec<Entry> = match fs::read(&path) {
+            Ok(data) => serde_json::from_slice(&data).unwrap_or_else(|e| {
+                warn!("Ignoring the unreadable transfer journal {}: {}", path.display(), e);
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+        let inner = JournalInner {
+            next_id: entries.iter().map(|entry| entry.id + 1).max().unwrap_or(0),
+            interrupted: entries.iter().map(|entry| entry.id).collect(),
+            entries,
+            path: Some(path),
+        };
+        Self { inner: Arc::new(Mutex::new(inner)) }
+    }
+
+    /// Write down transfers that are starting, they're taken out again when
+    /// the returned handle is dropped
+    pub fn record(&self, transfers: Vec<Transfer>) -> Recorded {
+        let mut inner = self.inner.lock().unwrap();
+        let mut ids = Vec::with_capacity(transfers.len());
+        for transfer in transfers {
+            let id = inner.next_id;
+            inner.next_id += 1;
+            inner.entries.push(Entry { id, transfer });
+            ids.push(Some(id));
+        }
+        inner.save();
+        Recorded { journal: self.clone(), ids }
+    }
+
+    /// Transfers the last run didn't get to finish
+    pub fn interrupted(&self) -> Vec<Transfer> {
+        let inner = self.inner.lock().unwrap();
+        inner.entries.iter().filter(|entry| inner.interrupted.contains(&entry.id)).map(|entry| entry.transfer.clone()).collect()
+    }
+
+    /// Take the transfers of the last run out of the journal, to start them
+    /// again or to forget them
+    pub fn take_interrupted(&self) -> Vec<Transfer> {
+        let transfers = self.interrupted();
+        let mut inner = self.inner.lock().unwrap();
+        let ids = std::mem::take(&mut inner.interrupted);
+        inner.remove(&ids);
+        transfers
+    }
+
+    /// Write the journal as it is now, for when the app closes
+    pub fn save(&self) {
+        self.inner.lock().unwrap().save();
+    }
+
+    /// Bytes the journal takes on disk. The partial files of its downloads are
+    /// the user's, in the download folder, and aren't counted.
+    pub fn disk_usage(&self) -> u64 {
+        let inner = self.inner.lock().unwrap();
+        inner.path.as_ref().and_then(|path| fs::metadata(path).ok()).map_or(0, |metadata| metadata.len())
+    }
+}
+
+/// Transfers of one worker in the journal, by the order they were recorded in
+pub struct Recorded {
+    journal: Journal,
+    ids: Vec<Option<u64>>,
+}
+
+impl Recorded {
+    /// File download `index` is written to, once it's known
+    pub fn set_path(&self, index: usize, file: PathBuf) {
+        let Some(Some(id)) = self.ids.get(index) else {
+            return;
+        };
+        let mut inner = self.journal.inner.lock().unwrap();
+        if let Some(Transfer::Download { path, .. }) = inner.entries.iter_mut().find(|entry| entry.id == *id).map(|entry| &mut entry.transfer) {
+            *path = Some(file);
+            inner.save();
+        }
+    }
+
+    /// Transfer `index` ended, it's taken out of the journal
+    pub fn done(&mut self, index: usize) {
+        if let Some(id) = self.ids.get_mut(index).and_then(Option::take) {
+            self.journal.inner.lock().unwrap().remove(&[id]);
+        }
+    }
+
+    /// Leave the transfers not done yet in the journal, for when the app closes before them
+    pub fn keep(mut self) {
+        self.ids.clear();
+    }
+}
+
+impl Drop for Recorded {
+    fn drop(&mut self) {
+        let ids: Vec<u64> = self.ids.iter().flatten().copied().collect();
+        if !ids.is_empty() {
+            self.journal.inner.lock().unwrap().remove(&ids);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache;
+
+    #[test]
+    fn unfinished_transfers_are_offered_after_a_restart() {
+        let dir = std::env::temp_dir().join(format!("pixeldrain-resume-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("transfers.json");
+        let partial = dir.join("movie.mkv");
+
+        let journal = Journal::open(path.clone());
+        assert!(journal.interrupted().is_empty());
+        let download = journal.record(vec![Transfer::Download { url: "https://pixeldrain.com/u/abc".to_string(), path: None, bytes_done: 0 }]);
+        let mut uploads = journal.record(vec![
+            Transfer::Upload { path: dir.join("a.txt") },
+            Transfer::Upload { path: dir.join("b.txt") },
+            Transfer::Upload { path: dir.join("c.txt") },
+        ]);
+        drop(journal.record(vec![Transfer::Upload { path: dir.join("finished.txt") }]));
+        uploads.done(0);
+        fs::write(&partial, b"12345").unwrap();
+        download.set_path(0, partial.clone());
+        // The app closed while these were running
+        uploads.keep();
+        std::mem::forget(download);
+        journal.save();
+        assert_eq!(journal.disk_usage(), fs::metadata(&path).unwrap().len());
+
+        let journal = Journal::open(path.clone());
+        assert_eq!(journal.interrupted(), [
+            Transfer::Download { url: "https://pixeldrain.com/u/abc".to_string(), path: Some(partial), bytes_done: 5 },
+            Transfer::Upload { path: dir.join("b.txt") },
+            Transfer::Upload { path: dir.join("c.txt") },
+        ]);
+        // New transfers aren't part of the last run
+        let running = journal.record(vec![Transfer::Upload { path: dir.join("d.txt") }]);
+        assert_eq!(journal.take_interrupted().len(), 3);
+        assert!(journal.interrupted().is_empty());
+        running.keep();
+
+        let journal = Journal::open(path);
+        assert_eq!(journal.interrupted(), [Transfer::Upload { path: dir.join("d.txt") }]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn big_partial_downloads_leave_the_thumbnail_cache_alone() {
+        let dir = std::env::temp_dir().join(format!("pixeldrain-resume-{}", uuid::Uuid::new_v4()));
+        let cache_dir = dir.join("cache");
+        let partial = dir.join("movie.mkv");
+        let journal = Journal::open(dir.join("transfers.json"));
+        let download = journal.record(vec![Transfer::Download { url: "https://pixeldrain.com/u/abc".to_string(), path: None, bytes_done: 0 }]);
+        // Sparse, it takes no room in the temp folder
+        fs::File::create(&partial).unwrap().set_len(2 * cache::DEFAULT_LIMIT).unwrap();
+        download.set_path(0, partial);
+        cache::store_thumbnail(&cache_dir, "abc", &[0u8; 100]).unwrap();
+
+        assert!(journal.disk_usage() < 1_000);
+        assert_eq!(cache::enforce_limit(&cache_dir, cache::DEFAULT_LIMIT, journal.disk_usage()).unwrap(), 0);
+        assert!(cache::read_thumbnail(&cache_dir, "abc").is_some());
+        drop(download);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,115 @@
+// secrets.rs - API keys in the OS credential store
+// (Keychain on macOS, Credential Manager on Windows, Secret Service on Linux)
+// One entry per profile, so settings.json doesn't have to contain the key.
+use keyring::Entry;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tracing::warn;
+
+const SERVICE: &str = "com.pixeldrain.client";
+
+// The passphrase of encrypted uploads has an entry of its own, under a service
+// name no profile can clash with. It's only stored when the user asks for it.
+const PASSPHRASE_USER: &str = "passphrase";
+
+fn passphrase_service() -> String {
+    format!("{}.encryption", SERVICE)
+}
+
+/// Where secrets are kept: the OS credential store, or memory only as in tests
+#[derive(Clone, Default)]
+pub enum SecretStore {
+    #[default]
+    Keyring,
+    /// By (service, user)
+    #[cfg_attr(not(test), allow(dead_code))] // Only tests keep secrets in memory
+    Memory(Arc<Mutex<HashMap<(String, String), String>>>),
+}
+
+impl SecretStore {
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn memory() -> Self {
+        Self::Memory(Arc::default())
+    }
+
+    fn get(&self, service: &str, user: &str) -> Result<Option<String>, keyring::Error> {
+        match self {
+            Self::Keyring => match Entry::new(service, user)?.get_password() {
+                Ok(secret) => Ok(Some(secret)),
+                Err(keyring::Error::NoEntry) => Ok(None),
+                Err(e) => Err(e),
+            },
+            Self::Memory(secrets) => Ok(secrets.lock().unwrap().get(&(service.to_string(), user.to_string())).cloned()),
+        }
+    }
+
+    fn set(&self, service: &str, user: &str, secret: &str) -> Result<(), keyring::Error> {
+        match self {
+            Self::Keyring => Entry::new(service, user)?.set_password(secret),
+            Self::Memory(secrets) => {
+                secrets.lock().unwrap().insert((service.to_string(), user.to_string()), secret.to_string());
+                Ok(())
+            }
+        }
+    }
+
+    fn delete(&self, service: &str, user: &str) -> Result<(), keyring::Error> {
+        match self {
+            Self::Keyring => match Entry::new(service, user)?.delete_credential() {
+                Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+                Err(e) => Err(e),
+            },
+            Self::Memory(secrets) => {
+                secrets.lock().unwrap().remove(&(service.to_string(), user.to_string()));
+                Ok(())
+            }
+        }
+    }
+
+    /// API key stored for `profile`, None when there is none or no keyring is available
+    pub fn load_api_key(&self, profile: &str) -> Option<String> {
+        self.get(SERVICE, profile).unwrap_or_else(|e| {
+            warn!("Failed to read API key for profile {} from the keyring: {}", profile, e);
+            None
+        })
+    }
+
+    /// Bring the store in line with `keys` (profile name -> API key). `stored` is
+    /// what is already in the store, so unchanged keys aren't written again.
+    pub fn sync_api_keys(&self, keys: &HashMap<String, String>, stored: &mut HashMap<String, String>) -> Result<(), keyring::Error> {
+        for (profile, key) in keys {
+            if stored.get(profile) == Some(key) {
+                continue;
+            }
+            if key.is_empty() {
+                // Only touch the store when a key was stored for this profile before
+                if stored.remove(profile).is_some() {
+                    self.delete(SERVICE, profile)?;
+                }
+                continue;
+            }
+            self.set(SERVICE, profile, key)?;
+            stored.insert(profile.clone(), key.clone());
+        }
+
+        // Profiles that were removed
+        let removed: Vec<String> = stored.keys().filter(|profile| !keys.contains_key(*profile)).cloned().collect();
+        for profile in removed {
+            self.delete(SERVICE, &profile)?;
+            stored.remove(&profile);
+        }
+        Ok(())
+    }
+
+    pub fn load_passphrase(&self) -> Option<String> {
+        self.get(&passphrase_service(), PASSPHRASE_USER).ok().flatten()
+    }
+
+    /// Store `passphrase`, or forget the stored one when None
+    pub fn store_passphrase(&self, passphrase: Option<&str>) -> Result<(), keyring::Error> {
+        match passphrase {
+            Some(passphrase) => self.set(&passphrase_service(), PASSPHRASE_USER, passphrase),
+            None => self.delete(&passphrase_service(), PASSPHRASE_USER),
+        }
+    }
+}
@@ -0,0 +1,143 @@
+// settings_export.rs - Settings export files for moving a setup to another machine
+// Exports without API keys are plain JSON with a SHA-256 checksum. Exports with
+// API keys are encrypted with ChaCha20-Poly1305 under an Argon2id key derived
+// from a passphrase, which also protects them against tampering.
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, rand_core::RngCore};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+const FORMAT: &str = "pixeldrain-settings";
+const VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct ExportFile {
+    format: String,
+    version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    checksum: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    settings: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    encrypted: Option<EncryptedSettings>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EncryptedSettings {
+    kdf: String,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Serialize `settings` for export, encrypted when a passphrase is given
+pub fn export(settings: &serde_json::Value, passphrase: Option<&str>) -> Result<String, String> {
+    let plaintext = serde_json::to_vec(settings).map_err(|e| e.to_string())?;
+    let file = match passphrase {
+        Some(passphrase) => {
+            let mut salt = [0u8; 16];
+            OsRng.fill_bytes(&mut salt);
+            let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &salt)?);
+            let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+            let ciphertext = cipher.encrypt(&nonce, plaintext.as_slice()).map_err(|e| e.to_string())?;
+            let b64 = base64::engine::general_purpose::STANDARD;
+            ExportFile {
+                format: FORMAT.to_string(),
+                version: VERSION,
+                checksum: None,
+                settings: None,
+                encrypted: Some(EncryptedSettings {
+                    kdf: "argon2id".to_string(),
+                    salt: b64.encode(salt),
+                    nonce: b64.encode(nonce),
+                    ciphertext: b64.encode(ciphertext),
+                }),
+            }
+        }
+        None => ExportFile {
+            format: FORMAT.to_string(),
+            version: VERSION,
+            checksum: Some(checksum(&plaintext)),
+            settings: Some(settings.clone()),
+            encrypted: None,
+        },
+    };
+    serde_json::to_string_pretty(&file).map_err(|e| e.to_string())
+}
+
+/// Read the settings back from an export file, checking its checksum or decrypting it
+pub fn import(data: &str, passphrase: Option<&str>) -> Result<serde_json::Value, String> {
+    let file: ExportFile = serde_json::from_str(data).map_err(|e| format!("Not a settings export: {}", e))?;
+    if file.format != FORMAT {
+        return Err("Not a PixelDrain settings export".to_string());
+    }
+    if file.version > VERSION {
+        return Err(format!("Settings export version {} is newer than this app supports", file.version));
+    }
+
+    if let Some(encrypted) = file.encrypted {
+        let passphrase = passphrase.filter(|p| !p.is_empty()).ok_or("This export is encrypted, enter its passphrase")?;
+        let b64 = base64::engine::general_purpose::STANDARD;
+        let decode = |value: &str| b64.decode(value).map_err(|e| format!("Corrupted export: {}", e));
+        let salt = decode(&encrypted.salt)?;
+        let nonce = decode(&encrypted.nonce)?;
+        if nonce.len() != 12 {
+            return Err("Corrupted export: invalid nonce".to_string());
+        }
+        let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &salt)?);
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(&nonce), decode(&encrypted.ciphertext)?.as_slice())
+            .map_err(|_| "Wrong passphrase or the export was modified".to_string())?;
+        return serde_json::from_slice(&plaintext).map_err(|e| format!("Corrupted export: {}", e));
+    }
+
+    let settings = file.settings.ok_or("Export contains no settings")?;
+    let expected = file.checksum.ok_or("Export has no checksum")?;
+    let plaintext = serde_json::to_vec(&settings).map_err(|e| e.to_string())?;
+    if checksum(&plaintext) != expected {
+        return Err("Checksum mismatch, the export was modified or damaged".to_string());
+    }
+    Ok(settings)
+}
+
+pub fn derive_key(passphrase: &str, salt: &[u8]) -> Result<Key, String> {
+    let mut key = Key::default();
+    argon2::Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| e.to_string())?;
+    Ok(key)
+}
+
+fn checksum(data: &[u8]) -> String {
+    Sha256::digest(data).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> serde_json::Value {
+        serde_json::json!({ "api_key": "secret", "dark_mode": true, "profiles": [{ "name": "Work", "api_key": "work" }] })
+    }
+
+    #[test]
+    fn plain_export_round_trips_and_detects_edits() {
+        let data = export(&sample(), None).unwrap();
+        assert!(data.contains("\"checksum\""));
+        assert_eq!(import(&data, None).unwrap(), sample());
+
+        let tampered = data.replace("\"dark_mode\": true", "\"dark_mode\": false");
+        assert!(import(&tampered, None).unwrap_err().contains("Checksum mismatch"));
+    }
+
+    #[test]
+    fn encrypted_export_needs_the_passphrase() {
+        let data = export(&sample(), Some("correct horse")).unwrap();
+        assert!(data.contains("\"encrypted\""));
+        assert!(!data.contains("secret"));
+        assert_eq!(import(&data, Some("correct horse")).unwrap(), sample());
+        assert!(import(&data, None).is_err());
+        assert_eq!(import(&data, Some("wrong")).unwrap_err(), "Wrong passphrase or the export was modified");
+    }
+}
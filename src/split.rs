@@ -0,0 +1,74 @@
+// split.rs - Files bigger than the account may upload, sent in parts named like
+// name.001, name.002 and kept together in a list, and joined again when such a
+// list is downloaded.
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Number of parts a file of `size` bytes is split into so each fits in `limit`
+pub fn part_count(size: u64, limit: u64) -> u64 {
+    size.div_ceil(limit.max(1)).max(1)
+}
+
+/// Size of each part but the last, the parts are about equally big
+pub fn part_size(size: u64, limit: u64) -> u64 {
+    size.div_ceil(part_count(size, limit)).max(1)
+}
+
+/// Name of part `index` (from 0) of `name`
+pub fn part_name(name: &str, index: u64) -> String {
+    format!("{}.{:03}", name, index + 1)
+}
+
+/// Part `index` of `path` split into parts of `part_size`, and its length
+pub fn open_part(path: &Path, index: u64, part_size: u64) -> io::Result<(io::Take<File>, u64)> {
+    let mut file = File::open(path)?;
+    let size = file.metadata()?.len();
+    let start = index * part_size;
+    file.seek(SeekFrom::Start(start))?;
+    let len = part_size.min(size.saturating_sub(start));
+    Ok((file.take(len), len))
+}
+
+/// Name of the split file when `names` are all of its parts in order
+pub fn joined_name(names: &[&str]) -> Option<String> {
+    let (name, _) = names.first()?.rsplit_once('.')?;
+    let complete = names.len() > 1 && names.iter().enumerate().all(|(index, part)| *part == part_name(name, index as u64));
+    complete.then(|| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn files_are_split_into_even_parts_and_recognized_when_joined() {
+        assert_eq!(part_count(10, 4), 3);
+        assert_eq!(part_size(10, 4), 4);
+        assert_eq!(part_count(12, 4), 3);
+        assert_eq!(part_count(0, 4), 1);
+        // Balanced instead of a tiny last part
+        assert_eq!(part_size(101, 100), 51);
+
+        let dir = std::env::temp_dir().join(format!("pixeldrain-split-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("video.mkv");
+        std::fs::write(&path, b"0123456789").unwrap();
+        let mut parts = Vec::new();
+        for index in 0..part_count(10, 4) {
+            let (mut part, len) = open_part(&path, index, part_size(10, 4)).unwrap();
+            let mut content = String::new();
+            part.read_to_string(&mut content).unwrap();
+            assert_eq!(content.len() as u64, len);
+            parts.push(content);
+        }
+        assert_eq!(parts, ["0123", "4567", "89"]);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(joined_name(&["video.mkv.001", "video.mkv.002"]), Some("video.mkv".to_string()));
+        assert_eq!(joined_name(&["video.mkv.001", "video.mkv.003"]), None);
+        assert_eq!(joined_name(&["video.mkv.002", "video.mkv.001"]), None);
+        assert_eq!(joined_name(&["video.mkv.001"]), None);
+        assert_eq!(joined_name(&["a.txt", "b.txt"]), None);
+    }
+}
@@ -0,0 +1,78 @@
+// stats.rs - Usage statistics kept only on this computer and never sent
+// anywhere: how much was transferred, how fast, and at which hours. Counted
+// each time the transfer queue empties.
+use std::time::Duration;
+
+use chrono::{DateTime, Local, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UsageStats {
+    /// When counting started, None until the first transfer
+    pub since: Option<DateTime<Utc>>,
+    pub uploads: u64,
+    pub upload_bytes: u64,
+    pub downloads: u64,
+    pub download_bytes: u64,
+    // Time transfers were running, the average speed is the bytes over this
+    pub busy_seconds: f64,
+    /// Files transferred in each hour of the day, local time
+    pub hours: [u64; 24],
+}
+
+impl UsageStats {
+    /// Count a queue run that moved the given bytes in `elapsed`, with when each
+    /// uploaded and downloaded file finished
+    pub fn record(&mut self, upload_bytes: u64, download_bytes: u64, elapsed: Duration, uploads: &[DateTime<Utc>], downloads: &[DateTime<Utc>]) {
+        let first = uploads.iter().chain(downloads).min().copied().unwrap_or_else(Utc::now);
+        self.since = Some(self.since.map_or(first, |since| since.min(first)));
+        self.uploads += uploads.len() as u64;
+        self.upload_bytes += upload_bytes;
+        self.downloads += downloads.len() as u64;
+        self.download_bytes += download_bytes;
+        self.busy_seconds += elapsed.as_secs_f64();
+        for time in uploads.iter().chain(downloads) {
+            self.hours[time.with_timezone(&Local).hour() as usize] += 1;
+        }
+    }
+
+    /// Bytes per second while transfers were running
+    pub fn average_speed(&self) -> f64 {
+        if self.busy_seconds > 0.0 {
+            (self.upload_bytes + self.download_bytes) as f64 / self.busy_seconds
+        } else {
+            0.0
+        }
+    }
+
+    /// Hours of the day with the most files, busiest first, at most `count`
+    pub fn most_active_hours(&self, count: usize) -> Vec<u32> {
+        let mut hours: Vec<u32> = (0..24).filter(|hour| self.hours[*hour as usize] > 0).collect();
+        hours.sort_by_key(|hour| std::cmp::Reverse(self.hours[*hour as usize]));
+        hours.truncate(count);
+        hours
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn runs_add_up_and_the_busiest_hours_come_first() {
+        let mut stats = UsageStats::default();
+        assert_eq!(stats.average_speed(), 0.0);
+        assert!(stats.most_active_hours(3).is_empty());
+
+        let at = |hour: u32| Local.with_ymd_and_hms(2024, 5, 1, hour, 30, 0).unwrap().with_timezone(&Utc);
+        stats.record(3_000, 1_000, Duration::from_secs(2), &[at(21), at(21)], &[at(9)]);
+        stats.record(2_000, 0, Duration::from_secs(3), &[at(21), at(8)], &[]);
+        assert_eq!((stats.uploads, stats.upload_bytes, stats.downloads, stats.download_bytes), (4, 5_000, 1, 1_000));
+        assert_eq!(stats.average_speed(), 1_200.0);
+        assert_eq!(stats.most_active_hours(2)[0], 21);
+        assert_eq!(stats.most_active_hours(5).len(), 3);
+        assert_eq!(stats.since, Some(at(8)));
+    }
+}
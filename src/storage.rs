@@ -0,0 +1,558 @@
+// storage.rs - Where history and cached data are kept
+// Two backends behind one trait: SQLite (the default), which filters and
+// searches in SQL and stays fast with tens of thousands of history entries,
+// and a plain JSON file that is easy to read and edit by hand.
+use crate::history::{self, DownloadHistoryEntry, HistoryEntry, HistoryViewOptions, UploadHistoryEntry};
+use rusqlite::{Connection, OptionalExtension, Row, params};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug)]
+pub enum StorageError {
+    Sqlite(rusqlite::Error),
+    Io(io::Error),
+    Json(serde_json::Error),
+}
+
+impl std::fmt::Display for StorageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StorageError::Sqlite(e) => write!(f, "Database error: {}", e),
+            StorageError::Io(e) => write!(f, "IO error: {}", e),
+            StorageError::Json(e) => write!(f, "JSON error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+impl From<rusqlite::Error> for StorageError {
+    fn from(e: rusqlite::Error) -> Self {
+        StorageError::Sqlite(e)
+    }
+}
+
+impl From<io::Error> for StorageError {
+    fn from(e: io::Error) -> Self {
+        StorageError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for StorageError {
+    fn from(e: serde_json::Error) -> Self {
+        StorageError::Json(e)
+    }
+}
+
+/// Persistence of the upload and download history and of cached data
+pub trait Storage: Send {
+    fn add_upload(&mut self, entry: &UploadHistoryEntry) -> Result<(), StorageError>;
+    fn add_download(&mut self, entry: &DownloadHistoryEntry) -> Result<(), StorageError>;
+    /// Add the entries that aren't stored yet, oldest first, and return how many were added.
+    /// Used to move history out of settings.json and between backends, so it can run again.
+    fn import(&mut self, uploads: &[UploadHistoryEntry], downloads: &[DownloadHistoryEntry]) -> Result<usize, StorageError>;
+    fn has_uploads(&self) -> Result<bool, StorageError>;
+    fn has_downloads(&self) -> Result<bool, StorageError>;
+    /// Upload history matching the view options, newest first and at most `limit`
+    /// entries. Each entry comes with the number of entries it stands for (more
+    /// than one when duplicates are collapsed).
+    fn uploads(&self, options: &HistoryViewOptions, limit: usize) -> Result<Vec<(UploadHistoryEntry, usize)>, StorageError>;
+    /// Download history matching the view options, like `uploads`
+    fn downloads(&self, options: &HistoryViewOptions, limit: usize) -> Result<Vec<(DownloadHistoryEntry, usize)>, StorageError>;
+    fn cache_get(&self, key: &str) -> Result<Option<String>, StorageError>;
+    fn cache_put(&mut self, key: &str, value: &str) -> Result<(), StorageError>;
+    /// Every cached key and value, to back them up
+    fn cache_entries(&self) -> Result<Vec<(String, String)>, StorageError>;
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    #[default]
+    Sqlite,
+    Json,
+}
+
+impl StorageBackend {
+    pub const ALL: [StorageBackend; 2] = [StorageBackend::Sqlite, StorageBackend::Json];
+
+    fn path(self) -> PathBuf {
+        let file = match self {
+            StorageBackend::Sqlite => "history.db",
+            StorageBackend::Json => "history.json",
+        };
+        directories::ProjectDirs::from("com", "pixeldrain", "client")
+            .map(|proj_dirs| proj_dirs.data_dir().join(file))
+            .unwrap_or_else(|| PathBuf::from(file))
+    }
+
+    /// Open this backend's store in the app data folder
+    pub fn open(self) -> Result<Box<dyn Storage>, StorageError> {
+        let path = self.path();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        Ok(match self {
+            StorageBackend::Sqlite => Box::new(SqliteStorage::open(&path)?),
+            StorageBackend::Json => Box::new(JsonStorage::open(&path)?),
+        })
+    }
+}
+
+/// Storage that only lasts as long as the process, for tests and as a
+/// fallback when the configured store can't be opened
+pub fn in_memory() -> Box<dyn Storage> {
+    Box::new(SqliteStorage::in_memory())
+}
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS uploads (
+        id INTEGER PRIMARY KEY,
+        file_id TEXT NOT NULL,
+        url TEXT NOT NULL,
+        filename TEXT NOT NULL,
+        size INTEGER NOT NULL,
+        timestamp TEXT NOT NULL,
+        verified INTEGER,
+        error TEXT,
+        anonymous INTEGER NOT NULL DEFAULT 0
+    );
+    CREATE INDEX IF NOT EXISTS uploads_filename ON uploads (filename COLLATE NOCASE);
+    CREATE INDEX IF NOT EXISTS uploads_url ON uploads (url);
+    CREATE INDEX IF NOT EXISTS uploads_timestamp ON uploads (timestamp);
+    CREATE TABLE IF NOT EXISTS downloads (
+        id INTEGER PRIMARY KEY,
+        url TEXT NOT NULL,
+        filename TEXT NOT NULL,
+        local_path TEXT NOT NULL,
+        timestamp TEXT NOT NULL,
+        error TEXT
+    );
+    CREATE INDEX IF NOT EXISTS downloads_filename ON downloads (filename COLLATE NOCASE);
+    CREATE INDEX IF NOT EXISTS downloads_url ON downloads (url);
+    CREATE INDEX IF NOT EXISTS downloads_timestamp ON downloads (timestamp);
+    CREATE TABLE IF NOT EXISTS cache (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+";
+
+const UPLOAD_COLUMNS: &str = "file_id, url, filename, size, timestamp, verified, error, anonymous";
+const DOWNLOAD_COLUMNS: &str = "url, filename, local_path, timestamp, error";
+
+/// History in an SQLite database. Views filter, search and collapse duplicates
+/// in SQL, so only the entries on screen are loaded.
+pub struct SqliteStorage {
+    conn: Connection,
+}
+
+impl SqliteStorage {
+    pub fn open(path: &Path) -> Result<Self, StorageError> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    pub fn in_memory() -> Self {
+        Self::with_connection(Connection::open_in_memory().expect("in-memory SQLite database"))
+            .expect("history schema")
+    }
+
+    fn with_connection(conn: Connection) -> Result<Self, StorageError> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    fn query_view<T>(
+        &self,
+        sql: &str,
+        options: &HistoryViewOptions,
+        limit: usize,
+        entry: impl Fn(&Row) -> rusqlite::Result<T>,
+    ) -> Result<Vec<(T, usize)>, StorageError> {
+        let mut statement = self.conn.prepare_cached(sql)?;
+        let search = search_pattern(&options.search);
+        let rows = statement.query_map(params![search, limit as i64, options.since, options.until], |row| {
+            let count: i64 = row.get("duplicates")?;
+            Ok((entry(row)?, count as usize))
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+}
+
+impl Storage for SqliteStorage {
+    fn add_upload(&mut self, entry: &UploadHistoryEntry) -> Result<(), StorageError> {
+        insert_upload(&self.conn, entry)
+    }
+
+    fn add_download(&mut self, entry: &DownloadHistoryEntry) -> Result<(), StorageError> {
+        insert_download(&self.conn, entry)
+    }
+
+    fn import(&mut self, uploads: &[UploadHistoryEntry], downloads: &[DownloadHistoryEntry]) -> Result<usize, StorageError> {
+        let tx = self.conn.transaction()?;
+        let mut imported = 0;
+        for entry in uploads {
+            let exists: Option<i64> = tx
+                .query_row(
+                    "SELECT id FROM uploads WHERE timestamp = ?1 AND url = ?2 AND filename = ?3",
+                    params![entry.timestamp, entry.url, entry.filename],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            if exists.is_none() {
+                insert_upload(&tx, entry)?;
+                imported += 1;
+            }
+        }
+        for entry in downloads {
+            let exists: Option<i64> = tx
+                .query_row(
+                    "SELECT id FROM downloads WHERE timestamp = ?1 AND url = ?2 AND filename = ?3",
+                    params![entry.timestamp, entry.url, entry.filename],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            if exists.is_none() {
+                insert_download(&tx, entry)?;
+                imported += 1;
+            }
+        }
+        tx.commit()?;
+        Ok(imported)
+    }
+
+    fn has_uploads(&self) -> Result<bool, StorageError> {
+        Ok(self.conn.query_row("SELECT EXISTS (SELECT 1 FROM uploads)", [], |row| row.get(0))?)
+    }
+
+    fn has_downloads(&self) -> Result<bool, StorageError> {
+        Ok(self.conn.query_row("SELECT EXISTS (SELECT 1 FROM downloads)", [], |row| row.get(0))?)
+    }
+
+    fn uploads(&self, options: &HistoryViewOptions, limit: usize) -> Result<Vec<(UploadHistoryEntry, usize)>, StorageError> {
+        // A verification mismatch counts as a failure too
+        let sql = view_query("uploads", UPLOAD_COLUMNS, "(error IS NOT NULL OR verified = 0)", options);
+        self.query_view(&sql, options, limit, |row| {
+            Ok(UploadHistoryEntry {
+                id: row.get(0)?,
+                url: row.get(1)?,
+                filename: row.get(2)?,
+                size: row.get::<_, i64>(3)? as u64,
+                timestamp: row.get(4)?,
+                verified: row.get(5)?,
+                error: row.get(6)?,
+                anonymous: row.get(7)?,
+            })
+        })
+    }
+
+    fn downloads(&self, options: &HistoryViewOptions, limit: usize) -> Result<Vec<(DownloadHistoryEntry, usize)>, StorageError> {
+        let sql = view_query("downloads", DOWNLOAD_COLUMNS, "error IS NOT NULL", options);
+        self.query_view(&sql, options, limit, |row| {
+            Ok(DownloadHistoryEntry {
+                url: row.get(0)?,
+                filename: row.get(1)?,
+                local_path: row.get(2)?,
+                timestamp: row.get(3)?,
+                error: row.get(4)?,
+            })
+        })
+    }
+
+    fn cache_get(&self, key: &str) -> Result<Option<String>, StorageError> {
+        Ok(self.conn.query_row("SELECT value FROM cache WHERE key = ?1", params![key], |row| row.get(0)).optional()?)
+    }
+
+    fn cache_put(&mut self, key: &str, value: &str) -> Result<(), StorageError> {
+        self.conn.execute("INSERT OR REPLACE INTO cache (key, value) VALUES (?1, ?2)", params![key, value])?;
+        Ok(())
+    }
+
+    fn cache_entries(&self) -> Result<Vec<(String, String)>, StorageError> {
+        let mut statement = self.conn.prepare("SELECT key, value FROM cache ORDER BY key")?;
+        let entries = statement.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?.collect::<Result<_, _>>()?;
+        Ok(entries)
+    }
+}
+
+fn insert_upload(conn: &Connection, entry: &UploadHistoryEntry) -> Result<(), StorageError> {
+    conn.execute(
+        &format!("INSERT INTO uploads ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)", UPLOAD_COLUMNS),
+        params![
+            entry.id,
+            entry.url,
+            entry.filename,
+            entry.size as i64,
+            entry.timestamp,
+            entry.verified,
+            entry.error,
+            entry.anonymous,
+        ],
+    )?;
+    Ok(())
+}
+
+fn insert_download(conn: &Connection, entry: &DownloadHistoryEntry) -> Result<(), StorageError> {
+    conn.execute(
+        &format!("INSERT INTO downloads ({}) VALUES (?1, ?2, ?3, ?4, ?5)", DOWNLOAD_COLUMNS),
+        params![entry.url, entry.filename, entry.local_path, entry.timestamp, entry.error],
+    )?;
+    Ok(())
+}
+
+/// Query for a history view. ?1 is the LIKE pattern of the search (NULL for
+/// none), ?2 the maximum number of rows and ?3 and ?4 the date range (NULL when open).
+fn view_query(table: &str, columns: &str, failed: &str, options: &HistoryViewOptions) -> String {
+    let mut filters = vec![
+        "(?1 IS NULL OR filename LIKE ?1 ESCAPE '\\' OR url LIKE ?1 ESCAPE '\\')".to_string(),
+        "(?3 IS NULL OR timestamp >= ?3) AND (?4 IS NULL OR timestamp < ?4)".to_string(),
+    ];
+    if options.only_failures {
+        filters.push(failed.to_string());
+    }
+    // Failed entries have no URL, collapse those by name instead
+    let partition = if options.collapse_duplicates {
+        "CASE WHEN url = '' THEN filename ELSE url END"
+    } else {
+        "id"
+    };
+    format!(
+        "SELECT {columns}, duplicates FROM (
+            SELECT {columns}, id,
+                COUNT(*) OVER (PARTITION BY {partition}) AS duplicates,
+                ROW_NUMBER() OVER (PARTITION BY {partition} ORDER BY id DESC) AS newest
+            FROM {table} WHERE {filters}
+        ) WHERE newest = 1 ORDER BY id DESC LIMIT ?2",
+        filters = filters.join(" AND "),
+    )
+}
+
+/// Case-insensitive substring pattern for LIKE, None when not searching
+fn search_pattern(search: &str) -> Option<String> {
+    let search = search.trim();
+    if search.is_empty() {
+        return None;
+    }
+    let escaped = search.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+    Some(format!("%{}%", escaped))
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct JsonData {
+    #[serde(default)]
+    uploads: Vec<UploadHistoryEntry>,
+    #[serde(default)]
+    downloads: Vec<DownloadHistoryEntry>,
+    #[serde(default)]
+    cache: HashMap<String, String>,
+}
+
+/// History in a JSON file, held in memory and rewritten on every change
+pub struct JsonStorage {
+    path: PathBuf,
+    data: JsonData,
+}
+
+impl JsonStorage {
+    pub fn open(path: &Path) -> Result<Self, StorageError> {
+        let data = match std::fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => JsonData::default(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self { path: path.to_path_buf(), data })
+    }
+
+    /// Write to a temporary file first, so a crash can't leave half a history behind
+    fn save(&self) -> Result<(), StorageError> {
+        let temp = self.path.with_extension("json.tmp");
+        std::fs::write(&temp, serde_json::to_string_pretty(&self.data)?)?;
+        std::fs::rename(&temp, &self.path)?;
+        Ok(())
+    }
+}
+
+impl Storage for JsonStorage {
+    fn add_upload(&mut self, entry: &UploadHistoryEntry) -> Result<(), StorageError> {
+        self.data.uploads.push(entry.clone());
+        self.save()
+    }
+
+    fn add_download(&mut self, entry: &DownloadHistoryEntry) -> Result<(), StorageError> {
+        self.data.downloads.push(entry.clone());
+        self.save()
+    }
+
+    fn import(&mut self, uploads: &[UploadHistoryEntry], downloads: &[DownloadHistoryEntry]) -> Result<usize, StorageError> {
+        let mut imported = 0;
+        for entry in uploads {
+            if !self.data.uploads.iter().any(|e| e.same_transfer(entry)) {
+                self.data.uploads.push(entry.clone());
+                imported += 1;
+            }
+        }
+        for entry in downloads {
+            if !self.data.downloads.iter().any(|e| e.same_transfer(entry)) {
+                self.data.downloads.push(entry.clone());
+                imported += 1;
+            }
+        }
+        if imported > 0 {
+            self.save()?;
+        }
+        Ok(imported)
+    }
+
+    fn has_uploads(&self) -> Result<bool, StorageError> {
+        Ok(!self.data.uploads.is_empty())
+    }
+
+    fn has_downloads(&self) -> Result<bool, StorageError> {
+        Ok(!self.data.downloads.is_empty())
+    }
+
+    fn uploads(&self, options: &HistoryViewOptions, limit: usize) -> Result<Vec<(UploadHistoryEntry, usize)>, StorageError> {
+        Ok(history::view(&self.data.uploads, options, limit))
+    }
+
+    fn downloads(&self, options: &HistoryViewOptions, limit: usize) -> Result<Vec<(DownloadHistoryEntry, usize)>, StorageError> {
+        Ok(history::view(&self.data.downloads, options, limit))
+    }
+
+    fn cache_get(&self, key: &str) -> Result<Option<String>, StorageError> {
+        Ok(self.data.cache.get(key).cloned())
+    }
+
+    fn cache_put(&mut self, key: &str, value: &str) -> Result<(), StorageError> {
+        self.data.cache.insert(key.to_string(), value.to_string());
+        self.save()
+    }
+
+    fn cache_entries(&self) -> Result<Vec<(String, String)>, StorageError> {
+        let mut entries: Vec<(String, String)> = self.data.cache.clone().into_iter().collect();
+        entries.sort();
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::{ExportFormat, HistoryKind};
+    use chrono::Utc;
+
+    fn download(url: &str, filename: &str, error: Option<&str>) -> DownloadHistoryEntry {
+        DownloadHistoryEntry {
+            url: url.to_string(),
+            filename: filename.to_string(),
+            local_path: String::new(),
+            timestamp: Utc::now(),
+            error: error.map(str::to_string),
+        }
+    }
+
+    /// One store of each backend, with the temporary folder of the JSON file
+    fn backends() -> (PathBuf, Vec<Box<dyn Storage>>) {
+        let dir = std::env::temp_dir().join(format!("pixeldrain-storage-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let json = JsonStorage::open(&dir.join("history.json")).unwrap();
+        (dir, vec![in_memory(), Box::new(json)])
+    }
+
+    #[test]
+    fn views_filter_search_and_collapse() {
+        let (dir, stores) = backends();
+        for mut store in stores {
+            store.add_download(&download("https://pixeldrain.com/u/a", "cat.png", None)).unwrap();
+            store.add_download(&download("https://pixeldrain.com/u/b", "dog_1.png", Some("404"))).unwrap();
+            store.add_download(&download("https://pixeldrain.com/u/a", "cat.png", None)).unwrap();
+            store.add_download(&download("https://pixeldrain.com/u/c", "dog1.png", None)).unwrap();
+
+            let names = |options: HistoryViewOptions| -> Vec<(String, usize)> {
+                store.downloads(&options, 100).unwrap().into_iter().map(|(e, n)| (e.filename, n)).collect()
+            };
+            let all = names(HistoryViewOptions::default());
+            assert_eq!(all.len(), 4);
+            assert_eq!(all[0].0, "dog1.png", "newest entry first");
+
+            let collapsed = names(HistoryViewOptions { collapse_duplicates: true, ..Default::default() });
+            assert_eq!(collapsed, [("dog1.png".to_string(), 1), ("cat.png".to_string(), 2), ("dog_1.png".to_string(), 1)]);
+
+            let failures = names(HistoryViewOptions { only_failures: true, ..Default::default() });
+            assert_eq!(failures, [("dog_1.png".to_string(), 1)]);
+
+            // Case-insensitive, and LIKE wildcards in the search are taken literally
+            let search = names(HistoryViewOptions { search: "U/B".to_string(), ..Default::default() });
+            assert_eq!(search, [("dog_1.png".to_string(), 1)]);
+            let search = names(HistoryViewOptions { search: "g_1".to_string(), ..Default::default() });
+            assert_eq!(search, [("dog_1.png".to_string(), 1)]);
+
+            assert_eq!(store.downloads(&HistoryViewOptions::default(), 2).unwrap().len(), 2);
+            assert!(!store.has_uploads().unwrap());
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn export_respects_date_range() {
+        let (dir, stores) = backends();
+        for mut store in stores {
+            let day = |d: u32| chrono::NaiveDate::from_ymd_opt(2024, 5, d).unwrap().and_hms_opt(12, 0, 0).unwrap().and_utc();
+            let mut old = download("https://pixeldrain.com/u/a", "old.txt", None);
+            old.timestamp = day(1);
+            let mut new = download("https://pixeldrain.com/u/b", "new, final.txt", None);
+            new.timestamp = day(3);
+            store.add_download(&old).unwrap();
+            store.add_download(&new).unwrap();
+
+            let options = HistoryViewOptions { since: Some(day(2)), until: Some(day(4)), ..Default::default() };
+            let csv = history::export(store.as_ref(), HistoryKind::Downloads, &options, ExportFormat::Csv).unwrap();
+            let lines: Vec<&str> = csv.lines().collect();
+            assert_eq!(lines.len(), 2);
+            assert_eq!(lines[0], "timestamp,filename,url,local_path,error");
+            assert!(lines[1].contains(",\"new, final.txt\",https://pixeldrain.com/u/b,"));
+
+            let json = history::export(store.as_ref(), HistoryKind::Downloads, &HistoryViewOptions::default(), ExportFormat::Json).unwrap();
+            let parsed: Vec<DownloadHistoryEntry> = serde_json::from_str(&json).unwrap();
+            assert_eq!(parsed, [new, old]);
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn import_skips_known_entries_and_cache_round_trips() {
+        let (dir, stores) = backends();
+        for mut store in stores {
+            let upload = UploadHistoryEntry {
+                id: "abc".to_string(),
+                url: "https://pixeldrain.com/u/abc".to_string(),
+                filename: "a.txt".to_string(),
+                size: 3,
+                timestamp: Utc::now(),
+                verified: Some(true),
+                error: None,
+                anonymous: true,
+            };
+            let downloads = [download("https://pixeldrain.com/u/x", "x.bin", None)];
+
+            assert_eq!(store.import(std::slice::from_ref(&upload), &downloads).unwrap(), 2);
+            assert_eq!(store.import(std::slice::from_ref(&upload), &downloads).unwrap(), 0);
+            let uploads = store.uploads(&HistoryViewOptions::default(), 10).unwrap();
+            assert_eq!(uploads, [(upload, 1)]);
+
+            assert_eq!(store.cache_get("files").unwrap(), None);
+            store.cache_put("files", "[1]").unwrap();
+            store.cache_put("files", "[2]").unwrap();
+            assert_eq!(store.cache_get("files").unwrap().as_deref(), Some("[2]"));
+            store.cache_put("b", "[]").unwrap();
+            assert_eq!(store.cache_entries().unwrap(), [("b".to_string(), "[]".to_string()), ("files".to_string(), "[2]".to_string())]);
+        }
+
+        // The JSON file is read back on the next start
+        let reopened = JsonStorage::open(&dir.join("history.json")).unwrap();
+        assert!(reopened.has_uploads().unwrap());
+        assert_eq!(reopened.cache_get("files").unwrap().as_deref(), Some("[2]"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
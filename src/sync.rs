@@ -0,0 +1,311 @@
+// sync.rs - One-way sync of local folders to the account. A sync pair is a
+// folder and where it goes, the whole account or one of its lists, and its rules
+// decide which files are synced at all (size cap, extensions), whether files
+// deleted locally are deleted from the account too, and what happens when the
+// account has a newer version of a file than the folder. Files are matched by
+// name and told apart by SHA-256, and the plan of what a sync would do can be
+// looked at before anything is changed. Only files the sync uploaded itself are
+// ever deleted, and not by the sync: they're offered for deletion after it.
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use pixeldrain_api::FileInfo;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum ConflictPolicy {
+    /// The newer of the two versions is kept, an older local file isn't uploaded
+    #[default]
+    NewestWins,
+    /// Nothing is changed until the user decided
+    Ask,
+}
+
+/// What to do with a local file that changed when the account has it too
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Resolution {
+    Upload,
+    Skip,
+    Ask,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SyncRules {
+    /// Bigger files are left out, None syncs every size
+    pub max_file_size: Option<u64>,
+    /// Only files with these extensions are synced, all when empty
+    pub include_extensions: Vec<String>,
+    /// Files with these extensions are left out, even when included
+    pub exclude_extensions: Vec<String>,
+    /// Offer to delete files the sync uploaded from the account when they're
+    /// deleted from the folder
+    pub propagate_deletes: bool,
+    pub conflict_policy: ConflictPolicy,
+}
+
+/// Extensions from a list like "jpg, .PNG tar.gz", lowercase without dots
+pub fn parse_extensions(text: &str) -> Vec<String> {
+    text.split(|c: char| c == ',' || c.is_whitespace())
+        .map(|extension| extension.trim().trim_start_matches('.').to_lowercase())
+        .filter(|extension| !extension.is_empty())
+        .collect()
+}
+
+impl SyncRules {
+    /// Whether a file named `name` of `size` bytes is synced
+    pub fn includes(&self, name: &str, size: u64) -> bool {
+        if self.max_file_size.is_some_and(|max| size > max) {
+            return false;
+        }
+        let name = name.to_lowercase();
+        // Also matches double extensions such as tar.gz
+        let has = |extension: &String| name.ends_with(&format!(".{}", extension));
+        (self.include_extensions.is_empty() || self.include_extensions.iter().any(has))
+            && !self.exclude_extensions.iter().any(has)
+    }
+
+    /// Resolve a local file that differs from the account's version of it
+    pub fn resolve(&self, local_modified: DateTime<Utc>, remote_modified: DateTime<Utc>) -> Resolution {
+        match self.conflict_policy {
+            _ if local_modified > remote_modified => Resolution::Upload,
+            ConflictPolicy::NewestWins => Resolution::Skip,
+            ConflictPolicy::Ask => Resolution::Ask,
+        }
+    }
+}
+
+/// A local folder and what it's synced to, each pair has its own rules
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SyncPair {
+    pub folder: String,
+    /// List the folder is synced to, the files of the whole account when None
+    pub list: Option<String>,
+    pub rules: SyncRules,
+    /// Files the sync uploaded from the folder, the only ones it offers to delete
+    pub uploaded: Vec<String>,
+}
+
+impl SyncPair {
+    /// Whether this is the pair of `folder` and `list`
+    pub fn is(&self, folder: &str, list: Option<&str>) -> bool {
+        self.folder == folder && self.list.as_deref() == list
+    }
+}
+
+/// A file of the synced folder
+#[derive(Clone, Debug, PartialEq)]
+pub struct LocalFile {
+    pub path: PathBuf,
+    pub name: String,
+    pub size: u64,
+    pub modified: DateTime<Utc>,
+}
+
+/// Files directly in `folder`, the account has no folders to put the ones of
+/// subfolders in
+pub fn local_files(folder: &Path) -> io::Result<Vec<LocalFile>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(folder)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if !metadata.is_file() {
+            continue;
+        }
+        files.push(LocalFile {
+            path: entry.path(),
+            name: entry.file_name().to_string_lossy().to_string(),
+            size: metadata.len(),
+            modified: metadata.modified().map(DateTime::from).unwrap_or_else(|_| Utc::now()),
+        });
+    }
+    files.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(files)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Action {
+    /// Not in the account yet
+    Upload(PathBuf),
+    /// Changed in the folder, the account's version is deleted once it's
+    /// uploaded. Only for a version the sync uploaded, next to any other file
+    /// of the same name the new one is uploaded as `Upload`.
+    Replace { path: PathBuf, old_id: String },
+    /// Uploaded by the sync and gone from the folder since, only deleted once
+    /// the user confirms
+    Delete { id: String, name: String },
+    /// The account's version is newer, left alone until the user decides
+    Conflict(PathBuf),
+}
+
+/// What a sync does, shown as is for a dry run
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Plan {
+    pub actions: Vec<Action>,
+    /// Files the account has with the same content
+    pub unchanged: usize,
+    /// Changed files the account has a newer version of
+    pub skipped: usize,
+    /// Files the rules leave out
+    pub excluded: usize,
+}
+
+impl Plan {
+    /// Actions that change the account, conflicts wait for the user
+    pub fn changes(&self) -> usize {
+        self.actions.iter().filter(|action| !matches!(action, Action::Conflict(_))).count()
+    }
+
+    /// Uploads and replacements, what a sync does without asking
+    pub fn uploads(&self) -> usize {
+        self.actions.iter().filter(|action| matches!(action, Action::Upload(_) | Action::Replace { .. })).count()
+    }
+
+    /// Files to delete from the account, as (ID, name)
+    pub fn deletions(&self) -> Vec<(String, String)> {
+        self.actions
+            .iter()
+            .filter_map(|action| match action {
+                Action::Delete { id, name } => Some((id.clone(), name.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// How a sync went
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Report {
+    pub uploaded: usize,
+    pub failures: Vec<String>,
+}
+
+/// Compare the folder's files with the account's. `uploaded` has the IDs of the
+/// files the sync uploaded, the only ones it deletes. `hash` gives the SHA-256
+/// of a local file, it's only asked for files the account has one of the same
+/// name and size of.
+pub fn plan(local: &[LocalFile], remote: &[FileInfo], uploaded: &[String], rules: &SyncRules, hash: impl Fn(&Path) -> Option<String>) -> Plan {
+    let mut plan = Plan::default();
+    for file in local {
+        if !rules.includes(&file.name, file.size) {
+            plan.excluded += 1;
+            continue;
+        }
+        let same_name: Vec<&FileInfo> = remote.iter().filter(|info| info.name == file.name).collect();
+        let Some(newest) = same_name.iter().max_by_key(|info| info.date_upload) else {
+            plan.actions.push(Action::Upload(file.path.clone()));
+            continue;
+        };
+        let unchanged = same_name.iter().any(|info| info.size == file.size)
+            && hash(&file.path).is_some_and(|hash| same_name.iter().any(|info| info.hash_sha256.eq_ignore_ascii_case(&hash)));
+        if unchanged {
+            plan.unchanged += 1;
+            continue;
+        }
+        match rules.resolve(file.modified, newest.date_upload) {
+            Resolution::Upload if uploaded.contains(&newest.id) => {
+                plan.actions.push(Action::Replace { path: file.path.clone(), old_id: newest.id.clone() });
+            }
+            Resolution::Upload => plan.actions.push(Action::Upload(file.path.clone())),
+            Resolution::Skip => plan.skipped += 1,
+            Resolution::Ask => plan.actions.push(Action::Conflict(file.path.clone())),
+        }
+    }
+    if rules.propagate_deletes {
+        for info in remote {
+            // The rest of the account stays, also files with a name the folder had
+            if uploaded.contains(&info.id) && !local.iter().any(|file| file.name == info.name) {
+                plan.actions.push(Action::Delete { id: info.id.clone(), name: info.name.clone() });
+            }
+        }
+    }
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn rules_filter_files_and_resolve_conflicts() {
+        let mut rules = SyncRules { max_file_size: Some(1_000), ..SyncRules::default() };
+        assert!(rules.includes("notes.txt", 1_000));
+        assert!(!rules.includes("video.mkv", 1_001));
+
+        rules.include_extensions = parse_extensions("JPG, .png tar.gz");
+        rules.exclude_extensions = parse_extensions("tmp.png");
+        assert_eq!(rules.include_extensions, ["jpg", "png", "tar.gz"]);
+        assert!(rules.includes("Holiday.JPG", 10));
+        assert!(rules.includes("backup.tar.gz", 10));
+        assert!(!rules.includes("notes.txt", 10));
+        assert!(!rules.includes("render.tmp.png", 10));
+        assert!(!rules.includes("jpg", 10));
+
+        let now = Utc::now();
+        let earlier = now - Duration::hours(1);
+        assert_eq!(rules.resolve(now, earlier), Resolution::Upload);
+        assert_eq!(rules.resolve(earlier, now), Resolution::Skip);
+        rules.conflict_policy = ConflictPolicy::Ask;
+        assert_eq!(rules.resolve(earlier, now), Resolution::Ask);
+        assert_eq!(rules.resolve(now, earlier), Resolution::Upload);
+    }
+
+    #[test]
+    fn plans_upload_new_and_changed_files_and_delete_removed_ones() {
+        let now = Utc::now();
+        let local = |name: &str, size: u64, modified: DateTime<Utc>| LocalFile { path: PathBuf::from(name), name: name.to_string(), size, modified };
+        let remote = |id: &str, name: &str, size: u64, hash: &str| FileInfo {
+            id: id.to_string(),
+            name: name.to_string(),
+            size,
+            hash_sha256: hash.to_string(),
+            date_upload: now - Duration::hours(1),
+            ..Default::default()
+        };
+        let folder = [
+            local("new.txt", 1, now),
+            local("same.txt", 2, now),
+            local("edited.txt", 3, now),
+            local("theirs.txt", 8, now),
+            local("older.txt", 4, now - Duration::hours(2)),
+            local("huge.iso", 5_000, now),
+        ];
+        let account = [
+            remote("s1", "same.txt", 2, "AA"),
+            remote("e1", "edited.txt", 3, "bb"),
+            remote("t1", "theirs.txt", 8, "bc"),
+            remote("o1", "older.txt", 9, "cc"),
+            remote("g1", "gone.txt", 6, "dd"),
+            remote("h1", "other.iso", 5_000, "ee"),
+            remote("u1", "unrelated.txt", 7, "ab"),
+        ];
+        let uploaded = ["s1".to_string(), "e1".to_string(), "g1".to_string(), "h1".to_string()];
+        let hash = |path: &Path| Some(if path == Path::new("same.txt") { "aa" } else { "ff" }.to_string());
+        let mut rules = SyncRules { max_file_size: Some(1_000), ..SyncRules::default() };
+
+        let planned = plan(&folder, &account, &uploaded, &rules, hash);
+        assert_eq!(planned.actions, [
+            Action::Upload(PathBuf::from("new.txt")),
+            Action::Replace { path: PathBuf::from("edited.txt"), old_id: "e1".to_string() },
+            // Not uploaded by the sync, so the account's version stays next to the new one
+            Action::Upload(PathBuf::from("theirs.txt")),
+        ]);
+        assert_eq!((planned.unchanged, planned.skipped, planned.excluded), (1, 1, 1));
+
+        rules.propagate_deletes = true;
+        rules.conflict_policy = ConflictPolicy::Ask;
+        let planned = plan(&folder, &account, &uploaded, &rules, hash);
+        assert_eq!(planned.actions[3..], [
+            Action::Conflict(PathBuf::from("older.txt")),
+            // unrelated.txt wasn't uploaded by the sync
+            Action::Delete { id: "g1".to_string(), name: "gone.txt".to_string() },
+            Action::Delete { id: "h1".to_string(), name: "other.iso".to_string() },
+        ]);
+        assert_eq!((planned.changes(), planned.uploads()), (5, 3));
+        assert_eq!(planned.deletions(), [("g1".to_string(), "gone.txt".to_string()), ("h1".to_string(), "other.iso".to_string())]);
+    }
+}
@@ -0,0 +1,145 @@
+// taskbar.rs - Aggregate transfer progress on the OS taskbar / dock
+// Windows: taskbar button progress (ITaskbarList3)
+// macOS: percentage badge on the dock icon
+// Linux: Unity launcher entry progress over D-Bus (also read by KDE, Dash to Dock, Plank)
+use eframe::egui;
+use std::time::Duration;
+
+#[cfg(target_os = "linux")]
+use std::process::{Child, Command, Stdio};
+
+// Desktop entry the launcher progress is attached to, see build/pixeldrain.desktop
+#[cfg(target_os = "linux")]
+const DESKTOP_ENTRY_URI: &str = "application://pixeldrain.desktop";
+
+pub struct TaskbarProgress {
+    // Last percentage shown, None when no progress is displayed
+    shown: Option<u8>,
+    #[cfg(target_os = "linux")]
+    pending: Option<Child>,
+    #[cfg(windows)]
+    taskbar: Option<windows::Win32::UI::Shell::ITaskbarList3>,
+}
+
+impl TaskbarProgress {
+    pub fn new() -> Self {
+        Self {
+            shown: None,
+            #[cfg(target_os = "linux")]
+            pending: None,
+            #[cfg(windows)]
+            taskbar: None,
+        }
+    }
+
+    /// Show `progress` (0.0 - 1.0) on the taskbar, or clear it with None.
+    /// Only whole percent changes are sent to the OS.
+    pub fn update(&mut self, ctx: &egui::Context, frame: &eframe::Frame, progress: Option<f32>) {
+        let percent = progress.map(|p| (p.clamp(0.0, 1.0) * 100.0).round() as u8);
+        if percent == self.shown {
+            return;
+        }
+        if self.send(frame, percent) {
+            self.shown = percent;
+        } else {
+            // The platform was busy with the previous update, try again shortly
+            ctx.request_repaint_after(Duration::from_millis(100));
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn send(&mut self, _frame: &eframe::Frame, percent: Option<u8>) -> bool {
+        // Don't pile up gdbus processes, wait for the previous one to exit
+        if let Some(child) = &mut self.pending {
+            match child.try_wait() {
+                Ok(None) => return false,
+                _ => self.pending = None,
+            }
+        }
+
+        let properties = match percent {
+            Some(percent) => format!("{{'progress': <{:.2}>, 'progress-visible': <true>}}", percent as f64 / 100.0),
+            None => "{'progress-visible': <false>}".to_string(),
+        };
+        self.pending = Command::new("gdbus")
+            .args([
+                "emit",
+                "--session",
+                "--object-path",
+                "/com/canonical/unity/launcherentry/pixeldrain",
+                "--signal",
+                "com.canonical.Unity.LauncherEntry.Update",
+                DESKTOP_ENTRY_URI,
+                &properties,
+            ])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .ok();
+        // Without gdbus there is nothing to retry
+        true
+    }
+
+    #[cfg(target_os = "macos")]
+    fn send(&mut self, _frame: &eframe::Frame, percent: Option<u8>) -> bool {
+        use objc2::MainThreadMarker;
+        use objc2_app_kit::NSApplication;
+        use objc2_foundation::NSString;
+
+        // AppKit may only be used from the main thread, which is where eframe calls update()
+        let Some(mtm) = MainThreadMarker::new() else {
+            return true;
+        };
+        let label = percent.map(|percent| NSString::from_str(&format!("{}%", percent)));
+        // Safety: called on the main thread with a valid (or nil) badge string
+        unsafe {
+            let dock_tile = NSApplication::sharedApplication(mtm).dockTile();
+            dock_tile.setBadgeLabel(label.as_deref());
+        }
+        true
+    }
+
+    #[cfg(windows)]
+    fn send(&mut self, frame: &eframe::Frame, percent: Option<u8>) -> bool {
+        use raw_window_handle::{HasWindowHandle, RawWindowHandle};
+        use windows::Win32::Foundation::HWND;
+        use windows::Win32::System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED};
+        use windows::Win32::UI::Shell::{ITaskbarList3, TaskbarList, TBPF_NOPROGRESS, TBPF_NORMAL};
+
+        let Ok(handle) = frame.window_handle() else {
+            return true;
+        };
+        let RawWindowHandle::Win32(handle) = handle.as_raw() else {
+            return true;
+        };
+        let hwnd = HWND(handle.hwnd.get() as *mut std::ffi::c_void);
+
+        if self.taskbar.is_none() {
+            // COM is usually initialized by the windowing backend already, the result doesn't matter
+            unsafe {
+                let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+                self.taskbar = CoCreateInstance::<_, ITaskbarList3>(&TaskbarList, None, CLSCTX_INPROC_SERVER)
+                    .ok()
+                    .filter(|taskbar| taskbar.HrInit().is_ok());
+            }
+        }
+        let Some(taskbar) = &self.taskbar else {
+            return true;
+        };
+
+        unsafe {
+            let _ = match percent {
+                Some(percent) => taskbar
+                    .SetProgressState(hwnd, TBPF_NORMAL)
+                    .and_then(|_| taskbar.SetProgressValue(hwnd, percent as u64, 100)),
+                None => taskbar.SetProgressState(hwnd, TBPF_NOPROGRESS),
+            };
+        }
+        true
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+    fn send(&mut self, _frame: &eframe::Frame, _percent: Option<u8>) -> bool {
+        true
+    }
+}
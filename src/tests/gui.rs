@@ -0,0 +1,73 @@
+// gui.rs - Tests that drive the whole window headlessly, through the same
+// update() eframe calls, against the mock API
+use super::*;
+use egui_kittest::Harness;
+use egui_kittest::kittest::Queryable;
+
+/// Window around `app`, first frame already drawn
+fn harness(app: PixelDrainApp) -> Harness<'static, PixelDrainApp> {
+    let mut harness = Harness::builder().with_size(egui::vec2(900.0, 1000.0)).build_eframe(|_| app);
+    harness.run_steps(2);
+    harness
+}
+
+fn drop_files(harness: &mut Harness<'_, PixelDrainApp>, paths: &[PathBuf]) {
+    harness.input_mut().dropped_files = paths.iter().map(|path| egui::DroppedFile { path: Some(path.clone()), ..Default::default() }).collect();
+    harness.run_steps(2);
+}
+
+#[test]
+fn dropped_files_upload_and_show_in_history() {
+    let dir = temp_dir();
+    let paths: Vec<PathBuf> = ["a.txt", "b.txt"].iter().map(|name| {
+        let path = dir.join(name);
+        fs::write(&path, name).unwrap();
+        path
+    }).collect();
+    let mock = Arc::new(MockApi { verify_matches: true, ..Default::default() });
+    let mut harness = harness(app_with(mock.clone(), &dir));
+    let events = harness.state().events.subscribe();
+
+    drop_files(&mut harness, &paths);
+    harness.get_by_label(&t!("upload-files-button", count = 2)).click();
+    harness.run_steps(2);
+    wait_for_workers(harness.state());
+    harness.run_steps(2);
+
+    assert_eq!(*mock.uploads.lock().unwrap(), paths);
+    let events: Vec<events::Event> = events.try_iter().collect();
+    assert!(matches!(&events[0], events::Event::Started { direction: Direction::Upload, name, .. } if name == "2 files"));
+    assert!(events.iter().any(|e| matches!(e, events::Event::Progress { fraction, .. } if *fraction == 1.0)));
+    assert!(matches!(events.last(), Some(events::Event::Finished { .. })));
+    harness.get_by_label(&t!("upload-complete-url-copied-to-clipboard"));
+    assert!(harness.state().state.lock().unwrap().last_error.is_none());
+
+    harness.get_by_label(&t!("tab-history")).click();
+    harness.run_steps(2);
+    assert!(harness.query_by_label_contains("a.txt").is_some());
+    assert_eq!(upload_history(&harness.state().state.lock().unwrap()).len(), 2);
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn skipped_files_are_listed_after_the_upload() {
+    let dir = temp_dir();
+    let paths = [dir.join("a.txt"), dir.join("empty.txt")];
+    fs::write(&paths[0], b"a").unwrap();
+    fs::write(&paths[1], b"").unwrap();
+    let mock = Arc::new(MockApi { verify_matches: true, ..Default::default() });
+    let mut harness = harness(app_with(mock.clone(), &dir));
+
+    drop_files(&mut harness, &paths);
+    harness.get_by_label(&t!("upload-files-button", count = 2)).click();
+    harness.run_steps(2);
+    wait_for_workers(harness.state());
+    harness.run_steps(2);
+
+    assert_eq!(*mock.uploads.lock().unwrap(), [paths[0].clone()]);
+    harness.get_by_label(&t!("upload-skipped", count = 1)).click();
+    // Lets the header finish opening
+    harness.run_steps(10);
+    harness.get_by_label(&paths[1].display().to_string());
+    let _ = fs::remove_dir_all(&dir);
+}
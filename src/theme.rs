@@ -0,0 +1,67 @@
+// theme.rs - Light, dark and high-contrast styles
+// The high-contrast style is meant for low-vision users: pure black or white
+// backgrounds, bold outlines around every control, a thick yellow (or blue, on
+// light) outline on the focused control and larger buttons and spacing.
+use egui::{Color32, Stroke, Style, Visuals};
+
+/// Style of the app for the chosen theme settings
+pub fn style(dark_mode: bool, high_contrast: bool) -> Style {
+    let mut style = Style {
+        visuals: if dark_mode { Visuals::dark() } else { Visuals::light() },
+        ..Style::default()
+    };
+    if high_contrast {
+        make_high_contrast(&mut style, dark_mode);
+    }
+    style
+}
+
+/// Apply the theme settings to the whole UI
+pub fn apply(ctx: &egui::Context, dark_mode: bool, high_contrast: bool) {
+    ctx.set_theme(if dark_mode { egui::Theme::Dark } else { egui::Theme::Light });
+    ctx.set_style(style(dark_mode, high_contrast));
+}
+
+fn make_high_contrast(style: &mut Style, dark_mode: bool) {
+    let (background, foreground, accent) = if dark_mode {
+        (Color32::BLACK, Color32::WHITE, Color32::YELLOW)
+    } else {
+        (Color32::WHITE, Color32::BLACK, Color32::from_rgb(0, 0, 170))
+    };
+
+    let visuals = &mut style.visuals;
+    visuals.override_text_color = Some(foreground);
+    visuals.panel_fill = background;
+    visuals.window_fill = background;
+    visuals.extreme_bg_color = background;
+    visuals.faint_bg_color = background;
+    visuals.window_stroke = Stroke::new(2.0, foreground);
+    visuals.hyperlink_color = accent;
+    visuals.selection.bg_fill = accent;
+    visuals.selection.stroke = Stroke::new(2.0, background);
+
+    let widgets = &mut visuals.widgets;
+    widgets.noninteractive.bg_stroke = Stroke::new(1.0, foreground);
+    widgets.noninteractive.fg_stroke = Stroke::new(1.0, foreground);
+    for widget in [&mut widgets.inactive, &mut widgets.hovered, &mut widgets.active, &mut widgets.open] {
+        widget.bg_fill = background;
+        widget.weak_bg_fill = background;
+        widget.bg_stroke = Stroke::new(2.0, foreground);
+        widget.fg_stroke = Stroke::new(2.0, foreground);
+    }
+    widgets.hovered.bg_stroke = Stroke::new(3.0, accent);
+    // egui draws focused controls with the active visuals, this is the focus outline
+    widgets.active.bg_stroke = Stroke::new(4.0, accent);
+    widgets.active.fg_stroke = Stroke::new(2.0, accent);
+
+    let spacing = &mut style.spacing;
+    spacing.interact_size = egui::vec2(56.0, 32.0);
+    spacing.button_padding = egui::vec2(10.0, 6.0);
+    spacing.item_spacing = egui::vec2(10.0, 8.0);
+    spacing.icon_width = 20.0;
+    spacing.icon_width_inner = 12.0;
+    spacing.combo_height = 300.0;
+    for font in style.text_styles.values_mut() {
+        font.size *= 1.2;
+    }
+}
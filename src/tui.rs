@@ -0,0 +1,281 @@
+// tui.rs - Terminal interface, `pixeldrain tui`, for machines without a display
+// Uploads and downloads are queued and run one at a time by the same code as
+// the window, with their progress taken from the transfer events. Built with
+// --features tui.
+use std::io;
+use std::path::PathBuf;
+use std::sync::mpsc::Receiver;
+use std::time::Duration;
+
+use ratatui::crossterm::event::{self, Event as TermEvent, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use tracing::warn;
+
+use pixeldrain_api::{Event, TransferId};
+use crate::PixelDrainApp;
+
+const HELP: &str = "a add file  d download link  x remove  s pause/resume  ↑↓ select  q quit";
+
+#[derive(Clone, Debug, PartialEq)]
+enum Kind {
+    Upload(PathBuf),
+    Download(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Status {
+    Queued,
+    Running(f32),
+    /// Link of the upload, or where the download was saved
+    Done(String),
+    Failed(String),
+}
+
+struct Item {
+    kind: Kind,
+    status: Status,
+    transfer: Option<TransferId>,
+}
+
+/// Text being typed at the bottom of the screen
+enum Input {
+    Path(String),
+    Url(String),
+}
+
+struct Queue {
+    items: Vec<Item>,
+    list: ListState,
+    input: Option<Input>,
+    paused: bool,
+    // Index of the item that's transferring
+    running: Option<usize>,
+    // Set when the transfers may have printed over the screen
+    redraw: bool,
+}
+
+impl Queue {
+    fn new() -> Self {
+        Self { items: Vec::new(), list: ListState::default(), input: None, paused: false, running: None, redraw: false }
+    }
+
+    fn push(&mut self, kind: Kind) {
+        self.items.push(Item { kind, status: Status::Queued, transfer: None });
+        if self.list.selected().is_none() {
+            self.list.select(Some(0));
+        }
+    }
+
+    /// Remove the selected item unless it's transferring
+    fn remove_selected(&mut self) {
+        let Some(index) = self.list.selected().filter(|&i| i < self.items.len() && Some(i) != self.running) else {
+            return;
+        };
+        self.items.remove(index);
+        if let Some(running) = self.running.as_mut()
+            && *running > index
+        {
+            *running -= 1;
+        }
+        self.list.select(if self.items.is_empty() { None } else { Some(index.min(self.items.len() - 1)) });
+    }
+
+    fn next_queued(&self) -> Option<usize> {
+        self.items.iter().position(|item| item.status == Status::Queued)
+    }
+
+    /// Update the items from an event of the transfers
+    fn apply(&mut self, event: Event) {
+        match event {
+            Event::Started { id, .. } => {
+                if let Some(item) = self.running.and_then(|i| self.items.get_mut(i)) {
+                    item.transfer = Some(id);
+                    item.status = Status::Running(0.0);
+                }
+                self.redraw = true;
+            }
+            Event::Progress { id, fraction } => {
+                if let Some(item) = self.items.iter_mut().find(|item| item.transfer == Some(id)) {
+                    item.status = Status::Running(fraction);
+                }
+            }
+            Event::Retrying { .. } => {}
+            Event::Finished { id, link, .. } => self.end(id, Status::Done(link)),
+            Event::Failed { id, error, .. } => self.end(id, Status::Failed(error)),
+        }
+    }
+
+    fn end(&mut self, id: TransferId, status: Status) {
+        if let Some(index) = self.items.iter().position(|item| item.transfer == Some(id)) {
+            self.items[index].status = status;
+            if self.running == Some(index) {
+                self.running = None;
+            }
+        }
+        self.redraw = true;
+    }
+}
+
+/// Run the terminal interface until the user quits
+pub fn run() -> Result<(), String> {
+    let mut app = PixelDrainApp::default();
+    let events = app.events.subscribe();
+    let mut terminal = ratatui::init();
+    let result = run_queue(&mut terminal, &mut app, &events);
+    ratatui::restore();
+    let remaining = app.workers.shutdown(crate::SHUTDOWN_TIMEOUT);
+    if remaining > 0 {
+        warn!("{} transfer(s) still running at exit", remaining);
+    }
+    app.save_preferences();
+    result.map_err(|e| e.to_string())
+}
+
+fn run_queue(terminal: &mut DefaultTerminal, app: &mut PixelDrainApp, events: &Receiver<Event>) -> io::Result<()> {
+    let mut queue = Queue::new();
+    loop {
+        // Nothing draws a window here, so the progress is drained once per loop instead
+        app.events.drain_progress();
+        for event in events.try_iter() {
+            queue.apply(event);
+        }
+        if queue.running.is_none() && !queue.paused && let Some(index) = queue.next_queued() {
+            start(app, &mut queue, index, events);
+        }
+        // The transfers print debug output to the terminal, draw everything again after it
+        if std::mem::take(&mut queue.redraw) {
+            terminal.clear()?;
+        }
+        terminal.draw(|frame| draw(frame, &mut queue))?;
+
+        if !event::poll(Duration::from_millis(100))? {
+            continue;
+        }
+        let TermEvent::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        if let Some(input) = queue.input.as_mut() {
+            let text = match input {
+                Input::Path(text) | Input::Url(text) => text,
+            };
+            match key.code {
+                KeyCode::Char(c) => text.push(c),
+                KeyCode::Backspace => {
+                    text.pop();
+                }
+                KeyCode::Esc => queue.input = None,
+                KeyCode::Enter => match queue.input.take() {
+                    Some(Input::Path(text)) if !text.trim().is_empty() => queue.push(Kind::Upload(PathBuf::from(text.trim()))),
+                    Some(Input::Url(text)) if !text.trim().is_empty() => queue.push(Kind::Download(text.trim().to_string())),
+                    _ => {}
+                },
+                _ => {}
+            }
+            continue;
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Char('a') => queue.input = Some(Input::Path(String::new())),
+            KeyCode::Char('d') => queue.input = Some(Input::Url(String::new())),
+            KeyCode::Char('x') | KeyCode::Delete => queue.remove_selected(),
+            KeyCode::Char('s') => queue.paused = !queue.paused,
+            KeyCode::Up => queue.list.select_previous(),
+            KeyCode::Down => queue.list.select_next(),
+            _ => {}
+        }
+    }
+}
+
+/// Start item `index` with the app's own upload or download
+fn start(app: &mut PixelDrainApp, queue: &mut Queue, index: usize, events: &Receiver<Event>) {
+    queue.running = Some(index);
+    match queue.items[index].kind.clone() {
+        Kind::Upload(path) => app.start_upload(path),
+        Kind::Download(url) => {
+            app.download_url = url;
+            app.start_download();
+        }
+    }
+    // The transfer announces itself before returning, unless it was refused
+    match events.try_recv() {
+        Ok(event) => queue.apply(event),
+        Err(_) => {
+            let error = app.state.lock().unwrap().last_error.take().unwrap_or_else(|| "Could not be started".to_string());
+            queue.items[index].status = Status::Failed(error);
+            queue.running = None;
+        }
+    }
+}
+
+fn draw(frame: &mut Frame, queue: &mut Queue) {
+    let [list_area, input_area, help_area] =
+        Layout::vertical([Constraint::Min(3), Constraint::Length(3), Constraint::Length(1)]).areas(frame.area());
+
+    let items: Vec<ListItem> = queue
+        .items
+        .iter()
+        .map(|item| {
+            let (verb, target) = match &item.kind {
+                Kind::Upload(path) => ("upload  ", path.display().to_string()),
+                Kind::Download(url) => ("download", url.clone()),
+            };
+            let (status, color) = match &item.status {
+                Status::Queued => ("queued".to_string(), Color::Gray),
+                Status::Running(fraction) => (format!("{:>5.1}%", fraction * 100.0), Color::Yellow),
+                Status::Done(link) => (format!("done {}", link), Color::Green),
+                Status::Failed(error) => (format!("failed: {}", error), Color::Red),
+            };
+            ListItem::new(format!("{} {}  {}", verb, target, status)).style(Style::default().fg(color))
+        })
+        .collect();
+    let title = if queue.paused { " PixelDrain - paused " } else { " PixelDrain " };
+    let list = List::new(items)
+        .block(Block::bordered().title(title))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(list, list_area, &mut queue.list);
+
+    let (label, text) = match &queue.input {
+        Some(Input::Path(text)) => (" File to upload, Enter to add ", text.as_str()),
+        Some(Input::Url(text)) => (" Link to download, Enter to add ", text.as_str()),
+        None => (" ", ""),
+    };
+    frame.render_widget(Paragraph::new(text).block(Block::bordered().title(label)), input_area);
+    frame.render_widget(Paragraph::new(HELP).style(Style::default().fg(Color::DarkGray)), help_area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pixeldrain_api::Direction;
+
+    #[test]
+    fn events_move_items_through_the_queue() {
+        let mut queue = Queue::new();
+        queue.push(Kind::Upload(PathBuf::from("a.txt")));
+        queue.push(Kind::Download("https://pixeldrain.com/u/abc".to_string()));
+        assert_eq!(queue.next_queued(), Some(0));
+
+        queue.running = Some(0);
+        queue.apply(Event::Started { id: 7, direction: Direction::Upload, name: "a.txt".to_string() });
+        queue.apply(Event::Progress { id: 7, fraction: 0.5 });
+        assert_eq!(queue.items[0].status, Status::Running(0.5));
+        // The running item stays
+        queue.list.select(Some(0));
+        queue.remove_selected();
+        assert_eq!(queue.items.len(), 2);
+
+        queue.apply(Event::Finished { id: 7, direction: Direction::Upload, name: "a.txt".to_string(), link: "https://pixeldrain.com/u/up1".to_string() });
+        assert_eq!(queue.items[0].status, Status::Done("https://pixeldrain.com/u/up1".to_string()));
+        assert_eq!(queue.running, None);
+        assert_eq!(queue.next_queued(), Some(1));
+
+        queue.remove_selected();
+        assert_eq!(queue.items[0].kind, Kind::Download("https://pixeldrain.com/u/abc".to_string()));
+    }
+}
@@ -0,0 +1,96 @@
+// undo.rs - Deletions wait a few seconds before they're sent, with a toast to
+// undo them in the meantime. Deleted files and lists can't be brought back, so
+// a click on the wrong Delete button shouldn't be the end of them. What waits
+// is hidden from the Files and Lists tabs as if it was gone already.
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_UNDO_SECS: u64 = 5;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Deletion {
+    Files(Vec<String>),
+    List(String),
+}
+
+#[derive(Clone, Debug)]
+pub struct Pending {
+    pub id: u64,
+    pub deletion: Deletion,
+    /// What the toast calls it, the file name or list title
+    pub label: String,
+    pub due: Instant,
+}
+
+#[derive(Default)]
+pub struct PendingDeletes {
+    next_id: u64,
+    pending: Vec<Pending>,
+}
+
+impl PendingDeletes {
+    /// Send `deletion` once `delay` after `now` has passed, unless it's undone
+    pub fn queue(&mut self, deletion: Deletion, label: String, delay: Duration, now: Instant) -> u64 {
+        self.next_id += 1;
+        self.pending.push(Pending { id: self.next_id, deletion, label, due: now + delay });
+        self.next_id
+    }
+
+    /// Drop deletion `id`, false when it was sent already
+    pub fn undo(&mut self, id: u64) -> bool {
+        let before = self.pending.len();
+        self.pending.retain(|pending| pending.id != id);
+        self.pending.len() < before
+    }
+
+    /// Deletions whose time is up, to be sent now
+    pub fn take_due(&mut self, now: Instant) -> Vec<Deletion> {
+        let (due, waiting) = std::mem::take(&mut self.pending).into_iter().partition(|pending| pending.due <= now);
+        self.pending = waiting;
+        due.into_iter().map(|pending: Pending| pending.deletion).collect()
+    }
+
+    /// Every waiting deletion, for when the app closes before their time
+    pub fn take_all(&mut self) -> Vec<Deletion> {
+        std::mem::take(&mut self.pending).into_iter().map(|pending| pending.deletion).collect()
+    }
+
+    pub fn pending(&self) -> &[Pending] {
+        &self.pending
+    }
+
+    pub fn hides_file(&self, file_id: &str) -> bool {
+        self.pending.iter().any(|pending| matches!(&pending.deletion, Deletion::Files(ids) if ids.iter().any(|id| id == file_id)))
+    }
+
+    pub fn hides_list(&self, list_id: &str) -> bool {
+        self.pending.iter().any(|pending| pending.deletion == Deletion::List(list_id.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deletions_wait_and_can_be_undone() {
+        let mut deletes = PendingDeletes::default();
+        let start = Instant::now();
+        let delay = Duration::from_secs(5);
+        let files = deletes.queue(Deletion::Files(vec!["a1".to_string(), "b2".to_string()]), "2 files".to_string(), delay, start);
+        deletes.queue(Deletion::List("l1".to_string()), "Notes".to_string(), delay, start + Duration::from_secs(2));
+        assert!(deletes.hides_file("b2"));
+        assert!(!deletes.hides_file("c3"));
+        assert!(deletes.hides_list("l1"));
+
+        assert!(deletes.take_due(start + Duration::from_secs(4)).is_empty());
+        assert!(deletes.undo(files));
+        assert!(!deletes.undo(files), "already undone");
+        assert!(!deletes.hides_file("b2"));
+        assert!(deletes.take_due(start + Duration::from_secs(6)).is_empty());
+        assert_eq!(deletes.take_due(start + Duration::from_secs(7)), [Deletion::List("l1".to_string())]);
+        assert!(deletes.pending().is_empty());
+
+        deletes.queue(Deletion::List("l2".to_string()), "Old".to_string(), delay, start);
+        assert_eq!(deletes.take_all(), [Deletion::List("l2".to_string())]);
+    }
+}
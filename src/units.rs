@@ -0,0 +1,91 @@
+// units.rs - File sizes the way people read and type them in the selected
+// language: shown as "1.50 GB" in English and "1,50 GB" in German, and typed
+// back in either way, like "1,5 GB", "1.5GB", "500 MiB" or a number of bytes.
+// Used by both the app and the command line.
+use crate::i18n::Language;
+
+// Units go by 1024 as everywhere else in the app, KiB and friends are accepted too
+const UNITS: [(&str, u64); 4] = [("TB", 1 << 40), ("GB", 1 << 30), ("MB", 1 << 20), ("KB", 1 << 10)];
+
+/// `bytes` in the largest unit it fills, with two decimals in that language
+pub fn format_size(bytes: u64, language: Language) -> String {
+    for (unit, size) in UNITS {
+        if bytes >= size {
+            let number = format!("{:.2}", bytes as f64 / size as f64);
+            return format!("{} {}", number.replace('.', &language.decimal_separator().to_string()), unit);
+        }
+    }
+    format!("{} B", bytes)
+}
+
+/// Size typed as `text`, None when it isn't one
+pub fn parse_size(text: &str, language: Language) -> Option<u64> {
+    let text = text.trim();
+    let (number, unit) = text.split_at(text.find(char::is_alphabetic).unwrap_or(text.len()));
+    let multiplier: u64 = match unit.trim().to_lowercase().as_str() {
+        "" | "b" | "byte" | "bytes" => 1,
+        "k" | "kb" | "kib" => 1 << 10,
+        "m" | "mb" | "mib" => 1 << 20,
+        "g" | "gb" | "gib" => 1 << 30,
+        "t" | "tb" | "tib" => 1 << 40,
+        _ => return None,
+    };
+    let value: f64 = normalize_number(number, language).parse().ok()?;
+    (value.is_finite() && value >= 0.0).then(|| (value * multiplier as f64).round() as u64)
+}
+
+/// `number` with its digit grouping dropped and a dot for its decimal separator
+fn normalize_number(number: &str, language: Language) -> String {
+    let number: String = number.chars().filter(|&c| !c.is_whitespace() && c != '\'').collect();
+    let decimal = match (number.rfind('.'), number.rfind(',')) {
+        // With both, the last one separates the decimals: "1,234.5" and "1.234,5"
+        (Some(dot), Some(comma)) => Some(dot.max(comma)),
+        (Some(at), None) | (None, Some(at)) => {
+            let separator = number[at..].chars().next().unwrap();
+            let once = number.matches(separator).count() == 1;
+            // "1,500" could be either, it's read the way the language writes it
+            let grouped = number.len() - at - 1 == 3 && separator != language.decimal_separator();
+            (once && !grouped).then_some(at)
+        }
+        (None, None) => None,
+    };
+    number
+        .char_indices()
+        .filter_map(|(at, c)| match c {
+            '.' | ',' if Some(at) == decimal => Some('.'),
+            '.' | ',' => None,
+            c => Some(c),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes_are_formatted_and_parsed_in_the_language() {
+        assert_eq!(format_size(512, Language::English), "512 B");
+        assert_eq!(format_size(1536 << 20, Language::English), "1.50 GB");
+        assert_eq!(format_size(1536 << 20, Language::German), "1,50 GB");
+        assert_eq!(format_size(3 << 40, Language::German), "3,00 TB");
+
+        for language in Language::ALL {
+            assert_eq!(parse_size("1,5 GB", language), Some(1536 << 20));
+            assert_eq!(parse_size("1.5gb", language), Some(1536 << 20));
+            assert_eq!(parse_size(" 500 MiB ", language), Some(500 << 20));
+            assert_eq!(parse_size("1.234.567", language), Some(1_234_567));
+            assert_eq!(parse_size("1 024 KB", language), Some(1 << 20));
+            assert_eq!(parse_size(&format_size(1536 << 20, language), language), Some(1536 << 20));
+            assert_eq!(parse_size("", language), None);
+            assert_eq!(parse_size("-1 GB", language), None);
+            assert_eq!(parse_size("5 parsecs", language), None);
+        }
+        assert_eq!(parse_size("1,234.5 KB", Language::German), Some(1_264_128));
+        assert_eq!(parse_size("1.234,5 KB", Language::English), Some(1_264_128));
+        // Ambiguous thousands go by the language
+        assert_eq!(parse_size("1,500 KB", Language::English), Some(1_536_000));
+        assert_eq!(parse_size("1,500 KB", Language::German), Some(1536));
+        assert_eq!(parse_size("1.500 KB", Language::German), Some(1_536_000));
+    }
+}
@@ -0,0 +1,104 @@
+// updates.rs - Checking GitHub for a newer release of the app
+// Only done at startup when turned on in Settings. The latest release is
+// fetched from the GitHub releases API, which leaves out drafts and
+// pre-releases, and nothing but the request itself is sent.
+use std::time::Duration;
+
+use serde::Deserialize;
+
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/Genxster1998/Pixeldrain-Rust/releases/latest";
+const CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Deserialize)]
+struct Release {
+    tag_name: String,
+    html_url: String,
+    #[serde(default)]
+    body: Option<String>,
+}
+
+/// A release newer than the running app
+#[derive(Clone, Debug, PartialEq)]
+pub struct Update {
+    pub version: String,
+    /// Release page with the downloads
+    pub url: String,
+    /// Release notes as written on GitHub, in Markdown
+    pub changelog: String,
+}
+
+/// Numbers of a version like "v1.2.10", anything after a number's digits is ignored
+fn version_numbers(version: &str) -> Vec<u64> {
+    version
+        .trim()
+        .trim_start_matches(['v', 'V'])
+        .split('.')
+        .map(|part| {
+            let digits: String = part.chars().take_while(char::is_ascii_digit).collect();
+            digits.parse().unwrap_or(0)
+        })
+        .collect()
+}
+
+/// Whether `latest` is a later version than `current`, missing numbers count as 0
+pub fn is_newer(latest: &str, current: &str) -> bool {
+    let (mut latest, mut current) = (version_numbers(latest), version_numbers(current));
+    let len = latest.len().max(current.len());
+    latest.resize(len, 0);
+    current.resize(len, 0);
+    latest > current
+}
+
+/// The update in a latest release response, None when `current` is up to date
+fn parse(json: &str, current: &str) -> Result<Option<Update>, String> {
+    let release: Release = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    if !is_newer(&release.tag_name, current) {
+        return Ok(None);
+    }
+    Ok(Some(Update {
+        version: release.tag_name.trim_start_matches(['v', 'V']).to_string(),
+        url: release.html_url,
+        changelog: release.body.unwrap_or_default().trim().to_string(),
+    }))
+}
+
+/// Ask GitHub for the latest release, Some when it's newer than `current`
+pub fn check(current: &str) -> Result<Option<Update>, String> {
+    let response = reqwest::blocking::Client::new()
+        .get(LATEST_RELEASE_URL)
+        // GitHub refuses requests without a user agent
+        .header(reqwest::header::USER_AGENT, concat!("pixeldrain-client/", env!("CARGO_PKG_VERSION")))
+        .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+        .timeout(CHECK_TIMEOUT)
+        .send()
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| e.to_string())?;
+    parse(&response.text().map_err(|e| e.to_string())?, current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions_compare_by_number() {
+        assert!(is_newer("v0.1.3", "0.1.2"));
+        assert!(is_newer("0.10.0", "0.9.9"));
+        assert!(is_newer("1.0", "0.99.1"));
+        assert!(!is_newer("v0.1.2", "0.1.2"));
+        assert!(!is_newer("0.1", "0.1.0"));
+        assert!(!is_newer("0.1.1", "0.1.2"));
+    }
+
+    #[test]
+    fn newer_releases_become_updates() {
+        let release = r#"{"tag_name":"v0.2.0","html_url":"https://github.com/Genxster1998/Pixeldrain-Rust/releases/tag/v0.2.0","body":"- Resume downloads\n"}"#;
+        assert_eq!(parse(release, "0.1.2"), Ok(Some(Update {
+            version: "0.2.0".to_string(),
+            url: "https://github.com/Genxster1998/Pixeldrain-Rust/releases/tag/v0.2.0".to_string(),
+            changelog: "- Resume downloads".to_string(),
+        })));
+        assert_eq!(parse(release, "0.2.0"), Ok(None));
+        assert!(parse("rate limited", "0.1.2").is_err());
+    }
+}
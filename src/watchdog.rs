@@ -0,0 +1,176 @@
+// watchdog.rs - Notices transfers that stopped moving. The events of a transfer
+// are its heartbeat: one whose progress hasn't changed for the time set in the
+// Debug panel is flagged there, and can be cancelled and started again. A
+// request stuck on a silent connection would otherwise sit there until the
+// client timeout of an hour runs out.
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use pixeldrain_api::{Direction, Event, TransferId};
+
+pub const DEFAULT_STALL_SECS: u64 = 120;
+
+/// How to start a transfer again after it was cancelled
+#[derive(Clone, Debug, PartialEq)]
+pub enum Retry {
+    Upload(PathBuf),
+    Download(String),
+}
+
+struct Heartbeat {
+    direction: Direction,
+    name: String,
+    fraction: f32,
+    last_change: Instant,
+    retrying: Option<Retrying>,
+    // Set when the transfer is cancelled, checked by the client between reads
+    cancel: Arc<AtomicBool>,
+    retry: Option<Retry>,
+}
+
+/// The last failed attempt of a transfer that's being tried again
+#[derive(Clone, Debug, PartialEq)]
+pub struct Retrying {
+    pub attempt: usize,
+    pub max_attempts: usize,
+    pub reason: String,
+}
+
+/// A running transfer as the Debug panel shows it
+#[derive(Clone, Debug, PartialEq)]
+pub struct Beat {
+    pub id: TransferId,
+    pub direction: Direction,
+    pub name: String,
+    pub fraction: f32,
+    pub idle: Duration,
+    pub can_retry: bool,
+    pub retrying: Option<Retrying>,
+}
+
+#[derive(Clone, Default)]
+pub struct Watchdog {
+    transfers: Arc<Mutex<HashMap<TransferId, Heartbeat>>>,
+}
+
+impl Watchdog {
+    /// Take a transfer event, at `now`
+    pub fn observe(&self, event: &Event, now: Instant) {
+        let mut transfers = self.transfers.lock().unwrap();
+        match event {
+            Event::Started { id, direction, name } => {
+                transfers.insert(*id, Heartbeat {
+                    direction: *direction,
+                    name: name.clone(),
+                    fraction: 0.0,
+                    last_change: now,
+                    retrying: None,
+                    cancel: Arc::new(AtomicBool::new(false)),
+                    retry: None,
+                });
+            }
+            Event::Progress { id, fraction } => {
+                // Reports of the same progress over and over aren't a sign of life
+                if let Some(heartbeat) = transfers.get_mut(id)
+                    && heartbeat.fraction != *fraction
+                {
+                    heartbeat.fraction = *fraction;
+                    heartbeat.last_change = now;
+                    heartbeat.retrying = None;
+                }
+            }
+            // The client is still at it, so this counts as a sign of life too
+            Event::Retrying { id, attempt, max_attempts, reason } => {
+                if let Some(heartbeat) = transfers.get_mut(id) {
+                    heartbeat.retrying = Some(Retrying { attempt: *attempt, max_attempts: *max_attempts, reason: reason.clone() });
+                    heartbeat.last_change = now;
+                }
+            }
+            Event::Finished { id, .. } | Event::Failed { id, .. } => {
+                transfers.remove(id);
+            }
+        }
+    }
+
+    /// Make transfer `id` one that can be cancelled and started again with
+    /// `retry`. Returns the flag its client should stop at.
+    pub fn watch(&self, id: TransferId, retry: Retry) -> Arc<AtomicBool> {
+        let mut transfers = self.transfers.lock().unwrap();
+        match transfers.get_mut(&id) {
+            Some(heartbeat) => {
+                heartbeat.retry = Some(retry);
+                heartbeat.cancel.clone()
+            }
+            // Already over
+            None => Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Every running transfer, oldest first
+    pub fn beats(&self, now: Instant) -> Vec<Beat> {
+        let transfers = self.transfers.lock().unwrap();
+        let mut beats: Vec<Beat> = transfers
+            .iter()
+            .map(|(&id, heartbeat)| Beat {
+                id,
+                direction: heartbeat.direction,
+                name: heartbeat.name.clone(),
+                fraction: heartbeat.fraction,
+                idle: now.saturating_duration_since(heartbeat.last_change),
+                can_retry: heartbeat.retry.is_some(),
+                retrying: heartbeat.retrying.clone(),
+            })
+            .collect();
+        beats.sort_by_key(|beat| beat.id);
+        beats
+    }
+
+    /// Whether a transfer made no progress for `after` or longer
+    pub fn any_stalled(&self, now: Instant, after: Duration) -> bool {
+        self.transfers.lock().unwrap().values().any(|heartbeat| now.saturating_duration_since(heartbeat.last_change) >= after)
+    }
+
+    /// Stop watching transfer `id` and tell its client to give up. Returns how
+    /// to start it again, None when it can't be cancelled.
+    pub fn cancel(&self, id: TransferId) -> Option<Retry> {
+        let mut transfers = self.transfers.lock().unwrap();
+        let retry = transfers.get(&id)?.retry.clone()?;
+        let heartbeat = transfers.remove(&id)?;
+        heartbeat.cancel.store(true, Ordering::Relaxed);
+        Some(retry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transfers_without_progress_are_flagged_and_can_be_retried() {
+        let watchdog = Watchdog::default();
+        let start = Instant::now();
+        let at = |seconds: u64| start + Duration::from_secs(seconds);
+        let stall = Duration::from_secs(60);
+        watchdog.observe(&Event::Started { id: 1, direction: Direction::Download, name: "a.bin".to_string() }, at(0));
+        watchdog.observe(&Event::Started { id: 2, direction: Direction::Upload, name: "b.bin".to_string() }, at(0));
+        let cancel = watchdog.watch(1, Retry::Download("https://pixeldrain.com/u/abc".to_string()));
+
+        watchdog.observe(&Event::Progress { id: 1, fraction: 0.5 }, at(30));
+        watchdog.observe(&Event::Progress { id: 2, fraction: 0.0 }, at(30));
+        assert!(!watchdog.any_stalled(at(59), stall));
+        assert!(watchdog.any_stalled(at(60), stall), "the upload never moved");
+        let beats = watchdog.beats(at(60));
+        assert_eq!(beats.iter().map(|beat| (beat.id, beat.idle.as_secs(), beat.can_retry)).collect::<Vec<_>>(), [(1, 30, true), (2, 60, false)]);
+
+        assert_eq!(watchdog.cancel(2), None, "nothing to start it again with");
+        assert_eq!(watchdog.cancel(1), Some(Retry::Download("https://pixeldrain.com/u/abc".to_string())));
+        assert!(cancel.load(Ordering::Relaxed));
+        assert_eq!(watchdog.beats(at(60)).len(), 1);
+
+        watchdog.observe(&Event::Failed { id: 2, direction: Direction::Upload, name: "b.bin".to_string(), error: String::new() }, at(61));
+        assert!(watchdog.beats(at(61)).is_empty());
+    }
+}
@@ -0,0 +1,152 @@
+// workers.rs - Tracking of background worker threads
+// Every worker is spawned through `Workers` so that on exit the app can signal
+// shutdown and wait for in-flight transfers to finish writing instead of
+// leaving detached threads behind. A `Limit` caps how many workers of one kind
+// talk to the API at the same time.
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Default)]
+pub struct Workers {
+    inner: Arc<WorkersInner>,
+}
+
+#[derive(Default)]
+struct WorkersInner {
+    shutdown: AtomicBool,
+    handles: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl Workers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spawn a tracked worker thread
+    pub fn spawn<F>(&self, f: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let mut handles = self.inner.handles.lock().unwrap();
+        // Drop handles of workers that already finished so the list stays small
+        handles.retain(|handle| !handle.is_finished());
+        handles.push(thread::spawn(f));
+    }
+
+    /// Whether shutdown was requested, long running workers should stop at the
+    /// next safe point (between files, before the next request)
+    pub fn is_shutting_down(&self) -> bool {
+        self.inner.shutdown.load(Ordering::Relaxed)
+    }
+
+    /// Signal shutdown and wait up to `timeout` for all workers to stop.
+    /// Returns the number of workers that were still running at the deadline.
+    pub fn shutdown(&self, timeout: Duration) -> usize {
+        self.inner.shutdown.store(true, Ordering::Relaxed);
+        self.wait(timeout)
+    }
+
+    /// Wait up to `timeout` for all workers to finish without signalling shutdown.
+    /// Returns the number of workers that were still running at the deadline.
+    pub fn wait(&self, timeout: Duration) -> usize {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let mut handles = self.inner.handles.lock().unwrap();
+            let (finished, running): (Vec<_>, Vec<_>) = handles.drain(..).partition(|handle| handle.is_finished());
+            *handles = running;
+            drop(handles);
+
+            for handle in finished {
+                let _ = handle.join();
+            }
+
+            let remaining = self.inner.handles.lock().unwrap().len();
+            if remaining == 0 || Instant::now() >= deadline {
+                return remaining;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+    }
+}
+
+/// How many workers may run a kind of request at once, the rest wait their turn
+#[derive(Clone)]
+pub struct Limit {
+    inner: Arc<(Mutex<LimitState>, Condvar)>,
+}
+
+struct LimitState {
+    max: usize,
+    running: usize,
+}
+
+/// A slot of a `Limit`, given back when dropped
+pub struct Permit {
+    inner: Arc<(Mutex<LimitState>, Condvar)>,
+}
+
+impl Limit {
+    pub fn new(max: usize) -> Self {
+        Self { inner: Arc::new((Mutex::new(LimitState { max: max.max(1), running: 0 }), Condvar::new())) }
+    }
+
+    /// Change the cap, workers already running keep their slot
+    pub fn set_max(&self, max: usize) {
+        let (state, freed) = &*self.inner;
+        let mut state = state.lock().unwrap();
+        if state.max != max.max(1) {
+            state.max = max.max(1);
+            freed.notify_all();
+        }
+    }
+
+    /// Wait for a free slot
+    pub fn acquire(&self) -> Permit {
+        let (state, freed) = &*self.inner;
+        let mut state = freed.wait_while(state.lock().unwrap(), |state| state.running >= state.max).unwrap();
+        state.running += 1;
+        Permit { inner: self.inner.clone() }
+    }
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        let (state, freed) = &*self.inner;
+        state.lock().unwrap().running -= 1;
+        freed.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    #[test]
+    fn no_more_than_the_limit_run_at_once() {
+        let limit = Limit::new(2);
+        let running = Arc::new(AtomicUsize::new(0));
+        let most = Arc::new(AtomicUsize::new(0));
+        let threads: Vec<_> = (0..6)
+            .map(|_| {
+                let (limit, running, most) = (limit.clone(), running.clone(), most.clone());
+                thread::spawn(move || {
+                    let _permit = limit.acquire();
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    most.fetch_max(now, Ordering::SeqCst);
+                    thread::sleep(Duration::from_millis(20));
+                    running.fetch_sub(1, Ordering::SeqCst);
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        assert_eq!(most.load(Ordering::SeqCst), 2);
+
+        limit.set_max(0);
+        drop(limit.acquire());
+    }
+}